@@ -780,7 +780,7 @@ fn list_proposals(
 // Takes a vec of vecs of strings. The first vec should include the title of the columns.
 // The max length of each column is calculated and is used as the column with when printing the
 // table.
-fn print_table(table: Vec<Vec<String>>) {
+pub(super) fn print_table(table: Vec<Vec<String>>) {
     let mut max_lengths = Vec::new();
 
     // find the max lengths of the columns
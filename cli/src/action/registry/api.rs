@@ -0,0 +1,391 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use reqwest::blocking::Client;
+#[cfg(any(
+    feature = "registry-bulk",
+    feature = "registry-health",
+    feature = "registry-merge"
+))]
+use serde::Deserialize;
+use splinter::protocol::REGISTRY_PROTOCOL_VERSION;
+#[cfg(any(
+    feature = "registry-bulk",
+    feature = "registry-merge",
+    feature = "registry-node-signing"
+))]
+use splinter::registry::Node;
+
+use crate::action::api::{ServerError, SplinterRestClient};
+use crate::error::CliError;
+
+impl<'a> SplinterRestClient<'a> {
+    /// Adds the node to this client's registry, or replaces the node with the same identity.
+    #[cfg(any(feature = "registry-bulk", feature = "registry-node-signing"))]
+    pub fn put_registry_node(&self, node: &Node) -> Result<(), CliError> {
+        Client::new()
+            .put(&format!("{}/registry/nodes/{}", self.url, node.identity))
+            .header("SplinterProtocolVersion", REGISTRY_PROTOCOL_VERSION)
+            .json(node)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to submit node: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    Ok(())
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Node submit request failed with status code '{}', but error \
+                                 response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to submit node: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
+    /// Adds the node to this client's registry; fails if the registry already has a node with the
+    /// same identity.
+    #[cfg(feature = "registry-bulk")]
+    pub fn add_registry_node(&self, node: &Node) -> Result<(), CliError> {
+        Client::new()
+            .post(&format!("{}/registry/nodes", self.url))
+            .header("SplinterProtocolVersion", REGISTRY_PROTOCOL_VERSION)
+            .json(node)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to add node: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    Ok(())
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Node add request failed with status code '{}', but error \
+                                 response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to add node: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
+    /// Adds the nodes to this client's registry as a single transaction, replacing the nodes with
+    /// the same identities, and returns the identities of the imported nodes.
+    #[cfg(feature = "registry-bulk")]
+    pub fn import_registry_nodes(&self, nodes: &[Node]) -> Result<Vec<String>, CliError> {
+        Client::new()
+            .post(&format!("{}/registry/import", self.url))
+            .header("SplinterProtocolVersion", REGISTRY_PROTOCOL_VERSION)
+            .json(nodes)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to import nodes: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<ImportNodesResponse>()
+                        .map(|response| response.data.inserted)
+                        .map_err(|_| {
+                            CliError::ActionError(
+                                "Request was successful, but received an invalid response".into(),
+                            )
+                        })
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Node import request failed with status code '{}', but error \
+                                 response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to import nodes: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
+    /// Exports the nodes of this client's registry in the given format, `yaml` or `json`.
+    #[cfg(feature = "registry-bulk")]
+    pub fn export_registry(&self, format: &str) -> Result<Vec<u8>, CliError> {
+        Client::new()
+            .get(&format!("{}/registry/export?format={}", self.url, format))
+            .header("SplinterProtocolVersion", REGISTRY_PROTOCOL_VERSION)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to export registry: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.bytes().map(|bytes| bytes.to_vec()).map_err(|err| {
+                        CliError::ActionError(format!("Failed to read exported nodes: {}", err))
+                    })
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Registry export request failed with status code '{}', but \
+                                 error response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to export registry: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
+    /// Deletes the nodes with the given identities from this client's registry as a single
+    /// transaction, and returns the identities of the nodes that were in the registry.
+    #[cfg(feature = "registry-bulk")]
+    pub fn remove_registry_nodes(&self, identities: &[String]) -> Result<Vec<String>, CliError> {
+        Client::new()
+            .post(&format!("{}/registry/remove", self.url))
+            .header("SplinterProtocolVersion", REGISTRY_PROTOCOL_VERSION)
+            .json(identities)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to remove nodes: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<RemoveNodesResponse>()
+                        .map(|response| response.data.removed)
+                        .map_err(|_| {
+                            CliError::ActionError(
+                                "Request was successful, but received an invalid response".into(),
+                            )
+                        })
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Node remove request failed with status code '{}', but error \
+                                 response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to remove nodes: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
+    /// Lists the nodes whose definitions conflict between the sources of this client's registry.
+    #[cfg(feature = "registry-merge")]
+    pub fn list_registry_conflicts(&self) -> Result<Vec<NodeConflict>, CliError> {
+        Client::new()
+            .get(&format!("{}/registry/conflicts", self.url))
+            .header("SplinterProtocolVersion", REGISTRY_PROTOCOL_VERSION)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to list conflicts: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<ListConflictsResponse>()
+                        .map(|response| response.data)
+                        .map_err(|_| {
+                            CliError::ActionError(
+                                "Request was successful, but received an invalid response".into(),
+                            )
+                        })
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Conflict list request failed with status code '{}', but error \
+                                 response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to list conflicts: {}",
+                        message
+                    )))
+                }
+            })
+    }
+
+    /// Fetches the report of the latest check of this client's registry.
+    #[cfg(feature = "registry-health")]
+    pub fn get_registry_health(&self) -> Result<RegistryHealth, CliError> {
+        Client::new()
+            .get(&format!("{}/registry/health", self.url))
+            .header("SplinterProtocolVersion", REGISTRY_PROTOCOL_VERSION)
+            .send()
+            .map_err(|err| {
+                CliError::ActionError(format!("Failed to fetch registry health: {}", err))
+            })
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<RegistryHealthResponse>()
+                        .map(|response| response.data)
+                        .map_err(|_| {
+                            CliError::ActionError(
+                                "Request was successful, but received an invalid response".into(),
+                            )
+                        })
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Registry health request failed with status code '{}', but \
+                                 error response was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to fetch registry health: {}",
+                        message
+                    )))
+                }
+            })
+    }
+}
+
+#[cfg(feature = "registry-bulk")]
+#[derive(Deserialize)]
+struct ImportNodesResponse {
+    data: ImportNodesData,
+}
+
+#[cfg(feature = "registry-bulk")]
+#[derive(Deserialize)]
+struct ImportNodesData {
+    inserted: Vec<String>,
+}
+
+#[cfg(feature = "registry-bulk")]
+#[derive(Deserialize)]
+struct RemoveNodesResponse {
+    data: RemoveNodesData,
+}
+
+#[cfg(feature = "registry-bulk")]
+#[derive(Deserialize)]
+struct RemoveNodesData {
+    removed: Vec<String>,
+}
+
+#[cfg(feature = "registry-health")]
+#[derive(Deserialize)]
+struct RegistryHealthResponse {
+    data: RegistryHealth,
+}
+
+/// The report of the latest check of a node's registry.
+#[cfg(feature = "registry-health")]
+#[derive(Deserialize)]
+pub struct RegistryHealth {
+    pub node_count: usize,
+    pub issues: Vec<RegistryHealthIssue>,
+}
+
+/// A problem found with the entries of a node's registry; which fields are set depends on the
+/// kind of issue.
+#[cfg(feature = "registry-health")]
+#[derive(Deserialize)]
+pub struct RegistryHealthIssue {
+    pub kind: String,
+    pub identity: Option<String>,
+    #[serde(default)]
+    pub identities: Vec<String>,
+    pub endpoint: Option<String>,
+    pub key: Option<String>,
+    pub reason: Option<String>,
+    pub unreachable_since: Option<u64>,
+}
+
+#[cfg(feature = "registry-health")]
+impl RegistryHealthIssue {
+    /// Converts the issue into a row of the `registry check` output.
+    pub fn to_row(&self) -> Vec<String> {
+        let nodes = match &self.identity {
+            Some(identity) => identity.clone(),
+            None => self.identities.join(";"),
+        };
+        let subject = self
+            .endpoint
+            .as_ref()
+            .or_else(|| self.key.as_ref())
+            .cloned()
+            .unwrap_or_default();
+        let details = match (&self.reason, self.unreachable_since) {
+            (Some(reason), _) => reason.clone(),
+            (None, Some(since)) => format!("unreachable since {} (Unix time)", since),
+            (None, None) => String::new(),
+        };
+        vec![self.kind.clone(), nodes, subject, details]
+    }
+}
+
+#[cfg(feature = "registry-merge")]
+#[derive(Deserialize)]
+struct ListConflictsResponse {
+    data: Vec<NodeConflict>,
+}
+
+/// A node whose definitions in the registry's sources have different endpoints or keys.
+#[cfg(feature = "registry-merge")]
+#[derive(Deserialize)]
+pub struct NodeConflict {
+    pub identity: String,
+    pub fields: Vec<String>,
+    pub definitions: Vec<NodeDefinition>,
+}
+
+/// A definition of a node, with the name of the registry it came from.
+#[cfg(feature = "registry-merge")]
+#[derive(Deserialize)]
+pub struct NodeDefinition {
+    pub source: String,
+    pub node: Node,
+}
@@ -12,21 +12,58 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(any(
+    feature = "registry-bulk",
+    feature = "registry-health",
+    feature = "registry-merge",
+    feature = "registry-node-signing"
+))]
+mod api;
+
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+#[cfg(any(feature = "registry-node-signing", feature = "registry-signing"))]
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use clap::ArgMatches;
+#[cfg(any(feature = "registry-node-signing", feature = "registry-signing"))]
+use sawtooth_sdk::signing::secp256k1;
+#[cfg(feature = "registry-database")]
+use splinter::database::ConnectionPool;
 use splinter::registry::Node;
+#[cfg(feature = "registry-signing")]
+use splinter::registry::{read_public_key_file, RegistrySignature, SIGNATURE_FILE_SUFFIX};
+#[cfg(feature = "registry-database")]
+use splinter::registry::{DieselRegistry, RegistryWriter};
+#[cfg(feature = "registry-health")]
+use splinter::registry::{RegistryChecker, RegistryIssue};
+#[cfg(any(feature = "registry-node-signing", feature = "registry-signing"))]
+use splinter::signing::sawtooth::SawtoothSecp256k1RefSigner;
+#[cfg(feature = "registry-signing")]
+use splinter::signing::sawtooth::SawtoothSecp256k1SignatureVerifier;
+#[cfg(feature = "registry-node-signing")]
+use splinter::signing::Signer;
+#[cfg(feature = "registry-health")]
+use splinter::transport::{
+    multi::MultiTransport,
+    socket::{TcpTransport, TlsTransport},
+    Transport,
+};
 
 use crate::error::CliError;
 
 use super::api::SplinterRestClient;
+#[cfg(any(feature = "registry-health", feature = "registry-merge"))]
+use super::circuit::print_table;
 use super::{
     msg_from_io_error, read_private_key, Action, DEFAULT_SPLINTER_REST_API_URL,
     SPLINTER_REST_API_URL_ENV,
 };
 
+#[cfg(feature = "registry-database")]
+use super::database::DEFAULT_DATABASE_URL;
+
 const DEFAULT_OUTPUT_FILE: &str = "./nodes.yaml";
 
 pub struct RegistryGenerateAction;
@@ -66,53 +103,18 @@ impl Action for RegistryGenerateAction {
             .values_of("key_files")
             .ok_or_else(|| CliError::ActionError("One or more key files must be specified".into()))?
             .map(|key_file| read_private_key(key_file))
-            .collect::<Result<_, _>>()?;
-
-        let metadata = if let Some(metadata) = args.values_of("metadata") {
-            metadata
-                .map(|kv| {
-                    let mut kv_iter = kv.splitn(2, '=');
-
-                    let key = kv_iter
-                        .next()
-                        .expect("str::split cannot return an empty iterator")
-                        .to_string();
-                    if key.is_empty() {
-                        return Err(CliError::ActionError(
-                            "Empty '--metadata' argument detected".into(),
-                        ));
-                    }
-
-                    let value = kv_iter
-                        .next()
-                        .ok_or_else(|| {
-                            CliError::ActionError(format!(
-                                "Missing value for metadata key '{}'",
-                                key
-                            ))
-                        })?
-                        .to_string();
-                    if value.is_empty() {
-                        return Err(CliError::ActionError(format!(
-                            "Empty value detected for metadata key '{}'",
-                            key
-                        )));
-                    }
-
-                    Ok((key, value))
-                })
-                .collect::<Result<_, _>>()?
-        } else {
-            Default::default()
-        };
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let node = Node {
-            identity: node_status.node_id.clone(),
-            endpoints: node_status.advertised_endpoints,
-            display_name: node_status.display_name,
-            keys,
-            metadata,
-        };
+        let mut builder = Node::builder(node_status.node_id.clone())
+            .with_endpoints(node_status.advertised_endpoints)
+            .with_display_name(node_status.display_name)
+            .with_keys(keys);
+        for (key, value) in parse_metadata(args)? {
+            builder = builder.with_metadata(key, value);
+        }
+        let node = builder
+            .build()
+            .map_err(|err| CliError::ActionError(format!("Invalid node: {}", err)))?;
 
         if let Some(idx) = nodes
             .iter()
@@ -162,3 +164,697 @@ impl Action for RegistryGenerateAction {
         Ok(())
     }
 }
+
+/// Adds the node's own entry to a registry, signed with one of the node's keys
+#[cfg(feature = "registry-node-signing")]
+pub struct RegistryAddSelfAction;
+
+#[cfg(feature = "registry-node-signing")]
+impl Action for RegistryAddSelfAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+
+        let url = args
+            .value_of("url")
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+        let registry_url = args.value_of("registry_url").unwrap_or(&url);
+        let key_file = args
+            .value_of("key")
+            .ok_or_else(|| CliError::ActionError("A private key file must be specified".into()))?;
+
+        let context = secp256k1::Secp256k1Context::new();
+        let private_key = secp256k1::Secp256k1PrivateKey::from_hex(&read_private_key(key_file)?)
+            .map_err(|err| {
+                CliError::ActionError(format!("Invalid secp256k1 private key provided: {}", err))
+            })?;
+        let signer = SawtoothSecp256k1RefSigner::new(&context, private_key).map_err(|err| {
+            CliError::ActionError(format!("Failed to create signer from private key: {}", err))
+        })?;
+
+        // The signing key is always listed first, followed by any other keys of the node
+        let mut keys = vec![signer
+            .public_key()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()];
+        if let Some(key_files) = args.values_of("key_files") {
+            for key_file in key_files {
+                let key = read_private_key(key_file)?;
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        let node_status = SplinterRestClient::new(&url).get_node_status()?;
+
+        let mut builder = Node::builder(node_status.node_id)
+            .with_endpoints(node_status.advertised_endpoints)
+            .with_display_name(node_status.display_name)
+            .with_keys(keys);
+        for (key, value) in parse_metadata(args)? {
+            builder = builder.with_metadata(key, value);
+        }
+        let mut node = builder
+            .build()
+            .map_err(|err| CliError::ActionError(format!("Invalid node: {}", err)))?;
+        // The entry's version is the current time, so that each new entry replaces the last
+        let version = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .map_err(|err| {
+                CliError::ActionError(format!("Failed to get the current time: {}", err))
+            })?;
+        node.sign(version, &signer)
+            .map_err(|err| CliError::ActionError(err.to_string()))?;
+
+        SplinterRestClient::new(registry_url).put_registry_node(&node)?;
+
+        info!(
+            "Added signed entry for node '{}' to the registry at '{}'",
+            node.identity, registry_url
+        );
+
+        Ok(())
+    }
+}
+
+/// Lists the nodes whose definitions conflict between the sources of a node's registry
+#[cfg(feature = "registry-merge")]
+pub struct RegistryConflictsAction;
+
+#[cfg(feature = "registry-merge")]
+impl Action for RegistryConflictsAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let url = arg_matches
+            .and_then(|args| args.value_of("url"))
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+        let format = arg_matches
+            .and_then(|args| args.value_of("format"))
+            .unwrap_or("human");
+
+        let conflicts = SplinterRestClient::new(&url).list_registry_conflicts()?;
+
+        if conflicts.is_empty() && format == "human" {
+            println!("No conflicting nodes");
+            return Ok(());
+        }
+
+        // One row per definition of each conflicting node
+        let mut data = vec![vec![
+            "IDENTITY".to_string(),
+            "CONFLICTS".to_string(),
+            "SOURCE".to_string(),
+            "ENDPOINTS".to_string(),
+            "KEYS".to_string(),
+        ]];
+        for conflict in conflicts {
+            let fields = conflict.fields.join(";");
+            for definition in conflict.definitions {
+                data.push(vec![
+                    conflict.identity.clone(),
+                    fields.clone(),
+                    definition.source,
+                    definition.node.endpoints.join(";"),
+                    definition.node.keys.join(";"),
+                ]);
+            }
+        }
+
+        if format == "csv" {
+            for row in data {
+                println!("{}", row.join(","))
+            }
+        } else {
+            print_table(data);
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the `--metadata` arguments (`<key>=<value>`) into key/value pairs.
+fn parse_metadata(args: &ArgMatches) -> Result<Vec<(String, String)>, CliError> {
+    match args.values_of("metadata") {
+        Some(metadata) => metadata
+            .map(|kv| {
+                let mut kv_iter = kv.splitn(2, '=');
+
+                let key = kv_iter
+                    .next()
+                    .expect("str::split cannot return an empty iterator")
+                    .to_string();
+                if key.is_empty() {
+                    return Err(CliError::ActionError(
+                        "Empty '--metadata' argument detected".into(),
+                    ));
+                }
+
+                let value = kv_iter
+                    .next()
+                    .ok_or_else(|| {
+                        CliError::ActionError(format!("Missing value for metadata key '{}'", key))
+                    })?
+                    .to_string();
+                if value.is_empty() {
+                    return Err(CliError::ActionError(format!(
+                        "Empty value detected for metadata key '{}'",
+                        key
+                    )));
+                }
+
+                Ok((key, value))
+            })
+            .collect(),
+        None => Ok(vec![]),
+    }
+}
+
+/// Imports the nodes of registry YAML files into a node's registry or a database-backed registry
+///
+/// Every file is read and parsed before any node is written. Nodes replace existing nodes with
+/// the same identity, so a node that appears in more than one file is taken from the last file.
+/// A node's registry imports the nodes as a single transaction, so either every node is imported
+/// or none are.
+#[cfg(any(feature = "registry-bulk", feature = "registry-database"))]
+pub struct RegistryImportAction;
+
+#[cfg(any(feature = "registry-bulk", feature = "registry-database"))]
+impl Action for RegistryImportAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+
+        let files = args
+            .values_of("files")
+            .ok_or_else(|| CliError::ActionError("One or more files must be specified".into()))?;
+
+        let nodes = read_registry_files(files)?;
+
+        // Without the registry-bulk feature, the nodes can only be imported into a database
+        #[cfg(feature = "registry-database")]
+        {
+            if args.is_present("connect") || cfg!(not(feature = "registry-bulk")) {
+                let url = args.value_of("connect").unwrap_or(DEFAULT_DATABASE_URL);
+                return import_into_database(url, nodes);
+            }
+        }
+
+        #[cfg(feature = "registry-bulk")]
+        {
+            let url = args
+                .value_of("url")
+                .map(ToOwned::to_owned)
+                .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+                .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+            let inserted = SplinterRestClient::new(&url).import_registry_nodes(&nodes)?;
+
+            info!(
+                "Imported {} nodes into the registry at '{}'",
+                inserted.len(),
+                url
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Imports the nodes into the database-backed registry at the given URL.
+#[cfg(feature = "registry-database")]
+fn import_into_database(url: &str, nodes: Vec<Node>) -> Result<(), CliError> {
+    let connection_pool = ConnectionPool::new(url).map_err(|err| {
+        CliError::ActionError(format!(
+            "Failed to establish database connection to '{}': {}",
+            url, err
+        ))
+    })?;
+    let registry = DieselRegistry::new(connection_pool);
+
+    let count = nodes.len();
+    for node in nodes {
+        let identity = node.identity.clone();
+        registry.insert_node(node).map_err(|err| {
+            CliError::ActionError(format!("Failed to import node '{}': {}", identity, err))
+        })?;
+    }
+
+    info!("Imported {} nodes", count);
+
+    Ok(())
+}
+
+/// Exports the nodes of a node's registry as a YAML or JSON list, in the same format as a registry
+/// YAML file
+#[cfg(feature = "registry-bulk")]
+pub struct RegistryExportAction;
+
+#[cfg(feature = "registry-bulk")]
+impl Action for RegistryExportAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let url = arg_matches
+            .and_then(|args| args.value_of("url"))
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+        let format = arg_matches
+            .and_then(|args| args.value_of("format"))
+            .unwrap_or("yaml");
+
+        let nodes = SplinterRestClient::new(&url).export_registry(format)?;
+
+        match arg_matches.and_then(|args| args.value_of("file")) {
+            Some(file_name) => {
+                let mut file = File::create(file_name).map_err(|err| {
+                    CliError::ActionError(format!(
+                        "Failed to create or overwrite '{}': {}",
+                        file_name,
+                        msg_from_io_error(err)
+                    ))
+                })?;
+                file.write_all(&nodes).map_err(|err| {
+                    CliError::ActionError(format!(
+                        "Failed to write to file '{}': {}",
+                        file_name,
+                        msg_from_io_error(err)
+                    ))
+                })?;
+
+                info!("Exported the registry at '{}' to '{}'", url, file_name);
+            }
+            None => std::io::stdout().write_all(&nodes).map_err(|err| {
+                CliError::ActionError(format!(
+                    "Failed to write to standard output: {}",
+                    msg_from_io_error(err)
+                ))
+            })?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds a node to a node's registry
+#[cfg(feature = "registry-bulk")]
+pub struct RegistryAddAction;
+
+#[cfg(feature = "registry-bulk")]
+impl Action for RegistryAddAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+
+        let url = args
+            .value_of("url")
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+        let identity = args
+            .value_of("identity")
+            .ok_or_else(|| CliError::ActionError("An identity must be specified".into()))?;
+        let endpoints = args
+            .values_of("endpoints")
+            .ok_or_else(|| CliError::ActionError("One or more endpoints must be specified".into()))?
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        let keys = args
+            .values_of("key_files")
+            .ok_or_else(|| CliError::ActionError("One or more key files must be specified".into()))?
+            .map(|key_file| read_private_key(key_file))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut builder = Node::builder(identity)
+            .with_endpoints(endpoints)
+            .with_keys(keys);
+        if let Some(display_name) = args.value_of("display_name") {
+            builder = builder.with_display_name(display_name);
+        }
+        for (key, value) in parse_metadata(args)? {
+            builder = builder.with_metadata(key, value);
+        }
+        let node = builder
+            .build()
+            .map_err(|err| CliError::ActionError(format!("Invalid node: {}", err)))?;
+
+        let client = SplinterRestClient::new(&url);
+        if args.is_present("force") {
+            client.put_registry_node(&node)?;
+        } else {
+            client.add_registry_node(&node)?;
+        }
+
+        info!(
+            "Added node '{}' to the registry at '{}'",
+            node.identity, url
+        );
+
+        Ok(())
+    }
+}
+
+/// Removes nodes from a node's registry as a single transaction
+#[cfg(feature = "registry-bulk")]
+pub struct RegistryRemoveAction;
+
+#[cfg(feature = "registry-bulk")]
+impl Action for RegistryRemoveAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+
+        let url = args
+            .value_of("url")
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+        let identities = args
+            .values_of("identities")
+            .ok_or_else(|| {
+                CliError::ActionError("One or more identities must be specified".into())
+            })?
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+
+        let removed = SplinterRestClient::new(&url).remove_registry_nodes(&identities)?;
+
+        for identity in identities
+            .iter()
+            .filter(|identity| !removed.contains(identity))
+        {
+            warn!("Node '{}' is not in the registry", identity);
+        }
+        info!(
+            "Removed {} nodes from the registry at '{}'",
+            removed.len(),
+            url
+        );
+
+        Ok(())
+    }
+}
+
+/// Reads the nodes of the given registry YAML files.
+#[cfg(any(
+    feature = "registry-bulk",
+    feature = "registry-database",
+    feature = "registry-health"
+))]
+fn read_registry_files<'a, I: Iterator<Item = &'a str>>(files: I) -> Result<Vec<Node>, CliError> {
+    let mut nodes = vec![];
+    for file_name in files {
+        let file = File::open(file_name).map_err(|err| {
+            CliError::ActionError(format!(
+                "Failed to open '{}': {}",
+                file_name,
+                msg_from_io_error(err)
+            ))
+        })?;
+        let file_nodes: Vec<Node> = serde_yaml::from_reader(file).map_err(|_| {
+            CliError::ActionError(format!(
+                "Failed to read registry file '{}': Not a valid YAML sequence of nodes",
+                file_name
+            ))
+        })?;
+        nodes.extend(file_nodes);
+    }
+    Ok(nodes)
+}
+
+/// Checks the nodes of registry files for invalid, duplicate and unreachable entries, or shows the
+/// report of the latest check of a node's registry
+#[cfg(feature = "registry-health")]
+pub struct RegistryCheckAction;
+
+#[cfg(feature = "registry-health")]
+impl Action for RegistryCheckAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+        let format = args.value_of("format").unwrap_or("human");
+
+        let (node_count, issues) = match args.values_of("files") {
+            Some(files) => {
+                let nodes = read_registry_files(files)?;
+                let mut checker = RegistryChecker::new();
+                if args.is_present("probe") {
+                    checker = checker.with_transport(build_probe_transport(args)?);
+                }
+                let report = checker.check(&nodes);
+                (
+                    report.node_count,
+                    report.issues.iter().map(issue_row).collect::<Vec<_>>(),
+                )
+            }
+            None => {
+                let url = args
+                    .value_of("url")
+                    .map(ToOwned::to_owned)
+                    .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+                    .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+                let health = SplinterRestClient::new(&url).get_registry_health()?;
+                (
+                    health.node_count,
+                    health.issues.iter().map(|issue| issue.to_row()).collect(),
+                )
+            }
+        };
+
+        if issues.is_empty() {
+            if format == "human" {
+                println!("Checked {} nodes: no issues found", node_count);
+            }
+            return Ok(());
+        }
+
+        let issue_count = issues.len();
+        let mut data = vec![vec![
+            "ISSUE".to_string(),
+            "NODES".to_string(),
+            "SUBJECT".to_string(),
+            "DETAILS".to_string(),
+        ]];
+        data.extend(issues);
+
+        if format == "csv" {
+            for row in data {
+                println!("{}", row.join(","))
+            }
+        } else {
+            print_table(data);
+        }
+
+        Err(CliError::ActionError(format!(
+            "Found {} issues with {} nodes",
+            issue_count, node_count
+        )))
+    }
+}
+
+/// Builds the transport that the endpoints are probed with. TLS endpoints can only be probed if a
+/// client certificate and key are given.
+#[cfg(feature = "registry-health")]
+fn build_probe_transport(args: &ArgMatches) -> Result<Box<dyn Transport>, CliError> {
+    let mut transports: Vec<Box<dyn Transport + Send>> = vec![Box::new(TcpTransport::default())];
+
+    if let (Some(cert), Some(key)) = (
+        args.value_of("tls_client_cert"),
+        args.value_of("tls_client_key"),
+    ) {
+        // The transport is only used to connect, so the client certificate also stands in for the
+        // server certificate
+        let transport = TlsTransport::new(
+            args.value_of("tls_ca_file").map(String::from),
+            key.to_string(),
+            cert.to_string(),
+            key.to_string(),
+            cert.to_string(),
+        )
+        .map_err(|err| CliError::ActionError(format!("Failed to create TLS transport: {}", err)))?;
+        transports.push(Box::new(transport));
+    }
+
+    Ok(Box::new(MultiTransport::new(transports)))
+}
+
+/// Converts an issue into a row of the `registry check` output.
+#[cfg(feature = "registry-health")]
+fn issue_row(issue: &RegistryIssue) -> Vec<String> {
+    match issue {
+        RegistryIssue::InvalidEndpoint {
+            identity,
+            endpoint,
+            reason,
+        } => vec![
+            "invalid_endpoint".into(),
+            identity.clone(),
+            endpoint.clone(),
+            reason.clone(),
+        ],
+        RegistryIssue::UnreachableEndpoint {
+            identity,
+            endpoint,
+            reason,
+        } => vec![
+            "unreachable_endpoint".into(),
+            identity.clone(),
+            endpoint.clone(),
+            reason.clone(),
+        ],
+        RegistryIssue::DuplicateEndpoint {
+            endpoint,
+            identities,
+        } => vec![
+            "duplicate_endpoint".into(),
+            identities.join(";"),
+            endpoint.clone(),
+            String::new(),
+        ],
+        RegistryIssue::DuplicateKey { key, identities } => vec![
+            "duplicate_key".into(),
+            identities.join(";"),
+            key.clone(),
+            String::new(),
+        ],
+        RegistryIssue::StaleNode { identity, .. } => vec![
+            "stale_node".into(),
+            identity.clone(),
+            String::new(),
+            issue.to_string(),
+        ],
+    }
+}
+
+/// Signs a registry YAML file, writing a detached signature file next to it
+#[cfg(feature = "registry-signing")]
+pub struct RegistrySignAction;
+
+#[cfg(feature = "registry-signing")]
+impl Action for RegistrySignAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+
+        let file_name = args
+            .value_of("file")
+            .ok_or_else(|| CliError::ActionError("A registry file must be specified".into()))?;
+        let signature_file_name = args
+            .value_of("signature")
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| format!("{}{}", file_name, SIGNATURE_FILE_SUFFIX));
+        let key_file = args
+            .value_of("key")
+            .ok_or_else(|| CliError::ActionError("A private key file must be specified".into()))?;
+        let serial = match args.value_of("serial") {
+            Some(serial) => serial.parse::<u64>().map_err(|_| {
+                CliError::ActionError(format!("Invalid serial number provided: {}", serial))
+            })?,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .map_err(|err| {
+                    CliError::ActionError(format!("Failed to get the current time: {}", err))
+                })?,
+        };
+
+        let registry_bytes = read_file(file_name)?;
+
+        let context = secp256k1::Secp256k1Context::new();
+        let private_key = secp256k1::Secp256k1PrivateKey::from_hex(&read_private_key(key_file)?)
+            .map_err(|err| {
+                CliError::ActionError(format!("Invalid secp256k1 private key provided: {}", err))
+            })?;
+        let signer = SawtoothSecp256k1RefSigner::new(&context, private_key).map_err(|err| {
+            CliError::ActionError(format!("Failed to create signer from private key: {}", err))
+        })?;
+
+        let signature = RegistrySignature::sign(&registry_bytes, serial, &signer)
+            .and_then(|signature| signature.to_yaml())
+            .map_err(|err| CliError::ActionError(err.to_string()))?;
+
+        let mut file = File::create(&signature_file_name).map_err(|err| {
+            CliError::ActionError(format!(
+                "Failed to create or overwrite '{}': {}",
+                signature_file_name,
+                msg_from_io_error(err)
+            ))
+        })?;
+        file.write_all(&signature).map_err(|err| {
+            CliError::ActionError(format!(
+                "Failed to write to file '{}': {}",
+                signature_file_name,
+                msg_from_io_error(err)
+            ))
+        })?;
+
+        info!(
+            "Signed '{}' with serial {}; signature written to '{}'",
+            file_name, serial, signature_file_name
+        );
+
+        Ok(())
+    }
+}
+
+/// Verifies that a registry YAML file was signed by one of the trusted keys
+#[cfg(feature = "registry-signing")]
+pub struct RegistryVerifyAction;
+
+#[cfg(feature = "registry-signing")]
+impl Action for RegistryVerifyAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+
+        let file_name = args
+            .value_of("file")
+            .ok_or_else(|| CliError::ActionError("A registry file must be specified".into()))?;
+        let signature_file_name = args
+            .value_of("signature")
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| format!("{}{}", file_name, SIGNATURE_FILE_SUFFIX));
+        let trusted_keys = args
+            .values_of("trust_keys")
+            .ok_or_else(|| {
+                CliError::ActionError("One or more trusted key files must be specified".into())
+            })?
+            .map(|key_file| {
+                read_public_key_file(key_file).map_err(|err| CliError::ActionError(err.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let registry_bytes = read_file(file_name)?;
+        let signature = RegistrySignature::from_yaml(&read_file(&signature_file_name)?)
+            .map_err(|err| CliError::ActionError(err.to_string()))?;
+
+        signature
+            .verify(
+                &registry_bytes,
+                &trusted_keys,
+                &SawtoothSecp256k1SignatureVerifier::new(),
+            )
+            .map_err(|err| {
+                CliError::ActionError(format!("Failed to verify '{}': {}", file_name, err))
+            })?;
+
+        info!(
+            "'{}' has a valid signature from a trusted key, with serial {}",
+            file_name, signature.serial
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "registry-signing")]
+fn read_file(file_name: &str) -> Result<Vec<u8>, CliError> {
+    std::fs::read(file_name).map_err(|err| {
+        CliError::ActionError(format!(
+            "Failed to read '{}': {}",
+            file_name,
+            msg_from_io_error(err)
+        ))
+    })
+}
@@ -27,6 +27,8 @@ mod template;
 
 use std::ffi::OsString;
 
+#[cfg(all(feature = "registry-bulk", feature = "registry-database"))]
+use clap::ArgGroup;
 use clap::{clap_app, AppSettings, Arg, SubCommand};
 #[cfg(test)]
 use flexi_logger::FlexiLoggerError;
@@ -507,45 +509,374 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
 
     app = app.subcommand(circuit_command);
 
-    app = app.subcommand(
-        SubCommand::with_name("registry")
-            .about("Splinter registry commands")
-            .setting(AppSettings::SubcommandRequiredElseHelp)
-            .subcommand(
-                SubCommand::with_name("build")
-                    .about("Add a node to a YAML file")
-                    .arg(Arg::with_name("file").long("file").takes_value(true).help(
-                        "Path of registry file to add node to; defaults to \
-                                './nodes.yaml'",
-                    ))
-                    .arg(
-                        Arg::with_name("force")
-                            .long("force")
-                            .help("Overwrite node if it already exists"),
-                    )
-                    .arg(
-                        Arg::with_name("status_url")
-                            .takes_value(true)
-                            .help("URL of splinter REST API to query for node data"),
-                    )
-                    .arg(
-                        Arg::with_name("key_files")
-                            .long("key-file")
-                            .takes_value(true)
-                            .multiple(true)
-                            .required(true)
-                            .help("Path of public key file to include with node"),
-                    )
-                    .arg(
-                        Arg::with_name("metadata")
-                            .long("metadata")
-                            .takes_value(true)
-                            .multiple(true)
-                            .help("Metadata to include with node (<key>=<value>)"),
+    let registry_command = SubCommand::with_name("registry")
+        .about("Splinter registry commands")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("build")
+                .about("Add a node to a YAML file")
+                .arg(Arg::with_name("file").long("file").takes_value(true).help(
+                    "Path of registry file to add node to; defaults to \
+                            './nodes.yaml'",
+                ))
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Overwrite node if it already exists"),
+                )
+                .arg(
+                    Arg::with_name("status_url")
+                        .takes_value(true)
+                        .help("URL of splinter REST API to query for node data"),
+                )
+                .arg(
+                    Arg::with_name("key_files")
+                        .long("key-file")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Path of public key file to include with node"),
+                )
+                .arg(
+                    Arg::with_name("metadata")
+                        .long("metadata")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Metadata to include with node (<key>=<value>)"),
+                ),
+        );
+
+    #[cfg(feature = "registry-health")]
+    let registry_command = registry_command.subcommand(
+        SubCommand::with_name("check")
+            .about(
+                "Check the nodes of registry YAML files, or show the latest check of a node's \
+                 registry",
+            )
+            .arg(
+                Arg::with_name("files")
+                    .takes_value(true)
+                    .multiple(true)
+                    .help(
+                        "Paths of registry YAML files to check; if not given, the report of the \
+                         Splinter daemon's registry validator is shown",
+                    ),
+            )
+            .arg(
+                Arg::with_name("url")
+                    .short("U")
+                    .long("url")
+                    .takes_value(true)
+                    .conflicts_with("files")
+                    .help("URL of the Splinter daemon REST API"),
+            )
+            .arg(
+                Arg::with_name("probe")
+                    .long("probe")
+                    .requires("files")
+                    .help("Connect to the endpoints of the nodes to check that they are reachable"),
+            )
+            .arg(
+                Arg::with_name("tls_client_cert")
+                    .long("tls-client-cert")
+                    .takes_value(true)
+                    .requires_all(&["probe", "tls_client_key"])
+                    .help("Path to the client certificate used to probe TLS endpoints"),
+            )
+            .arg(
+                Arg::with_name("tls_client_key")
+                    .long("tls-client-key")
+                    .takes_value(true)
+                    .requires_all(&["probe", "tls_client_cert"])
+                    .help("Path to the private key of the client certificate"),
+            )
+            .arg(
+                Arg::with_name("tls_ca_file")
+                    .long("tls-ca-file")
+                    .takes_value(true)
+                    .requires("tls_client_cert")
+                    .help(
+                        "Path to the file with the CA certificates that TLS endpoints are \
+                         verified with; if not given, they are not verified",
+                    ),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .short("f")
+                    .long("format")
+                    .help("Output format")
+                    .possible_values(&["human", "csv"])
+                    .default_value("human")
+                    .takes_value(true),
+            ),
+    );
+
+    #[cfg(any(feature = "registry-bulk", feature = "registry-database"))]
+    let registry_command = {
+        let import_command = SubCommand::with_name("import")
+            .about("Import the nodes of registry YAML files into a node's registry or a database")
+            .arg(
+                Arg::with_name("files")
+                    .takes_value(true)
+                    .multiple(true)
+                    .required(true)
+                    .help("Paths of registry YAML files to import"),
+            );
+
+        #[cfg(feature = "registry-bulk")]
+        let import_command = import_command.arg(
+            Arg::with_name("url")
+                .short("U")
+                .long("url")
+                .takes_value(true)
+                .help("URL of the Splinter daemon REST API to import the nodes into"),
+        );
+
+        #[cfg(feature = "registry-database")]
+        let import_command = import_command.arg(
+            Arg::with_name("connect")
+                .short("C")
+                .takes_value(true)
+                .help("Database URL or path to a SQLite database file to import the nodes into"),
+        );
+
+        #[cfg(all(feature = "registry-bulk", feature = "registry-database"))]
+        let import_command =
+            import_command.group(ArgGroup::with_name("destination").args(&["url", "connect"]));
+
+        registry_command.subcommand(import_command)
+    };
+
+    #[cfg(feature = "registry-bulk")]
+    let registry_command = registry_command
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Export the nodes of a node's registry")
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of the Splinter daemon REST API"),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .long("file")
+                        .takes_value(true)
+                        .help("Path of file to write the nodes to; defaults to standard output"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .short("f")
+                        .long("format")
+                        .help("Output format")
+                        .possible_values(&["yaml", "json"])
+                        .default_value("yaml")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("add")
+                .about("Add a node to a node's registry")
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of the Splinter daemon REST API"),
+                )
+                .arg(
+                    Arg::with_name("identity")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Splinter identity of the node"),
+                )
+                .arg(
+                    Arg::with_name("endpoints")
+                        .long("endpoint")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Endpoint the node can be reached at"),
+                )
+                .arg(
+                    Arg::with_name("display_name")
+                        .long("display-name")
+                        .takes_value(true)
+                        .help("Human-readable name of the node; defaults to 'Node <identity>'"),
+                )
+                .arg(
+                    Arg::with_name("key_files")
+                        .long("key-file")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Path of public key file to include with node"),
+                )
+                .arg(
+                    Arg::with_name("metadata")
+                        .long("metadata")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Metadata to include with node (<key>=<value>)"),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Overwrite node if it already exists"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("remove")
+                .about("Remove nodes from a node's registry as a single transaction")
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of the Splinter daemon REST API"),
+                )
+                .arg(
+                    Arg::with_name("identities")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Splinter identities of the nodes to remove"),
+                ),
+        );
+
+    #[cfg(feature = "registry-node-signing")]
+    let registry_command = registry_command.subcommand(
+        SubCommand::with_name("add-self")
+            .about("Add this node's own signed entry to a registry")
+            .arg(
+                Arg::with_name("url")
+                    .short("U")
+                    .long("url")
+                    .takes_value(true)
+                    .help("URL of the Splinter daemon to query for node data"),
+            )
+            .arg(
+                Arg::with_name("registry_url")
+                    .long("registry-url")
+                    .takes_value(true)
+                    .help(
+                        "URL of the Splinter REST API that serves the registry; defaults to the \
+                         URL of the Splinter daemon",
                     ),
+            )
+            .arg(
+                Arg::with_name("key")
+                    .value_name("private-key-file")
+                    .short("k")
+                    .long("key")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Path to the private key file of the node's key that signs the entry"),
+            )
+            .arg(
+                Arg::with_name("key_files")
+                    .long("key-file")
+                    .takes_value(true)
+                    .multiple(true)
+                    .help("Path of an additional public key file to include with node"),
+            )
+            .arg(
+                Arg::with_name("metadata")
+                    .long("metadata")
+                    .takes_value(true)
+                    .multiple(true)
+                    .help("Metadata to include with node (<key>=<value>)"),
+            ),
+    );
+
+    #[cfg(feature = "registry-merge")]
+    let registry_command = registry_command.subcommand(
+        SubCommand::with_name("conflicts")
+            .about("List the nodes whose definitions conflict between a node's registries")
+            .arg(
+                Arg::with_name("url")
+                    .short("U")
+                    .long("url")
+                    .takes_value(true)
+                    .help("URL of the Splinter daemon REST API"),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .short("f")
+                    .long("format")
+                    .help("Output format")
+                    .possible_values(&["human", "csv"])
+                    .default_value("human")
+                    .takes_value(true),
             ),
     );
 
+    #[cfg(feature = "registry-signing")]
+    let registry_command = registry_command
+        .subcommand(
+            SubCommand::with_name("sign")
+                .about("Sign a registry YAML file")
+                .arg(
+                    Arg::with_name("key")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the registry publisher's private key file"),
+                )
+                .arg(
+                    Arg::with_name("signature")
+                        .long("signature")
+                        .takes_value(true)
+                        .help("Path of the signature file to write; defaults to '<file>.sig'"),
+                )
+                .arg(
+                    Arg::with_name("serial")
+                        .long("serial")
+                        .takes_value(true)
+                        .help(
+                            "Serial number of this version of the file, which must be higher \
+                             than that of the previously published version; defaults to the \
+                             current Unix time in seconds",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path of the registry YAML file to sign"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Verify the signature of a registry YAML file")
+                .arg(
+                    Arg::with_name("trust_keys")
+                        .value_name("public-key-file")
+                        .long("trust-key")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .help("Path to the public key file of a trusted registry publisher"),
+                )
+                .arg(
+                    Arg::with_name("signature")
+                        .long("signature")
+                        .takes_value(true)
+                        .help("Path of the signature file; defaults to '<file>.sig'"),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path of the registry YAML file to verify"),
+                ),
+        );
+
+    app = app.subcommand(registry_command);
+
     #[cfg(feature = "health")]
     {
         app = app.subcommand(
@@ -582,7 +913,7 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
                             Arg::with_name("connect")
                                 .short("C")
                                 .takes_value(true)
-                                .help("Database connection URI"),
+                                .help("Database URL or path to a SQLite database file"),
                         ),
                 ),
         )
@@ -650,10 +981,35 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
 
     subcommands = subcommands.with_command("circuit", circuit_command);
 
-    subcommands = subcommands.with_command(
-        "registry",
-        SubcommandActions::new().with_command("build", registry::RegistryGenerateAction),
-    );
+    let registry_command =
+        SubcommandActions::new().with_command("build", registry::RegistryGenerateAction);
+
+    #[cfg(feature = "registry-health")]
+    let registry_command = registry_command.with_command("check", registry::RegistryCheckAction);
+
+    #[cfg(any(feature = "registry-bulk", feature = "registry-database"))]
+    let registry_command = registry_command.with_command("import", registry::RegistryImportAction);
+
+    #[cfg(feature = "registry-bulk")]
+    let registry_command = registry_command
+        .with_command("add", registry::RegistryAddAction)
+        .with_command("export", registry::RegistryExportAction)
+        .with_command("remove", registry::RegistryRemoveAction);
+
+    #[cfg(feature = "registry-node-signing")]
+    let registry_command =
+        registry_command.with_command("add-self", registry::RegistryAddSelfAction);
+
+    #[cfg(feature = "registry-merge")]
+    let registry_command =
+        registry_command.with_command("conflicts", registry::RegistryConflictsAction);
+
+    #[cfg(feature = "registry-signing")]
+    let registry_command = registry_command
+        .with_command("sign", registry::RegistrySignAction)
+        .with_command("verify", registry::RegistryVerifyAction);
+
+    subcommands = subcommands.with_command("registry", registry_command);
 
     #[cfg(feature = "health")]
     {
@@ -30,6 +30,8 @@ use std::time::{Duration, SystemTime};
 use openssl::hash::{hash, MessageDigest};
 use protobuf::{self, Message};
 
+#[cfg(feature = "audit")]
+use crate::audit::AuditLog;
 use crate::circuit::SplinterState;
 use crate::consensus::Proposal;
 use crate::hex::to_hex;
@@ -62,6 +64,9 @@ pub use self::shared::AdminServiceStatus;
 
 const DEFAULT_COORDINATOR_TIMEOUT: u64 = 30; // 30 seconds
 
+/// Subscribers added with this event type receive the events for all circuit management types.
+pub const ALL_CIRCUIT_MANAGEMENT_TYPES: &str = "*";
+
 pub trait AdminServiceEventSubscriber: Send {
     fn handle_event(
         &self,
@@ -243,6 +248,18 @@ impl AdminService {
         AdminServiceProposals::new(&self.admin_service_shared)
     }
 
+    /// Records the circuit proposals and votes submitted to this node in the given audit log,
+    /// with the public key that signed each payload as the actor.
+    #[cfg(feature = "audit")]
+    pub fn set_audit_log(&self, audit_log: AuditLog) -> Result<(), AdminServiceError> {
+        self.admin_service_shared
+            .lock()
+            .map_err(|_| AdminServiceError::general_error("Admin shared lock was lock poisoned"))?
+            .set_audit_log(audit_log);
+
+        Ok(())
+    }
+
     /// On restart of a splinter node, all services that this node should run on the existing
     /// circuits should be initialized using the service orchestrator. This may not include all
     /// services if they are not supported locally. It is expected that some services will be
@@ -22,6 +22,8 @@ use std::time::SystemTime;
 use protobuf::{Message, RepeatedField};
 use std::sync::mpsc::Sender;
 
+#[cfg(feature = "audit")]
+use crate::audit::{unverified_actor, AuditLog};
 use crate::circuit::SplinterState;
 use crate::circuit::{
     service::SplinterNode as StateNode,
@@ -59,7 +61,7 @@ use super::messages;
 use super::open_proposals::OpenProposals;
 use super::{
     admin_service_id, sha256, AdminKeyVerifier, AdminServiceEventSubscriber, AdminSubscriberError,
-    Events,
+    Events, ALL_CIRCUIT_MANAGEMENT_TYPES,
 };
 
 static VOTER_ROLE: &str = "voter";
@@ -123,17 +125,19 @@ impl SubscriberMap {
         timestamp: &SystemTime,
     ) {
         let mut subscribers_by_type = self.subscribers_by_type.borrow_mut();
-        if let Some(subscribers) = subscribers_by_type.get_mut(event_type) {
-            subscribers.retain(|subscriber| {
-                match subscriber.handle_event(admin_service_event, timestamp) {
-                    Ok(()) => true,
-                    Err(AdminSubscriberError::Unsubscribe) => false,
-                    Err(AdminSubscriberError::UnableToHandleEvent(msg)) => {
-                        error!("Unable to send event: {}", msg);
-                        true
+        for subscriber_type in &[event_type, ALL_CIRCUIT_MANAGEMENT_TYPES] {
+            if let Some(subscribers) = subscribers_by_type.get_mut(*subscriber_type) {
+                subscribers.retain(|subscriber| {
+                    match subscriber.handle_event(admin_service_event, timestamp) {
+                        Ok(()) => true,
+                        Err(AdminSubscriberError::Unsubscribe) => false,
+                        Err(AdminSubscriberError::UnableToHandleEvent(msg)) => {
+                            error!("Unable to send event: {}", msg);
+                            true
+                        }
                     }
-                }
-            });
+                });
+            }
         }
     }
 
@@ -200,6 +204,9 @@ pub struct AdminServiceShared {
     key_verifier: Box<dyn AdminKeyVerifier>,
     key_permission_manager: Box<dyn KeyPermissionManager>,
     proposal_sender: Option<Sender<ProposalUpdate>>,
+    // Records the circuit management payloads submitted to this node
+    #[cfg(feature = "audit")]
+    audit_log: AuditLog,
 
     admin_service_status: AdminServiceStatus,
 }
@@ -262,6 +269,8 @@ impl AdminServiceShared {
             key_verifier,
             key_permission_manager,
             proposal_sender: None,
+            #[cfg(feature = "audit")]
+            audit_log: AuditLog::default(),
             admin_service_status: AdminServiceStatus::NotRunning,
         })
     }
@@ -282,6 +291,11 @@ impl AdminServiceShared {
         self.proposal_sender = proposal_sender;
     }
 
+    #[cfg(feature = "audit")]
+    pub fn set_audit_log(&mut self, audit_log: AuditLog) {
+        self.audit_log = audit_log;
+    }
+
     pub fn pop_pending_circuit_payload(&mut self) -> Option<CircuitManagementPayload> {
         self.pending_circuit_payloads.pop_front()
     }
@@ -815,10 +829,74 @@ impl AdminServiceShared {
 
         let header =
             protobuf::parse_from_bytes::<CircuitManagementPayload_Header>(payload.get_header())?;
-        self.validate_circuit_management_payload(&payload, &header)
+
+        #[cfg(feature = "audit")]
+        let audited_action = match header.get_action() {
+            CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST => Some((
+                "circuit.propose",
+                payload
+                    .get_circuit_create_request()
+                    .get_circuit()
+                    .get_circuit_id()
+                    .to_string(),
+            )),
+            CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_VOTE => {
+                let proposal_vote = payload.get_circuit_proposal_vote();
+                let action = match proposal_vote.get_vote() {
+                    CircuitProposalVote_Vote::ACCEPT => "circuit.vote.accept",
+                    CircuitProposalVote_Vote::REJECT => "circuit.vote.reject",
+                    CircuitProposalVote_Vote::UNSET_VOTE => "circuit.vote",
+                };
+                Some((action, proposal_vote.get_circuit_id().to_string()))
+            }
+            _ => None,
+        };
+
+        let verification = self.verify_payload(&payload, &header);
+        #[cfg(feature = "audit")]
+        let verified = verification.is_ok();
+        let result = verification.and_then(|_| self.submit_payload(payload, &header));
+
+        #[cfg(feature = "audit")]
+        {
+            if let Some((action, circuit_id)) = audited_action {
+                // The requester is only known to have taken the action if it signed the payload
+                let requester = to_hex(header.get_requester());
+                let actor = if verified {
+                    requester
+                } else {
+                    unverified_actor(&requester)
+                };
+                self.audit_log
+                    .record_result(&actor, action, &circuit_id, &result);
+            }
+        }
+
+        result
+    }
+
+    /// Validates a submitted payload and verifies that it was signed by its requester
+    fn verify_payload(
+        &self,
+        payload: &CircuitManagementPayload,
+        header: &CircuitManagementPayload_Header,
+    ) -> Result<(), ServiceError> {
+        self.validate_circuit_management_payload(payload, header)
             .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
-        self.verify_signature(&payload)?;
+        if self.verify_signature(payload)? {
+            Ok(())
+        } else {
+            Err(ServiceError::UnableToHandleMessage(Box::new(
+                AdminSharedError::ValidationFailed(String::from("Unable to verify signature")),
+            )))
+        }
+    }
 
+    fn submit_payload(
+        &mut self,
+        payload: CircuitManagementPayload,
+        header: &CircuitManagementPayload_Header,
+    ) -> Result<(), ServiceError> {
         match header.get_action() {
             CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST => {
                 let signer_public_key = header.get_requester();
@@ -3211,6 +3289,62 @@ mod tests {
         shutdown(mesh, cm, pm);
     }
 
+    #[cfg(feature = "audit")]
+    #[test]
+    // test that a submitted payload whose signature does not verify is rejected, and that it is
+    // recorded in the audit log with the requester marked as unverified.
+    fn test_submit_unverified_signature_audited() {
+        use crate::audit::store::{AuditStore, MemoryAuditStore};
+        use crate::audit::AuditOutcome;
+
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let mut shared = AdminServiceShared::new(
+            "node_a".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            state,
+            Box::new(HashVerifier),
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            "memory",
+            STATE_DIR,
+        )
+        .unwrap();
+        let audit_store = MemoryAuditStore::new();
+        shared.set_audit_log(AuditLog::new(audit_store.clone()));
+
+        let circuit = setup_test_circuit();
+        let circuit_id = circuit.get_circuit_id().to_string();
+
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
+
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        header.set_requester(PUB_KEY.into());
+        header.set_requester_node_id("node_a".to_string());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        payload.set_signature(b"not a signature".to_vec());
+        payload.set_circuit_create_request(request);
+
+        assert!(shared.submit(payload).is_err());
+
+        let entries = audit_store.list_entries(0, 10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, unverified_actor(&to_hex(PUB_KEY)));
+        assert_eq!(entries[0].action, "circuit.propose");
+        assert_eq!(entries[0].target, circuit_id);
+        assert!(matches!(entries[0].outcome, AuditOutcome::Failure(_)));
+
+        shutdown(mesh, cm, pm);
+    }
+
     #[test]
     // test that the validate_circuit_management_payload method returns an error in case the
     // header is empty.
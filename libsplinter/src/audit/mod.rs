@@ -0,0 +1,188 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An append-only log of administrative and authentication actions.
+//!
+//! Each [`AuditEntry`] records who performed an action (the actor, such as a biome user ID or the
+//! public key that signed a request), what the action was, what it acted on, whether it
+//! succeeded, and when it happened. Components record entries through an [`AuditLog`], which
+//! appends them to an [`AuditStore`]; entries are never updated or removed.
+//!
+//! Actions are named `<component>.<action>`; for example, `circuit.propose`, `biome.login` or
+//! `registry.node.add`.
+//!
+//! [`AuditEntry`]: struct.AuditEntry.html
+//! [`AuditLog`]: struct.AuditLog.html
+//! [`AuditStore`]: store/trait.AuditStore.html
+
+#[cfg(feature = "diesel")]
+pub mod migrations;
+#[cfg(feature = "rest-api")]
+pub mod rest_api;
+pub mod store;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use store::AuditStore;
+
+/// The actor recorded for requests whose client could not be identified
+pub const ANONYMOUS_ACTOR: &str = "anonymous";
+
+/// Returns the actor recorded for actions taken by the biome user with the given ID. This is the
+/// same as the user's REST API identity, `user:<id>`.
+pub fn user_actor(user_id: &str) -> String {
+    format!("user:{}", user_id)
+}
+
+/// Returns the actor recorded for an action requested on behalf of the given actor, whose
+/// identity could not be verified; for example, because the request's signature is invalid.
+pub fn unverified_actor(actor: &str) -> String {
+    format!("unverified:{}", actor)
+}
+
+/// Whether an audited action succeeded
+#[derive(Clone, Debug, PartialEq)]
+pub enum AuditOutcome {
+    Success,
+    /// The action failed for the given reason
+    Failure(String),
+}
+
+impl<T, E: fmt::Display> From<&Result<T, E>> for AuditOutcome {
+    fn from(result: &Result<T, E>) -> Self {
+        match result {
+            Ok(_) => AuditOutcome::Success,
+            Err(err) => AuditOutcome::Failure(err.to_string()),
+        }
+    }
+}
+
+/// A record of an action taken by an actor
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    /// When the action was taken
+    pub timestamp: SystemTime,
+    /// Who took the action; for example, `user:<biome user id>` or a hex-encoded public key
+    pub actor: String,
+    /// What the action was, named `<component>.<action>`
+    pub action: String,
+    /// What the action was taken on; for example, a circuit ID or a node ID
+    pub target: String,
+    /// Whether the action succeeded
+    pub outcome: AuditOutcome,
+}
+
+impl AuditEntry {
+    /// Creates an entry for an action that was taken now
+    pub fn new(actor: &str, action: &str, target: &str, outcome: AuditOutcome) -> Self {
+        AuditEntry {
+            timestamp: SystemTime::now(),
+            actor: actor.to_string(),
+            action: action.to_string(),
+            target: target.to_string(),
+            outcome,
+        }
+    }
+}
+
+/// Records audit entries in an `AuditStore`.
+///
+/// A default `AuditLog` has no store and discards the entries recorded in it, so components can
+/// record entries whether or not auditing is enabled.
+#[derive(Clone, Default)]
+pub struct AuditLog {
+    store: Option<Arc<dyn AuditStore>>,
+}
+
+impl AuditLog {
+    /// Creates an `AuditLog` that appends entries to the given store
+    pub fn new(store: impl AuditStore + 'static) -> Self {
+        AuditLog {
+            store: Some(Arc::new(store)),
+        }
+    }
+
+    /// Returns the store entries are appended to, if any
+    pub fn store(&self) -> Option<Arc<dyn AuditStore>> {
+        self.store.clone()
+    }
+
+    /// Records that the actor took an action on the target.
+    ///
+    /// Failing to store the entry is logged rather than returned, so that an unavailable audit
+    /// store does not prevent the action itself.
+    pub fn record(&self, actor: &str, action: &str, target: &str, outcome: AuditOutcome) {
+        if let Some(store) = &self.store {
+            let entry = AuditEntry::new(actor, action, target, outcome);
+            if let Err(err) = store.add_entry(entry) {
+                error!(
+                    "Unable to record {} of {} by {} in audit log: {}",
+                    action, target, actor, err
+                );
+            }
+        }
+    }
+
+    /// Records that the actor took an action on the target, with an outcome determined by the
+    /// action's result
+    pub fn record_result<T, E: fmt::Display>(
+        &self,
+        actor: &str,
+        action: &str,
+        target: &str,
+        result: &Result<T, E>,
+    ) {
+        self.record(actor, action, target, AuditOutcome::from(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use store::MemoryAuditStore;
+
+    /// Verify that an `AuditLog` appends entries to its store in the order they are recorded, and
+    /// that a default `AuditLog` discards them.
+    #[test]
+    fn record_entries() {
+        let store = MemoryAuditStore::new();
+        let audit_log = AuditLog::new(store.clone());
+
+        audit_log.record("user:abc", "biome.login", "alice", AuditOutcome::Success);
+        audit_log.record_result::<(), _>(
+            "0123",
+            "circuit.vote",
+            "alpha-beta",
+            &Err("no such proposal"),
+        );
+
+        let entries = store.list_entries(0, 10).expect("failed to list entries");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].actor, "user:abc");
+        assert_eq!(entries[0].action, "biome.login");
+        assert_eq!(entries[0].target, "alice");
+        assert_eq!(entries[0].outcome, AuditOutcome::Success);
+        assert_eq!(entries[1].action, "circuit.vote");
+        assert_eq!(
+            entries[1].outcome,
+            AuditOutcome::Failure("no such proposal".into())
+        );
+
+        AuditLog::default().record("user:abc", "biome.login", "alice", AuditOutcome::Success);
+        assert_eq!(store.count_entries().expect("failed to count entries"), 2);
+    }
+}
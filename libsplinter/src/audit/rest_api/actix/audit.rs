@@ -0,0 +1,210 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /audit` for listing the entries in the audit log
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+use crate::audit::{
+    rest_api::resources::{AuditEntryResponse, ListAuditEntriesResponse},
+    store::AuditStore,
+};
+use crate::futures::{future::IntoFuture, Future};
+use crate::protocol;
+use crate::rest_api::{
+    paging::{get_response_paging_info, DEFAULT_LIMIT, DEFAULT_OFFSET},
+    ErrorResponse, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+pub fn make_audit_resource(store: Arc<dyn AuditStore>) -> Resource {
+    Resource::build("/audit")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::AUDIT_LIST_ENTRIES_MIN,
+            protocol::AUDIT_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |r, _| list_entries(r, store.clone()))
+}
+
+fn list_entries(
+    req: HttpRequest,
+    store: Arc<dyn AuditStore>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let query: web::Query<HashMap<String, String>> =
+        if let Ok(q) = web::Query::from_query(req.query_string()) {
+            q
+        } else {
+            return Box::new(
+                HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request("Invalid query"))
+                    .into_future(),
+            );
+        };
+
+    let offset = match query.get("offset") {
+        Some(value) => match value.parse::<usize>() {
+            Ok(val) => val,
+            Err(err) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Invalid offset value passed: {}. Error: {}",
+                            value, err
+                        )))
+                        .into_future(),
+                )
+            }
+        },
+        None => DEFAULT_OFFSET,
+    };
+
+    let limit = match query.get("limit") {
+        Some(value) => match value.parse::<usize>() {
+            Ok(val) => val,
+            Err(err) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Invalid limit value passed: {}. Error: {}",
+                            value, err
+                        )))
+                        .into_future(),
+                )
+            }
+        },
+        None => DEFAULT_LIMIT,
+    };
+
+    let link = format!("{}?", req.uri().path());
+
+    Box::new(
+        web::block(move || {
+            let total = store.count_entries().map_err(|err| err.to_string())?;
+            let entries = store
+                .list_entries(offset, limit)
+                .map_err(|err| err.to_string())?;
+            Ok((entries, total))
+        })
+        .then(move |res: Result<_, BlockingError<String>>| match res {
+            Ok((entries, total)) => Ok(HttpResponse::Ok().json(ListAuditEntriesResponse {
+                data: entries.iter().map(AuditEntryResponse::from).collect(),
+                paging: get_response_paging_info(Some(limit), Some(offset), &link, total),
+            })),
+            Err(err) => {
+                error!("Unable to list audit entries: {}", err);
+                Ok(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+            }
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
+
+    use crate::audit::{store::MemoryAuditStore, AuditEntry, AuditOutcome};
+    use crate::rest_api::{RestApiBuilder, RestApiServerError, RestApiShutdownHandle};
+
+    #[test]
+    /// Tests that a GET /audit request returns the requested page of entries, oldest first, with
+    /// paging information.
+    fn test_list_entries_ok() {
+        let store = MemoryAuditStore::new();
+        for i in 0..3 {
+            store
+                .add_entry(AuditEntry::new(
+                    "user:abc",
+                    "registry.node.add",
+                    &format!("node-{}", i),
+                    AuditOutcome::Success,
+                ))
+                .expect("failed to add entry");
+        }
+        store
+            .add_entry(AuditEntry::new(
+                "anonymous",
+                "biome.login",
+                "alice",
+                AuditOutcome::Failure("Invalid password".into()),
+            ))
+            .expect("failed to add entry");
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_audit_resource(Arc::new(store))]);
+
+        let url = Url::parse(&format!("http://{}/audit?offset=2&limit=2", bind_url))
+            .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header("SplinterProtocolVersion", protocol::AUDIT_PROTOCOL_VERSION)
+            .send()
+            .expect("Failed to perform request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+
+        let entries = body
+            .get("data")
+            .expect("No data field in response")
+            .as_array()
+            .expect("data field is not an array")
+            .to_vec();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["target"], "node-2");
+        assert_eq!(entries[0]["outcome"], "success");
+        assert!(entries[0].get("reason").is_none());
+        assert_eq!(entries[1]["action"], "biome.login");
+        assert_eq!(entries[1]["outcome"], "failure");
+        assert_eq!(entries[1]["reason"], "Invalid password");
+
+        let paging = body.get("paging").expect("no paging field in response");
+        assert_eq!(paging["total"], 4);
+        assert_eq!(paging["offset"], 2);
+        assert_eq!(paging["limit"], 2);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        (10000..20000)
+            .find_map(|port| {
+                let bind_url = format!("127.0.0.1:{}", port);
+                let result = RestApiBuilder::new()
+                    .with_bind(&bind_url)
+                    .add_resources(resources.clone())
+                    .build()
+                    .expect("Failed to build REST API")
+                    .run();
+                match result {
+                    Ok((shutdown_handle, join_handle)) => {
+                        Some((shutdown_handle, join_handle, bind_url))
+                    }
+                    Err(RestApiServerError::BindError(_)) => None,
+                    Err(err) => panic!("Failed to run REST API: {}", err),
+                }
+            })
+            .expect("No port available")
+    }
+}
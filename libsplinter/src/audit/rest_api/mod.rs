@@ -0,0 +1,66 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module defines the REST API endpoints for reading the audit log.
+
+#[cfg(feature = "rest-api-actix")]
+mod actix;
+mod resources;
+
+use crate::actix_web::HttpRequest;
+#[cfg(feature = "rest-api-auth")]
+use crate::rest_api::auth::Identity;
+use crate::rest_api::{Resource, RestResourceProvider};
+
+use super::{AuditLog, ANONYMOUS_ACTOR};
+
+/// The audit log provides the following endpoints as REST API resources, if it has a store:
+///
+/// * `GET /audit` - List the entries in the audit log, oldest first
+///
+/// These endpoints are only available if the following REST API backend feature is enabled:
+///
+/// * `rest-api-actix`
+impl RestResourceProvider for AuditLog {
+    fn resources(&self) -> Vec<Resource> {
+        // Allowing unused_mut because resources must be mutable if feature rest-api-actix is
+        // enabled
+        #[allow(unused_mut)]
+        let mut resources = Vec::new();
+
+        #[cfg(feature = "rest-api-actix")]
+        {
+            if let Some(store) = self.store() {
+                resources.push(actix::audit::make_audit_resource(store));
+            }
+        }
+
+        resources
+    }
+}
+
+/// Returns the actor to record for a REST API request: the identity of the client that made the
+/// request, if it was authenticated, or `anonymous` otherwise.
+pub fn request_actor(request: &HttpRequest) -> String {
+    #[cfg(feature = "rest-api-auth")]
+    {
+        if let Some(identity) = request.extensions().get::<Identity>() {
+            return identity.to_string();
+        }
+    }
+    #[cfg(not(feature = "rest-api-auth"))]
+    let _ = request;
+
+    ANONYMOUS_ACTOR.to_string()
+}
@@ -0,0 +1,57 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::UNIX_EPOCH;
+
+use crate::audit::{AuditEntry, AuditOutcome};
+use crate::rest_api::paging::Paging;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListAuditEntriesResponse<'a> {
+    pub data: Vec<AuditEntryResponse<'a>>,
+    pub paging: Paging,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditEntryResponse<'a> {
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub actor: &'a str,
+    pub action: &'a str,
+    pub target: &'a str,
+    pub outcome: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'a str>,
+}
+
+impl<'a> From<&'a AuditEntry> for AuditEntryResponse<'a> {
+    fn from(entry: &'a AuditEntry) -> Self {
+        let (outcome, reason) = match &entry.outcome {
+            AuditOutcome::Success => ("success", None),
+            AuditOutcome::Failure(reason) => ("failure", Some(reason.as_str())),
+        };
+        Self {
+            timestamp: entry
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            actor: &entry.actor,
+            action: &entry.action,
+            target: &entry.target,
+            outcome,
+            reason,
+        }
+    }
+}
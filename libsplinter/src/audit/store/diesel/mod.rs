@@ -0,0 +1,68 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod models;
+mod operations;
+mod schema;
+
+use crate::audit::{
+    store::{AuditStore, AuditStoreError},
+    AuditEntry,
+};
+use crate::database::ConnectionPool;
+use operations::{
+    add_entry::AuditStoreAddEntryOperation, count_entries::AuditStoreCountEntriesOperation,
+    list_entries::AuditStoreListEntriesOperation, AuditStoreOperations,
+};
+
+/// Manages appending and listing audit entries in the database
+pub struct DieselAuditStore {
+    connection_pool: ConnectionPool,
+}
+
+impl DieselAuditStore {
+    /// Creates a new DieselAuditStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: ConnectionPool) -> Self {
+        Self { connection_pool }
+    }
+}
+
+impl AuditStore for DieselAuditStore {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            AuditStoreOperations::new(conn).add_entry(entry)
+        })
+    }
+
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            AuditStoreOperations::new(conn).list_entries(offset, limit)
+        })
+    }
+
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            AuditStoreOperations::new(conn).count_entries()
+        })
+    }
+}
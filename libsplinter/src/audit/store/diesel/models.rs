@@ -0,0 +1,76 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::schema::audit_entries;
+use crate::audit::{
+    store::{OUTCOME_FAILURE, OUTCOME_SUCCESS},
+    AuditEntry, AuditOutcome,
+};
+use crate::database::DbTimestamp;
+
+#[derive(Insertable, PartialEq, Debug)]
+#[table_name = "audit_entries"]
+pub struct NewAuditEntryModel {
+    pub created: DbTimestamp,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub outcome: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Queryable, PartialEq, Debug)]
+pub struct AuditEntryModel {
+    pub id: i64,
+    pub created: DbTimestamp,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub outcome: String,
+    pub reason: Option<String>,
+}
+
+impl From<AuditEntry> for NewAuditEntryModel {
+    fn from(entry: AuditEntry) -> Self {
+        let (outcome, reason) = match entry.outcome {
+            AuditOutcome::Success => (OUTCOME_SUCCESS.to_string(), None),
+            AuditOutcome::Failure(reason) => (OUTCOME_FAILURE.to_string(), Some(reason)),
+        };
+        NewAuditEntryModel {
+            created: entry.timestamp.into(),
+            actor: entry.actor,
+            action: entry.action,
+            target: entry.target,
+            outcome,
+            reason,
+        }
+    }
+}
+
+impl From<AuditEntryModel> for AuditEntry {
+    fn from(model: AuditEntryModel) -> Self {
+        let outcome = if model.outcome == OUTCOME_SUCCESS {
+            AuditOutcome::Success
+        } else {
+            AuditOutcome::Failure(model.reason.unwrap_or_default())
+        };
+        AuditEntry {
+            timestamp: model.created.into(),
+            actor: model.actor,
+            action: model.action,
+            target: model.target,
+            outcome,
+        }
+    }
+}
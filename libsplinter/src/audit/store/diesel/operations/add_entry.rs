@@ -0,0 +1,60 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AuditStoreOperations;
+use crate::audit::{
+    store::{
+        diesel::{models::NewAuditEntryModel, schema::audit_entries},
+        AuditStoreError,
+    },
+    AuditEntry,
+};
+
+use diesel::{dsl::insert_into, prelude::*};
+
+pub(in crate::audit) trait AuditStoreAddEntryOperation {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuditStoreAddEntryOperation for AuditStoreOperations<'a, diesel::pg::PgConnection> {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        let action = entry.action.clone();
+        insert_into(audit_entries::table)
+            .values(NewAuditEntryModel::from(entry))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| AuditStoreError::OperationError {
+                context: format!("Failed to add audit entry for {}", action),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuditStoreAddEntryOperation
+    for AuditStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        let action = entry.action.clone();
+        insert_into(audit_entries::table)
+            .values(NewAuditEntryModel::from(entry))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| AuditStoreError::OperationError {
+                context: format!("Failed to add audit entry for {}", action),
+                source: Box::new(err),
+            })
+    }
+}
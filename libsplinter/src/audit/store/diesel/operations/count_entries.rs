@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AuditStoreOperations;
+use crate::audit::store::{diesel::schema::audit_entries, AuditStoreError};
+
+use diesel::{dsl::count_star, prelude::*};
+
+pub(in crate::audit) trait AuditStoreCountEntriesOperation {
+    fn count_entries(&self) -> Result<usize, AuditStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuditStoreCountEntriesOperation for AuditStoreOperations<'a, diesel::pg::PgConnection> {
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        audit_entries::table
+            .select(count_star())
+            .first::<i64>(self.conn)
+            .map(|count| count as usize)
+            .map_err(|err| AuditStoreError::QueryError {
+                context: "Failed to count audit entries".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuditStoreCountEntriesOperation
+    for AuditStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        audit_entries::table
+            .select(count_star())
+            .first::<i64>(self.conn)
+            .map(|count| count as usize)
+            .map_err(|err| AuditStoreError::QueryError {
+                context: "Failed to count audit entries".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AuditStoreOperations;
+use crate::audit::{
+    store::{
+        diesel::{models::AuditEntryModel, schema::audit_entries},
+        AuditStoreError,
+    },
+    AuditEntry,
+};
+
+use diesel::prelude::*;
+
+pub(in crate::audit) trait AuditStoreListEntriesOperation {
+    fn list_entries(&self, offset: usize, limit: usize)
+        -> Result<Vec<AuditEntry>, AuditStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuditStoreListEntriesOperation for AuditStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        audit_entries::table
+            .order(audit_entries::id.asc())
+            .offset(offset as i64)
+            .limit(limit as i64)
+            .load::<AuditEntryModel>(self.conn)
+            .map(|entries| entries.into_iter().map(AuditEntry::from).collect())
+            .map_err(|err| AuditStoreError::QueryError {
+                context: "Failed to list audit entries".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuditStoreListEntriesOperation
+    for AuditStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        audit_entries::table
+            .order(audit_entries::id.asc())
+            .offset(offset as i64)
+            .limit(limit as i64)
+            .load::<AuditEntryModel>(self.conn)
+            .map(|entries| entries.into_iter().map(AuditEntry::from).collect())
+            .map_err(|err| AuditStoreError::QueryError {
+                context: "Failed to list audit entries".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
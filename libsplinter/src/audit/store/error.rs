@@ -0,0 +1,91 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "diesel")]
+use crate::database::error;
+
+/// Represents AuditStore errors
+#[derive(Debug)]
+pub enum AuditStoreError {
+    /// Represents CRUD operations failures
+    OperationError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents database query failures
+    QueryError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents general failures in the underlying storage
+    StorageError {
+        context: String,
+        source: Option<Box<dyn Error>>,
+    },
+    /// Represents an issue connecting to the database
+    ConnectionError(Box<dyn Error>),
+}
+
+impl Error for AuditStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AuditStoreError::OperationError { source, .. } => Some(&**source),
+            AuditStoreError::QueryError { source, .. } => Some(&**source),
+            AuditStoreError::StorageError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            AuditStoreError::StorageError { source: None, .. } => None,
+            AuditStoreError::ConnectionError(err) => Some(&**err),
+        }
+    }
+}
+
+impl fmt::Display for AuditStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuditStoreError::OperationError { context, source } => {
+                write!(f, "failed to perform operation: {}: {}", context, source)
+            }
+            AuditStoreError::QueryError { context, source } => {
+                write!(f, "failed query: {}: {}", context, source)
+            }
+            AuditStoreError::StorageError {
+                context,
+                source: Some(source),
+            } => write!(
+                f,
+                "the underlying storage returned an error: {}: {}",
+                context, source
+            ),
+            AuditStoreError::StorageError {
+                context,
+                source: None,
+            } => write!(f, "the underlying storage returned an error: {}", context),
+            AuditStoreError::ConnectionError(ref s) => {
+                write!(f, "failed to connect to underlying storage: {}", s)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<error::ConnectionError> for AuditStoreError {
+    fn from(err: error::ConnectionError) -> AuditStoreError {
+        AuditStoreError::ConnectionError(Box::new(err))
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::audit::{
+    store::{AuditStore, AuditStoreError, OUTCOME_FAILURE, OUTCOME_SUCCESS},
+    AuditEntry, AuditOutcome,
+};
+
+/// The entries read from an audit log file
+type Entries = Box<dyn Iterator<Item = Result<FileAuditEntry, AuditStoreError>>>;
+
+/// Appends audit entries to a file with one JSON object per line.
+///
+/// Each line has the form:
+///
+/// ```json
+/// {"timestamp":1588000000000,"actor":"user:<id>","action":"biome.login","target":"alice","outcome":"success"}
+/// ```
+///
+/// where `timestamp` is milliseconds since the Unix epoch; entries of failed actions also have a
+/// `reason`. The file is created if it does not exist, and is only ever appended to.
+#[derive(Clone)]
+pub struct FileAuditStore {
+    path: PathBuf,
+    /// Serializes writes from the threads sharing this store
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl FileAuditStore {
+    /// Creates a store that appends entries to the file at the given path
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FileAuditStore {
+            path: path.as_ref().to_path_buf(),
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Reads the entries on the non-empty lines of the file, or no entries if the file does not
+    /// exist yet. The file is read as the entries are consumed; lines that are not valid entries
+    /// are skipped with a warning.
+    fn entries(&self) -> Result<Entries, AuditStoreError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(Box::new(std::iter::empty()))
+            }
+            Err(err) => {
+                return Err(AuditStoreError::StorageError {
+                    context: format!("Unable to open audit log {}", self.path.display()),
+                    source: Some(Box::new(err)),
+                })
+            }
+        };
+
+        let path = self.path.clone();
+        Ok(Box::new(
+            BufReader::new(file)
+                .lines()
+                .enumerate()
+                .filter_map(move |(index, line)| {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(err) => {
+                            return Some(Err(AuditStoreError::StorageError {
+                                context: format!("Unable to read audit log {}", path.display()),
+                                source: Some(Box::new(err)),
+                            }))
+                        }
+                    };
+                    if line.is_empty() {
+                        return None;
+                    }
+                    match serde_json::from_str(&line) {
+                        Ok(entry) => Some(Ok(entry)),
+                        Err(err) => {
+                            warn!(
+                                "Skipping invalid entry on line {} of audit log {}: {}",
+                                index + 1,
+                                path.display(),
+                                err
+                            );
+                            None
+                        }
+                    }
+                }),
+        ))
+    }
+}
+
+impl AuditStore for FileAuditStore {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        let mut line = serde_json::to_vec(&FileAuditEntry::from(entry)).map_err(|err| {
+            AuditStoreError::OperationError {
+                context: "Unable to serialize audit entry".to_string(),
+                source: Box::new(err),
+            }
+        })?;
+        line.push(b'\n');
+
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| AuditStoreError::StorageError {
+                context: "Cannot access audit log: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|err| AuditStoreError::StorageError {
+                context: format!("Unable to write to audit log {}", self.path.display()),
+                source: Some(Box::new(err)),
+            })
+    }
+
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        let mut entries = Vec::new();
+        for (index, entry) in self.entries()?.enumerate() {
+            if index >= offset.saturating_add(limit) {
+                break;
+            }
+            let entry = entry?;
+            if index >= offset {
+                entries.push(AuditEntry::from(entry));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        let mut count = 0;
+        for entry in self.entries()? {
+            entry?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// The form of an entry on a line of the audit log file
+#[derive(Serialize, Deserialize)]
+struct FileAuditEntry {
+    /// Milliseconds since the Unix epoch
+    timestamp: u64,
+    actor: String,
+    action: String,
+    target: String,
+    outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl From<AuditEntry> for FileAuditEntry {
+    fn from(entry: AuditEntry) -> Self {
+        let (outcome, reason) = match entry.outcome {
+            AuditOutcome::Success => (OUTCOME_SUCCESS.to_string(), None),
+            AuditOutcome::Failure(reason) => (OUTCOME_FAILURE.to_string(), Some(reason)),
+        };
+        FileAuditEntry {
+            timestamp: entry
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            actor: entry.actor,
+            action: entry.action,
+            target: entry.target,
+            outcome,
+            reason,
+        }
+    }
+}
+
+impl From<FileAuditEntry> for AuditEntry {
+    fn from(entry: FileAuditEntry) -> Self {
+        let outcome = if entry.outcome == OUTCOME_SUCCESS {
+            AuditOutcome::Success
+        } else {
+            AuditOutcome::Failure(entry.reason.unwrap_or_default())
+        };
+        AuditEntry {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(entry.timestamp),
+            actor: entry.actor,
+            action: entry.action,
+            target: entry.target,
+            outcome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempdir::TempDir;
+
+    /// Verify that entries are appended to the file and read back in order, including across
+    /// instances of the store, and that a missing file has no entries.
+    #[test]
+    fn file_store_round_trip() {
+        let temp_dir = TempDir::new("file_store_round_trip").expect("failed to create temp dir");
+        let path = temp_dir.path().join("audit.jsonl");
+
+        let store = FileAuditStore::new(&path);
+        assert_eq!(store.count_entries().expect("failed to count entries"), 0);
+
+        let mut first = AuditEntry::new("user:abc", "biome.login", "alice", AuditOutcome::Success);
+        first.timestamp = UNIX_EPOCH + Duration::from_millis(1_588_000_000_123);
+        let second = AuditEntry::new(
+            "0123",
+            "circuit.propose",
+            "alpha-beta",
+            AuditOutcome::Failure("invalid circuit".into()),
+        );
+        store.add_entry(first.clone()).expect("failed to add entry");
+        store
+            .add_entry(second.clone())
+            .expect("failed to add entry");
+
+        let store = FileAuditStore::new(&path);
+        assert_eq!(store.count_entries().expect("failed to count entries"), 2);
+        let entries = store.list_entries(0, 10).expect("failed to list entries");
+        assert_eq!(entries[0], first);
+        assert_eq!(entries[1].outcome, second.outcome);
+        assert_eq!(
+            store.list_entries(1, 10).expect("failed to list entries")[0].action,
+            "circuit.propose"
+        );
+        assert!(store
+            .list_entries(0, 0)
+            .expect("failed to list entries")
+            .is_empty());
+    }
+
+    /// Verify that lines that are not valid entries are skipped when counting and listing
+    /// entries.
+    #[test]
+    fn file_store_invalid_lines() {
+        let temp_dir = TempDir::new("file_store_invalid_lines").expect("failed to create temp dir");
+        let path = temp_dir.path().join("audit.jsonl");
+
+        let store = FileAuditStore::new(&path);
+        store
+            .add_entry(AuditEntry::new(
+                "user:abc",
+                "biome.login",
+                "alice",
+                AuditOutcome::Success,
+            ))
+            .expect("failed to add entry");
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(b"{\"timestamp\":\n"))
+            .expect("failed to write invalid line");
+        store
+            .add_entry(AuditEntry::new(
+                "user:abc",
+                "biome.logout",
+                "alice",
+                AuditOutcome::Success,
+            ))
+            .expect("failed to add entry");
+
+        assert_eq!(store.count_entries().expect("failed to count entries"), 2);
+        let entries = store.list_entries(0, 10).expect("failed to list entries");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].action, "biome.logout");
+        assert_eq!(
+            store.list_entries(1, 1).expect("failed to list entries")[0].action,
+            "biome.logout"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Mutex};
+
+use crate::audit::{
+    store::{AuditStore, AuditStoreError},
+    AuditEntry,
+};
+
+/// Keeps audit entries in memory; the entries are lost when the store is dropped.
+#[derive(Default, Clone)]
+pub struct MemoryAuditStore {
+    inner: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+impl MemoryAuditStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AuditStore for MemoryAuditStore {
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError> {
+        self.inner
+            .lock()
+            .map_err(|_| AuditStoreError::StorageError {
+                context: "Cannot access audit store: mutex lock poisoned".to_string(),
+                source: None,
+            })?
+            .push(entry);
+        Ok(())
+    }
+
+    fn list_entries(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, AuditStoreError> {
+        Ok(self
+            .inner
+            .lock()
+            .map_err(|_| AuditStoreError::StorageError {
+                context: "Cannot access audit store: mutex lock poisoned".to_string(),
+                source: None,
+            })?
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn count_entries(&self) -> Result<usize, AuditStoreError> {
+        Ok(self
+            .inner
+            .lock()
+            .map_err(|_| AuditStoreError::StorageError {
+                context: "Cannot access audit store: mutex lock poisoned".to_string(),
+                source: None,
+            })?
+            .len())
+    }
+}
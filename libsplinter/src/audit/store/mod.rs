@@ -0,0 +1,56 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines an API to append audit entries and to list them.
+
+#[cfg(feature = "diesel")]
+mod diesel;
+mod error;
+mod file;
+mod memory;
+
+#[cfg(feature = "diesel")]
+pub use self::diesel::DieselAuditStore;
+pub use error::AuditStoreError;
+pub use file::FileAuditStore;
+pub use memory::MemoryAuditStore;
+
+use super::AuditEntry;
+
+/// The names under which outcomes are stored
+const OUTCOME_SUCCESS: &str = "success";
+const OUTCOME_FAILURE: &str = "failure";
+
+/// Defines methods for appending entries to an audit log and listing them. Entries are listed in
+/// the order they were added.
+pub trait AuditStore: Send + Sync {
+    /// Appends an entry to the log
+    ///
+    /// # Arguments
+    ///
+    ///   * `entry` - The entry to be added
+    fn add_entry(&self, entry: AuditEntry) -> Result<(), AuditStoreError>;
+
+    /// Lists the entries in the log, oldest first
+    ///
+    /// # Arguments
+    ///
+    ///   * `offset` - The number of entries to skip
+    ///   * `limit` - The maximum number of entries to return
+    fn list_entries(&self, offset: usize, limit: usize)
+        -> Result<Vec<AuditEntry>, AuditStoreError>;
+
+    /// Returns the number of entries in the log
+    fn count_entries(&self) -> Result<usize, AuditStoreError>;
+}
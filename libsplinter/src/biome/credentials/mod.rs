@@ -15,4 +15,13 @@
 //! Defines a basic API to register and authenticate a User using a username and a password.
 //! Not recommended for use in production.
 
+#[cfg(feature = "biome-account-security")]
+mod policy;
 pub mod store;
+
+#[cfg(feature = "biome-account-security")]
+pub use policy::{PasswordPolicy, PasswordPolicyError};
+
+/// Permission to create password reset tokens for other users
+#[cfg(feature = "biome-account-security")]
+pub const BIOME_PASSWORD_RESET_PERMISSION: &str = "biome.user.password_reset";
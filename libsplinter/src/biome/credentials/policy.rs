@@ -0,0 +1,186 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+const DEFAULT_MIN_LENGTH: usize = 8;
+
+/// The requirements a password must meet when a user registers or changes their password.
+///
+/// The policy is checked against the password submitted to biome. Clients that hash passwords
+/// before submitting them should check the policy against the plain password themselves, since
+/// biome only sees the hash.
+///
+/// By default, passwords must be at least 8 characters long.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct PasswordPolicy {
+    #[serde(default = "default_min_length")]
+    min_length: usize,
+    #[serde(default)]
+    max_length: Option<usize>,
+    #[serde(default)]
+    require_uppercase: bool,
+    #[serde(default)]
+    require_lowercase: bool,
+    #[serde(default)]
+    require_digit: bool,
+    #[serde(default)]
+    require_symbol: bool,
+}
+
+fn default_min_length() -> usize {
+    DEFAULT_MIN_LENGTH
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: DEFAULT_MIN_LENGTH,
+            max_length: None,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Sets the minimum number of characters in a password
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Sets the maximum number of characters in a password
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Requires passwords to contain an uppercase letter
+    pub fn with_uppercase_required(mut self, required: bool) -> Self {
+        self.require_uppercase = required;
+        self
+    }
+
+    /// Requires passwords to contain a lowercase letter
+    pub fn with_lowercase_required(mut self, required: bool) -> Self {
+        self.require_lowercase = required;
+        self
+    }
+
+    /// Requires passwords to contain a digit
+    pub fn with_digit_required(mut self, required: bool) -> Self {
+        self.require_digit = required;
+        self
+    }
+
+    /// Requires passwords to contain a character that is neither a letter, a digit nor
+    /// whitespace
+    pub fn with_symbol_required(mut self, required: bool) -> Self {
+        self.require_symbol = required;
+        self
+    }
+
+    /// Checks that the password meets the policy, returning every requirement it does not meet
+    pub fn validate(&self, password: &str) -> Result<(), PasswordPolicyError> {
+        let length = password.chars().count();
+        let mut violations = vec![];
+
+        if length < self.min_length {
+            violations.push(format!(
+                "must be at least {} characters long",
+                self.min_length
+            ));
+        }
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                violations.push(format!("must be at most {} characters long", max_length));
+            }
+        }
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push("must contain an uppercase letter".into());
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push("must contain a lowercase letter".into());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push("must contain a digit".into());
+        }
+        if self.require_symbol
+            && !password
+                .chars()
+                .any(|c| !c.is_alphanumeric() && !c.is_whitespace())
+        {
+            violations.push("must contain a symbol".into());
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PasswordPolicyError::PolicyViolation(violations))
+        }
+    }
+}
+
+/// Returned when a password does not meet the password policy
+#[derive(Debug, PartialEq)]
+pub enum PasswordPolicyError {
+    /// Lists the requirements the password does not meet
+    PolicyViolation(Vec<String>),
+}
+
+impl Error for PasswordPolicyError {}
+
+impl fmt::Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PasswordPolicyError::PolicyViolation(violations) => {
+                write!(f, "password {}", violations.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that the default policy only requires a minimum length, and that a stricter
+    /// policy reports every requirement a password does not meet.
+    #[test]
+    fn test_password_policy() {
+        let policy = PasswordPolicy::default();
+        assert!(policy.validate("password").is_ok());
+        assert!(policy.validate("short").is_err());
+
+        let policy = PasswordPolicy::default()
+            .with_min_length(10)
+            .with_uppercase_required(true)
+            .with_digit_required(true)
+            .with_symbol_required(true);
+        assert!(policy.validate("Correct-Horse-42").is_ok());
+        assert_eq!(
+            policy.validate("horse"),
+            Err(PasswordPolicyError::PolicyViolation(vec![
+                "must be at least 10 characters long".into(),
+                "must contain an uppercase letter".into(),
+                "must contain a digit".into(),
+                "must contain a symbol".into(),
+            ]))
+        );
+    }
+}
@@ -16,15 +16,36 @@ pub(in crate::biome) mod models;
 mod operations;
 pub(in crate::biome) mod schema;
 
+#[cfg(feature = "biome-account-security")]
+use std::convert::TryFrom;
+#[cfg(feature = "biome-account-security")]
+use std::time::{Duration, SystemTime};
+
 use super::{Credentials, CredentialsStore, CredentialsStoreError, UsernameId};
+#[cfg(feature = "biome-account-security")]
+use super::{LoginAttempts, PasswordResetToken};
 use crate::database::ConnectionPool;
 use models::CredentialsModel;
+#[cfg(feature = "biome-account-security")]
+use models::{LoginAttemptsModel, PasswordResetTokenModel};
 use operations::add_credentials::CredentialsStoreAddCredentialsOperation as _;
+#[cfg(feature = "biome-account-security")]
+use operations::add_password_reset_token::CredentialsStoreAddPasswordResetTokenOperation as _;
 use operations::fetch_credential_by_id::CredentialsStoreFetchCredentialByIdOperation as _;
 use operations::fetch_credential_by_username::CredentialsStoreFetchCredentialByUsernameOperation as _;
+#[cfg(feature = "biome-account-security")]
+use operations::fetch_login_attempts::CredentialsStoreFetchLoginAttemptsOperation as _;
+#[cfg(feature = "biome-account-security")]
+use operations::fetch_password_reset_token::CredentialsStoreFetchPasswordResetTokenOperation as _;
 use operations::fetch_username::CredentialsStoreFetchUsernameOperation as _;
 use operations::list_usernames::CredentialsStoreListUsernamesOperation as _;
+#[cfg(feature = "biome-account-security")]
+use operations::record_failed_login::CredentialsStoreRecordFailedLoginOperation as _;
 use operations::remove_credentials::CredentialsStoreRemoveCredentialsOperation as _;
+#[cfg(feature = "biome-account-security")]
+use operations::reset_failed_logins::CredentialsStoreResetFailedLoginsOperation as _;
+#[cfg(feature = "biome-account-security")]
+use operations::reset_password::CredentialsStoreResetPasswordOperation as _;
 use operations::update_credentials::CredentialsStoreUpdateCredentialsOperation as _;
 use operations::CredentialsStoreOperations;
 
@@ -46,7 +67,9 @@ impl DieselCredentialsStore {
 
 impl CredentialsStore for DieselCredentialsStore {
     fn add_credentials(&self, credentials: Credentials) -> Result<(), CredentialsStoreError> {
-        CredentialsStoreOperations::new(&*self.connection_pool.get()?).add_credentials(credentials)
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).add_credentials(credentials)
+        })
     }
 
     fn update_credentials(
@@ -55,36 +78,106 @@ impl CredentialsStore for DieselCredentialsStore {
         username: &str,
         password: &str,
     ) -> Result<(), CredentialsStoreError> {
-        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
-            .update_credentials(user_id, username, password)
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).update_credentials(user_id, username, password)
+        })
     }
 
     fn remove_credentials(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
-        CredentialsStoreOperations::new(&*self.connection_pool.get()?).remove_credentials(user_id)
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).remove_credentials(user_id)
+        })
     }
 
     fn fetch_credential_by_user_id(
         &self,
         user_id: &str,
     ) -> Result<Credentials, CredentialsStoreError> {
-        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
-            .fetch_credential_by_id(user_id)
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).fetch_credential_by_id(user_id)
+        })
     }
 
     fn fetch_credential_by_username(
         &self,
         username: &str,
     ) -> Result<Credentials, CredentialsStoreError> {
-        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
-            .fetch_credential_by_username(username)
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).fetch_credential_by_username(username)
+        })
     }
 
     fn fetch_username_by_id(&self, user_id: &str) -> Result<UsernameId, CredentialsStoreError> {
-        CredentialsStoreOperations::new(&*self.connection_pool.get()?).fetch_username_by_id(user_id)
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).fetch_username_by_id(user_id)
+        })
     }
 
     fn list_usernames(&self) -> Result<Vec<UsernameId>, CredentialsStoreError> {
-        CredentialsStoreOperations::new(&*self.connection_pool.get()?).list_usernames()
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).list_usernames()
+        })
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).fetch_login_attempts(user_id)
+        })
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn record_failed_login(
+        &self,
+        user_id: &str,
+        max_attempts: u32,
+        lockout_duration: Duration,
+    ) -> Result<LoginAttempts, CredentialsStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).record_failed_login(
+                user_id,
+                max_attempts,
+                lockout_duration,
+            )
+        })
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn reset_failed_logins(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).reset_failed_logins(user_id)
+        })
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).add_password_reset_token(token)
+        })
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn fetch_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).fetch_password_reset_token(token_hash)
+        })
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn reset_password(
+        &self,
+        token_hash: &str,
+        hashed_password: &str,
+    ) -> Result<(), CredentialsStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            CredentialsStoreOperations::new(conn).reset_password(token_hash, hashed_password)
+        })
     }
 }
 
@@ -106,3 +199,35 @@ impl From<CredentialsModel> for Credentials {
         }
     }
 }
+
+#[cfg(feature = "biome-account-security")]
+impl From<LoginAttemptsModel> for LoginAttempts {
+    fn from(attempts: LoginAttemptsModel) -> Self {
+        Self {
+            failed_attempts: u32::try_from(attempts.failed_attempts).unwrap_or(0),
+            locked_until: attempts.locked_until.map(SystemTime::from),
+        }
+    }
+}
+
+#[cfg(feature = "biome-account-security")]
+impl From<PasswordResetTokenModel> for PasswordResetToken {
+    fn from(token: PasswordResetTokenModel) -> Self {
+        Self {
+            token_hash: token.token_hash,
+            user_id: token.user_id,
+            expires: token.expires.into(),
+        }
+    }
+}
+
+#[cfg(feature = "biome-account-security")]
+impl From<PasswordResetToken> for PasswordResetTokenModel {
+    fn from(token: PasswordResetToken) -> Self {
+        Self {
+            token_hash: token.token_hash,
+            user_id: token.user_id,
+            expires: token.expires.into(),
+        }
+    }
+}
@@ -13,7 +13,11 @@
 // limitations under the License.
 
 use super::schema::user_credentials;
+#[cfg(feature = "biome-account-security")]
+use super::schema::{user_login_attempts, user_password_reset_tokens};
 use crate::biome::user::store::diesel::models::UserModel;
+#[cfg(feature = "biome-account-security")]
+use crate::database::DbTimestamp;
 
 #[derive(Queryable, Identifiable, Associations, PartialEq, Debug)]
 #[table_name = "user_credentials"]
@@ -32,3 +36,21 @@ pub struct NewCredentialsModel {
     pub username: String,
     pub password: String,
 }
+
+#[cfg(feature = "biome-account-security")]
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "user_login_attempts"]
+pub struct LoginAttemptsModel {
+    pub user_id: String,
+    pub failed_attempts: i32,
+    pub locked_until: Option<DbTimestamp>,
+}
+
+#[cfg(feature = "biome-account-security")]
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "user_password_reset_tokens"]
+pub struct PasswordResetTokenModel {
+    pub token_hash: String,
+    pub user_id: String,
+    pub expires: DbTimestamp,
+}
@@ -0,0 +1,63 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::models::PasswordResetTokenModel;
+use crate::biome::credentials::store::diesel::schema::user_password_reset_tokens;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use crate::biome::credentials::store::PasswordResetToken;
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::credentials) trait CredentialsStoreAddPasswordResetTokenOperation {
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError>;
+}
+
+impl_for_diesel_backends! {
+    CredentialsStoreAddPasswordResetTokenOperation for CredentialsStoreOperations {
+        fn add_password_reset_token(
+            &self,
+            token: PasswordResetToken,
+        ) -> Result<(), CredentialsStoreError> {
+            let user_id = token.user_id.clone();
+            insert_into(user_password_reset_tokens::table)
+                .values(PasswordResetTokenModel::from(token))
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                        CredentialsStoreError::NotFoundError(format!(
+                            "User with user id {} not found",
+                            user_id
+                        ))
+                    }
+                    QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        CredentialsStoreError::DuplicateError(
+                            "Password reset token already exists".to_string(),
+                        )
+                    }
+                    _ => CredentialsStoreError::OperationError {
+                        context: "Failed to add password reset token".to_string(),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::models::LoginAttemptsModel;
+use crate::biome::credentials::store::diesel::schema::user_login_attempts;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use crate::biome::credentials::store::LoginAttempts;
+use diesel::prelude::*;
+
+pub(in crate::biome::credentials) trait CredentialsStoreFetchLoginAttemptsOperation {
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError>;
+}
+
+impl_for_diesel_backends! {
+    CredentialsStoreFetchLoginAttemptsOperation for CredentialsStoreOperations {
+        fn fetch_login_attempts(
+            &self,
+            user_id: &str,
+        ) -> Result<LoginAttempts, CredentialsStoreError> {
+            user_login_attempts::table
+                .find(user_id)
+                .first::<LoginAttemptsModel>(self.conn)
+                .optional()
+                .map(|attempts| attempts.map(LoginAttempts::from).unwrap_or_default())
+                .map_err(|err| CredentialsStoreError::QueryError {
+                    context: "Failed to fetch login attempts".to_string(),
+                    source: Box::new(err),
+                })
+        }
+    }
+}
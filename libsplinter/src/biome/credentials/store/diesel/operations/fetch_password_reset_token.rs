@@ -0,0 +1,53 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::models::PasswordResetTokenModel;
+use crate::biome::credentials::store::diesel::schema::user_password_reset_tokens;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use crate::biome::credentials::store::PasswordResetToken;
+use crate::database::DbTimestamp;
+use diesel::prelude::*;
+
+pub(in crate::biome::credentials) trait CredentialsStoreFetchPasswordResetTokenOperation {
+    fn fetch_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError>;
+}
+
+impl_for_diesel_backends! {
+    CredentialsStoreFetchPasswordResetTokenOperation for CredentialsStoreOperations {
+        fn fetch_password_reset_token(
+            &self,
+            token_hash: &str,
+        ) -> Result<PasswordResetToken, CredentialsStoreError> {
+            user_password_reset_tokens::table
+                .find(token_hash)
+                .filter(user_password_reset_tokens::expires.gt(DbTimestamp::now()))
+                .first::<PasswordResetTokenModel>(self.conn)
+                .optional()
+                .map_err(|err| CredentialsStoreError::QueryError {
+                    context: "Failed to fetch password reset token".to_string(),
+                    source: Box::new(err),
+                })?
+                .map(PasswordResetToken::from)
+                .ok_or_else(|| {
+                    CredentialsStoreError::NotFoundError(
+                        "Password reset token not found or expired".to_string(),
+                    )
+                })
+        }
+    }
+}
@@ -15,11 +15,23 @@
 //! Provides CredentialsStoreOperations implemented for a diesel backend
 
 pub(super) mod add_credentials;
+#[cfg(feature = "biome-account-security")]
+pub(super) mod add_password_reset_token;
 pub(super) mod fetch_credential_by_id;
 pub(super) mod fetch_credential_by_username;
+#[cfg(feature = "biome-account-security")]
+pub(super) mod fetch_login_attempts;
+#[cfg(feature = "biome-account-security")]
+pub(super) mod fetch_password_reset_token;
 pub(super) mod fetch_username;
 pub(super) mod list_usernames;
+#[cfg(feature = "biome-account-security")]
+pub(super) mod record_failed_login;
 pub(super) mod remove_credentials;
+#[cfg(feature = "biome-account-security")]
+pub(super) mod reset_failed_logins;
+#[cfg(feature = "biome-account-security")]
+pub(super) mod reset_password;
 pub(super) mod update_credentials;
 
 pub(super) struct CredentialsStoreOperations<'a, C> {
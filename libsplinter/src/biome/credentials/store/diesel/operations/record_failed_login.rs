@@ -0,0 +1,141 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::models::LoginAttemptsModel;
+use crate::biome::credentials::store::diesel::schema::user_login_attempts;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use crate::biome::credentials::store::LoginAttempts;
+use crate::database::DbTimestamp;
+#[cfg(feature = "postgres")]
+use diesel::dsl::insert_into;
+#[cfg(feature = "sqlite")]
+use diesel::insert_or_ignore_into;
+use diesel::{
+    dsl::update,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::credentials) trait CredentialsStoreRecordFailedLoginOperation {
+    fn record_failed_login(
+        &self,
+        user_id: &str,
+        max_attempts: u32,
+        lockout_duration: Duration,
+    ) -> Result<LoginAttempts, CredentialsStoreError>;
+}
+
+/// Fetches the login attempts of a user within a transaction, creating the row if it does not
+/// exist, and keeps concurrent transactions from changing them until the transaction ends
+pub(super) trait FetchLoginAttemptsForUpdate {
+    fn fetch_login_attempts_for_update(&self, user_id: &str) -> Result<LoginAttempts, QueryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> FetchLoginAttemptsForUpdate for CredentialsStoreOperations<'a, diesel::pg::PgConnection> {
+    fn fetch_login_attempts_for_update(&self, user_id: &str) -> Result<LoginAttempts, QueryError> {
+        // Make sure the row exists so it can be locked for the rest of the transaction
+        insert_into(user_login_attempts::table)
+            .values(&LoginAttemptsModel {
+                user_id: user_id.to_string(),
+                failed_attempts: 0,
+                locked_until: None,
+            })
+            .on_conflict_do_nothing()
+            .execute(self.conn)?;
+
+        user_login_attempts::table
+            .find(user_id)
+            .for_update()
+            .first::<LoginAttemptsModel>(self.conn)
+            .map(LoginAttempts::from)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> FetchLoginAttemptsForUpdate
+    for CredentialsStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn fetch_login_attempts_for_update(&self, user_id: &str) -> Result<LoginAttempts, QueryError> {
+        // Make sure the row exists; SQLite allows a single writer at a time, so the insert also
+        // holds the database write lock for the rest of the transaction.
+        insert_or_ignore_into(user_login_attempts::table)
+            .values(&LoginAttemptsModel {
+                user_id: user_id.to_string(),
+                failed_attempts: 0,
+                locked_until: None,
+            })
+            .execute(self.conn)?;
+
+        user_login_attempts::table
+            .find(user_id)
+            .first::<LoginAttemptsModel>(self.conn)
+            .map(LoginAttempts::from)
+    }
+}
+
+impl_for_diesel_backends! {
+    CredentialsStoreRecordFailedLoginOperation for CredentialsStoreOperations {
+        fn record_failed_login(
+            &self,
+            user_id: &str,
+            max_attempts: u32,
+            lockout_duration: Duration,
+        ) -> Result<LoginAttempts, CredentialsStoreError> {
+            self.conn
+                .transaction::<_, QueryError, _>(|| {
+                    // Locking the row keeps concurrent failures from being lost
+                    let mut attempts = self.fetch_login_attempts_for_update(user_id)?;
+
+                    if attempts.is_locked() {
+                        return Ok(attempts);
+                    }
+
+                    attempts.failed_attempts += 1;
+                    attempts.locked_until = None;
+                    if attempts.failed_attempts >= max_attempts {
+                        attempts.failed_attempts = 0;
+                        attempts.locked_until = Some(SystemTime::now() + lockout_duration);
+                    }
+
+                    update(user_login_attempts::table.find(user_id))
+                        .set((
+                            user_login_attempts::failed_attempts
+                                .eq(i32::try_from(attempts.failed_attempts).unwrap_or(i32::MAX)),
+                            user_login_attempts::locked_until
+                                .eq(attempts.locked_until.map(DbTimestamp::from)),
+                        ))
+                        .execute(self.conn)?;
+
+                    Ok(attempts)
+                })
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                        CredentialsStoreError::NotFoundError(format!(
+                            "User with user id {} not found",
+                            user_id
+                        ))
+                    }
+                    _ => CredentialsStoreError::OperationError {
+                        context: "Failed to record failed login".to_string(),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::record_failed_login::FetchLoginAttemptsForUpdate;
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::schema::user_login_attempts;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use crate::biome::credentials::store::LoginAttempts;
+use diesel::{dsl::delete, prelude::*, result::Error as QueryError};
+
+pub(in crate::biome::credentials) trait CredentialsStoreResetFailedLoginsOperation {
+    fn reset_failed_logins(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError>;
+}
+
+impl_for_diesel_backends! {
+    CredentialsStoreResetFailedLoginsOperation for CredentialsStoreOperations {
+        fn reset_failed_logins(
+            &self,
+            user_id: &str,
+        ) -> Result<LoginAttempts, CredentialsStoreError> {
+            self.conn
+                .transaction::<_, QueryError, _>(|| {
+                    // Locking the row keeps a concurrent failure from locking the user out
+                    // between the check and the delete
+                    let attempts = self.fetch_login_attempts_for_update(user_id)?;
+
+                    if attempts.is_locked() {
+                        return Ok(attempts);
+                    }
+
+                    delete(user_login_attempts::table.find(user_id)).execute(self.conn)?;
+                    Ok(LoginAttempts::default())
+                })
+                .map_err(|err| CredentialsStoreError::OperationError {
+                    context: "Failed to reset failed logins".to_string(),
+                    source: Box::new(err),
+                })
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::models::PasswordResetTokenModel;
+use crate::biome::credentials::store::diesel::schema::{
+    user_credentials, user_login_attempts, user_password_reset_tokens,
+};
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use crate::database::DbTimestamp;
+use diesel::{
+    dsl::{delete, update},
+    prelude::*,
+    result::Error as QueryError,
+};
+
+pub(in crate::biome::credentials) trait CredentialsStoreResetPasswordOperation {
+    fn reset_password(
+        &self,
+        token_hash: &str,
+        hashed_password: &str,
+    ) -> Result<(), CredentialsStoreError>;
+}
+
+/// Fetches an unexpired password reset token within a transaction, so that only one
+/// transaction can consume it
+trait FetchResetTokenForUpdate {
+    fn fetch_reset_token_for_update(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetTokenModel, QueryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> FetchResetTokenForUpdate for CredentialsStoreOperations<'a, diesel::pg::PgConnection> {
+    fn fetch_reset_token_for_update(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetTokenModel, QueryError> {
+        // Lock the token so that it can only be consumed once
+        user_password_reset_tokens::table
+            .find(token_hash)
+            .filter(user_password_reset_tokens::expires.gt(DbTimestamp::now()))
+            .for_update()
+            .first::<PasswordResetTokenModel>(self.conn)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> FetchResetTokenForUpdate
+    for CredentialsStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn fetch_reset_token_for_update(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetTokenModel, QueryError> {
+        // SQLite allows a single writer at a time, so only one transaction can consume the token
+        user_password_reset_tokens::table
+            .find(token_hash)
+            .filter(user_password_reset_tokens::expires.gt(DbTimestamp::now()))
+            .first::<PasswordResetTokenModel>(self.conn)
+    }
+}
+
+impl_for_diesel_backends! {
+    CredentialsStoreResetPasswordOperation for CredentialsStoreOperations {
+        fn reset_password(
+            &self,
+            token_hash: &str,
+            hashed_password: &str,
+        ) -> Result<(), CredentialsStoreError> {
+            self.conn
+                .transaction::<_, QueryError, _>(|| {
+                    let token = self.fetch_reset_token_for_update(token_hash)?;
+
+                    let updated = update(
+                        user_credentials::table
+                            .filter(user_credentials::user_id.eq(&token.user_id)),
+                    )
+                    .set(user_credentials::password.eq(hashed_password))
+                    .execute(self.conn)?;
+                    if updated == 0 {
+                        return Err(QueryError::NotFound);
+                    }
+
+                    delete(
+                        user_password_reset_tokens::table
+                            .filter(user_password_reset_tokens::user_id.eq(&token.user_id)),
+                    )
+                    .execute(self.conn)?;
+                    delete(user_login_attempts::table.find(&token.user_id)).execute(self.conn)?;
+
+                    Ok(())
+                })
+                .map_err(|err| match err {
+                    QueryError::NotFound => CredentialsStoreError::NotFoundError(
+                        "Password reset token not found or expired".to_string(),
+                    ),
+                    _ => CredentialsStoreError::OperationError {
+                        context: "Failed to reset password".to_string(),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
@@ -20,3 +20,21 @@ table! {
         password -> Text,
     }
 }
+
+#[cfg(feature = "biome-account-security")]
+table! {
+    user_login_attempts (user_id) {
+        user_id -> Text,
+        failed_attempts -> Int4,
+        locked_until -> Nullable<Timestamp>,
+    }
+}
+
+#[cfg(feature = "biome-account-security")]
+table! {
+    user_password_reset_tokens (token_hash) {
+        token_hash -> Text,
+        user_id -> Text,
+        expires -> Timestamp,
+    }
+}
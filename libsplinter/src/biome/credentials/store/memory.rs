@@ -14,20 +14,36 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+#[cfg(feature = "biome-account-security")]
+use std::time::{Duration, SystemTime};
 
 use crate::biome::credentials::store::{
     error::CredentialsStoreError, Credentials, CredentialsStore, UsernameId,
 };
+#[cfg(feature = "biome-account-security")]
+use crate::biome::credentials::store::{LoginAttempts, PasswordResetToken};
 
 #[derive(Default, Clone)]
 pub struct MemoryCredentialsStore {
     inner: Arc<Mutex<HashMap<String, Credentials>>>,
+    #[cfg(feature = "biome-account-security")]
+    security: Arc<Mutex<AccountSecurity>>,
+}
+
+/// Login attempts keyed by user ID and password reset tokens keyed by token hash
+#[cfg(feature = "biome-account-security")]
+#[derive(Default)]
+struct AccountSecurity {
+    login_attempts: HashMap<String, LoginAttempts>,
+    reset_tokens: HashMap<String, PasswordResetToken>,
 }
 
 impl MemoryCredentialsStore {
     pub fn new() -> Self {
         MemoryCredentialsStore {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-account-security")]
+            security: Arc::new(Mutex::new(AccountSecurity::default())),
         }
     }
 }
@@ -169,4 +185,260 @@ impl CredentialsStore for MemoryCredentialsStore {
             })
             .collect())
     }
+
+    #[cfg(feature = "biome-account-security")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        let security = self
+            .security
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access login attempts: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        Ok(security
+            .login_attempts
+            .get(user_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn record_failed_login(
+        &self,
+        user_id: &str,
+        max_attempts: u32,
+        lockout_duration: Duration,
+    ) -> Result<LoginAttempts, CredentialsStoreError> {
+        let mut security =
+            self.security
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access login attempts: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        let attempts = security
+            .login_attempts
+            .entry(user_id.to_string())
+            .or_default();
+        if !attempts.is_locked() {
+            attempts.failed_attempts += 1;
+            attempts.locked_until = None;
+            if attempts.failed_attempts >= max_attempts {
+                attempts.failed_attempts = 0;
+                attempts.locked_until = Some(SystemTime::now() + lockout_duration);
+            }
+        }
+        Ok(attempts.clone())
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn reset_failed_logins(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        let mut security =
+            self.security
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access login attempts: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        match security.login_attempts.get(user_id) {
+            Some(attempts) if attempts.is_locked() => Ok(attempts.clone()),
+            _ => {
+                security.login_attempts.remove(user_id);
+                Ok(LoginAttempts::default())
+            }
+        }
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        if !self
+            .inner
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access credentials: mutex lock poisoned".to_string(),
+                source: None,
+            })?
+            .contains_key(&token.user_id)
+        {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "User with user id {} not found",
+                token.user_id
+            )));
+        }
+        let mut security =
+            self.security
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access password reset tokens: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        security
+            .reset_tokens
+            .insert(token.token_hash.clone(), token);
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn fetch_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError> {
+        let security = self
+            .security
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access password reset tokens: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        security
+            .reset_tokens
+            .get(token_hash)
+            .filter(|token| token.expires > SystemTime::now())
+            .cloned()
+            .ok_or_else(|| {
+                CredentialsStoreError::NotFoundError(
+                    "Password reset token not found or expired".to_string(),
+                )
+            })
+    }
+
+    #[cfg(feature = "biome-account-security")]
+    fn reset_password(
+        &self,
+        token_hash: &str,
+        hashed_password: &str,
+    ) -> Result<(), CredentialsStoreError> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access credentials: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        let mut security =
+            self.security
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access password reset tokens: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        let user_id = security
+            .reset_tokens
+            .get(token_hash)
+            .filter(|token| token.expires > SystemTime::now())
+            .map(|token| token.user_id.clone())
+            .ok_or_else(|| {
+                CredentialsStoreError::NotFoundError(
+                    "Password reset token not found or expired".to_string(),
+                )
+            })?;
+        let credentials = inner.get_mut(&user_id).ok_or_else(|| {
+            CredentialsStoreError::NotFoundError(format!("User with user id {} not found", user_id))
+        })?;
+        credentials.password = hashed_password.to_string();
+        security
+            .reset_tokens
+            .retain(|_, token| token.user_id != user_id);
+        security.login_attempts.remove(&user_id);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "biome-account-security"))]
+mod tests {
+    use super::*;
+
+    /// Verifies that a user is locked out after the maximum number of failed logins, that
+    /// failures are not counted while locked out, that resetting leaves an active lockout in
+    /// place, and that resetting clears the failures otherwise.
+    #[test]
+    fn test_login_lockout() {
+        let store = MemoryCredentialsStore::new();
+
+        for expected in 1..3 {
+            let attempts = store
+                .record_failed_login("user", 3, Duration::from_secs(60))
+                .expect("Failed to record failed login");
+            assert_eq!(attempts.failed_attempts, expected);
+            assert!(!attempts.is_locked());
+        }
+
+        let attempts = store
+            .record_failed_login("user", 3, Duration::from_secs(60))
+            .expect("Failed to record failed login");
+        assert!(attempts.is_locked());
+        assert_eq!(attempts.failed_attempts, 0);
+
+        let attempts = store
+            .record_failed_login("user", 3, Duration::from_secs(60))
+            .expect("Failed to record failed login");
+        assert!(attempts.is_locked());
+        assert_eq!(attempts.failed_attempts, 0);
+
+        let attempts = store
+            .reset_failed_logins("user")
+            .expect("Failed to reset failed logins");
+        assert!(attempts.is_locked());
+        assert!(store
+            .fetch_login_attempts("user")
+            .expect("Failed to fetch login attempts")
+            .is_locked());
+
+        store
+            .record_failed_login("other", 3, Duration::from_secs(60))
+            .expect("Failed to record failed login");
+        let attempts = store
+            .reset_failed_logins("other")
+            .expect("Failed to reset failed logins");
+        assert_eq!(attempts, LoginAttempts::default());
+        assert_eq!(
+            store
+                .fetch_login_attempts("other")
+                .expect("Failed to fetch login attempts"),
+            LoginAttempts::default()
+        );
+    }
+
+    /// Verifies that a password reset token replaces the user's password and can only be used
+    /// once.
+    #[test]
+    fn test_reset_password() {
+        let store = MemoryCredentialsStore::new();
+        store
+            .add_credentials(Credentials {
+                user_id: "user".into(),
+                username: "username".into(),
+                password: "old".into(),
+            })
+            .expect("Failed to add credentials");
+        store
+            .add_password_reset_token(PasswordResetToken {
+                token_hash: "hash".into(),
+                user_id: "user".into(),
+                expires: SystemTime::now() + Duration::from_secs(60),
+            })
+            .expect("Failed to add token");
+
+        assert_eq!(
+            store
+                .fetch_password_reset_token("hash")
+                .expect("Failed to fetch token")
+                .user_id,
+            "user"
+        );
+        store
+            .reset_password("hash", "new")
+            .expect("Failed to reset password");
+        assert_eq!(
+            store
+                .fetch_credential_by_user_id("user")
+                .expect("Failed to fetch credentials")
+                .password,
+            "new"
+        );
+        assert!(store.reset_password("hash", "newer").is_err());
+    }
 }
@@ -18,6 +18,8 @@
 pub(in crate::biome) mod diesel;
 pub(in crate::biome) mod memory;
 use std::str::FromStr;
+#[cfg(feature = "biome-account-security")]
+use std::time::SystemTime;
 mod error;
 
 pub use error::CredentialsStoreError;
@@ -58,6 +60,42 @@ pub struct UsernameId {
     pub user_id: String,
 }
 
+/// The failed login attempts recorded for a user
+#[cfg(feature = "biome-account-security")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoginAttempts {
+    /// The number of consecutive failed login attempts since the last successful login or
+    /// lockout
+    pub failed_attempts: u32,
+    /// The time until which the user is not allowed to log in, if the user has been locked out
+    pub locked_until: Option<SystemTime>,
+}
+
+#[cfg(feature = "biome-account-security")]
+impl LoginAttempts {
+    /// Returns true if the user is currently locked out
+    pub fn is_locked(&self) -> bool {
+        match self.locked_until {
+            Some(locked_until) => locked_until > SystemTime::now(),
+            None => false,
+        }
+    }
+}
+
+/// A one-time token that allows a user to set a new password without their current password
+///
+/// Only the SHA-256 hash of the token is stored; the token itself is handed to the user once.
+#[cfg(feature = "biome-account-security")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordResetToken {
+    /// The hex-encoded SHA-256 hash of the token
+    pub token_hash: String,
+    /// The unique identifier of the user whose password may be reset
+    pub user_id: String,
+    /// The time after which the token may no longer be used
+    pub expires: SystemTime,
+}
+
 /// Builder for Credential. It hashes the password upon build.
 #[derive(Default)]
 pub struct CredentialsBuilder {
@@ -235,6 +273,105 @@ pub trait CredentialsStore: Send + Sync {
     ///
     /// Returns a CredentialsStoreError if implementation cannot fetch the user IDs
     fn list_usernames(&self) -> Result<Vec<UsernameId>, CredentialsStoreError>;
+
+    /// Fetches the failed login attempts recorded for a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot fetch the login attempts
+    #[cfg(feature = "biome-account-security")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError>;
+
+    /// Records a failed login attempt for a user. Once the user has reached `max_attempts`
+    /// consecutive failures the user is locked out for `lockout_duration` and the count starts
+    /// over. Failures are not counted while the user is locked out.
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user
+    ///  * `max_attempts` - The number of consecutive failures that locks the user out
+    ///  * `lockout_duration` - How long the user is locked out for
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot record the attempt
+    #[cfg(feature = "biome-account-security")]
+    fn record_failed_login(
+        &self,
+        user_id: &str,
+        max_attempts: u32,
+        lockout_duration: std::time::Duration,
+    ) -> Result<LoginAttempts, CredentialsStoreError>;
+
+    /// Clears the failed login attempts for a user after a successful login, unless the user is
+    /// locked out. The check and the reset are atomic, so a lockout caused by a concurrent
+    /// failed attempt is never cleared. Returns the user's login attempts after the reset; if
+    /// the user is locked out, the login must be refused.
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot clear the login attempts
+    #[cfg(feature = "biome-account-security")]
+    fn reset_failed_logins(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError>;
+
+    /// Adds a password reset token to the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `token` - The password reset token to be added
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot add the token or if the user
+    /// does not have credentials
+    #[cfg(feature = "biome-account-security")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError>;
+
+    /// Fetches an unexpired password reset token by its hash
+    ///
+    /// # Arguments
+    ///
+    ///  * `token_hash` - The hex-encoded SHA-256 hash of the token
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot fetch the token or if the token
+    /// does not exist or has expired
+    #[cfg(feature = "biome-account-security")]
+    fn fetch_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError>;
+
+    /// Consumes a password reset token, replacing the password of the user the token belongs
+    /// to. All of the user's reset tokens are removed and any lockout is cleared.
+    ///
+    /// # Arguments
+    ///
+    ///  * `token_hash` - The hex-encoded SHA-256 hash of the token
+    ///  * `hashed_password` - The new password, already hashed
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot update the password or if the
+    /// token does not exist or has expired
+    #[cfg(feature = "biome-account-security")]
+    fn reset_password(
+        &self,
+        token_hash: &str,
+        hashed_password: &str,
+    ) -> Result<(), CredentialsStoreError>;
 }
 
 #[cfg(feature = "diesel")]
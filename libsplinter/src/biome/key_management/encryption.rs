@@ -0,0 +1,346 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Server-side envelope encryption of private keys.
+//!
+//! Each user's private keys are encrypted with a data key that belongs to that user. The data
+//! keys are in turn encrypted ("wrapped") with the node's master key before they are stored, so
+//! the key store never holds a usable key on its own.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use openssl::rand::rand_bytes;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+
+use crate::hex::{parse_hex, to_hex};
+
+use super::store::{KeyStore, KeyStoreError};
+use super::Key;
+
+/// The length in bytes of master keys and data keys
+const KEY_LENGTH: usize = 32;
+/// The length in bytes of the nonce used for each encryption
+const NONCE_LENGTH: usize = 12;
+/// The length in bytes of the authentication tag appended to each ciphertext
+const TAG_LENGTH: usize = 16;
+/// Identifies the format of the ciphertexts produced by this module
+const CIPHERTEXT_VERSION: &str = "v1";
+
+/// Encrypts and decrypts private keys on behalf of biome users
+pub struct KeyEncryptor {
+    master_key: Vec<u8>,
+}
+
+impl KeyEncryptor {
+    /// Creates a new KeyEncryptor
+    ///
+    /// # Arguments
+    ///
+    /// * `master_key`: the node's 256-bit master key
+    pub fn new(master_key: &[u8]) -> Result<Self, KeyEncryptionError> {
+        if master_key.len() != KEY_LENGTH {
+            return Err(KeyEncryptionError::MasterKeyError(format!(
+                "master key must be {} bytes, but was {} bytes",
+                KEY_LENGTH,
+                master_key.len()
+            )));
+        }
+
+        Ok(Self {
+            master_key: master_key.to_vec(),
+        })
+    }
+
+    /// Creates a new KeyEncryptor with the master key in the given file. The file must contain
+    /// the hex-encoded 256-bit key.
+    pub fn from_file(path: &str) -> Result<Self, KeyEncryptionError> {
+        let contents = fs::read_to_string(path).map_err(|err| {
+            KeyEncryptionError::MasterKeyError(format!(
+                "unable to read master key file {}: {}",
+                path, err
+            ))
+        })?;
+        let master_key = parse_hex(contents.trim()).map_err(|err| {
+            KeyEncryptionError::MasterKeyError(format!(
+                "master key file {} is not valid hex: {}",
+                path, err
+            ))
+        })?;
+
+        Self::new(&master_key)
+    }
+
+    /// Encrypts a user's private key with the user's data key, creating the data key if the user
+    /// does not have one yet. Returns the encrypted private key, which may be stored with
+    /// `Key::new_server_encrypted`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_store`: the KeyStore that holds the users' data keys
+    /// * `user_id`: the ID of the user who owns the key
+    /// * `public_key`: the public key of the key pair
+    /// * `private_key`: the private key to encrypt
+    pub fn encrypt_private_key(
+        &self,
+        key_store: &dyn KeyStore,
+        user_id: &str,
+        public_key: &str,
+        private_key: &str,
+    ) -> Result<String, KeyEncryptionError> {
+        let data_key = self.data_key(key_store, user_id)?;
+        seal(
+            &data_key,
+            &private_key_aad(user_id, public_key),
+            private_key.as_bytes(),
+        )
+    }
+
+    /// Decrypts the private key of a key that was encrypted by `encrypt_private_key`
+    ///
+    /// # Arguments
+    ///
+    /// * `key_store`: the KeyStore that holds the users' data keys
+    /// * `key`: the key whose private key is decrypted
+    pub fn decrypt_private_key(
+        &self,
+        key_store: &dyn KeyStore,
+        key: &Key,
+    ) -> Result<String, KeyEncryptionError> {
+        if !key.server_encrypted {
+            return Err(KeyEncryptionError::NotServerEncrypted(
+                key.public_key.to_string(),
+            ));
+        }
+
+        let wrapped_key = key_store.fetch_data_key(&key.user_id)?;
+        let data_key = open(
+            &self.master_key,
+            data_key_aad(&key.user_id).as_bytes(),
+            &wrapped_key,
+        )?;
+        let private_key = open(
+            &data_key,
+            &private_key_aad(&key.user_id, &key.public_key),
+            &key.encrypted_private_key,
+        )?;
+
+        String::from_utf8(private_key).map_err(|_| {
+            KeyEncryptionError::EncryptionError("decrypted private key is not valid UTF-8".into())
+        })
+    }
+
+    /// Returns the user's unwrapped data key, creating it if the user does not have one yet
+    fn data_key(
+        &self,
+        key_store: &dyn KeyStore,
+        user_id: &str,
+    ) -> Result<Vec<u8>, KeyEncryptionError> {
+        let aad = data_key_aad(user_id);
+        match key_store.fetch_data_key(user_id) {
+            Ok(wrapped_key) => return open(&self.master_key, aad.as_bytes(), &wrapped_key),
+            Err(KeyStoreError::NotFoundError(_)) => (),
+            Err(err) => return Err(err.into()),
+        }
+
+        let mut data_key = vec![0; KEY_LENGTH];
+        rand_bytes(&mut data_key).map_err(|err| {
+            KeyEncryptionError::EncryptionError(format!("unable to generate data key: {}", err))
+        })?;
+        let wrapped_key = seal(&self.master_key, aad.as_bytes(), &data_key)?;
+
+        match key_store.add_data_key(user_id, &wrapped_key) {
+            Ok(()) => Ok(data_key),
+            // Another request created the user's data key first, so use that one
+            Err(KeyStoreError::DuplicateKeyError(_)) => {
+                let wrapped_key = key_store.fetch_data_key(user_id)?;
+                open(&self.master_key, aad.as_bytes(), &wrapped_key)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Binds a wrapped data key to the user it belongs to
+fn data_key_aad(user_id: &str) -> String {
+    format!("biome-data-key:{}", user_id)
+}
+
+/// Binds an encrypted private key to the key pair and user it belongs to
+fn private_key_aad(user_id: &str, public_key: &str) -> Vec<u8> {
+    format!("biome-private-key:{}:{}", user_id, public_key).into_bytes()
+}
+
+/// Encrypts the plaintext with AES-256-GCM, returning the version, nonce, ciphertext and tag
+fn seal(key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<String, KeyEncryptionError> {
+    let mut nonce = [0; NONCE_LENGTH];
+    rand_bytes(&mut nonce).map_err(|err| {
+        KeyEncryptionError::EncryptionError(format!("unable to generate nonce: {}", err))
+    })?;
+
+    let mut tag = [0; TAG_LENGTH];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(&nonce),
+        aad,
+        plaintext,
+        &mut tag,
+    )
+    .map_err(|err| KeyEncryptionError::EncryptionError(format!("unable to encrypt: {}", err)))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    sealed.extend(&tag);
+    Ok(format!("{}:{}", CIPHERTEXT_VERSION, to_hex(&sealed)))
+}
+
+/// Decrypts a ciphertext produced by `seal`
+fn open(key: &[u8], aad: &[u8], sealed: &str) -> Result<Vec<u8>, KeyEncryptionError> {
+    let sealed = match sealed.split_at(sealed.find(':').unwrap_or(0)) {
+        (CIPHERTEXT_VERSION, sealed) => parse_hex(&sealed[1..]).map_err(|err| {
+            KeyEncryptionError::EncryptionError(format!("ciphertext is not valid hex: {}", err))
+        })?,
+        _ => {
+            return Err(KeyEncryptionError::EncryptionError(
+                "unsupported ciphertext format".into(),
+            ))
+        }
+    };
+
+    if sealed.len() < NONCE_LENGTH + TAG_LENGTH {
+        return Err(KeyEncryptionError::EncryptionError(
+            "ciphertext is too short".into(),
+        ));
+    }
+
+    let (nonce, rest) = sealed.split_at(NONCE_LENGTH);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LENGTH);
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(nonce),
+        aad,
+        ciphertext,
+        tag,
+    )
+    .map_err(|err| KeyEncryptionError::EncryptionError(format!("unable to decrypt: {}", err)))
+}
+
+/// Represents errors that occur while encrypting or decrypting private keys
+#[derive(Debug)]
+pub enum KeyEncryptionError {
+    /// Returned when the master key cannot be loaded or is invalid
+    MasterKeyError(String),
+    /// Returned when a key cannot be encrypted or decrypted
+    EncryptionError(String),
+    /// Returned when decrypting a key whose private key was encrypted by the client
+    NotServerEncrypted(String),
+    /// Returned when a data key cannot be stored or fetched
+    KeyStoreError(KeyStoreError),
+}
+
+impl Error for KeyEncryptionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KeyEncryptionError::MasterKeyError(_) => None,
+            KeyEncryptionError::EncryptionError(_) => None,
+            KeyEncryptionError::NotServerEncrypted(_) => None,
+            KeyEncryptionError::KeyStoreError(err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for KeyEncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyEncryptionError::MasterKeyError(msg) => write!(f, "invalid master key: {}", msg),
+            KeyEncryptionError::EncryptionError(msg) => write!(f, "{}", msg),
+            KeyEncryptionError::NotServerEncrypted(public_key) => write!(
+                f,
+                "private key of {} was not encrypted by the server",
+                public_key
+            ),
+            KeyEncryptionError::KeyStoreError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<KeyStoreError> for KeyEncryptionError {
+    fn from(err: KeyStoreError) -> Self {
+        KeyEncryptionError::KeyStoreError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "biome-credentials")]
+    use crate::biome::credentials::store::memory::MemoryCredentialsStore;
+    use crate::biome::key_management::store::memory::MemoryKeyStore;
+
+    /// Verify that a private key encrypted by the server can be decrypted again, that both keys
+    /// of a user share a data key, and that decryption fails with the wrong master key.
+    #[test]
+    fn test_encrypt_decrypt_private_key() {
+        #[cfg(feature = "biome-credentials")]
+        let store = MemoryKeyStore::new(MemoryCredentialsStore::new());
+        #[cfg(not(feature = "biome-credentials"))]
+        let store = MemoryKeyStore::new();
+
+        let encryptor = KeyEncryptor::new(&[1; KEY_LENGTH]).expect("Failed to create encryptor");
+
+        let encrypted = encryptor
+            .encrypt_private_key(&store, "user", "public", "private")
+            .expect("Failed to encrypt private key");
+        assert_ne!(encrypted, "private");
+        let wrapped_key = store
+            .fetch_data_key("user")
+            .expect("Data key was not stored");
+
+        let other_encrypted = encryptor
+            .encrypt_private_key(&store, "user", "other_public", "other_private")
+            .expect("Failed to encrypt private key");
+        assert_eq!(
+            wrapped_key,
+            store.fetch_data_key("user").expect("Data key was removed")
+        );
+
+        let key = Key::new_server_encrypted("public", &encrypted, "user", "key");
+        assert_eq!(
+            "private",
+            encryptor
+                .decrypt_private_key(&store, &key)
+                .expect("Failed to decrypt private key")
+        );
+        let other_key =
+            Key::new_server_encrypted("other_public", &other_encrypted, "user", "other key");
+        assert_eq!(
+            "other_private",
+            encryptor
+                .decrypt_private_key(&store, &other_key)
+                .expect("Failed to decrypt private key")
+        );
+
+        let wrong_encryptor =
+            KeyEncryptor::new(&[2; KEY_LENGTH]).expect("Failed to create encryptor");
+        assert!(wrong_encryptor.decrypt_private_key(&store, &key).is_err());
+
+        // the ciphertext is bound to its public key
+        let swapped_key = Key::new_server_encrypted("other_public", &encrypted, "user", "key");
+        assert!(encryptor.decrypt_private_key(&store, &swapped_key).is_err());
+    }
+}
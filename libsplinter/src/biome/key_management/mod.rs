@@ -14,8 +14,16 @@
 
 //! Provides an API for storing key pairs and associating them with users.
 
+#[cfg(feature = "biome-key-encryption")]
+mod encryption;
 pub mod store;
 
+use std::time::SystemTime;
+
+#[cfg(feature = "diesel")]
+use crate::database::DbTimestamp;
+#[cfg(feature = "biome-key-encryption")]
+pub use encryption::{KeyEncryptionError, KeyEncryptor};
 #[cfg(feature = "diesel")]
 use store::diesel::models::KeyModel;
 
@@ -26,6 +34,14 @@ pub struct Key {
     pub encrypted_private_key: String,
     pub user_id: String,
     pub display_name: String,
+    /// When the key was added
+    pub created: SystemTime,
+    /// When the key was last changed
+    pub updated: SystemTime,
+    /// When the key was retired by a key rotation, if it has been
+    pub retired: Option<SystemTime>,
+    /// Whether the private key was encrypted by the server rather than by the client
+    pub server_encrypted: bool,
 }
 
 impl Key {
@@ -46,13 +62,45 @@ impl Key {
         user_id: &str,
         display_name: &str,
     ) -> Self {
+        let now = SystemTime::now();
         Key {
             public_key: public_key.to_string(),
             encrypted_private_key: encrypted_private_key.to_string(),
             user_id: user_id.to_string(),
             display_name: display_name.to_string(),
+            created: now,
+            updated: now,
+            retired: None,
+            server_encrypted: false,
         }
     }
+
+    /// Creates a new Key whose private key was encrypted by a `KeyEncryptor`
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key`: The public key of the key pair.
+    /// * `encrypted_private_key`: The private key of the key pair, as encrypted by
+    ///     `KeyEncryptor::encrypt_private_key`
+    /// * `user_id`: The identity of the Biome user who owns the key.
+    /// * `display_name`: A human readable name for the key.
+    #[cfg(feature = "biome-key-encryption")]
+    pub fn new_server_encrypted(
+        public_key: &str,
+        encrypted_private_key: &str,
+        user_id: &str,
+        display_name: &str,
+    ) -> Self {
+        Key {
+            server_encrypted: true,
+            ..Key::new(public_key, encrypted_private_key, user_id, display_name)
+        }
+    }
+
+    /// Returns true if the key has been retired by a key rotation
+    pub fn is_retired(&self) -> bool {
+        self.retired.is_some()
+    }
 }
 
 #[cfg(feature = "diesel")]
@@ -63,6 +111,10 @@ impl From<KeyModel> for Key {
             encrypted_private_key: key.encrypted_private_key,
             user_id: key.user_id,
             display_name: key.display_name,
+            created: key.created.into(),
+            updated: key.updated.into(),
+            retired: key.retired.map(SystemTime::from),
+            server_encrypted: key.server_encrypted,
         }
     }
 }
@@ -75,6 +127,10 @@ impl Into<KeyModel> for Key {
             encrypted_private_key: self.encrypted_private_key,
             user_id: self.user_id,
             display_name: self.display_name,
+            created: self.created.into(),
+            updated: self.updated.into(),
+            retired: self.retired.map(DbTimestamp::from),
+            server_encrypted: self.server_encrypted,
         }
     }
 }
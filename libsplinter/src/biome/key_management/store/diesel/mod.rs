@@ -22,14 +22,22 @@ use crate::database::ConnectionPool;
 
 #[cfg(feature = "biome-credentials")]
 use operations::update_keys_and_password::KeyStoreUpdateKeysAndPasswordOperation as _;
+#[cfg(feature = "biome-key-encryption")]
+use operations::{
+    add_data_key::KeyStoreAddDataKeyOperation as _,
+    fetch_data_key::KeyStoreFetchDataKeyOperation as _,
+    rotate_key::KeyStoreRotateKeyOperation as _,
+};
 use operations::{
     fetch_key::KeyStoreFetchKeyOperation as _, insert_key::KeyStoreInsertKeyOperation as _,
-    list_keys::KeyStoreListKeysOperation as _, list_keys::KeyStoreListKeysWithUserIDOperation as _,
+    list_keys::KeyStoreListKeysOperation as _,
+    list_keys::KeyStoreListKeysWithPublicKeysOperation as _,
+    list_keys::KeyStoreListKeysWithUserIDOperation as _,
     remove_key::KeyStoreRemoveKeyOperation as _, update_key::KeyStoreUpdateKeyOperation as _,
     KeyStoreOperations,
 };
 
-/// Manages creating, updating and fetching keys from a PostgreSQL database.
+/// Manages creating, updating and fetching keys from a database.
 pub struct DieselKeyStore {
     pub connection_pool: ConnectionPool,
 }
@@ -39,7 +47,7 @@ impl DieselKeyStore {
     ///
     /// # Arguments
     ///
-    ///  * `connection_pool`: connection pool to the PostgreSQL database
+    ///  * `connection_pool`: connection pool to the database
     ///
     pub fn new(connection_pool: ConnectionPool) -> Self {
         DieselKeyStore { connection_pool }
@@ -48,7 +56,9 @@ impl DieselKeyStore {
 
 impl KeyStore for DieselKeyStore {
     fn add_key(&self, key: Key) -> Result<(), KeyStoreError> {
-        KeyStoreOperations::new(&*self.connection_pool.get()?).insert_key(key)
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).insert_key(key)
+        })
     }
 
     fn update_key(
@@ -57,27 +67,40 @@ impl KeyStore for DieselKeyStore {
         user_id: &str,
         new_display_name: &str,
     ) -> Result<(), KeyStoreError> {
-        KeyStoreOperations::new(&*self.connection_pool.get()?).update_key(
-            public_key,
-            user_id,
-            new_display_name,
-        )
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).update_key(public_key, user_id, new_display_name)
+        })
     }
 
     fn remove_key(&self, public_key: &str, user_id: &str) -> Result<Key, KeyStoreError> {
-        KeyStoreOperations::new(&*self.connection_pool.get()?).remove_key(public_key, user_id)
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).remove_key(public_key, user_id)
+        })
     }
 
     fn fetch_key(&self, public_key: &str, user_id: &str) -> Result<Key, KeyStoreError> {
-        KeyStoreOperations::new(&*self.connection_pool.get()?).fetch_key(public_key, user_id)
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).fetch_key(public_key, user_id)
+        })
     }
 
     fn list_keys(&self, user_id: Option<&str>) -> Result<Vec<Key>, KeyStoreError> {
-        match user_id {
-            Some(user_id) => KeyStoreOperations::new(&*self.connection_pool.get()?)
-                .list_keys_with_user_id(user_id),
-            None => KeyStoreOperations::new(&*self.connection_pool.get()?).list_keys(),
+        with_connection!(self.connection_pool, |conn| match user_id {
+            Some(user_id) => KeyStoreOperations::new(conn).list_keys_with_user_id(user_id),
+            None => KeyStoreOperations::new(conn).list_keys(),
+        })
+    }
+
+    fn list_keys_with_public_keys(
+        &self,
+        public_keys: &[String],
+    ) -> Result<Vec<Key>, KeyStoreError> {
+        if public_keys.is_empty() {
+            return Ok(vec![]);
         }
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).list_keys_with_public_keys(public_keys)
+        })
     }
 
     #[cfg(feature = "biome-credentials")]
@@ -87,10 +110,34 @@ impl KeyStore for DieselKeyStore {
         updated_password: &str,
         keys: &[Key],
     ) -> Result<(), KeyStoreError> {
-        KeyStoreOperations::new(&*self.connection_pool.get()?).update_keys_and_password(
-            user_id,
-            updated_password,
-            keys,
-        )
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).update_keys_and_password(user_id, updated_password, keys)
+        })
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    fn rotate_key(
+        &self,
+        public_key: &str,
+        user_id: &str,
+        new_key: Key,
+    ) -> Result<(), KeyStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).rotate_key(public_key, user_id, new_key)
+        })
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    fn add_data_key(&self, user_id: &str, wrapped_key: &str) -> Result<(), KeyStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).add_data_key(user_id, wrapped_key)
+        })
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    fn fetch_data_key(&self, user_id: &str) -> Result<String, KeyStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            KeyStoreOperations::new(conn).fetch_data_key(user_id)
+        })
     }
 }
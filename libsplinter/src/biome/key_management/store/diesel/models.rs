@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::schema::keys;
+use super::schema::{keys, user_data_keys};
+use crate::database::DbTimestamp;
 
 #[derive(Insertable, Queryable, Identifiable, PartialEq, Debug)]
 #[table_name = "keys"]
@@ -22,4 +23,17 @@ pub struct KeyModel {
     pub encrypted_private_key: String,
     pub user_id: String,
     pub display_name: String,
+    pub created: DbTimestamp,
+    pub updated: DbTimestamp,
+    pub retired: Option<DbTimestamp>,
+    pub server_encrypted: bool,
+}
+
+#[derive(Insertable, Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "user_data_keys"]
+#[primary_key(user_id)]
+pub struct UserDataKeyModel {
+    pub user_id: String,
+    pub wrapped_key: String,
+    pub created: DbTimestamp,
 }
@@ -0,0 +1,62 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::KeyStoreOperations;
+use crate::biome::key_management::store::diesel::models::UserDataKeyModel;
+use crate::biome::key_management::store::diesel::schema::user_data_keys;
+use crate::biome::key_management::store::KeyStoreError;
+use crate::database::DbTimestamp;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::key_management) trait KeyStoreAddDataKeyOperation {
+    fn add_data_key(&self, user_id: &str, wrapped_key: &str) -> Result<(), KeyStoreError>;
+}
+
+impl_for_diesel_backends! {
+    KeyStoreAddDataKeyOperation for KeyStoreOperations {
+        fn add_data_key(&self, user_id: &str, wrapped_key: &str) -> Result<(), KeyStoreError> {
+            insert_into(user_data_keys::table)
+                .values(UserDataKeyModel {
+                    user_id: user_id.to_string(),
+                    wrapped_key: wrapped_key.to_string(),
+                    created: DbTimestamp::now(),
+                })
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        KeyStoreError::DuplicateKeyError(format!(
+                            "Data key for user {} is already in database",
+                            user_id
+                        ))
+                    }
+                    QueryError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                        KeyStoreError::UserDoesNotExistError(format!(
+                            "User with ID {} does not exist in database",
+                            user_id
+                        ))
+                    }
+                    _ => KeyStoreError::OperationError {
+                        context: "Failed to add data key".to_string(),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
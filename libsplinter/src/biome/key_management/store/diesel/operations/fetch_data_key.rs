@@ -0,0 +1,47 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::KeyStoreOperations;
+use crate::biome::key_management::store::diesel::models::UserDataKeyModel;
+use crate::biome::key_management::store::diesel::schema::user_data_keys;
+use crate::biome::key_management::store::KeyStoreError;
+
+use diesel::{prelude::*, result::Error::NotFound};
+
+pub(in crate::biome::key_management) trait KeyStoreFetchDataKeyOperation {
+    fn fetch_data_key(&self, user_id: &str) -> Result<String, KeyStoreError>;
+}
+
+impl_for_diesel_backends! {
+    KeyStoreFetchDataKeyOperation for KeyStoreOperations {
+        fn fetch_data_key(&self, user_id: &str) -> Result<String, KeyStoreError> {
+            let data_key = user_data_keys::table
+                .find(user_id)
+                .first::<UserDataKeyModel>(self.conn)
+                .map(Some)
+                .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+                .map_err(|err| KeyStoreError::QueryError {
+                    context: "Failed to fetch data key by user ID".to_string(),
+                    source: Box::new(err),
+                })?
+                .ok_or_else(|| {
+                    KeyStoreError::NotFoundError(format!(
+                        "Failed to find data key for user id: {}",
+                        user_id
+                    ))
+                })?;
+            Ok(data_key.wrapped_key)
+        }
+    }
+}
@@ -23,29 +23,25 @@ pub(in crate::biome::key_management) trait KeyStoreListKeysOperation {
     fn list_keys(&self) -> Result<Vec<Key>, KeyStoreError>;
 }
 
-impl<'a, C> KeyStoreListKeysOperation for KeyStoreOperations<'a, C>
-where
-    C: diesel::Connection,
-    <C as diesel::Connection>::Backend: diesel::backend::SupportsDefaultKeyword,
-    <C as diesel::Connection>::Backend: 'static,
-    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
-{
-    fn list_keys(&self) -> Result<Vec<Key>, KeyStoreError> {
-        let keys = keys::table
-            .load::<KeyModel>(self.conn)
-            .map(Some)
-            .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
-            .map_err(|err| KeyStoreError::OperationError {
-                context: "Failed to get keys".to_string(),
-                source: Box::new(err),
-            })?
-            .ok_or_else(|| {
-                KeyStoreError::NotFoundError("Could not get all keys from storage".to_string())
-            })?
-            .into_iter()
-            .map(Key::from)
-            .collect();
-        Ok(keys)
+impl_for_diesel_backends! {
+    KeyStoreListKeysOperation for KeyStoreOperations {
+        fn list_keys(&self) -> Result<Vec<Key>, KeyStoreError> {
+            let keys = keys::table
+                .load::<KeyModel>(self.conn)
+                .map(Some)
+                .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+                .map_err(|err| KeyStoreError::OperationError {
+                    context: "Failed to get keys".to_string(),
+                    source: Box::new(err),
+                })?
+                .ok_or_else(|| {
+                    KeyStoreError::NotFoundError("Could not get all keys from storage".to_string())
+                })?
+                .into_iter()
+                .map(Key::from)
+                .collect();
+            Ok(keys)
+        }
     }
 }
 
@@ -53,31 +49,53 @@ pub(in crate::biome::key_management) trait KeyStoreListKeysWithUserIDOperation {
     fn list_keys_with_user_id(&self, user_id: &str) -> Result<Vec<Key>, KeyStoreError>;
 }
 
-impl<'a, C> KeyStoreListKeysWithUserIDOperation for KeyStoreOperations<'a, C>
-where
-    C: diesel::Connection,
-    <C as diesel::Connection>::Backend: diesel::backend::SupportsDefaultKeyword,
-    <C as diesel::Connection>::Backend: 'static,
-    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
-{
-    fn list_keys_with_user_id(&self, user_id: &str) -> Result<Vec<Key>, KeyStoreError> {
-        let keys = keys::table
-            .filter(keys::user_id.eq(user_id))
-            .load::<KeyModel>(self.conn)
-            .map(Some)
-            .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
-            .map_err(|err| KeyStoreError::OperationError {
-                context: "Failed to get keys with user ID".to_string(),
-                source: Box::new(err),
-            })?
-            .ok_or_else(|| {
-                KeyStoreError::NotFoundError(
-                    "Could not get all keys with user ID from storage".to_string(),
-                )
-            })?
-            .into_iter()
-            .map(Key::from)
-            .collect();
-        Ok(keys)
+impl_for_diesel_backends! {
+    KeyStoreListKeysWithUserIDOperation for KeyStoreOperations {
+        fn list_keys_with_user_id(&self, user_id: &str) -> Result<Vec<Key>, KeyStoreError> {
+            let keys = keys::table
+                .filter(keys::user_id.eq(user_id))
+                .load::<KeyModel>(self.conn)
+                .map(Some)
+                .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+                .map_err(|err| KeyStoreError::OperationError {
+                    context: "Failed to get keys with user ID".to_string(),
+                    source: Box::new(err),
+                })?
+                .ok_or_else(|| {
+                    KeyStoreError::NotFoundError(
+                        "Could not get all keys with user ID from storage".to_string(),
+                    )
+                })?
+                .into_iter()
+                .map(Key::from)
+                .collect();
+            Ok(keys)
+        }
+    }
+}
+
+pub(in crate::biome::key_management) trait KeyStoreListKeysWithPublicKeysOperation {
+    fn list_keys_with_public_keys(&self, public_keys: &[String])
+        -> Result<Vec<Key>, KeyStoreError>;
+}
+
+impl_for_diesel_backends! {
+    KeyStoreListKeysWithPublicKeysOperation for KeyStoreOperations {
+        fn list_keys_with_public_keys(
+            &self,
+            public_keys: &[String],
+        ) -> Result<Vec<Key>, KeyStoreError> {
+            let keys = keys::table
+                .filter(keys::public_key.eq_any(public_keys))
+                .load::<KeyModel>(self.conn)
+                .map_err(|err| KeyStoreError::OperationError {
+                    context: "Failed to get keys with public keys".to_string(),
+                    source: Box::new(err),
+                })?
+                .into_iter()
+                .map(Key::from)
+                .collect();
+            Ok(keys)
+        }
     }
 }
@@ -12,10 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "biome-key-encryption")]
+pub(super) mod add_data_key;
+#[cfg(feature = "biome-key-encryption")]
+pub(super) mod fetch_data_key;
 pub(super) mod fetch_key;
 pub(super) mod insert_key;
 pub(super) mod list_keys;
 pub(super) mod remove_key;
+#[cfg(feature = "biome-key-encryption")]
+pub(super) mod rotate_key;
 pub(super) mod update_key;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod update_keys_and_password;
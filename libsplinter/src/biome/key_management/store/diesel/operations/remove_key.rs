@@ -23,48 +23,44 @@ pub(in crate::biome::key_management) trait KeyStoreRemoveKeyOperation {
     fn remove_key(&self, public_key: &str, user_id: &str) -> Result<Key, KeyStoreError>;
 }
 
-impl<'a, C> KeyStoreRemoveKeyOperation for KeyStoreOperations<'a, C>
-where
-    C: diesel::Connection,
-    <C as diesel::Connection>::Backend: diesel::backend::SupportsDefaultKeyword,
-    <C as diesel::Connection>::Backend: 'static,
-    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
-{
-    fn remove_key(&self, public_key: &str, user_id: &str) -> Result<Key, KeyStoreError> {
-        let key = keys::table
-            .filter(
-                keys::public_key
-                    .eq(public_key)
-                    .and(keys::user_id.eq(user_id)),
+impl_for_diesel_backends! {
+    KeyStoreRemoveKeyOperation for KeyStoreOperations {
+        fn remove_key(&self, public_key: &str, user_id: &str) -> Result<Key, KeyStoreError> {
+            let key = keys::table
+                .filter(
+                    keys::public_key
+                        .eq(public_key)
+                        .and(keys::user_id.eq(user_id)),
+                )
+                .first::<KeyModel>(self.conn)
+                .map(Some)
+                .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+                .map_err(|err| KeyStoreError::QueryError {
+                    context: "Failed to fetch key by user ID and public key".to_string(),
+                    source: Box::new(err),
+                })?
+                .ok_or_else(|| {
+                    KeyStoreError::NotFoundError(format!(
+                        "Failed to find key with public key: {} and user id: {}",
+                        public_key, user_id
+                    ))
+                })?;
+
+            delete(
+                keys::table.filter(
+                    keys::public_key
+                        .eq(public_key)
+                        .and(keys::user_id.eq(user_id)),
+                ),
             )
-            .first::<KeyModel>(self.conn)
-            .map(Some)
-            .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
-            .map_err(|err| KeyStoreError::QueryError {
-                context: "Failed to fetch key by user ID and public key".to_string(),
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| KeyStoreError::OperationError {
+                context: "Failed to delete key".to_string(),
                 source: Box::new(err),
-            })?
-            .ok_or_else(|| {
-                KeyStoreError::NotFoundError(format!(
-                    "Failed to find key with public key: {} and user id: {}",
-                    public_key, user_id
-                ))
             })?;
 
-        delete(
-            keys::table.filter(
-                keys::public_key
-                    .eq(public_key)
-                    .and(keys::user_id.eq(user_id)),
-            ),
-        )
-        .execute(self.conn)
-        .map(|_| ())
-        .map_err(|err| KeyStoreError::OperationError {
-            context: "Failed to delete key".to_string(),
-            source: Box::new(err),
-        })?;
-
-        Ok(Key::from(key))
+            Ok(Key::from(key))
+        }
     }
 }
@@ -0,0 +1,92 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::KeyStoreOperations;
+use crate::biome::key_management::store::diesel::models::KeyModel;
+use crate::biome::key_management::store::diesel::schema::keys;
+use crate::biome::key_management::{store::KeyStoreError, Key};
+use crate::database::DbTimestamp;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::key_management) trait KeyStoreRotateKeyOperation {
+    fn rotate_key(
+        &self,
+        public_key: &str,
+        user_id: &str,
+        new_key: Key,
+    ) -> Result<(), KeyStoreError>;
+}
+
+impl_for_diesel_backends! {
+    KeyStoreRotateKeyOperation for KeyStoreOperations {
+        fn rotate_key(
+            &self,
+            public_key: &str,
+            user_id: &str,
+            new_key: Key,
+        ) -> Result<(), KeyStoreError> {
+            let new_key_model: KeyModel = new_key.into();
+            let new_public_key = new_key_model.public_key.clone();
+
+            self.conn
+                .transaction::<_, QueryError, _>(|| {
+                    let now = DbTimestamp::now();
+                    let retired = diesel::update(
+                        keys::table
+                            .find((public_key, user_id))
+                            .filter(keys::retired.is_null()),
+                    )
+                    .set((keys::retired.eq(Some(now)), keys::updated.eq(now)))
+                    .execute(self.conn)?;
+
+                    if retired == 0 {
+                        return Ok(false);
+                    }
+
+                    insert_into(keys::table)
+                        .values(new_key_model)
+                        .execute(self.conn)?;
+
+                    Ok(true)
+                })
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        KeyStoreError::DuplicateKeyError(format!(
+                            "Public key {} for user {} is already in database",
+                            new_public_key, user_id
+                        ))
+                    }
+                    _ => KeyStoreError::OperationError {
+                        context: "Failed to rotate key".to_string(),
+                        source: Box::new(err),
+                    },
+                })
+                .and_then(|rotated| {
+                    if rotated {
+                        Ok(())
+                    } else {
+                        Err(KeyStoreError::NotFoundError(format!(
+                            "Failed to find unretired key with public key: {} and user id: {}",
+                            public_key, user_id
+                        )))
+                    }
+                })
+        }
+    }
+}
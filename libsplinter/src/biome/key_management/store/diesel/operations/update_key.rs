@@ -15,6 +15,7 @@
 use super::KeyStoreOperations;
 use crate::biome::key_management::store::diesel::schema::keys;
 use crate::biome::key_management::store::KeyStoreError;
+use crate::database::DbTimestamp;
 
 use diesel::prelude::*;
 
@@ -27,31 +28,30 @@ pub(in crate::biome::key_management) trait KeyStoreUpdateKeyOperation {
     ) -> Result<(), KeyStoreError>;
 }
 
-impl<'a, C> KeyStoreUpdateKeyOperation for KeyStoreOperations<'a, C>
-where
-    C: diesel::Connection,
-    <C as diesel::Connection>::Backend: diesel::backend::SupportsDefaultKeyword,
-    <C as diesel::Connection>::Backend: 'static,
-    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
-{
-    fn update_key(
-        &self,
-        user_id: &str,
-        public_key: &str,
-        display_name: &str,
-    ) -> Result<(), KeyStoreError> {
-        match diesel::update(keys::table.find((public_key, user_id)))
-            .set((keys::display_name.eq(display_name),))
-            .execute(self.conn)
-            .map_err(|err| KeyStoreError::OperationError {
-                context: "Failed to update key".to_string(),
-                source: Box::new(err),
-            })? {
-            0 => Err(KeyStoreError::NotFoundError(format!(
-                "Key with public key {} and user ID {} not found",
-                public_key, user_id
-            ))),
-            _ => Ok(()),
+impl_for_diesel_backends! {
+    KeyStoreUpdateKeyOperation for KeyStoreOperations {
+        fn update_key(
+            &self,
+            user_id: &str,
+            public_key: &str,
+            display_name: &str,
+        ) -> Result<(), KeyStoreError> {
+            match diesel::update(keys::table.find((public_key, user_id)))
+                .set((
+                    keys::display_name.eq(display_name),
+                    keys::updated.eq(DbTimestamp::now()),
+                ))
+                .execute(self.conn)
+                .map_err(|err| KeyStoreError::OperationError {
+                    context: "Failed to update key".to_string(),
+                    source: Box::new(err),
+                })? {
+                0 => Err(KeyStoreError::NotFoundError(format!(
+                    "Key with public key {} and user ID {} not found",
+                    public_key, user_id
+                ))),
+                _ => Ok(()),
+            }
         }
     }
 }
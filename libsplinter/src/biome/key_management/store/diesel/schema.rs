@@ -18,5 +18,17 @@ table! {
         encrypted_private_key -> Text,
         user_id -> Text,
         display_name -> Text,
+        created -> Timestamp,
+        updated -> Timestamp,
+        retired -> Nullable<Timestamp>,
+        server_encrypted -> Bool,
+    }
+}
+
+table! {
+    user_data_keys (user_id) {
+        user_id -> Text,
+        wrapped_key -> Text,
+        created -> Timestamp,
     }
 }
@@ -12,6 +12,7 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 #[cfg(feature = "biome-credentials")]
 use crate::biome::credentials::store::{memory::MemoryCredentialsStore, CredentialsStore};
@@ -25,6 +26,8 @@ pub struct MemoryKeyStore {
     inner: Arc<Mutex<HashMap<(String, String), Key>>>,
     #[cfg(feature = "biome-credentials")]
     credentials_store: MemoryCredentialsStore,
+    #[cfg(feature = "biome-key-encryption")]
+    data_keys: Arc<Mutex<HashMap<String, String>>>,
 }
 
 impl MemoryKeyStore {
@@ -33,6 +36,8 @@ impl MemoryKeyStore {
         MemoryKeyStore {
             inner: Arc::new(Mutex::new(HashMap::new())),
             credentials_store,
+            #[cfg(feature = "biome-key-encryption")]
+            data_keys: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -40,6 +45,8 @@ impl MemoryKeyStore {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-key-encryption")]
+            data_keys: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -67,6 +74,7 @@ impl KeyStore for MemoryKeyStore {
 
         if let Some(key) = inner.get_mut(&(user_id.into(), public_key.into())) {
             key.display_name = new_display_name.to_string();
+            key.updated = SystemTime::now();
             Ok(())
         } else {
             Err(KeyStoreError::NotFoundError(format!(
@@ -124,6 +132,21 @@ impl KeyStore for MemoryKeyStore {
         }
     }
 
+    fn list_keys_with_public_keys(
+        &self,
+        public_keys: &[String],
+    ) -> Result<Vec<Key>, KeyStoreError> {
+        let inner = self.inner.lock().map_err(|_| KeyStoreError::StorageError {
+            context: "Cannot access key store: mutex lock poisoned".to_string(),
+            source: None,
+        })?;
+        Ok(inner
+            .values()
+            .filter(|key| public_keys.contains(&key.public_key))
+            .cloned()
+            .collect())
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn update_keys_and_password(
         &self,
@@ -152,4 +175,155 @@ impl KeyStore for MemoryKeyStore {
 
         Ok(())
     }
+
+    #[cfg(feature = "biome-key-encryption")]
+    fn rotate_key(
+        &self,
+        public_key: &str,
+        user_id: &str,
+        new_key: Key,
+    ) -> Result<(), KeyStoreError> {
+        let mut inner = self.inner.lock().map_err(|_| KeyStoreError::StorageError {
+            context: "Cannot access key store: mutex lock poisoned".to_string(),
+            source: None,
+        })?;
+
+        let new_key_id = (new_key.user_id.clone(), new_key.public_key.clone());
+        if inner.contains_key(&new_key_id) {
+            return Err(KeyStoreError::DuplicateKeyError(format!(
+                "Public key {} for user {} is already in database",
+                new_key.public_key, new_key.user_id
+            )));
+        }
+
+        match inner.get_mut(&(user_id.to_string(), public_key.to_string())) {
+            Some(key) if !key.is_retired() => {
+                let now = SystemTime::now();
+                key.retired = Some(now);
+                key.updated = now;
+            }
+            _ => {
+                return Err(KeyStoreError::NotFoundError(format!(
+                    "Failed to find unretired key with public key: {} and user id: {}",
+                    public_key, user_id
+                )))
+            }
+        }
+
+        inner.insert(new_key_id, new_key);
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    fn add_data_key(&self, user_id: &str, wrapped_key: &str) -> Result<(), KeyStoreError> {
+        let mut data_keys = self
+            .data_keys
+            .lock()
+            .map_err(|_| KeyStoreError::StorageError {
+                context: "Cannot access key store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        if data_keys.contains_key(user_id) {
+            return Err(KeyStoreError::DuplicateKeyError(format!(
+                "Data key for user {} is already in database",
+                user_id
+            )));
+        }
+
+        data_keys.insert(user_id.to_string(), wrapped_key.to_string());
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    fn fetch_data_key(&self, user_id: &str) -> Result<String, KeyStoreError> {
+        let data_keys = self
+            .data_keys
+            .lock()
+            .map_err(|_| KeyStoreError::StorageError {
+                context: "Cannot access key store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        data_keys.get(user_id).cloned().ok_or_else(|| {
+            KeyStoreError::NotFoundError(format!(
+                "Failed to find data key for user id: {}",
+                user_id
+            ))
+        })
+    }
+}
+
+#[cfg(all(test, feature = "biome-key-encryption"))]
+mod tests {
+    use super::*;
+
+    /// Verify that rotating a key retires the old key and adds the new one, and that a retired
+    /// key cannot be rotated again.
+    #[test]
+    fn test_rotate_key() {
+        #[cfg(feature = "biome-credentials")]
+        let store = MemoryKeyStore::new(MemoryCredentialsStore::new());
+        #[cfg(not(feature = "biome-credentials"))]
+        let store = MemoryKeyStore::new();
+
+        store
+            .add_key(Key::new("old_public", "old_private", "user", "key"))
+            .expect("Failed to add key");
+        store
+            .rotate_key(
+                "old_public",
+                "user",
+                Key::new("new_public", "new_private", "user", "key"),
+            )
+            .expect("Failed to rotate key");
+
+        let old_key = store
+            .fetch_key("old_public", "user")
+            .expect("Failed to fetch old key");
+        assert!(old_key.is_retired());
+        let new_key = store
+            .fetch_key("new_public", "user")
+            .expect("Failed to fetch new key");
+        assert!(!new_key.is_retired());
+
+        match store.rotate_key(
+            "old_public",
+            "user",
+            Key::new("other_public", "other_private", "user", "key"),
+        ) {
+            Err(KeyStoreError::NotFoundError(_)) => (),
+            res => panic!("Expected NotFoundError, got {:?}", res),
+        }
+    }
+
+    /// Verify that keys are listed by public key, including keys with the same public key owned
+    /// by different users, and that other keys are not listed.
+    #[test]
+    fn test_list_keys_with_public_keys() {
+        #[cfg(feature = "biome-credentials")]
+        let store = MemoryKeyStore::new(MemoryCredentialsStore::new());
+        #[cfg(not(feature = "biome-credentials"))]
+        let store = MemoryKeyStore::new();
+
+        for (public_key, user_id) in &[("shared", "alice"), ("shared", "bob"), ("other", "carol")] {
+            store
+                .add_key(Key::new(public_key, "private", user_id, "key"))
+                .expect("Failed to add key");
+        }
+
+        let mut users = store
+            .list_keys_with_public_keys(&["shared".to_string(), "unknown".to_string()])
+            .expect("Failed to list keys")
+            .into_iter()
+            .map(|key| key.user_id)
+            .collect::<Vec<_>>();
+        users.sort();
+        assert_eq!(users, vec!["alice".to_string(), "bob".to_string()]);
+
+        assert!(store
+            .list_keys_with_public_keys(&[])
+            .expect("Failed to list keys")
+            .is_empty());
+    }
 }
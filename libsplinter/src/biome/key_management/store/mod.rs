@@ -31,7 +31,7 @@ pub trait KeyStore: Sync + Send {
     ///  * `key` - The key to be added
     fn add_key(&self, key: Key) -> Result<(), KeyStoreError>;
 
-    /// Updates a key information in the underling storage
+    /// Updates a key information in the underling storage, setting its last updated time
     ///
     /// # Arguments
     ///
@@ -61,13 +61,22 @@ pub trait KeyStore: Sync + Send {
     /// * `user_id`: The ID owner of the key record to be fetched.
     fn fetch_key(&self, public_key: &str, user_id: &str) -> Result<Key, KeyStoreError>;
 
-    /// List all keys from the underlying storage
+    /// List all keys from the underlying storage, including retired keys
     ///
     /// # Arguments
     ///
     /// * `user_id`: The ID owner of the key records to list.
     fn list_keys(&self, user_id: Option<&str>) -> Result<Vec<Key>, KeyStoreError>;
 
+    /// Lists the keys with any of the given public keys, including retired keys, from the
+    /// underlying storage. A public key may be owned by more than one user.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys`: The public keys of the key records to list.
+    fn list_keys_with_public_keys(&self, public_keys: &[String])
+        -> Result<Vec<Key>, KeyStoreError>;
+
     #[cfg(feature = "biome-credentials")]
     /// Updates keys and the associated user's password in the underlying storage
     ///
@@ -83,4 +92,41 @@ pub trait KeyStore: Sync + Send {
         updated_password: &str,
         keys: &[Key],
     ) -> Result<(), KeyStoreError>;
+
+    /// Retires a key and adds the key that replaces it. Retired keys are kept so that data
+    /// signed or encrypted with them can still be used.
+    ///
+    /// Returns a `NotFoundError` if the key does not exist or has already been retired.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key`: The public key of the key record to be retired.
+    /// * `user_id`: The ID owner of the key record to be retired.
+    /// * `new_key`: The key that replaces the retired key
+    #[cfg(feature = "biome-key-encryption")]
+    fn rotate_key(
+        &self,
+        public_key: &str,
+        user_id: &str,
+        new_key: Key,
+    ) -> Result<(), KeyStoreError>;
+
+    /// Adds a user's data key, wrapped by the node's master key, to the underlying storage
+    ///
+    /// Returns a `DuplicateKeyError` if the user already has a data key.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id`: The ID of the user who owns the data key.
+    /// * `wrapped_key`: The data key, encrypted with the node's master key
+    #[cfg(feature = "biome-key-encryption")]
+    fn add_data_key(&self, user_id: &str, wrapped_key: &str) -> Result<(), KeyStoreError>;
+
+    /// Fetches a user's wrapped data key from the underlying storage
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id`: The ID of the user who owns the data key.
+    #[cfg(feature = "biome-key-encryption")]
+    fn fetch_data_key(&self, user_id: &str) -> Result<String, KeyStoreError>;
 }
@@ -0,0 +1,19 @@
+-- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+DELETE FROM user_notifications a USING user_notifications b
+  WHERE a.notification_id = b.notification_id AND a.user_id > b.user_id;
+ALTER TABLE user_notifications DROP CONSTRAINT user_notifications_pkey;
+ALTER TABLE user_notifications ADD PRIMARY KEY (notification_id);
@@ -0,0 +1,26 @@
+-- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS refresh_token_sessions (
+    session_id            TEXT          PRIMARY KEY,
+    user_id               TEXT          NOT NULL,
+    token                 TEXT          NOT NULL,
+    created               TIMESTAMP     NOT NULL,
+    client_info           TEXT,
+    FOREIGN KEY (user_id) REFERENCES splinter_user(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS refresh_token_sessions_user_id_idx
+    ON refresh_token_sessions (user_id);
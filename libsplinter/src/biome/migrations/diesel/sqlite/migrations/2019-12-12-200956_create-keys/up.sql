@@ -0,0 +1,23 @@
+-- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS keys (
+    public_key            TEXT NOT NULL,
+    encrypted_private_key TEXT NOT NULL,
+    user_id               TEXT NOT NULL,
+    display_name          TEXT NOT NULL,
+    PRIMARY KEY(public_key, user_id),
+    FOREIGN KEY (user_id) REFERENCES splinter_user(id)
+);
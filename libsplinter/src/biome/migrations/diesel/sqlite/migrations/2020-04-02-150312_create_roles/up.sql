@@ -0,0 +1,33 @@
+---- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS biome_roles (
+    name                  TEXT          PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS biome_role_permissions (
+    role_name             TEXT          NOT NULL,
+    permission            TEXT          NOT NULL,
+    PRIMARY KEY(role_name, permission),
+    FOREIGN KEY (role_name) REFERENCES biome_roles(name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS biome_user_roles (
+    user_id               TEXT          NOT NULL,
+    role_name             TEXT          NOT NULL,
+    PRIMARY KEY(user_id, role_name),
+    FOREIGN KEY (user_id) REFERENCES splinter_user(id) ON DELETE CASCADE,
+    FOREIGN KEY (role_name) REFERENCES biome_roles(name) ON DELETE CASCADE
+);
@@ -0,0 +1,28 @@
+-- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS user_login_attempts (
+    user_id               TEXT          PRIMARY KEY,
+    failed_attempts       INTEGER       NOT NULL,
+    locked_until          TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES splinter_user(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS user_password_reset_tokens (
+    token_hash            TEXT          PRIMARY KEY,
+    user_id               TEXT          NOT NULL,
+    expires               TIMESTAMP     NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES splinter_user(id) ON DELETE CASCADE
+);
@@ -0,0 +1,32 @@
+-- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+-- SQLite cannot add a column with a non-constant default, so the existing keys are given the
+-- time of the migration explicitly.
+ALTER TABLE keys ADD COLUMN created TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00.000000';
+ALTER TABLE keys ADD COLUMN updated TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00.000000';
+ALTER TABLE keys ADD COLUMN retired TIMESTAMP;
+ALTER TABLE keys ADD COLUMN server_encrypted BOOLEAN NOT NULL DEFAULT 0;
+
+UPDATE keys SET
+    created = strftime('%Y-%m-%d %H:%M:%f000', 'now'),
+    updated = strftime('%Y-%m-%d %H:%M:%f000', 'now');
+
+CREATE TABLE IF NOT EXISTS user_data_keys (
+    user_id               TEXT          PRIMARY KEY,
+    wrapped_key           TEXT          NOT NULL,
+    created               TIMESTAMP     NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES splinter_user(id) ON DELETE CASCADE
+);
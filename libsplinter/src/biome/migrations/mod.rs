@@ -25,9 +25,13 @@
 //! run_postgres_migrations(&connection).unwrap();
 //!
 //! ```
+//!
+//! SQLite databases are migrated the same way with `run_sqlite_migrations`.
 
 #[cfg(feature = "diesel")]
 mod diesel;
 
 #[cfg(feature = "postgres")]
 pub use self::diesel::postgres::run_migrations as run_postgres_migrations;
+#[cfg(feature = "sqlite")]
+pub use self::diesel::sqlite::run_migrations as run_sqlite_migrations;
@@ -24,6 +24,10 @@
 //! Private Key Management: API to store and retrieve encrypted private keys.
 //!
 //! User Notifications: API to create and manage user notifications.
+//!
+//! Roles: API to define roles, which group permissions, and assign them to users.
+//!
+//! OAuth: API to log in with an account at an OpenID Connect provider.
 
 #[cfg(feature = "biome-credentials")]
 pub mod credentials;
@@ -36,9 +40,15 @@ pub mod migrations;
 #[cfg(feature = "biome-notifications")]
 pub mod notifications;
 
+#[cfg(feature = "biome-oauth")]
+pub mod oauth;
+
 #[cfg(feature = "biome-credentials")]
 pub mod refresh_tokens;
 
+#[cfg(feature = "biome-roles")]
+pub mod roles;
+
 #[cfg(feature = "rest-api")]
 pub mod rest_api;
 mod user;
@@ -53,11 +63,26 @@ pub use key_management::store::diesel::DieselKeyStore;
 #[cfg(feature = "biome-key-management")]
 pub use key_management::store::memory::MemoryKeyStore;
 
+#[cfg(all(feature = "biome-notifications", feature = "diesel"))]
+pub use notifications::store::diesel::DieselNotificationStore;
+#[cfg(feature = "biome-notifications")]
+pub use notifications::store::memory::MemoryNotificationStore;
+
+#[cfg(all(feature = "biome-oauth", feature = "diesel"))]
+pub use oauth::store::diesel::DieselOAuthUserStore;
+#[cfg(feature = "biome-oauth")]
+pub use oauth::store::memory::MemoryOAuthUserStore;
+
 #[cfg(all(feature = "biome-credentials", feature = "diesel"))]
 pub use refresh_tokens::store::diesel::DieselRefreshTokenStore;
 #[cfg(feature = "biome-credentials")]
 pub use refresh_tokens::store::memory::MemoryRefreshTokenStore;
 
+#[cfg(all(feature = "biome-roles", feature = "diesel"))]
+pub use roles::store::diesel::DieselRoleStore;
+#[cfg(feature = "biome-roles")]
+pub use roles::store::memory::MemoryRoleStore;
+
 #[cfg(feature = "diesel")]
 pub use user::store::diesel::DieselUserStore;
 pub use user::store::memory::MemoryUserStore;
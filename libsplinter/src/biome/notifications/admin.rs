@@ -0,0 +1,156 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use std::collections::BTreeSet;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+
+use crate::admin::service::messages::AdminServiceEvent;
+use crate::admin::service::{AdminServiceEventSubscriber, AdminSubscriberError};
+use crate::biome::key_management::store::KeyStore;
+use crate::hex::to_hex;
+
+use super::{Notification, NotificationManager};
+
+/// Creates notifications for the biome users whose keys were used to submit or vote on circuit
+/// proposals.
+///
+/// The notifier is subscribed to the admin service's events. Events are handled on a separate
+/// thread, so the admin service is not blocked while the notifications are stored; the thread
+/// exits once the notifier is dropped by the admin service.
+pub struct AdminEventNotifier {
+    sender: Mutex<Sender<(AdminServiceEvent, SystemTime)>>,
+}
+
+impl AdminEventNotifier {
+    /// Creates a new AdminEventNotifier and starts the thread that handles its events
+    ///
+    /// # Arguments
+    ///
+    /// * `manager`: The manager used to save and send the notifications
+    /// * `key_store`: The key store used to find the users that own a public key
+    ///
+    pub fn new(
+        manager: NotificationManager,
+        key_store: Arc<dyn KeyStore>,
+    ) -> Result<Self, std::io::Error> {
+        let (sender, receiver) = channel::<(AdminServiceEvent, SystemTime)>();
+
+        thread::Builder::new()
+            .name("BiomeAdminEventNotifier".into())
+            .spawn(move || {
+                for (event, timestamp) in receiver {
+                    if let Err(err) = notify(&manager, &*key_store, &event, timestamp) {
+                        error!("Unable to create notification for admin event: {}", err);
+                    }
+                }
+            })?;
+
+        Ok(AdminEventNotifier {
+            sender: Mutex::new(sender),
+        })
+    }
+}
+
+impl AdminServiceEventSubscriber for AdminEventNotifier {
+    fn handle_event(
+        &self,
+        event: &AdminServiceEvent,
+        timestamp: &SystemTime,
+    ) -> Result<(), AdminSubscriberError> {
+        self.sender
+            .lock()
+            .map_err(|_| {
+                AdminSubscriberError::UnableToHandleEvent(
+                    "notifier sender lock poisoned".to_string(),
+                )
+            })?
+            .send((event.clone(), *timestamp))
+            .map_err(|_| AdminSubscriberError::Unsubscribe)
+    }
+}
+
+fn notify(
+    manager: &NotificationManager,
+    key_store: &dyn KeyStore,
+    event: &AdminServiceEvent,
+    timestamp: SystemTime,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let proposal = event.proposal();
+
+    let public_keys = std::iter::once(to_hex(&proposal.requester))
+        .chain(proposal.votes.iter().map(|vote| to_hex(&vote.public_key)))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let recipients = key_store
+        .list_keys_with_public_keys(&public_keys)?
+        .into_iter()
+        .map(|key| key.user_id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    if recipients.is_empty() {
+        return Ok(());
+    }
+
+    let circuit_id = &proposal.circuit_id;
+    let (event_type, title, body) = match event {
+        AdminServiceEvent::ProposalSubmitted(_) => (
+            "proposal_submitted",
+            "Circuit proposal submitted",
+            format!("A proposal for circuit {} was submitted", circuit_id),
+        ),
+        AdminServiceEvent::ProposalVote(_) => (
+            "proposal_vote",
+            "Circuit proposal vote",
+            format!("A vote was cast on the proposal for circuit {}", circuit_id),
+        ),
+        AdminServiceEvent::ProposalAccepted(_) => (
+            "proposal_accepted",
+            "Circuit proposal accepted",
+            format!("The proposal for circuit {} was accepted", circuit_id),
+        ),
+        AdminServiceEvent::ProposalRejected(_) => (
+            "proposal_rejected",
+            "Circuit proposal rejected",
+            format!("The proposal for circuit {} was rejected", circuit_id),
+        ),
+        AdminServiceEvent::CircuitReady(_) => (
+            "circuit_ready",
+            "Circuit ready",
+            format!("Circuit {} is ready", circuit_id),
+        ),
+    };
+
+    let mut notification = Notification::new(title, &body, recipients)
+        .with_property("event_type", event_type)
+        .with_property("circuit_id", circuit_id)
+        .with_property(
+            "circuit_management_type",
+            &proposal.circuit.circuit_management_type,
+        );
+    notification.created = timestamp;
+
+    manager.add_notification(notification)?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use std::error::Error;
+use std::fmt;
+
+/// Returned by a `NotificationSubscriber` that could not handle a notification
+#[derive(Debug)]
+pub enum NotificationSubscriberError {
+    /// The subscriber failed to handle the notification, but should remain subscribed
+    UnableToHandleNotification(String),
+    /// The subscriber should be removed, for example because its websocket was closed
+    Unsubscribe,
+}
+
+impl Error for NotificationSubscriberError {}
+
+impl fmt::Display for NotificationSubscriberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotificationSubscriberError::UnableToHandleNotification(msg) => {
+                write!(f, "unable to handle notification: {}", msg)
+            }
+            NotificationSubscriberError::Unsubscribe => f.write_str("subscriber has unsubscribed"),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::store::{NotificationStore, NotificationStoreError};
+use super::{Notification, NotificationSubscriberError};
+
+/// Receives the notifications sent to a user
+pub trait NotificationSubscriber: Send {
+    fn handle_notification(
+        &self,
+        notification: &Notification,
+    ) -> Result<(), NotificationSubscriberError>;
+}
+
+type SubscriberMap = HashMap<String, Vec<Box<dyn NotificationSubscriber>>>;
+
+/// Stores new notifications and pushes them to the subscribers of their recipients
+#[derive(Clone)]
+pub struct NotificationManager {
+    store: Arc<dyn NotificationStore>,
+    subscribers: Arc<Mutex<SubscriberMap>>,
+}
+
+impl NotificationManager {
+    /// Creates a new NotificationManager
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: The store in which notifications are saved
+    ///
+    pub fn new(store: Arc<dyn NotificationStore>) -> Self {
+        NotificationManager {
+            store,
+            subscribers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the underlying notification store
+    pub fn store(&self) -> Arc<dyn NotificationStore> {
+        self.store.clone()
+    }
+
+    /// Saves a notification and sends it to the subscribers of each of its recipients
+    pub fn add_notification(
+        &self,
+        notification: Notification,
+    ) -> Result<(), NotificationStoreError> {
+        self.store.add_notification(notification.clone())?;
+
+        let mut subscribers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(_) => {
+                error!("Unable to notify subscribers: mutex lock poisoned");
+                return Ok(());
+            }
+        };
+        for recipient in &notification.recipients {
+            if let Some(user_subscribers) = subscribers.get_mut(recipient) {
+                user_subscribers.retain(|subscriber| {
+                    match subscriber.handle_notification(&notification) {
+                        Ok(()) => true,
+                        Err(NotificationSubscriberError::Unsubscribe) => false,
+                        Err(err) => {
+                            error!("Unable to send notification: {}", err);
+                            true
+                        }
+                    }
+                });
+            }
+        }
+        subscribers.retain(|_, user_subscribers| !user_subscribers.is_empty());
+
+        Ok(())
+    }
+
+    /// Adds a subscriber that receives all new notifications sent to the given user
+    pub fn add_subscriber(&self, user_id: &str, subscriber: Box<dyn NotificationSubscriber>) {
+        match self.subscribers.lock() {
+            Ok(mut subscribers) => subscribers
+                .entry(user_id.to_string())
+                .or_insert_with(Vec::new)
+                .push(subscriber),
+            Err(_) => error!("Unable to add notification subscriber: mutex lock poisoned"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc::{channel, Sender};
+
+    use crate::biome::notifications::store::memory::MemoryNotificationStore;
+
+    struct ChannelSubscriber(Mutex<Sender<String>>);
+
+    impl NotificationSubscriber for ChannelSubscriber {
+        fn handle_notification(
+            &self,
+            notification: &Notification,
+        ) -> Result<(), NotificationSubscriberError> {
+            self.0
+                .lock()
+                .expect("lock poisoned")
+                .send(notification.id.clone())
+                .map_err(|_| NotificationSubscriberError::Unsubscribe)
+        }
+    }
+
+    /// Verify that a new notification is stored for each recipient, that it is pushed only to the
+    /// subscribers of its recipients, and that subscribers that have gone away are removed.
+    #[test]
+    fn add_notification() {
+        let store = Arc::new(MemoryNotificationStore::new());
+        let manager = NotificationManager::new(store.clone());
+
+        let (alice_tx, alice_rx) = channel();
+        manager.add_subscriber("alice", Box::new(ChannelSubscriber(Mutex::new(alice_tx))));
+        let (bob_tx, bob_rx) = channel();
+        manager.add_subscriber("bob", Box::new(ChannelSubscriber(Mutex::new(bob_tx))));
+        drop(bob_rx);
+
+        let notification = Notification::new("title", "body", vec!["alice".into(), "bob".into()]);
+        manager
+            .add_notification(notification.clone())
+            .expect("failed to add notification");
+
+        assert_eq!(
+            alice_rx.try_recv().expect("no notification"),
+            notification.id
+        );
+        assert!(!manager
+            .subscribers
+            .lock()
+            .expect("lock poisoned")
+            .contains_key("bob"));
+
+        let bobs = store
+            .list_user_notifications("bob", true)
+            .expect("failed to list notifications");
+        assert_eq!(bobs.len(), 1);
+        assert_eq!(bobs[0].notification, notification);
+    }
+}
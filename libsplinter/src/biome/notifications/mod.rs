@@ -16,5 +16,67 @@
  */
 
 //! Provides an API for notifications.
+//!
+//! Notifications are stored with a `NotificationStore` and delivered to their recipients through a
+//! `NotificationManager`, which also pushes each new notification to the subscribers registered
+//! for its recipients, such as websocket connections.
 
+#[cfg(feature = "biome-key-management")]
+mod admin;
+mod error;
+mod manager;
 pub mod store;
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+#[cfg(feature = "biome-key-management")]
+pub use admin::AdminEventNotifier;
+pub use error::NotificationSubscriberError;
+pub use manager::{NotificationManager, NotificationSubscriber};
+
+/// Represents a notification sent to one or more biome users
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    pub id: String,
+    pub payload_title: String,
+    pub payload_body: String,
+    pub created: SystemTime,
+    pub recipients: Vec<String>,
+    pub properties: BTreeMap<String, String>,
+}
+
+impl Notification {
+    /// Creates a new Notification with a random ID and the current time as its creation time
+    ///
+    /// # Arguments
+    ///
+    /// * `payload_title`: The title of the notification
+    /// * `payload_body`: The body of the notification
+    /// * `recipients`: The IDs of the users that receive the notification
+    ///
+    pub fn new(payload_title: &str, payload_body: &str, recipients: Vec<String>) -> Self {
+        Notification {
+            id: uuid::Uuid::new_v4().to_string(),
+            payload_title: payload_title.to_string(),
+            payload_body: payload_body.to_string(),
+            created: SystemTime::now(),
+            recipients,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a property, such as the ID of the circuit the notification refers to
+    pub fn with_property(mut self, property: &str, value: &str) -> Self {
+        self.properties
+            .insert(property.to_string(), value.to_string());
+        self
+    }
+}
+
+/// A notification as received by a single user
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserNotification {
+    pub notification: Notification,
+    pub unread: bool,
+}
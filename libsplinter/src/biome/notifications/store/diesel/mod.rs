@@ -16,4 +16,145 @@
  */
 
 pub(in crate::biome) mod models;
-mod schema;
+mod operations;
+pub(in crate::biome) mod schema;
+
+use std::collections::BTreeMap;
+#[cfg(feature = "sqlite")]
+use std::convert::TryFrom;
+
+use crate::biome::notifications::{
+    store::{NotificationStore, NotificationStoreError},
+    Notification, UserNotification,
+};
+use crate::database::ConnectionPool;
+#[cfg(feature = "postgres")]
+use models::NotificationModel;
+#[cfg(feature = "sqlite")]
+use models::SqliteNotificationModel;
+use operations::{
+    add_notification::NotificationStoreAddNotificationOperation,
+    fetch_user_notification::NotificationStoreFetchUserNotificationOperation,
+    list_user_notifications::NotificationStoreListUserNotificationsOperation,
+    mark_all_read::NotificationStoreMarkAllReadOperation,
+    remove_user_notification::NotificationStoreRemoveUserNotificationOperation,
+    update_user_notification::NotificationStoreUpdateUserNotificationOperation,
+    NotificationStoreOperations,
+};
+
+/// Manages creating, updating and fetching notifications from the database
+pub struct DieselNotificationStore {
+    connection_pool: ConnectionPool,
+}
+
+impl DieselNotificationStore {
+    /// Creates a new DieselNotificationStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: ConnectionPool) -> Self {
+        Self { connection_pool }
+    }
+}
+
+impl NotificationStore for DieselNotificationStore {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            NotificationStoreOperations::new(conn).add_notification(notification)
+        })
+    }
+
+    fn list_user_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            NotificationStoreOperations::new(conn).list_user_notifications(user_id, unread_only)
+        })
+    }
+
+    fn fetch_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            NotificationStoreOperations::new(conn).fetch_user_notification(user_id, notification_id)
+        })
+    }
+
+    fn update_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+        unread: bool,
+    ) -> Result<(), NotificationStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            NotificationStoreOperations::new(conn).update_user_notification(
+                user_id,
+                notification_id,
+                unread,
+            )
+        })
+    }
+
+    fn mark_all_read(&self, user_id: &str) -> Result<(), NotificationStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            NotificationStoreOperations::new(conn).mark_all_read(user_id)
+        })
+    }
+
+    fn remove_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            NotificationStoreOperations::new(conn)
+                .remove_user_notification(user_id, notification_id)
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<NotificationModel> for Notification {
+    fn from(notification: NotificationModel) -> Self {
+        Notification {
+            id: notification.id,
+            payload_title: notification.payload_title,
+            payload_body: notification.payload_body,
+            created: notification.created.into(),
+            recipients: notification.recipients,
+            properties: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl TryFrom<SqliteNotificationModel> for Notification {
+    type Error = NotificationStoreError;
+
+    fn try_from(notification: SqliteNotificationModel) -> Result<Self, Self::Error> {
+        let recipients = serde_json::from_str(&notification.recipients).map_err(|err| {
+            NotificationStoreError::StorageError {
+                context: format!(
+                    "Failed to decode the recipients of notification {}",
+                    notification.id
+                ),
+                source: Some(Box::new(err)),
+            }
+        })?;
+
+        Ok(Notification {
+            id: notification.id,
+            payload_title: notification.payload_title,
+            payload_body: notification.payload_body,
+            created: notification.created.into(),
+            recipients,
+            properties: BTreeMap::new(),
+        })
+    }
+}
@@ -15,33 +15,56 @@
  * -----------------------------------------------------------------------------
  */
 
-use std::time::SystemTime;
-
-use super::schema::{notification_properties, notifications, user_notifications};
+#[cfg(feature = "postgres")]
+use super::schema::notifications;
+#[cfg(feature = "sqlite")]
+use super::schema::sqlite::notifications as sqlite_notifications;
+use super::schema::{notification_properties, user_notifications};
+use crate::database::DbTimestamp;
 
+#[cfg(feature = "postgres")]
 #[derive(Insertable, Queryable)]
 #[table_name = "notifications"]
-pub struct Notification {
+pub struct NotificationModel {
     pub id: String,
     pub payload_title: String,
     pub payload_body: String,
-    pub created: SystemTime,
+    pub created: DbTimestamp,
     pub recipients: Vec<String>,
 }
 
+/// A notification as it is stored in SQLite, with the recipients encoded as a JSON array
+#[cfg(feature = "sqlite")]
+#[derive(Insertable, Queryable)]
+#[table_name = "sqlite_notifications"]
+pub struct SqliteNotificationModel {
+    pub id: String,
+    pub payload_title: String,
+    pub payload_body: String,
+    pub created: DbTimestamp,
+    pub recipients: String,
+}
+
 #[derive(Insertable, Queryable)]
 #[table_name = "user_notifications"]
-pub struct UserNotification {
+pub struct UserNotificationModel {
     pub notification_id: String,
     pub user_id: String,
     pub unread: bool,
 }
 
-#[derive(Insertable, Queryable)]
-#[table_name = "notification_properties"]
-pub struct NotificationProperty {
+#[derive(Queryable)]
+pub struct NotificationPropertyModel {
     pub id: i64,
     pub notification_id: String,
     pub property: String,
     pub property_value: String,
 }
+
+#[derive(Insertable)]
+#[table_name = "notification_properties"]
+pub struct NewNotificationPropertyModel {
+    pub notification_id: String,
+    pub property: String,
+    pub property_value: String,
+}
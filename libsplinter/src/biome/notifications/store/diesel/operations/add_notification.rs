@@ -0,0 +1,148 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use super::NotificationStoreOperations;
+#[cfg(feature = "postgres")]
+use crate::biome::notifications::store::diesel::{
+    models::NotificationModel, schema::notifications,
+};
+#[cfg(feature = "sqlite")]
+use crate::biome::notifications::store::diesel::{
+    models::SqliteNotificationModel, schema::sqlite::notifications as sqlite_notifications,
+};
+use crate::biome::notifications::store::diesel::{
+    models::{NewNotificationPropertyModel, UserNotificationModel},
+    schema::{notification_properties, user_notifications},
+};
+use crate::biome::notifications::{store::NotificationStoreError, Notification};
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::notifications) trait NotificationStoreAddNotificationOperation {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError>;
+}
+
+/// Inserts the row of the notification itself, whose recipients are stored differently by each
+/// backend
+trait InsertNotificationRow {
+    fn insert_notification_row(&self, notification: &Notification) -> Result<(), QueryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> InsertNotificationRow for NotificationStoreOperations<'a, diesel::pg::PgConnection> {
+    fn insert_notification_row(&self, notification: &Notification) -> Result<(), QueryError> {
+        insert_into(notifications::table)
+            .values(NotificationModel {
+                id: notification.id.clone(),
+                payload_title: notification.payload_title.clone(),
+                payload_body: notification.payload_body.clone(),
+                created: notification.created.into(),
+                recipients: notification.recipients.clone(),
+            })
+            .execute(self.conn)
+            .map(|_| ())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> InsertNotificationRow
+    for NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn insert_notification_row(&self, notification: &Notification) -> Result<(), QueryError> {
+        let recipients = serde_json::to_string(&notification.recipients)
+            .map_err(|err| QueryError::SerializationError(Box::new(err)))?;
+        insert_into(sqlite_notifications::table)
+            .values(SqliteNotificationModel {
+                id: notification.id.clone(),
+                payload_title: notification.payload_title.clone(),
+                payload_body: notification.payload_body.clone(),
+                created: notification.created.into(),
+                recipients,
+            })
+            .execute(self.conn)
+            .map(|_| ())
+    }
+}
+
+impl_for_diesel_backends! {
+    NotificationStoreAddNotificationOperation for NotificationStoreOperations {
+        fn add_notification(
+            &self,
+            notification: Notification,
+        ) -> Result<(), NotificationStoreError> {
+            let properties = notification
+                .properties
+                .iter()
+                .map(|(property, value)| NewNotificationPropertyModel {
+                    notification_id: notification.id.clone(),
+                    property: property.clone(),
+                    property_value: value.clone(),
+                })
+                .collect::<Vec<_>>();
+            let user_notifications = notification
+                .recipients
+                .iter()
+                .map(|user_id| UserNotificationModel {
+                    notification_id: notification.id.clone(),
+                    user_id: user_id.clone(),
+                    unread: true,
+                })
+                .collect::<Vec<_>>();
+
+            self.conn
+                .transaction::<(), _, _>(|| {
+                    self.insert_notification_row(&notification)?;
+                    // Rows are inserted one at a time, since SQLite does not support inserting
+                    // multiple rows with a single statement
+                    for property in properties {
+                        insert_into(notification_properties::table)
+                            .values(property)
+                            .execute(self.conn)?;
+                    }
+                    for user_notification in user_notifications {
+                        insert_into(user_notifications::table)
+                            .values(user_notification)
+                            .execute(self.conn)?;
+                    }
+                    Ok(())
+                })
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        NotificationStoreError::DuplicateNotificationError(format!(
+                            "Notification {} is already in database",
+                            notification.id
+                        ))
+                    }
+                    QueryError::SerializationError(err) => NotificationStoreError::StorageError {
+                        context: format!(
+                            "Failed to encode the recipients of notification {}",
+                            notification.id
+                        ),
+                        source: Some(err),
+                    },
+                    _ => NotificationStoreError::OperationError {
+                        context: "Failed to add notification".to_string(),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
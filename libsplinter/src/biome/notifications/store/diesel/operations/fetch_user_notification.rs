@@ -0,0 +1,122 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+#[cfg(feature = "sqlite")]
+use std::convert::TryFrom;
+
+use super::NotificationStoreOperations;
+use crate::biome::notifications::store::diesel::schema::user_notifications;
+#[cfg(feature = "postgres")]
+use crate::biome::notifications::store::diesel::{
+    models::NotificationModel, schema::notifications,
+};
+#[cfg(feature = "sqlite")]
+use crate::biome::notifications::store::diesel::{
+    models::SqliteNotificationModel, schema::sqlite::notifications as sqlite_notifications,
+};
+use crate::biome::notifications::{store::NotificationStoreError, Notification, UserNotification};
+
+use diesel::prelude::*;
+
+pub(in crate::biome::notifications) trait NotificationStoreFetchUserNotificationOperation {
+    fn fetch_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> NotificationStoreFetchUserNotificationOperation
+    for NotificationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn fetch_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        let (notification, unread) = user_notifications::table
+            .inner_join(notifications::table)
+            .filter(user_notifications::user_id.eq(user_id))
+            .filter(user_notifications::notification_id.eq(notification_id))
+            .select((notifications::all_columns, user_notifications::unread))
+            .first::<(NotificationModel, bool)>(self.conn)
+            .optional()
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: format!(
+                    "Failed to fetch notification {} for user {}",
+                    notification_id, user_id
+                ),
+                source: Box::new(err),
+            })?
+            .ok_or_else(|| {
+                NotificationStoreError::NotFoundError(format!(
+                    "Notification {} not found for user {}",
+                    notification_id, user_id
+                ))
+            })?;
+
+        self.load_user_notifications(vec![(Notification::from(notification), unread)])?
+            .pop()
+            .ok_or_else(|| NotificationStoreError::StorageError {
+                context: "Notification was lost while loading its properties".to_string(),
+                source: None,
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> NotificationStoreFetchUserNotificationOperation
+    for NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn fetch_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        let (notification, unread) = user_notifications::table
+            .inner_join(sqlite_notifications::table)
+            .filter(user_notifications::user_id.eq(user_id))
+            .filter(user_notifications::notification_id.eq(notification_id))
+            .select((
+                sqlite_notifications::all_columns,
+                user_notifications::unread,
+            ))
+            .first::<(SqliteNotificationModel, bool)>(self.conn)
+            .optional()
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: format!(
+                    "Failed to fetch notification {} for user {}",
+                    notification_id, user_id
+                ),
+                source: Box::new(err),
+            })?
+            .ok_or_else(|| {
+                NotificationStoreError::NotFoundError(format!(
+                    "Notification {} not found for user {}",
+                    notification_id, user_id
+                ))
+            })?;
+
+        self.load_user_notifications(vec![(Notification::try_from(notification)?, unread)])?
+            .pop()
+            .ok_or_else(|| NotificationStoreError::StorageError {
+                context: "Notification was lost while loading its properties".to_string(),
+                source: None,
+            })
+    }
+}
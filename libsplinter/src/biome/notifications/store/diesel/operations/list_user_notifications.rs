@@ -0,0 +1,112 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+#[cfg(feature = "sqlite")]
+use std::convert::TryFrom;
+
+use super::NotificationStoreOperations;
+use crate::biome::notifications::store::diesel::schema::user_notifications;
+#[cfg(feature = "postgres")]
+use crate::biome::notifications::store::diesel::{
+    models::NotificationModel, schema::notifications,
+};
+#[cfg(feature = "sqlite")]
+use crate::biome::notifications::store::diesel::{
+    models::SqliteNotificationModel, schema::sqlite::notifications as sqlite_notifications,
+};
+use crate::biome::notifications::{store::NotificationStoreError, Notification, UserNotification};
+
+use diesel::prelude::*;
+
+pub(in crate::biome::notifications) trait NotificationStoreListUserNotificationsOperation {
+    fn list_user_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> NotificationStoreListUserNotificationsOperation
+    for NotificationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_user_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        let mut query = user_notifications::table
+            .inner_join(notifications::table)
+            .filter(user_notifications::user_id.eq(user_id))
+            .into_boxed();
+        if unread_only {
+            query = query.filter(user_notifications::unread.eq(true));
+        }
+
+        let rows = query
+            .order(notifications::created.desc())
+            .select((notifications::all_columns, user_notifications::unread))
+            .load::<(NotificationModel, bool)>(self.conn)
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: format!("Failed to list notifications for user {}", user_id),
+                source: Box::new(err),
+            })?
+            .into_iter()
+            .map(|(notification, unread)| (Notification::from(notification), unread))
+            .collect();
+
+        self.load_user_notifications(rows)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> NotificationStoreListUserNotificationsOperation
+    for NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_user_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        let mut query = user_notifications::table
+            .inner_join(sqlite_notifications::table)
+            .filter(user_notifications::user_id.eq(user_id))
+            .into_boxed();
+        if unread_only {
+            query = query.filter(user_notifications::unread.eq(true));
+        }
+
+        let rows = query
+            .order(sqlite_notifications::created.desc())
+            .select((
+                sqlite_notifications::all_columns,
+                user_notifications::unread,
+            ))
+            .load::<(SqliteNotificationModel, bool)>(self.conn)
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: format!("Failed to list notifications for user {}", user_id),
+                source: Box::new(err),
+            })?
+            .into_iter()
+            .map(|(notification, unread)| {
+                Notification::try_from(notification).map(|notification| (notification, unread))
+            })
+            .collect::<Result<_, NotificationStoreError>>()?;
+
+        self.load_user_notifications(rows)
+    }
+}
@@ -0,0 +1,41 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use super::NotificationStoreOperations;
+use crate::biome::notifications::store::diesel::schema::user_notifications;
+use crate::biome::notifications::store::NotificationStoreError;
+
+use diesel::{dsl::update, prelude::*};
+
+pub(in crate::biome::notifications) trait NotificationStoreMarkAllReadOperation {
+    fn mark_all_read(&self, user_id: &str) -> Result<(), NotificationStoreError>;
+}
+
+impl_for_diesel_backends! {
+    NotificationStoreMarkAllReadOperation for NotificationStoreOperations {
+        fn mark_all_read(&self, user_id: &str) -> Result<(), NotificationStoreError> {
+            update(user_notifications::table.filter(user_notifications::user_id.eq(user_id)))
+                .set(user_notifications::unread.eq(false))
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(|err| NotificationStoreError::OperationError {
+                    context: format!("Failed to mark notifications read for user {}", user_id),
+                    source: Box::new(err),
+                })
+        }
+    }
+}
@@ -0,0 +1,119 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+pub(super) mod add_notification;
+pub(super) mod fetch_user_notification;
+pub(super) mod list_user_notifications;
+pub(super) mod mark_all_read;
+pub(super) mod remove_user_notification;
+pub(super) mod update_user_notification;
+
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+
+use crate::biome::notifications::store::diesel::{
+    models::NotificationPropertyModel, schema::notification_properties,
+};
+use crate::biome::notifications::{store::NotificationStoreError, Notification, UserNotification};
+
+pub(super) struct NotificationStoreOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C> NotificationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    pub fn new(conn: &'a C) -> Self {
+        NotificationStoreOperations { conn }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> NotificationStoreOperations<'a, diesel::pg::PgConnection> {
+    /// Loads the properties of the given notifications and combines them into user notifications
+    fn load_user_notifications(
+        &self,
+        rows: Vec<(Notification, bool)>,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        let ids = rows
+            .iter()
+            .map(|(notification, _)| notification.id.clone())
+            .collect::<Vec<_>>();
+
+        let properties = notification_properties::table
+            .filter(notification_properties::notification_id.eq_any(ids))
+            .load::<NotificationPropertyModel>(self.conn)
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: "Failed to load notification properties".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(combine_properties(rows, properties))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection> {
+    /// Loads the properties of the given notifications and combines them into user notifications
+    fn load_user_notifications(
+        &self,
+        rows: Vec<(Notification, bool)>,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        let ids = rows
+            .iter()
+            .map(|(notification, _)| notification.id.clone())
+            .collect::<Vec<_>>();
+
+        let properties = notification_properties::table
+            .filter(notification_properties::notification_id.eq_any(ids))
+            .load::<NotificationPropertyModel>(self.conn)
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: "Failed to load notification properties".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(combine_properties(rows, properties))
+    }
+}
+
+/// Adds the loaded properties to their notifications
+fn combine_properties(
+    rows: Vec<(Notification, bool)>,
+    properties: Vec<NotificationPropertyModel>,
+) -> Vec<UserNotification> {
+    let mut properties = properties.into_iter().fold(
+        BTreeMap::<String, BTreeMap<String, String>>::new(),
+        |mut acc, property| {
+            acc.entry(property.notification_id)
+                .or_insert_with(BTreeMap::new)
+                .insert(property.property, property.property_value);
+            acc
+        },
+    );
+
+    rows.into_iter()
+        .map(|(mut notification, unread)| {
+            notification.properties = properties.remove(&notification.id).unwrap_or_default();
+            UserNotification {
+                notification,
+                unread,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,137 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use super::NotificationStoreOperations;
+#[cfg(feature = "postgres")]
+use crate::biome::notifications::store::diesel::schema::notifications;
+#[cfg(feature = "sqlite")]
+use crate::biome::notifications::store::diesel::schema::sqlite::notifications as sqlite_notifications;
+use crate::biome::notifications::store::diesel::schema::user_notifications;
+use crate::biome::notifications::store::NotificationStoreError;
+
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::notifications) trait NotificationStoreRemoveUserNotificationOperation {
+    fn remove_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> NotificationStoreRemoveUserNotificationOperation
+    for NotificationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn remove_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let removed = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let removed = delete(
+                    user_notifications::table
+                        .filter(user_notifications::user_id.eq(user_id))
+                        .filter(user_notifications::notification_id.eq(notification_id)),
+                )
+                .execute(self.conn)?;
+
+                // Remove the notification itself once no user has it
+                let remaining = user_notifications::table
+                    .filter(user_notifications::notification_id.eq(notification_id))
+                    .count()
+                    .get_result::<i64>(self.conn)?;
+                if remaining == 0 {
+                    delete(notifications::table.filter(notifications::id.eq(notification_id)))
+                        .execute(self.conn)?;
+                }
+
+                Ok(removed)
+            })
+            .map_err(|err| NotificationStoreError::OperationError {
+                context: format!(
+                    "Failed to remove notification {} for user {}",
+                    notification_id, user_id
+                ),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(NotificationStoreError::NotFoundError(format!(
+                "Notification {} not found for user {}",
+                notification_id, user_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> NotificationStoreRemoveUserNotificationOperation
+    for NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn remove_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let removed = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let removed = delete(
+                    user_notifications::table
+                        .filter(user_notifications::user_id.eq(user_id))
+                        .filter(user_notifications::notification_id.eq(notification_id)),
+                )
+                .execute(self.conn)?;
+
+                // Remove the notification itself once no user has it
+                let remaining = user_notifications::table
+                    .filter(user_notifications::notification_id.eq(notification_id))
+                    .count()
+                    .get_result::<i64>(self.conn)?;
+                if remaining == 0 {
+                    delete(
+                        sqlite_notifications::table
+                            .filter(sqlite_notifications::id.eq(notification_id)),
+                    )
+                    .execute(self.conn)?;
+                }
+
+                Ok(removed)
+            })
+            .map_err(|err| NotificationStoreError::OperationError {
+                context: format!(
+                    "Failed to remove notification {} for user {}",
+                    notification_id, user_id
+                ),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(NotificationStoreError::NotFoundError(format!(
+                "Notification {} not found for user {}",
+                notification_id, user_id
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+/*
+ * Copyright 2018-2020 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use super::NotificationStoreOperations;
+use crate::biome::notifications::store::diesel::schema::user_notifications;
+use crate::biome::notifications::store::NotificationStoreError;
+
+use diesel::{dsl::update, prelude::*};
+
+pub(in crate::biome::notifications) trait NotificationStoreUpdateUserNotificationOperation {
+    fn update_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+        unread: bool,
+    ) -> Result<(), NotificationStoreError>;
+}
+
+impl_for_diesel_backends! {
+    NotificationStoreUpdateUserNotificationOperation for NotificationStoreOperations {
+        fn update_user_notification(
+            &self,
+            user_id: &str,
+            notification_id: &str,
+            unread: bool,
+        ) -> Result<(), NotificationStoreError> {
+            let updated = update(
+                user_notifications::table
+                    .filter(user_notifications::user_id.eq(user_id))
+                    .filter(user_notifications::notification_id.eq(notification_id)),
+            )
+            .set(user_notifications::unread.eq(unread))
+            .execute(self.conn)
+            .map_err(|err| NotificationStoreError::OperationError {
+                context: format!(
+                    "Failed to update notification {} for user {}",
+                    notification_id, user_id
+                ),
+                source: Box::new(err),
+            })?;
+
+            if updated == 0 {
+                return Err(NotificationStoreError::NotFoundError(format!(
+                    "Notification {} not found for user {}",
+                    notification_id, user_id
+                )));
+            }
+
+            Ok(())
+        }
+    }
+}
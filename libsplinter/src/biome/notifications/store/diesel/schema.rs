@@ -14,6 +14,7 @@
  * limitations under the License.
  * -----------------------------------------------------------------------------
  */
+#[cfg(feature = "postgres")]
 table! {
     notifications (id) {
         id -> Text,
@@ -25,7 +26,7 @@ table! {
 }
 
 table! {
-    user_notifications (notification_id) {
+    user_notifications (notification_id, user_id) {
         notification_id -> Text,
         user_id -> Text,
         unread -> Bool,
@@ -41,7 +42,35 @@ table! {
     }
 }
 
+#[cfg(feature = "postgres")]
 joinable!(user_notifications -> notifications (notification_id));
+#[cfg(feature = "postgres")]
 joinable!(notification_properties -> notifications (notification_id));
 
+#[cfg(feature = "postgres")]
 allow_tables_to_appear_in_same_query!(notifications, user_notifications, notification_properties);
+#[cfg(not(feature = "postgres"))]
+allow_tables_to_appear_in_same_query!(user_notifications, notification_properties);
+
+/// The notifications table as it is stored in SQLite, which has no array type; the recipients are
+/// stored as a JSON array instead.
+#[cfg(feature = "sqlite")]
+pub mod sqlite {
+    use super::{notification_properties, user_notifications};
+
+    table! {
+        notifications (id) {
+            id -> Text,
+            payload_title -> Text,
+            payload_body -> Text,
+            created -> Timestamp,
+            recipients -> Text,
+        }
+    }
+
+    joinable!(user_notifications -> notifications (notification_id));
+    joinable!(notification_properties -> notifications (notification_id));
+
+    allow_tables_to_appear_in_same_query!(notifications, user_notifications);
+    allow_tables_to_appear_in_same_query!(notifications, notification_properties);
+}
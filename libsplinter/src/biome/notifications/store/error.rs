@@ -0,0 +1,103 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "diesel")]
+use crate::database::error;
+
+/// Represents NotificationStore errors
+#[derive(Debug)]
+pub enum NotificationStoreError {
+    /// Represents CRUD operations failures
+    OperationError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents database query failures
+    QueryError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents general failures in the database
+    StorageError {
+        context: String,
+        source: Option<Box<dyn Error>>,
+    },
+    /// Represents an issue connecting to the database
+    ConnectionError(Box<dyn Error>),
+    /// Returned when a notification is not found for a user
+    NotFoundError(String),
+    /// Returned when a notification with the same ID is already in the database
+    DuplicateNotificationError(String),
+}
+
+impl Error for NotificationStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NotificationStoreError::OperationError { source, .. } => Some(&**source),
+            NotificationStoreError::QueryError { source, .. } => Some(&**source),
+            NotificationStoreError::StorageError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            NotificationStoreError::StorageError { source: None, .. } => None,
+            NotificationStoreError::ConnectionError(err) => Some(&**err),
+            NotificationStoreError::NotFoundError(_) => None,
+            NotificationStoreError::DuplicateNotificationError(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for NotificationStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotificationStoreError::OperationError { context, source } => {
+                write!(f, "failed to perform operation: {}: {}", context, source)
+            }
+            NotificationStoreError::QueryError { context, source } => {
+                write!(f, "failed query: {}: {}", context, source)
+            }
+            NotificationStoreError::StorageError {
+                context,
+                source: Some(source),
+            } => write!(
+                f,
+                "the underlying storage returned an error: {}: {}",
+                context, source
+            ),
+            NotificationStoreError::StorageError {
+                context,
+                source: None,
+            } => write!(f, "the underlying storage returned an error: {}", context),
+            NotificationStoreError::ConnectionError(ref s) => {
+                write!(f, "failed to connect to underlying storage: {}", s)
+            }
+            NotificationStoreError::NotFoundError(ref s) => {
+                write!(f, "notification not found: {}", s)
+            }
+            NotificationStoreError::DuplicateNotificationError(ref s) => {
+                write!(f, "notification already exists: {}", s)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<error::ConnectionError> for NotificationStoreError {
+    fn from(err: error::ConnectionError) -> NotificationStoreError {
+        NotificationStoreError::ConnectionError(Box::new(err))
+    }
+}
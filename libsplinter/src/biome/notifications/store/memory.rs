@@ -0,0 +1,225 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::biome::notifications::{
+    store::{NotificationStore, NotificationStoreError},
+    Notification, UserNotification,
+};
+
+#[derive(Default)]
+struct Inner {
+    /// Notifications, keyed by ID
+    notifications: BTreeMap<String, Notification>,
+    /// The unread flag of each notification received by each user, keyed by user ID and then by
+    /// notification ID
+    user_notifications: BTreeMap<String, BTreeMap<String, bool>>,
+}
+
+impl Inner {
+    fn user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        let unread = self
+            .user_notifications
+            .get(user_id)
+            .and_then(|notifications| notifications.get(notification_id))
+            .ok_or_else(|| not_found(user_id, notification_id))?;
+        let notification = self
+            .notifications
+            .get(notification_id)
+            .cloned()
+            .ok_or_else(|| not_found(user_id, notification_id))?;
+
+        Ok(UserNotification {
+            notification,
+            unread: *unread,
+        })
+    }
+}
+
+fn not_found(user_id: &str, notification_id: &str) -> NotificationStoreError {
+    NotificationStoreError::NotFoundError(format!(
+        "Notification {} not found for user {}",
+        notification_id, user_id
+    ))
+}
+
+#[derive(Default, Clone)]
+pub struct MemoryNotificationStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MemoryNotificationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<Inner>, NotificationStoreError> {
+        self.inner
+            .lock()
+            .map_err(|_| NotificationStoreError::StorageError {
+                context: "Cannot access notification store: mutex lock poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+impl NotificationStore for MemoryNotificationStore {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let mut inner = self.lock()?;
+        if inner.notifications.contains_key(&notification.id) {
+            return Err(NotificationStoreError::DuplicateNotificationError(format!(
+                "Notification {} is already in the store",
+                notification.id
+            )));
+        }
+        for recipient in &notification.recipients {
+            inner
+                .user_notifications
+                .entry(recipient.clone())
+                .or_insert_with(BTreeMap::new)
+                .insert(notification.id.clone(), true);
+        }
+        inner
+            .notifications
+            .insert(notification.id.clone(), notification);
+        Ok(())
+    }
+
+    fn list_user_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        let inner = self.lock()?;
+        let mut notifications = match inner.user_notifications.get(user_id) {
+            Some(user_notifications) => user_notifications
+                .iter()
+                .filter(|(_, unread)| !unread_only || **unread)
+                .map(|(id, _)| inner.user_notification(user_id, id))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![],
+        };
+        notifications.sort_by(|a, b| b.notification.created.cmp(&a.notification.created));
+        Ok(notifications)
+    }
+
+    fn fetch_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        self.lock()?.user_notification(user_id, notification_id)
+    }
+
+    fn update_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+        unread: bool,
+    ) -> Result<(), NotificationStoreError> {
+        let mut inner = self.lock()?;
+        let flag = inner
+            .user_notifications
+            .get_mut(user_id)
+            .and_then(|notifications| notifications.get_mut(notification_id))
+            .ok_or_else(|| not_found(user_id, notification_id))?;
+        *flag = unread;
+        Ok(())
+    }
+
+    fn mark_all_read(&self, user_id: &str) -> Result<(), NotificationStoreError> {
+        if let Some(notifications) = self.lock()?.user_notifications.get_mut(user_id) {
+            notifications
+                .values_mut()
+                .for_each(|unread| *unread = false);
+        }
+        Ok(())
+    }
+
+    fn remove_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let mut inner = self.lock()?;
+        inner
+            .user_notifications
+            .get_mut(user_id)
+            .and_then(|notifications| notifications.remove(notification_id))
+            .ok_or_else(|| not_found(user_id, notification_id))?;
+
+        let still_received = inner
+            .user_notifications
+            .values()
+            .any(|notifications| notifications.contains_key(notification_id));
+        if !still_received {
+            inner.notifications.remove(notification_id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a user's notifications can be marked as read and removed, and that removing a
+    /// notification for one recipient leaves it in place for the others.
+    #[test]
+    fn read_and_remove_notifications() {
+        let store = MemoryNotificationStore::new();
+        let notification = Notification::new("title", "body", vec!["alice".into(), "bob".into()]);
+        let id = notification.id.clone();
+        store
+            .add_notification(notification)
+            .expect("failed to add notification");
+
+        store
+            .update_user_notification("alice", &id, false)
+            .expect("failed to mark notification read");
+        assert!(store
+            .list_user_notifications("alice", true)
+            .expect("failed to list notifications")
+            .is_empty());
+        assert!(
+            store
+                .fetch_user_notification("bob", &id)
+                .expect("failed to fetch notification")
+                .unread
+        );
+
+        store.mark_all_read("bob").expect("failed to mark all read");
+        assert!(
+            !store
+                .fetch_user_notification("bob", &id)
+                .expect("failed to fetch notification")
+                .unread
+        );
+
+        store
+            .remove_user_notification("alice", &id)
+            .expect("failed to remove notification");
+        match store.fetch_user_notification("alice", &id) {
+            Err(NotificationStoreError::NotFoundError(_)) => (),
+            res => panic!("expected NotFoundError, got {:?}", res),
+        }
+        assert!(store.fetch_user_notification("bob", &id).is_ok());
+    }
+}
@@ -15,4 +15,78 @@
 //! Defines a basic representation of a notification.
 
 #[cfg(feature = "diesel")]
-pub(in crate::biome) mod diesel;
+pub(crate) mod diesel;
+mod error;
+pub(in crate::biome) mod memory;
+
+pub use error::NotificationStoreError;
+
+use super::{Notification, UserNotification};
+
+/// Defines methods for saving notifications and managing the notifications received by users
+pub trait NotificationStore: Send + Sync {
+    /// Adds a notification, which is received as unread by each of its recipients
+    ///
+    /// # Arguments
+    ///
+    ///   * `notification` - The notification to be added
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError>;
+
+    /// Lists the notifications received by a user, most recent first
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The ID of the user
+    ///   * `unread_only` - Whether to only list the notifications the user has not read
+    fn list_user_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError>;
+
+    /// Fetches a notification received by a user
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The ID of the user
+    ///   * `notification_id` - The ID of the notification
+    fn fetch_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError>;
+
+    /// Marks a notification received by a user as read or unread
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The ID of the user
+    ///   * `notification_id` - The ID of the notification
+    ///   * `unread` - Whether the notification is unread
+    fn update_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+        unread: bool,
+    ) -> Result<(), NotificationStoreError>;
+
+    /// Marks all of the notifications received by a user as read
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The ID of the user
+    fn mark_all_read(&self, user_id: &str) -> Result<(), NotificationStoreError>;
+
+    /// Removes a notification from a user's notifications. The notification itself is removed
+    /// once none of its recipients have it.
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The ID of the user
+    ///   * `notification_id` - The ID of the notification
+    fn remove_user_notification(
+        &self,
+        user_id: &str,
+        notification_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+}
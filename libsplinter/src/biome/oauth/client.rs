@@ -0,0 +1,669 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{Algorithm, Validation};
+use openssl::base64;
+use openssl::bn::BigNum;
+use openssl::rsa::Rsa;
+use reqwest::blocking::Client;
+use url::Url;
+
+use super::{OAuthConfig, OAuthError};
+
+/// The shortest time between fetches of the provider's signing keys, which are fetched again
+/// when an ID token is signed with a key that is not known
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The identity of a user who has signed in at an OpenID Connect provider
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenIdIdentity {
+    /// The issuer identifier of the provider
+    pub issuer: String,
+    /// The identifier of the user's account at the provider
+    pub subject: String,
+    /// The user's email address, if the provider shared it
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProviderMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct JwkSet {
+    keys: Vec<ProviderJwk>,
+}
+
+/// A signing key of the provider, in the JSON Web Key format
+#[derive(Clone, Deserialize)]
+struct ProviderJwk {
+    kty: String,
+    #[serde(default)]
+    kid: Option<String>,
+    #[serde(default)]
+    alg: Option<String>,
+    #[serde(default, rename = "use")]
+    key_use: Option<String>,
+    #[serde(default)]
+    n: Option<String>,
+    #[serde(default)]
+    e: Option<String>,
+    #[serde(default)]
+    crv: Option<String>,
+    #[serde(default)]
+    x: Option<String>,
+    #[serde(default)]
+    y: Option<String>,
+}
+
+impl ProviderJwk {
+    /// Returns the algorithm the key verifies signatures with, which is the key's `alg` if it
+    /// has one, or is implied by its type otherwise. Only RS256 and ES256 keys are supported.
+    fn algorithm(&self) -> Option<Algorithm> {
+        match (self.alg.as_deref(), self.kty.as_str(), self.crv.as_deref()) {
+            (Some("RS256"), "RSA", _) | (None, "RSA", _) => Some(Algorithm::RS256),
+            (Some("ES256"), "EC", Some("P-256")) | (None, "EC", Some("P-256")) => {
+                Some(Algorithm::ES256)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the key in the form expected by `jsonwebtoken::decode`
+    fn verification_key(&self) -> Result<Vec<u8>, OAuthError> {
+        match self.algorithm() {
+            Some(Algorithm::RS256) => {
+                let (n, e) = match (&self.n, &self.e) {
+                    (Some(n), Some(e)) => (decode_base64url(n)?, decode_base64url(e)?),
+                    _ => return Err(invalid_key("RSA key is missing its modulus or exponent")),
+                };
+                BigNum::from_slice(&n)
+                    .and_then(|n| Ok((n, BigNum::from_slice(&e)?)))
+                    .and_then(|(n, e)| Rsa::from_public_components(n, e))
+                    .and_then(|rsa| rsa.public_key_to_der_pkcs1())
+                    .map_err(|err| invalid_key(&err.to_string()))
+            }
+            Some(Algorithm::ES256) => {
+                let (x, y) = match (&self.x, &self.y) {
+                    (Some(x), Some(y)) => (decode_base64url(x)?, decode_base64url(y)?),
+                    _ => return Err(invalid_key("EC key is missing its coordinates")),
+                };
+                if x.len() != 32 || y.len() != 32 {
+                    return Err(invalid_key("EC key has invalid coordinates"));
+                }
+                // An uncompressed P-256 point is 0x04 followed by the x and y coordinates
+                let mut key = vec![4];
+                key.extend(x);
+                key.extend(y);
+                Ok(key)
+            }
+            _ => Err(invalid_key("unsupported key type")),
+        }
+    }
+}
+
+/// The provider's signing keys, as last fetched
+#[derive(Default)]
+struct SigningKeys {
+    keys: Vec<ProviderJwk>,
+    fetched: Option<Instant>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Audience::Single(aud) => aud == client_id,
+            Audience::Multiple(auds) => auds.iter().any(|aud| aud == client_id),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: Audience,
+    exp: u64,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+}
+
+/// A client for the authorization code flow of an OpenID Connect provider
+pub struct OpenIdClient {
+    config: OAuthConfig,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    signing_keys: Mutex<SigningKeys>,
+    http_client: Client,
+}
+
+impl OpenIdClient {
+    /// Creates a new OpenIdClient. Endpoints that are not set in the configuration are discovered
+    /// from the provider's `/.well-known/openid-configuration` document.
+    ///
+    /// The issuer and all of the provider's endpoints must be `https` URLs, unless the
+    /// configuration explicitly allows plain `http` with `OAuthConfig::with_insecure_http`.
+    pub fn new(config: OAuthConfig) -> Result<Self, OAuthError> {
+        let http_client = Client::new();
+
+        let insecure_http = config.insecure_http();
+        check_https("issuer", config.issuer(), insecure_http)?;
+
+        let (authorization_endpoint, token_endpoint, jwks_uri) = match (
+            config.authorization_endpoint(),
+            config.token_endpoint(),
+            config.jwks_uri(),
+        ) {
+            (Some(authorization_endpoint), Some(token_endpoint), Some(jwks_uri)) => (
+                authorization_endpoint.to_string(),
+                token_endpoint.to_string(),
+                jwks_uri.to_string(),
+            ),
+            (authorization_endpoint, token_endpoint, jwks_uri) => {
+                let metadata = discover(&http_client, config.issuer())?;
+                (
+                    authorization_endpoint
+                        .map(String::from)
+                        .unwrap_or(metadata.authorization_endpoint),
+                    token_endpoint
+                        .map(String::from)
+                        .unwrap_or(metadata.token_endpoint),
+                    jwks_uri.map(String::from).unwrap_or(metadata.jwks_uri),
+                )
+            }
+        };
+
+        check_https(
+            "authorization endpoint",
+            &authorization_endpoint,
+            insecure_http,
+        )?;
+        check_https("token endpoint", &token_endpoint, insecure_http)?;
+        check_https("JWKS URI", &jwks_uri, insecure_http)?;
+
+        Ok(OpenIdClient {
+            config,
+            authorization_endpoint,
+            token_endpoint,
+            jwks_uri,
+            signing_keys: Mutex::new(SigningKeys::default()),
+            http_client,
+        })
+    }
+
+    /// Returns the issuer identifier of the provider
+    pub fn issuer(&self) -> &str {
+        self.config.issuer()
+    }
+
+    /// Returns the URL of the provider's authorization endpoint that a user is sent to in order
+    /// to sign in
+    ///
+    /// # Arguments
+    ///
+    /// * `state`: An unguessable value that is returned to the callback, which ties the callback
+    ///   to this login attempt
+    /// * `nonce`: An unguessable value that the provider includes in the ID token
+    pub fn authorization_url(&self, state: &str, nonce: &str) -> Result<String, OAuthError> {
+        let mut url = Url::parse(&self.authorization_endpoint).map_err(|err| {
+            OAuthError::ConfigError(format!("invalid authorization endpoint: {}", err))
+        })?;
+
+        let mut scopes = self.config.scopes().to_vec();
+        if !scopes.iter().any(|scope| scope == "openid") {
+            scopes.insert(0, "openid".into());
+        }
+
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", self.config.client_id())
+            .append_pair("redirect_uri", self.config.redirect_uri())
+            .append_pair("scope", &scopes.join(" "))
+            .append_pair("state", state)
+            .append_pair("nonce", nonce);
+
+        Ok(url.into_string())
+    }
+
+    /// Exchanges an authorization code for an ID token and returns the identity of the user it
+    /// was issued for.
+    ///
+    /// The ID token must be signed, with RS256 or ES256, by one of the keys in the provider's
+    /// JSON Web Key Set, and the algorithm must match the key's. The token's issuer, audience,
+    /// expiry and nonce are checked.
+    pub fn exchange_code(&self, code: &str, nonce: &str) -> Result<OpenIdIdentity, OAuthError> {
+        let response = self
+            .http_client
+            .post(&self.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.config.redirect_uri()),
+                ("client_id", self.config.client_id()),
+                ("client_secret", self.config.client_secret()),
+            ])
+            .send()
+            .map_err(|err| OAuthError::RequestError(err.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(OAuthError::InvalidResponse(format!(
+                "token endpoint returned {}: {}",
+                response.status(),
+                response.text().unwrap_or_default()
+            )));
+        }
+
+        let token_response = response
+            .json::<TokenResponse>()
+            .map_err(|err| OAuthError::InvalidResponse(err.to_string()))?;
+
+        let claims = self.verify_id_token(&token_response.id_token)?;
+
+        self.validate_claims(&claims, nonce)?;
+
+        Ok(OpenIdIdentity {
+            issuer: claims.iss,
+            subject: claims.sub,
+            email: claims.email,
+        })
+    }
+
+    /// Verifies the signature of the ID token with the provider's signing key and returns its
+    /// claims
+    fn verify_id_token(&self, id_token: &str) -> Result<IdTokenClaims, OAuthError> {
+        let header = jsonwebtoken::decode_header(id_token)
+            .map_err(|err| OAuthError::InvalidIdToken(err.to_string()))?;
+
+        match header.alg {
+            Algorithm::RS256 | Algorithm::ES256 => (),
+            alg => {
+                return Err(OAuthError::InvalidIdToken(format!(
+                    "unsupported signing algorithm {:?}",
+                    alg
+                )))
+            }
+        }
+
+        let key = self
+            .signing_key(header.kid.as_deref(), header.alg)?
+            .verification_key()?;
+
+        let validation = Validation {
+            algorithms: vec![header.alg],
+            ..Validation::default()
+        };
+
+        jsonwebtoken::decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|err| OAuthError::InvalidIdToken(err.to_string()))
+    }
+
+    /// Returns the provider's signing key with the given key ID and algorithm. If there is no
+    /// such key, the keys are fetched again, in case the provider has rotated them, unless they
+    /// were fetched recently.
+    fn signing_key(&self, kid: Option<&str>, alg: Algorithm) -> Result<ProviderJwk, OAuthError> {
+        let mut signing_keys = self
+            .signing_keys
+            .lock()
+            .map_err(|_| OAuthError::RequestError("provider signing keys lock poisoned".into()))?;
+
+        if let Some(key) = find_key(&signing_keys.keys, kid, alg) {
+            return Ok(key);
+        }
+
+        let refresh_due = signing_keys
+            .fetched
+            .map(|fetched| fetched.elapsed() >= JWKS_REFRESH_INTERVAL)
+            .unwrap_or(true);
+        if refresh_due {
+            signing_keys.fetched = Some(Instant::now());
+            signing_keys.keys = fetch_jwks(&self.http_client, &self.jwks_uri)?;
+            if let Some(key) = find_key(&signing_keys.keys, kid, alg) {
+                return Ok(key);
+            }
+        }
+
+        Err(OAuthError::InvalidIdToken(format!(
+            "no {:?} signing key found with key ID {}",
+            alg,
+            kid.unwrap_or("<none>")
+        )))
+    }
+
+    fn validate_claims(&self, claims: &IdTokenClaims, nonce: &str) -> Result<(), OAuthError> {
+        if claims.iss.trim_end_matches('/') != self.config.issuer().trim_end_matches('/') {
+            return Err(OAuthError::InvalidIdToken(format!(
+                "unexpected issuer {}",
+                claims.iss
+            )));
+        }
+        if !claims.aud.contains(self.config.client_id()) {
+            return Err(OAuthError::InvalidIdToken(
+                "token was not issued for this client".into(),
+            ));
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| OAuthError::InvalidIdToken(err.to_string()))?
+            .as_secs();
+        if claims.exp <= now {
+            return Err(OAuthError::InvalidIdToken("token has expired".into()));
+        }
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(OAuthError::InvalidIdToken("nonce does not match".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the signing key with the given key ID, or the first key if the token has no key ID,
+/// that is used with the given algorithm
+fn find_key(keys: &[ProviderJwk], kid: Option<&str>, alg: Algorithm) -> Option<ProviderJwk> {
+    keys.iter()
+        .filter(|key| key.key_use.as_deref().unwrap_or("sig") == "sig")
+        .filter(|key| kid.is_none() || key.kid.as_deref() == kid)
+        .find(|key| key.algorithm() == Some(alg))
+        .cloned()
+}
+
+fn fetch_jwks(http_client: &Client, jwks_uri: &str) -> Result<Vec<ProviderJwk>, OAuthError> {
+    let response = http_client
+        .get(jwks_uri)
+        .send()
+        .map_err(|err| OAuthError::RequestError(err.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(OAuthError::InvalidResponse(format!(
+            "unable to fetch signing keys from {}: {}",
+            jwks_uri,
+            response.status()
+        )));
+    }
+
+    response
+        .json::<JwkSet>()
+        .map(|jwks| jwks.keys)
+        .map_err(|err| OAuthError::InvalidResponse(err.to_string()))
+}
+
+/// Checks that a URL of the provider is an `https` URL, so that the credentials and tokens
+/// exchanged with the provider can not be read or modified in transit. If `allow_http` is set,
+/// a plain `http` URL is accepted with a warning.
+fn check_https(name: &str, url: &str, allow_http: bool) -> Result<(), OAuthError> {
+    if url.starts_with("https://") {
+        Ok(())
+    } else if allow_http {
+        warn!("OAuth provider {} is not an https URL: {}", name, url);
+        Ok(())
+    } else {
+        Err(OAuthError::ConfigError(format!(
+            "{} must be an https URL: {}",
+            name, url
+        )))
+    }
+}
+
+fn decode_base64url(value: &str) -> Result<Vec<u8>, OAuthError> {
+    let mut value = value.replace('-', "+").replace('_', "/");
+    while value.len() % 4 != 0 {
+        value.push('=');
+    }
+    base64::decode_block(&value).map_err(|err| invalid_key(&err.to_string()))
+}
+
+fn invalid_key(msg: &str) -> OAuthError {
+    OAuthError::InvalidResponse(format!("invalid provider signing key: {}", msg))
+}
+
+fn discover(http_client: &Client, issuer: &str) -> Result<ProviderMetadata, OAuthError> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    let response = http_client
+        .get(&url)
+        .send()
+        .map_err(|err| OAuthError::RequestError(err.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(OAuthError::InvalidResponse(format!(
+            "unable to discover OpenID configuration at {}: {}",
+            url,
+            response.status()
+        )));
+    }
+
+    response
+        .json::<ProviderMetadata>()
+        .map_err(|err| OAuthError::InvalidResponse(err.to_string()))
+}
+
+#[cfg(all(test, feature = "rest-api"))]
+mod tests {
+    use super::*;
+
+    use std::thread::JoinHandle;
+
+    use jsonwebtoken::{encode, Header};
+
+    use crate::actix_web::HttpResponse;
+    use crate::futures::{Future, IntoFuture};
+    use crate::rest_api::{into_bytes, Method, Resource, RestApiBuilder, RestApiShutdownHandle};
+
+    const ISSUER: &str = "http://provider.example.com";
+    const NONCE: &str = "nonce";
+    const KEY_ID: &str = "provider-key";
+
+    /// Starts a stand-in provider whose token endpoint returns the given ID token and whose
+    /// JWKS endpoint returns the public key of the given RSA key, and returns the client for it
+    fn start_provider(
+        id_token: String,
+        rsa: &Rsa<openssl::pkey::Private>,
+    ) -> (RestApiShutdownHandle, JoinHandle<()>, OpenIdClient) {
+        let jwks = json!({
+            "keys": [{
+                "kty": "RSA",
+                "kid": KEY_ID,
+                "alg": "RS256",
+                "use": "sig",
+                "n": base64url(&rsa.n().to_vec()),
+                "e": base64url(&rsa.e().to_vec()),
+            }]
+        });
+
+        let (handle, join_handle) = RestApiBuilder::new()
+            .with_bind("127.0.0.1:0")
+            .add_resource(
+                Resource::build("/token").add_method(Method::Post, move |_, payload| {
+                    let id_token = id_token.clone();
+                    // The form is read so that the connection can be reused for the JWKS request
+                    Box::new(into_bytes(payload).and_then(move |_| {
+                        HttpResponse::Ok()
+                            .json(json!({
+                                "access_token": "access",
+                                "token_type": "Bearer",
+                                "id_token": id_token,
+                            }))
+                            .into_future()
+                    }))
+                }),
+            )
+            .add_resource(
+                Resource::build("/jwks").add_method(Method::Get, move |_, _| {
+                    Box::new(HttpResponse::Ok().json(jwks.clone()).into_future())
+                }),
+            )
+            .build()
+            .expect("failed to build provider")
+            .run()
+            .expect("failed to run provider");
+
+        let url = format!("http://127.0.0.1:{}", handle.port_numbers()[0]);
+        let client = client(&format!("{}/token", url), &format!("{}/jwks", url));
+        (handle, join_handle, client)
+    }
+
+    fn client(token_endpoint: &str, jwks_uri: &str) -> OpenIdClient {
+        OpenIdClient::new(
+            OAuthConfig::new(ISSUER, "splinter", "secret", "http://localhost/callback")
+                .with_authorization_endpoint("http://provider.example.com/authorize")
+                .with_token_endpoint(token_endpoint)
+                .with_jwks_uri(jwks_uri)
+                .with_insecure_http(true),
+        )
+        .expect("failed to create client")
+    }
+
+    fn id_token(alg: Algorithm, key: &[u8]) -> String {
+        let exp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_secs()
+            + 60;
+        let mut header = Header::new(alg);
+        header.kid = Some(KEY_ID.into());
+        encode(
+            &header,
+            &json!({
+                "iss": ISSUER,
+                "sub": "alice",
+                "aud": "splinter",
+                "exp": exp,
+                "nonce": NONCE,
+                "email": "alice@example.com",
+            }),
+            key,
+        )
+        .expect("failed to encode ID token")
+    }
+
+    fn base64url(bytes: &[u8]) -> String {
+        base64::encode_block(bytes)
+            .trim_end_matches('=')
+            .replace('+', "-")
+            .replace('/', "_")
+    }
+
+    /// Verify that the authorization URL includes the parameters of the authorization code flow.
+    #[test]
+    fn authorization_url() {
+        let url = client(
+            "http://provider.example.com/token",
+            "http://provider.example.com/jwks",
+        )
+        .authorization_url("state", NONCE)
+        .expect("failed to build URL");
+        let url = Url::parse(&url).expect("invalid URL");
+        let pairs = url.query_pairs().into_owned().collect::<Vec<_>>();
+
+        assert!(pairs.contains(&("response_type".into(), "code".into())));
+        assert!(pairs.contains(&("client_id".into(), "splinter".into())));
+        assert!(pairs.contains(&("scope".into(), "openid profile email".into())));
+        assert!(pairs.contains(&("state".into(), "state".into())));
+        assert!(pairs.contains(&("nonce".into(), NONCE.into())));
+    }
+
+    /// Verify that a provider with plain `http` endpoints is rejected unless the configuration
+    /// explicitly allows it.
+    #[test]
+    fn insecure_http() {
+        let config = OAuthConfig::new(ISSUER, "splinter", "secret", "http://localhost/callback")
+            .with_authorization_endpoint("https://provider.example.com/authorize")
+            .with_token_endpoint("http://provider.example.com/token")
+            .with_jwks_uri("https://provider.example.com/jwks");
+
+        match OpenIdClient::new(config.clone()) {
+            Err(OAuthError::ConfigError(_)) => (),
+            res => panic!("expected ConfigError, got {:?}", res.map(|_| ())),
+        }
+
+        assert!(OpenIdClient::new(config.with_insecure_http(true)).is_ok());
+    }
+
+    /// Verify that an authorization code is exchanged for the identity in the provider's signed
+    /// ID token, and that an ID token with the wrong nonce is rejected.
+    #[test]
+    fn exchange_code() {
+        let rsa = Rsa::generate(2048).expect("failed to generate key");
+        let key = rsa.private_key_to_der().expect("failed to encode key");
+        let (handle, join_handle, client) = start_provider(id_token(Algorithm::RS256, &key), &rsa);
+
+        let identity = client.exchange_code("code", NONCE);
+        let wrong_nonce = client.exchange_code("code", "other nonce");
+
+        handle.shutdown().expect("failed to shut down provider");
+        join_handle.join().expect("failed to join provider thread");
+
+        assert_eq!(
+            identity.expect("failed to exchange code"),
+            OpenIdIdentity {
+                issuer: ISSUER.into(),
+                subject: "alice".into(),
+                email: Some("alice@example.com".into()),
+            }
+        );
+        match wrong_nonce {
+            Err(OAuthError::InvalidIdToken(_)) => (),
+            res => panic!("expected InvalidIdToken error, got {:?}", res),
+        }
+    }
+
+    /// Verify that ID tokens that are not signed by the provider's key, or are signed with an
+    /// algorithm other than the key's, are rejected.
+    #[test]
+    fn exchange_code_unverified_token() {
+        let rsa = Rsa::generate(2048).expect("failed to generate key");
+        let other_rsa = Rsa::generate(2048).expect("failed to generate key");
+        let other_key = other_rsa
+            .private_key_to_der()
+            .expect("failed to encode key");
+
+        for id_token in vec![
+            id_token(Algorithm::RS256, &other_key),
+            id_token(Algorithm::HS256, b"secret"),
+            id_token(Algorithm::RS512, &other_key),
+        ] {
+            let (handle, join_handle, client) = start_provider(id_token, &rsa);
+            let result = client.exchange_code("code", NONCE);
+            handle.shutdown().expect("failed to shut down provider");
+            join_handle.join().expect("failed to join provider thread");
+
+            match result {
+                Err(OAuthError::InvalidIdToken(_)) => (),
+                res => panic!("expected InvalidIdToken error, got {:?}", res),
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+
+use super::OAuthError;
+
+/// Configures the OpenID Connect provider that biome users log in with.
+///
+/// The configuration can be loaded from a YAML file of the following form:
+///
+/// ```yaml
+/// issuer: https://login.example.com
+/// client_id: splinter
+/// client_secret: secret
+/// redirect_uri: https://splinter.example.com/biome/oauth/callback
+/// # Optional; discovered from the issuer's OpenID configuration if not set
+/// authorization_endpoint: https://login.example.com/authorize
+/// token_endpoint: https://login.example.com/token
+/// jwks_uri: https://login.example.com/keys
+/// # Optional; defaults to "openid", "profile" and "email"
+/// scopes:
+///   - openid
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct OAuthConfig {
+    issuer: String,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    #[serde(default)]
+    authorization_endpoint: Option<String>,
+    #[serde(default)]
+    token_endpoint: Option<String>,
+    #[serde(default)]
+    jwks_uri: Option<String>,
+    #[serde(default = "default_scopes")]
+    scopes: Vec<String>,
+    #[serde(skip)]
+    insecure_http: bool,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec!["openid".into(), "profile".into(), "email".into()]
+}
+
+impl OAuthConfig {
+    /// Creates a new OAuthConfig
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer`: The issuer identifier of the OpenID Connect provider
+    /// * `client_id`: The client ID biome is registered with at the provider
+    /// * `client_secret`: The client secret biome is registered with at the provider
+    /// * `redirect_uri`: The URL of biome's `/biome/oauth/callback` endpoint, as registered with
+    ///   the provider
+    ///
+    pub fn new(issuer: &str, client_id: &str, client_secret: &str, redirect_uri: &str) -> Self {
+        OAuthConfig {
+            issuer: issuer.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            authorization_endpoint: None,
+            token_endpoint: None,
+            jwks_uri: None,
+            scopes: default_scopes(),
+            insecure_http: false,
+        }
+    }
+
+    /// Loads the configuration from a YAML file
+    pub fn from_file(path: &str) -> Result<Self, OAuthError> {
+        let file = File::open(path)
+            .map_err(|err| OAuthError::ConfigError(format!("unable to open {}: {}", path, err)))?;
+        serde_yaml::from_reader(file)
+            .map_err(|err| OAuthError::ConfigError(format!("unable to parse {}: {}", path, err)))
+    }
+
+    /// Sets the provider's authorization endpoint, instead of discovering it
+    pub fn with_authorization_endpoint(mut self, endpoint: &str) -> Self {
+        self.authorization_endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Sets the provider's token endpoint, instead of discovering it
+    pub fn with_token_endpoint(mut self, endpoint: &str) -> Self {
+        self.token_endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Sets the URL of the provider's JSON Web Key Set, which has the keys that sign its ID
+    /// tokens, instead of discovering it
+    pub fn with_jwks_uri(mut self, uri: &str) -> Self {
+        self.jwks_uri = Some(uri.to_string());
+        self
+    }
+
+    /// Sets the scopes requested from the provider; `openid` is always requested
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    /// Allows the issuer and the provider's endpoints to be plain `http` URLs. The credentials
+    /// and tokens exchanged with the provider are then sent unencrypted, so this should only be
+    /// enabled for testing against a local provider.
+    pub fn with_insecure_http(mut self, insecure_http: bool) -> Self {
+        self.insecure_http = insecure_http;
+        self
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    pub(super) fn client_secret(&self) -> &str {
+        &self.client_secret
+    }
+
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    pub fn authorization_endpoint(&self) -> Option<&str> {
+        self.authorization_endpoint.as_deref()
+    }
+
+    pub fn token_endpoint(&self) -> Option<&str> {
+        self.token_endpoint.as_deref()
+    }
+
+    pub fn jwks_uri(&self) -> Option<&str> {
+        self.jwks_uri.as_deref()
+    }
+
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    pub fn insecure_http(&self) -> bool {
+        self.insecure_http
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+/// Represents errors that occur while logging in through an OpenID Connect provider
+#[derive(Debug)]
+pub enum OAuthError {
+    /// The OAuth configuration is invalid or could not be read
+    ConfigError(String),
+    /// A request to the provider failed
+    RequestError(String),
+    /// The provider returned a response that could not be used
+    InvalidResponse(String),
+    /// The ID token returned by the provider did not pass validation
+    InvalidIdToken(String),
+}
+
+impl Error for OAuthError {}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OAuthError::ConfigError(msg) => write!(f, "invalid OAuth configuration: {}", msg),
+            OAuthError::RequestError(msg) => {
+                write!(f, "request to OAuth provider failed: {}", msg)
+            }
+            OAuthError::InvalidResponse(msg) => {
+                write!(f, "invalid response from OAuth provider: {}", msg)
+            }
+            OAuthError::InvalidIdToken(msg) => write!(f, "invalid ID token: {}", msg),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides support for logging in to biome through an OAuth 2.0 / OpenID Connect provider, so
+//! that users can sign in with an existing account, such as a corporate single sign-on account.
+//!
+//! Login uses the authorization code flow. The user is redirected to the provider's
+//! authorization endpoint and, once they have signed in, back to biome's callback with an
+//! authorization code. Biome exchanges the code for an ID token at the provider's token endpoint
+//! and looks up the biome user linked to the ID token's subject, creating a new user the first
+//! time a subject signs in. Biome then issues its own access and refresh tokens, as it does for a
+//! username and password login.
+
+mod client;
+mod config;
+mod error;
+pub mod store;
+
+pub use client::{OpenIdClient, OpenIdIdentity};
+pub use config::OAuthConfig;
+pub use error::OAuthError;
+
+/// Links a biome user to an account at an OpenID Connect provider
+#[derive(Clone, Debug, PartialEq)]
+pub struct OAuthUser {
+    pub user_id: String,
+    pub issuer: String,
+    pub subject: String,
+}
+
+impl OAuthUser {
+    /// Creates a new OAuthUser
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id`: The ID of the biome user
+    /// * `issuer`: The issuer identifier of the OpenID Connect provider
+    /// * `subject`: The identifier of the user's account at the provider
+    ///
+    pub fn new(user_id: &str, issuer: &str, subject: &str) -> Self {
+        OAuthUser {
+            user_id: user_id.to_string(),
+            issuer: issuer.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+}
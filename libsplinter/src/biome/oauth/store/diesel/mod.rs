@@ -0,0 +1,63 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(in crate::biome) mod models;
+mod operations;
+pub(in crate::biome) mod schema;
+
+use crate::biome::oauth::{
+    store::{OAuthUserStore, OAuthUserStoreError},
+    OAuthUser,
+};
+use crate::database::ConnectionPool;
+use operations::{
+    add_oauth_user::OAuthUserStoreAddOAuthUserOperation,
+    fetch_oauth_user::OAuthUserStoreFetchOAuthUserOperation, OAuthUserStoreOperations,
+};
+
+/// Manages adding and fetching the links between biome users and OpenID Connect accounts in the
+/// database
+pub struct DieselOAuthUserStore {
+    connection_pool: ConnectionPool,
+}
+
+impl DieselOAuthUserStore {
+    /// Creates a new DieselOAuthUserStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: ConnectionPool) -> Self {
+        Self { connection_pool }
+    }
+}
+
+impl OAuthUserStore for DieselOAuthUserStore {
+    fn add_oauth_user(&self, oauth_user: OAuthUser) -> Result<(), OAuthUserStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            OAuthUserStoreOperations::new(conn).add_oauth_user(oauth_user)
+        })
+    }
+
+    fn fetch_oauth_user(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<OAuthUser, OAuthUserStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            OAuthUserStoreOperations::new(conn).fetch_oauth_user(issuer, subject)
+        })
+    }
+}
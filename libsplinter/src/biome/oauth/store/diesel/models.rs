@@ -0,0 +1,44 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::schema::biome_oauth_users;
+use crate::biome::oauth::OAuthUser;
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "biome_oauth_users"]
+pub struct OAuthUserModel {
+    pub issuer: String,
+    pub subject: String,
+    pub user_id: String,
+}
+
+impl From<OAuthUserModel> for OAuthUser {
+    fn from(model: OAuthUserModel) -> Self {
+        OAuthUser {
+            user_id: model.user_id,
+            issuer: model.issuer,
+            subject: model.subject,
+        }
+    }
+}
+
+impl From<OAuthUser> for OAuthUserModel {
+    fn from(oauth_user: OAuthUser) -> Self {
+        OAuthUserModel {
+            issuer: oauth_user.issuer,
+            subject: oauth_user.subject,
+            user_id: oauth_user.user_id,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::OAuthUserStoreOperations;
+use crate::biome::oauth::store::diesel::{models::OAuthUserModel, schema::biome_oauth_users};
+use crate::biome::oauth::{store::OAuthUserStoreError, OAuthUser};
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::oauth) trait OAuthUserStoreAddOAuthUserOperation {
+    fn add_oauth_user(&self, oauth_user: OAuthUser) -> Result<(), OAuthUserStoreError>;
+}
+
+impl_for_diesel_backends! {
+    OAuthUserStoreAddOAuthUserOperation for OAuthUserStoreOperations {
+        fn add_oauth_user(&self, oauth_user: OAuthUser) -> Result<(), OAuthUserStoreError> {
+            let description = format!("subject {} of {}", oauth_user.subject, oauth_user.issuer);
+            let user_id = oauth_user.user_id.clone();
+
+            insert_into(biome_oauth_users::table)
+                .values(OAuthUserModel::from(oauth_user))
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        OAuthUserStoreError::DuplicateOAuthUserError(format!(
+                            "{} is already linked to a user",
+                            description
+                        ))
+                    }
+                    QueryError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                        OAuthUserStoreError::UserDoesNotExistError(format!(
+                            "User with ID {} does not exist in database",
+                            user_id
+                        ))
+                    }
+                    _ => OAuthUserStoreError::OperationError {
+                        context: format!("Failed to add OAuth user for {}", description),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::OAuthUserStoreOperations;
+use crate::biome::oauth::store::diesel::{models::OAuthUserModel, schema::biome_oauth_users};
+use crate::biome::oauth::{store::OAuthUserStoreError, OAuthUser};
+
+use diesel::prelude::*;
+
+pub(in crate::biome::oauth) trait OAuthUserStoreFetchOAuthUserOperation {
+    fn fetch_oauth_user(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<OAuthUser, OAuthUserStoreError>;
+}
+
+impl<'a, C> OAuthUserStoreFetchOAuthUserOperation for OAuthUserStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    <C as diesel::Connection>::Backend: 'static,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_oauth_user(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<OAuthUser, OAuthUserStoreError> {
+        biome_oauth_users::table
+            .filter(biome_oauth_users::issuer.eq(issuer))
+            .filter(biome_oauth_users::subject.eq(subject))
+            .first::<OAuthUserModel>(self.conn)
+            .optional()
+            .map_err(|err| OAuthUserStoreError::QueryError {
+                context: format!("Failed to fetch OAuth user for subject {}", subject),
+                source: Box::new(err),
+            })?
+            .map(OAuthUser::from)
+            .ok_or_else(|| {
+                OAuthUserStoreError::NotFoundError(format!(
+                    "No user is linked to subject {} of {}",
+                    subject, issuer
+                ))
+            })
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "diesel")]
+use crate::database::error;
+
+/// Represents OAuthUserStore errors
+#[derive(Debug)]
+pub enum OAuthUserStoreError {
+    /// Represents CRUD operations failures
+    OperationError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents database query failures
+    QueryError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents general failures in the database
+    StorageError {
+        context: String,
+        source: Option<Box<dyn Error>>,
+    },
+    /// Represents an issue connecting to the database
+    ConnectionError(Box<dyn Error>),
+    /// Returned when no user is linked to an account
+    NotFoundError(String),
+    /// Returned when an account is already linked to a user
+    DuplicateOAuthUserError(String),
+    /// Returned when a user is not found with the provided ID
+    UserDoesNotExistError(String),
+}
+
+impl Error for OAuthUserStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OAuthUserStoreError::OperationError { source, .. } => Some(&**source),
+            OAuthUserStoreError::QueryError { source, .. } => Some(&**source),
+            OAuthUserStoreError::StorageError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            OAuthUserStoreError::StorageError { source: None, .. } => None,
+            OAuthUserStoreError::ConnectionError(err) => Some(&**err),
+            OAuthUserStoreError::NotFoundError(_) => None,
+            OAuthUserStoreError::DuplicateOAuthUserError(_) => None,
+            OAuthUserStoreError::UserDoesNotExistError(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for OAuthUserStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OAuthUserStoreError::OperationError { context, source } => {
+                write!(f, "failed to perform operation: {}: {}", context, source)
+            }
+            OAuthUserStoreError::QueryError { context, source } => {
+                write!(f, "failed query: {}: {}", context, source)
+            }
+            OAuthUserStoreError::StorageError {
+                context,
+                source: Some(source),
+            } => write!(
+                f,
+                "the underlying storage returned an error: {}: {}",
+                context, source
+            ),
+            OAuthUserStoreError::StorageError {
+                context,
+                source: None,
+            } => write!(f, "the underlying storage returned an error: {}", context),
+            OAuthUserStoreError::ConnectionError(ref s) => {
+                write!(f, "failed to connect to underlying storage: {}", s)
+            }
+            OAuthUserStoreError::NotFoundError(ref s) => write!(f, "OAuth user not found: {}", s),
+            OAuthUserStoreError::DuplicateOAuthUserError(ref s) => {
+                write!(f, "OAuth user already exists: {}", s)
+            }
+            OAuthUserStoreError::UserDoesNotExistError(ref s) => {
+                write!(f, "user does not exist: {}", s)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<error::ConnectionError> for OAuthUserStoreError {
+    fn from(err: error::ConnectionError) -> OAuthUserStoreError {
+        OAuthUserStoreError::ConnectionError(Box::new(err))
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::biome::oauth::{
+    store::{OAuthUserStore, OAuthUserStoreError},
+    OAuthUser,
+};
+
+#[derive(Default, Clone)]
+pub struct MemoryOAuthUserStore {
+    /// OAuth users, keyed by issuer and subject
+    inner: Arc<Mutex<BTreeMap<(String, String), OAuthUser>>>,
+}
+
+impl MemoryOAuthUserStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<MutexGuard<BTreeMap<(String, String), OAuthUser>>, OAuthUserStoreError> {
+        self.inner
+            .lock()
+            .map_err(|_| OAuthUserStoreError::StorageError {
+                context: "Cannot access OAuth user store: mutex lock poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+impl OAuthUserStore for MemoryOAuthUserStore {
+    fn add_oauth_user(&self, oauth_user: OAuthUser) -> Result<(), OAuthUserStoreError> {
+        let mut inner = self.lock()?;
+        let key = (oauth_user.issuer.clone(), oauth_user.subject.clone());
+        if inner.contains_key(&key) {
+            return Err(OAuthUserStoreError::DuplicateOAuthUserError(format!(
+                "Subject {} of {} is already linked to a user",
+                oauth_user.subject, oauth_user.issuer
+            )));
+        }
+        inner.insert(key, oauth_user);
+        Ok(())
+    }
+
+    fn fetch_oauth_user(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<OAuthUser, OAuthUserStoreError> {
+        self.lock()?
+            .get(&(issuer.to_string(), subject.to_string()))
+            .cloned()
+            .ok_or_else(|| {
+                OAuthUserStoreError::NotFoundError(format!(
+                    "No user is linked to subject {} of {}",
+                    subject, issuer
+                ))
+            })
+    }
+}
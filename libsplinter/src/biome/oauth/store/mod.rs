@@ -0,0 +1,47 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines an API to manage the links between biome users and OpenID Connect accounts.
+
+#[cfg(feature = "diesel")]
+pub(crate) mod diesel;
+mod error;
+pub(in crate::biome) mod memory;
+
+pub use error::OAuthUserStoreError;
+
+use super::OAuthUser;
+
+/// Defines methods for adding and fetching the links between biome users and the accounts they
+/// sign in with at OpenID Connect providers
+pub trait OAuthUserStore: Send + Sync {
+    /// Adds a link between a biome user and an account at a provider
+    ///
+    /// # Arguments
+    ///
+    ///   * `oauth_user` - The link to be added
+    fn add_oauth_user(&self, oauth_user: OAuthUser) -> Result<(), OAuthUserStoreError>;
+
+    /// Fetches the link for an account at a provider
+    ///
+    /// # Arguments
+    ///
+    ///   * `issuer` - The issuer identifier of the provider
+    ///   * `subject` - The identifier of the account at the provider
+    fn fetch_oauth_user(
+        &self,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<OAuthUser, OAuthUserStoreError>;
+}
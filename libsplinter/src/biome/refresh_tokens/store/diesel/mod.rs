@@ -16,8 +16,19 @@ mod models;
 mod operations;
 mod schema;
 
+#[cfg(feature = "biome-sessions")]
+use crate::biome::refresh_tokens::store::RefreshTokenSession;
 use crate::biome::refresh_tokens::store::{RefreshTokenError, RefreshTokenStore};
 use crate::database::ConnectionPool;
+#[cfg(feature = "biome-sessions")]
+use models::RefreshTokenSessionModel;
+#[cfg(feature = "biome-sessions")]
+use operations::{
+    add_session::RefreshTokenStoreAddSessionOperation,
+    fetch_session::RefreshTokenStoreFetchSessionOperation,
+    list_sessions::RefreshTokenStoreListSessionsOperation,
+    remove_session::RefreshTokenStoreRemoveSessionOperation,
+};
 use operations::{
     add_token::RefreshTokenStoreAddTokenOperation,
     fetch_token::RefreshTokenStoreFetchTokenOperation,
@@ -37,15 +48,76 @@ impl DieselRefreshTokenStore {
 
 impl RefreshTokenStore for DieselRefreshTokenStore {
     fn add_token(&self, user_id: &str, token: &str) -> Result<(), RefreshTokenError> {
-        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).add_token(user_id, token)
+        with_connection!(self.connection_pool, |conn| {
+            RefreshTokenStoreOperations::new(conn).add_token(user_id, token)
+        })
     }
     fn remove_token(&self, user_id: &str) -> Result<(), RefreshTokenError> {
-        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).remove_token(user_id)
+        with_connection!(self.connection_pool, |conn| {
+            let operations = RefreshTokenStoreOperations::new(conn);
+            #[cfg(feature = "biome-sessions")]
+            operations.remove_user_sessions(user_id)?;
+            operations.remove_token(user_id)
+        })
     }
     fn update_token(&self, user_id: &str, token: &str) -> Result<(), RefreshTokenError> {
-        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).update_token(user_id, token)
+        with_connection!(self.connection_pool, |conn| {
+            RefreshTokenStoreOperations::new(conn).update_token(user_id, token)
+        })
     }
     fn fetch_token(&self, user_id: &str) -> Result<String, RefreshTokenError> {
-        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).fetch_token(user_id)
+        with_connection!(self.connection_pool, |conn| {
+            RefreshTokenStoreOperations::new(conn).fetch_token(user_id)
+        })
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: RefreshTokenSession) -> Result<(), RefreshTokenError> {
+        with_connection!(self.connection_pool, |conn| {
+            RefreshTokenStoreOperations::new(conn).add_session(session)
+        })
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<RefreshTokenSession, RefreshTokenError> {
+        with_connection!(self.connection_pool, |conn| {
+            RefreshTokenStoreOperations::new(conn).fetch_session(session_id)
+        })
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<RefreshTokenSession>, RefreshTokenError> {
+        with_connection!(self.connection_pool, |conn| {
+            RefreshTokenStoreOperations::new(conn).list_sessions(user_id)
+        })
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn remove_session(&self, session_id: &str) -> Result<(), RefreshTokenError> {
+        with_connection!(self.connection_pool, |conn| {
+            RefreshTokenStoreOperations::new(conn).remove_session(session_id)
+        })
+    }
+}
+
+#[cfg(feature = "biome-sessions")]
+impl From<RefreshTokenSessionModel> for RefreshTokenSession {
+    fn from(session: RefreshTokenSessionModel) -> Self {
+        Self {
+            session_id: session.session_id,
+            user_id: session.user_id,
+            token: session.token,
+            created: session.created.into(),
+            client_info: session.client_info,
+        }
+    }
+}
+
+#[cfg(feature = "biome-sessions")]
+impl From<RefreshTokenSession> for RefreshTokenSessionModel {
+    fn from(session: RefreshTokenSession) -> Self {
+        Self {
+            session_id: session.session_id,
+            user_id: session.user_id,
+            token: session.token,
+            created: session.created.into(),
+            client_info: session.client_info,
+        }
     }
 }
@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "biome-sessions")]
+use super::schema::refresh_token_sessions;
 use super::schema::refresh_tokens;
+#[cfg(feature = "biome-sessions")]
+use crate::database::DbTimestamp;
 
 #[derive(Queryable, Identifiable, PartialEq, Debug)]
 #[table_name = "refresh_tokens"]
@@ -29,3 +33,14 @@ pub struct NewRefreshToken<'a> {
     pub user_id: &'a str,
     pub token: &'a str,
 }
+
+#[cfg(feature = "biome-sessions")]
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "refresh_token_sessions"]
+pub struct RefreshTokenSessionModel {
+    pub session_id: String,
+    pub user_id: String,
+    pub token: String,
+    pub created: DbTimestamp,
+    pub client_info: Option<String>,
+}
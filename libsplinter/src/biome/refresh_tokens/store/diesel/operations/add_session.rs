@@ -0,0 +1,49 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::{models::RefreshTokenSessionModel, schema::refresh_token_sessions},
+    RefreshTokenError, RefreshTokenSession,
+};
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome) trait RefreshTokenStoreAddSessionOperation {
+    fn add_session(&self, session: RefreshTokenSession) -> Result<(), RefreshTokenError>;
+}
+
+impl_for_diesel_backends! {
+    RefreshTokenStoreAddSessionOperation for RefreshTokenStoreOperations {
+        fn add_session(&self, session: RefreshTokenSession) -> Result<(), RefreshTokenError> {
+            let user_id = session.user_id.clone();
+            insert_into(refresh_token_sessions::table)
+                .values(RefreshTokenSessionModel::from(session))
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                        RefreshTokenError::NotFoundError(format!("User {} not found", user_id))
+                    }
+                    _ => RefreshTokenError::OperationError {
+                        context: format!("Failed to add session for user {}", user_id),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
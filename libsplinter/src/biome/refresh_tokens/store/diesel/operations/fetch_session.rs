@@ -0,0 +1,51 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::{models::RefreshTokenSessionModel, schema::refresh_token_sessions},
+    RefreshTokenError, RefreshTokenSession,
+};
+use diesel::{prelude::*, result::Error::NotFound};
+
+pub(in crate::biome) trait RefreshTokenStoreFetchSessionOperation {
+    fn fetch_session(&self, session_id: &str) -> Result<RefreshTokenSession, RefreshTokenError>;
+}
+
+impl_for_diesel_backends! {
+    RefreshTokenStoreFetchSessionOperation for RefreshTokenStoreOperations {
+        fn fetch_session(
+            &self,
+            session_id: &str,
+        ) -> Result<RefreshTokenSession, RefreshTokenError> {
+            refresh_token_sessions::table
+                .find(session_id)
+                .first::<RefreshTokenSessionModel>(self.conn)
+                .map(RefreshTokenSession::from)
+                .map_err(|err| {
+                    if err == NotFound {
+                        RefreshTokenError::NotFoundError(format!(
+                            "Session {} not found",
+                            session_id
+                        ))
+                    } else {
+                        RefreshTokenError::QueryError {
+                            context: format!("Failed to retrieve session {}", session_id),
+                            source: Box::new(err),
+                        }
+                    }
+                })
+        }
+    }
+}
@@ -26,7 +26,6 @@ pub(in crate::biome) trait RefreshTokenStoreFetchTokenOperation {
 impl<'a, C> RefreshTokenStoreFetchTokenOperation for RefreshTokenStoreOperations<'a, C>
 where
     C: diesel::Connection,
-    <C as diesel::Connection>::Backend: diesel::backend::SupportsDefaultKeyword,
     <C as diesel::Connection>::Backend: 'static,
     i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
@@ -0,0 +1,48 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::{models::RefreshTokenSessionModel, schema::refresh_token_sessions},
+    RefreshTokenError, RefreshTokenSession,
+};
+use diesel::prelude::*;
+
+pub(in crate::biome) trait RefreshTokenStoreListSessionsOperation {
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<RefreshTokenSession>, RefreshTokenError>;
+}
+
+impl_for_diesel_backends! {
+    RefreshTokenStoreListSessionsOperation for RefreshTokenStoreOperations {
+        fn list_sessions(
+            &self,
+            user_id: &str,
+        ) -> Result<Vec<RefreshTokenSession>, RefreshTokenError> {
+            refresh_token_sessions::table
+                .filter(refresh_token_sessions::user_id.eq(user_id))
+                .order(refresh_token_sessions::created.asc())
+                .load::<RefreshTokenSessionModel>(self.conn)
+                .map(|sessions| {
+                    sessions
+                        .into_iter()
+                        .map(RefreshTokenSession::from)
+                        .collect()
+                })
+                .map_err(|err| RefreshTokenError::QueryError {
+                    context: format!("Failed to list sessions for user {}", user_id),
+                    source: Box::new(err),
+                })
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::schema::refresh_token_sessions, RefreshTokenError,
+};
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome) trait RefreshTokenStoreRemoveSessionOperation {
+    fn remove_session(&self, session_id: &str) -> Result<(), RefreshTokenError>;
+
+    fn remove_user_sessions(&self, user_id: &str) -> Result<usize, RefreshTokenError>;
+}
+
+impl_for_diesel_backends! {
+    RefreshTokenStoreRemoveSessionOperation for RefreshTokenStoreOperations {
+        fn remove_session(&self, session_id: &str) -> Result<(), RefreshTokenError> {
+            let removed = delete(refresh_token_sessions::table.find(session_id))
+                .execute(self.conn)
+                .map_err(|err| RefreshTokenError::OperationError {
+                    context: format!("Failed to delete session {}", session_id),
+                    source: Box::new(err),
+                })?;
+
+            if removed == 0 {
+                Err(RefreshTokenError::NotFoundError(format!(
+                    "Session {} not found",
+                    session_id
+                )))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn remove_user_sessions(&self, user_id: &str) -> Result<usize, RefreshTokenError> {
+            delete(
+                refresh_token_sessions::table.filter(refresh_token_sessions::user_id.eq(user_id)),
+            )
+            .execute(self.conn)
+            .map_err(|err| RefreshTokenError::OperationError {
+                context: format!("Failed to delete sessions for user {}", user_id),
+                source: Box::new(err),
+            })
+        }
+    }
+}
@@ -19,3 +19,14 @@ table! {
         token -> Text,
     }
 }
+
+#[cfg(feature = "biome-sessions")]
+table! {
+    refresh_token_sessions (session_id) {
+        session_id -> Text,
+        user_id -> Text,
+        token -> Text,
+        created -> Timestamp,
+        client_info -> Nullable<Text>,
+    }
+}
@@ -15,17 +15,23 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "biome-sessions")]
+use crate::biome::refresh_tokens::store::RefreshTokenSession;
 use crate::biome::refresh_tokens::store::{error::RefreshTokenError, RefreshTokenStore};
 
 #[derive(Default, Clone)]
 pub struct MemoryRefreshTokenStore {
     inner: Arc<Mutex<HashMap<String, String>>>,
+    #[cfg(feature = "biome-sessions")]
+    sessions: Arc<Mutex<HashMap<String, RefreshTokenSession>>>,
 }
 
 impl MemoryRefreshTokenStore {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-sessions")]
+            sessions: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -52,7 +58,25 @@ impl RefreshTokenStore for MemoryRefreshTokenStore {
                 source: None,
             })?;
 
-        if inner.remove(user_id).is_some() {
+        #[allow(unused_mut)]
+        let mut removed = inner.remove(user_id).is_some();
+
+        #[cfg(feature = "biome-sessions")]
+        {
+            let mut sessions =
+                self.sessions
+                    .lock()
+                    .map_err(|_| RefreshTokenError::StorageError {
+                        context: "Cannot access refresh token store: mutex lock poisoned"
+                            .to_string(),
+                        source: None,
+                    })?;
+            let session_count = sessions.len();
+            sessions.retain(|_, session| session.user_id != user_id);
+            removed = removed || sessions.len() < session_count;
+        }
+
+        if removed {
             Ok(())
         } else {
             Err(RefreshTokenError::NotFoundError(format!(
@@ -100,4 +124,120 @@ impl RefreshTokenStore for MemoryRefreshTokenStore {
             )))
         }
     }
+
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: RefreshTokenSession) -> Result<(), RefreshTokenError> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        sessions.insert(session.session_id.clone(), session);
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<RefreshTokenSession, RefreshTokenError> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        sessions.get(session_id).cloned().ok_or_else(|| {
+            RefreshTokenError::NotFoundError(format!("Session {} not found.", session_id))
+        })
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<RefreshTokenSession>, RefreshTokenError> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        let mut user_sessions = sessions
+            .values()
+            .filter(|session| session.user_id == user_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        user_sessions.sort_by_key(|session| session.created);
+        Ok(user_sessions)
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn remove_session(&self, session_id: &str) -> Result<(), RefreshTokenError> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        if sessions.remove(session_id).is_some() {
+            Ok(())
+        } else {
+            Err(RefreshTokenError::NotFoundError(format!(
+                "Session {} not found.",
+                session_id
+            )))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "biome-sessions"))]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, SystemTime};
+
+    /// Verifies that sessions are listed per user, that a single session can be removed, and
+    /// that removing a user's token removes all of the user's sessions.
+    #[test]
+    fn test_sessions() {
+        let store = MemoryRefreshTokenStore::new();
+        let now = SystemTime::now();
+        for (session_id, user_id, created) in &[
+            ("session-2", "user-1", now),
+            ("session-1", "user-1", now - Duration::from_secs(60)),
+            ("session-3", "user-2", now),
+        ] {
+            store
+                .add_session(RefreshTokenSession {
+                    session_id: session_id.to_string(),
+                    user_id: user_id.to_string(),
+                    token: format!("token-{}", session_id),
+                    created: *created,
+                    client_info: None,
+                })
+                .expect("Failed to add session");
+        }
+
+        let session_ids = |user_id| {
+            store
+                .list_sessions(user_id)
+                .expect("Failed to list sessions")
+                .into_iter()
+                .map(|session| session.session_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(session_ids("user-1"), vec!["session-1", "session-2"]);
+
+        store
+            .remove_session("session-1")
+            .expect("Failed to remove session");
+        assert_eq!(session_ids("user-1"), vec!["session-2"]);
+        assert!(store.fetch_session("session-1").is_err());
+
+        store
+            .remove_token("user-1")
+            .expect("Failed to remove user sessions");
+        assert!(session_ids("user-1").is_empty());
+        assert_eq!(session_ids("user-2"), vec!["session-3"]);
+    }
 }
@@ -17,8 +17,27 @@ pub(crate) mod diesel;
 mod error;
 pub(in crate::biome) mod memory;
 
+#[cfg(feature = "biome-sessions")]
+use std::time::SystemTime;
+
 pub use error::RefreshTokenError;
 
+/// A login session, identified by the refresh token issued when the user logged in
+#[cfg(feature = "biome-sessions")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefreshTokenSession {
+    /// The unique identifier of the session, included in the session's tokens
+    pub session_id: String,
+    /// The user the session belongs to
+    pub user_id: String,
+    /// The refresh token issued for the session
+    pub token: String,
+    /// When the session was created
+    pub created: SystemTime,
+    /// A description of the client that created the session, such as its user agent
+    pub client_info: Option<String>,
+}
+
 /// Defines methods for CRUD operations for handling refresh tokens
 pub trait RefreshTokenStore: Send + Sync {
     /// Adds a refresh token to underlying storage
@@ -29,7 +48,8 @@ pub trait RefreshTokenStore: Send + Sync {
     ///   * `token` - A refresh token for user
     fn add_token(&self, user_id: &str, token: &str) -> Result<(), RefreshTokenError>;
 
-    /// Removes a token in underlying storage
+    /// Removes a token in underlying storage. When the `biome-sessions` feature is enabled,
+    /// all of the user's sessions are removed as well.
     ///
     /// # Arguments
     ///
@@ -50,4 +70,36 @@ pub trait RefreshTokenStore: Send + Sync {
     ///
     ///   * `user_id` - The user whom which the token is for
     fn fetch_token(&self, user_id: &str) -> Result<String, RefreshTokenError>;
+
+    /// Adds a session and its refresh token to underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `session` - The session to add
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: RefreshTokenSession) -> Result<(), RefreshTokenError>;
+
+    /// Fetch a session from underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `session_id` - The unique identifier of the session
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<RefreshTokenSession, RefreshTokenError>;
+
+    /// List the sessions of a user, oldest first
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The user whom the sessions belong to
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<RefreshTokenSession>, RefreshTokenError>;
+
+    /// Removes a session and its refresh token from underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `session_id` - The unique identifier of the session
+    #[cfg(feature = "biome-sessions")]
+    fn remove_session(&self, session_id: &str) -> Result<(), RefreshTokenError>;
 }
@@ -0,0 +1,398 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use openssl::sha::sha256;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+
+use crate::actix_web::HttpResponse;
+#[cfg(feature = "audit")]
+use crate::audit::{rest_api::request_actor, user_actor, AuditLog};
+use crate::biome::credentials::store::{
+    CredentialsBuilder, CredentialsStore, CredentialsStoreError, PasswordResetToken,
+};
+use crate::biome::credentials::BIOME_PASSWORD_RESET_PERMISSION;
+use crate::biome::refresh_tokens::store::{RefreshTokenError, RefreshTokenStore};
+use crate::biome::rest_api::resources::credentials::PasswordReset;
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::{Future, IntoFuture};
+use crate::hex::to_hex;
+use crate::protocol;
+use crate::rest_api::{
+    auth::{Authorizer, PermissionGuard},
+    into_bytes, ErrorResponse, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+#[cfg(feature = "biome-sessions")]
+use super::sessions::deny_session;
+#[cfg(feature = "biome-sessions")]
+use crate::rest_api::sessions::AccessTokenDenylist;
+
+const RESET_TOKEN_LENGTH: usize = 32;
+
+fn locked_response() -> HttpResponse {
+    HttpResponse::Forbidden().json(ErrorResponse::forbidden(
+        "Account is temporarily locked due to too many failed login attempts",
+    ))
+}
+
+/// Returns an error response if the user is locked out after too many failed logins.
+pub(super) fn check_lockout(
+    credentials_store: &dyn CredentialsStore,
+    user_id: &str,
+) -> Result<(), HttpResponse> {
+    match credentials_store.fetch_login_attempts(user_id) {
+        Ok(attempts) if attempts.is_locked() => Err(locked_response()),
+        Ok(_) => Ok(()),
+        Err(err) => {
+            error!("Failed to fetch login attempts: {}", err);
+            Err(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    }
+}
+
+/// Records the outcome of a password check: a failure counts towards the lockout, a success
+/// clears any previous failures.
+///
+/// A success is refused with an error response if the user has been locked out since
+/// `check_lockout` was called, by failed attempts running concurrently with this one.
+pub(super) fn record_login_result(
+    credentials_store: &dyn CredentialsStore,
+    rest_config: &BiomeRestConfig,
+    user_id: &str,
+    is_valid: bool,
+) -> Result<(), HttpResponse> {
+    let result = if is_valid {
+        match credentials_store.reset_failed_logins(user_id) {
+            Ok(attempts) if attempts.is_locked() => return Err(locked_response()),
+            res => res.map(|_| ()),
+        }
+    } else {
+        credentials_store
+            .record_failed_login(
+                user_id,
+                rest_config.max_failed_logins(),
+                rest_config.lockout_duration(),
+            )
+            .map(|attempts| {
+                if attempts.is_locked() {
+                    warn!(
+                        "User {} locked out after {} failed login attempts",
+                        user_id,
+                        rest_config.max_failed_logins()
+                    );
+                }
+            })
+    };
+
+    result.map_err(|err| {
+        error!("Failed to record login attempt: {}", err);
+        HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+    })
+}
+
+/// Returns an error response if the password does not meet the configured password policy.
+pub(super) fn check_password_policy(
+    rest_config: &BiomeRestConfig,
+    password: &str,
+) -> Result<(), HttpResponse> {
+    rest_config
+        .password_policy()
+        .validate(password)
+        .map_err(|err| {
+            HttpResponse::BadRequest().json(ErrorResponse::bad_request(&err.to_string()))
+        })
+}
+
+/// Defines a REST endpoint for an administrator to create a password reset token for a user
+///
+/// The caller must have a role that grants the `biome.user.password_reset` permission. The
+/// response contains a one-time token that the user can exchange for a new password at
+/// `/biome/password_reset`:
+///   {
+///       "message": "Password reset token created",
+///       "reset_token": <the one-time token>,
+///       "expires_in": <seconds until the token expires>
+///   }
+pub fn make_password_reset_token_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    authorizer: &Arc<Authorizer>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/users/{id}/password_reset")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_PASSWORD_RESET_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(PermissionGuard::new(
+            authorizer.clone(),
+            BIOME_PASSWORD_RESET_PERMISSION,
+            BIOME_PASSWORD_RESET_PERMISSION,
+        ))
+        .add_method(Method::Post, move |request, _| {
+            let user_id = match request.match_info().get("id") {
+                Some(user_id) => user_id.to_string(),
+                None => {
+                    error!("User ID is not in path request");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            let reset_token: String = thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(RESET_TOKEN_LENGTH)
+                .collect();
+            let duration = rest_config.password_reset_token_duration();
+            let token = PasswordResetToken {
+                token_hash: hash_reset_token(&reset_token),
+                user_id: user_id.clone(),
+                expires: SystemTime::now() + duration,
+            };
+
+            let result = credentials_store.add_password_reset_token(token);
+            #[cfg(feature = "audit")]
+            audit_log.record_result(
+                &request_actor(&request),
+                "biome.password_reset_token",
+                &user_id,
+                &result,
+            );
+
+            match result {
+                Ok(()) => {
+                    info!("Password reset token created for user {}", user_id);
+                    Box::new(
+                        HttpResponse::Ok()
+                            .json(json!({
+                                "message": "Password reset token created",
+                                "reset_token": reset_token,
+                                "expires_in": duration.as_secs(),
+                            }))
+                            .into_future(),
+                    )
+                }
+                Err(CredentialsStoreError::NotFoundError(_)) => Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&format!(
+                            "User ID not found: {}",
+                            user_id
+                        )))
+                        .into_future(),
+                ),
+                Err(err) => {
+                    error!("Failed to add password reset token: {}", err);
+                    Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    )
+                }
+            }
+        })
+}
+
+/// Defines a REST endpoint to set a new password using a password reset token
+///
+/// The payload should be in the JSON format:
+///   {
+///       "reset_token": <token created by an administrator>
+///       "new_password": <hash of the user's new password>
+///   }
+///
+/// The token can only be used once. Resetting the password clears any lockout and revokes the
+/// user's refresh token. When the `biome-sessions` feature is enabled, all of the user's sessions
+/// are revoked.
+pub fn make_password_reset_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    #[cfg(feature = "biome-sessions")] denylist: AccessTokenDenylist,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/password_reset")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_PASSWORD_RESET_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Post, move |_, payload| {
+            let credentials_store = credentials_store.clone();
+            let refresh_token_store = refresh_token_store.clone();
+            let rest_config = rest_config.clone();
+            #[cfg(feature = "biome-sessions")]
+            let denylist = denylist.clone();
+            #[cfg(feature = "audit")]
+            let audit_log = audit_log.clone();
+            Box::new(into_bytes(payload).and_then(move |bytes| {
+                let password_reset = match serde_json::from_slice::<PasswordReset>(&bytes) {
+                    Ok(val) => val,
+                    Err(err) => {
+                        debug!("Error parsing payload {}", err);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Failed to parse payload: {}",
+                                err
+                            )))
+                            .into_future();
+                    }
+                };
+
+                if let Err(response) =
+                    check_password_policy(&rest_config, &password_reset.new_password)
+                {
+                    return response.into_future();
+                }
+
+                let token_hash = hash_reset_token(&password_reset.reset_token);
+                let credentials = match credentials_store
+                    .fetch_password_reset_token(&token_hash)
+                    .and_then(|token| credentials_store.fetch_credential_by_user_id(&token.user_id))
+                {
+                    Ok(credentials) => credentials,
+                    Err(CredentialsStoreError::NotFoundError(_)) => {
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "Invalid or expired password reset token",
+                            ))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to fetch password reset token: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                };
+
+                let hashed_password = match CredentialsBuilder::default()
+                    .with_user_id(&credentials.user_id)
+                    .with_username(&credentials.username)
+                    .with_password(&password_reset.new_password)
+                    .with_password_encryption_cost(rest_config.password_encryption_cost())
+                    .build()
+                {
+                    Ok(creds) => creds.password,
+                    Err(err) => {
+                        error!("Failed to salt new password: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                };
+
+                let result = credentials_store.reset_password(&token_hash, &hashed_password);
+                #[cfg(feature = "audit")]
+                audit_log.record_result(
+                    &user_actor(&credentials.user_id),
+                    "biome.password_reset",
+                    &credentials.user_id,
+                    &result,
+                );
+
+                match result {
+                    Ok(()) => (),
+                    Err(CredentialsStoreError::NotFoundError(_)) => {
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "Invalid or expired password reset token",
+                            ))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to reset password: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                }
+
+                #[cfg(feature = "biome-sessions")]
+                match refresh_token_store.list_sessions(&credentials.user_id) {
+                    Ok(sessions) => {
+                        for session in sessions {
+                            deny_session(&denylist, &rest_config, &session.session_id);
+                        }
+                    }
+                    Err(err) => {
+                        error!("Failed to list sessions: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                }
+
+                match refresh_token_store.remove_token(&credentials.user_id) {
+                    Ok(()) | Err(RefreshTokenError::NotFoundError(_)) => (),
+                    Err(err) => {
+                        error!("Failed to remove refresh token: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                }
+
+                info!("Password reset for user {}", credentials.user_id);
+                HttpResponse::Ok()
+                    .json(json!({
+                        "message": "Password reset successfully",
+                        "user_id": credentials.user_id,
+                    }))
+                    .into_future()
+            }))
+        })
+}
+
+/// Only the hash of a reset token is stored, so a leaked database does not leak usable tokens.
+fn hash_reset_token(reset_token: &str) -> String {
+    to_hex(&sha256(reset_token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::actix_web::http::StatusCode;
+    use crate::biome::credentials::store::memory::MemoryCredentialsStore;
+    use crate::biome::rest_api::BiomeRestConfigBuilder;
+
+    /// Verifies that a correct password is refused if the user was locked out after the lockout
+    /// check, and that the lockout is left in place.
+    #[test]
+    fn test_successful_login_while_locked() {
+        let store = MemoryCredentialsStore::new();
+        let rest_config = BiomeRestConfigBuilder::default()
+            .with_max_failed_logins(1)
+            .build()
+            .expect("Failed to build config");
+
+        assert!(check_lockout(&store, "user").is_ok());
+        // A concurrent failed attempt locks the user out
+        assert!(record_login_result(&store, &rest_config, "user", false).is_ok());
+
+        match record_login_result(&store, &rest_config, "user", true) {
+            Err(response) => assert_eq!(response.status(), StatusCode::FORBIDDEN),
+            Ok(()) => panic!("Successful login was not refused while locked out"),
+        }
+        assert!(store
+            .fetch_login_attempts("user")
+            .expect("Failed to fetch login attempts")
+            .is_locked());
+    }
+}
@@ -14,13 +14,13 @@
 
 use std::sync::Arc;
 
-use jsonwebtoken::{decode, Validation};
+use jsonwebtoken::Validation;
 
 use crate::actix_web::HttpRequest;
 use crate::biome::rest_api::resources::authorize::AuthorizationResult;
 use crate::rest_api::get_authorization_token;
 use crate::rest_api::secrets::SecretManager;
-use crate::rest_api::sessions::Claims;
+use crate::rest_api::sessions::{decode_token, Claims, TokenValidationError};
 
 /// Verifies the user has the correct permissions
 pub(crate) fn authorize_user(
@@ -44,16 +44,12 @@ pub(crate) fn validate_claims(
     secret_manager: &Arc<dyn SecretManager>,
     validation: &Validation,
 ) -> AuthorizationResult {
-    let secret = match secret_manager.secret() {
-        Ok(secret) => secret,
-        Err(err) => {
+    match decode_token::<Claims>(&token, &**secret_manager, validation) {
+        Ok(claims) => AuthorizationResult::Authorized(claims),
+        Err(TokenValidationError::SecretError(err)) => {
             debug!("Failed to fetch secret {}", err);
-            return AuthorizationResult::Failed;
+            AuthorizationResult::Failed
         }
-    };
-
-    match decode::<Claims>(&token, secret.as_ref(), validation) {
-        Ok(claims) => AuthorizationResult::Authorized(claims.claims),
         Err(err) => {
             debug!("Invalid token: {}", err);
             AuthorizationResult::Unauthorized("User is not authorized".to_string())
@@ -0,0 +1,56 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use crate::actix_web::HttpResponse;
+use crate::futures::IntoFuture;
+use crate::protocol;
+use crate::rest_api::{
+    secrets::SecretManager, ErrorResponse, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+/// Defines a REST endpoint that returns the public keys that verify biome access tokens, as a
+/// JSON Web Key Set, so that other services can verify the tokens.
+///
+/// The response is in the JSON format:
+///   {
+///       "keys": [<JSON Web Key>, ...]
+///   }
+///
+/// The set is empty if access tokens are signed with a shared secret.
+pub fn make_jwks_route(secret_manager: Arc<dyn SecretManager>) -> Resource {
+    Resource::build("/biome/jwks")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_JWKS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |_, _| {
+            match secret_manager.public_keys() {
+                Ok(keys) => Box::new(
+                    HttpResponse::Ok()
+                        .json(json!({ "keys": keys }))
+                        .into_future(),
+                ),
+                Err(err) => {
+                    error!("Unable to fetch public keys: {}", err);
+                    Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    )
+                }
+            }
+        })
+}
@@ -0,0 +1,327 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! REST endpoints for server-side private key encryption and key rotation
+
+use std::sync::Arc;
+
+use super::authorize::authorize_user;
+use crate::actix_web::{HttpRequest, HttpResponse};
+#[cfg(feature = "audit")]
+use crate::audit::{user_actor, AuditLog};
+use crate::biome::key_management::{
+    store::{KeyStore, KeyStoreError},
+    Key, KeyEncryptor,
+};
+use crate::biome::rest_api::resources::authorize::AuthorizationResult;
+use crate::biome::rest_api::resources::key_management::{NewKey, ResponseKey};
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    into_bytes, secrets::SecretManager, sessions::default_validation, ErrorResponse,
+    HandlerFunction, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+/// Creates the key described by a request payload.
+///
+/// If the payload contains an unencrypted `private_key`, it is encrypted with the user's data
+/// key. Otherwise the payload must contain an `encrypted_private_key` that the client encrypted.
+pub(super) fn key_from_request(
+    new_key: &NewKey,
+    user_id: &str,
+    key_store: &dyn KeyStore,
+    key_encryptor: Option<&KeyEncryptor>,
+) -> Result<Key, HttpResponse> {
+    match (
+        &new_key.private_key,
+        new_key.encrypted_private_key.is_empty(),
+    ) {
+        (Some(private_key), true) => {
+            let key_encryptor = key_encryptor.ok_or_else(|| {
+                HttpResponse::BadRequest().json(ErrorResponse::bad_request(
+                    "Server-side key encryption is not enabled; provide an encrypted_private_key",
+                ))
+            })?;
+
+            let encrypted_private_key = key_encryptor
+                .encrypt_private_key(key_store, user_id, &new_key.public_key, private_key)
+                .map_err(|err| {
+                    error!("Failed to encrypt private key: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                })?;
+
+            Ok(Key::new_server_encrypted(
+                &new_key.public_key,
+                &encrypted_private_key,
+                user_id,
+                &new_key.display_name,
+            ))
+        }
+        (None, false) => Ok(Key::new(
+            &new_key.public_key,
+            &new_key.encrypted_private_key,
+            user_id,
+            &new_key.display_name,
+        )),
+        (Some(_), false) => Err(HttpResponse::BadRequest().json(ErrorResponse::bad_request(
+            "Only one of private_key and encrypted_private_key may be provided",
+        ))),
+        (None, true) => Err(HttpResponse::BadRequest().json(ErrorResponse::bad_request(
+            "One of private_key or encrypted_private_key must be provided",
+        ))),
+    }
+}
+
+/// Defines a REST endpoint for rotating a key. The key is retired rather than deleted, and the
+/// key in the payload replaces it.
+///
+/// The payload should be in the JSON format:
+///   {
+///       "public_key": <public key of the new key pair>
+///       "encrypted_private_key": <private key, encrypted by the client>
+///       "private_key": <private key, to be encrypted by the server>
+///       "display_name": <display name of the new key>
+///   }
+///
+/// Exactly one of `encrypted_private_key` and `private_key` must be provided.
+pub fn make_key_rotation_route(
+    rest_config: Arc<BiomeRestConfig>,
+    key_store: Arc<dyn KeyStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/keys/{public_key}/rotate")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_KEY_ENCRYPTION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Post,
+            handle_rotate(
+                rest_config,
+                key_store,
+                secret_manager,
+                key_encryptor,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
+        )
+}
+
+fn handle_rotate(
+    rest_config: Arc<BiomeRestConfig>,
+    key_store: Arc<dyn KeyStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> HandlerFunction {
+    Box::new(move |request, payload| {
+        let key_store = key_store.clone();
+        let key_encryptor = key_encryptor.clone();
+        #[cfg(feature = "audit")]
+        let audit_log = audit_log.clone();
+
+        let (user_id, public_key) =
+            match authorize_key_request(&request, &rest_config, &secret_manager) {
+                Ok(ids) => ids,
+                Err(response) => return Box::new(response.into_future()),
+            };
+
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let new_key = match serde_json::from_slice::<NewKey>(&bytes) {
+                Ok(val) => val,
+                Err(err) => {
+                    debug!("Error parsing payload {}", err);
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Failed to parse payload: {}",
+                            err
+                        )))
+                        .into_future();
+                }
+            };
+
+            let key = match key_from_request(
+                &new_key,
+                &user_id,
+                &*key_store,
+                key_encryptor.as_ref().map(|encryptor| &**encryptor),
+            ) {
+                Ok(key) => key,
+                Err(response) => return response.into_future(),
+            };
+            let response_key = ResponseKey::from(&key);
+
+            let result = key_store.rotate_key(&public_key, &user_id, key.clone());
+            #[cfg(feature = "audit")]
+            audit_log.record_result(
+                &user_actor(&user_id),
+                "biome.key.rotate",
+                &public_key,
+                &result,
+            );
+
+            match result {
+                Ok(()) => HttpResponse::Ok()
+                    .json(json!({ "message": "Key rotated successfully", "data": response_key }))
+                    .into_future(),
+                Err(KeyStoreError::NotFoundError(msg)) => HttpResponse::NotFound()
+                    .json(ErrorResponse::not_found(&msg))
+                    .into_future(),
+                Err(KeyStoreError::DuplicateKeyError(msg)) => HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(&msg))
+                    .into_future(),
+                Err(err) => {
+                    error!("Failed to rotate key: {}", err);
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future()
+                }
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint for retrieving the decrypted private key of a key that was encrypted
+/// by the server
+///
+/// The response is in the JSON format:
+///   {
+///       "data": {
+///           "public_key": <public key of the key pair>
+///           "private_key": <decrypted private key of the key pair>
+///       }
+///   }
+pub fn make_private_key_route(
+    rest_config: Arc<BiomeRestConfig>,
+    key_store: Arc<dyn KeyStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/keys/{public_key}/private_key")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_KEY_ENCRYPTION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |request, _| {
+            let (user_id, public_key) =
+                match authorize_key_request(&request, &rest_config, &secret_manager) {
+                    Ok(ids) => ids,
+                    Err(response) => return Box::new(response.into_future()),
+                };
+
+            let key_encryptor = match &key_encryptor {
+                Some(key_encryptor) => key_encryptor,
+                None => {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "Server-side key encryption is not enabled",
+                            ))
+                            .into_future(),
+                    )
+                }
+            };
+
+            let key = match key_store.fetch_key(&public_key, &user_id) {
+                Ok(key) => key,
+                Err(KeyStoreError::NotFoundError(msg)) => {
+                    return Box::new(
+                        HttpResponse::NotFound()
+                            .json(ErrorResponse::not_found(&msg))
+                            .into_future(),
+                    )
+                }
+                Err(err) => {
+                    error!("Failed to fetch key: {}", err);
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            if !key.server_encrypted {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            "Private key was encrypted by the client",
+                        ))
+                        .into_future(),
+                );
+            }
+
+            let result = key_encryptor.decrypt_private_key(&*key_store, &key);
+            #[cfg(feature = "audit")]
+            audit_log.record_result(
+                &user_actor(&user_id),
+                "biome.key.private_key",
+                &public_key,
+                &result,
+            );
+
+            match result {
+                Ok(private_key) => Box::new(
+                    HttpResponse::Ok()
+                        .json(json!({
+                            "data": {
+                                "public_key": key.public_key,
+                                "private_key": private_key,
+                            }
+                        }))
+                        .into_future(),
+                ),
+                Err(err) => {
+                    error!("Failed to decrypt private key: {}", err);
+                    Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    )
+                }
+            }
+        })
+}
+
+/// Returns the ID of the authorized user and the public key in the request's path
+fn authorize_key_request(
+    request: &HttpRequest,
+    rest_config: &BiomeRestConfig,
+    secret_manager: &Arc<dyn SecretManager>,
+) -> Result<(String, String), HttpResponse> {
+    let public_key = match request.match_info().get("public_key") {
+        Some(public_key) => public_key.to_owned(),
+        None => {
+            error!("Public key is not in path request");
+            return Err(HttpResponse::BadRequest().json(ErrorResponse::bad_request(
+                "Failed to process request: no public key",
+            )));
+        }
+    };
+
+    let validation = default_validation(&rest_config.issuer());
+    match authorize_user(request, secret_manager, &validation) {
+        AuthorizationResult::Authorized(claims) => Ok((claims.user_id(), public_key)),
+        AuthorizationResult::Unauthorized(msg) => {
+            Err(HttpResponse::Unauthorized().json(ErrorResponse::unauthorized(&msg)))
+        }
+        AuthorizationResult::Failed => {
+            Err(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    }
+}
@@ -15,11 +15,16 @@
 use std::sync::Arc;
 
 use super::authorize::authorize_user;
+#[cfg(feature = "biome-key-encryption")]
+use super::key_encryption::key_from_request;
 use crate::actix_web::HttpResponse;
-use crate::biome::key_management::{
-    store::{KeyStore, KeyStoreError},
-    Key,
-};
+#[cfg(feature = "audit")]
+use crate::audit::{user_actor, AuditLog};
+use crate::biome::key_management::store::{KeyStore, KeyStoreError};
+#[cfg(not(feature = "biome-key-encryption"))]
+use crate::biome::key_management::Key;
+#[cfg(feature = "biome-key-encryption")]
+use crate::biome::key_management::KeyEncryptor;
 use crate::biome::rest_api::resources::authorize::AuthorizationResult;
 use crate::biome::rest_api::resources::key_management::{NewKey, ResponseKey, UpdatedKey};
 use crate::biome::rest_api::BiomeRestConfig;
@@ -31,10 +36,15 @@ use crate::rest_api::{
 use crate::rest_api::{secrets::SecretManager, sessions::default_validation};
 
 /// Defines a REST endpoint for managing keys including inserting, listing and updating keys
+///
+/// When the `biome-key-encryption` feature is enabled and a `KeyEncryptor` is provided, keys may
+/// be added with an unencrypted `private_key`, which the server encrypts.
 pub fn make_key_management_route(
     rest_config: Arc<BiomeRestConfig>,
     key_store: Arc<dyn KeyStore>,
     secret_manager: Arc<dyn SecretManager>,
+    #[cfg(feature = "biome-key-encryption")] key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Resource {
     Resource::build("/biome/keys")
         .add_request_guard(ProtocolVersionRangeGuard::new(
@@ -47,6 +57,10 @@ pub fn make_key_management_route(
                 rest_config.clone(),
                 key_store.clone(),
                 secret_manager.clone(),
+                #[cfg(feature = "biome-key-encryption")]
+                key_encryptor,
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
             ),
         )
         .add_method(
@@ -59,7 +73,13 @@ pub fn make_key_management_route(
         )
         .add_method(
             Method::Patch,
-            handle_patch(rest_config, key_store, secret_manager),
+            handle_patch(
+                rest_config,
+                key_store,
+                secret_manager,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
         )
 }
 
@@ -68,9 +88,15 @@ fn handle_post(
     rest_config: Arc<BiomeRestConfig>,
     key_store: Arc<dyn KeyStore>,
     secret_manager: Arc<dyn SecretManager>,
+    #[cfg(feature = "biome-key-encryption")] key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> HandlerFunction {
     Box::new(move |request, payload| {
         let key_store = key_store.clone();
+        #[cfg(feature = "biome-key-encryption")]
+        let key_encryptor = key_encryptor.clone();
+        #[cfg(feature = "audit")]
+        let audit_log = audit_log.clone();
         let validation = default_validation(&rest_config.issuer());
 
         let user_id = match authorize_user(&request, &secret_manager, &validation) {
@@ -104,6 +130,17 @@ fn handle_post(
                         .into_future();
                 }
             };
+            #[cfg(feature = "biome-key-encryption")]
+            let key = match key_from_request(
+                &new_key,
+                &user_id,
+                &*key_store,
+                key_encryptor.as_ref().map(|encryptor| &**encryptor),
+            ) {
+                Ok(key) => key,
+                Err(response) => return response.into_future(),
+            };
+            #[cfg(not(feature = "biome-key-encryption"))]
             let key = Key::new(
                 &new_key.public_key,
                 &new_key.encrypted_private_key,
@@ -112,7 +149,16 @@ fn handle_post(
             );
             let response_key = ResponseKey::from(&key);
 
-            match key_store.add_key(key.clone()) {
+            let result = key_store.add_key(key.clone());
+            #[cfg(feature = "audit")]
+            audit_log.record_result(
+                &user_actor(&user_id),
+                "biome.key.add",
+                &key.public_key,
+                &result,
+            );
+
+            match result {
                 Ok(()) => HttpResponse::Ok()
                     .json(json!({ "message": "Key added successfully", "data": response_key }))
                     .into_future(),
@@ -192,9 +238,12 @@ fn handle_patch(
     rest_config: Arc<BiomeRestConfig>,
     key_store: Arc<dyn KeyStore>,
     secret_manager: Arc<dyn SecretManager>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> HandlerFunction {
     Box::new(move |request, payload| {
         let key_store = key_store.clone();
+        #[cfg(feature = "audit")]
+        let audit_log = audit_log.clone();
         let validation = default_validation(&rest_config.issuer());
 
         let user_id = match authorize_user(&request, &secret_manager, &validation) {
@@ -229,11 +278,20 @@ fn handle_patch(
                 }
             };
 
-            match key_store.update_key(
+            let result = key_store.update_key(
                 &updated_key.public_key,
                 &user_id,
                 &updated_key.new_display_name,
-            ) {
+            );
+            #[cfg(feature = "audit")]
+            audit_log.record_result(
+                &user_actor(&user_id),
+                "biome.key.update",
+                &updated_key.public_key,
+                &result,
+            );
+
+            match result {
                 Ok(()) => HttpResponse::Ok()
                     .json(json!({ "message": "Key updated successfully" }))
                     .into_future(),
@@ -258,6 +316,7 @@ pub fn make_key_management_route_with_public_key(
     rest_config: Arc<BiomeRestConfig>,
     key_store: Arc<dyn KeyStore>,
     secret_manager: Arc<dyn SecretManager>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Resource {
     Resource::build("/biome/keys/{public_key}")
         .add_request_guard(ProtocolVersionRangeGuard::new(
@@ -274,7 +333,13 @@ pub fn make_key_management_route_with_public_key(
         )
         .add_method(
             Method::Delete,
-            handle_delete(rest_config, key_store, secret_manager),
+            handle_delete(
+                rest_config,
+                key_store,
+                secret_manager,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
         )
 }
 
@@ -353,6 +418,7 @@ fn handle_delete(
     rest_config: Arc<BiomeRestConfig>,
     key_store: Arc<dyn KeyStore>,
     secret_manager: Arc<dyn SecretManager>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> HandlerFunction {
     Box::new(move |request, _| {
         let key_store = key_store.clone();
@@ -390,7 +456,16 @@ fn handle_delete(
             }
         };
 
-        match key_store.remove_key(&public_key, &user_id) {
+        let result = key_store.remove_key(&public_key, &user_id);
+        #[cfg(feature = "audit")]
+        audit_log.record_result(
+            &user_actor(&user_id),
+            "biome.key.remove",
+            &public_key,
+            &result,
+        );
+
+        match result {
             Ok(key) => Box::new(
                 HttpResponse::Ok()
                     .json(json!(
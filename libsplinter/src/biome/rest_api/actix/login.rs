@@ -15,16 +15,31 @@
 use std::sync::Arc;
 
 use crate::actix_web::HttpResponse;
+#[cfg(feature = "audit")]
+use crate::audit::{user_actor, AuditLog, AuditOutcome, ANONYMOUS_ACTOR};
 use crate::biome::refresh_tokens::store::RefreshTokenStore;
 use crate::futures::{Future, IntoFuture};
 use crate::protocol;
 use crate::rest_api::{into_bytes, ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
 
 use crate::biome::credentials::store::{CredentialsStore, CredentialsStoreError};
+#[cfg(feature = "biome-account-security")]
+use crate::biome::rest_api::actix::account_security::{check_lockout, record_login_result};
+#[cfg(feature = "biome-roles")]
+use crate::biome::rest_api::actix::roles::with_role_claims;
+#[cfg(feature = "biome-sessions")]
+use crate::biome::rest_api::actix::sessions::{client_info, new_session, new_session_id};
 use crate::biome::rest_api::resources::credentials::UsernamePassword;
 use crate::biome::rest_api::BiomeRestConfig;
+#[cfg(feature = "biome-roles")]
+use crate::biome::roles::store::RoleStore;
 use crate::rest_api::sessions::{AccessTokenIssuer, ClaimsBuilder, TokenIssuer};
 
+/// The audit target of logins with a username that does not exist; the username is chosen by
+/// the client, so it is not recorded
+#[cfg(feature = "audit")]
+const UNKNOWN_USER_TARGET: &str = "unknown user";
+
 /// Defines a REST endpoint for login
 ///
 /// The payload should be in the JSON format:
@@ -32,22 +47,43 @@ use crate::rest_api::sessions::{AccessTokenIssuer, ClaimsBuilder, TokenIssuer};
 ///       "username": <existing username of the user>
 ///       "hashed_password": <hash of the user's existing password>
 ///   }
+///
+/// When the `biome-roles` feature is enabled, the access token includes the user's roles and the permissions
+/// they grant as custom claims.
+///
+/// When the `biome-sessions` feature is enabled, each login starts a new session that can be listed
+/// and revoked separately.
+///
+/// When the `biome-account-security` feature is enabled, a user is temporarily locked out after too
+/// many consecutive failed logins.
+///
+/// When the `audit` feature is enabled, every login attempt is recorded in the audit log.
 pub fn make_login_route(
     credentials_store: Arc<dyn CredentialsStore>,
     refresh_token_store: Arc<dyn RefreshTokenStore>,
+    #[cfg(feature = "biome-roles")] role_store: Arc<dyn RoleStore>,
     rest_config: Arc<BiomeRestConfig>,
     token_issuer: Arc<AccessTokenIssuer>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Resource {
     Resource::build("/biome/login")
         .add_request_guard(ProtocolVersionRangeGuard::new(
             protocol::BIOME_LOGIN_PROTOCOL_MIN,
             protocol::BIOME_PROTOCOL_VERSION,
         ))
-        .add_method(Method::Post, move |_, payload| {
+        .add_method(Method::Post, move |request, payload| {
+            #[cfg(feature = "biome-sessions")]
+            let client_info = client_info(&request);
+            #[cfg(not(feature = "biome-sessions"))]
+            let _ = request;
             let credentials_store = credentials_store.clone();
             let rest_config = rest_config.clone();
             let token_issuer = token_issuer.clone();
             let refresh_token_store = refresh_token_store.clone();
+            #[cfg(feature = "biome-roles")]
+            let role_store = role_store.clone();
+            #[cfg(feature = "audit")]
+            let audit_log = audit_log.clone();
             Box::new(into_bytes(payload).and_then(move |bytes| {
                 let username_password = match serde_json::from_slice::<UsernamePassword>(&bytes) {
                     Ok(val) => val,
@@ -70,6 +106,13 @@ pub fn make_login_route(
                         debug!("Failed to fetch credentials {}", err);
                         match err {
                             CredentialsStoreError::NotFoundError(_) => {
+                                #[cfg(feature = "audit")]
+                                audit_log.record(
+                                    ANONYMOUS_ACTOR,
+                                    "biome.login",
+                                    UNKNOWN_USER_TARGET,
+                                    AuditOutcome::Failure("Username not found".into()),
+                                );
                                 return HttpResponse::BadRequest()
                                     .json(ErrorResponse::bad_request(&format!(
                                         "Username not found: {}",
@@ -86,10 +129,55 @@ pub fn make_login_route(
                     }
                 };
 
+                #[cfg(feature = "biome-account-security")]
+                {
+                    if let Err(response) = check_lockout(&*credentials_store, &credentials.user_id)
+                    {
+                        #[cfg(feature = "audit")]
+                        audit_log.record(
+                            &user_actor(&credentials.user_id),
+                            "biome.login",
+                            &username_password.username,
+                            AuditOutcome::Failure("Account is locked".into()),
+                        );
+                        return response.into_future();
+                    }
+                }
+
                 match credentials.verify_password(&username_password.hashed_password) {
                     Ok(is_valid) => {
+                        #[cfg(feature = "biome-account-security")]
+                        {
+                            if let Err(response) = record_login_result(
+                                &*credentials_store,
+                                &rest_config,
+                                &credentials.user_id,
+                                is_valid,
+                            ) {
+                                return response.into_future();
+                            }
+                        }
+
                         if is_valid {
+                            #[cfg(feature = "biome-sessions")]
+                            let session_id = new_session_id();
                             let claim_builder = ClaimsBuilder::default();
+                            #[cfg(feature = "biome-sessions")]
+                            let claim_builder = claim_builder.with_session_id(&session_id);
+                            #[cfg(feature = "biome-roles")]
+                            let claim_builder = match with_role_claims(
+                                claim_builder,
+                                &*role_store,
+                                &credentials.user_id,
+                            ) {
+                                Ok(claim_builder) => claim_builder,
+                                Err(err) => {
+                                    debug!("Failed to fetch user roles {}", err);
+                                    return HttpResponse::InternalServerError()
+                                        .json(ErrorResponse::internal_error())
+                                        .into_future();
+                                }
+                            };
                             let claim = match claim_builder
                                 .with_user_id(&credentials.user_id)
                                 .with_issuer(&rest_config.issuer())
@@ -115,7 +203,11 @@ pub fn make_login_route(
                                 }
                             };
 
-                            let refresh_claims = match ClaimsBuilder::default()
+                            let refresh_claim_builder = ClaimsBuilder::default();
+                            #[cfg(feature = "biome-sessions")]
+                            let refresh_claim_builder =
+                                refresh_claim_builder.with_session_id(&session_id);
+                            let refresh_claims = match refresh_claim_builder
                                 .with_user_id(&credentials.user_id)
                                 .with_issuer(&rest_config.issuer())
                                 .with_duration(rest_config.refresh_token_duration())
@@ -142,15 +234,31 @@ pub fn make_login_route(
                                 }
                             };
 
-                            if let Err(err) =
-                                refresh_token_store.add_token(&credentials.user_id, &refresh_token)
-                            {
+                            #[cfg(feature = "biome-sessions")]
+                            let result = refresh_token_store.add_session(new_session(
+                                &session_id,
+                                &credentials.user_id,
+                                &refresh_token,
+                                client_info,
+                            ));
+                            #[cfg(not(feature = "biome-sessions"))]
+                            let result =
+                                refresh_token_store.add_token(&credentials.user_id, &refresh_token);
+                            if let Err(err) = result {
                                 debug!("Failed to store refresh token {}", err);
                                 return HttpResponse::InternalServerError()
                                     .json(ErrorResponse::internal_error())
                                     .into_future();
                             }
 
+                            #[cfg(feature = "audit")]
+                            audit_log.record(
+                                &user_actor(&credentials.user_id),
+                                "biome.login",
+                                &username_password.username,
+                                AuditOutcome::Success,
+                            );
+
                             HttpResponse::Ok()
                                 .json(json!({
                                     "message": "Successful login",
@@ -160,6 +268,14 @@ pub fn make_login_route(
                                 }))
                                 .into_future()
                         } else {
+                            #[cfg(feature = "audit")]
+                            audit_log.record(
+                                &user_actor(&credentials.user_id),
+                                "biome.login",
+                                &username_password.username,
+                                AuditOutcome::Failure("Invalid password".into()),
+                            );
+
                             HttpResponse::BadRequest()
                                 .json(ErrorResponse::bad_request("Invalid password"))
                                 .into_future()
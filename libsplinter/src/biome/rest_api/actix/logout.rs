@@ -16,12 +16,16 @@ use std::sync::Arc;
 
 use crate::actix_web::HttpResponse;
 use crate::biome::refresh_tokens::store::{RefreshTokenError, RefreshTokenStore};
+#[cfg(feature = "biome-sessions")]
+use crate::biome::rest_api::actix::sessions::deny_session;
 use crate::biome::rest_api::{
     actix::authorize::authorize_user, config::BiomeRestConfig,
     resources::authorize::AuthorizationResult,
 };
 use crate::futures::IntoFuture;
 use crate::protocol;
+#[cfg(feature = "biome-sessions")]
+use crate::rest_api::sessions::AccessTokenDenylist;
 use crate::rest_api::{
     secrets::SecretManager, sessions::default_validation, ErrorResponse, HandlerFunction, Method,
     ProtocolVersionRangeGuard, Resource,
@@ -29,10 +33,13 @@ use crate::rest_api::{
 
 /// Defines a REST endpoint to remove any refresh tokens belonging to the user.
 ///
+/// When the `biome-sessions` feature is enabled, only the session of the access token used to log
+/// out is ended, and the session's access tokens are no longer accepted.
 pub fn make_logout_route(
     refresh_token_store: Arc<dyn RefreshTokenStore>,
     secret_manager: Arc<dyn SecretManager>,
     rest_config: Arc<BiomeRestConfig>,
+    #[cfg(feature = "biome-sessions")] denylist: AccessTokenDenylist,
 ) -> Resource {
     Resource::build("/biome/logout")
         .add_request_guard(ProtocolVersionRangeGuard::new(
@@ -41,7 +48,13 @@ pub fn make_logout_route(
         ))
         .add_method(
             Method::Patch,
-            add_logout_route(refresh_token_store, secret_manager, rest_config),
+            add_logout_route(
+                refresh_token_store,
+                secret_manager,
+                rest_config,
+                #[cfg(feature = "biome-sessions")]
+                denylist,
+            ),
         )
 }
 
@@ -49,14 +62,15 @@ pub fn add_logout_route(
     refresh_token_store: Arc<dyn RefreshTokenStore>,
     secret_manager: Arc<dyn SecretManager>,
     rest_config: Arc<BiomeRestConfig>,
+    #[cfg(feature = "biome-sessions")] denylist: AccessTokenDenylist,
 ) -> HandlerFunction {
     Box::new(move |request, _| {
         let rest_config = rest_config.clone();
         let secret_manager = secret_manager.clone();
         let refresh_token_store = refresh_token_store.clone();
         let validation = default_validation(&rest_config.issuer());
-        let user_id = match authorize_user(&request, &secret_manager, &validation) {
-            AuthorizationResult::Authorized(claims) => claims.user_id(),
+        let claims = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims,
             AuthorizationResult::Unauthorized(msg) => {
                 return Box::new(
                     HttpResponse::Unauthorized()
@@ -73,7 +87,20 @@ pub fn add_logout_route(
             }
         };
 
-        Box::new(match refresh_token_store.remove_token(&user_id) {
+        let user_id = claims.user_id();
+
+        #[cfg(feature = "biome-sessions")]
+        let result = match claims.session_id() {
+            Some(session_id) => {
+                deny_session(&denylist, &rest_config, &session_id);
+                refresh_token_store.remove_session(&session_id)
+            }
+            None => refresh_token_store.remove_token(&user_id),
+        };
+        #[cfg(not(feature = "biome-sessions"))]
+        let result = refresh_token_store.remove_token(&user_id);
+
+        Box::new(match result {
             Ok(()) => HttpResponse::Ok()
                 .json(json!({
                     "message": "User successfully logged out"
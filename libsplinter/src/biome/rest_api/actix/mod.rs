@@ -12,16 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "biome-account-security")]
+pub(super) mod account_security;
 #[cfg(any(feature = "biome-key-management", feature = "biome-credentials"))]
 pub(crate) mod authorize;
+#[cfg(all(
+    feature = "rest-api-keyring",
+    any(feature = "biome-key-management", feature = "biome-credentials")
+))]
+pub(super) mod jwks;
+#[cfg(feature = "biome-key-encryption")]
+pub(super) mod key_encryption;
 #[cfg(feature = "biome-key-management")]
 pub(super) mod key_management;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod login;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod logout;
+#[cfg(feature = "biome-notifications")]
+pub(super) mod notifications;
+#[cfg(feature = "biome-oauth")]
+pub(super) mod oauth;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod register;
+#[cfg(feature = "biome-roles")]
+pub(super) mod roles;
+#[cfg(feature = "biome-sessions")]
+pub(super) mod sessions;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod token;
 #[cfg(feature = "biome-credentials")]
@@ -0,0 +1,428 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! REST and websocket endpoints for reading and managing a user's notifications.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use jsonwebtoken::Validation;
+
+use super::authorize::authorize_user;
+use crate::actix_web::{web, HttpRequest, HttpResponse};
+use crate::biome::notifications::{
+    store::{NotificationStore, NotificationStoreError},
+    Notification, NotificationManager, NotificationSubscriber, NotificationSubscriberError,
+};
+use crate::biome::rest_api::resources::{
+    authorize::AuthorizationResult,
+    notifications::{NotificationUpdate, ResponseNotification},
+};
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    into_bytes, new_websocket_event_sender, secrets::SecretManager, sessions::default_validation,
+    ErrorResponse, EventSender, HandlerFunction, Method, ProtocolVersionRangeGuard, Request,
+    Resource,
+};
+
+/// Defines a REST endpoint for listing a user's notifications and marking them all as read
+///
+/// `GET` accepts the optional query parameter `unread=true` to only list unread notifications.
+///
+/// `PATCH` payloads should be in the JSON format:
+///   {
+///       "unread": false
+///   }
+pub fn make_user_notifications_route(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/users/{id}/notifications")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_NOTIFICATIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Get,
+            handle_list_notifications(
+                rest_config.clone(),
+                notification_store.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Patch,
+            handle_mark_all_read(rest_config, notification_store, secret_manager),
+        )
+}
+
+/// Defines a REST endpoint for fetching, updating and removing one of a user's notifications
+///
+/// `PATCH` payloads should be in the JSON format:
+///   {
+///       "unread": <true or false>
+///   }
+pub fn make_user_notification_route(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/users/{id}/notifications/{notification_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_NOTIFICATIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Get,
+            handle_fetch_notification(
+                rest_config.clone(),
+                notification_store.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Patch,
+            handle_update_notification(
+                rest_config.clone(),
+                notification_store.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Delete,
+            handle_remove_notification(rest_config, notification_store, secret_manager),
+        )
+}
+
+/// Defines a websocket endpoint that sends a user's unread notifications, followed by each new
+/// notification the user receives
+pub fn make_notifications_ws_route(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_manager: NotificationManager,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/ws/biome/users/{id}/notifications")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_NOTIFICATIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |request, payload| {
+            let validation = default_validation(&rest_config.issuer());
+            let user_id =
+                match authorize_notification_request(&request, &secret_manager, &validation) {
+                    Ok(user_id) => user_id,
+                    Err(response) => return Box::new(response.into_future()),
+                };
+
+            let initial_notifications = match notification_manager
+                .store()
+                .list_user_notifications(&user_id, true)
+            {
+                Ok(notifications) => notifications
+                    .iter()
+                    .rev()
+                    .map(ResponseNotification::from)
+                    .collect::<Vec<_>>(),
+                Err(err) => return Box::new(notification_store_error_response(err).into_future()),
+            };
+
+            let request = Request::from((request, payload));
+            match new_websocket_event_sender(request, Box::new(initial_notifications.into_iter())) {
+                Ok((sender, res)) => {
+                    notification_manager
+                        .add_subscriber(&user_id, Box::new(WsNotificationSubscriber { sender }));
+                    debug!("Websocket response: {:?}", res);
+                    Box::new(res.into_future())
+                }
+                Err(err) => {
+                    debug!("Failed to create websocket: {:?}", err);
+                    Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    )
+                }
+            }
+        })
+}
+
+struct WsNotificationSubscriber {
+    sender: EventSender<ResponseNotification>,
+}
+
+impl NotificationSubscriber for WsNotificationSubscriber {
+    fn handle_notification(
+        &self,
+        notification: &Notification,
+    ) -> Result<(), NotificationSubscriberError> {
+        self.sender
+            .send(ResponseNotification::from(notification))
+            .map_err(|_| {
+                debug!("Dropping notification and unsubscribing due to websocket being closed");
+                NotificationSubscriberError::Unsubscribe
+            })
+    }
+}
+
+/// Defines a REST endpoint method to list a user's notifications
+fn handle_list_notifications(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_notification_request(&request, &secret_manager, &validation) {
+            Ok(user_id) => user_id,
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        let query = match web::Query::<HashMap<String, String>>::from_query(request.query_string())
+        {
+            Ok(query) => query,
+            Err(_) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("Invalid query"))
+                        .into_future(),
+                )
+            }
+        };
+        let unread_only = match query.get("unread").map(String::as_str) {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(value) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Invalid value for unread: {}",
+                            value
+                        )))
+                        .into_future(),
+                )
+            }
+        };
+
+        match notification_store.list_user_notifications(&user_id, unread_only) {
+            Ok(notifications) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({
+                        "data": notifications
+                            .iter()
+                            .map(ResponseNotification::from)
+                            .collect::<Vec<_>>()
+                    }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(notification_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to mark all of a user's notifications as read
+fn handle_mark_all_read(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, payload| {
+        let notification_store = notification_store.clone();
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_notification_request(&request, &secret_manager, &validation) {
+            Ok(user_id) => user_id,
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let update = match parse_update(&bytes) {
+                Ok(update) => update,
+                Err(response) => return response.into_future(),
+            };
+
+            if update.unread {
+                return HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(
+                        "All notifications can only be marked as read",
+                    ))
+                    .into_future();
+            }
+
+            match notification_store.mark_all_read(&user_id) {
+                Ok(()) => HttpResponse::Ok()
+                    .json(json!({ "message": "Notifications marked as read" }))
+                    .into_future(),
+                Err(err) => notification_store_error_response(err).into_future(),
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint method to fetch one of a user's notifications
+fn handle_fetch_notification(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+        let notification_id = match request.match_info().get("notification_id") {
+            Some(id) => id.to_owned(),
+            None => return Box::new(missing_path_parameter("notification ID").into_future()),
+        };
+        let user_id = match authorize_notification_request(&request, &secret_manager, &validation) {
+            Ok(user_id) => user_id,
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        match notification_store.fetch_user_notification(&user_id, &notification_id) {
+            Ok(notification) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "data": ResponseNotification::from(&notification) }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(notification_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to mark one of a user's notifications as read or unread
+fn handle_update_notification(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, payload| {
+        let notification_store = notification_store.clone();
+        let validation = default_validation(&rest_config.issuer());
+        let notification_id = match request.match_info().get("notification_id") {
+            Some(id) => id.to_owned(),
+            None => return Box::new(missing_path_parameter("notification ID").into_future()),
+        };
+        let user_id = match authorize_notification_request(&request, &secret_manager, &validation) {
+            Ok(user_id) => user_id,
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let update = match parse_update(&bytes) {
+                Ok(update) => update,
+                Err(response) => return response.into_future(),
+            };
+
+            match notification_store.update_user_notification(
+                &user_id,
+                &notification_id,
+                update.unread,
+            ) {
+                Ok(()) => HttpResponse::Ok()
+                    .json(json!({ "message": "Notification updated successfully" }))
+                    .into_future(),
+                Err(err) => notification_store_error_response(err).into_future(),
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint method to remove one of a user's notifications
+fn handle_remove_notification(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+        let notification_id = match request.match_info().get("notification_id") {
+            Some(id) => id.to_owned(),
+            None => return Box::new(missing_path_parameter("notification ID").into_future()),
+        };
+        let user_id = match authorize_notification_request(&request, &secret_manager, &validation) {
+            Ok(user_id) => user_id,
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        match notification_store.remove_user_notification(&user_id, &notification_id) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Notification removed successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(notification_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Authorizes the request and checks that the caller is the user whose notifications are
+/// requested; users may only access their own notifications. Returns the user's ID.
+fn authorize_notification_request(
+    request: &HttpRequest,
+    secret_manager: &Arc<dyn SecretManager>,
+    validation: &Validation,
+) -> Result<String, HttpResponse> {
+    let user_id = match request.match_info().get("id") {
+        Some(id) => id.to_owned(),
+        None => return Err(missing_path_parameter("user ID")),
+    };
+
+    match authorize_user(request, secret_manager, validation) {
+        AuthorizationResult::Authorized(claims) => {
+            if claims.user_id() == user_id {
+                Ok(user_id)
+            } else {
+                Err(HttpResponse::Forbidden().json(ErrorResponse::forbidden(
+                    "Users may only access their own notifications",
+                )))
+            }
+        }
+        AuthorizationResult::Unauthorized(msg) => {
+            Err(HttpResponse::Unauthorized().json(ErrorResponse::unauthorized(&msg)))
+        }
+        AuthorizationResult::Failed => {
+            Err(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    }
+}
+
+fn parse_update(bytes: &[u8]) -> Result<NotificationUpdate, HttpResponse> {
+    serde_json::from_slice::<NotificationUpdate>(bytes).map_err(|err| {
+        debug!("Error parsing payload {}", err);
+        HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+            "Failed to parse payload: {}",
+            err
+        )))
+    })
+}
+
+fn notification_store_error_response(err: NotificationStoreError) -> HttpResponse {
+    match err {
+        NotificationStoreError::NotFoundError(msg) => {
+            debug!("Notification request failed: {}", msg);
+            HttpResponse::NotFound().json(ErrorResponse::not_found(&msg))
+        }
+        _ => {
+            error!("Notification request failed: {}", err);
+            HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+        }
+    }
+}
+
+fn missing_path_parameter(name: &str) -> HttpResponse {
+    error!("{} is not in path request", name);
+    HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+        "Failed to process request: no {}",
+        name
+    )))
+}
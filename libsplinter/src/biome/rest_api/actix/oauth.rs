@@ -0,0 +1,396 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! REST endpoints for logging in through an OpenID Connect provider.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+
+use crate::actix_web::{error::BlockingError, web, Error as ActixError, HttpResponse};
+#[cfg(feature = "audit")]
+use crate::audit::{user_actor, AuditLog, AuditOutcome, ANONYMOUS_ACTOR};
+use crate::biome::oauth::{
+    store::{OAuthUserStore, OAuthUserStoreError},
+    OAuthUser, OpenIdClient, OpenIdIdentity,
+};
+use crate::biome::refresh_tokens::store::RefreshTokenStore;
+#[cfg(feature = "biome-roles")]
+use crate::biome::rest_api::actix::roles::with_role_claims;
+#[cfg(feature = "biome-sessions")]
+use crate::biome::rest_api::actix::sessions::{client_info, new_session, new_session_id};
+use crate::biome::rest_api::BiomeRestConfig;
+#[cfg(feature = "biome-roles")]
+use crate::biome::roles::store::RoleStore;
+use crate::biome::user::store::{User, UserStore};
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::sessions::{AccessTokenIssuer, ClaimsBuilder, TokenIssuer};
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+/// How long a user has to sign in at the provider before the login attempt expires
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(600);
+/// The most login attempts that may be waiting for the provider at once; new attempts are
+/// refused until others finish or expire
+const MAX_PENDING_LOGINS: usize = 10_000;
+
+/// The login attempts that are waiting for the provider to redirect back to the callback,
+/// keyed by state
+#[derive(Clone, Default)]
+pub(in crate::biome::rest_api) struct PendingLogins {
+    inner: Arc<Mutex<HashMap<String, (String, Instant)>>>,
+}
+
+impl PendingLogins {
+    /// Records a new login attempt and returns its state and nonce
+    fn start(&self) -> Result<(String, String), HttpResponse> {
+        let state = random_string();
+        let nonce = random_string();
+
+        let mut pending = self.inner.lock().map_err(|_| {
+            error!("Pending OAuth logins lock poisoned");
+            HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+        })?;
+        pending.retain(|_, (_, started)| started.elapsed() < LOGIN_TIMEOUT);
+        if pending.len() >= MAX_PENDING_LOGINS {
+            warn!("Too many pending OAuth logins; refusing new login");
+            return Err(HttpResponse::ServiceUnavailable().json(
+                ErrorResponse::service_unavailable("Too many logins in progress, try again later"),
+            ));
+        }
+        pending.insert(state.clone(), (nonce.clone(), Instant::now()));
+
+        Ok((state, nonce))
+    }
+
+    /// Removes the login attempt with the given state and returns its nonce, if the attempt
+    /// exists and has not expired
+    fn finish(&self, state: &str) -> Result<Option<String>, HttpResponse> {
+        let mut pending = self.inner.lock().map_err(|_| {
+            error!("Pending OAuth logins lock poisoned");
+            HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+        })?;
+
+        Ok(pending
+            .remove(state)
+            .filter(|(_, started)| started.elapsed() < LOGIN_TIMEOUT)
+            .map(|(nonce, _)| nonce))
+    }
+}
+
+fn random_string() -> String {
+    thread_rng().sample_iter(&Alphanumeric).take(32).collect()
+}
+
+/// Defines a REST endpoint that starts a login by redirecting the user to the OpenID Connect
+/// provider's authorization endpoint
+pub fn make_oauth_login_route(
+    client: Arc<OpenIdClient>,
+    pending_logins: PendingLogins,
+) -> Resource {
+    Resource::build("/biome/oauth/login")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_OAUTH_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |_, _| {
+            let (state, nonce) = match pending_logins.start() {
+                Ok(login) => login,
+                Err(response) => return Box::new(response.into_future()),
+            };
+
+            match client.authorization_url(&state, &nonce) {
+                Ok(url) => Box::new(
+                    HttpResponse::Found()
+                        .header("Location", url)
+                        .finish()
+                        .into_future(),
+                ),
+                Err(err) => {
+                    error!("Unable to build OAuth authorization URL: {}", err);
+                    Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    )
+                }
+            }
+        })
+}
+
+/// Defines the REST endpoint the OpenID Connect provider redirects the user to once they have
+/// signed in.
+///
+/// The authorization code is exchanged for the user's identity, which is linked to a biome user,
+/// creating one if this is the first time the identity has been used. The response is the same
+/// as the response to a successful `/biome/login`.
+#[allow(clippy::too_many_arguments)]
+pub fn make_oauth_callback_route(
+    client: Arc<OpenIdClient>,
+    pending_logins: PendingLogins,
+    oauth_user_store: Arc<dyn OAuthUserStore>,
+    user_store: Arc<dyn UserStore>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    #[cfg(feature = "biome-roles")] role_store: Arc<dyn RoleStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    token_issuer: Arc<AccessTokenIssuer>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/oauth/callback")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_OAUTH_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |request, _| {
+            let query =
+                match web::Query::<HashMap<String, String>>::from_query(request.query_string()) {
+                    Ok(query) => query.into_inner(),
+                    Err(_) => return Box::new(bad_request("Invalid query").into_future()),
+                };
+
+            if let Some(error) = query.get("error") {
+                debug!("OAuth provider returned error: {}", error);
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&format!(
+                            "Login with OAuth provider failed: {}",
+                            error
+                        )))
+                        .into_future(),
+                );
+            }
+
+            let (code, state) = match (query.get("code"), query.get("state")) {
+                (Some(code), Some(state)) => (code.clone(), state.clone()),
+                _ => return Box::new(bad_request("Missing code or state").into_future()),
+            };
+
+            let nonce = match pending_logins.finish(&state) {
+                Ok(Some(nonce)) => nonce,
+                Ok(None) => {
+                    return Box::new(
+                        HttpResponse::Unauthorized()
+                            .json(ErrorResponse::unauthorized(
+                                "Unknown or expired login attempt",
+                            ))
+                            .into_future(),
+                    )
+                }
+                Err(response) => return Box::new(response.into_future()),
+            };
+
+            #[cfg(feature = "biome-sessions")]
+            let client_info = client_info(&request);
+            let client = client.clone();
+            let oauth_user_store = oauth_user_store.clone();
+            let user_store = user_store.clone();
+            let refresh_token_store = refresh_token_store.clone();
+            #[cfg(feature = "biome-roles")]
+            let role_store = role_store.clone();
+            let rest_config = rest_config.clone();
+            let token_issuer = token_issuer.clone();
+            #[cfg(feature = "audit")]
+            let audit_log = audit_log.clone();
+            #[cfg(feature = "audit")]
+            let issuer = client.issuer().to_string();
+
+            Box::new(
+                web::block(move || client.exchange_code(&code, &nonce)).then(
+                    move |result| -> Result<HttpResponse, ActixError> {
+                        let identity = match result {
+                            Ok(identity) => identity,
+                            Err(BlockingError::Error(err)) => {
+                                debug!("Failed to exchange OAuth authorization code: {}", err);
+                                #[cfg(feature = "audit")]
+                                audit_log.record(
+                                    ANONYMOUS_ACTOR,
+                                    "biome.oauth.login",
+                                    &issuer,
+                                    AuditOutcome::Failure(err.to_string()),
+                                );
+                                return Ok(HttpResponse::Unauthorized().json(
+                                    ErrorResponse::unauthorized("Login with OAuth provider failed"),
+                                ));
+                            }
+                            Err(BlockingError::Canceled) => {
+                                error!("OAuth authorization code exchange was canceled");
+                                return Ok(HttpResponse::InternalServerError()
+                                    .json(ErrorResponse::internal_error()));
+                            }
+                        };
+
+                        #[cfg(feature = "audit")]
+                        let identity_target = format!("{}/{}", identity.issuer, identity.subject);
+
+                        let user_id = match find_or_create_user(
+                            &*oauth_user_store,
+                            &*user_store,
+                            &identity,
+                        ) {
+                            Ok(user_id) => user_id,
+                            Err(err) => {
+                                error!("Failed to find user for OAuth identity: {}", err);
+                                #[cfg(feature = "audit")]
+                                audit_log.record(
+                                    ANONYMOUS_ACTOR,
+                                    "biome.oauth.login",
+                                    &identity_target,
+                                    AuditOutcome::Failure(err.to_string()),
+                                );
+                                return Ok(HttpResponse::InternalServerError()
+                                    .json(ErrorResponse::internal_error()));
+                            }
+                        };
+
+                        let response = issue_tokens(
+                            &user_id,
+                            &*refresh_token_store,
+                            #[cfg(feature = "biome-roles")]
+                            &*role_store,
+                            &rest_config,
+                            &token_issuer,
+                            #[cfg(feature = "biome-sessions")]
+                            client_info,
+                        );
+                        #[cfg(feature = "audit")]
+                        audit_log.record(
+                            &user_actor(&user_id),
+                            "biome.oauth.login",
+                            &identity_target,
+                            if response.status().is_success() {
+                                AuditOutcome::Success
+                            } else {
+                                AuditOutcome::Failure("Failed to issue tokens".into())
+                            },
+                        );
+
+                        Ok(response)
+                    },
+                ),
+            )
+        })
+}
+
+/// Returns the ID of the biome user linked to the identity, creating a new user the first time
+/// an identity signs in
+fn find_or_create_user(
+    oauth_user_store: &dyn OAuthUserStore,
+    user_store: &dyn UserStore,
+    identity: &OpenIdIdentity,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match oauth_user_store.fetch_oauth_user(&identity.issuer, &identity.subject) {
+        Ok(oauth_user) => return Ok(oauth_user.user_id),
+        Err(OAuthUserStoreError::NotFoundError(_)) => (),
+        Err(err) => return Err(Box::new(err)),
+    }
+
+    let user_id = uuid::Uuid::new_v4().to_string();
+    user_store.add_user(User::new(&user_id))?;
+    oauth_user_store.add_oauth_user(OAuthUser::new(
+        &user_id,
+        &identity.issuer,
+        &identity.subject,
+    ))?;
+
+    Ok(user_id)
+}
+
+fn issue_tokens(
+    user_id: &str,
+    refresh_token_store: &dyn RefreshTokenStore,
+    #[cfg(feature = "biome-roles")] role_store: &dyn RoleStore,
+    rest_config: &BiomeRestConfig,
+    token_issuer: &AccessTokenIssuer,
+    #[cfg(feature = "biome-sessions")] client_info: Option<String>,
+) -> HttpResponse {
+    #[cfg(feature = "biome-sessions")]
+    let session_id = new_session_id();
+    let claim_builder = ClaimsBuilder::default();
+    #[cfg(feature = "biome-sessions")]
+    let claim_builder = claim_builder.with_session_id(&session_id);
+    #[cfg(feature = "biome-roles")]
+    let claim_builder = match with_role_claims(claim_builder, role_store, user_id) {
+        Ok(claim_builder) => claim_builder,
+        Err(err) => {
+            debug!("Failed to fetch user roles {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    let token = match claim_builder
+        .with_user_id(user_id)
+        .with_issuer(&rest_config.issuer())
+        .with_duration(rest_config.access_token_duration())
+        .build()
+        .map_err(|err| err.to_string())
+        .and_then(|claims| {
+            token_issuer
+                .issue_token_with_claims(claims)
+                .map_err(|err| err.to_string())
+        }) {
+        Ok(token) => token,
+        Err(err) => {
+            debug!("Failed to issue token {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    let refresh_claim_builder = ClaimsBuilder::default();
+    #[cfg(feature = "biome-sessions")]
+    let refresh_claim_builder = refresh_claim_builder.with_session_id(&session_id);
+    let refresh_token = match refresh_claim_builder
+        .with_user_id(user_id)
+        .with_issuer(&rest_config.issuer())
+        .with_duration(rest_config.refresh_token_duration())
+        .build()
+        .map_err(|err| err.to_string())
+        .and_then(|claims| {
+            token_issuer
+                .issue_refresh_token_with_claims(claims)
+                .map_err(|err| err.to_string())
+        }) {
+        Ok(token) => token,
+        Err(err) => {
+            debug!("Failed to issue refresh token {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    #[cfg(feature = "biome-sessions")]
+    let result = refresh_token_store.add_session(new_session(
+        &session_id,
+        user_id,
+        &refresh_token,
+        client_info,
+    ));
+    #[cfg(not(feature = "biome-sessions"))]
+    let result = refresh_token_store.add_token(user_id, &refresh_token);
+    if let Err(err) = result {
+        debug!("Failed to store refresh token {}", err);
+        return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+    }
+
+    HttpResponse::Ok().json(json!({
+        "message": "Successful login",
+        "user_id": user_id,
+        "token": token,
+        "refresh_token": refresh_token,
+    }))
+}
+
+fn bad_request(msg: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ErrorResponse::bad_request(msg))
+}
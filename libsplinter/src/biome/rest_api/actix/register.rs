@@ -16,9 +16,13 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::actix_web::HttpResponse;
+#[cfg(feature = "audit")]
+use crate::audit::{user_actor, AuditLog};
 use crate::biome::credentials::store::{
     CredentialsBuilder, CredentialsStore, CredentialsStoreError,
 };
+#[cfg(feature = "biome-account-security")]
+use crate::biome::rest_api::actix::account_security::check_password_policy;
 use crate::biome::rest_api::resources::credentials::{NewUser, UsernamePassword};
 use crate::biome::rest_api::BiomeRestConfig;
 use crate::biome::user::store::{User, UserStore};
@@ -33,10 +37,14 @@ use crate::rest_api::{into_bytes, ErrorResponse, Method, ProtocolVersionRangeGua
 ///       "username": <username of new user>
 ///       "hashed_password": <hash of the password the user will use to log in>
 ///   }
+///
+/// When the `biome-account-security` feature is enabled, the password must meet the configured
+/// password policy.
 pub fn make_register_route(
     credentials_store: Arc<dyn CredentialsStore>,
     user_store: Arc<dyn UserStore>,
     rest_config: Arc<BiomeRestConfig>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Resource {
     Resource::build("/biome/register")
         .add_request_guard(ProtocolVersionRangeGuard::new(
@@ -47,6 +55,8 @@ pub fn make_register_route(
             let credentials_store = credentials_store.clone();
             let user_store = user_store.clone();
             let rest_config = rest_config.clone();
+            #[cfg(feature = "audit")]
+            let audit_log = audit_log.clone();
             Box::new(into_bytes(payload).and_then(move |bytes| {
                 let username_password = match serde_json::from_slice::<UsernamePassword>(&bytes) {
                     Ok(val) => val,
@@ -60,6 +70,16 @@ pub fn make_register_route(
                             .into_future();
                     }
                 };
+
+                #[cfg(feature = "biome-account-security")]
+                {
+                    if let Err(response) =
+                        check_password_policy(&rest_config, &username_password.hashed_password)
+                    {
+                        return response.into_future();
+                    }
+                }
+
                 let user_id = Uuid::new_v4().to_string();
                 let splinter_user = User::new(&user_id);
                 match user_store.add_user(splinter_user) {
@@ -81,7 +101,16 @@ pub fn make_register_route(
                             }
                         };
 
-                        match credentials_store.add_credentials(credentials) {
+                        let result = credentials_store.add_credentials(credentials);
+                        #[cfg(feature = "audit")]
+                        audit_log.record_result(
+                            &user_actor(&user_id),
+                            "biome.register",
+                            &username_password.username,
+                            &result,
+                        );
+
+                        match result {
                             Ok(()) => {
                                 let new_user = NewUser {
                                     user_id: &user_id,
@@ -0,0 +1,491 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! REST endpoints for managing biome roles, along with support for including a user's roles in
+//! their access tokens and granting the permissions of those roles to REST API clients.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::actix_web::{HttpRequest, HttpResponse};
+#[cfg(feature = "audit")]
+use crate::audit::{rest_api::request_actor, AuditLog};
+use crate::biome::rest_api::resources::roles::{NewRole, ResponseRole};
+use crate::biome::roles::{
+    store::{RoleStore, RoleStoreError},
+    Role, BIOME_ROLE_READ_PERMISSION, BIOME_ROLE_WRITE_PERMISSION,
+};
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::auth::{
+    identity::{IdentityProvider, JwtIdentityProvider},
+    Authorizer, Identity, IdentityError, PermissionGuard,
+};
+use crate::rest_api::{
+    into_bytes,
+    sessions::{Claims, ClaimsBuilder},
+    ErrorResponse, HandlerFunction, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+/// The custom claim that lists the names of the user's roles, separated by commas
+pub const ROLES_CLAIM: &str = "roles";
+/// The custom claim that lists the permissions granted by the user's roles, separated by commas
+pub const PERMISSIONS_CLAIM: &str = "permissions";
+
+/// Adds the user's roles, and the permissions they grant, to the claims as custom claims.
+pub(in crate::biome::rest_api) fn with_role_claims(
+    claims_builder: ClaimsBuilder,
+    role_store: &dyn RoleStore,
+    user_id: &str,
+) -> Result<ClaimsBuilder, RoleStoreError> {
+    let roles = role_store.list_user_roles(user_id)?;
+    let role_names = roles
+        .iter()
+        .map(|role| role.name.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let permissions = roles
+        .iter()
+        .flat_map(|role| role.permissions.iter().map(String::as_str))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>()
+        .join(",");
+
+    Ok(claims_builder
+        .with_custom_claim(ROLES_CLAIM, &role_names)
+        .with_custom_claim(PERMISSIONS_CLAIM, &permissions))
+}
+
+/// Returns the names of the roles listed in the claims of a biome access token.
+pub fn roles_from_claims(claims: &Claims) -> Vec<String> {
+    split_claim(claims, ROLES_CLAIM)
+}
+
+/// Returns the permissions listed in the claims of a biome access token.
+pub fn permissions_from_claims(claims: &Claims) -> Vec<String> {
+    split_claim(claims, PERMISSIONS_CLAIM)
+}
+
+fn split_claim(claims: &Claims, claim: &str) -> Vec<String> {
+    claims
+        .custom_claims()
+        .get(claim)
+        .map(|value| {
+            value
+                .split(',')
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_else(Vec::new)
+}
+
+/// Identifies biome users by their access tokens, like `JwtIdentityProvider`, and grants them the
+/// permissions of their roles, so that resources can be guarded by the REST API's
+/// `PermissionGuard` with role permissions. Permissions are read from the role store, so changes
+/// to a user's roles take effect immediately.
+pub struct BiomeRoleIdentityProvider {
+    identity_provider: JwtIdentityProvider,
+    role_store: Arc<dyn RoleStore>,
+}
+
+impl BiomeRoleIdentityProvider {
+    /// Creates a new provider that identifies users with the given JWT identity provider and
+    /// grants them the permissions of their roles in the given role store.
+    pub fn new(identity_provider: JwtIdentityProvider, role_store: Arc<dyn RoleStore>) -> Self {
+        Self {
+            identity_provider,
+            role_store,
+        }
+    }
+}
+
+impl IdentityProvider for BiomeRoleIdentityProvider {
+    fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError> {
+        self.identity_provider.get_identity(request)
+    }
+
+    fn has_permission(&self, identity: &Identity, permission: &str) -> Result<bool, IdentityError> {
+        match identity {
+            Identity::User(user_id) => Ok(self
+                .role_store
+                .list_user_roles(user_id)
+                .map_err(|err| IdentityError(format!("failed to list user roles: {}", err)))?
+                .iter()
+                .any(|role| role.permissions.iter().any(|granted| granted == permission))),
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Returns a guard that requires the role read permission for `GET` requests and the role write
+/// permission for all other requests. The authorizer should grant users the permissions of their
+/// roles, as one using `BiomeRoleIdentityProvider` does.
+fn role_permission_guard(authorizer: &Arc<Authorizer>) -> PermissionGuard {
+    PermissionGuard::new(
+        authorizer.clone(),
+        BIOME_ROLE_READ_PERMISSION,
+        BIOME_ROLE_WRITE_PERMISSION,
+    )
+}
+
+/// Defines a REST endpoint for listing and creating roles
+///
+/// `POST` payloads should be in the JSON format:
+///   {
+///       "name": <name of the role>,
+///       "permissions": [<permission>, ...]
+///   }
+pub fn make_roles_route(
+    role_store: Arc<dyn RoleStore>,
+    authorizer: &Arc<Authorizer>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/roles")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_ROLES_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(role_permission_guard(authorizer))
+        .add_method(Method::Get, handle_list_roles(role_store.clone()))
+        .add_method(
+            Method::Post,
+            handle_add_role(
+                role_store,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
+        )
+}
+
+/// Defines a REST endpoint for fetching and removing a role
+pub fn make_role_route(
+    role_store: Arc<dyn RoleStore>,
+    authorizer: &Arc<Authorizer>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/roles/{name}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_ROLES_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(role_permission_guard(authorizer))
+        .add_method(Method::Get, handle_fetch_role(role_store.clone()))
+        .add_method(
+            Method::Delete,
+            handle_remove_role(
+                role_store,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
+        )
+}
+
+/// Defines a REST endpoint for listing the roles assigned to a user. Users may always list their
+/// own roles.
+pub fn make_user_roles_route(
+    role_store: Arc<dyn RoleStore>,
+    authorizer: &Arc<Authorizer>,
+) -> Resource {
+    Resource::build("/biome/users/{id}/roles")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_ROLES_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(role_permission_guard(authorizer).with_owner_read_access("id"))
+        .add_method(Method::Get, handle_list_user_roles(role_store))
+}
+
+/// Defines a REST endpoint for assigning a role to, and removing a role from, a user
+pub fn make_user_role_route(
+    role_store: Arc<dyn RoleStore>,
+    authorizer: &Arc<Authorizer>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/users/{id}/roles/{role}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_ROLES_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(role_permission_guard(authorizer))
+        .add_method(
+            Method::Put,
+            handle_assign_role(
+                role_store.clone(),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            ),
+        )
+        .add_method(
+            Method::Delete,
+            handle_unassign_role(
+                role_store,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
+        )
+}
+
+/// Defines a REST endpoint method to list all roles
+fn handle_list_roles(role_store: Arc<dyn RoleStore>) -> HandlerFunction {
+    Box::new(move |_, _| match role_store.list_roles() {
+        Ok(roles) => Box::new(
+            HttpResponse::Ok()
+                .json(json!({
+                    "data": roles.iter().map(ResponseRole::from).collect::<Vec<_>>()
+                }))
+                .into_future(),
+        ),
+        Err(err) => Box::new(role_store_error_response(err).into_future()),
+    })
+}
+
+/// Defines a REST endpoint method to create a role
+fn handle_add_role(
+    role_store: Arc<dyn RoleStore>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> HandlerFunction {
+    Box::new(move |request, payload| {
+        let role_store = role_store.clone();
+        #[cfg(feature = "audit")]
+        let audit_log = audit_log.clone();
+        #[cfg(feature = "audit")]
+        let actor = request_actor(&request);
+        #[cfg(not(feature = "audit"))]
+        let _ = request;
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let new_role = match serde_json::from_slice::<NewRole>(&bytes) {
+                Ok(val) => val,
+                Err(err) => {
+                    debug!("Error parsing payload {}", err);
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Failed to parse payload: {}",
+                            err
+                        )))
+                        .into_future();
+                }
+            };
+
+            if let Err(msg) = validate_new_role(&new_role) {
+                return HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(&msg))
+                    .into_future();
+            }
+
+            let role = Role::new(&new_role.name, new_role.permissions);
+            let result = role_store.add_role(role.clone());
+            #[cfg(feature = "audit")]
+            audit_log.record_result(&actor, "biome.role.add", &role.name, &result);
+
+            match result {
+                Ok(()) => HttpResponse::Ok()
+                    .json(json!({
+                        "message": "Role added successfully",
+                        "data": ResponseRole::from(&role)
+                    }))
+                    .into_future(),
+                Err(err) => role_store_error_response(err).into_future(),
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint method to fetch a role
+fn handle_fetch_role(role_store: Arc<dyn RoleStore>) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let name = match request.match_info().get("name") {
+            Some(name) => name.to_owned(),
+            None => return Box::new(missing_path_parameter("role name").into_future()),
+        };
+
+        match role_store.fetch_role(&name) {
+            Ok(role) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "data": ResponseRole::from(&role) }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(role_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to remove a role
+fn handle_remove_role(
+    role_store: Arc<dyn RoleStore>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let name = match request.match_info().get("name") {
+            Some(name) => name.to_owned(),
+            None => return Box::new(missing_path_parameter("role name").into_future()),
+        };
+
+        let result = role_store.remove_role(&name);
+        #[cfg(feature = "audit")]
+        audit_log.record_result(
+            &request_actor(&request),
+            "biome.role.remove",
+            &name,
+            &result,
+        );
+
+        match result {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Role removed successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(role_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to list the roles of a user
+fn handle_list_user_roles(role_store: Arc<dyn RoleStore>) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let user_id = match request.match_info().get("id") {
+            Some(id) => id.to_owned(),
+            None => return Box::new(missing_path_parameter("user ID").into_future()),
+        };
+
+        match role_store.list_user_roles(&user_id) {
+            Ok(roles) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({
+                        "data": roles.iter().map(ResponseRole::from).collect::<Vec<_>>()
+                    }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(role_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to assign a role to a user. The first role administrators are
+/// set up by the node operator with `bootstrap_role_admins`.
+fn handle_assign_role(
+    role_store: Arc<dyn RoleStore>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let (user_id, role_name) = match (
+            request.match_info().get("id"),
+            request.match_info().get("role"),
+        ) {
+            (Some(id), Some(role)) => (id.to_owned(), role.to_owned()),
+            _ => return Box::new(missing_path_parameter("user ID or role name").into_future()),
+        };
+
+        let result = role_store.assign_role(&user_id, &role_name);
+        #[cfg(feature = "audit")]
+        audit_log.record_result(
+            &request_actor(&request),
+            "biome.role.assign",
+            &role_target(&user_id, &role_name),
+            &result,
+        );
+
+        match result {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Role assigned successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(role_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to remove a role from a user
+fn handle_unassign_role(
+    role_store: Arc<dyn RoleStore>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let (user_id, role_name) = match (
+            request.match_info().get("id"),
+            request.match_info().get("role"),
+        ) {
+            (Some(id), Some(role)) => (id.to_owned(), role.to_owned()),
+            _ => return Box::new(missing_path_parameter("user ID or role name").into_future()),
+        };
+
+        let result = role_store.unassign_role(&user_id, &role_name);
+        #[cfg(feature = "audit")]
+        audit_log.record_result(
+            &request_actor(&request),
+            "biome.role.unassign",
+            &role_target(&user_id, &role_name),
+            &result,
+        );
+
+        match result {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Role removed from user successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(role_store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Returns the audit target of a change to a user's roles: `<user id>/<role name>`
+#[cfg(feature = "audit")]
+fn role_target(user_id: &str, role_name: &str) -> String {
+    format!("{}/{}", user_id, role_name)
+}
+
+/// Role names and permissions are stored comma-separated in access token claims, so they may not
+/// contain commas.
+fn validate_new_role(new_role: &NewRole) -> Result<(), String> {
+    if new_role.name.is_empty() || new_role.name.contains(',') || new_role.name.contains('/') {
+        return Err(format!("Invalid role name: {:?}", new_role.name));
+    }
+    if let Some(permission) = new_role
+        .permissions
+        .iter()
+        .find(|permission| permission.is_empty() || permission.contains(','))
+    {
+        return Err(format!("Invalid permission: {:?}", permission));
+    }
+    Ok(())
+}
+
+fn role_store_error_response(err: RoleStoreError) -> HttpResponse {
+    match err {
+        RoleStoreError::NotFoundError(msg) | RoleStoreError::UserDoesNotExistError(msg) => {
+            debug!("Role request failed: {}", msg);
+            HttpResponse::NotFound().json(ErrorResponse::not_found(&msg))
+        }
+        RoleStoreError::DuplicateRoleError(msg) => {
+            debug!("Role request failed: {}", msg);
+            HttpResponse::BadRequest().json(ErrorResponse::bad_request(&msg))
+        }
+        _ => {
+            error!("Role request failed: {}", err);
+            HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+        }
+    }
+}
+
+fn missing_path_parameter(name: &str) -> HttpResponse {
+    error!("{} is not in path request", name);
+    HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+        "Failed to process request: no {}",
+        name
+    )))
+}
@@ -0,0 +1,413 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use jsonwebtoken::Validation;
+use uuid::Uuid;
+
+use crate::actix_web::{HttpRequest, HttpResponse};
+#[cfg(feature = "audit")]
+use crate::audit::{user_actor, AuditLog};
+use crate::biome::refresh_tokens::store::{
+    RefreshTokenError, RefreshTokenSession, RefreshTokenStore,
+};
+use crate::biome::rest_api::resources::{
+    authorize::AuthorizationResult, sessions::ResponseSession,
+};
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::IntoFuture;
+use crate::protocol;
+use crate::rest_api::{
+    get_authorization_token,
+    secrets::SecretManager,
+    sessions::{default_validation, AccessTokenDenylist, Claims},
+    Continuation, ErrorResponse, HandlerFunction, Method, ProtocolVersionRangeGuard, RequestGuard,
+    Resource,
+};
+
+use super::authorize::{authorize_user, validate_claims};
+
+/// The longest client description that is stored with a session
+const MAX_CLIENT_INFO_LENGTH: usize = 256;
+
+/// Rejects requests whose access token belongs to a revoked session.
+///
+/// Requests without an access token, or with one that is otherwise invalid, are passed on so
+/// that the resource can respond as it normally would.
+#[derive(Clone)]
+pub struct RevokedSessionGuard {
+    secret_manager: Arc<dyn SecretManager>,
+    validation: Validation,
+    denylist: AccessTokenDenylist,
+}
+
+impl RevokedSessionGuard {
+    /// Creates a new guard
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_manager`: the SecretManager used to verify biome access tokens
+    /// * `issuer`: the issuer of biome access tokens
+    /// * `denylist`: the sessions whose access tokens are no longer accepted
+    pub fn new(
+        secret_manager: Arc<dyn SecretManager>,
+        issuer: &str,
+        denylist: AccessTokenDenylist,
+    ) -> Self {
+        Self {
+            secret_manager,
+            validation: default_validation(issuer),
+            denylist,
+        }
+    }
+}
+
+impl RequestGuard for RevokedSessionGuard {
+    fn evaluate(&self, req: &HttpRequest) -> Continuation {
+        let token = match get_authorization_token(req) {
+            Ok(token) => token,
+            Err(_) => return Continuation::Continue,
+        };
+
+        match validate_claims(&token, &self.secret_manager, &self.validation) {
+            AuthorizationResult::Authorized(ref claims) if self.denylist.is_denied(claims) => {
+                Continuation::terminate(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized("Session has been revoked"))
+                        .into_future(),
+                )
+            }
+            _ => Continuation::Continue,
+        }
+    }
+}
+
+/// Returns a new, unique session ID
+pub(super) fn new_session_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Returns a description of the client that made the request, taken from its user agent
+pub(super) fn client_info(request: &HttpRequest) -> Option<String> {
+    request
+        .headers()
+        .get("User-Agent")
+        .and_then(|value| value.to_str().ok())
+        .map(|user_agent| user_agent.chars().take(MAX_CLIENT_INFO_LENGTH).collect())
+}
+
+/// Builds the session for a refresh token issued to a user
+pub(super) fn new_session(
+    session_id: &str,
+    user_id: &str,
+    refresh_token: &str,
+    client_info: Option<String>,
+) -> RefreshTokenSession {
+    RefreshTokenSession {
+        session_id: session_id.to_string(),
+        user_id: user_id.to_string(),
+        token: refresh_token.to_string(),
+        created: SystemTime::now(),
+        client_info,
+    }
+}
+
+/// Denies the access tokens of a session until the last one issued for it has expired
+pub(super) fn deny_session(
+    denylist: &AccessTokenDenylist,
+    rest_config: &BiomeRestConfig,
+    session_id: &str,
+) {
+    denylist.deny_session(
+        session_id,
+        SystemTime::now() + rest_config.access_token_duration(),
+    );
+}
+
+/// Defines REST endpoints for listing and revoking all of a user's sessions
+///
+/// `GET` returns the user's sessions in the JSON format:
+///   {
+///       "data": [
+///           {
+///               "session_id": <ID of the session>,
+///               "created": <seconds since the Unix epoch>,
+///               "client_info": <user agent of the client that logged in, if known>,
+///               "current": <true if the request was made with this session's access token>
+///           }
+///       ]
+///   }
+///
+/// `DELETE` revokes all of the user's sessions, including the current one.
+pub fn make_sessions_route(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+    denylist: AccessTokenDenylist,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/users/{id}/sessions")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_SESSIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Get,
+            handle_list_sessions(
+                refresh_token_store.clone(),
+                rest_config.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Delete,
+            handle_revoke_sessions(
+                refresh_token_store,
+                rest_config,
+                secret_manager,
+                denylist,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
+        )
+}
+
+/// Defines a REST endpoint for revoking one of a user's sessions
+pub fn make_session_route(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+    denylist: AccessTokenDenylist,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/biome/users/{id}/sessions/{session_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_SESSIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Delete,
+            handle_revoke_session(
+                refresh_token_store,
+                rest_config,
+                secret_manager,
+                denylist,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
+        )
+}
+
+fn handle_list_sessions(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let claims = match authorize_session_request(&request, &secret_manager, &rest_config) {
+            Ok(claims) => claims,
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        match refresh_token_store.list_sessions(&claims.user_id()) {
+            Ok(sessions) => {
+                let current_session_id = claims.session_id();
+                let sessions = sessions
+                    .iter()
+                    .map(|session| ResponseSession::new(session, current_session_id.as_deref()))
+                    .collect::<Vec<_>>();
+                Box::new(
+                    HttpResponse::Ok()
+                        .json(json!({ "data": sessions }))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to list sessions: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+fn handle_revoke_sessions(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+    denylist: AccessTokenDenylist,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let user_id = match authorize_session_request(&request, &secret_manager, &rest_config) {
+            Ok(claims) => claims.user_id(),
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        let sessions = match refresh_token_store.list_sessions(&user_id) {
+            Ok(sessions) => sessions,
+            Err(err) => {
+                error!("Failed to list sessions: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        // A user without any sessions has no refresh tokens to remove
+        let result = match refresh_token_store.remove_token(&user_id) {
+            Err(RefreshTokenError::NotFoundError(_)) => Ok(()),
+            result => result,
+        };
+        #[cfg(feature = "audit")]
+        audit_log.record_result(
+            &user_actor(&user_id),
+            "biome.sessions.revoke",
+            &user_id,
+            &result,
+        );
+
+        match result {
+            Ok(()) => {
+                for session in &sessions {
+                    deny_session(&denylist, &rest_config, &session.session_id);
+                }
+                Box::new(
+                    HttpResponse::Ok()
+                        .json(json!({ "message": "Sessions revoked" }))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to remove sessions: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+fn handle_revoke_session(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+    denylist: AccessTokenDenylist,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let user_id = match authorize_session_request(&request, &secret_manager, &rest_config) {
+            Ok(claims) => claims.user_id(),
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        let session_id = match request.match_info().get("session_id") {
+            Some(session_id) => session_id.to_string(),
+            None => {
+                error!("Session ID is not in path request");
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        // Sessions of other users are reported as missing, so their IDs cannot be probed
+        let result = refresh_token_store
+            .fetch_session(&session_id)
+            .and_then(|session| {
+                if session.user_id == user_id {
+                    refresh_token_store.remove_session(&session_id)
+                } else {
+                    Err(RefreshTokenError::NotFoundError(format!(
+                        "Session {} not found",
+                        session_id
+                    )))
+                }
+            });
+        #[cfg(feature = "audit")]
+        audit_log.record_result(
+            &user_actor(&user_id),
+            "biome.session.revoke",
+            &session_id,
+            &result,
+        );
+
+        match result {
+            Ok(()) => {
+                deny_session(&denylist, &rest_config, &session_id);
+                Box::new(
+                    HttpResponse::Ok()
+                        .json(json!({ "message": "Session revoked" }))
+                        .into_future(),
+                )
+            }
+            Err(RefreshTokenError::NotFoundError(_)) => Box::new(
+                HttpResponse::NotFound()
+                    .json(ErrorResponse::not_found(&format!(
+                        "Session not found: {}",
+                        session_id
+                    )))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to remove session: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Authorizes the caller and checks that the sessions in the path are the caller's own.
+fn authorize_session_request(
+    request: &HttpRequest,
+    secret_manager: &Arc<dyn SecretManager>,
+    rest_config: &BiomeRestConfig,
+) -> Result<Claims, HttpResponse> {
+    let validation = default_validation(&rest_config.issuer());
+    let claims = match authorize_user(request, secret_manager, &validation) {
+        AuthorizationResult::Authorized(claims) => claims,
+        AuthorizationResult::Unauthorized(msg) => {
+            return Err(HttpResponse::Unauthorized().json(ErrorResponse::unauthorized(&msg)))
+        }
+        AuthorizationResult::Failed => {
+            return Err(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    };
+
+    match request.match_info().get("id") {
+        Some(user_id) if user_id == claims.user_id() => Ok(claims),
+        Some(_) => Err(HttpResponse::Forbidden().json(ErrorResponse::forbidden(
+            "Users may only manage their own sessions",
+        ))),
+        None => {
+            error!("User ID is not in path request");
+            Err(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    }
+}
@@ -15,6 +15,10 @@
 use std::sync::Arc;
 
 use crate::actix_web::HttpResponse;
+#[cfg(feature = "biome-roles")]
+use crate::biome::rest_api::actix::roles::with_role_claims;
+#[cfg(feature = "biome-roles")]
+use crate::biome::roles::store::RoleStore;
 use crate::biome::{
     refresh_tokens::store::{RefreshTokenError, RefreshTokenStore},
     rest_api::{
@@ -45,8 +49,12 @@ use crate::rest_api::{
 ///   {
 ///     "token": <new auth token>
 ///   }
+///
+/// When the `biome-sessions` feature is enabled, the refresh token must belong to the session of
+/// the access token, and the new access token is issued for the same session.
 pub fn make_token_route(
     refresh_token_store: Arc<dyn RefreshTokenStore>,
+    #[cfg(feature = "biome-roles")] role_store: Arc<dyn RoleStore>,
     secret_manager: Arc<dyn SecretManager>,
     refresh_token_secret_manager: Arc<dyn SecretManager>,
     token_issuer: Arc<AccessTokenIssuer>,
@@ -65,6 +73,8 @@ pub fn make_token_route(
             let refresh_token_store = refresh_token_store.clone();
             let token_issuer = token_issuer.clone();
             let rest_config = rest_config.clone();
+            #[cfg(feature = "biome-roles")]
+            let role_store = role_store.clone();
             Box::new(into_bytes(payload).and_then(move |bytes| {
                 let claims = match authorize_user(&req, &secret_manager, &validation) {
                     AuthorizationResult::Authorized(claims) => claims,
@@ -93,8 +103,29 @@ pub fn make_token_route(
                     }
                 };
 
-                let refresh_token_from_db = match refresh_token_store.fetch_token(&claims.user_id())
-                {
+                #[cfg(feature = "biome-sessions")]
+                let session_id = claims.session_id();
+                #[cfg(feature = "biome-sessions")]
+                let stored_token =
+                    match session_id {
+                        Some(ref session_id) => refresh_token_store
+                            .fetch_session(session_id)
+                            .and_then(|session| {
+                                if session.user_id == claims.user_id() {
+                                    Ok(session.token)
+                                } else {
+                                    Err(RefreshTokenError::NotFoundError(format!(
+                                        "Session {} not found",
+                                        session_id
+                                    )))
+                                }
+                            }),
+                        None => refresh_token_store.fetch_token(&claims.user_id()),
+                    };
+                #[cfg(not(feature = "biome-sessions"))]
+                let stored_token = refresh_token_store.fetch_token(&claims.user_id());
+
+                let refresh_token_from_db = match stored_token {
                     Ok(token) => token,
                     Err(RefreshTokenError::NotFoundError(msg)) => {
                         return HttpResponse::Forbidden()
@@ -122,7 +153,14 @@ pub fn make_token_route(
                 ) {
                     AuthorizationResult::Authorized(_) => (),
                     AuthorizationResult::Unauthorized(msg) => {
-                        if let Err(err) = refresh_token_store.remove_token(&claims.user_id()) {
+                        #[cfg(feature = "biome-sessions")]
+                        let result = match session_id {
+                            Some(ref session_id) => refresh_token_store.remove_session(session_id),
+                            None => refresh_token_store.remove_token(&claims.user_id()),
+                        };
+                        #[cfg(not(feature = "biome-sessions"))]
+                        let result = refresh_token_store.remove_token(&claims.user_id());
+                        if let Err(err) = result {
                             error!("Failed to delete refresh token {}", err);
                             return HttpResponse::InternalServerError()
                                 .json(ErrorResponse::internal_error())
@@ -140,6 +178,24 @@ pub fn make_token_route(
                     }
                 }
                 let claim_builder = ClaimsBuilder::default();
+                #[cfg(feature = "biome-sessions")]
+                let claim_builder = match session_id {
+                    Some(ref session_id) => claim_builder.with_session_id(session_id),
+                    None => claim_builder,
+                };
+                // Roles are looked up again so that the new token reflects any changes to the
+                // user's roles since the last token was issued
+                #[cfg(feature = "biome-roles")]
+                let claim_builder =
+                    match with_role_claims(claim_builder, &*role_store, &claims.user_id()) {
+                        Ok(claim_builder) => claim_builder,
+                        Err(err) => {
+                            error!("Failed to fetch user roles {}", err);
+                            return HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future();
+                        }
+                    };
                 let claim = match claim_builder
                     .with_user_id(&claims.user_id())
                     .with_issuer(&rest_config.issuer())
@@ -15,6 +15,8 @@
 use std::sync::Arc;
 
 use crate::actix_web::HttpResponse;
+#[cfg(feature = "audit")]
+use crate::audit::{user_actor, AuditLog, AuditOutcome};
 use crate::biome::credentials::store::{
     CredentialsBuilder, CredentialsStore, CredentialsStoreError,
 };
@@ -28,6 +30,8 @@ use crate::rest_api::{
     ProtocolVersionRangeGuard, Resource,
 };
 
+#[cfg(feature = "biome-key-encryption")]
+use crate::biome::key_management::KeyEncryptor;
 #[cfg(feature = "biome-key-management")]
 use crate::biome::key_management::{
     store::{KeyStore, KeyStoreError},
@@ -35,7 +39,13 @@ use crate::biome::key_management::{
 };
 use crate::rest_api::secrets::SecretManager;
 
+#[cfg(feature = "biome-account-security")]
+use crate::biome::rest_api::actix::account_security::{
+    check_lockout, check_password_policy, record_login_result,
+};
 use crate::biome::rest_api::actix::authorize::authorize_user;
+#[cfg(feature = "biome-key-encryption")]
+use crate::biome::rest_api::actix::key_encryption::key_from_request;
 #[cfg(feature = "biome-key-management")]
 use crate::biome::rest_api::resources::{key_management::ResponseKey, user::ModifyUser};
 
@@ -68,6 +78,8 @@ pub fn make_user_routes(
     credentials_store: Arc<dyn CredentialsStore>,
     user_store: Arc<dyn UserStore>,
     key_store: Arc<dyn KeyStore>,
+    #[cfg(feature = "biome-key-encryption")] key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Resource {
     Resource::build("/biome/users/{id}")
         .add_request_guard(ProtocolVersionRangeGuard::new(
@@ -81,12 +93,22 @@ pub fn make_user_routes(
                 rest_config.clone(),
                 secret_manager.clone(),
                 key_store,
+                #[cfg(feature = "biome-key-encryption")]
+                key_encryptor,
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
             ),
         )
         .add_method(Method::Get, add_fetch_user_method(credentials_store))
         .add_method(
             Method::Delete,
-            add_delete_user_method(rest_config, secret_manager, user_store),
+            add_delete_user_method(
+                rest_config,
+                secret_manager,
+                user_store,
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
         )
 }
 
@@ -144,16 +166,28 @@ fn add_fetch_user_method(credentials_store: Arc<dyn CredentialsStore>) -> Handle
 ///           { ... }, { ... }, ...
 ///       ]
 ///   }
+///
+/// When the `biome-key-encryption` feature is enabled, a new key pair may provide an unencrypted
+/// `private_key` instead, which the server encrypts. Keys encrypted by the server and retired keys
+/// are kept, since they do not depend on the user's password.
 fn add_modify_user_method(
     credentials_store: Arc<dyn CredentialsStore>,
     rest_config: Arc<BiomeRestConfig>,
     secret_manager: Arc<dyn SecretManager>,
     key_store: Arc<dyn KeyStore>,
+    #[cfg(feature = "biome-key-encryption")] key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> HandlerFunction {
     let ecryption_cost = rest_config.password_encryption_cost();
     Box::new(move |request, payload| {
         let credentials_store = credentials_store.clone();
+        #[cfg(feature = "biome-account-security")]
+        let rest_config = rest_config.clone();
         let key_store = key_store.clone();
+        #[cfg(feature = "biome-key-encryption")]
+        let key_encryptor = key_encryptor.clone();
+        #[cfg(feature = "audit")]
+        let audit_log = audit_log.clone();
         let validation = default_validation(&rest_config.issuer());
         let user_id = match authorize_user(&request, &secret_manager, &validation) {
             AuthorizationResult::Authorized(claims) => claims.user_id(),
@@ -186,6 +220,23 @@ fn add_modify_user_method(
                         .into_future();
                 }
             };
+            #[cfg(feature = "biome-key-encryption")]
+            let new_key_pairs = {
+                let mut new_key_pairs = Vec::with_capacity(modify_user.new_key_pairs.len());
+                for new_key in &modify_user.new_key_pairs {
+                    match key_from_request(
+                        new_key,
+                        &user_id,
+                        &*key_store,
+                        key_encryptor.as_ref().map(|encryptor| &**encryptor),
+                    ) {
+                        Ok(key) => new_key_pairs.push(key),
+                        Err(response) => return response.into_future(),
+                    }
+                }
+                new_key_pairs
+            };
+            #[cfg(not(feature = "biome-key-encryption"))]
             let new_key_pairs: Vec<Key> = modify_user
                 .new_key_pairs
                 .iter()
@@ -221,10 +272,40 @@ fn add_modify_user_method(
                         }
                     }
                 };
-            match credentials.verify_password(&modify_user.hashed_password) {
+            #[cfg(feature = "biome-account-security")]
+            {
+                if let Err(response) = check_lockout(&*credentials_store, &credentials.user_id) {
+                    return response.into_future();
+                }
+            }
+
+            let is_valid = credentials.verify_password(&modify_user.hashed_password);
+
+            #[cfg(feature = "biome-account-security")]
+            {
+                if let Ok(is_valid) = is_valid {
+                    if let Err(response) = record_login_result(
+                        &*credentials_store,
+                        &rest_config,
+                        &credentials.user_id,
+                        is_valid,
+                    ) {
+                        return response.into_future();
+                    }
+                }
+            }
+
+            match is_valid {
                 Ok(true) => {
                     let new_password = match modify_user.new_password {
                         Some(val) => {
+                            #[cfg(feature = "biome-account-security")]
+                            {
+                                if let Err(response) = check_password_policy(&rest_config, &val) {
+                                    return response.into_future();
+                                }
+                            }
+
                             // Use credentials builder to salt password
                             match CredentialsBuilder::default()
                                 .with_user_id(&credentials.user_id)
@@ -246,16 +327,46 @@ fn add_modify_user_method(
                         None => credentials.password,
                     };
 
+                    #[cfg(feature = "biome-key-encryption")]
+                    let new_key_pairs = match key_store.list_keys(Some(&user_id)) {
+                        Ok(existing_keys) => {
+                            let kept_keys = existing_keys
+                                .into_iter()
+                                .filter(|key| {
+                                    (key.server_encrypted || key.is_retired())
+                                        && !new_key_pairs
+                                            .iter()
+                                            .any(|new_key| new_key.public_key == key.public_key)
+                                })
+                                .collect::<Vec<Key>>();
+                            let mut new_key_pairs = new_key_pairs;
+                            new_key_pairs.extend(kept_keys);
+                            new_key_pairs
+                        }
+                        Err(err) => {
+                            error!("Failed to fetch keys: {}", err);
+                            return HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future();
+                        }
+                    };
+
                     let response_keys = new_key_pairs
                         .iter()
                         .map(ResponseKey::from)
                         .collect::<Vec<ResponseKey>>();
 
-                    match key_store.update_keys_and_password(
+                    let result =
+                        key_store.update_keys_and_password(&user_id, &new_password, &new_key_pairs);
+                    #[cfg(feature = "audit")]
+                    audit_log.record_result(
+                        &user_actor(&user_id),
+                        "biome.user.update",
                         &user_id,
-                        &new_password,
-                        &new_key_pairs,
-                    ) {
+                        &result,
+                    );
+
+                    match result {
                         Ok(()) => HttpResponse::Ok()
                             .json(json!({
                                 "message": "Credentials and key updated successfully",
@@ -275,9 +386,19 @@ fn add_modify_user_method(
                         },
                     }
                 }
-                Ok(false) => HttpResponse::BadRequest()
-                    .json(ErrorResponse::bad_request("Invalid password"))
-                    .into_future(),
+                Ok(false) => {
+                    #[cfg(feature = "audit")]
+                    audit_log.record(
+                        &user_actor(&user_id),
+                        "biome.user.update",
+                        &user_id,
+                        AuditOutcome::Failure("Invalid password".into()),
+                    );
+
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("Invalid password"))
+                        .into_future()
+                }
                 Err(err) => {
                     error!("Failed to verify password {}", err);
                     HttpResponse::InternalServerError()
@@ -294,6 +415,7 @@ fn add_delete_user_method(
     rest_config: Arc<BiomeRestConfig>,
     secret_manager: Arc<dyn SecretManager>,
     user_store: Arc<dyn UserStore>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> HandlerFunction {
     Box::new(move |request, _| {
         let user_store = user_store.clone();
@@ -316,7 +438,16 @@ fn add_delete_user_method(
             }
         };
 
-        Box::new(match user_store.remove_user(&user_id) {
+        let result = user_store.remove_user(&user_id);
+        #[cfg(feature = "audit")]
+        audit_log.record_result(
+            &user_actor(&user_id),
+            "biome.user.remove",
+            &user_id,
+            &result,
+        );
+
+        Box::new(match result {
             Ok(()) => HttpResponse::Ok()
                 .json(json!({ "message": "User deleted sucessfully" }))
                 .into_future(),
@@ -23,6 +23,8 @@ use crate::rest_api::{
 };
 
 use crate::biome::credentials::store::{CredentialsStore, CredentialsStoreError};
+#[cfg(feature = "biome-account-security")]
+use crate::biome::rest_api::actix::account_security::{check_lockout, record_login_result};
 use crate::biome::rest_api::config::BiomeRestConfig;
 
 use super::super::resources::authorize::AuthorizationResult;
@@ -92,7 +94,33 @@ pub fn make_verify_route(
                 let validation = default_validation(&rest_config.issuer());
                 match authorize_user(&request, &secret_manager, &validation) {
                     AuthorizationResult::Authorized(_) => {
-                        match credentials.verify_password(&username_password.hashed_password) {
+                        #[cfg(feature = "biome-account-security")]
+                        {
+                            if let Err(response) =
+                                check_lockout(&*credentials_store, &credentials.user_id)
+                            {
+                                return response.into_future();
+                            }
+                        }
+
+                        let is_valid =
+                            credentials.verify_password(&username_password.hashed_password);
+
+                        #[cfg(feature = "biome-account-security")]
+                        {
+                            if let Ok(is_valid) = is_valid {
+                                if let Err(response) = record_login_result(
+                                    &*credentials_store,
+                                    &rest_config,
+                                    &credentials.user_id,
+                                    is_valid,
+                                ) {
+                                    return response.into_future();
+                                }
+                            }
+                        }
+
+                        match is_valid {
                             Ok(true) => HttpResponse::Ok()
                                 .json(json!(
                                     {
@@ -17,11 +17,19 @@ use std::time::Duration;
 use super::error::BiomeRestConfigBuilderError;
 #[cfg(feature = "biome-credentials")]
 use crate::biome::credentials::store::PasswordEncryptionCost;
+#[cfg(feature = "biome-account-security")]
+use crate::biome::credentials::PasswordPolicy;
 
 const DEFAULT_ISSUER: &str = "self-issued";
 const DEFAULT_DURATION: u64 = 5400; // in seconds = 90 minutes
 #[cfg(feature = "biome-credentials")]
 const DEFAULT_REFRESH_DURATION: u64 = 5_184_000; // in seconds = 60 days
+#[cfg(feature = "biome-account-security")]
+const DEFAULT_MAX_FAILED_LOGINS: u32 = 5;
+#[cfg(feature = "biome-account-security")]
+const DEFAULT_LOCKOUT_DURATION: u64 = 900; // in seconds = 15 minutes
+#[cfg(feature = "biome-account-security")]
+const DEFAULT_PASSWORD_RESET_TOKEN_DURATION: u64 = 3600; // in seconds = 1 hour
 
 /// Configuration for Biome REST resources
 #[derive(Deserialize, Debug)]
@@ -36,6 +44,18 @@ pub struct BiomeRestConfig {
     #[cfg(feature = "biome-credentials")]
     /// Cost for encrypting user's password
     password_encryption_cost: PasswordEncryptionCost,
+    /// Requirements for new passwords
+    #[cfg(feature = "biome-account-security")]
+    password_policy: PasswordPolicy,
+    /// Number of consecutive failed logins before a user is locked out
+    #[cfg(feature = "biome-account-security")]
+    max_failed_logins: u32,
+    /// Duration a user is locked out for after too many failed logins
+    #[cfg(feature = "biome-account-security")]
+    lockout_duration: Duration,
+    /// Duration of password reset tokens issued by this service
+    #[cfg(feature = "biome-account-security")]
+    password_reset_token_duration: Duration,
 }
 
 impl BiomeRestConfig {
@@ -64,6 +84,34 @@ impl BiomeRestConfig {
     pub fn password_encryption_cost(&self) -> PasswordEncryptionCost {
         self.password_encryption_cost
     }
+
+    /// Returns the policy new passwords must meet.
+    /// Defaults to a minimum length of 8 characters.
+    #[cfg(feature = "biome-account-security")]
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+
+    /// Returns the number of consecutive failed logins before a user is locked out.
+    /// Defaults to 5.
+    #[cfg(feature = "biome-account-security")]
+    pub fn max_failed_logins(&self) -> u32 {
+        self.max_failed_logins
+    }
+
+    /// Returns the duration a user is locked out for after too many failed logins.
+    /// Defaults to 15 minutes.
+    #[cfg(feature = "biome-account-security")]
+    pub fn lockout_duration(&self) -> Duration {
+        self.lockout_duration
+    }
+
+    /// Returns the duration a password reset token is valid.
+    /// Defaults to 1 hour.
+    #[cfg(feature = "biome-account-security")]
+    pub fn password_reset_token_duration(&self) -> Duration {
+        self.password_reset_token_duration
+    }
 }
 
 /// Builder for BiomeRestConfig
@@ -74,6 +122,14 @@ pub struct BiomeRestConfigBuilder {
     refresh_token_duration: Option<Duration>,
     #[cfg(feature = "biome-credentials")]
     password_encryption_cost: Option<String>,
+    #[cfg(feature = "biome-account-security")]
+    password_policy: Option<PasswordPolicy>,
+    #[cfg(feature = "biome-account-security")]
+    max_failed_logins: Option<u32>,
+    #[cfg(feature = "biome-account-security")]
+    lockout_duration: Option<Duration>,
+    #[cfg(feature = "biome-account-security")]
+    password_reset_token_duration: Option<Duration>,
 }
 
 impl Default for BiomeRestConfigBuilder {
@@ -85,6 +141,16 @@ impl Default for BiomeRestConfigBuilder {
             refresh_token_duration: Some(Duration::from_secs(DEFAULT_REFRESH_DURATION)),
             #[cfg(feature = "biome-credentials")]
             password_encryption_cost: Some("high".to_string()),
+            #[cfg(feature = "biome-account-security")]
+            password_policy: Some(PasswordPolicy::default()),
+            #[cfg(feature = "biome-account-security")]
+            max_failed_logins: Some(DEFAULT_MAX_FAILED_LOGINS),
+            #[cfg(feature = "biome-account-security")]
+            lockout_duration: Some(Duration::from_secs(DEFAULT_LOCKOUT_DURATION)),
+            #[cfg(feature = "biome-account-security")]
+            password_reset_token_duration: Some(Duration::from_secs(
+                DEFAULT_PASSWORD_RESET_TOKEN_DURATION,
+            )),
         }
     }
 }
@@ -99,6 +165,14 @@ impl BiomeRestConfigBuilder {
             refresh_token_duration: None,
             #[cfg(feature = "biome-credentials")]
             password_encryption_cost: None,
+            #[cfg(feature = "biome-account-security")]
+            password_policy: None,
+            #[cfg(feature = "biome-account-security")]
+            max_failed_logins: None,
+            #[cfg(feature = "biome-account-security")]
+            lockout_duration: None,
+            #[cfg(feature = "biome-account-security")]
+            password_reset_token_duration: None,
         }
     }
 
@@ -129,6 +203,34 @@ impl BiomeRestConfigBuilder {
         self
     }
 
+    /// Adds the policy new passwords must meet.
+    #[cfg(feature = "biome-account-security")]
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = Some(policy);
+        self
+    }
+
+    /// Adds the number of consecutive failed logins before a user is locked out.
+    #[cfg(feature = "biome-account-security")]
+    pub fn with_max_failed_logins(mut self, max_failed_logins: u32) -> Self {
+        self.max_failed_logins = Some(max_failed_logins);
+        self
+    }
+
+    /// Adds a lockout duration in seconds.
+    #[cfg(feature = "biome-account-security")]
+    pub fn with_lockout_duration_in_secs(mut self, duration: u64) -> Self {
+        self.lockout_duration = Some(Duration::from_secs(duration));
+        self
+    }
+
+    /// Adds a password reset token duration in seconds.
+    #[cfg(feature = "biome-account-security")]
+    pub fn with_password_reset_token_duration_in_secs(mut self, duration: u64) -> Self {
+        self.password_reset_token_duration = Some(Duration::from_secs(duration));
+        self
+    }
+
     /// Creates a new BiomeRestConfig.
     pub fn build(self) -> Result<BiomeRestConfig, BiomeRestConfigBuilderError> {
         let issuer = self.issuer.unwrap_or_else(|| {
@@ -152,6 +254,30 @@ impl BiomeRestConfigBuilder {
             .parse()
             .map_err(BiomeRestConfigBuilderError::InvalidValue)?;
 
+        #[cfg(feature = "biome-account-security")]
+        let password_policy = self.password_policy.unwrap_or_default();
+
+        #[cfg(feature = "biome-account-security")]
+        let max_failed_logins = self.max_failed_logins.unwrap_or(DEFAULT_MAX_FAILED_LOGINS);
+        #[cfg(feature = "biome-account-security")]
+        {
+            if max_failed_logins == 0 {
+                return Err(BiomeRestConfigBuilderError::InvalidValue(
+                    "max_failed_logins must be greater than 0".to_string(),
+                ));
+            }
+        }
+
+        #[cfg(feature = "biome-account-security")]
+        let lockout_duration = self
+            .lockout_duration
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_LOCKOUT_DURATION));
+
+        #[cfg(feature = "biome-account-security")]
+        let password_reset_token_duration = self
+            .password_reset_token_duration
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_PASSWORD_RESET_TOKEN_DURATION));
+
         Ok(BiomeRestConfig {
             issuer,
             access_token_duration,
@@ -159,6 +285,14 @@ impl BiomeRestConfigBuilder {
             refresh_token_duration,
             #[cfg(feature = "biome-credentials")]
             password_encryption_cost,
+            #[cfg(feature = "biome-account-security")]
+            password_policy,
+            #[cfg(feature = "biome-account-security")]
+            max_failed_logins,
+            #[cfg(feature = "biome-account-security")]
+            lockout_duration,
+            #[cfg(feature = "biome-account-security")]
+            password_reset_token_duration,
         })
     }
 }
@@ -22,6 +22,8 @@ pub enum BiomeRestResourceManagerBuilderError {
     MissingRequiredField(String),
     /// Returned if a required field is missing
     BuildingError(BiomeRestConfigBuilderError),
+    /// Returned if the initial contents of a store could not be written
+    StoreError(String),
 }
 
 impl Error for BiomeRestResourceManagerBuilderError {
@@ -29,6 +31,7 @@ impl Error for BiomeRestResourceManagerBuilderError {
         match self {
             BiomeRestResourceManagerBuilderError::MissingRequiredField(_) => None,
             BiomeRestResourceManagerBuilderError::BuildingError(err) => Some(err),
+            BiomeRestResourceManagerBuilderError::StoreError(_) => None,
         }
     }
 }
@@ -42,6 +45,9 @@ impl fmt::Display for BiomeRestResourceManagerBuilderError {
             BiomeRestResourceManagerBuilderError::BuildingError(ref s) => {
                 write!(f, "failed to build BiomeRestResourceManager: {}", s)
             }
+            BiomeRestResourceManagerBuilderError::StoreError(ref s) => {
+                write!(f, "failed to build BiomeRestResourceManager: {}", s)
+            }
         }
     }
 }
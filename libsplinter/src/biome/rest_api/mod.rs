@@ -52,10 +52,14 @@ mod resources;
 
 use std::sync::Arc;
 
+#[cfg(feature = "audit")]
+use crate::audit::AuditLog;
 #[cfg(feature = "biome-credentials")]
 use crate::biome::refresh_tokens::store::RefreshTokenStore;
 use crate::rest_api::{Resource, RestResourceProvider};
 
+#[cfg(all(feature = "biome-key-encryption", feature = "rest-api-actix",))]
+use self::actix::key_encryption::{make_key_rotation_route, make_private_key_route};
 #[cfg(all(feature = "biome-key-management", feature = "rest-api-actix",))]
 use self::actix::key_management::{
     make_key_management_route, make_key_management_route_with_public_key,
@@ -63,6 +67,8 @@ use self::actix::key_management::{
 
 #[cfg(feature = "biome-key-management")]
 use super::key_management::store::KeyStore;
+#[cfg(feature = "biome-key-encryption")]
+use super::key_management::KeyEncryptor;
 use super::user::store::UserStore;
 
 #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
@@ -72,10 +78,38 @@ use crate::rest_api::secrets::SecretManager;
 pub use config::{BiomeRestConfig, BiomeRestConfigBuilder};
 pub use error::BiomeRestResourceManagerBuilderError;
 
+#[cfg(all(feature = "biome-account-security", feature = "rest-api-actix"))]
+use self::actix::account_security::{make_password_reset_route, make_password_reset_token_route};
+#[cfg(all(
+    feature = "rest-api-keyring",
+    feature = "rest-api-actix",
+    any(feature = "biome-key-management", feature = "biome-credentials")
+))]
+use self::actix::jwks::make_jwks_route;
 #[cfg(all(feature = "rest-api-actix", feature = "biome-credentials"))]
 use self::actix::logout::make_logout_route;
+#[cfg(all(feature = "biome-notifications", feature = "rest-api-actix"))]
+use self::actix::notifications::{
+    make_notifications_ws_route, make_user_notification_route, make_user_notifications_route,
+};
+#[cfg(all(feature = "biome-oauth", feature = "rest-api-actix"))]
+use self::actix::oauth::{make_oauth_callback_route, make_oauth_login_route, PendingLogins};
 #[cfg(all(feature = "biome-credentials", feature = "rest-api-actix"))]
 use self::actix::register::make_register_route;
+#[cfg(all(feature = "biome-roles", feature = "rest-api-actix"))]
+pub use self::actix::roles::BiomeRoleIdentityProvider;
+#[cfg(all(feature = "biome-roles", feature = "rest-api-actix"))]
+use self::actix::roles::{
+    make_role_route, make_roles_route, make_user_role_route, make_user_roles_route,
+};
+#[cfg(all(feature = "biome-roles", feature = "rest-api-actix"))]
+pub use self::actix::roles::{
+    permissions_from_claims, roles_from_claims, PERMISSIONS_CLAIM, ROLES_CLAIM,
+};
+#[cfg(all(feature = "biome-sessions", feature = "rest-api-actix"))]
+pub use self::actix::sessions::RevokedSessionGuard;
+#[cfg(all(feature = "biome-sessions", feature = "rest-api-actix"))]
+use self::actix::sessions::{make_session_route, make_sessions_route};
 #[cfg(all(feature = "biome-credentials", feature = "rest-api-actix"))]
 use self::actix::token::make_token_route;
 #[cfg(all(
@@ -88,7 +122,24 @@ use self::actix::user::make_user_routes;
 use self::actix::{login::make_login_route, user::make_list_route, verify::make_verify_route};
 #[cfg(feature = "biome-credentials")]
 use super::credentials::store::CredentialsStore;
+#[cfg(all(feature = "biome-notifications", feature = "biome-key-management"))]
+use super::notifications::AdminEventNotifier;
+#[cfg(feature = "biome-notifications")]
+use super::notifications::{store::NotificationStore, NotificationManager};
+#[cfg(feature = "biome-oauth")]
+use super::oauth::{store::OAuthUserStore, OpenIdClient};
+#[cfg(feature = "biome-roles")]
+use super::roles::{bootstrap_role_admins, store::RoleStore};
 
+#[cfg(all(
+    feature = "rest-api-auth",
+    any(feature = "biome-key-management", feature = "biome-credentials")
+))]
+use crate::rest_api::auth::identity::JwtIdentityProvider;
+#[cfg(all(feature = "biome-roles", feature = "rest-api-actix"))]
+use crate::rest_api::auth::AuthorizerBuilder;
+#[cfg(feature = "biome-sessions")]
+use crate::rest_api::sessions::AccessTokenDenylist;
 #[allow(unused_imports)]
 use crate::rest_api::sessions::AccessTokenIssuer;
 
@@ -114,6 +165,40 @@ use crate::rest_api::sessions::AccessTokenIssuer;
 /// * `PUT /biome/user/{id}` - Update user with specified ID
 /// * `GET /biome/user/{id}` - Retrieve user with specified ID
 /// * `DELETE /biome/user/{id}` - Remove user with specified ID
+/// * `GET /biome/roles` - List all roles
+/// * `POST /biome/roles` - Create a new role
+/// * `GET /biome/roles/{name}` - Retrieve the role with the specified name
+/// * `DELETE /biome/roles/{name}` - Remove the role with the specified name
+/// * `GET /biome/users/{id}/roles` - List the roles assigned to the user with specified ID
+/// * `PUT /biome/users/{id}/roles/{role}` - Assign a role to the user with specified ID
+/// * `DELETE /biome/users/{id}/roles/{role}` - Remove a role from the user with specified ID
+/// * `POST /biome/users/{id}/password_reset` - Create a one-time password reset token for the user
+///    with specified ID
+/// * `POST /biome/password_reset` - Set a new password using a password reset token
+/// * `GET /biome/users/{id}/notifications` - List the notifications of the user with specified ID
+/// * `PATCH /biome/users/{id}/notifications` - Mark all of the user's notifications as read
+/// * `GET /biome/users/{id}/notifications/{notification_id}` - Retrieve one of the user's
+///    notifications
+/// * `PATCH /biome/users/{id}/notifications/{notification_id}` - Mark one of the user's
+///    notifications as read or unread
+/// * `DELETE /biome/users/{id}/notifications/{notification_id}` - Remove one of the user's
+///    notifications
+/// * `GET /ws/biome/users/{id}/notifications` - Websocket that sends the user's new notifications
+/// * `GET /biome/oauth/login` - Redirects to the OpenID Connect provider to log in, if one is
+///    configured
+/// * `GET /biome/oauth/callback` - Completes a login through the OpenID Connect provider and
+///    returns access tokens and refresh tokens
+/// * `GET /biome/jwks` - Get the public keys that verify access tokens, as a JSON Web Key Set
+/// * `GET /biome/users/{id}/sessions` - List the sessions of the user with specified ID
+/// * `DELETE /biome/users/{id}/sessions` - Revoke all of the user's sessions
+/// * `DELETE /biome/users/{id}/sessions/{session_id}` - Revoke one of the user's sessions
+/// * `POST /biome/keys/{public_key}/rotate` - Retire the key that has `public_key` and add the
+///    key that replaces it
+/// * `GET /biome/keys/{public_key}/private_key` - Retrieve the decrypted private key of a key
+///    that was encrypted by the server
+///
+/// When an audit log is set, logins, registrations, password resets, access to private keys, and
+/// changes to users, keys, roles and sessions are recorded in it.
 pub struct BiomeRestResourceManager {
     #[cfg(feature = "biome-credentials")]
     user_store: Arc<dyn UserStore>,
@@ -129,6 +214,66 @@ pub struct BiomeRestResourceManager {
     refresh_token_store: Arc<dyn RefreshTokenStore>,
     #[cfg(feature = "biome-credentials")]
     credentials_store: Arc<dyn CredentialsStore>,
+    #[cfg(feature = "biome-roles")]
+    role_store: Arc<dyn RoleStore>,
+    #[cfg(feature = "biome-notifications")]
+    notification_manager: NotificationManager,
+    #[cfg(feature = "biome-oauth")]
+    oauth: Option<(Arc<OpenIdClient>, Arc<dyn OAuthUserStore>)>,
+    #[cfg(feature = "biome-sessions")]
+    access_token_denylist: AccessTokenDenylist,
+    #[cfg(feature = "biome-key-encryption")]
+    key_encryptor: Option<Arc<KeyEncryptor>>,
+    #[cfg(feature = "audit")]
+    audit_log: AuditLog,
+}
+
+impl BiomeRestResourceManager {
+    /// Returns the manager used to send notifications to biome users
+    #[cfg(feature = "biome-notifications")]
+    pub fn notification_manager(&self) -> NotificationManager {
+        self.notification_manager.clone()
+    }
+
+    /// Returns an admin service event subscriber that notifies the biome users whose keys
+    /// submitted or voted on a circuit proposal
+    #[cfg(all(feature = "biome-notifications", feature = "biome-key-management"))]
+    pub fn admin_event_notifier(&self) -> Result<AdminEventNotifier, std::io::Error> {
+        AdminEventNotifier::new(self.notification_manager.clone(), self.key_store.clone())
+    }
+
+    /// Returns an identity provider that authenticates requests using the access tokens issued
+    /// by this biome instance, for use with the REST API's `Authorizer`.
+    #[cfg(all(
+        feature = "rest-api-auth",
+        any(feature = "biome-key-management", feature = "biome-credentials")
+    ))]
+    pub fn jwt_identity_provider(&self) -> JwtIdentityProvider {
+        #[allow(unused_mut)]
+        let mut provider = JwtIdentityProvider::new(
+            self.token_secret_manager.clone(),
+            &self.rest_config.issuer(),
+        );
+        #[cfg(feature = "biome-sessions")]
+        {
+            provider = provider.with_denylist(self.access_token_denylist.clone());
+        }
+        provider
+    }
+
+    /// Returns an identity provider that authenticates requests like `jwt_identity_provider`,
+    /// and also grants users the permissions of their roles, so that resources guarded by the
+    /// REST API's `PermissionGuard` accept role permissions.
+    #[cfg(all(feature = "biome-roles", feature = "rest-api-actix"))]
+    pub fn role_identity_provider(&self) -> BiomeRoleIdentityProvider {
+        BiomeRoleIdentityProvider::new(self.jwt_identity_provider(), self.role_store.clone())
+    }
+
+    /// Returns the list of revoked sessions whose access tokens are no longer accepted
+    #[cfg(feature = "biome-sessions")]
+    pub fn access_token_denylist(&self) -> AccessTokenDenylist {
+        self.access_token_denylist.clone()
+    }
 }
 
 impl RestResourceProvider for BiomeRestResourceManager {
@@ -149,6 +294,10 @@ impl RestResourceProvider for BiomeRestResourceManager {
                 self.credentials_store.clone(),
                 self.user_store.clone(),
                 self.key_store.clone(),
+                #[cfg(feature = "biome-key-encryption")]
+                self.key_encryptor.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
             ));
         }
 
@@ -163,14 +312,20 @@ impl RestResourceProvider for BiomeRestResourceManager {
             resources.push(make_login_route(
                 self.credentials_store.clone(),
                 self.refresh_token_store.clone(),
+                #[cfg(feature = "biome-roles")]
+                self.role_store.clone(),
                 self.rest_config.clone(),
                 Arc::new(AccessTokenIssuer::new(
                     self.token_secret_manager.clone(),
                     self.refresh_token_secret_manager.clone(),
                 )),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
             ));
             resources.push(make_token_route(
                 self.refresh_token_store.clone(),
+                #[cfg(feature = "biome-roles")]
+                self.role_store.clone(),
                 self.token_secret_manager.clone(),
                 self.refresh_token_secret_manager.clone(),
                 Arc::new(AccessTokenIssuer::new(
@@ -183,28 +338,204 @@ impl RestResourceProvider for BiomeRestResourceManager {
                 self.refresh_token_store.clone(),
                 self.token_secret_manager.clone(),
                 self.rest_config.clone(),
+                #[cfg(feature = "biome-sessions")]
+                self.access_token_denylist.clone(),
             ));
 
             resources.push(make_register_route(
                 self.credentials_store.clone(),
                 self.user_store.clone(),
                 self.rest_config.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
             ));
         }
 
+        #[cfg(all(
+            feature = "rest-api-keyring",
+            feature = "rest-api-actix",
+            any(feature = "biome-key-management", feature = "biome-credentials")
+        ))]
+        {
+            resources.push(make_jwks_route(self.token_secret_manager.clone()));
+        }
+
         #[cfg(all(feature = "biome-key-management", feature = "rest-api-actix",))]
         {
             resources.push(make_key_management_route(
                 self.rest_config.clone(),
                 self.key_store.clone(),
                 self.token_secret_manager.clone(),
+                #[cfg(feature = "biome-key-encryption")]
+                self.key_encryptor.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
             ));
             resources.push(make_key_management_route_with_public_key(
                 self.rest_config.clone(),
                 self.key_store.clone(),
                 self.token_secret_manager.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-key-encryption", feature = "rest-api-actix",))]
+        {
+            resources.push(make_key_rotation_route(
+                self.rest_config.clone(),
+                self.key_store.clone(),
+                self.token_secret_manager.clone(),
+                self.key_encryptor.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+            resources.push(make_private_key_route(
+                self.rest_config.clone(),
+                self.key_store.clone(),
+                self.token_secret_manager.clone(),
+                self.key_encryptor.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+        }
+
+        // Role management is authorized by the permissions currently granted by the caller's
+        // roles, so that changes to role assignments take effect immediately
+        #[cfg(all(feature = "biome-roles", feature = "rest-api-actix"))]
+        let role_authorizer = Arc::new(
+            AuthorizerBuilder::new()
+                .with_identity_provider(Box::new(self.role_identity_provider()))
+                .build(),
+        );
+
+        #[cfg(all(feature = "biome-roles", feature = "rest-api-actix",))]
+        {
+            resources.push(make_roles_route(
+                self.role_store.clone(),
+                &role_authorizer,
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+            resources.push(make_role_route(
+                self.role_store.clone(),
+                &role_authorizer,
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+            resources.push(make_user_roles_route(
+                self.role_store.clone(),
+                &role_authorizer,
+            ));
+            resources.push(make_user_role_route(
+                self.role_store.clone(),
+                &role_authorizer,
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-account-security", feature = "rest-api-actix",))]
+        {
+            resources.push(make_password_reset_token_route(
+                self.credentials_store.clone(),
+                self.rest_config.clone(),
+                &role_authorizer,
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+            resources.push(make_password_reset_route(
+                self.credentials_store.clone(),
+                self.refresh_token_store.clone(),
+                self.rest_config.clone(),
+                #[cfg(feature = "biome-sessions")]
+                self.access_token_denylist.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-sessions", feature = "rest-api-actix",))]
+        {
+            resources.push(make_sessions_route(
+                self.refresh_token_store.clone(),
+                self.rest_config.clone(),
+                self.token_secret_manager.clone(),
+                self.access_token_denylist.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+            resources.push(make_session_route(
+                self.refresh_token_store.clone(),
+                self.rest_config.clone(),
+                self.token_secret_manager.clone(),
+                self.access_token_denylist.clone(),
+                #[cfg(feature = "audit")]
+                self.audit_log.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-notifications", feature = "rest-api-actix",))]
+        {
+            resources.push(make_user_notifications_route(
+                self.rest_config.clone(),
+                self.notification_manager.store(),
+                self.token_secret_manager.clone(),
+            ));
+            resources.push(make_user_notification_route(
+                self.rest_config.clone(),
+                self.notification_manager.store(),
+                self.token_secret_manager.clone(),
             ));
+            resources.push(make_notifications_ws_route(
+                self.rest_config.clone(),
+                self.notification_manager.clone(),
+                self.token_secret_manager.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-oauth", feature = "rest-api-actix",))]
+        {
+            if let Some((oauth_client, oauth_user_store)) = &self.oauth {
+                let pending_logins = PendingLogins::default();
+                resources.push(make_oauth_login_route(
+                    oauth_client.clone(),
+                    pending_logins.clone(),
+                ));
+                resources.push(make_oauth_callback_route(
+                    oauth_client.clone(),
+                    pending_logins,
+                    oauth_user_store.clone(),
+                    self.user_store.clone(),
+                    self.refresh_token_store.clone(),
+                    #[cfg(feature = "biome-roles")]
+                    self.role_store.clone(),
+                    self.rest_config.clone(),
+                    Arc::new(AccessTokenIssuer::new(
+                        self.token_secret_manager.clone(),
+                        self.refresh_token_secret_manager.clone(),
+                    )),
+                    #[cfg(feature = "audit")]
+                    self.audit_log.clone(),
+                ));
+            }
+        }
+
+        // Access tokens of revoked sessions are rejected by every biome endpoint
+        #[cfg(all(feature = "biome-sessions", feature = "rest-api-actix",))]
+        {
+            resources = resources
+                .into_iter()
+                .map(|resource| {
+                    resource.add_request_guard(RevokedSessionGuard::new(
+                        self.token_secret_manager.clone(),
+                        &self.rest_config.issuer(),
+                        self.access_token_denylist.clone(),
+                    ))
+                })
+                .collect();
         }
+
         resources
     }
 }
@@ -223,6 +554,20 @@ pub struct BiomeRestResourceManagerBuilder {
     refresh_token_store: Option<Arc<dyn RefreshTokenStore>>,
     #[cfg(feature = "biome-credentials")]
     credentials_store: Option<Arc<dyn CredentialsStore>>,
+    #[cfg(feature = "biome-roles")]
+    role_store: Option<Arc<dyn RoleStore>>,
+    #[cfg(feature = "biome-roles")]
+    role_admins: Vec<String>,
+    #[cfg(feature = "biome-key-encryption")]
+    key_encryptor: Option<KeyEncryptor>,
+    #[cfg(feature = "biome-notifications")]
+    notification_store: Option<Arc<dyn NotificationStore>>,
+    #[cfg(feature = "biome-oauth")]
+    oauth_client: Option<OpenIdClient>,
+    #[cfg(feature = "biome-oauth")]
+    oauth_user_store: Option<Arc<dyn OAuthUserStore>>,
+    #[cfg(feature = "audit")]
+    audit_log: Option<AuditLog>,
 }
 
 impl BiomeRestResourceManagerBuilder {
@@ -322,6 +667,103 @@ impl BiomeRestResourceManagerBuilder {
         self
     }
 
+    /// Sets a RoleStore for the BiomeRestResourceManager
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: the RoleStore used to manage roles and look up a user's roles when issuing
+    ///   access tokens
+    #[cfg(feature = "biome-roles")]
+    pub fn with_role_store(
+        mut self,
+        store: impl RoleStore + 'static,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.role_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets the IDs of the users that are assigned the role administrator role when the resource
+    /// manager is built, so that the node operator can set up the first administrators who may
+    /// manage roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_ids`: the IDs of the users to make role administrators
+    #[cfg(feature = "biome-roles")]
+    pub fn with_role_admins(mut self, user_ids: Vec<String>) -> BiomeRestResourceManagerBuilder {
+        self.role_admins = user_ids;
+        self
+    }
+
+    /// Sets the KeyEncryptor used to encrypt private keys on behalf of users. If no encryptor
+    /// is set, clients must encrypt private keys themselves.
+    ///
+    /// # Arguments
+    ///
+    /// * `encryptor`: the KeyEncryptor that holds the node's master key
+    #[cfg(feature = "biome-key-encryption")]
+    pub fn with_key_encryptor(
+        mut self,
+        encryptor: KeyEncryptor,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.key_encryptor = Some(encryptor);
+        self
+    }
+
+    /// Sets a NotificationStore for the BiomeRestResourceManager
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: the NotificationStore used to save notifications and manage the notifications
+    ///   received by users
+    #[cfg(feature = "biome-notifications")]
+    pub fn with_notification_store(
+        mut self,
+        store: impl NotificationStore + 'static,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.notification_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets the OpenID Connect client used to log users in through an external provider. If a
+    /// client is set, an OAuthUserStore must also be set.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: the client for the provider users log in with
+    #[cfg(feature = "biome-oauth")]
+    pub fn with_oauth_client(mut self, client: OpenIdClient) -> BiomeRestResourceManagerBuilder {
+        self.oauth_client = Some(client);
+        self
+    }
+
+    /// Sets an OAuthUserStore for the BiomeRestResourceManager
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: the OAuthUserStore used to link biome users to their accounts at the provider
+    #[cfg(feature = "biome-oauth")]
+    pub fn with_oauth_user_store(
+        mut self,
+        store: impl OAuthUserStore + 'static,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.oauth_user_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets the AuditLog that records logins, registrations, password resets, access to private
+    /// keys, and changes to users, keys, roles and sessions. If no audit log is set, these actions
+    /// are not recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_log`: the AuditLog that actions are recorded in
+    #[cfg(feature = "audit")]
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> BiomeRestResourceManagerBuilder {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
     /// Consumes the builder and returns a BiomeRestResourceManager
     pub fn build(self) -> Result<BiomeRestResourceManager, BiomeRestResourceManagerBuilderError> {
         #[cfg(feature = "biome-credentials")]
@@ -372,6 +814,39 @@ impl BiomeRestResourceManagerBuilder {
             )
         })?;
 
+        #[cfg(feature = "biome-roles")]
+        let role_store = self.role_store.ok_or_else(|| {
+            BiomeRestResourceManagerBuilderError::MissingRequiredField(
+                "Missing role store".to_string(),
+            )
+        })?;
+
+        #[cfg(feature = "biome-roles")]
+        bootstrap_role_admins(&*role_store, &self.role_admins).map_err(|err| {
+            BiomeRestResourceManagerBuilderError::StoreError(format!(
+                "Unable to assign role administrators: {}",
+                err
+            ))
+        })?;
+
+        #[cfg(feature = "biome-notifications")]
+        let notification_store = self.notification_store.ok_or_else(|| {
+            BiomeRestResourceManagerBuilderError::MissingRequiredField(
+                "Missing notification store".to_string(),
+            )
+        })?;
+
+        #[cfg(feature = "biome-oauth")]
+        let oauth = match (self.oauth_client, self.oauth_user_store) {
+            (Some(client), Some(store)) => Some((Arc::new(client), store)),
+            (Some(_), None) => {
+                return Err(BiomeRestResourceManagerBuilderError::MissingRequiredField(
+                    "Missing OAuth user store".to_string(),
+                ))
+            }
+            (None, _) => None,
+        };
+
         Ok(BiomeRestResourceManager {
             #[cfg(feature = "biome-credentials")]
             user_store,
@@ -387,6 +862,22 @@ impl BiomeRestResourceManagerBuilder {
             refresh_token_store,
             #[cfg(feature = "biome-credentials")]
             credentials_store,
+            #[cfg(feature = "biome-roles")]
+            role_store,
+            #[cfg(feature = "biome-notifications")]
+            notification_manager: NotificationManager::new(notification_store),
+            #[cfg(feature = "biome-oauth")]
+            oauth,
+            // The denylist is only kept in memory: when the node restarts, or on other nodes
+            // sharing the same token keys, the access tokens of revoked sessions are accepted
+            // again until they expire. Access tokens are short-lived (see
+            // `BiomeRestConfig::access_token_duration`), which bounds how long that can be.
+            #[cfg(feature = "biome-sessions")]
+            access_token_denylist: AccessTokenDenylist::new(),
+            #[cfg(feature = "biome-key-encryption")]
+            key_encryptor: self.key_encryptor.map(Arc::new),
+            #[cfg(feature = "audit")]
+            audit_log: self.audit_log.unwrap_or_default(),
         })
     }
 }
@@ -404,6 +895,10 @@ mod tests {
 
     use reqwest::blocking::Client;
 
+    #[cfg(feature = "biome-notifications")]
+    use crate::biome::MemoryNotificationStore;
+    #[cfg(feature = "biome-roles")]
+    use crate::biome::MemoryRoleStore;
     use crate::biome::{
         MemoryCredentialsStore, MemoryKeyStore, MemoryRefreshTokenStore, MemoryUserStore,
     };
@@ -518,9 +1013,13 @@ mod tests {
             .with_refresh_token_store(refresh_token_store)
             .with_credentials_store(cred_store)
             .with_key_store(key_store)
-            .with_rest_config(config)
-            .build()
-            .unwrap();
+            .with_rest_config(config);
+        #[cfg(feature = "biome-roles")]
+        let resource_manager = resource_manager.with_role_store(MemoryRoleStore::new());
+        #[cfg(feature = "biome-notifications")]
+        let resource_manager =
+            resource_manager.with_notification_store(MemoryNotificationStore::new());
+        let resource_manager = resource_manager.build().unwrap();
 
         RestApiBuilder::new()
             .with_bind("127.0.0.1:0")
@@ -25,3 +25,10 @@ pub(crate) struct NewUser<'a> {
     pub user_id: &'a str,
     pub username: &'a str,
 }
+
+#[cfg(feature = "biome-account-security")]
+#[derive(Deserialize)]
+pub(crate) struct PasswordReset {
+    pub reset_token: String,
+    pub new_password: String,
+}
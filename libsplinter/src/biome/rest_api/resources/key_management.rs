@@ -14,12 +14,20 @@
 
 //! Defines structures used in key management.
 
+#[cfg(feature = "biome-key-encryption")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use crate::biome::key_management::Key;
 
 #[derive(Deserialize)]
 pub(crate) struct NewKey {
     pub public_key: String,
+    #[cfg_attr(feature = "biome-key-encryption", serde(default))]
     pub encrypted_private_key: String,
+    /// The unencrypted private key, which is encrypted by the server
+    #[cfg(feature = "biome-key-encryption")]
+    #[serde(default)]
+    pub private_key: Option<String>,
     pub display_name: String,
 }
 
@@ -35,6 +43,14 @@ pub(crate) struct ResponseKey<'a> {
     user_id: &'a str,
     display_name: &'a str,
     encrypted_private_key: &'a str,
+    #[cfg(feature = "biome-key-encryption")]
+    created: u64,
+    #[cfg(feature = "biome-key-encryption")]
+    updated: u64,
+    #[cfg(feature = "biome-key-encryption")]
+    retired: Option<u64>,
+    #[cfg(feature = "biome-key-encryption")]
+    server_encrypted: bool,
 }
 
 impl<'a> From<&'a Key> for ResponseKey<'a> {
@@ -44,6 +60,22 @@ impl<'a> From<&'a Key> for ResponseKey<'a> {
             user_id: &key.user_id,
             display_name: &key.display_name,
             encrypted_private_key: &key.encrypted_private_key,
+            #[cfg(feature = "biome-key-encryption")]
+            created: to_seconds(key.created),
+            #[cfg(feature = "biome-key-encryption")]
+            updated: to_seconds(key.updated),
+            #[cfg(feature = "biome-key-encryption")]
+            retired: key.retired.map(to_seconds),
+            #[cfg(feature = "biome-key-encryption")]
+            server_encrypted: key.server_encrypted,
         }
     }
 }
+
+/// Returns the number of seconds since the Unix epoch
+#[cfg(feature = "biome-key-encryption")]
+fn to_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
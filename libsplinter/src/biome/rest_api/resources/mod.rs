@@ -20,6 +20,12 @@ pub(in crate::biome::rest_api) mod authorize;
 pub(in crate::biome::rest_api) mod credentials;
 #[cfg(feature = "biome-key-management")]
 pub(in crate::biome::rest_api) mod key_management;
+#[cfg(feature = "biome-notifications")]
+pub(in crate::biome::rest_api) mod notifications;
+#[cfg(feature = "biome-roles")]
+pub(in crate::biome::rest_api) mod roles;
+#[cfg(feature = "biome-sessions")]
+pub(in crate::biome::rest_api) mod sessions;
 #[cfg(feature = "biome-credentials")]
 pub(in crate::biome::rest_api) mod token;
 #[cfg(all(feature = "biome-key-management", feature = "biome-credentials"))]
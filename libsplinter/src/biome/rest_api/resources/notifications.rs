@@ -0,0 +1,68 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in notification management.
+
+use std::collections::BTreeMap;
+use std::time::UNIX_EPOCH;
+
+use crate::biome::notifications::{Notification, UserNotification};
+
+#[derive(Deserialize)]
+pub(crate) struct NotificationUpdate {
+    pub unread: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ResponseNotification {
+    id: String,
+    payload_title: String,
+    payload_body: String,
+    /// Milliseconds since the Unix epoch
+    created: u128,
+    recipients: Vec<String>,
+    properties: BTreeMap<String, String>,
+    unread: bool,
+}
+
+impl ResponseNotification {
+    fn new(notification: &Notification, unread: bool) -> Self {
+        ResponseNotification {
+            id: notification.id.clone(),
+            payload_title: notification.payload_title.clone(),
+            payload_body: notification.payload_body.clone(),
+            created: notification
+                .created
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_millis())
+                .unwrap_or(0),
+            recipients: notification.recipients.clone(),
+            properties: notification.properties.clone(),
+            unread,
+        }
+    }
+}
+
+impl From<&UserNotification> for ResponseNotification {
+    fn from(user_notification: &UserNotification) -> Self {
+        ResponseNotification::new(&user_notification.notification, user_notification.unread)
+    }
+}
+
+/// New notifications are always unread
+impl From<&Notification> for ResponseNotification {
+    fn from(notification: &Notification) -> Self {
+        ResponseNotification::new(notification, true)
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in role management.
+
+use crate::biome::roles::Role;
+
+#[derive(Deserialize)]
+pub(crate) struct NewRole {
+    pub name: String,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ResponseRole<'a> {
+    name: &'a str,
+    permissions: &'a [String],
+}
+
+impl<'a> From<&'a Role> for ResponseRole<'a> {
+    fn from(role: &'a Role) -> Self {
+        ResponseRole {
+            name: &role.name,
+            permissions: &role.permissions,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in session management.
+
+use std::time::UNIX_EPOCH;
+
+use crate::biome::refresh_tokens::store::RefreshTokenSession;
+
+#[derive(Serialize)]
+pub(crate) struct ResponseSession<'a> {
+    session_id: &'a str,
+    /// Seconds since the Unix epoch
+    created: u64,
+    client_info: Option<&'a str>,
+    /// Whether the session is the one the request was made with
+    current: bool,
+}
+
+impl<'a> ResponseSession<'a> {
+    pub fn new(session: &'a RefreshTokenSession, current_session_id: Option<&str>) -> Self {
+        ResponseSession {
+            session_id: &session.session_id,
+            created: session
+                .created
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            client_info: session.client_info.as_deref(),
+            current: current_session_id == Some(session.session_id.as_str()),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides an API for defining roles, which group permissions, and assigning them to users.
+//!
+//! A biome user is granted the union of the permissions of all of their roles. The names of a
+//! user's roles and the permissions they grant are included in the access tokens issued at login,
+//! and REST API resources check them with the REST API's `PermissionGuard` through biome's role
+//! identity provider.
+//!
+//! Only users with the `biome.role.write` permission may manage roles, so the first role
+//! administrators are set up by the node operator with `bootstrap_role_admins`.
+
+pub mod store;
+
+#[cfg(feature = "diesel")]
+use store::diesel::models::{RoleModel, RolePermissionModel};
+use store::{RoleStore, RoleStoreError};
+
+/// Permission to list roles and view the roles assigned to other users
+pub const BIOME_ROLE_READ_PERMISSION: &str = "biome.role.read";
+/// Permission to create and remove roles and to assign roles to users
+pub const BIOME_ROLE_WRITE_PERMISSION: &str = "biome.role.write";
+/// The role, granting the role read and write permissions, that `bootstrap_role_admins` assigns
+pub const ROLE_ADMIN_ROLE: &str = "role-admin";
+
+/// Represents a named set of permissions
+#[derive(Clone, Debug, PartialEq)]
+pub struct Role {
+    pub name: String,
+    pub permissions: Vec<String>,
+}
+
+impl Role {
+    /// Creates a new Role
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The unique name of the role, such as "admin" or "auditor"
+    /// * `permissions`: The permissions granted to users that have the role
+    ///
+    pub fn new(name: &str, permissions: Vec<String>) -> Self {
+        Role {
+            name: name.to_string(),
+            permissions,
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<(RoleModel, Vec<RolePermissionModel>)> for Role {
+    fn from((role, permissions): (RoleModel, Vec<RolePermissionModel>)) -> Self {
+        Role {
+            name: role.name,
+            permissions: permissions
+                .into_iter()
+                .map(|permission| permission.permission)
+                .collect(),
+        }
+    }
+}
+
+/// Makes the given users role administrators by assigning them the `role-admin` role, which is
+/// created if it does not exist yet.
+///
+/// # Arguments
+///
+/// * `role_store`: The store that holds the roles
+/// * `user_ids`: The IDs of the users to make role administrators
+///
+pub fn bootstrap_role_admins(
+    role_store: &dyn RoleStore,
+    user_ids: &[String],
+) -> Result<(), RoleStoreError> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    match role_store.fetch_role(ROLE_ADMIN_ROLE) {
+        Ok(_) => (),
+        Err(RoleStoreError::NotFoundError(_)) => role_store.add_role(Role::new(
+            ROLE_ADMIN_ROLE,
+            vec![
+                BIOME_ROLE_READ_PERMISSION.to_string(),
+                BIOME_ROLE_WRITE_PERMISSION.to_string(),
+            ],
+        ))?,
+        Err(err) => return Err(err),
+    }
+
+    for user_id in user_ids {
+        role_store.assign_role(user_id, ROLE_ADMIN_ROLE)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use store::memory::MemoryRoleStore;
+
+    /// Verify that bootstrapping creates the role administrator role once and assigns it to the
+    /// given users.
+    #[test]
+    fn bootstrap_role_admins_assigns_role() {
+        let store = MemoryRoleStore::new();
+
+        bootstrap_role_admins(&store, &["alice".to_string()]).expect("failed to bootstrap");
+        bootstrap_role_admins(&store, &["alice".to_string(), "bob".to_string()])
+            .expect("failed to bootstrap again");
+
+        let role = store
+            .fetch_role(ROLE_ADMIN_ROLE)
+            .expect("failed to fetch role");
+        assert!(role
+            .permissions
+            .contains(&BIOME_ROLE_WRITE_PERMISSION.to_string()));
+        assert_eq!(
+            store
+                .list_role_users(ROLE_ADMIN_ROLE)
+                .expect("failed to list users"),
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+    }
+}
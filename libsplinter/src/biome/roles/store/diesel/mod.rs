@@ -0,0 +1,97 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(in crate::biome) mod models;
+mod operations;
+pub(in crate::biome) mod schema;
+
+use crate::biome::roles::{
+    store::{RoleStore, RoleStoreError},
+    Role,
+};
+use crate::database::ConnectionPool;
+use operations::{
+    add_role::RoleStoreAddRoleOperation, assign_role::RoleStoreAssignRoleOperation,
+    fetch_role::RoleStoreFetchRoleOperation, list_role_users::RoleStoreListRoleUsersOperation,
+    list_roles::RoleStoreListRolesOperation, list_user_roles::RoleStoreListUserRolesOperation,
+    remove_role::RoleStoreRemoveRoleOperation, unassign_role::RoleStoreUnassignRoleOperation,
+    RoleStoreOperations,
+};
+
+/// Manages creating, updating and fetching roles and role assignments from the database
+pub struct DieselRoleStore {
+    connection_pool: ConnectionPool,
+}
+
+impl DieselRoleStore {
+    /// Creates a new DieselRoleStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: ConnectionPool) -> Self {
+        Self { connection_pool }
+    }
+}
+
+impl RoleStore for DieselRoleStore {
+    fn add_role(&self, role: Role) -> Result<(), RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).add_role(role)
+        })
+    }
+
+    fn fetch_role(&self, name: &str) -> Result<Role, RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).fetch_role(name)
+        })
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).list_roles()
+        })
+    }
+
+    fn remove_role(&self, name: &str) -> Result<(), RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).remove_role(name)
+        })
+    }
+
+    fn assign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).assign_role(user_id, role_name)
+        })
+    }
+
+    fn unassign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).unassign_role(user_id, role_name)
+        })
+    }
+
+    fn list_user_roles(&self, user_id: &str) -> Result<Vec<Role>, RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).list_user_roles(user_id)
+        })
+    }
+
+    fn list_role_users(&self, role_name: &str) -> Result<Vec<String>, RoleStoreError> {
+        with_connection!(self.connection_pool, |conn| {
+            RoleStoreOperations::new(conn).list_role_users(role_name)
+        })
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::schema::{biome_role_permissions, biome_roles, biome_user_roles};
+
+#[derive(Insertable, Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "biome_roles"]
+#[primary_key(name)]
+pub struct RoleModel {
+    pub name: String,
+}
+
+#[derive(Insertable, Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "biome_role_permissions"]
+#[primary_key(role_name, permission)]
+pub struct RolePermissionModel {
+    pub role_name: String,
+    pub permission: String,
+}
+
+#[derive(Insertable, Queryable, Identifiable, PartialEq, Debug)]
+#[table_name = "biome_user_roles"]
+#[primary_key(user_id, role_name)]
+pub struct UserRoleModel {
+    pub user_id: String,
+    pub role_name: String,
+}
@@ -0,0 +1,75 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RoleStoreOperations;
+use crate::biome::roles::store::diesel::{
+    models::{RoleModel, RolePermissionModel},
+    schema::{biome_role_permissions, biome_roles},
+};
+use crate::biome::roles::{store::RoleStoreError, Role};
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::roles) trait RoleStoreAddRoleOperation {
+    fn add_role(&self, role: Role) -> Result<(), RoleStoreError>;
+}
+
+impl_for_diesel_backends! {
+    RoleStoreAddRoleOperation for RoleStoreOperations {
+        fn add_role(&self, role: Role) -> Result<(), RoleStoreError> {
+            let name = role.name.clone();
+            let permissions = role
+                .permissions
+                .iter()
+                .map(|permission| RolePermissionModel {
+                    role_name: role.name.clone(),
+                    permission: permission.clone(),
+                })
+                .collect::<Vec<_>>();
+
+            self.conn
+                .transaction::<(), _, _>(|| {
+                    insert_into(biome_roles::table)
+                        .values(RoleModel {
+                            name: role.name.clone(),
+                        })
+                        .execute(self.conn)?;
+                    // Permissions are inserted one at a time, since SQLite does not support
+                    // inserting multiple rows with a single statement
+                    for permission in permissions {
+                        insert_into(biome_role_permissions::table)
+                            .values(permission)
+                            .execute(self.conn)?;
+                    }
+                    Ok(())
+                })
+                .map_err(|err| match err {
+                    QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        RoleStoreError::DuplicateRoleError(format!(
+                            "Role {} is already in database",
+                            name
+                        ))
+                    }
+                    _ => RoleStoreError::OperationError {
+                        context: "Failed to add role".to_string(),
+                        source: Box::new(err),
+                    },
+                })
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{fetch_role::RoleStoreFetchRoleOperation, RoleStoreOperations};
+use crate::biome::roles::store::diesel::{models::UserRoleModel, schema::biome_user_roles};
+use crate::biome::roles::store::RoleStoreError;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::roles) trait RoleStoreAssignRoleOperation {
+    fn assign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError>;
+}
+
+impl_for_diesel_backends! {
+    RoleStoreAssignRoleOperation for RoleStoreOperations {
+        fn assign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError> {
+            // Check the role exists so that a missing role is reported as not found
+            self.fetch_role(role_name)?;
+
+            match insert_into(biome_user_roles::table)
+                .values(UserRoleModel {
+                    user_id: user_id.to_string(),
+                    role_name: role_name.to_string(),
+                })
+                .execute(self.conn)
+            {
+                Ok(_) => Ok(()),
+                // The user already has the role
+                Err(QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+                Err(QueryError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+                    Err(RoleStoreError::UserDoesNotExistError(format!(
+                        "User with ID {} does not exist in database",
+                        user_id
+                    )))
+                }
+                Err(err) => Err(RoleStoreError::OperationError {
+                    context: format!("Failed to assign role {} to user {}", role_name, user_id),
+                    source: Box::new(err),
+                }),
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RoleStoreOperations;
+use crate::biome::roles::store::diesel::{
+    models::{RoleModel, RolePermissionModel},
+    schema::{biome_role_permissions, biome_roles},
+};
+use crate::biome::roles::{store::RoleStoreError, Role};
+
+use diesel::prelude::*;
+
+pub(in crate::biome::roles) trait RoleStoreFetchRoleOperation {
+    fn fetch_role(&self, name: &str) -> Result<Role, RoleStoreError>;
+}
+
+impl<'a, C> RoleStoreFetchRoleOperation for RoleStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    <C as diesel::Connection>::Backend: 'static,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_role(&self, name: &str) -> Result<Role, RoleStoreError> {
+        let role = biome_roles::table
+            .filter(biome_roles::name.eq(name))
+            .first::<RoleModel>(self.conn)
+            .optional()
+            .map_err(|err| RoleStoreError::QueryError {
+                context: "Failed to fetch role".to_string(),
+                source: Box::new(err),
+            })?
+            .ok_or_else(|| RoleStoreError::NotFoundError(format!("Role {} not found", name)))?;
+
+        let permissions = biome_role_permissions::table
+            .filter(biome_role_permissions::role_name.eq(name))
+            .order(biome_role_permissions::permission)
+            .load::<RolePermissionModel>(self.conn)
+            .map_err(|err| RoleStoreError::QueryError {
+                context: "Failed to fetch role permissions".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(Role::from((role, permissions)))
+    }
+}
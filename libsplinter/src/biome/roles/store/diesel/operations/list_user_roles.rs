@@ -0,0 +1,45 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{fetch_role::RoleStoreFetchRoleOperation, RoleStoreOperations};
+use crate::biome::roles::store::diesel::schema::biome_user_roles;
+use crate::biome::roles::{store::RoleStoreError, Role};
+
+use diesel::prelude::*;
+
+pub(in crate::biome::roles) trait RoleStoreListUserRolesOperation {
+    fn list_user_roles(&self, user_id: &str) -> Result<Vec<Role>, RoleStoreError>;
+}
+
+impl<'a, C> RoleStoreListUserRolesOperation for RoleStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    <C as diesel::Connection>::Backend: 'static,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_user_roles(&self, user_id: &str) -> Result<Vec<Role>, RoleStoreError> {
+        biome_user_roles::table
+            .filter(biome_user_roles::user_id.eq(user_id))
+            .select(biome_user_roles::role_name)
+            .order(biome_user_roles::role_name)
+            .load::<String>(self.conn)
+            .map_err(|err| RoleStoreError::QueryError {
+                context: format!("Failed to list roles for user {}", user_id),
+                source: Box::new(err),
+            })?
+            .iter()
+            .map(|name| self.fetch_role(name))
+            .collect()
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(super) mod add_role;
+pub(super) mod assign_role;
+pub(super) mod fetch_role;
+pub(super) mod list_role_users;
+pub(super) mod list_roles;
+pub(super) mod list_user_roles;
+pub(super) mod remove_role;
+pub(super) mod unassign_role;
+
+pub(super) struct RoleStoreOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C> RoleStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    pub fn new(conn: &'a C) -> Self {
+        RoleStoreOperations { conn }
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RoleStoreOperations;
+use crate::biome::roles::store::diesel::schema::{
+    biome_role_permissions, biome_roles, biome_user_roles,
+};
+use crate::biome::roles::store::RoleStoreError;
+
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::roles) trait RoleStoreRemoveRoleOperation {
+    fn remove_role(&self, name: &str) -> Result<(), RoleStoreError>;
+}
+
+impl<'a, C> RoleStoreRemoveRoleOperation for RoleStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    <C as diesel::Connection>::Backend: 'static,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn remove_role(&self, name: &str) -> Result<(), RoleStoreError> {
+        let removed = self
+            .conn
+            .transaction::<usize, _, _>(|| {
+                delete(biome_user_roles::table.filter(biome_user_roles::role_name.eq(name)))
+                    .execute(self.conn)?;
+                delete(
+                    biome_role_permissions::table
+                        .filter(biome_role_permissions::role_name.eq(name)),
+                )
+                .execute(self.conn)?;
+                delete(biome_roles::table.filter(biome_roles::name.eq(name))).execute(self.conn)
+            })
+            .map_err(|err| RoleStoreError::OperationError {
+                context: format!("Failed to remove role {}", name),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(RoleStoreError::NotFoundError(format!(
+                "Role {} not found",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RoleStoreOperations;
+use crate::biome::roles::store::diesel::schema::biome_user_roles;
+use crate::biome::roles::store::RoleStoreError;
+
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::roles) trait RoleStoreUnassignRoleOperation {
+    fn unassign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError>;
+}
+
+impl<'a, C> RoleStoreUnassignRoleOperation for RoleStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    <C as diesel::Connection>::Backend: 'static,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn unassign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError> {
+        let removed = delete(
+            biome_user_roles::table
+                .filter(biome_user_roles::user_id.eq(user_id))
+                .filter(biome_user_roles::role_name.eq(role_name)),
+        )
+        .execute(self.conn)
+        .map_err(|err| RoleStoreError::OperationError {
+            context: format!("Failed to remove role {} from user {}", role_name, user_id),
+            source: Box::new(err),
+        })?;
+
+        if removed == 0 {
+            return Err(RoleStoreError::NotFoundError(format!(
+                "User {} does not have role {}",
+                user_id, role_name
+            )));
+        }
+
+        Ok(())
+    }
+}
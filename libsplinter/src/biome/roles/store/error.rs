@@ -0,0 +1,103 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "diesel")]
+use crate::database::error;
+
+/// Represents RoleStore errors
+#[derive(Debug)]
+pub enum RoleStoreError {
+    /// Represents CRUD operations failures
+    OperationError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents database query failures
+    QueryError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents general failures in the database
+    StorageError {
+        context: String,
+        source: Option<Box<dyn Error>>,
+    },
+    /// Represents an issue connecting to the database
+    ConnectionError(Box<dyn Error>),
+    /// Returned when a role or role assignment is not found
+    NotFoundError(String),
+    /// Returned when a role with the same name is already in the database
+    DuplicateRoleError(String),
+    /// Returned when a user is not found with the provided ID
+    UserDoesNotExistError(String),
+}
+
+impl Error for RoleStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RoleStoreError::OperationError { source, .. } => Some(&**source),
+            RoleStoreError::QueryError { source, .. } => Some(&**source),
+            RoleStoreError::StorageError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            RoleStoreError::StorageError { source: None, .. } => None,
+            RoleStoreError::ConnectionError(err) => Some(&**err),
+            RoleStoreError::NotFoundError(_) => None,
+            RoleStoreError::DuplicateRoleError(_) => None,
+            RoleStoreError::UserDoesNotExistError(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for RoleStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoleStoreError::OperationError { context, source } => {
+                write!(f, "failed to perform operation: {}: {}", context, source)
+            }
+            RoleStoreError::QueryError { context, source } => {
+                write!(f, "failed query: {}: {}", context, source)
+            }
+            RoleStoreError::StorageError {
+                context,
+                source: Some(source),
+            } => write!(
+                f,
+                "the underlying storage returned an error: {}: {}",
+                context, source
+            ),
+            RoleStoreError::StorageError {
+                context,
+                source: None,
+            } => write!(f, "the underlying storage returned an error: {}", context),
+            RoleStoreError::ConnectionError(ref s) => {
+                write!(f, "failed to connect to underlying storage: {}", s)
+            }
+            RoleStoreError::NotFoundError(ref s) => write!(f, "role not found: {}", s),
+            RoleStoreError::DuplicateRoleError(ref s) => write!(f, "role already exists: {}", s),
+            RoleStoreError::UserDoesNotExistError(ref s) => write!(f, "user does not exist: {}", s),
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<error::ConnectionError> for RoleStoreError {
+    fn from(err: error::ConnectionError) -> RoleStoreError {
+        RoleStoreError::ConnectionError(Box::new(err))
+    }
+}
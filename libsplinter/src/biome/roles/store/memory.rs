@@ -0,0 +1,219 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::biome::roles::{
+    store::{RoleStore, RoleStoreError},
+    Role,
+};
+
+#[derive(Default)]
+struct Inner {
+    /// Roles, keyed by name
+    roles: BTreeMap<String, Role>,
+    /// The names of the roles assigned to each user, keyed by user ID
+    assignments: BTreeMap<String, BTreeSet<String>>,
+}
+
+#[derive(Default, Clone)]
+pub struct MemoryRoleStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MemoryRoleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<Inner>, RoleStoreError> {
+        self.inner.lock().map_err(|_| RoleStoreError::StorageError {
+            context: "Cannot access role store: mutex lock poisoned".to_string(),
+            source: None,
+        })
+    }
+}
+
+impl RoleStore for MemoryRoleStore {
+    fn add_role(&self, role: Role) -> Result<(), RoleStoreError> {
+        let mut inner = self.lock()?;
+        if inner.roles.contains_key(&role.name) {
+            return Err(RoleStoreError::DuplicateRoleError(format!(
+                "Role {} is already in the store",
+                role.name
+            )));
+        }
+        inner.roles.insert(role.name.clone(), role);
+        Ok(())
+    }
+
+    fn fetch_role(&self, name: &str) -> Result<Role, RoleStoreError> {
+        self.lock()?
+            .roles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| RoleStoreError::NotFoundError(format!("Role {} not found", name)))
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, RoleStoreError> {
+        Ok(self.lock()?.roles.values().cloned().collect())
+    }
+
+    fn remove_role(&self, name: &str) -> Result<(), RoleStoreError> {
+        let mut inner = self.lock()?;
+        if inner.roles.remove(name).is_none() {
+            return Err(RoleStoreError::NotFoundError(format!(
+                "Role {} not found",
+                name
+            )));
+        }
+        for roles in inner.assignments.values_mut() {
+            roles.remove(name);
+        }
+        Ok(())
+    }
+
+    fn assign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError> {
+        let mut inner = self.lock()?;
+        if !inner.roles.contains_key(role_name) {
+            return Err(RoleStoreError::NotFoundError(format!(
+                "Role {} not found",
+                role_name
+            )));
+        }
+        inner
+            .assignments
+            .entry(user_id.to_string())
+            .or_insert_with(BTreeSet::new)
+            .insert(role_name.to_string());
+        Ok(())
+    }
+
+    fn unassign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError> {
+        let mut inner = self.lock()?;
+        let removed = inner
+            .assignments
+            .get_mut(user_id)
+            .map(|roles| roles.remove(role_name))
+            .unwrap_or(false);
+        if removed {
+            Ok(())
+        } else {
+            Err(RoleStoreError::NotFoundError(format!(
+                "User {} does not have role {}",
+                user_id, role_name
+            )))
+        }
+    }
+
+    fn list_user_roles(&self, user_id: &str) -> Result<Vec<Role>, RoleStoreError> {
+        let inner = self.lock()?;
+        Ok(inner
+            .assignments
+            .get(user_id)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| inner.roles.get(name).cloned())
+                    .collect()
+            })
+            .unwrap_or_else(Vec::new))
+    }
+
+    fn list_role_users(&self, role_name: &str) -> Result<Vec<String>, RoleStoreError> {
+        Ok(self
+            .lock()?
+            .assignments
+            .iter()
+            .filter(|(_, roles)| roles.contains(role_name))
+            .map(|(user_id, _)| user_id.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that roles can be assigned to and removed from users, and that removing a role
+    /// removes its assignments.
+    #[test]
+    fn assign_and_remove_roles() {
+        let store = MemoryRoleStore::new();
+        store
+            .add_role(Role::new("admin", vec!["circuit.write".into()]))
+            .expect("failed to add admin role");
+        store
+            .add_role(Role::new("auditor", vec!["circuit.read".into()]))
+            .expect("failed to add auditor role");
+
+        match store.add_role(Role::new("admin", vec![])) {
+            Err(RoleStoreError::DuplicateRoleError(_)) => (),
+            res => panic!("expected DuplicateRoleError, got {:?}", res),
+        }
+        match store.assign_role("alice", "unknown") {
+            Err(RoleStoreError::NotFoundError(_)) => (),
+            res => panic!("expected NotFoundError, got {:?}", res),
+        }
+
+        store
+            .assign_role("alice", "admin")
+            .expect("failed to assign admin role");
+        store
+            .assign_role("alice", "auditor")
+            .expect("failed to assign auditor role");
+        store
+            .assign_role("bob", "auditor")
+            .expect("failed to assign auditor role");
+
+        let names = |roles: Vec<Role>| roles.into_iter().map(|role| role.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(
+                store
+                    .list_user_roles("alice")
+                    .expect("failed to list roles")
+            ),
+            vec!["admin", "auditor"]
+        );
+        assert_eq!(
+            store
+                .list_role_users("auditor")
+                .expect("failed to list users"),
+            vec!["alice", "bob"]
+        );
+
+        store
+            .unassign_role("bob", "auditor")
+            .expect("failed to unassign role");
+        assert!(store
+            .list_user_roles("bob")
+            .expect("failed to list roles")
+            .is_empty());
+
+        store.remove_role("admin").expect("failed to remove role");
+        assert_eq!(
+            names(
+                store
+                    .list_user_roles("alice")
+                    .expect("failed to list roles")
+            ),
+            vec!["auditor"]
+        );
+        assert!(store
+            .list_role_users("admin")
+            .expect("failed to list users")
+            .is_empty());
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "diesel")]
+pub(crate) mod diesel;
+mod error;
+pub(in crate::biome) mod memory;
+
+pub use error::RoleStoreError;
+
+use super::Role;
+
+/// Defines methods for CRUD operations on roles and the assignment of roles to users
+pub trait RoleStore: Send + Sync {
+    /// Adds a role to underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `role` - The role to be added
+    fn add_role(&self, role: Role) -> Result<(), RoleStoreError>;
+
+    /// Fetches a role from underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `name` - The name of the role
+    fn fetch_role(&self, name: &str) -> Result<Role, RoleStoreError>;
+
+    /// Lists all roles in underlying storage
+    fn list_roles(&self) -> Result<Vec<Role>, RoleStoreError>;
+
+    /// Removes a role, and all assignments of it to users, from underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `name` - The name of the role
+    fn remove_role(&self, name: &str) -> Result<(), RoleStoreError>;
+
+    /// Assigns a role to a user. Assigning a role the user already has is not an error.
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The user to whom the role is assigned
+    ///   * `role_name` - The name of the role
+    fn assign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError>;
+
+    /// Removes a role from a user
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The user from whom the role is removed
+    ///   * `role_name` - The name of the role
+    fn unassign_role(&self, user_id: &str, role_name: &str) -> Result<(), RoleStoreError>;
+
+    /// Lists the roles assigned to a user
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The user whose roles are listed
+    fn list_user_roles(&self, user_id: &str) -> Result<Vec<Role>, RoleStoreError>;
+
+    /// Lists the IDs of the users that have been assigned a role
+    ///
+    /// # Arguments
+    ///
+    ///   * `role_name` - The name of the role
+    fn list_role_users(&self, role_name: &str) -> Result<Vec<String>, RoleStoreError>;
+}
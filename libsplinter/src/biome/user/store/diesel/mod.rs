@@ -46,22 +46,32 @@ impl DieselUserStore {
 
 impl UserStore for DieselUserStore {
     fn add_user(&self, user: User) -> Result<(), UserStoreError> {
-        UserStoreOperations::new(&*self.connection_pool.get()?).add_user(user.into())
+        with_connection!(self.connection_pool, |conn| {
+            UserStoreOperations::new(conn).add_user(user.into())
+        })
     }
 
     fn update_user(&self, updated_user: User) -> Result<(), UserStoreError> {
-        UserStoreOperations::new(&*self.connection_pool.get()?).update_user(updated_user)
+        with_connection!(self.connection_pool, |conn| {
+            UserStoreOperations::new(conn).update_user(updated_user)
+        })
     }
 
     fn remove_user(&self, id: &str) -> Result<(), UserStoreError> {
-        UserStoreOperations::new(&*self.connection_pool.get()?).delete_user(id)
+        with_connection!(self.connection_pool, |conn| {
+            UserStoreOperations::new(conn).delete_user(id)
+        })
     }
 
     fn fetch_user(&self, id: &str) -> Result<User, UserStoreError> {
-        UserStoreOperations::new(&*self.connection_pool.get()?).fetch_user(id)
+        with_connection!(self.connection_pool, |conn| {
+            UserStoreOperations::new(conn).fetch_user(id)
+        })
     }
 
     fn list_users(&self) -> Result<Vec<User>, UserStoreError> {
-        UserStoreOperations::new(&*self.connection_pool.get()?).list_users()
+        with_connection!(self.connection_pool, |conn| {
+            UserStoreOperations::new(conn).list_users()
+        })
     }
 }
@@ -23,21 +23,18 @@ pub(in crate::biome::user) trait UserStoreAddUserOperation {
     fn add_user(&self, user_model: UserModel) -> Result<(), UserStoreError>;
 }
 
-impl<'a, C> UserStoreAddUserOperation for UserStoreOperations<'a, C>
-where
-    C: diesel::Connection,
-    <C as diesel::Connection>::Backend: diesel::backend::SupportsDefaultKeyword,
-    <C as diesel::Connection>::Backend: 'static,
-{
-    fn add_user(&self, user_model: UserModel) -> Result<(), UserStoreError> {
-        insert_into(splinter_user::table)
-            .values(&vec![user_model])
-            .execute(self.conn)
-            .map(|_| ())
-            .map_err(|err| UserStoreError::OperationError {
-                context: "Failed to add user".to_string(),
-                source: Box::new(err),
-            })?;
-        Ok(())
+impl_for_diesel_backends! {
+    UserStoreAddUserOperation for UserStoreOperations {
+        fn add_user(&self, user_model: UserModel) -> Result<(), UserStoreError> {
+            insert_into(splinter_user::table)
+                .values(&user_model)
+                .execute(self.conn)
+                .map(|_| ())
+                .map_err(|err| UserStoreError::OperationError {
+                    context: "Failed to add user".to_string(),
+                    source: Box::new(err),
+                })?;
+            Ok(())
+        }
     }
 }
@@ -138,3 +138,18 @@ impl From<ConsensusSendError> for ConsensusEngineError {
         ConsensusEngineError(Box::new(err))
     }
 }
+
+#[derive(Debug)]
+pub struct ConsensusRunnerError(pub Box<dyn Error + Send>);
+
+impl Error for ConsensusRunnerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+impl std::fmt::Display for ConsensusRunnerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "consensus runner failed: {}", self.0)
+    }
+}
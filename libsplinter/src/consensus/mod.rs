@@ -15,6 +15,10 @@
 //! The API that defines interactions between consensus and a Splinter service.
 
 pub mod error;
+#[cfg(feature = "consensus-quorum")]
+pub mod quorum;
+mod runner;
+mod timing;
 pub mod two_phase;
 
 use std::convert::{TryFrom, TryInto};
@@ -27,7 +31,10 @@ use crate::protos::consensus::{
     ConsensusMessage as ConsensusMessageProto, Proposal as ProposalProto,
 };
 
-pub use error::{ConsensusEngineError, ConsensusSendError, ProposalManagerError};
+pub use error::{
+    ConsensusEngineError, ConsensusRunnerError, ConsensusSendError, ProposalManagerError,
+};
+pub use runner::{ConsensusRunner, ServiceConsensusNetworkSender, ServicePeers};
 
 macro_rules! id_type {
     ($type:ident) => {
@@ -175,6 +182,16 @@ pub trait ProposalManager: Send {
 
     /// Consensus has rejected the given proposal.
     fn reject_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError>;
+
+    /// Consensus has stopped evaluating the given proposal without accepting or rejecting it.
+    /// Any changes prepared while checking the proposal should be discarded, but the proposal
+    /// must be kept, since consensus may check and accept it later.
+    ///
+    /// The default implementation does nothing, since only some consensus algorithms abandon
+    /// proposals.
+    fn abandon_proposal(&self, _id: &ProposalId) -> Result<(), ProposalManagerError> {
+        Ok(())
+    }
 }
 
 /// Messages the `ProposalManager` sends to consensus
@@ -305,6 +322,7 @@ pub mod tests {
         last_proposal_id: RefCell<ProposalId>,
         accepted_proposals: Arc<Mutex<Vec<(ProposalId, Vec<u8>)>>>,
         rejected_proposals: Arc<Mutex<Vec<ProposalId>>>,
+        abandoned_proposals: Arc<Mutex<Vec<ProposalId>>>,
         next_proposal_valid: Arc<AtomicBool>,
         return_proposal: Arc<AtomicBool>,
         consensus_data: Option<Vec<u8>>,
@@ -320,6 +338,7 @@ pub mod tests {
                 last_proposal_id: self.last_proposal_id.clone(),
                 accepted_proposals: self.accepted_proposals.clone(),
                 rejected_proposals: self.rejected_proposals.clone(),
+                abandoned_proposals: self.abandoned_proposals.clone(),
                 next_proposal_valid: self.next_proposal_valid.clone(),
                 return_proposal: self.return_proposal.clone(),
                 consensus_data: self.consensus_data.clone(),
@@ -335,6 +354,7 @@ pub mod tests {
                 last_proposal_id: RefCell::new(ProposalId::default()),
                 accepted_proposals: Arc::new(Mutex::new(vec![])),
                 rejected_proposals: Arc::new(Mutex::new(vec![])),
+                abandoned_proposals: Arc::new(Mutex::new(vec![])),
                 next_proposal_valid: Arc::new(AtomicBool::new(true)),
                 return_proposal: Arc::new(AtomicBool::new(true)),
                 consensus_data: None,
@@ -365,6 +385,12 @@ pub mod tests {
                 .lock()
                 .expect("failed to get rejected proposals")
         }
+
+        pub fn abandoned_proposals(&self) -> MutexGuard<Vec<ProposalId>> {
+            self.abandoned_proposals
+                .lock()
+                .expect("failed to get abandoned proposals")
+        }
     }
 
     impl ProposalManager for MockProposalManager {
@@ -438,6 +464,14 @@ pub mod tests {
                 .push(id.clone());
             Ok(())
         }
+
+        fn abandon_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
+            self.abandoned_proposals
+                .lock()
+                .expect("failed to get abandoned proposals lock")
+                .push(id.clone());
+            Ok(())
+        }
     }
 
     #[derive(Clone)]
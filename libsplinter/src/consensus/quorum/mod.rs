@@ -0,0 +1,1800 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A rotating-coordinator, quorum-commit consensus algorithm implemented as a `ConsensusEngine`.
+//! This algorithm is a variant of two-phase commit that is intended for networks with more than
+//! two or three members:
+//!
+//! - Coordinator duty rotates. The members of the network are sorted by ID, and the coordinator
+//!   for epoch `e` is the member at index `e mod n`. The epoch advances each time a proposal is
+//!   completed (applied or rejected).
+//! - Each member only coordinates its own proposals. A member may create a proposal while
+//!   another member is coordinating; the proposal is held until the member becomes the
+//!   coordinator, and is withdrawn and created again if it is no longer valid by then.
+//! - A proposal is applied as soon as a quorum of members (by default, a simple majority that
+//!   includes the coordinator) has verified it, rather than waiting on every member. The quorum
+//!   can be raised but never lowered below a majority, so any two quorums share a member. Members
+//!   that did not take part in the verification check and apply the proposal when they receive
+//!   the result, which is only accepted from the coordinator of the proposal's epoch.
+//! - A member that has verified a proposal is locked on it: it does not verify any other proposal
+//!   until the coordinator sends the result of the proposal, even if the network moves on to a
+//!   later epoch. Since any two quorums share a member, two conflicting proposals can never both
+//!   be applied.
+//! - Once a proposal has been applied, no other proposal that is based on the same previous
+//!   proposal can be applied, so these proposals are rejected.
+//! - A member that has outstanding work but does not hear from the current coordinator within the
+//!   coordinator timeout moves on to the next epoch, so a single slow or unavailable member only
+//!   delays the network for the length of the timeout. A member that is locked on a proposal
+//!   waits for the proposal's coordinator instead, which rejects the proposal if it does not
+//!   reach a quorum within the coordinator timeout.
+//!
+//! As with the two-phase commit engine, only one proposal is evaluated at a time and the engine
+//! is not fully resilient to crashes.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use protobuf::Message;
+
+use crate::consensus::{
+    timing::Timeout, ConsensusEngine, ConsensusEngineError, ConsensusMessage,
+    ConsensusNetworkSender, PeerId, Proposal, ProposalId, ProposalManager, ProposalUpdate,
+    StartupState,
+};
+use crate::protos::quorum::{
+    QuorumMessage, QuorumMessage_ProposalResult, QuorumMessage_ProposalVerificationResponse,
+    QuorumMessage_Type,
+};
+
+const MESSAGE_RECV_TIMEOUT_MILLIS: u64 = 100;
+const PROPOSAL_RECV_TIMEOUT_MILLIS: u64 = 100;
+/// The number of accepted proposals whose previous proposal is remembered, so that proposals
+/// received after a conflicting proposal was accepted can be rejected
+const ACCEPTED_HISTORY_LENGTH: usize = 16;
+
+#[derive(Debug)]
+enum State {
+    Idle,
+    AwaitingProposal,
+    EvaluatingProposal(QuorumProposal),
+    /// Checking a proposal that the network applied without this member's verification
+    CatchingUp(PeerProposal),
+}
+
+/// A proposal created by another member, which only that member may coordinate
+#[derive(Debug)]
+struct PeerProposal {
+    proposal_id: ProposalId,
+    previous_id: ProposalId,
+    creator_id: PeerId,
+}
+
+/// Contains information about a proposal that quorum consensus needs to keep track of
+#[derive(Debug)]
+struct QuorumProposal {
+    proposal_id: ProposalId,
+    previous_id: ProposalId,
+    epoch: u64,
+    coordinator_id: PeerId,
+    /// Whether this member has verified the proposal; a member is locked on the proposal it has
+    /// verified until the coordinator sends the proposal's result
+    verified: bool,
+    peers_verified: HashSet<PeerId>,
+    peers_failed: HashSet<PeerId>,
+}
+
+impl QuorumProposal {
+    fn new(
+        proposal_id: ProposalId,
+        previous_id: ProposalId,
+        epoch: u64,
+        coordinator_id: PeerId,
+    ) -> Self {
+        QuorumProposal {
+            proposal_id,
+            previous_id,
+            epoch,
+            coordinator_id,
+            verified: false,
+            peers_verified: HashSet::new(),
+            peers_failed: HashSet::new(),
+        }
+    }
+}
+
+pub struct QuorumEngine {
+    id: PeerId,
+    /// All members of the network (including this one), sorted by ID
+    members: Vec<PeerId>,
+    quorum: Option<usize>,
+    epoch: u64,
+    state: State,
+    coordinator_timeout: Timeout,
+    epoch_timeout: Timeout,
+    /// Proposals received from other members that have not been resolved, in the order received
+    proposal_backlog: VecDeque<PeerProposal>,
+    verification_request_backlog: VecDeque<(ProposalId, u64, PeerId)>,
+    /// Proposals applied by the network without this member's verification, along with the
+    /// member that coordinated each one
+    applied_by_network: HashMap<ProposalId, PeerId>,
+    /// The previous proposals of the most recently accepted proposals
+    accepted_previous_ids: VecDeque<ProposalId>,
+    /// This member's proposal, which it coordinates once it is the coordinator
+    own_proposal: Option<Proposal>,
+}
+
+impl QuorumEngine {
+    /// Create a new `QuorumEngine`.
+    ///
+    /// # Arguments
+    ///
+    /// * `coordinator_timeout_duration`: How long the coordinator waits for a quorum before
+    ///   rejecting a proposal, and how long other members wait to hear from the coordinator
+    ///   before moving on to the next epoch
+    /// * `quorum`: The number of members (including the coordinator) that must verify a proposal
+    ///   for it to be applied; if `None` or less than a simple majority of the members, a simple
+    ///   majority is required
+    pub fn new(coordinator_timeout_duration: Duration, quorum: Option<usize>) -> Self {
+        QuorumEngine {
+            id: PeerId::default(),
+            members: vec![],
+            quorum,
+            epoch: 0,
+            state: State::Idle,
+            coordinator_timeout: Timeout::new(coordinator_timeout_duration),
+            epoch_timeout: Timeout::new(coordinator_timeout_duration),
+            proposal_backlog: VecDeque::new(),
+            verification_request_backlog: VecDeque::new(),
+            applied_by_network: HashMap::new(),
+            accepted_previous_ids: VecDeque::new(),
+            own_proposal: None,
+        }
+    }
+
+    /// The number of verifications required to apply a proposal; never less than a majority of
+    /// the members
+    fn quorum(&self) -> usize {
+        let members = self.members.len();
+        let majority = members / 2 + 1;
+        self.quorum.unwrap_or(majority).max(majority).min(members)
+    }
+
+    fn coordinator_for(&self, epoch: u64) -> &PeerId {
+        &self.members[(epoch % self.members.len() as u64) as usize]
+    }
+
+    fn is_coordinator(&self) -> bool {
+        self.coordinator_for(self.epoch) == &self.id
+    }
+
+    fn is_idle(&self) -> bool {
+        match self.state {
+            State::Idle => true,
+            _ => false,
+        }
+    }
+
+    /// Whether this member has verified a proposal coordinated by another member and is waiting
+    /// for its result
+    fn is_locked(&self) -> bool {
+        match self.state {
+            State::EvaluatingProposal(ref proposal) => {
+                proposal.verified && proposal.coordinator_id != self.id
+            }
+            _ => false,
+        }
+    }
+
+    fn evaluating_proposal(&self, proposal_id: &ProposalId) -> bool {
+        match self.state {
+            State::EvaluatingProposal(ref proposal) => &proposal.proposal_id == proposal_id,
+            _ => false,
+        }
+    }
+
+    /// Move to the given epoch if it is newer than the current one. If this member was
+    /// coordinating a proposal in an older epoch, the proposal is rejected. A proposal coordinated
+    /// by another member that this member has not verified is abandoned, since the network may
+    /// have moved on without it; a proposal that this member has verified is kept until its
+    /// coordinator sends the result.
+    fn advance_epoch(
+        &mut self,
+        epoch: u64,
+        network_sender: &dyn ConsensusNetworkSender,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        if epoch <= self.epoch {
+            return Ok(());
+        }
+
+        self.epoch = epoch;
+        self.epoch_timeout.stop();
+        self.verification_request_backlog
+            .retain(|(_, request_epoch, _)| *request_epoch >= epoch);
+
+        debug!(
+            "Advanced to epoch {}; coordinator is {}",
+            self.epoch,
+            self.coordinator_for(self.epoch)
+        );
+
+        let outdated = match self.state {
+            State::EvaluatingProposal(ref proposal) if proposal.epoch < epoch => Some((
+                proposal.proposal_id.clone(),
+                proposal.epoch,
+                proposal.coordinator_id == self.id,
+                proposal.verified,
+            )),
+            _ => None,
+        };
+
+        match outdated {
+            Some((proposal_id, proposal_epoch, true, _)) => {
+                warn!(
+                    "Network moved on to epoch {}; rejecting proposal {}",
+                    epoch, proposal_id
+                );
+                self.complete_coordination(
+                    proposal_id,
+                    proposal_epoch,
+                    QuorumMessage_ProposalResult::REJECT,
+                    network_sender,
+                    proposal_manager,
+                )?;
+            }
+            Some((proposal_id, _, false, true)) => {
+                debug!(
+                    "Network moved on to epoch {}; remaining locked on proposal {} until its \
+                     result is received",
+                    epoch, proposal_id
+                );
+            }
+            Some((proposal_id, _, false, false)) => {
+                debug!(
+                    "Network moved on to epoch {}; abandoning proposal {}",
+                    epoch, proposal_id
+                );
+                self.abandon_proposal(proposal_manager)?;
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Stop evaluating a proposal coordinated by another member without resolving it. The
+    /// changes prepared while checking the proposal are rolled back, and the proposal is returned
+    /// to the backlog since the network may still apply it.
+    fn abandon_proposal(
+        &mut self,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        match std::mem::replace(&mut self.state, State::Idle) {
+            State::EvaluatingProposal(proposal) => {
+                proposal_manager.abandon_proposal(&proposal.proposal_id)?;
+                self.proposal_backlog.push_front(PeerProposal {
+                    proposal_id: proposal.proposal_id,
+                    previous_id: proposal.previous_id,
+                    creator_id: proposal.coordinator_id,
+                });
+            }
+            state => self.state = state,
+        }
+
+        Ok(())
+    }
+
+    fn handle_consensus_msg(
+        &mut self,
+        consensus_msg: ConsensusMessage,
+        network_sender: &dyn ConsensusNetworkSender,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        let quorum_msg: QuorumMessage = protobuf::parse_from_bytes(&consensus_msg.message)?;
+        let proposal_id = ProposalId::from(quorum_msg.get_proposal_id());
+        let epoch = quorum_msg.get_epoch();
+
+        match quorum_msg.get_message_type() {
+            QuorumMessage_Type::PROPOSAL_VERIFICATION_REQUEST => {
+                debug!(
+                    "Proposal verification request received for epoch {}: {}",
+                    epoch, proposal_id
+                );
+
+                if epoch < self.epoch {
+                    debug!(
+                        "Ignoring verification request from old epoch {}: {}",
+                        epoch, proposal_id
+                    );
+                    return Ok(());
+                }
+
+                if self.coordinator_for(epoch) != &consensus_msg.origin_id {
+                    warn!(
+                        "Ignoring verification request from {}; not the coordinator for epoch {}",
+                        consensus_msg.origin_id, epoch
+                    );
+                    return Ok(());
+                }
+
+                self.advance_epoch(epoch, network_sender, proposal_manager)?;
+                self.epoch_timeout.start();
+
+                if self.evaluating_proposal(&proposal_id) {
+                    debug!("Already evaluating proposal {}", proposal_id);
+                    return Ok(());
+                }
+
+                match self
+                    .proposal_backlog
+                    .iter()
+                    .position(|backlogged| backlogged.proposal_id == proposal_id)
+                {
+                    Some(idx)
+                        if self.proposal_backlog[idx].creator_id != consensus_msg.origin_id =>
+                    {
+                        warn!(
+                            "Ignoring verification request for proposal {} from {}; only the \
+                             member that created a proposal may coordinate it",
+                            proposal_id, consensus_msg.origin_id
+                        );
+                    }
+                    Some(idx) if self.is_idle() => {
+                        // unwrap is safe because the index was just found
+                        let proposal = self.proposal_backlog.remove(idx).unwrap();
+                        debug!("Checking proposal {}", proposal_id);
+                        proposal_manager.check_proposal(&proposal_id)?;
+                        self.state = State::EvaluatingProposal(QuorumProposal::new(
+                            proposal.proposal_id,
+                            proposal.previous_id,
+                            epoch,
+                            proposal.creator_id,
+                        ));
+                    }
+                    _ => {
+                        debug!("Backlogging verification request: {}", proposal_id);
+                        self.verification_request_backlog.push_back((
+                            proposal_id,
+                            epoch,
+                            consensus_msg.origin_id,
+                        ));
+                    }
+                }
+            }
+            QuorumMessage_Type::PROPOSAL_VERIFICATION_RESPONSE => {
+                let quorum = self.quorum();
+                let members = self.members.len();
+
+                let result = match self.state {
+                    State::EvaluatingProposal(ref mut proposal)
+                        if proposal.proposal_id == proposal_id
+                            && proposal.coordinator_id == self.id =>
+                    {
+                        match quorum_msg.get_proposal_verification_response() {
+                            QuorumMessage_ProposalVerificationResponse::VERIFIED => {
+                                debug!(
+                                    "Proposal {} verified by peer {}",
+                                    proposal_id, consensus_msg.origin_id
+                                );
+                                proposal.peers_verified.insert(consensus_msg.origin_id);
+                                if proposal.peers_verified.len() >= quorum {
+                                    debug!("Quorum reached; accepting proposal {}", proposal_id);
+                                    Some((proposal.epoch, QuorumMessage_ProposalResult::APPLY))
+                                } else {
+                                    None
+                                }
+                            }
+                            QuorumMessage_ProposalVerificationResponse::FAILED => {
+                                debug!(
+                                    "Proposal {} failed by peer {}",
+                                    proposal_id, consensus_msg.origin_id
+                                );
+                                proposal.peers_failed.insert(consensus_msg.origin_id);
+                                if proposal.peers_failed.len() > members - quorum {
+                                    debug!(
+                                        "Quorum can no longer be reached; rejecting proposal {}",
+                                        proposal_id
+                                    );
+                                    Some((proposal.epoch, QuorumMessage_ProposalResult::REJECT))
+                                } else {
+                                    None
+                                }
+                            }
+                            QuorumMessage_ProposalVerificationResponse::UNSET_VERIFICATION_RESPONSE => {
+                                warn!(
+                                    "Ignoring improperly specified proposal verification response \
+                                     from {}",
+                                    consensus_msg.origin_id
+                                );
+                                None
+                            }
+                        }
+                    }
+                    _ => {
+                        warn!(
+                            "Received unexpected verification response for proposal {}",
+                            proposal_id
+                        );
+                        None
+                    }
+                };
+
+                if let Some((proposal_epoch, proposal_result)) = result {
+                    self.complete_coordination(
+                        proposal_id,
+                        proposal_epoch,
+                        proposal_result,
+                        network_sender,
+                        proposal_manager,
+                    )?;
+                }
+            }
+            QuorumMessage_Type::PROPOSAL_RESULT => {
+                if self.coordinator_for(epoch) != &consensus_msg.origin_id {
+                    warn!(
+                        "Ignoring proposal result from {}; not the coordinator for epoch {}",
+                        consensus_msg.origin_id, epoch
+                    );
+                    return Ok(());
+                }
+
+                match quorum_msg.get_proposal_result() {
+                    QuorumMessage_ProposalResult::APPLY => self.handle_apply_result(
+                        proposal_id,
+                        consensus_msg.origin_id,
+                        proposal_manager,
+                    )?,
+                    QuorumMessage_ProposalResult::REJECT => self.handle_reject_result(
+                        &proposal_id,
+                        &consensus_msg.origin_id,
+                        proposal_manager,
+                    )?,
+                    QuorumMessage_ProposalResult::UNSET_RESULT => {
+                        warn!(
+                            "Ignoring improperly specified proposal result from {}",
+                            consensus_msg.origin_id
+                        );
+                        return Ok(());
+                    }
+                }
+
+                self.advance_epoch(epoch + 1, network_sender, proposal_manager)?;
+            }
+            QuorumMessage_Type::UNSET_TYPE => warn!(
+                "Ignoring improperly specified quorum message from {}",
+                consensus_msg.origin_id
+            ),
+        }
+
+        Ok(())
+    }
+
+    fn handle_apply_result(
+        &mut self,
+        proposal_id: ProposalId,
+        coordinator_id: PeerId,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        let evaluating = match self.state {
+            State::EvaluatingProposal(ref proposal) if proposal.proposal_id == proposal_id => {
+                Some((
+                    proposal.coordinator_id == coordinator_id,
+                    proposal.previous_id.clone(),
+                ))
+            }
+            _ => None,
+        };
+
+        match evaluating {
+            Some((true, previous_id)) => {
+                debug!("Accepting proposal {}", proposal_id);
+                proposal_manager.accept_proposal(&proposal_id, None)?;
+                self.state = State::Idle;
+                return self.reject_conflicting_proposals(
+                    &proposal_id,
+                    previous_id,
+                    proposal_manager,
+                );
+            }
+            Some((false, _)) => {
+                warn!(
+                    "Ignoring result for proposal {} from {}; not the proposal's coordinator",
+                    proposal_id, coordinator_id
+                );
+                return Ok(());
+            }
+            None => {}
+        }
+
+        // The network applied a proposal that this member did not verify. Any other proposal this
+        // member was verifying was checked against the state before that proposal was applied, so
+        // it can no longer be applied and is abandoned; the applied proposal will be checked and
+        // applied locally once it has been received.
+        debug!(
+            "Proposal applied by network without this member's verification: {}",
+            proposal_id
+        );
+        let abandon = match self.state {
+            State::EvaluatingProposal(ref proposal) => proposal.coordinator_id != self.id,
+            _ => false,
+        };
+        if abandon {
+            self.abandon_proposal(proposal_manager)?;
+        }
+        self.applied_by_network.insert(proposal_id, coordinator_id);
+
+        Ok(())
+    }
+
+    fn handle_reject_result(
+        &mut self,
+        proposal_id: &ProposalId,
+        coordinator_id: &PeerId,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        let evaluating = match self.state {
+            State::EvaluatingProposal(ref proposal) if &proposal.proposal_id == proposal_id => {
+                Some(&proposal.coordinator_id == coordinator_id)
+            }
+            _ => None,
+        };
+
+        match evaluating {
+            Some(true) => {
+                debug!("Rejecting proposal {}", proposal_id);
+                proposal_manager.reject_proposal(proposal_id)?;
+                self.state = State::Idle;
+            }
+            Some(false) => warn!(
+                "Ignoring result for proposal {} from {}; not the proposal's coordinator",
+                proposal_id, coordinator_id
+            ),
+            None => {
+                if let Some(idx) = self.proposal_backlog.iter().position(|backlogged| {
+                    &backlogged.proposal_id == proposal_id
+                        && &backlogged.creator_id == coordinator_id
+                }) {
+                    debug!("Rejecting backlogged proposal {}", proposal_id);
+                    self.proposal_backlog.remove(idx);
+                    proposal_manager.reject_proposal(proposal_id)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Once a proposal has been accepted, no other proposal that is based on the same previous
+    /// proposal can be applied, so any such proposal received from another member is rejected.
+    /// Proposals that do not specify a previous proposal never conflict.
+    fn reject_conflicting_proposals(
+        &mut self,
+        accepted_id: &ProposalId,
+        previous_id: ProposalId,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        if previous_id.as_ref().is_empty() {
+            return Ok(());
+        }
+
+        let applied_by_network = &self.applied_by_network;
+        let (conflicting, backlog) = self.proposal_backlog.drain(..).partition(|backlogged| {
+            backlogged.previous_id == previous_id
+                && &backlogged.proposal_id != accepted_id
+                && !applied_by_network.contains_key(&backlogged.proposal_id)
+        });
+        self.proposal_backlog = backlog;
+
+        for proposal in conflicting.into_iter().collect::<Vec<PeerProposal>>() {
+            debug!(
+                "Rejecting proposal {}; it conflicts with accepted proposal {}",
+                proposal.proposal_id, accepted_id
+            );
+            self.verification_request_backlog
+                .retain(|(proposal_id, _, _)| proposal_id != &proposal.proposal_id);
+            proposal_manager.reject_proposal(&proposal.proposal_id)?;
+        }
+
+        self.accepted_previous_ids.push_back(previous_id);
+        if self.accepted_previous_ids.len() > ACCEPTED_HISTORY_LENGTH {
+            self.accepted_previous_ids.pop_front();
+        }
+
+        Ok(())
+    }
+
+    fn handle_proposal_update(
+        &mut self,
+        update: ProposalUpdate,
+        network_sender: &dyn ConsensusNetworkSender,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        match update {
+            ProposalUpdate::ProposalCreated(None) => {
+                if let State::AwaitingProposal = self.state {
+                    self.state = State::Idle;
+                }
+            }
+            ProposalUpdate::ProposalCreated(Some(proposal)) => {
+                debug!("Proposal created: {}", proposal.id);
+                if let State::AwaitingProposal = self.state {
+                    self.state = State::Idle;
+                }
+                self.own_proposal = Some(proposal);
+
+                if self.is_coordinator() && self.is_idle() {
+                    self.start_coordination(proposal_manager)?;
+                }
+            }
+            ProposalUpdate::ProposalReceived(proposal, creator_id) => {
+                debug!("Proposal received: {}", proposal.id);
+                if self.evaluating_proposal(&proposal.id)
+                    || self
+                        .proposal_backlog
+                        .iter()
+                        .any(|backlogged| backlogged.proposal_id == proposal.id)
+                {
+                    debug!("Proposal already received; ignoring: {}", proposal.id);
+                } else if !proposal.previous_id.as_ref().is_empty()
+                    && self.accepted_previous_ids.contains(&proposal.previous_id)
+                    && !self.applied_by_network.contains_key(&proposal.id)
+                {
+                    debug!(
+                        "Rejecting proposal {}; a conflicting proposal was already accepted",
+                        proposal.id
+                    );
+                    proposal_manager.reject_proposal(&proposal.id)?;
+                } else {
+                    self.proposal_backlog.push_back(PeerProposal {
+                        proposal_id: proposal.id,
+                        previous_id: proposal.previous_id,
+                        creator_id,
+                    });
+                }
+            }
+            ProposalUpdate::ProposalValid(proposal_id) => {
+                let quorum = self.quorum();
+                match self.state {
+                    State::CatchingUp(ref catching_up)
+                        if catching_up.proposal_id == proposal_id =>
+                    {
+                        debug!("Accepting proposal applied by network: {}", proposal_id);
+                        proposal_manager.accept_proposal(&proposal_id, None)?;
+                        let previous_id = catching_up.previous_id.clone();
+                        self.state = State::Idle;
+                        self.reject_conflicting_proposals(
+                            &proposal_id,
+                            previous_id,
+                            proposal_manager,
+                        )?;
+                    }
+                    State::EvaluatingProposal(ref mut proposal)
+                        if proposal.proposal_id == proposal_id =>
+                    {
+                        debug!("Proposal valid: {}", proposal_id);
+                        proposal.verified = true;
+
+                        if proposal.coordinator_id == self.id {
+                            proposal.peers_verified.insert(self.id.clone());
+
+                            debug!("Requesting verification of proposal {}", proposal_id);
+                            let mut request = QuorumMessage::new();
+                            request.set_message_type(
+                                QuorumMessage_Type::PROPOSAL_VERIFICATION_REQUEST,
+                            );
+                            request.set_proposal_id(proposal_id.clone().into());
+                            request.set_epoch(proposal.epoch);
+                            network_sender.broadcast(request.write_to_bytes()?)?;
+
+                            if proposal.peers_verified.len() >= quorum {
+                                let epoch = proposal.epoch;
+                                self.complete_coordination(
+                                    proposal_id,
+                                    epoch,
+                                    QuorumMessage_ProposalResult::APPLY,
+                                    network_sender,
+                                    proposal_manager,
+                                )?;
+                            }
+                        } else {
+                            debug!("Sending verified response for proposal {}", proposal_id);
+                            let mut response = QuorumMessage::new();
+                            response.set_message_type(
+                                QuorumMessage_Type::PROPOSAL_VERIFICATION_RESPONSE,
+                            );
+                            response.set_proposal_id(proposal_id.into());
+                            response.set_epoch(proposal.epoch);
+                            response.set_proposal_verification_response(
+                                QuorumMessage_ProposalVerificationResponse::VERIFIED,
+                            );
+                            network_sender
+                                .send_to(&proposal.coordinator_id, response.write_to_bytes()?)?;
+                        }
+                    }
+                    _ => warn!("Got valid message for unknown proposal: {}", proposal_id),
+                }
+            }
+            ProposalUpdate::ProposalInvalid(proposal_id) => match self.state {
+                State::CatchingUp(ref catching_up) if catching_up.proposal_id == proposal_id => {
+                    error!(
+                        "Proposal {} was applied by the network but is invalid for this member; \
+                         this member is out of sync",
+                        proposal_id
+                    );
+                    proposal_manager.reject_proposal(&proposal_id)?;
+                    self.state = State::Idle;
+                }
+                State::EvaluatingProposal(ref proposal) if proposal.proposal_id == proposal_id => {
+                    debug!("Proposal invalid: {}", proposal_id);
+
+                    if proposal.coordinator_id == self.id {
+                        // The proposal was created before the state it was based on changed; no
+                        // member has been asked to verify it, so it can be withdrawn without
+                        // ending this member's epoch.
+                        debug!("Withdrawing own proposal {}", proposal_id);
+                        self.withdraw_own_proposal(proposal_manager)?;
+                    } else {
+                        debug!("Sending failed response for proposal {}", proposal_id);
+                        let mut response = QuorumMessage::new();
+                        response
+                            .set_message_type(QuorumMessage_Type::PROPOSAL_VERIFICATION_RESPONSE);
+                        response.set_proposal_id(proposal_id.into());
+                        response.set_epoch(proposal.epoch);
+                        response.set_proposal_verification_response(
+                            QuorumMessage_ProposalVerificationResponse::FAILED,
+                        );
+                        network_sender
+                            .send_to(&proposal.coordinator_id, response.write_to_bytes()?)?;
+                    }
+                }
+                _ => warn!("Got invalid message for unknown proposal: {}", proposal_id),
+            },
+            ProposalUpdate::ProposalAccepted(proposal_id) => {
+                info!("proposal accepted: {}", proposal_id);
+            }
+            ProposalUpdate::ProposalAcceptFailed(proposal_id, err) => {
+                error!(
+                    "failed to accept proposal {} due to error: {}",
+                    proposal_id, err
+                );
+            }
+            other => {
+                debug!("ignoring update: {:?}", other);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check this member's own proposal; once it is found to be valid, the other members are
+    /// asked to verify it.
+    fn start_coordination(
+        &mut self,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        let (proposal_id, previous_id) = match self.own_proposal {
+            Some(ref proposal) => (proposal.id.clone(), proposal.previous_id.clone()),
+            None => return Ok(()),
+        };
+
+        debug!(
+            "Starting coordination of proposal {} for epoch {}",
+            proposal_id, self.epoch
+        );
+        match proposal_manager.check_proposal(&proposal_id) {
+            Ok(_) => {
+                self.state = State::EvaluatingProposal(QuorumProposal::new(
+                    proposal_id,
+                    previous_id,
+                    self.epoch,
+                    self.id.clone(),
+                ));
+                self.coordinator_timeout.start();
+            }
+            Err(err) => {
+                debug!(
+                    "Withdrawing proposal {}; failed to check proposal due to err: {}",
+                    proposal_id, err
+                );
+                self.withdraw_own_proposal(proposal_manager)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject this member's own proposal before any other member has been asked to verify it.
+    /// The other members reject the proposal once they accept a conflicting one.
+    fn withdraw_own_proposal(
+        &mut self,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        self.state = State::Idle;
+        self.coordinator_timeout.stop();
+
+        if let Some(proposal) = self.own_proposal.take() {
+            proposal_manager.reject_proposal(&proposal.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn complete_coordination(
+        &mut self,
+        proposal_id: ProposalId,
+        epoch: u64,
+        proposal_result: QuorumMessage_ProposalResult,
+        network_sender: &dyn ConsensusNetworkSender,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        match proposal_result {
+            QuorumMessage_ProposalResult::APPLY => {
+                proposal_manager.accept_proposal(&proposal_id, None)?;
+            }
+            QuorumMessage_ProposalResult::REJECT => {
+                proposal_manager.reject_proposal(&proposal_id)?;
+            }
+            QuorumMessage_ProposalResult::UNSET_RESULT => {
+                warn!(
+                    "Unset proposal result when completing proposal {}",
+                    proposal_id
+                );
+                return Ok(());
+            }
+        }
+
+        self.state = State::Idle;
+        self.coordinator_timeout.stop();
+
+        let own_proposal = match self.own_proposal {
+            Some(ref proposal) if proposal.id == proposal_id => self.own_proposal.take(),
+            _ => None,
+        };
+
+        let mut result = QuorumMessage::new();
+        result.set_message_type(QuorumMessage_Type::PROPOSAL_RESULT);
+        result.set_proposal_id(proposal_id.clone().into());
+        result.set_epoch(epoch);
+        result.set_proposal_result(proposal_result);
+
+        network_sender.broadcast(result.write_to_bytes()?)?;
+
+        if let (QuorumMessage_ProposalResult::APPLY, Some(proposal)) =
+            (proposal_result, own_proposal)
+        {
+            self.reject_conflicting_proposals(
+                &proposal_id,
+                proposal.previous_id,
+                proposal_manager,
+            )?;
+        }
+
+        self.advance_epoch(epoch + 1, network_sender, proposal_manager)
+    }
+
+    /// Check the coordinator timeout if coordinating a proposal, or the epoch timeout if waiting
+    /// on another member to coordinate.
+    fn check_timeouts(
+        &mut self,
+        network_sender: &dyn ConsensusNetworkSender,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        let coordinating = match self.state {
+            State::EvaluatingProposal(ref proposal) if proposal.coordinator_id == self.id => {
+                Some((proposal.proposal_id.clone(), proposal.epoch))
+            }
+            _ => None,
+        };
+
+        if let Some((proposal_id, epoch)) = coordinating {
+            if self.coordinator_timeout.check_expired() {
+                warn!("Proposal timed out; rejecting: {}", proposal_id);
+                self.complete_coordination(
+                    proposal_id,
+                    epoch,
+                    QuorumMessage_ProposalResult::REJECT,
+                    network_sender,
+                    proposal_manager,
+                )?;
+            }
+            return Ok(());
+        }
+
+        // A member that is locked on a proposal waits for the proposal's coordinator to send the
+        // result, rather than moving on to another epoch.
+        let evaluating = match self.state {
+            State::EvaluatingProposal(_) => true,
+            _ => false,
+        };
+        let waiting_on_coordinator = !self.is_coordinator()
+            && !self.is_locked()
+            && (self.own_proposal.is_some() || evaluating);
+
+        if !waiting_on_coordinator {
+            self.epoch_timeout.stop();
+        } else if !self.epoch_timeout.is_started() {
+            self.epoch_timeout.start();
+        } else if self.epoch_timeout.check_expired() {
+            warn!(
+                "Coordinator {} for epoch {} did not respond in time; moving to next epoch",
+                self.coordinator_for(self.epoch),
+                self.epoch
+            );
+            let next_epoch = self.epoch + 1;
+            self.advance_epoch(next_epoch, network_sender, proposal_manager)?;
+        }
+
+        Ok(())
+    }
+
+    /// If not doing anything, see if there are any backlogged verification requests that this
+    /// member has received a proposal for, and evaluate that proposal.
+    fn handle_backlogged_verification_request(
+        &mut self,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        if let State::Idle = self.state {
+            let proposal_backlog = &self.proposal_backlog;
+            if let Some(idx) = self.verification_request_backlog.iter().position(
+                |(proposal_id, _, coordinator_id)| {
+                    proposal_backlog.iter().any(|backlogged| {
+                        &backlogged.proposal_id == proposal_id
+                            && &backlogged.creator_id == coordinator_id
+                    })
+                },
+            ) {
+                // unwraps are safe because the indexes were just found
+                let (proposal_id, epoch, _) =
+                    self.verification_request_backlog.remove(idx).unwrap();
+                let backlog_idx = self
+                    .proposal_backlog
+                    .iter()
+                    .position(|backlogged| backlogged.proposal_id == proposal_id)
+                    .unwrap();
+                let proposal = self.proposal_backlog.remove(backlog_idx).unwrap();
+
+                debug!("Checking proposal from backlog: {}", proposal_id);
+                proposal_manager.check_proposal(&proposal_id)?;
+                self.state = State::EvaluatingProposal(QuorumProposal::new(
+                    proposal.proposal_id,
+                    proposal.previous_id,
+                    epoch,
+                    proposal.creator_id,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// If not doing anything, see if any backlogged proposals have already been applied by the
+    /// network, and check that proposal so it can be applied locally.
+    fn catch_up_on_applied_proposal(
+        &mut self,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        if let State::Idle = self.state {
+            let applied_by_network = &self.applied_by_network;
+            if let Some(idx) = self.proposal_backlog.iter().position(|backlogged| {
+                applied_by_network.get(&backlogged.proposal_id) == Some(&backlogged.creator_id)
+            }) {
+                // unwrap is safe because the index was just found
+                let proposal = self.proposal_backlog.remove(idx).unwrap();
+                self.applied_by_network.remove(&proposal.proposal_id);
+
+                debug!(
+                    "Checking proposal applied by network: {}",
+                    proposal.proposal_id
+                );
+                proposal_manager.check_proposal(&proposal.proposal_id)?;
+                self.state = State::CatchingUp(proposal);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// If not doing anything, try to get the next proposal. A member without a proposal of its
+    /// own asks the proposal manager for one; the coordinator starts coordinating its proposal.
+    fn get_next_proposal(
+        &mut self,
+        proposal_manager: &dyn ProposalManager,
+    ) -> Result<(), ConsensusEngineError> {
+        if let State::Idle = self.state {
+            if self.own_proposal.is_none() {
+                match proposal_manager.create_proposal(None, vec![]) {
+                    Ok(()) => self.state = State::AwaitingProposal,
+                    Err(err) => error!("Error while creating proposal: {}", err),
+                }
+            } else if self.is_coordinator() {
+                if let Err(err) = self.start_coordination(proposal_manager) {
+                    error!("Failed to start coordination for proposal: {}", err);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ConsensusEngine for QuorumEngine {
+    fn name(&self) -> &str {
+        "quorum"
+    }
+
+    fn version(&self) -> &str {
+        "0.1"
+    }
+
+    fn additional_protocols(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    fn run(
+        &mut self,
+        consensus_messages: Receiver<ConsensusMessage>,
+        proposal_updates: Receiver<ProposalUpdate>,
+        network_sender: Box<dyn ConsensusNetworkSender>,
+        proposal_manager: Box<dyn ProposalManager>,
+        startup_state: StartupState,
+    ) -> Result<(), ConsensusEngineError> {
+        let message_timeout = Duration::from_millis(MESSAGE_RECV_TIMEOUT_MILLIS);
+        let proposal_timeout = Duration::from_millis(PROPOSAL_RECV_TIMEOUT_MILLIS);
+
+        self.id = startup_state.id;
+
+        let mut members = startup_state.peer_ids;
+        members.push(self.id.clone());
+        members.sort();
+        members.dedup();
+        self.members = members;
+
+        if let Some(quorum) = self.quorum {
+            if quorum != self.quorum() {
+                warn!(
+                    "Configured quorum of {} is not valid for {} members; using {}",
+                    quorum,
+                    self.members.len(),
+                    self.quorum()
+                );
+            }
+        }
+
+        loop {
+            if let Err(err) = self.check_timeouts(&*network_sender, &*proposal_manager) {
+                error!("Failed to handle timeout: {}", err);
+            }
+
+            if let Err(err) = self.catch_up_on_applied_proposal(&*proposal_manager) {
+                error!("Failed to catch up on applied proposal: {}", err);
+            }
+
+            if let Err(err) = self.handle_backlogged_verification_request(&*proposal_manager) {
+                error!("Failed to handle backlogged verification request: {}", err);
+            }
+
+            if let Err(err) = self.get_next_proposal(&*proposal_manager) {
+                error!("Failed to get next proposal: {}", err);
+            }
+
+            // Get and handle a consensus message if there is one
+            match consensus_messages.recv_timeout(message_timeout) {
+                Ok(consensus_message) => {
+                    if let Err(err) = self.handle_consensus_msg(
+                        consensus_message,
+                        &*network_sender,
+                        &*proposal_manager,
+                    ) {
+                        error!("error while handling consensus message: {}", err);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    info!("consensus message receiver disconnected");
+                    break;
+                }
+            }
+
+            // Get and handle a proposal update if there is one
+            match proposal_updates.recv_timeout(proposal_timeout) {
+                Ok(ProposalUpdate::Shutdown) => {
+                    info!("received shutdown");
+                    break;
+                }
+                Ok(update) => {
+                    if let Err(err) =
+                        self.handle_proposal_update(update, &*network_sender, &*proposal_manager)
+                    {
+                        error!("error while handling proposal update: {}", err);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    info!("proposal update receiver disconnected");
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    use std::sync::mpsc::channel;
+
+    use crate::consensus::tests::{MockConsensusNetworkSender, MockProposalManager};
+
+    const COORDINATOR_TIMEOUT_MILLIS: u64 = 5000;
+
+    /// Verify that the engine properly shuts down when it receives the Shutdown update.
+    #[test]
+    fn test_shutdown() {
+        let (update_tx, update_rx) = channel();
+        let (_, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![0].into(),
+            peer_ids: vec![vec![1].into()],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(COORDINATOR_TIMEOUT_MILLIS), None);
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network),
+                    Box::new(manager),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    /// Test the coordinator of a 5 member network. The proposal should be applied as soon as a
+    /// majority (the coordinator and 2 other members) has verified it, without waiting for the
+    /// remaining members.
+    #[test]
+    fn test_coordinator_quorum() {
+        let (update_tx, update_rx) = channel();
+        let (consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![0].into(),
+            peer_ids: vec![
+                vec![1].into(),
+                vec![2].into(),
+                vec![3].into(),
+                vec![4].into(),
+            ],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(COORDINATOR_TIMEOUT_MILLIS), None);
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        // Check that the verification request is sent for the first proposal in epoch 0
+        loop {
+            if let Some(msg) = network.broadcast_messages().get(0) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(
+                    msg.get_message_type(),
+                    QuorumMessage_Type::PROPOSAL_VERIFICATION_REQUEST
+                );
+                assert_eq!(msg.get_proposal_id(), vec![1].as_slice());
+                assert_eq!(msg.get_epoch(), 0);
+                break;
+            }
+        }
+
+        // Receive verification responses from 2 of the 4 other members
+        let message_bytes = verification_response(
+            vec![1],
+            0,
+            QuorumMessage_ProposalVerificationResponse::VERIFIED,
+        );
+        consensus_msg_tx
+            .send(ConsensusMessage::new(message_bytes.clone(), vec![1].into()))
+            .expect("failed to send 1st response");
+        consensus_msg_tx
+            .send(ConsensusMessage::new(message_bytes, vec![2].into()))
+            .expect("failed to send 2nd response");
+
+        // Verify the Apply message is sent for the proposal
+        loop {
+            if let Some(msg) = network.broadcast_messages().get(1) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(msg.get_message_type(), QuorumMessage_Type::PROPOSAL_RESULT);
+                assert_eq!(
+                    msg.get_proposal_result(),
+                    QuorumMessage_ProposalResult::APPLY
+                );
+                assert_eq!(msg.get_proposal_id(), vec![1].as_slice());
+                assert_eq!(msg.get_epoch(), 0);
+                break;
+            }
+        }
+
+        // Verify the proposal was accepted
+        loop {
+            if let Some((id, _)) = manager.accepted_proposals().get(0) {
+                assert_eq!(id, &vec![1].into());
+                break;
+            }
+        }
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    /// Test a member of a 3 member network that is not the coordinator for the first epoch. The
+    /// member should hold its own proposal, verify and apply the proposal requested by the first
+    /// coordinator, then take over as coordinator for the next epoch and coordinate its own
+    /// proposal.
+    #[test]
+    fn test_coordinator_rotation() {
+        let (update_tx, update_rx) = channel();
+        let (consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![1].into(),
+            peer_ids: vec![vec![0].into(), vec![2].into()],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(COORDINATOR_TIMEOUT_MILLIS), None);
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        // Receive the proposal (ID 10) created by the coordinator for epoch 0 and its request for
+        // verification; the member's own proposal (ID 1) is held until its turn
+        update_tx
+            .send(ProposalUpdate::ProposalReceived(
+                peer_proposal(vec![10]),
+                vec![0].into(),
+            ))
+            .expect("failed to send proposal");
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![10], 0),
+                vec![0].into(),
+            ))
+            .expect("failed to send request");
+
+        // Verify the verified response is sent to the coordinator
+        loop {
+            if let Some((msg, peer_id)) = network.sent_messages().get(0) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(
+                    msg.get_message_type(),
+                    QuorumMessage_Type::PROPOSAL_VERIFICATION_RESPONSE
+                );
+                assert_eq!(msg.get_proposal_id(), vec![10].as_slice());
+                assert_eq!(
+                    msg.get_proposal_verification_response(),
+                    QuorumMessage_ProposalVerificationResponse::VERIFIED
+                );
+                assert_eq!(peer_id, &vec![0].into());
+                break;
+            }
+        }
+
+        // Receive the result from the coordinator
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                proposal_result(vec![10], 0, QuorumMessage_ProposalResult::APPLY),
+                vec![0].into(),
+            ))
+            .expect("failed to send result");
+
+        // Verify the proposal was accepted
+        loop {
+            if let Some((id, _)) = manager.accepted_proposals().get(0) {
+                assert_eq!(id, &vec![10].into());
+                break;
+            }
+        }
+
+        // This member is the coordinator for epoch 1, so it should request verification of its
+        // own proposal
+        loop {
+            if let Some(msg) = network.broadcast_messages().get(0) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(
+                    msg.get_message_type(),
+                    QuorumMessage_Type::PROPOSAL_VERIFICATION_REQUEST
+                );
+                assert_eq!(msg.get_proposal_id(), vec![1].as_slice());
+                assert_eq!(msg.get_epoch(), 1);
+                break;
+            }
+        }
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    /// Test that a member only accepts a proposal result from the coordinator of the proposal's
+    /// epoch. A reject result sent by another member is ignored, and the apply result from the
+    /// coordinator is honored.
+    #[test]
+    fn test_result_from_non_coordinator() {
+        let (update_tx, update_rx) = channel();
+        let (consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![1].into(),
+            peer_ids: vec![vec![0].into(), vec![2].into()],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(COORDINATOR_TIMEOUT_MILLIS), None);
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        update_tx
+            .send(ProposalUpdate::ProposalReceived(
+                peer_proposal(vec![10]),
+                vec![0].into(),
+            ))
+            .expect("failed to send proposal");
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![10], 0),
+                vec![0].into(),
+            ))
+            .expect("failed to send request");
+
+        loop {
+            if !network.sent_messages().is_empty() {
+                break;
+            }
+        }
+
+        // Member 2 is not the coordinator for epoch 0, so its result must be ignored
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                proposal_result(vec![10], 0, QuorumMessage_ProposalResult::REJECT),
+                vec![2].into(),
+            ))
+            .expect("failed to send result");
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                proposal_result(vec![10], 0, QuorumMessage_ProposalResult::APPLY),
+                vec![0].into(),
+            ))
+            .expect("failed to send result");
+
+        loop {
+            if let Some((id, _)) = manager.accepted_proposals().get(0) {
+                assert_eq!(id, &vec![10].into());
+                break;
+            }
+        }
+        assert!(manager.rejected_proposals().is_empty());
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    /// Test that a member that has verified a proposal is locked on it. The member verifies a
+    /// proposal for epoch 0, then the network moves on to epoch 2; the member must not verify
+    /// the proposal requested in epoch 2, even after the coordinator timeout, until the
+    /// coordinator for epoch 0 sends the result of the first proposal.
+    #[test]
+    fn test_vote_locked_until_result() {
+        let (update_tx, update_rx) = channel();
+        let (consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        manager.set_return_proposal(false);
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![1].into(),
+            peer_ids: vec![vec![0].into(), vec![2].into()],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(200), None);
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        // 1. Receive the proposals of members 0 and 2, and verify member 0's proposal
+        update_tx
+            .send(ProposalUpdate::ProposalReceived(
+                peer_proposal(vec![10]),
+                vec![0].into(),
+            ))
+            .expect("failed to send proposal");
+        update_tx
+            .send(ProposalUpdate::ProposalReceived(
+                peer_proposal(vec![20]),
+                vec![2].into(),
+            ))
+            .expect("failed to send proposal");
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![10], 0),
+                vec![0].into(),
+            ))
+            .expect("failed to send request");
+
+        loop {
+            if !network.sent_messages().is_empty() {
+                break;
+            }
+        }
+
+        // 2. Member 2 requests verification of its proposal in epoch 2; the member is locked, so
+        //    it must not respond
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![20], 2),
+                vec![2].into(),
+            ))
+            .expect("failed to send request");
+
+        std::thread::sleep(Duration::from_millis(1000));
+        assert_eq!(network.sent_messages().len(), 1);
+        assert!(manager.abandoned_proposals().is_empty());
+
+        // 3. The coordinator for epoch 0 rejects its proposal, which unlocks the member
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                proposal_result(vec![10], 0, QuorumMessage_ProposalResult::REJECT),
+                vec![0].into(),
+            ))
+            .expect("failed to send result");
+
+        // 4. Verify the member now verifies member 2's proposal
+        loop {
+            if let Some((msg, peer_id)) = network.sent_messages().get(1) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(msg.get_proposal_id(), vec![20].as_slice());
+                assert_eq!(
+                    msg.get_proposal_verification_response(),
+                    QuorumMessage_ProposalVerificationResponse::VERIFIED
+                );
+                assert_eq!(peer_id, &vec![2].into());
+                break;
+            }
+        }
+        assert_eq!(manager.rejected_proposals().as_slice(), &[vec![10].into()]);
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    /// Test that a member that has not verified a proposal abandons it when the network moves on
+    /// to a later epoch, so that the changes prepared for it are rolled back.
+    #[test]
+    fn test_abandon_unverified_proposal() {
+        let (update_tx, update_rx) = channel();
+        let (consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        manager.set_return_proposal(false);
+        manager.set_next_proposal_valid(false);
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![1].into(),
+            peer_ids: vec![vec![0].into(), vec![2].into()],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(COORDINATOR_TIMEOUT_MILLIS), None);
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        update_tx
+            .send(ProposalUpdate::ProposalReceived(
+                peer_proposal(vec![10]),
+                vec![0].into(),
+            ))
+            .expect("failed to send proposal");
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![10], 0),
+                vec![0].into(),
+            ))
+            .expect("failed to send request");
+
+        // Verify the failed response is sent to the coordinator
+        loop {
+            if let Some((msg, _)) = network.sent_messages().get(0) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(
+                    msg.get_proposal_verification_response(),
+                    QuorumMessage_ProposalVerificationResponse::FAILED
+                );
+                break;
+            }
+        }
+
+        // A request from the coordinator for epoch 2 moves the member on, abandoning the proposal
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![20], 2),
+                vec![2].into(),
+            ))
+            .expect("failed to send request");
+
+        loop {
+            if let Some(id) = manager.abandoned_proposals().get(0) {
+                assert_eq!(id, &vec![10].into());
+                break;
+            }
+        }
+        assert!(manager.rejected_proposals().is_empty());
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    /// Test that a member ignores a verification request from a coordinator that did not create
+    /// the proposal, and verifies the proposal once its creator requests verification.
+    #[test]
+    fn test_request_from_non_creator() {
+        let (update_tx, update_rx) = channel();
+        let (consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        manager.set_return_proposal(false);
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![1].into(),
+            peer_ids: vec![vec![0].into(), vec![2].into()],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(COORDINATOR_TIMEOUT_MILLIS), None);
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        update_tx
+            .send(ProposalUpdate::ProposalReceived(
+                peer_proposal(vec![10]),
+                vec![0].into(),
+            ))
+            .expect("failed to send proposal");
+
+        // Member 2 is the coordinator for epoch 2, but did not create the proposal
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![10], 2),
+                vec![2].into(),
+            ))
+            .expect("failed to send request");
+        // Member 0 created the proposal and is the coordinator for epoch 3
+        consensus_msg_tx
+            .send(ConsensusMessage::new(
+                verification_request(vec![10], 3),
+                vec![0].into(),
+            ))
+            .expect("failed to send request");
+
+        loop {
+            if let Some((msg, peer_id)) = network.sent_messages().get(0) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(msg.get_proposal_id(), vec![10].as_slice());
+                assert_eq!(msg.get_epoch(), 3);
+                assert_eq!(peer_id, &vec![0].into());
+                break;
+            }
+        }
+        assert_eq!(network.sent_messages().len(), 1);
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    /// Test that a member skips an unresponsive coordinator. The member has a proposal to commit
+    /// but never hears from the coordinator for epoch 0, so after the timeout it should move to
+    /// epoch 1 (for which it is the coordinator) and coordinate the proposal itself.
+    #[test]
+    fn test_skip_unresponsive_coordinator() {
+        let (update_tx, update_rx) = channel();
+        let (_consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![1].into(),
+            peer_ids: vec![vec![0].into(), vec![2].into()],
+            last_proposal: None,
+        };
+
+        let mut engine = QuorumEngine::new(Duration::from_millis(200), None);
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        loop {
+            if let Some(msg) = network.broadcast_messages().get(0) {
+                let msg: QuorumMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(
+                    msg.get_message_type(),
+                    QuorumMessage_Type::PROPOSAL_VERIFICATION_REQUEST
+                );
+                assert_eq!(msg.get_proposal_id(), vec![1].as_slice());
+                assert_eq!(msg.get_epoch(), 1);
+                break;
+            }
+        }
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
+
+    fn peer_proposal(id: Vec<u8>) -> Proposal {
+        let mut proposal = Proposal::default();
+        proposal.id = id.clone().into();
+        proposal.summary = id;
+        proposal
+    }
+
+    fn verification_request(proposal_id: Vec<u8>, epoch: u64) -> Vec<u8> {
+        let mut msg = QuorumMessage::new();
+        msg.set_message_type(QuorumMessage_Type::PROPOSAL_VERIFICATION_REQUEST);
+        msg.set_proposal_id(proposal_id);
+        msg.set_epoch(epoch);
+        msg.write_to_bytes()
+            .expect("failed to write verification request to bytes")
+    }
+
+    fn verification_response(
+        proposal_id: Vec<u8>,
+        epoch: u64,
+        response: QuorumMessage_ProposalVerificationResponse,
+    ) -> Vec<u8> {
+        let mut msg = QuorumMessage::new();
+        msg.set_message_type(QuorumMessage_Type::PROPOSAL_VERIFICATION_RESPONSE);
+        msg.set_proposal_id(proposal_id);
+        msg.set_epoch(epoch);
+        msg.set_proposal_verification_response(response);
+        msg.write_to_bytes()
+            .expect("failed to write verification response to bytes")
+    }
+
+    fn proposal_result(
+        proposal_id: Vec<u8>,
+        epoch: u64,
+        result: QuorumMessage_ProposalResult,
+    ) -> Vec<u8> {
+        let mut msg = QuorumMessage::new();
+        msg.set_message_type(QuorumMessage_Type::PROPOSAL_RESULT);
+        msg.set_proposal_id(proposal_id);
+        msg.set_epoch(epoch);
+        msg.set_proposal_result(result);
+        msg.write_to_bytes()
+            .expect("failed to write proposal result to bytes")
+    }
+}
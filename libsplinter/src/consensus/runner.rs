@@ -0,0 +1,234 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Plumbing shared by the Splinter services that agree on changes using a consensus engine.
+
+use std::collections::HashSet;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::fmt;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{Builder, JoinHandle};
+
+use protobuf::error::ProtobufError;
+
+use crate::service::ServiceNetworkSender;
+
+use super::error::{ConsensusRunnerError, ConsensusSendError, ProposalManagerError};
+use super::{
+    ConsensusEngine, ConsensusMessage, ConsensusNetworkSender, PeerId, ProposalManager,
+    ProposalUpdate, StartupState,
+};
+
+/// The state a service shares with its consensus components that is needed to reach the other
+/// services it runs consensus with.
+pub trait ServicePeers: Send {
+    /// Returns the sender for messages to the other services; `None` if the service is not
+    /// running.
+    fn network_sender(&self) -> Option<&dyn ServiceNetworkSender>;
+
+    /// Returns the IDs of the other services that the service runs consensus with.
+    fn peer_services(&self) -> &HashSet<String>;
+
+    /// Sends the given message to every peer service.
+    fn send_to_peers(&self, message: &[u8]) -> Result<(), ProposalManagerError> {
+        let sender = self
+            .network_sender()
+            .ok_or(ProposalManagerError::NotReady)?;
+
+        for service in self.peer_services() {
+            sender
+                .send(service, message)
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs a service's consensus engine in a separate thread and forwards consensus messages and
+/// proposal updates to it.
+pub struct ConsensusRunner {
+    consensus_msg_tx: Sender<ConsensusMessage>,
+    proposal_update_tx: Sender<ProposalUpdate>,
+    thread_handle: JoinHandle<()>,
+}
+
+impl ConsensusRunner {
+    /// Starts the consensus engine for the given service.
+    ///
+    /// The proposal manager must send its updates using the sender of `proposal_update_channel`;
+    /// the engine receives them on its receiver.
+    pub fn start(
+        service_id: &str,
+        peer_ids: Vec<PeerId>,
+        mut engine: Box<dyn ConsensusEngine>,
+        network_sender: Box<dyn ConsensusNetworkSender>,
+        proposal_manager: Box<dyn ProposalManager>,
+        proposal_update_channel: (Sender<ProposalUpdate>, Receiver<ProposalUpdate>),
+    ) -> Result<Self, ConsensusRunnerError> {
+        let (consensus_msg_tx, consensus_msg_rx) = channel();
+        let (proposal_update_tx, proposal_update_rx) = proposal_update_channel;
+
+        let startup_state = StartupState {
+            id: service_id.as_bytes().into(),
+            peer_ids,
+            last_proposal: None,
+        };
+
+        let thread_handle = Builder::new()
+            .name(format!("consensus-{}", service_id))
+            .spawn(move || {
+                if let Err(err) = engine.run(
+                    consensus_msg_rx,
+                    proposal_update_rx,
+                    network_sender,
+                    proposal_manager,
+                    startup_state,
+                ) {
+                    error!("{} consensus exited with an error: {}", engine.name(), err)
+                }
+            })
+            .map_err(|err| ConsensusRunnerError(Box::new(err)))?;
+
+        Ok(ConsensusRunner {
+            consensus_msg_tx,
+            proposal_update_tx,
+            thread_handle,
+        })
+    }
+
+    /// Consumes self and shuts down the consensus thread.
+    pub fn shutdown(self) -> Result<(), ConsensusRunnerError> {
+        self.send_update(ProposalUpdate::Shutdown)?;
+
+        self.thread_handle
+            .join()
+            .unwrap_or_else(|err| error!("consensus thread failed: {:?}", err));
+
+        Ok(())
+    }
+
+    /// Parses the given consensus message and passes it to the consensus engine.
+    pub fn handle_message(&self, message_bytes: &[u8]) -> Result<(), ConsensusRunnerError> {
+        let consensus_message = ConsensusMessage::try_from(message_bytes)
+            .map_err(|err| ConsensusRunnerError(Box::new(err)))?;
+
+        self.consensus_msg_tx
+            .send(consensus_message)
+            .map_err(|err| ConsensusRunnerError(Box::new(err)))
+    }
+
+    /// Passes the given proposal update to the consensus engine.
+    pub fn send_update(&self, update: ProposalUpdate) -> Result<(), ConsensusRunnerError> {
+        self.proposal_update_tx
+            .send(update)
+            .map_err(|err| ConsensusRunnerError(Box::new(err)))
+    }
+}
+
+/// A consensus network sender that delivers consensus messages to a service's peers, wrapped in
+/// the service's own message format.
+pub struct ServiceConsensusNetworkSender<S: ServicePeers> {
+    service_id: String,
+    shared: Arc<Mutex<S>>,
+    wrap_message: fn(Vec<u8>) -> Result<Vec<u8>, ProtobufError>,
+}
+
+impl<S: ServicePeers> ServiceConsensusNetworkSender<S> {
+    /// Creates a new network sender for the given service.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The ID of the service that consensus messages originate from
+    /// * `shared` - The service state that holds its network sender and peer services
+    /// * `wrap_message` - Wraps a serialized consensus message in the service's message format
+    pub fn new(
+        service_id: String,
+        shared: Arc<Mutex<S>>,
+        wrap_message: fn(Vec<u8>) -> Result<Vec<u8>, ProtobufError>,
+    ) -> Self {
+        ServiceConsensusNetworkSender {
+            service_id,
+            shared,
+            wrap_message,
+        }
+    }
+
+    fn create_message(&self, message: Vec<u8>) -> Result<Vec<u8>, ConsensusSendError> {
+        let consensus_message = ConsensusMessage::new(message, self.service_id.as_bytes().into());
+        Ok((self.wrap_message)(consensus_message.try_into()?)?)
+    }
+}
+
+impl<S: ServicePeers> ConsensusNetworkSender for ServiceConsensusNetworkSender<S> {
+    fn send_to(&self, peer_id: &PeerId, message: Vec<u8>) -> Result<(), ConsensusSendError> {
+        let peer_id_string = String::from_utf8(peer_id.clone().into())
+            .map_err(|err| ConsensusSendError::Internal(Box::new(err)))?;
+
+        let msg_bytes = self.create_message(message)?;
+
+        let shared = self
+            .shared
+            .lock()
+            .map_err(|_| ConsensusSendError::Internal(Box::new(SharedLockPoisoned)))?;
+
+        if !shared.peer_services().contains(&peer_id_string) {
+            return Err(ConsensusSendError::UnknownPeer(peer_id.clone()));
+        }
+
+        let network_sender = shared
+            .network_sender()
+            .ok_or(ConsensusSendError::NotReady)?;
+
+        network_sender
+            .send(&peer_id_string, msg_bytes.as_slice())
+            .map_err(|err| ConsensusSendError::Internal(Box::new(err)))?;
+
+        Ok(())
+    }
+
+    fn broadcast(&self, message: Vec<u8>) -> Result<(), ConsensusSendError> {
+        let msg_bytes = self.create_message(message)?;
+
+        let shared = self
+            .shared
+            .lock()
+            .map_err(|_| ConsensusSendError::Internal(Box::new(SharedLockPoisoned)))?;
+
+        let network_sender = shared
+            .network_sender()
+            .ok_or(ConsensusSendError::NotReady)?;
+
+        for service in shared.peer_services() {
+            network_sender
+                .send(service, msg_bytes.as_slice())
+                .map_err(|err| ConsensusSendError::Internal(Box::new(err)))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct SharedLockPoisoned;
+
+impl Error for SharedLockPoisoned {}
+
+impl fmt::Display for SharedLockPoisoned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("service state lock was poisoned")
+    }
+}
@@ -59,4 +59,10 @@ impl Timeout {
     pub fn stop(&mut self) {
         self.state = TimeoutState::Inactive;
     }
+
+    /// Check if the timer has been started and has not been stopped
+    #[cfg(feature = "consensus-quorum")]
+    pub fn is_started(&self) -> bool {
+        self.state != TimeoutState::Inactive
+    }
 }
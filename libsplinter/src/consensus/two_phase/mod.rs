@@ -39,8 +39,6 @@
 //! does not know to send the message when it restarts. This limitation will be solved by
 //! re-implementing 2PC as a stateless algorithm.
 
-mod timing;
-
 use std::collections::{HashSet, VecDeque};
 use std::iter::FromIterator;
 use std::sync::mpsc::{Receiver, RecvTimeoutError};
@@ -49,16 +47,15 @@ use std::time::Duration;
 use protobuf::Message;
 
 use crate::consensus::{
-    ConsensusEngine, ConsensusEngineError, ConsensusMessage, ConsensusNetworkSender, PeerId,
-    Proposal, ProposalId, ProposalManager, ProposalUpdate, StartupState,
+    timing::Timeout, ConsensusEngine, ConsensusEngineError, ConsensusMessage,
+    ConsensusNetworkSender, PeerId, Proposal, ProposalId, ProposalManager, ProposalUpdate,
+    StartupState,
 };
 use crate::protos::two_phase::{
     RequiredVerifiers, TwoPhaseMessage, TwoPhaseMessage_ProposalResult,
     TwoPhaseMessage_ProposalVerificationResponse, TwoPhaseMessage_Type,
 };
 
-use self::timing::Timeout;
-
 const MESSAGE_RECV_TIMEOUT_MILLIS: u64 = 100;
 const PROPOSAL_RECV_TIMEOUT_MILLIS: u64 = 100;
 
@@ -16,47 +16,78 @@
  */
 
 pub mod error;
+mod timestamp;
 
 embed_migrations!("./src/biome/migrations/diesel/postgres/migrations");
 
-use std::ops::Deref;
-
-use diesel::{
-    pg::PgConnection,
-    r2d2::{ConnectionManager, Pool, PooledConnection},
-};
+#[cfg(feature = "postgres")]
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+#[cfg(feature = "sqlite")]
+use diesel::{connection::SimpleConnection, r2d2::CustomizeConnection, sqlite::SqliteConnection};
 
 pub use super::database::error::ConnectionError;
+pub(crate) use timestamp::DbTimestamp;
+
+/// The prefixes of database URLs that refer to a PostgreSQL database
+const POSTGRES_URL_PREFIXES: &[&str] = &["postgres://", "postgresql://"];
+/// The optional prefix of database URLs that refer to a SQLite database file
+const SQLITE_URL_PREFIX: &str = "sqlite://";
+/// The SQLite database path that refers to an in-memory database
+#[cfg(feature = "sqlite")]
+const SQLITE_MEMORY_PATH: &str = ":memory:";
 
 enum InnerConnection {
+    #[cfg(feature = "postgres")]
     Pg(PooledConnection<ConnectionManager<PgConnection>>),
+    #[cfg(feature = "sqlite")]
+    Sqlite(PooledConnection<ConnectionManager<SqliteConnection>>),
 }
 
 pub struct Connection {
     inner: InnerConnection,
 }
 
+/// A reference to the backend-specific diesel connection held by a `Connection`
+pub(crate) enum BackendConnection<'a> {
+    #[cfg(feature = "postgres")]
+    Pg(&'a PgConnection),
+    #[cfg(feature = "sqlite")]
+    Sqlite(&'a SqliteConnection),
+}
+
 impl Connection {
+    #[cfg(feature = "postgres")]
     fn new_pg(conn: PooledConnection<ConnectionManager<PgConnection>>) -> Self {
         Connection {
             inner: InnerConnection::Pg(conn),
         }
     }
-}
 
-impl Deref for Connection {
-    type Target = PgConnection;
+    #[cfg(feature = "sqlite")]
+    fn new_sqlite(conn: PooledConnection<ConnectionManager<SqliteConnection>>) -> Self {
+        Connection {
+            inner: InnerConnection::Sqlite(conn),
+        }
+    }
 
-    fn deref(&self) -> &Self::Target {
+    /// Returns the backend-specific diesel connection, for use with the `with_connection!` macro
+    pub(crate) fn backend(&self) -> BackendConnection<'_> {
         match &self.inner {
-            InnerConnection::Pg(conn) => &conn,
+            #[cfg(feature = "postgres")]
+            InnerConnection::Pg(conn) => BackendConnection::Pg(&conn),
+            #[cfg(feature = "sqlite")]
+            InnerConnection::Sqlite(conn) => BackendConnection::Sqlite(&conn),
         }
     }
 }
 
 #[derive(Clone)]
 enum InnerPool {
+    #[cfg(feature = "postgres")]
     Pg(Pool<ConnectionManager<PgConnection>>),
+    #[cfg(feature = "sqlite")]
+    Sqlite(Pool<ConnectionManager<SqliteConnection>>),
 }
 
 #[derive(Clone)]
@@ -65,6 +96,48 @@ pub struct ConnectionPool {
 }
 
 impl ConnectionPool {
+    /// Creates a connection pool for the database at the given URL.
+    ///
+    /// URLs starting with `postgres://` or `postgresql://` refer to a PostgreSQL database. URLs
+    /// starting with `sqlite://`, or paths without a URL scheme, refer to a SQLite database file.
+    /// Any other URL is rejected.
+    pub fn new(database_url: &str) -> Result<Self, ConnectionError> {
+        if POSTGRES_URL_PREFIXES
+            .iter()
+            .any(|prefix| database_url.starts_with(prefix))
+        {
+            #[cfg(feature = "postgres")]
+            {
+                Self::new_pg(database_url)
+            }
+            #[cfg(not(feature = "postgres"))]
+            {
+                Err(ConnectionError {
+                    context: format!("Unsupported database URL: {}", database_url),
+                    source: "PostgreSQL support is not enabled".into(),
+                })
+            }
+        } else if database_url.starts_with(SQLITE_URL_PREFIX) || !database_url.contains("://") {
+            #[cfg(feature = "sqlite")]
+            {
+                Self::new_sqlite(database_url)
+            }
+            #[cfg(not(feature = "sqlite"))]
+            {
+                Err(ConnectionError {
+                    context: format!("Unsupported database URL: {}", database_url),
+                    source: "SQLite support is not enabled".into(),
+                })
+            }
+        } else {
+            Err(ConnectionError {
+                context: format!("Unsupported database URL: {}", database_url),
+                source: "the URL scheme is not postgres://, postgresql:// or sqlite://".into(),
+            })
+        }
+    }
+
+    #[cfg(feature = "postgres")]
     pub fn new_pg(database_url: &str) -> Result<Self, ConnectionError> {
         let connection_manager = ConnectionManager::<PgConnection>::new(database_url);
         Ok(ConnectionPool {
@@ -77,8 +150,35 @@ impl ConnectionPool {
         })
     }
 
+    /// Creates a connection pool for the SQLite database file at the given path.
+    ///
+    /// Foreign key constraints are enabled on every connection. An in-memory database
+    /// (`:memory:`) is limited to a single connection, as each SQLite connection would otherwise
+    /// open its own separate database.
+    #[cfg(feature = "sqlite")]
+    pub fn new_sqlite(database_url: &str) -> Result<Self, ConnectionError> {
+        let path = database_url
+            .trim_start_matches(SQLITE_URL_PREFIX)
+            .to_string();
+        let max_size = if path == SQLITE_MEMORY_PATH { 1 } else { 10 };
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(path);
+        Ok(ConnectionPool {
+            inner: InnerPool::Sqlite(
+                Pool::builder()
+                    .max_size(max_size)
+                    .connection_customizer(Box::new(SqliteConnectionCustomizer))
+                    .build(connection_manager)
+                    .map_err(|err| ConnectionError {
+                        context: "Failed to build connection pool".to_string(),
+                        source: Box::new(err),
+                    })?,
+            ),
+        })
+    }
+
     pub fn get(&self) -> Result<Connection, ConnectionError> {
         match &self.inner {
+            #[cfg(feature = "postgres")]
             InnerPool::Pg(pool) => {
                 pool.get()
                     .map(Connection::new_pg)
@@ -87,6 +187,46 @@ impl ConnectionPool {
                         source: Box::new(err),
                     })
             }
+            #[cfg(feature = "sqlite")]
+            InnerPool::Sqlite(pool) => {
+                pool.get()
+                    .map(Connection::new_sqlite)
+                    .map_err(|err| ConnectionError {
+                        context: "Failed to get Connection from connection pool".to_string(),
+                        source: Box::new(err),
+                    })
+            }
+        }
+    }
+}
+
+/// Configures each new SQLite connection; SQLite does not enforce foreign keys by default, which
+/// the `ON DELETE CASCADE` constraints of the biome tables rely on.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+struct SqliteConnectionCustomizer;
+
+#[cfg(feature = "sqlite")]
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for SqliteConnectionCustomizer {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+        conn.batch_execute("PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;")
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that database URLs with a scheme other than PostgreSQL's or SQLite's are
+    /// rejected, instead of being treated as the path to a SQLite database file.
+    #[test]
+    fn test_unsupported_database_url() {
+        for url in &["mysql://localhost/splinter", "postgress://localhost/splinter"] {
+            match ConnectionPool::new(url) {
+                Err(err) => assert!(err.to_string().contains("Unsupported database URL")),
+                Ok(_) => panic!("Database URL {} was not rejected", url),
+            }
         }
     }
 }
@@ -0,0 +1,243 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides a timestamp type that may be stored by every supported database backend.
+
+#[cfg(feature = "sqlite")]
+use std::error::Error;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use std::io::Write;
+#[cfg(feature = "sqlite")]
+use std::time::Duration;
+use std::time::SystemTime;
+
+#[cfg(feature = "postgres")]
+use diesel::pg::Pg;
+use diesel::sql_types::Timestamp;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql},
+    serialize::{self, Output, ToSql},
+};
+#[cfg(feature = "sqlite")]
+use diesel::{sql_types::Text, sqlite::Sqlite};
+
+/// A point in time stored in a `Timestamp` column.
+///
+/// PostgreSQL stores the timestamp natively. SQLite has no timestamp storage class, so the
+/// timestamp is stored as UTC text of the form `YYYY-MM-DD HH:MM:SS.ffffff`; the fixed width of
+/// this format keeps the lexical order of the stored values the same as their chronological
+/// order, allowing timestamps to be compared in queries.
+#[derive(AsExpression, FromSqlRow, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[sql_type = "Timestamp"]
+pub struct DbTimestamp(SystemTime);
+
+impl DbTimestamp {
+    /// Returns the current time
+    pub fn now() -> Self {
+        DbTimestamp(SystemTime::now())
+    }
+
+    /// Formats the timestamp as it is stored by SQLite
+    #[cfg(feature = "sqlite")]
+    fn to_sqlite_string(self) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let since_epoch = self
+            .0
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| "Timestamps before the Unix epoch are not supported")?;
+        let secs = since_epoch.as_secs();
+        let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+        let secs_of_day = secs % 86_400;
+        Ok(format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}",
+            year,
+            month,
+            day,
+            secs_of_day / 3600,
+            secs_of_day % 3600 / 60,
+            secs_of_day % 60,
+            since_epoch.subsec_micros()
+        ))
+    }
+
+    /// Parses a timestamp as it is stored by SQLite; the fractional seconds are optional, so
+    /// values set by SQLite's `CURRENT_TIMESTAMP` are accepted as well.
+    #[cfg(feature = "sqlite")]
+    fn from_sqlite_str(value: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let invalid = || format!("Invalid timestamp: {}", value);
+
+        let mut date_time = value.splitn(2, |c| c == ' ' || c == 'T');
+        let date = date_time.next().ok_or_else(invalid)?;
+        let time = date_time.next().ok_or_else(invalid)?;
+
+        let mut date_parts = date.splitn(3, '-');
+        let mut next_date_part = || -> Result<u64, String> {
+            date_parts
+                .next()
+                .and_then(|part| part.parse().ok())
+                .ok_or_else(invalid)
+        };
+        let year = next_date_part()?;
+        let month = next_date_part()?;
+        let day = next_date_part()?;
+
+        let (time, fraction) = match time.find('.') {
+            Some(index) => (&time[..index], &time[index + 1..]),
+            None => (time, ""),
+        };
+        let mut time_parts = time.splitn(3, ':');
+        let mut next_time_part = || -> Result<u64, String> {
+            time_parts
+                .next()
+                .and_then(|part| part.parse().ok())
+                .ok_or_else(invalid)
+        };
+        let hours = next_time_part()?;
+        let minutes = next_time_part()?;
+        let seconds = next_time_part()?;
+
+        if year < 1970 || month < 1 || month > 12 || day < 1 || day > 31 {
+            return Err(invalid().into());
+        }
+        if hours > 23 || minutes > 59 || seconds > 60 {
+            return Err(invalid().into());
+        }
+
+        let micros = if fraction.is_empty() {
+            0
+        } else {
+            if !fraction.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid().into());
+            }
+            format!("{:0<6}", &fraction[..fraction.len().min(6)])
+                .parse::<u64>()
+                .map_err(|_| invalid())?
+        };
+
+        let days = days_from_civil(year as i64, month as u32, day as u32) as u64;
+        let secs = days * 86_400 + hours * 3600 + minutes * 60 + seconds;
+
+        Ok(DbTimestamp(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_micros(micros),
+        ))
+    }
+}
+
+impl From<SystemTime> for DbTimestamp {
+    fn from(time: SystemTime) -> Self {
+        DbTimestamp(time)
+    }
+}
+
+impl From<DbTimestamp> for SystemTime {
+    fn from(timestamp: DbTimestamp) -> Self {
+        timestamp.0
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl ToSql<Timestamp, Pg> for DbTimestamp {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        ToSql::<Timestamp, Pg>::to_sql(&self.0, out)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl FromSql<Timestamp, Pg> for DbTimestamp {
+    fn from_sql(bytes: Option<&<Pg as Backend>::RawValue>) -> deserialize::Result<Self> {
+        <SystemTime as FromSql<Timestamp, Pg>>::from_sql(bytes).map(DbTimestamp)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl ToSql<Timestamp, Sqlite> for DbTimestamp {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Sqlite>) -> serialize::Result {
+        ToSql::<Text, Sqlite>::to_sql(&self.to_sqlite_string()?, out)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl FromSql<Timestamp, Sqlite> for DbTimestamp {
+    fn from_sql(value: Option<&<Sqlite as Backend>::RawValue>) -> deserialize::Result<Self> {
+        let text = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+        DbTimestamp::from_sqlite_str(&text)
+    }
+}
+
+/// Converts a number of days since 1970-01-01 to a (year, month, day) date in the proleptic
+/// Gregorian calendar.
+#[cfg(feature = "sqlite")]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = (if days >= 0 { days } else { days - 146_096 }) / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = (if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    }) as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Converts a (year, month, day) date in the proleptic Gregorian calendar to the number of days
+/// since 1970-01-01.
+#[cfg(feature = "sqlite")]
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let year_of_era = year - era * 400;
+    let shifted_month = i64::from(if month > 2 { month - 3 } else { month + 9 });
+    let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    /// Verifies that timestamps are formatted as expected for SQLite, and that the formatted
+    /// value parses back to the same timestamp.
+    #[test]
+    fn test_sqlite_round_trip() {
+        let epoch = DbTimestamp::from(SystemTime::UNIX_EPOCH);
+        assert_eq!(
+            epoch.to_sqlite_string().expect("Failed to format epoch"),
+            "1970-01-01 00:00:00.000000"
+        );
+
+        let time = DbTimestamp::from(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_583_020_800) + Duration::from_micros(42),
+        );
+        let formatted = time.to_sqlite_string().expect("Failed to format timestamp");
+        assert_eq!(formatted, "2020-03-01 00:00:00.000042");
+        assert_eq!(
+            DbTimestamp::from_sqlite_str(&formatted).expect("Failed to parse timestamp"),
+            time
+        );
+
+        assert_eq!(
+            DbTimestamp::from_sqlite_str("2020-03-01 00:00:00").expect("Failed to parse"),
+            DbTimestamp::from(SystemTime::UNIX_EPOCH + Duration::from_secs(1_583_020_800))
+        );
+        assert!(DbTimestamp::from_sqlite_str("not a timestamp").is_err());
+    }
+}
@@ -56,7 +56,40 @@ macro_rules! mutex_lock_unwrap {
     };
 }
 
+/// Gets a connection from a `database::ConnectionPool` and evaluates `$body` with `$conn` bound to
+/// the backend-specific diesel connection, e.g. `&PgConnection` or `&SqliteConnection`.
+#[cfg(feature = "diesel")]
+macro_rules! with_connection {
+    ($pool:expr, |$conn:ident| $body:expr) => {
+        match $pool.get()?.backend() {
+            #[cfg(feature = "postgres")]
+            $crate::database::BackendConnection::Pg($conn) => $body,
+            #[cfg(feature = "sqlite")]
+            $crate::database::BackendConnection::Sqlite($conn) => $body,
+        }
+    };
+}
+
+/// Implements a store operation trait for the operations struct `$ops` with each enabled diesel
+/// backend's connection, for operations whose implementation is the same for every backend.
+#[cfg(all(feature = "biome", feature = "diesel"))]
+macro_rules! impl_for_diesel_backends {
+    ($operation:ident for $ops:ident { $($body:tt)* }) => {
+        #[cfg(feature = "postgres")]
+        impl<'a> $operation for $ops<'a, diesel::pg::PgConnection> {
+            $($body)*
+        }
+
+        #[cfg(feature = "sqlite")]
+        impl<'a> $operation for $ops<'a, diesel::sqlite::SqliteConnection> {
+            $($body)*
+        }
+    };
+}
+
 pub mod admin;
+#[cfg(feature = "audit")]
+pub mod audit;
 mod base62;
 #[cfg(feature = "biome")]
 pub mod biome;
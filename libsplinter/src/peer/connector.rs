@@ -17,8 +17,8 @@ use std::sync::mpsc::{channel, Sender};
 use crate::collections::BiHashMap;
 
 use super::error::{
-    PeerConnectionIdError, PeerListError, PeerLookupError, PeerManagerError, PeerRefAddError,
-    PeerRefRemoveError, PeerUnknownAddError,
+    PeerConnectionIdError, PeerEndpointsUpdateError, PeerListError, PeerLookupError,
+    PeerManagerError, PeerRefAddError, PeerRefRemoveError, PeerUnknownAddError,
 };
 use super::notification::{PeerManagerNotification, PeerNotificationIter, SubscriberId};
 use super::{EndpointPeerRef, PeerRef};
@@ -127,6 +127,42 @@ impl PeerManagerConnector {
             .map_err(|err| PeerUnknownAddError::ReceiveError(format!("{:?}", err)))?
     }
 
+    /// Requests that the endpoints of a peer are replaced, e.g. after the peer's registry entry
+    /// has changed. If the peer is not currently connected, the `PeerManager` will immediately
+    /// try to connect to it using the new endpoints. Updating a peer that has not been added is
+    /// not an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `peer_id` -  The unique ID for the peer.
+    /// * `endpoints` -  The new list of endpoints associated with the peer, in order of
+    ///   preference.
+    pub fn update_peer_endpoints(
+        &self,
+        peer_id: String,
+        endpoints: Vec<String>,
+    ) -> Result<(), PeerEndpointsUpdateError> {
+        let (sender, recv) = channel();
+
+        let message = PeerManagerMessage::Request(PeerManagerRequest::UpdateEndpoints {
+            peer_id,
+            endpoints,
+            sender,
+        });
+
+        match self.sender.send(message) {
+            Ok(()) => (),
+            Err(_) => {
+                return Err(PeerEndpointsUpdateError::InternalError(
+                    "Unable to send message to PeerManager, receiver dropped".to_string(),
+                ))
+            }
+        };
+
+        recv.recv()
+            .map_err(|err| PeerEndpointsUpdateError::ReceiveError(format!("{:?}", err)))?
+    }
+
     /// Requests the list of currently connected peers.
     ///
     /// Returns the list of peer IDs.
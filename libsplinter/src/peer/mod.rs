@@ -47,8 +47,8 @@ pub use self::builder::PeerManagerBuilder;
 pub use self::connector::PeerManagerConnector;
 use self::connector::PeerRemover;
 use self::error::{
-    PeerConnectionIdError, PeerListError, PeerLookupError, PeerManagerError, PeerRefAddError,
-    PeerRefRemoveError, PeerUnknownAddError,
+    PeerConnectionIdError, PeerEndpointsUpdateError, PeerListError, PeerLookupError,
+    PeerManagerError, PeerRefAddError, PeerRefRemoveError, PeerUnknownAddError,
 };
 pub use self::notification::{PeerManagerNotification, PeerNotificationIter, SubscriberId};
 use self::notification::{Subscriber, SubscriberMap};
@@ -95,6 +95,11 @@ pub(crate) enum PeerManagerRequest {
         endpoint: String,
         sender: Sender<Result<(), PeerRefRemoveError>>,
     },
+    UpdateEndpoints {
+        peer_id: String,
+        endpoints: Vec<String>,
+        sender: Sender<Result<(), PeerEndpointsUpdateError>>,
+    },
     ListPeers {
         sender: Sender<Result<Vec<String>, PeerListError>>,
     },
@@ -452,6 +457,18 @@ fn handle_request(
                 warn!("Connector dropped before receiving result of removing peer");
             }
         }
+        PeerManagerRequest::UpdateEndpoints {
+            peer_id,
+            endpoints,
+            sender,
+        } => {
+            if sender
+                .send(update_endpoints(peer_id, endpoints, connector, peers))
+                .is_err()
+            {
+                warn!("Connector dropped before receiving result of updating peer endpoints");
+            }
+        }
         PeerManagerRequest::ListPeers { sender } => {
             if sender.send(Ok(peers.peer_ids())).is_err() {
                 warn!("Connector dropped before receiving result of list peers");
@@ -695,6 +712,60 @@ fn add_peer(
     Ok(peer_ref)
 }
 
+// Replace the endpoints of a peer. A connected peer keeps its current connection and uses the new
+// endpoints the next time it reconnects; a peer that is not connected is retried immediately
+// using the new endpoints.
+fn update_endpoints(
+    peer_id: String,
+    endpoints: Vec<String>,
+    connector: Connector,
+    peers: &mut PeerMap,
+) -> Result<(), PeerEndpointsUpdateError> {
+    if endpoints.is_empty() {
+        return Err(PeerEndpointsUpdateError::UpdateError(format!(
+            "No endpoints provided for peer {}",
+            peer_id
+        )));
+    }
+
+    let mut peer_metadata = match peers.get_by_peer_id(&peer_id) {
+        Some(peer_metadata) if peer_metadata.endpoints != endpoints => peer_metadata.clone(),
+        // The peer is not known or its endpoints have not changed
+        _ => return Ok(()),
+    };
+
+    debug!("Updating peer {} to endpoints {:?}", peer_id, endpoints);
+    peers
+        .update_endpoints(&peer_id, endpoints.clone())
+        .map_err(|err| PeerEndpointsUpdateError::UpdateError(err.to_string()))?;
+    peer_metadata.endpoints = endpoints;
+
+    if peer_metadata.status == PeerStatus::Connected {
+        return Ok(());
+    }
+
+    for endpoint in peer_metadata.endpoints.iter() {
+        match connector.request_connection(&endpoint, &peer_metadata.connection_id) {
+            Ok(()) => {
+                peer_metadata.active_endpoint = endpoint.to_string();
+                break;
+            }
+            // If request_connection errored we will retry in the future
+            Err(err) => {
+                error!(
+                    "Unable to request connection for peer {}: {}",
+                    peer_metadata.id, err
+                );
+            }
+        }
+    }
+    peer_metadata.last_connection_attempt = Instant::now();
+
+    peers
+        .update_peer(peer_metadata)
+        .map_err(|err| PeerEndpointsUpdateError::UpdateError(err.to_string()))
+}
+
 // Request a connection, the resulting connection will be treated as an InboundConnection
 fn add_unidentified(
     endpoint: String,
@@ -1461,6 +1532,76 @@ pub mod tests {
         mesh.shutdown_signaler().shutdown();
     }
 
+    // Test that updating the endpoints of a pending peer connects it using the new endpoints
+    //
+    // 1. add test_peer with an endpoint that is not listening
+    // 2. update test_peer's endpoints to an endpoint that is listening
+    // 3. validate a Connected notification is eventually returned
+    #[test]
+    fn test_peer_manager_update_endpoints() {
+        let mut transport = Box::new(InprocTransport::default());
+        let mut listener = transport.listen("inproc://test").unwrap();
+
+        thread::spawn(move || {
+            listener.accept().unwrap();
+        });
+
+        let mesh = Mesh::new(512, 128);
+        let cm = ConnectionManager::builder()
+            .with_authorizer(Box::new(NoopAuthorizer::new("test_peer")))
+            .with_matrix_life_cycle(mesh.get_life_cycle())
+            .with_matrix_sender(mesh.get_sender())
+            .with_transport(transport.clone())
+            .start()
+            .expect("Unable to start Connection Manager");
+
+        let connector = cm.connector();
+        let peer_manager = PeerManager::builder()
+            .with_connector(connector)
+            .with_retry_interval(1)
+            .with_identity("my_id".to_string())
+            .with_strict_ref_counts(true)
+            .start()
+            .expect("Cannot start peer_manager");
+        let peer_connector = peer_manager.connector();
+        let (tx, notification_rx): (
+            Sender<PeerManagerNotification>,
+            mpsc::Receiver<PeerManagerNotification>,
+        ) = channel();
+        peer_connector
+            .subscribe_sender(tx)
+            .expect("Unable to get subscriber");
+        let _peer_ref = peer_connector
+            .add_peer_ref("test_peer".to_string(), vec!["inproc://moved".to_string()])
+            .expect("Unable to add peer");
+
+        peer_connector
+            .update_peer_endpoints("test_peer".to_string(), vec!["inproc://test".to_string()])
+            .expect("Unable to update peer endpoints");
+
+        // timeout after 60 seconds; a Disconnected notification may be received for the original
+        // endpoint first
+        let timeout = Duration::from_secs(60);
+        loop {
+            let notification = notification_rx
+                .recv_timeout(timeout)
+                .expect("Unable to get new notifications");
+            if notification
+                == (PeerManagerNotification::Connected {
+                    peer: "test_peer".to_string(),
+                })
+            {
+                break;
+            }
+        }
+
+        peer_manager.shutdown_handle().unwrap().shutdown();
+        cm.shutdown_signaler().shutdown();
+        peer_manager.await_shutdown();
+        cm.await_shutdown();
+        mesh.shutdown_signaler().shutdown();
+    }
+
     // Test that a call to add_peer_ref, where the authorizer returns an different id than
     // requested, the connector returns an error.
     //
@@ -134,6 +134,29 @@ impl PeerMap {
         }
     }
 
+    /// Replaces the endpoints of an existing peer, removing its previous endpoints from the
+    /// endpoint table.
+    pub fn update_endpoints(
+        &mut self,
+        peer_id: &str,
+        endpoints: Vec<String>,
+    ) -> Result<(), PeerUpdateError> {
+        let peer_metadata = self.peers.get_mut(peer_id).ok_or_else(|| {
+            PeerUpdateError(format!("Unable to update peer {}, does not exist", peer_id))
+        })?;
+
+        for endpoint in peer_metadata.endpoints.iter() {
+            self.endpoints.remove(endpoint);
+        }
+        for endpoint in endpoints.iter() {
+            self.endpoints
+                .insert(endpoint.to_string(), peer_id.to_string());
+        }
+        peer_metadata.endpoints = endpoints;
+
+        Ok(())
+    }
+
     /// Returns the endpoint for the given peer ID
     pub fn get_peer_from_endpoint(&self, endpoint: &str) -> Option<&PeerMetadata> {
         if let Some(peer) = self.endpoints.get(endpoint) {
@@ -402,4 +425,44 @@ pub mod tests {
             PeerStatus::Disconnected { retry_attempts: 5 }
         );
     }
+
+    // Test that a peer's endpoints can be replaced
+    //  1. Verify that the endpoints of a peer that does not exist cannot be updated
+    //  2. Insert test_peer with test_endpoint1 and test_endpoint2
+    //  3. Replace the endpoints with test_endpoint2 and new_endpoint
+    //  4. Check that the peer can be found by its new endpoints but not by the removed endpoint
+    #[test]
+    fn test_update_endpoints() {
+        let mut peer_map = PeerMap::new(10);
+
+        assert!(peer_map
+            .update_endpoints("test_peer", vec!["new_endpoint".to_string()])
+            .is_err());
+
+        peer_map.insert(
+            "test_peer".to_string(),
+            "connection_id".to_string(),
+            vec!["test_endpoint1".to_string(), "test_endpoint2".to_string()],
+            "test_endpoint1".to_string(),
+            PeerStatus::Pending,
+        );
+
+        peer_map
+            .update_endpoints(
+                "test_peer",
+                vec!["test_endpoint2".to_string(), "new_endpoint".to_string()],
+            )
+            .expect("Unable to update endpoints");
+
+        assert_eq!(
+            peer_map
+                .get_by_peer_id("test_peer")
+                .expect("Missing peer_metadata")
+                .endpoints,
+            vec!["test_endpoint2".to_string(), "new_endpoint".to_string()]
+        );
+        assert!(peer_map.get_peer_from_endpoint("test_endpoint1").is_none());
+        assert!(peer_map.get_peer_from_endpoint("test_endpoint2").is_some());
+        assert!(peer_map.contains_endpoint("new_endpoint"));
+    }
 }
@@ -43,9 +43,23 @@ pub const REGISTRY_PROTOCOL_VERSION: u32 = 1;
 pub(crate) const REGISTRY_LIST_NODES_MIN: u32 = 1;
 #[cfg(all(feature = "registry", feature = "rest-api-actix"))]
 pub(crate) const REGISTRY_FETCH_NODE_MIN: u32 = 1;
+#[cfg(all(feature = "registry-bulk", feature = "rest-api-actix"))]
+pub(crate) const REGISTRY_BULK_MIN: u32 = 1;
+#[cfg(all(feature = "registry-events", feature = "rest-api-actix"))]
+pub(crate) const REGISTRY_WS_EVENTS_MIN: u32 = 1;
+#[cfg(all(feature = "registry-merge", feature = "rest-api-actix"))]
+pub(crate) const REGISTRY_LIST_CONFLICTS_MIN: u32 = 1;
+#[cfg(all(feature = "registry-health", feature = "rest-api-actix"))]
+pub(crate) const REGISTRY_FETCH_HEALTH_MIN: u32 = 1;
+
+#[cfg(feature = "audit")]
+pub const AUDIT_PROTOCOL_VERSION: u32 = 1;
+
+#[cfg(all(feature = "audit", feature = "rest-api-actix"))]
+pub(crate) const AUDIT_LIST_ENTRIES_MIN: u32 = 1;
 
 #[cfg(feature = "biome")]
-pub const BIOME_PROTOCOL_VERSION: u32 = 1;
+pub const BIOME_PROTOCOL_VERSION: u32 = 2;
 
 #[cfg(all(feature = "biome-credentials", feature = "rest-api",))]
 pub(crate) const BIOME_REGISTER_PROTOCOL_MIN: u32 = 1;
@@ -60,3 +74,27 @@ pub(crate) const BIOME_VERIFY_PROTOCOL_MIN: u32 = 1;
 
 #[cfg(all(feature = "biome-key-management", feature = "rest-api",))]
 pub(crate) const BIOME_KEYS_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-roles", feature = "rest-api",))]
+pub(crate) const BIOME_ROLES_PROTOCOL_MIN: u32 = 2;
+
+#[cfg(all(feature = "biome-notifications", feature = "rest-api",))]
+pub(crate) const BIOME_NOTIFICATIONS_PROTOCOL_MIN: u32 = 2;
+
+#[cfg(all(feature = "biome-oauth", feature = "rest-api",))]
+pub(crate) const BIOME_OAUTH_PROTOCOL_MIN: u32 = 2;
+
+#[cfg(all(feature = "biome-account-security", feature = "rest-api",))]
+pub(crate) const BIOME_PASSWORD_RESET_PROTOCOL_MIN: u32 = 2;
+
+#[cfg(all(feature = "biome-sessions", feature = "rest-api",))]
+pub(crate) const BIOME_SESSIONS_PROTOCOL_MIN: u32 = 2;
+
+#[cfg(all(feature = "biome-key-encryption", feature = "rest-api",))]
+pub(crate) const BIOME_KEY_ENCRYPTION_PROTOCOL_MIN: u32 = 2;
+
+#[cfg(all(
+    feature = "rest-api-keyring",
+    any(feature = "biome-key-management", feature = "biome-credentials"),
+))]
+pub(crate) const BIOME_JWKS_PROTOCOL_MIN: u32 = 2;
@@ -0,0 +1,605 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A database-backed registry.
+//!
+//! This module contains the [`DieselRegistry`], which provides an implementation of the
+//! [`RwRegistry`] trait backed by a PostgreSQL or SQLite database.
+//!
+//! [`DieselRegistry`]: struct.DieselRegistry.html
+//! [`RwRegistry`]: ../trait.RwRegistry.html
+
+mod models;
+mod operations;
+mod schema;
+
+use crate::database::ConnectionPool;
+#[cfg(feature = "registry-events")]
+use crate::registry::events::{diff_nodes, RegistryEventSubscribers};
+use crate::registry::{
+    MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader, RegistryWriter, RwRegistry,
+};
+#[cfg(feature = "registry-events")]
+use crate::registry::{RegistryEvent, RegistryEventSubscriber};
+
+use operations::count_nodes::RegistryCountNodesOperation as _;
+use operations::delete_node::RegistryDeleteNodeOperation as _;
+#[cfg(feature = "registry-bulk")]
+use operations::delete_nodes::RegistryDeleteNodesOperation as _;
+use operations::fetch_node::RegistryFetchNodeOperation as _;
+use operations::insert_node::RegistryInsertNodeOperation as _;
+#[cfg(feature = "registry-bulk")]
+use operations::insert_nodes::RegistryInsertNodesOperation as _;
+use operations::list_nodes::RegistryListNodesOperation as _;
+use operations::RegistryOperations;
+
+/// A read/write registry backed by a database.
+///
+/// Each node's metadata is stored in an indexed table, so the `MetadataPredicate`s passed to
+/// `list_nodes` and `count_nodes` are evaluated by the database rather than by loading every node.
+/// Metadata values are compared as text by the database, using the database's collation for the
+/// `Gt`, `Ge`, `Lt` and `Le` predicates. Numeric predicates, and any group containing one, are
+/// applied to the nodes after they are loaded.
+///
+/// The tables must be created by running the registry migrations, e.g. with
+/// `splinter database migrate`.
+///
+/// Event subscribers are only notified of the changes made through this registry or its clones;
+/// changes made to the database by other processes are not reported.
+#[derive(Clone)]
+pub struct DieselRegistry {
+    connection_pool: ConnectionPool,
+    #[cfg(feature = "registry-events")]
+    subscribers: RegistryEventSubscribers,
+}
+
+impl DieselRegistry {
+    /// Creates a new `DieselRegistry`
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: ConnectionPool) -> Self {
+        Self {
+            connection_pool,
+            #[cfg(feature = "registry-events")]
+            subscribers: RegistryEventSubscribers::default(),
+        }
+    }
+}
+
+impl RegistryReader for DieselRegistry {
+    fn list_nodes<'a, 'b: 'a>(
+        &'b self,
+        predicates: &'a [MetadataPredicate],
+    ) -> Result<NodeIter<'a>, RegistryError> {
+        let nodes = with_connection!(self.connection_pool, |conn| {
+            RegistryOperations::new(conn).list_nodes(predicates)
+        })?;
+        Ok(Box::new(nodes.into_iter()))
+    }
+
+    fn count_nodes(&self, predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+        with_connection!(self.connection_pool, |conn| {
+            RegistryOperations::new(conn).count_nodes(predicates)
+        })
+    }
+
+    fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        with_connection!(self.connection_pool, |conn| {
+            RegistryOperations::new(conn).fetch_node(identity)
+        })
+    }
+
+    #[cfg(feature = "registry-events")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.subscribers.add(subscriber)
+    }
+}
+
+impl RegistryWriter for DieselRegistry {
+    fn insert_node(&self, node: Node) -> Result<(), RegistryError> {
+        #[cfg(feature = "registry-events")]
+        let previous = self.fetch_node(&node.identity)?;
+        #[cfg(feature = "registry-events")]
+        let current = node.clone();
+
+        with_connection!(self.connection_pool, |conn| {
+            RegistryOperations::new(conn).insert_node(node)
+        })?;
+
+        #[cfg(feature = "registry-events")]
+        self.subscribers.broadcast(diff_nodes(
+            &previous.into_iter().collect::<Vec<_>>(),
+            &[current],
+        ));
+
+        Ok(())
+    }
+
+    fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        let deleted = with_connection!(self.connection_pool, |conn| {
+            RegistryOperations::new(conn).delete_node(identity)
+        })?;
+
+        #[cfg(feature = "registry-events")]
+        {
+            if let Some(node) = &deleted {
+                self.subscribers
+                    .broadcast(vec![RegistryEvent::NodeRemoved(node.clone())]);
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    #[cfg(feature = "registry-bulk")]
+    fn insert_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError> {
+        #[cfg(feature = "registry-events")]
+        let previous = nodes
+            .iter()
+            .filter_map(|node| self.fetch_node(&node.identity).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+        #[cfg(feature = "registry-events")]
+        let current = nodes.clone();
+
+        with_connection!(self.connection_pool, |conn| {
+            RegistryOperations::new(conn).insert_nodes(nodes)
+        })?;
+
+        #[cfg(feature = "registry-events")]
+        self.subscribers.broadcast(diff_nodes(&previous, &current));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "registry-bulk")]
+    fn delete_nodes(&self, identities: &[String]) -> Result<Vec<Node>, RegistryError> {
+        let deleted = with_connection!(self.connection_pool, |conn| {
+            RegistryOperations::new(conn).delete_nodes(identities)
+        })?;
+
+        #[cfg(feature = "registry-events")]
+        self.subscribers.broadcast(
+            deleted
+                .iter()
+                .cloned()
+                .map(RegistryEvent::NodeRemoved)
+                .collect(),
+        );
+
+        Ok(deleted)
+    }
+}
+
+impl RwRegistry for DieselRegistry {
+    fn clone_box(&self) -> Box<dyn RwRegistry> {
+        Box::new(self.clone())
+    }
+
+    fn clone_box_as_reader(&self) -> Box<dyn RegistryReader> {
+        Box::new(self.clone())
+    }
+
+    fn clone_box_as_writer(&self) -> Box<dyn RegistryWriter> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use crate::database::BackendConnection;
+    use crate::registry::migrations::run_sqlite_migrations;
+    use crate::registry::InvalidNodeError;
+
+    /// Creates a registry backed by an in-memory SQLite database with the registry tables
+    fn create_registry() -> DieselRegistry {
+        let connection_pool =
+            ConnectionPool::new_sqlite(":memory:").expect("Failed to create connection pool");
+        {
+            let connection = connection_pool.get().expect("Failed to get connection");
+            match connection.backend() {
+                BackendConnection::Sqlite(conn) => {
+                    run_sqlite_migrations(conn).expect("Failed to run migrations")
+                }
+                #[allow(unreachable_patterns)]
+                _ => panic!("Expected a SQLite connection"),
+            }
+        }
+        DieselRegistry::new(connection_pool)
+    }
+
+    fn node(identity: &str, endpoint: &str, company: &str) -> Node {
+        Node::builder(identity)
+            .with_endpoint(endpoint)
+            .with_display_name(identity)
+            .with_key("abcd")
+            .with_metadata("company", company)
+            .build()
+            .expect("Failed to build node")
+    }
+
+    /// Verify that nodes can be inserted, fetched, replaced and deleted.
+    ///
+    /// 1. Insert a node and verify that it is fetched with the same contents
+    /// 2. Insert a node with the same identity and verify that it replaces the first
+    /// 3. Delete the node and verify that the deleted node is returned and is no longer fetched
+    #[test]
+    fn insert_fetch_delete() {
+        let registry = create_registry();
+
+        let node1 = node("Node-123", "tcps://12.0.0.123:8431", "Bitwise IO");
+        registry
+            .insert_node(node1.clone())
+            .expect("Failed to insert node");
+        assert_eq!(
+            registry.fetch_node("Node-123").expect("Failed to fetch"),
+            Some(node1)
+        );
+
+        let node2 = Node::builder("Node-123")
+            .with_endpoint("tcps://12.0.0.123:8432")
+            .with_endpoint("tcps://12.0.0.123:8433")
+            .with_key("efgh")
+            .with_key("abcd")
+            .build()
+            .expect("Failed to build node");
+        registry
+            .insert_node(node2.clone())
+            .expect("Failed to replace node");
+        assert_eq!(
+            registry.fetch_node("Node-123").expect("Failed to fetch"),
+            Some(node2.clone())
+        );
+
+        assert_eq!(
+            registry.delete_node("Node-123").expect("Failed to delete"),
+            Some(node2)
+        );
+        assert_eq!(
+            registry.fetch_node("Node-123").expect("Failed to fetch"),
+            None
+        );
+        assert_eq!(
+            registry.delete_node("Node-123").expect("Failed to delete"),
+            None
+        );
+    }
+
+    /// Verify that a node with an endpoint that belongs to another node is rejected.
+    #[test]
+    fn insert_duplicate_endpoint() {
+        let registry = create_registry();
+
+        registry
+            .insert_node(node("Node-123", "tcps://12.0.0.123:8431", "Bitwise IO"))
+            .expect("Failed to insert node");
+
+        match registry.insert_node(node("Node-456", "tcps://12.0.0.123:8431", "Cargill")) {
+            Err(RegistryError::InvalidNode(InvalidNodeError::DuplicateEndpoint(endpoint))) => {
+                assert_eq!(endpoint, "tcps://12.0.0.123:8431")
+            }
+            res => panic!("Expected DuplicateEndpoint, got {:?}", res),
+        }
+    }
+
+    /// Verify that nodes are inserted and deleted in batches, and that a failed batch makes no
+    /// changes.
+    ///
+    /// 1. Insert two nodes in a batch and verify that both are listed
+    /// 2. Insert a batch with a new node and a node whose endpoint belongs to an existing node,
+    ///    and verify that the batch is rejected and the new node is not inserted
+    /// 3. Delete a batch containing an existing and an unknown identity, and verify that only the
+    ///    existing node is returned and deleted
+    #[cfg(feature = "registry-bulk")]
+    #[test]
+    fn insert_and_delete_batches() {
+        let registry = create_registry();
+
+        let node1 = node("Node-123", "tcps://12.0.0.123:8431", "Bitwise IO");
+        let node2 = node("Node-456", "tcps://12.0.0.123:8434", "Cargill");
+        registry
+            .insert_nodes(vec![node1.clone(), node2.clone()])
+            .expect("Failed to insert nodes");
+        assert_eq!(registry.count_nodes(&[]).expect("Failed to count"), 2);
+
+        match registry.insert_nodes(vec![
+            node("Node-789", "tcps://12.0.0.123:8435", "Cargill"),
+            node("Node-000", "tcps://12.0.0.123:8431", "Cargill"),
+        ]) {
+            Err(RegistryError::InvalidNode(InvalidNodeError::DuplicateEndpoint(endpoint))) => {
+                assert_eq!(endpoint, "tcps://12.0.0.123:8431")
+            }
+            res => panic!("Expected DuplicateEndpoint, got {:?}", res),
+        }
+        assert_eq!(
+            registry.fetch_node("Node-789").expect("Failed to fetch"),
+            None
+        );
+
+        let deleted = registry
+            .delete_nodes(&["Node-123".into(), "Node-999".into()])
+            .expect("Failed to delete nodes");
+        assert_eq!(deleted, vec![node1]);
+        assert_eq!(
+            registry
+                .list_nodes(&[])
+                .expect("Failed to list")
+                .collect::<Vec<_>>(),
+            vec![node2]
+        );
+    }
+
+    /// Verify that the predicates passed to `list_nodes` and `count_nodes` are applied.
+    ///
+    /// 1. Insert three nodes, two of which have the same company
+    /// 2. Verify that listing and counting without predicates returns every node
+    /// 3. Verify that the `Eq` predicate returns the nodes with the company
+    /// 4. Verify that the `Ne` predicate returns the other node and nodes without the key
+    /// 5. Verify that the comparison predicates are applied, comparing values byte-wise
+    #[test]
+    fn list_and_count_with_predicates() {
+        let registry = create_registry();
+
+        let node1 = node("Node-123", "tcps://12.0.0.123:8431", "Bitwise IO");
+        let node2 = node("Node-456", "tcps://12.0.0.123:8434", "Cargill");
+        let node3 = node("Node-789", "tcps://12.0.0.123:8435", "Cargill");
+        let node4 = Node::builder("Node-000")
+            .with_endpoint("tcps://12.0.0.123:8436")
+            .with_key("abcd")
+            .build()
+            .expect("Failed to build node");
+        for node in vec![&node1, &node2, &node3, &node4] {
+            registry
+                .insert_node(node.clone())
+                .expect("Failed to insert node");
+        }
+
+        let list = |predicates: &[MetadataPredicate]| {
+            registry
+                .list_nodes(predicates)
+                .expect("Failed to list nodes")
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            list(&[]),
+            vec![node4.clone(), node1.clone(), node2.clone(), node3.clone()]
+        );
+        assert_eq!(registry.count_nodes(&[]).expect("Failed to count"), 4);
+
+        let eq = [MetadataPredicate::eq("company", "Cargill")];
+        assert_eq!(list(&eq), vec![node2.clone(), node3.clone()]);
+        assert_eq!(registry.count_nodes(&eq).expect("Failed to count"), 2);
+
+        let ne = [MetadataPredicate::ne("company", "Cargill")];
+        assert_eq!(list(&ne), vec![node4, node1.clone()]);
+        assert_eq!(registry.count_nodes(&ne).expect("Failed to count"), 2);
+
+        let lt = [MetadataPredicate::Lt("company".into(), "C".into())];
+        assert_eq!(list(&lt), vec![node1]);
+
+        let range = [
+            MetadataPredicate::Ge("company".into(), "C".into()),
+            MetadataPredicate::Ne("company".into(), "Bitwise IO".into()),
+        ];
+        assert_eq!(list(&range), vec![node2, node3]);
+
+        // Values are compared byte-wise, so uppercase letters sort before lowercase ones
+        let gt = [MetadataPredicate::Gt("company".into(), "bitwise".into())];
+        assert_eq!(list(&gt), vec![]);
+    }
+
+    /// Verify that the grouped, field and numeric predicates are applied, whether or not the
+    /// database can evaluate them.
+    ///
+    /// 1. Insert three nodes with a region and a tier
+    /// 2. Verify that an `Or` of `In` and `Exists` predicates is applied
+    /// 3. Verify that `Not`, `StartsWith` and field predicates are applied
+    /// 4. Verify that numeric predicates compare the values as numbers, alone and in a group
+    #[cfg(feature = "registry-query")]
+    #[test]
+    fn list_and_count_with_query_predicates() {
+        use crate::registry::{FieldMatch, NodeField};
+
+        let registry = create_registry();
+
+        let mut node1 = node("Node-123", "tcps://12.0.0.123:8431", "Bitwise IO");
+        node1.metadata.insert("region".into(), "eu".into());
+        node1.metadata.insert("tier".into(), "10".into());
+        let mut node2 = node("Node-456", "tcps://12.0.0.123:8434", "Cargill");
+        node2.metadata.insert("region".into(), "uk".into());
+        node2.metadata.insert("tier".into(), "2".into());
+        let mut node3 = node("Node-789", "tcps://12.0.0.123:8435", "Cargill");
+        node3.metadata.insert("region".into(), "us".into());
+        node3.metadata.insert("tier".into(), "1".into());
+        node3.metadata.insert("beta".into(), "true".into());
+        for node in vec![&node1, &node2, &node3] {
+            registry
+                .insert_node(node.clone())
+                .expect("Failed to insert node");
+        }
+
+        let list = |predicates: &[MetadataPredicate]| {
+            registry
+                .list_nodes(predicates)
+                .expect("Failed to list nodes")
+                .map(|node| node.identity)
+                .collect::<Vec<_>>()
+        };
+
+        let or = [MetadataPredicate::Or(vec![
+            MetadataPredicate::is_in("region", vec!["eu", "uk"]),
+            MetadataPredicate::exists("beta"),
+        ])];
+        assert_eq!(list(&or), vec!["Node-123", "Node-456", "Node-789"]);
+
+        let not = [
+            MetadataPredicate::not(MetadataPredicate::StartsWith(
+                "company".into(),
+                "Bit".into(),
+            )),
+            MetadataPredicate::Field(NodeField::Endpoints, FieldMatch::Contains(":8435".into())),
+        ];
+        assert_eq!(list(&not), vec!["Node-789"]);
+        assert_eq!(registry.count_nodes(&not).expect("Failed to count"), 1);
+
+        // Compared as text, "10" < "2"
+        let numeric = [
+            MetadataPredicate::is_in("region", vec!["eu", "uk"]),
+            MetadataPredicate::NumGe("tier".into(), 2.0),
+        ];
+        assert_eq!(list(&numeric), vec!["Node-123", "Node-456"]);
+        assert_eq!(registry.count_nodes(&numeric).expect("Failed to count"), 2);
+
+        let group = [MetadataPredicate::And(vec![
+            MetadataPredicate::eq("company", "Cargill"),
+            MetadataPredicate::NumGt("tier".into(), 1.0),
+        ])];
+        assert_eq!(list(&group), vec!["Node-456"]);
+        assert_eq!(registry.count_nodes(&group).expect("Failed to count"), 1);
+    }
+
+    /// Verify that a self-signed node is stored with its signature, including its version, and
+    /// cannot be replaced by an unsigned entry or by an entry with a lower version.
+    #[cfg(feature = "registry-node-signing")]
+    #[test]
+    fn insert_signed_node() {
+        use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
+
+        use crate::hex::to_hex;
+        use crate::signing::{sawtooth::SawtoothSecp256k1RefSigner, Signer};
+
+        let registry = create_registry();
+
+        let context = Secp256k1Context::new();
+        let signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(
+                "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088",
+            )
+            .expect("Failed to parse private key"),
+        )
+        .expect("Failed to create signer");
+
+        let mut unsigned = node("Node-1", "tcps://12.0.0.1:8431", "Bitwise IO");
+        unsigned.keys = vec![to_hex(signer.public_key())];
+        let mut signed = unsigned.clone();
+        signed.sign(1, &signer).expect("Failed to sign node");
+
+        registry
+            .insert_node(signed.clone())
+            .expect("Failed to insert node");
+        assert_eq!(
+            registry.fetch_node("Node-1").expect("Failed to fetch node"),
+            Some(signed.clone())
+        );
+
+        match registry.insert_node(unsigned) {
+            Err(RegistryError::InvalidNode(InvalidNodeError::InvalidSignature(_))) => (),
+            res => panic!("Expected an invalid signature error, got {:?}", res),
+        }
+        assert_eq!(
+            registry.fetch_node("Node-1").expect("Failed to fetch node"),
+            Some(signed.clone())
+        );
+
+        let mut updated = signed.clone();
+        updated.endpoints = vec!["tcps://12.0.0.1:8432".into()];
+        updated
+            .sign(u64::MAX, &signer)
+            .expect("Failed to sign node");
+        registry
+            .insert_node(updated.clone())
+            .expect("Failed to insert node");
+        assert_eq!(
+            registry.fetch_node("Node-1").expect("Failed to fetch node"),
+            Some(updated)
+        );
+
+        match registry.insert_node(signed) {
+            Err(RegistryError::InvalidNode(InvalidNodeError::InvalidSignature(_))) => (),
+            res => panic!("Expected an invalid signature error, got {:?}", res),
+        }
+    }
+
+    /// Verify that event subscribers are notified of the nodes inserted, replaced and deleted
+    /// through the registry, and that re-inserting an identical node is not reported.
+    #[cfg(feature = "registry-events")]
+    #[test]
+    fn event_subscriber() {
+        use std::sync::mpsc::{channel, Sender};
+
+        use crate::registry::RegistrySubscriberError;
+
+        struct ChannelSubscriber(Sender<RegistryEvent>);
+
+        impl RegistryEventSubscriber for ChannelSubscriber {
+            fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+                self.0
+                    .send(event.clone())
+                    .map_err(|_| RegistrySubscriberError::Unsubscribe)
+            }
+        }
+
+        let registry = create_registry();
+        let (sender, receiver) = channel();
+        registry
+            .add_event_subscriber(Box::new(ChannelSubscriber(sender)))
+            .expect("Failed to add subscriber");
+
+        // Events are delivered by the registry's dispatch thread
+        let receive = |count| {
+            (0..count)
+                .map(|_| {
+                    receiver
+                        .recv_timeout(std::time::Duration::from_secs(5))
+                        .expect("No event received")
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let node1 = node("Node-123", "tcps://12.0.0.123:8431", "Bitwise IO");
+        let node1_moved = node("Node-123", "tcps://12.0.0.123:9431", "Bitwise IO");
+        registry
+            .insert_node(node1.clone())
+            .expect("Failed to insert node");
+        registry
+            .insert_node(node1.clone())
+            .expect("Failed to insert node");
+        registry
+            .insert_node(node1_moved.clone())
+            .expect("Failed to replace node");
+        registry.delete_node("Node-123").expect("Failed to delete");
+        registry.delete_node("Node-123").expect("Failed to delete");
+
+        assert_eq!(
+            receive(3),
+            vec![
+                RegistryEvent::NodeAdded(node1.clone()),
+                RegistryEvent::NodeUpdated {
+                    previous: node1,
+                    node: node1_moved.clone(),
+                },
+                RegistryEvent::NodeRemoved(node1_moved),
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "registry-node-signing")]
+use super::schema::splinter_nodes_signatures;
+use super::schema::{
+    splinter_nodes, splinter_nodes_endpoints, splinter_nodes_keys, splinter_nodes_metadata,
+};
+use crate::registry::Node;
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::NodeSignature;
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "splinter_nodes"]
+pub struct NodeModel {
+    pub identity: String,
+    pub display_name: String,
+}
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "splinter_nodes_endpoints"]
+pub struct NodeEndpointModel {
+    pub identity: String,
+    pub position: i32,
+    pub endpoint: String,
+}
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "splinter_nodes_keys"]
+pub struct NodeKeyModel {
+    pub identity: String,
+    pub position: i32,
+    pub public_key: String,
+}
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "splinter_nodes_metadata"]
+pub struct NodeMetadataModel {
+    pub identity: String,
+    pub key: String,
+    pub value: String,
+}
+
+#[cfg(feature = "registry-node-signing")]
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "splinter_nodes_signatures"]
+pub struct NodeSignatureModel {
+    pub identity: String,
+    pub public_key: String,
+    pub signature: String,
+    /// The signature's version; stored with the same bits as the `u64` version, since versions
+    /// are only compared once loaded.
+    pub version: i64,
+}
+
+impl From<&Node> for NodeModel {
+    fn from(node: &Node) -> Self {
+        NodeModel {
+            identity: node.identity.clone(),
+            display_name: node.display_name.clone(),
+        }
+    }
+}
+
+/// Returns the rows that store the endpoints, keys and metadata of the given node
+pub fn node_children(
+    node: &Node,
+) -> (
+    Vec<NodeEndpointModel>,
+    Vec<NodeKeyModel>,
+    Vec<NodeMetadataModel>,
+) {
+    let endpoints = node
+        .endpoints
+        .iter()
+        .enumerate()
+        .map(|(position, endpoint)| NodeEndpointModel {
+            identity: node.identity.clone(),
+            position: position as i32,
+            endpoint: endpoint.clone(),
+        })
+        .collect();
+    let keys = node
+        .keys
+        .iter()
+        .enumerate()
+        .map(|(position, public_key)| NodeKeyModel {
+            identity: node.identity.clone(),
+            position: position as i32,
+            public_key: public_key.clone(),
+        })
+        .collect();
+    let metadata = node
+        .metadata
+        .iter()
+        .map(|(key, value)| NodeMetadataModel {
+            identity: node.identity.clone(),
+            key: key.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    (endpoints, keys, metadata)
+}
+
+/// Returns the row that stores the signature of the given node, if it is signed
+#[cfg(feature = "registry-node-signing")]
+pub fn node_signature(node: &Node) -> Option<NodeSignatureModel> {
+    node.signature.as_ref().map(|signature| NodeSignatureModel {
+        identity: node.identity.clone(),
+        public_key: signature.public_key.clone(),
+        signature: signature.signature.clone(),
+        version: signature.version as i64,
+    })
+}
+
+/// Assembles nodes from their rows. The endpoints and keys must be ordered by position; the
+/// nodes are returned in the order they are given.
+pub fn build_nodes(
+    nodes: Vec<NodeModel>,
+    endpoints: Vec<NodeEndpointModel>,
+    keys: Vec<NodeKeyModel>,
+    metadata: Vec<NodeMetadataModel>,
+    #[cfg(feature = "registry-node-signing")] signatures: Vec<NodeSignatureModel>,
+) -> Vec<Node> {
+    let mut endpoints_by_node: HashMap<String, Vec<String>> = HashMap::new();
+    for endpoint in endpoints {
+        endpoints_by_node
+            .entry(endpoint.identity)
+            .or_default()
+            .push(endpoint.endpoint);
+    }
+    let mut keys_by_node: HashMap<String, Vec<String>> = HashMap::new();
+    for key in keys {
+        keys_by_node
+            .entry(key.identity)
+            .or_default()
+            .push(key.public_key);
+    }
+    let mut metadata_by_node: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for entry in metadata {
+        metadata_by_node
+            .entry(entry.identity)
+            .or_default()
+            .insert(entry.key, entry.value);
+    }
+    #[cfg(feature = "registry-node-signing")]
+    let mut signatures_by_node: HashMap<String, NodeSignature> = signatures
+        .into_iter()
+        .map(|signature| {
+            (
+                signature.identity,
+                NodeSignature {
+                    public_key: signature.public_key,
+                    signature: signature.signature,
+                    version: signature.version as u64,
+                },
+            )
+        })
+        .collect();
+
+    nodes
+        .into_iter()
+        .map(|node| Node {
+            endpoints: endpoints_by_node.remove(&node.identity).unwrap_or_default(),
+            keys: keys_by_node.remove(&node.identity).unwrap_or_default(),
+            metadata: metadata_by_node.remove(&node.identity).unwrap_or_default(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: signatures_by_node.remove(&node.identity),
+            identity: node.identity,
+            display_name: node.display_name,
+        })
+        .collect()
+}
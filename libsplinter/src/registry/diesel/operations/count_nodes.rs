@@ -0,0 +1,76 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RegistryOperations;
+use crate::registry::{diesel::models::NodeModel, MetadataPredicate, RegistryError};
+
+use diesel::prelude::*;
+
+pub(in crate::registry) trait RegistryCountNodesOperation {
+    fn count_nodes(&self, predicates: &[MetadataPredicate]) -> Result<u32, RegistryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RegistryCountNodesOperation for RegistryOperations<'a, diesel::pg::PgConnection> {
+    fn count_nodes(&self, predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+        let (query, remaining) = Self::filtered_nodes(predicates);
+        let count = if remaining.is_empty() {
+            query
+                .count()
+                .get_result::<i64>(self.conn)
+                .map(|count| count as u32)
+        } else {
+            // Some predicates can only be applied to the loaded nodes
+            query
+                .load::<NodeModel>(self.conn)
+                .and_then(|nodes| self.load_nodes(nodes))
+                .map(|nodes| {
+                    nodes
+                        .iter()
+                        .filter(|node| remaining.iter().all(|predicate| predicate.apply(node)))
+                        .count() as u32
+                })
+        };
+        count.map_err(|err| {
+            RegistryError::general_error_with_source("Failed to count nodes", Box::new(err))
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RegistryCountNodesOperation for RegistryOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn count_nodes(&self, predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+        let (query, remaining) = Self::filtered_nodes(predicates);
+        let count = if remaining.is_empty() {
+            query
+                .count()
+                .get_result::<i64>(self.conn)
+                .map(|count| count as u32)
+        } else {
+            // Some predicates can only be applied to the loaded nodes
+            query
+                .load::<NodeModel>(self.conn)
+                .and_then(|nodes| self.load_nodes(nodes))
+                .map(|nodes| {
+                    nodes
+                        .iter()
+                        .filter(|node| remaining.iter().all(|predicate| predicate.apply(node)))
+                        .count() as u32
+                })
+        };
+        count.map_err(|err| {
+            RegistryError::general_error_with_source("Failed to count nodes", Box::new(err))
+        })
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::fetch_node::RegistryFetchNodeOperation as _;
+use super::RegistryOperations;
+use crate::registry::{
+    diesel::schema::{
+        splinter_nodes, splinter_nodes_endpoints, splinter_nodes_keys, splinter_nodes_metadata,
+        splinter_nodes_signatures,
+    },
+    Node, RegistryError,
+};
+
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::registry) trait RegistryDeleteNodeOperation {
+    fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RegistryDeleteNodeOperation for RegistryOperations<'a, diesel::pg::PgConnection> {
+    fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        let node = match self.fetch_node(identity)? {
+            Some(node) => node,
+            None => return Ok(None),
+        };
+
+        let deleted = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                delete(
+                    splinter_nodes_endpoints::table
+                        .filter(splinter_nodes_endpoints::identity.eq(identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_keys::table.filter(splinter_nodes_keys::identity.eq(identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_metadata::table
+                        .filter(splinter_nodes_metadata::identity.eq(identity)),
+                )
+                .execute(self.conn)?;
+                delete(splinter_nodes_signatures::table.find(identity)).execute(self.conn)?;
+                delete(splinter_nodes::table.find(identity)).execute(self.conn)
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to delete node {}", identity),
+                    Box::new(err),
+                )
+            })?;
+
+        // The node may have been removed since it was fetched
+        if deleted == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(node))
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RegistryDeleteNodeOperation for RegistryOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        let node = match self.fetch_node(identity)? {
+            Some(node) => node,
+            None => return Ok(None),
+        };
+
+        let deleted = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                delete(
+                    splinter_nodes_endpoints::table
+                        .filter(splinter_nodes_endpoints::identity.eq(identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_keys::table.filter(splinter_nodes_keys::identity.eq(identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_metadata::table
+                        .filter(splinter_nodes_metadata::identity.eq(identity)),
+                )
+                .execute(self.conn)?;
+                delete(splinter_nodes_signatures::table.find(identity)).execute(self.conn)?;
+                delete(splinter_nodes::table.find(identity)).execute(self.conn)
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to delete node {}", identity),
+                    Box::new(err),
+                )
+            })?;
+
+        // The node may have been removed since it was fetched
+        if deleted == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(node))
+        }
+    }
+}
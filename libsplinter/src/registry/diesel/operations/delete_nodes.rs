@@ -0,0 +1,55 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::delete_node::RegistryDeleteNodeOperation;
+use super::RegistryOperations;
+use crate::registry::{Node, RegistryError};
+
+pub(in crate::registry) trait RegistryDeleteNodesOperation {
+    fn delete_nodes(&self, identities: &[String]) -> Result<Vec<Node>, RegistryError>;
+}
+
+impl<'a, C> RegistryDeleteNodesOperation for RegistryOperations<'a, C>
+where
+    C: diesel::Connection,
+    RegistryOperations<'a, C>: RegistryDeleteNodeOperation,
+{
+    fn delete_nodes(&self, identities: &[String]) -> Result<Vec<Node>, RegistryError> {
+        // Each node is deleted in a nested transaction; the first failure rolls back all of them
+        let mut deleted = vec![];
+        let mut failure = None;
+        let result = self.conn.transaction::<_, diesel::result::Error, _>(|| {
+            for identity in identities {
+                match self.delete_node(identity) {
+                    Ok(Some(node)) => deleted.push(node),
+                    Ok(None) => (),
+                    Err(err) => {
+                        failure = Some(err);
+                        return Err(diesel::result::Error::RollbackTransaction);
+                    }
+                }
+            }
+            Ok(())
+        });
+
+        match (result, failure) {
+            (_, Some(err)) => Err(err),
+            (Err(err), None) => Err(RegistryError::general_error_with_source(
+                "Failed to delete nodes",
+                Box::new(err),
+            )),
+            (Ok(()), None) => Ok(deleted),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RegistryOperations;
+use crate::registry::{
+    diesel::{models::NodeModel, schema::splinter_nodes},
+    Node, RegistryError,
+};
+
+use diesel::prelude::*;
+
+pub(in crate::registry) trait RegistryFetchNodeOperation {
+    fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RegistryFetchNodeOperation for RegistryOperations<'a, diesel::pg::PgConnection> {
+    fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        splinter_nodes::table
+            .find(identity)
+            .first::<NodeModel>(self.conn)
+            .optional()
+            .and_then(|node| match node {
+                Some(node) => self.load_nodes(vec![node]).map(|mut nodes| nodes.pop()),
+                None => Ok(None),
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to fetch node {}", identity),
+                    Box::new(err),
+                )
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RegistryFetchNodeOperation for RegistryOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        splinter_nodes::table
+            .find(identity)
+            .first::<NodeModel>(self.conn)
+            .optional()
+            .and_then(|node| match node {
+                Some(node) => self.load_nodes(vec![node]).map(|mut nodes| nodes.pop()),
+                None => Ok(None),
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to fetch node {}", identity),
+                    Box::new(err),
+                )
+            })
+    }
+}
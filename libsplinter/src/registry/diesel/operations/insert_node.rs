@@ -0,0 +1,215 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RegistryOperations;
+use crate::registry::{
+    check_node_required_fields_are_not_empty,
+    diesel::{
+        models::{node_children, NodeModel},
+        schema::{
+            splinter_nodes, splinter_nodes_endpoints, splinter_nodes_keys, splinter_nodes_metadata,
+            splinter_nodes_signatures,
+        },
+    },
+    InvalidNodeError, Node, RegistryError,
+};
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::{diesel::models::node_signature, node_signature::check_node_signature};
+
+use diesel::{
+    dsl::{delete, insert_into},
+    prelude::*,
+};
+
+pub(in crate::registry) trait RegistryInsertNodeOperation {
+    fn insert_node(&self, node: Node) -> Result<(), RegistryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RegistryInsertNodeOperation for RegistryOperations<'a, diesel::pg::PgConnection> {
+    fn insert_node(&self, node: Node) -> Result<(), RegistryError> {
+        check_node_required_fields_are_not_empty(&node)?;
+
+        let (endpoints, keys, metadata) = node_children(&node);
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                // Endpoints must be unique in the registry
+                let duplicate_endpoint = splinter_nodes_endpoints::table
+                    .filter(splinter_nodes_endpoints::endpoint.eq_any(&node.endpoints))
+                    .filter(splinter_nodes_endpoints::identity.ne(&node.identity))
+                    .select(splinter_nodes_endpoints::endpoint)
+                    .first::<String>(self.conn)
+                    .optional()?;
+                if let Some(endpoint) = duplicate_endpoint {
+                    return Ok(Err(InvalidNodeError::DuplicateEndpoint(endpoint)));
+                }
+
+                // A self-signed node may only be replaced by an entry signed with one of its keys
+                #[cfg(feature = "registry-node-signing")]
+                {
+                    let existing = match splinter_nodes::table
+                        .find(&node.identity)
+                        .first::<NodeModel>(self.conn)
+                        .optional()?
+                    {
+                        Some(existing) => self.load_nodes(vec![existing])?.pop(),
+                        None => None,
+                    };
+                    if let Err(err) = check_node_signature(&node, existing.as_ref()) {
+                        return Ok(Err(err));
+                    }
+                }
+
+                // Replace any existing node with the same identity
+                delete(
+                    splinter_nodes_endpoints::table
+                        .filter(splinter_nodes_endpoints::identity.eq(&node.identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_keys::table
+                        .filter(splinter_nodes_keys::identity.eq(&node.identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_metadata::table
+                        .filter(splinter_nodes_metadata::identity.eq(&node.identity)),
+                )
+                .execute(self.conn)?;
+                delete(splinter_nodes_signatures::table.find(&node.identity)).execute(self.conn)?;
+                delete(splinter_nodes::table.find(&node.identity)).execute(self.conn)?;
+
+                insert_into(splinter_nodes::table)
+                    .values(NodeModel::from(&node))
+                    .execute(self.conn)?;
+                insert_into(splinter_nodes_endpoints::table)
+                    .values(&endpoints)
+                    .execute(self.conn)?;
+                insert_into(splinter_nodes_keys::table)
+                    .values(&keys)
+                    .execute(self.conn)?;
+                if !metadata.is_empty() {
+                    insert_into(splinter_nodes_metadata::table)
+                        .values(&metadata)
+                        .execute(self.conn)?;
+                }
+                #[cfg(feature = "registry-node-signing")]
+                {
+                    if let Some(signature) = node_signature(&node) {
+                        insert_into(splinter_nodes_signatures::table)
+                            .values(&signature)
+                            .execute(self.conn)?;
+                    }
+                }
+
+                Ok(Ok(()))
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to insert node {}", node.identity),
+                    Box::new(err),
+                )
+            })?
+            .map_err(RegistryError::from)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RegistryInsertNodeOperation for RegistryOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn insert_node(&self, node: Node) -> Result<(), RegistryError> {
+        check_node_required_fields_are_not_empty(&node)?;
+
+        let (endpoints, keys, metadata) = node_children(&node);
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                // Endpoints must be unique in the registry
+                let duplicate_endpoint = splinter_nodes_endpoints::table
+                    .filter(splinter_nodes_endpoints::endpoint.eq_any(&node.endpoints))
+                    .filter(splinter_nodes_endpoints::identity.ne(&node.identity))
+                    .select(splinter_nodes_endpoints::endpoint)
+                    .first::<String>(self.conn)
+                    .optional()?;
+                if let Some(endpoint) = duplicate_endpoint {
+                    return Ok(Err(InvalidNodeError::DuplicateEndpoint(endpoint)));
+                }
+
+                // A self-signed node may only be replaced by an entry signed with one of its keys
+                #[cfg(feature = "registry-node-signing")]
+                {
+                    let existing = match splinter_nodes::table
+                        .find(&node.identity)
+                        .first::<NodeModel>(self.conn)
+                        .optional()?
+                    {
+                        Some(existing) => self.load_nodes(vec![existing])?.pop(),
+                        None => None,
+                    };
+                    if let Err(err) = check_node_signature(&node, existing.as_ref()) {
+                        return Ok(Err(err));
+                    }
+                }
+
+                // Replace any existing node with the same identity
+                delete(
+                    splinter_nodes_endpoints::table
+                        .filter(splinter_nodes_endpoints::identity.eq(&node.identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_keys::table
+                        .filter(splinter_nodes_keys::identity.eq(&node.identity)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    splinter_nodes_metadata::table
+                        .filter(splinter_nodes_metadata::identity.eq(&node.identity)),
+                )
+                .execute(self.conn)?;
+                delete(splinter_nodes_signatures::table.find(&node.identity)).execute(self.conn)?;
+                delete(splinter_nodes::table.find(&node.identity)).execute(self.conn)?;
+
+                insert_into(splinter_nodes::table)
+                    .values(NodeModel::from(&node))
+                    .execute(self.conn)?;
+                insert_into(splinter_nodes_endpoints::table)
+                    .values(&endpoints)
+                    .execute(self.conn)?;
+                insert_into(splinter_nodes_keys::table)
+                    .values(&keys)
+                    .execute(self.conn)?;
+                if !metadata.is_empty() {
+                    insert_into(splinter_nodes_metadata::table)
+                        .values(&metadata)
+                        .execute(self.conn)?;
+                }
+                #[cfg(feature = "registry-node-signing")]
+                {
+                    if let Some(signature) = node_signature(&node) {
+                        insert_into(splinter_nodes_signatures::table)
+                            .values(&signature)
+                            .execute(self.conn)?;
+                    }
+                }
+
+                Ok(Ok(()))
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to insert node {}", node.identity),
+                    Box::new(err),
+                )
+            })?
+            .map_err(RegistryError::from)
+    }
+}
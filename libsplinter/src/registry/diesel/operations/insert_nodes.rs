@@ -0,0 +1,54 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::insert_node::RegistryInsertNodeOperation;
+use super::RegistryOperations;
+use crate::registry::{validate_nodes, Node, RegistryError};
+
+pub(in crate::registry) trait RegistryInsertNodesOperation {
+    fn insert_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError>;
+}
+
+impl<'a, C> RegistryInsertNodesOperation for RegistryOperations<'a, C>
+where
+    C: diesel::Connection,
+    RegistryOperations<'a, C>: RegistryInsertNodeOperation,
+{
+    fn insert_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError> {
+        // The nodes must be valid amongst themselves; each insert checks them against the nodes
+        // already in the database
+        validate_nodes(&nodes)?;
+
+        // Each node is inserted in a nested transaction; the first failure rolls back all of them
+        let mut failure = None;
+        let result = self.conn.transaction::<_, diesel::result::Error, _>(|| {
+            for node in nodes {
+                if let Err(err) = self.insert_node(node) {
+                    failure = Some(err);
+                    return Err(diesel::result::Error::RollbackTransaction);
+                }
+            }
+            Ok(())
+        });
+
+        match (result, failure) {
+            (_, Some(err)) => Err(err),
+            (Err(err), None) => Err(RegistryError::general_error_with_source(
+                "Failed to insert nodes",
+                Box::new(err),
+            )),
+            (Ok(()), None) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RegistryOperations;
+use crate::registry::{
+    diesel::{models::NodeModel, schema::splinter_nodes},
+    MetadataPredicate, Node, RegistryError,
+};
+
+use diesel::prelude::*;
+
+pub(in crate::registry) trait RegistryListNodesOperation {
+    fn list_nodes(&self, predicates: &[MetadataPredicate]) -> Result<Vec<Node>, RegistryError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RegistryListNodesOperation for RegistryOperations<'a, diesel::pg::PgConnection> {
+    fn list_nodes(&self, predicates: &[MetadataPredicate]) -> Result<Vec<Node>, RegistryError> {
+        let (query, remaining) = Self::filtered_nodes(predicates);
+        query
+            .order(splinter_nodes::identity)
+            .load::<NodeModel>(self.conn)
+            .and_then(|nodes| self.load_nodes(nodes))
+            .map(|nodes| {
+                nodes
+                    .into_iter()
+                    .filter(|node| remaining.iter().all(|predicate| predicate.apply(node)))
+                    .collect()
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source("Failed to list nodes", Box::new(err))
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RegistryListNodesOperation for RegistryOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn list_nodes(&self, predicates: &[MetadataPredicate]) -> Result<Vec<Node>, RegistryError> {
+        let (query, remaining) = Self::filtered_nodes(predicates);
+        query
+            .order(splinter_nodes::identity)
+            .load::<NodeModel>(self.conn)
+            .and_then(|nodes| self.load_nodes(nodes))
+            .map(|nodes| {
+                nodes
+                    .into_iter()
+                    .filter(|node| remaining.iter().all(|predicate| predicate.apply(node)))
+                    .collect()
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source("Failed to list nodes", Box::new(err))
+            })
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(super) mod count_nodes;
+pub(super) mod delete_node;
+#[cfg(feature = "registry-bulk")]
+pub(super) mod delete_nodes;
+pub(super) mod fetch_node;
+pub(super) mod insert_node;
+#[cfg(feature = "registry-bulk")]
+pub(super) mod insert_nodes;
+pub(super) mod list_nodes;
+
+#[cfg(feature = "registry-query")]
+use diesel::dsl::not;
+#[cfg(feature = "registry-query")]
+use diesel::sql_types::Integer;
+use diesel::{
+    dsl::sql,
+    prelude::*,
+    sql_types::{Bool, Text},
+};
+
+#[cfg(feature = "registry-node-signing")]
+use super::models::NodeSignatureModel;
+use super::models::{build_nodes, NodeEndpointModel, NodeKeyModel, NodeMetadataModel, NodeModel};
+#[cfg(feature = "registry-node-signing")]
+use super::schema::splinter_nodes_signatures;
+use super::schema::{
+    splinter_nodes, splinter_nodes_endpoints, splinter_nodes_keys, splinter_nodes_metadata,
+};
+#[cfg(feature = "registry-query")]
+use crate::registry::{FieldMatch, NodeField};
+use crate::registry::{MetadataPredicate, Node};
+
+/// The maximum number of node identities bound in a single query; SQLite limits the number of
+/// parameters in a statement.
+const MAX_IDENTITIES_PER_QUERY: usize = 500;
+
+/// A boolean expression on the `splinter_nodes` table
+type NodeFilter<DB> = Box<dyn BoxableExpression<splinter_nodes::table, DB, SqlType = Bool>>;
+
+#[cfg(all(feature = "registry-query", feature = "postgres"))]
+sql_function! {
+    /// Returns the position of `substring` in `string`, starting at 1, or 0 if it is not found
+    fn strpos(string: Text, substring: Text) -> Integer;
+}
+
+#[cfg(all(feature = "registry-query", feature = "sqlite"))]
+sql_function! {
+    /// Returns the position of `substring` in `string`, starting at 1, or 0 if it is not found
+    fn instr(string: Text, substring: Text) -> Integer;
+}
+
+/// Builds the expression for a `FieldMatch` on a text column, and passes it to `$wrap` as
+/// `$expr`. Prefix and substring matches use the backend's `$position` function, which is
+/// case-sensitive, rather than `LIKE`.
+#[cfg(feature = "registry-query")]
+macro_rules! field_match_expression {
+    ($field_match:expr, $column:expr, $position:ident, |$expr:ident| $wrap:expr) => {
+        match $field_match {
+            FieldMatch::Eq(value) => {
+                let $expr = $column.eq(value.clone());
+                $wrap
+            }
+            FieldMatch::In(values) => {
+                let $expr = $column.eq_any(values.clone());
+                $wrap
+            }
+            FieldMatch::StartsWith(prefix) => {
+                let $expr = $position($column, prefix.clone()).eq(1);
+                $wrap
+            }
+            FieldMatch::Contains(value) => {
+                let $expr = $position($column, value.clone()).gt(0);
+                $wrap
+            }
+        }
+    };
+}
+
+pub(super) struct RegistryOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C> RegistryOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    pub fn new(conn: &'a C) -> Self {
+        RegistryOperations { conn }
+    }
+}
+
+/// Implements the operations shared by the backends, which differ only in their types, the
+/// `$position` function used for prefix and substring matches, and `$collated_value`, the
+/// metadata value column with a collation that compares text byte-wise, so that ordered
+/// comparisons of metadata values do not depend on the database's locale.
+macro_rules! impl_registry_operations {
+    ($conn:ty, $backend:ty, $position:ident, $collated_value:expr) => {
+        impl<'a> RegistryOperations<'a, $conn> {
+            /// Returns a query for the nodes that match all of the given predicates that can be evaluated
+            /// by the database, along with the predicates that must be applied to the loaded nodes
+            fn filtered_nodes(
+                predicates: &[MetadataPredicate],
+            ) -> (
+                splinter_nodes::BoxedQuery<'static, $backend>,
+                Vec<&MetadataPredicate>,
+            ) {
+                let mut query = splinter_nodes::table.into_boxed();
+                let mut remaining = vec![];
+                for predicate in predicates {
+                    match Self::predicate_filter(predicate) {
+                        Some(filter) => query = query.filter(filter),
+                        None => remaining.push(predicate),
+                    }
+                }
+                (query, remaining)
+            }
+
+            /// Returns the expression for the given predicate, or `None` if the predicate cannot be
+            /// evaluated by the database
+            fn predicate_filter(predicate: &MetadataPredicate) -> Option<NodeFilter<$backend>> {
+                let filter: NodeFilter<$backend> = match predicate {
+                    MetadataPredicate::Eq(key, value) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(splinter_nodes_metadata::value.eq(value.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    // A node without the key matches, so exclude the nodes that have the value
+                    MetadataPredicate::Ne(key, value) => Box::new(
+                        splinter_nodes::identity.ne_all(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(splinter_nodes_metadata::value.eq(value.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    MetadataPredicate::Gt(key, value) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(sql::<Text>($collated_value).gt(value.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    MetadataPredicate::Ge(key, value) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(sql::<Text>($collated_value).ge(value.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    MetadataPredicate::Lt(key, value) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(sql::<Text>($collated_value).lt(value.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    MetadataPredicate::Le(key, value) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(sql::<Text>($collated_value).le(value.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::In(key, values) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(splinter_nodes_metadata::value.eq_any(values.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::StartsWith(key, prefix) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(
+                                    $position(splinter_nodes_metadata::value, prefix.clone()).eq(1),
+                                )
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::Contains(key, value) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .filter(
+                                    $position(splinter_nodes_metadata::value, value.clone()).gt(0),
+                                )
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::Exists(key) => Box::new(
+                        splinter_nodes::identity.eq_any(
+                            splinter_nodes_metadata::table
+                                .filter(splinter_nodes_metadata::key.eq(key.clone()))
+                                .select(splinter_nodes_metadata::identity),
+                        ),
+                    ),
+                    // Metadata values are stored as text, so numeric comparisons are applied to the
+                    // loaded nodes
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::NumEq(..)
+                    | MetadataPredicate::NumGt(..)
+                    | MetadataPredicate::NumGe(..)
+                    | MetadataPredicate::NumLt(..)
+                    | MetadataPredicate::NumLe(..) => return None,
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::Field(field, field_match) => match field {
+                        NodeField::Identity => {
+                            field_match_expression!(
+                                field_match,
+                                splinter_nodes::identity,
+                                $position,
+                                |expr| Box::new(expr)
+                            )
+                        }
+                        NodeField::DisplayName => {
+                            field_match_expression!(
+                                field_match,
+                                splinter_nodes::display_name,
+                                $position,
+                                |expr| Box::new(expr)
+                            )
+                        }
+                        NodeField::Endpoints => {
+                            field_match_expression!(
+                                field_match,
+                                splinter_nodes_endpoints::endpoint,
+                                $position,
+                                |expr| {
+                                    Box::new(
+                                        splinter_nodes::identity.eq_any(
+                                            splinter_nodes_endpoints::table
+                                                .filter(expr)
+                                                .select(splinter_nodes_endpoints::identity),
+                                        ),
+                                    )
+                                }
+                            )
+                        }
+                        NodeField::Keys => {
+                            field_match_expression!(
+                                field_match,
+                                splinter_nodes_keys::public_key,
+                                $position,
+                                |expr| {
+                                    Box::new(
+                                        splinter_nodes::identity.eq_any(
+                                            splinter_nodes_keys::table
+                                                .filter(expr)
+                                                .select(splinter_nodes_keys::identity),
+                                        ),
+                                    )
+                                }
+                            )
+                        }
+                    },
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::And(predicates) => {
+                        let mut filter: NodeFilter<$backend> = Box::new(sql::<Bool>("1 = 1"));
+                        for predicate in predicates {
+                            filter = Box::new(filter.and(Self::predicate_filter(predicate)?));
+                        }
+                        filter
+                    }
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::Or(predicates) => {
+                        let mut filter: NodeFilter<$backend> = Box::new(sql::<Bool>("1 = 0"));
+                        for predicate in predicates {
+                            filter = Box::new(filter.or(Self::predicate_filter(predicate)?));
+                        }
+                        filter
+                    }
+                    #[cfg(feature = "registry-query")]
+                    MetadataPredicate::Not(predicate) => {
+                        Box::new(not(Self::predicate_filter(predicate)?))
+                    }
+                };
+                Some(filter)
+            }
+
+            /// Loads the endpoints, keys, metadata and signatures of the given nodes
+            fn load_nodes(&self, nodes: Vec<NodeModel>) -> QueryResult<Vec<Node>> {
+                let identities: Vec<String> =
+                    nodes.iter().map(|node| node.identity.clone()).collect();
+                let mut endpoints = vec![];
+                let mut keys = vec![];
+                let mut metadata = vec![];
+                #[cfg(feature = "registry-node-signing")]
+                let mut signatures = vec![];
+                for chunk in identities.chunks(MAX_IDENTITIES_PER_QUERY) {
+                    endpoints.extend(
+                        splinter_nodes_endpoints::table
+                            .filter(splinter_nodes_endpoints::identity.eq_any(chunk))
+                            .order((
+                                splinter_nodes_endpoints::identity,
+                                splinter_nodes_endpoints::position,
+                            ))
+                            .load::<NodeEndpointModel>(self.conn)?,
+                    );
+                    keys.extend(
+                        splinter_nodes_keys::table
+                            .filter(splinter_nodes_keys::identity.eq_any(chunk))
+                            .order((splinter_nodes_keys::identity, splinter_nodes_keys::position))
+                            .load::<NodeKeyModel>(self.conn)?,
+                    );
+                    metadata.extend(
+                        splinter_nodes_metadata::table
+                            .filter(splinter_nodes_metadata::identity.eq_any(chunk))
+                            .load::<NodeMetadataModel>(self.conn)?,
+                    );
+                    #[cfg(feature = "registry-node-signing")]
+                    signatures.extend(
+                        splinter_nodes_signatures::table
+                            .filter(splinter_nodes_signatures::identity.eq_any(chunk))
+                            .load::<NodeSignatureModel>(self.conn)?,
+                    );
+                }
+                Ok(build_nodes(
+                    nodes,
+                    endpoints,
+                    keys,
+                    metadata,
+                    #[cfg(feature = "registry-node-signing")]
+                    signatures,
+                ))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "postgres")]
+impl_registry_operations!(
+    diesel::pg::PgConnection,
+    diesel::pg::Pg,
+    strpos,
+    "splinter_nodes_metadata.value COLLATE \"C\""
+);
+
+#[cfg(feature = "sqlite")]
+impl_registry_operations!(
+    diesel::sqlite::SqliteConnection,
+    diesel::sqlite::Sqlite,
+    instr,
+    "splinter_nodes_metadata.value COLLATE BINARY"
+);
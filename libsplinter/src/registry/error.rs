@@ -75,6 +75,14 @@ impl fmt::Display for RegistryError {
     }
 }
 
+#[cfg(feature = "diesel")]
+impl From<crate::database::error::ConnectionError> for RegistryError {
+    fn from(err: crate::database::error::ConnectionError) -> Self {
+        // The connection error's source is not `Send`, so only its message is kept
+        RegistryError::general_error(&format!("{}: {}", err, err.source))
+    }
+}
+
 impl From<InvalidNodeError> for RegistryError {
     fn from(err: InvalidNodeError) -> Self {
         RegistryError::InvalidNode(err)
@@ -98,6 +106,9 @@ pub enum InvalidNodeError {
     EmptyKey,
     /// The node's identity is invalid (identity, message)
     InvalidIdentity(String, String),
+    /// The node's signature is missing or invalid
+    #[cfg(feature = "registry-node-signing")]
+    InvalidSignature(String),
     /// The node's list of endpoints is empty
     MissingEndpoints,
     /// The node's list of keys is empty
@@ -114,6 +125,8 @@ impl Error for InvalidNodeError {
             InvalidNodeError::EmptyDisplayName => None,
             InvalidNodeError::EmptyKey => None,
             InvalidNodeError::InvalidIdentity(..) => None,
+            #[cfg(feature = "registry-node-signing")]
+            InvalidNodeError::InvalidSignature(_) => None,
             InvalidNodeError::MissingEndpoints => None,
             InvalidNodeError::MissingKeys => None,
         }
@@ -138,6 +151,8 @@ impl fmt::Display for InvalidNodeError {
             InvalidNodeError::InvalidIdentity(identity, msg) => {
                 write!(f, "identity {} is invalid: {}", identity, msg)
             }
+            #[cfg(feature = "registry-node-signing")]
+            InvalidNodeError::InvalidSignature(msg) => write!(f, "invalid signature: {}", msg),
             InvalidNodeError::MissingEndpoints => write!(f, "node must have one or more endpoints"),
             InvalidNodeError::MissingKeys => write!(f, "node must have one or more keys"),
         }
@@ -0,0 +1,316 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Notifications of changes to the nodes in a registry.
+//!
+//! Registries that support subscriptions send a [`RegistryEvent`] to each
+//! [`RegistryEventSubscriber`] added with `RegistryReader::add_event_subscriber` whenever a node
+//! is added, updated or removed.
+//!
+//! [`RegistryEvent`]: enum.RegistryEvent.html
+//! [`RegistryEventSubscriber`]: trait.RegistryEventSubscriber.html
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::{
+    mpsc::{channel, Receiver, Sender},
+    Arc, Mutex,
+};
+use std::thread;
+
+use super::{Node, RegistryError};
+
+/// A change to a node in a registry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistryEvent {
+    /// A node was added to the registry.
+    NodeAdded(Node),
+    /// A node in the registry was replaced by a different definition of the node.
+    NodeUpdated {
+        /// The node before the update.
+        previous: Node,
+        /// The node after the update.
+        node: Node,
+    },
+    /// A node was removed from the registry.
+    NodeRemoved(Node),
+}
+
+impl RegistryEvent {
+    /// Returns the node that the event is about, after the change (or the removed node).
+    pub fn node(&self) -> &Node {
+        match self {
+            RegistryEvent::NodeAdded(node) => node,
+            RegistryEvent::NodeUpdated { node, .. } => node,
+            RegistryEvent::NodeRemoved(node) => node,
+        }
+    }
+}
+
+/// Receives the events of a registry.
+///
+/// Events are delivered in order by a dispatch thread of the registry, after the change has been
+/// made, so a subscriber may read from the registry while handling an event; the registry may
+/// have changed again by then. Events are queued while a subscriber handles an event, so a
+/// subscriber should return promptly.
+pub trait RegistryEventSubscriber: Send {
+    /// Handles an event. Returning `RegistrySubscriberError::Unsubscribe` removes the subscriber
+    /// from the registry.
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError>;
+}
+
+/// Errors returned by a `RegistryEventSubscriber`.
+#[derive(Debug)]
+pub enum RegistrySubscriberError {
+    /// The subscriber was unable to handle the event, but remains subscribed.
+    UnableToHandleEvent(String),
+    /// The subscriber no longer wants to receive events.
+    Unsubscribe,
+}
+
+impl Error for RegistrySubscriberError {}
+
+impl fmt::Display for RegistrySubscriberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistrySubscriberError::UnableToHandleEvent(msg) => {
+                write!(f, "Unable to handle event: {}", msg)
+            }
+            RegistrySubscriberError::Unsubscribe => f.write_str("Unsubscribe"),
+        }
+    }
+}
+
+/// The subscribers of a registry. Clones share the same list of subscribers.
+///
+/// Events are handed to a dispatch thread, which is started when the first subscriber is added and
+/// calls the subscribers, so a registry never calls a subscriber while it holds its own locks. The
+/// thread stops once every clone has been dropped and the queued events have been delivered.
+#[derive(Clone, Default)]
+pub(super) struct RegistryEventSubscribers {
+    subscribers: Arc<Mutex<Vec<Box<dyn RegistryEventSubscriber>>>>,
+    /// Queues events for the dispatch thread; `None` until the first subscriber is added.
+    dispatcher: Arc<Mutex<Option<Sender<Vec<RegistryEvent>>>>>,
+}
+
+impl RegistryEventSubscribers {
+    pub fn add(&self, subscriber: Box<dyn RegistryEventSubscriber>) -> Result<(), RegistryError> {
+        let mut dispatcher = self
+            .dispatcher
+            .lock()
+            .map_err(|_| RegistryError::general_error("Registry dispatcher lock poisoned"))?;
+        if dispatcher.is_none() {
+            let (sender, receiver) = channel();
+            let subscribers = self.subscribers.clone();
+            thread::Builder::new()
+                .name("Registry Event Dispatcher".into())
+                .spawn(move || dispatch_events(receiver, subscribers))
+                .map_err(|err| {
+                    RegistryError::general_error_with_source(
+                        "Failed to start registry event dispatcher",
+                        Box::new(err),
+                    )
+                })?;
+            *dispatcher = Some(sender);
+        }
+
+        self.subscribers
+            .lock()
+            .map_err(|_| RegistryError::general_error("Registry subscriber lock poisoned"))?
+            .push(subscriber);
+        Ok(())
+    }
+
+    /// Queues the given events to be sent to every subscriber. This does not wait for the
+    /// subscribers, so it may be called while holding the registry's locks; doing so ensures that
+    /// events are delivered in the order of the changes.
+    pub fn broadcast(&self, events: Vec<RegistryEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        match self.dispatcher.lock() {
+            Ok(dispatcher) => {
+                // Without a dispatch thread there are no subscribers to notify
+                if let Some(sender) = dispatcher.as_ref() {
+                    if sender.send(events).is_err() {
+                        error!("Registry event dispatcher stopped; dropping registry events");
+                    }
+                }
+            }
+            Err(_) => error!("Registry dispatcher lock poisoned; dropping registry events"),
+        }
+    }
+}
+
+/// Sends the events received on the `receiver` to every subscriber, removing the subscribers that
+/// unsubscribe, until every sender has been dropped.
+fn dispatch_events(
+    receiver: Receiver<Vec<RegistryEvent>>,
+    subscribers: Arc<Mutex<Vec<Box<dyn RegistryEventSubscriber>>>>,
+) {
+    for events in receiver {
+        let mut subscribers = match subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(_) => {
+                error!("Registry subscriber lock poisoned; dropping registry events");
+                continue;
+            }
+        };
+        for event in &events {
+            subscribers.retain(|subscriber| match subscriber.handle_event(event) {
+                Ok(()) => true,
+                Err(RegistrySubscriberError::Unsubscribe) => false,
+                Err(RegistrySubscriberError::UnableToHandleEvent(msg)) => {
+                    error!("Unable to send registry event: {}", msg);
+                    true
+                }
+            });
+        }
+    }
+}
+
+/// Returns the events that describe the change from the `previous` list of nodes to the `current`
+/// list of nodes.
+pub(super) fn diff_nodes(previous: &[Node], current: &[Node]) -> Vec<RegistryEvent> {
+    let mut previous_nodes: HashMap<&str, &Node> = previous
+        .iter()
+        .map(|node| (node.identity.as_str(), node))
+        .collect();
+
+    let mut events = current
+        .iter()
+        .filter_map(|node| match previous_nodes.remove(node.identity.as_str()) {
+            None => Some(RegistryEvent::NodeAdded(node.clone())),
+            Some(previous) if previous != node => Some(RegistryEvent::NodeUpdated {
+                previous: previous.clone(),
+                node: node.clone(),
+            }),
+            Some(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Any nodes that are left were removed; report them in their original order
+    events.extend(
+        previous
+            .iter()
+            .filter(|node| previous_nodes.contains_key(node.identity.as_str()))
+            .map(|node| RegistryEvent::NodeRemoved(node.clone())),
+    );
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, Instant};
+
+    fn node(identity: &str, endpoint: &str) -> Node {
+        Node::builder(identity)
+            .with_endpoint(endpoint)
+            .with_key("abcd")
+            .build()
+            .expect("Failed to build node")
+    }
+
+    /// Verify that `diff_nodes` reports added, updated and removed nodes, and nothing for
+    /// unchanged nodes.
+    #[test]
+    fn diff() {
+        let node1 = node("node1", "tcps://localhost:8044");
+        let node2 = node("node2", "tcps://localhost:8045");
+        let node2_moved = node("node2", "tcps://localhost:9045");
+        let node3 = node("node3", "tcps://localhost:8046");
+
+        assert_eq!(
+            diff_nodes(
+                &[node1.clone(), node2.clone()],
+                &[node2_moved.clone(), node3.clone()]
+            ),
+            vec![
+                RegistryEvent::NodeUpdated {
+                    previous: node2,
+                    node: node2_moved.clone(),
+                },
+                RegistryEvent::NodeAdded(node3.clone()),
+                RegistryEvent::NodeRemoved(node1),
+            ]
+        );
+        assert!(
+            diff_nodes(&[node2_moved.clone(), node3.clone()], &[node3, node2_moved]).is_empty()
+        );
+    }
+
+    struct ChannelSubscriber(Sender<RegistryEvent>);
+
+    impl RegistryEventSubscriber for ChannelSubscriber {
+        fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+            self.0
+                .send(event.clone())
+                .map_err(|_| RegistrySubscriberError::Unsubscribe)
+        }
+    }
+
+    /// Verify that broadcast events are received by the subscribers in order, that a subscriber
+    /// is removed when it unsubscribes, and that the dispatch thread stops when the subscribers are
+    /// dropped.
+    #[test]
+    fn broadcast() {
+        let subscribers = RegistryEventSubscribers::default();
+        let (sender, receiver) = channel();
+        subscribers
+            .add(Box::new(ChannelSubscriber(sender)))
+            .expect("Failed to add subscriber");
+
+        let event1 = RegistryEvent::NodeAdded(node("node1", "tcps://localhost:8044"));
+        let event2 = RegistryEvent::NodeAdded(node("node2", "tcps://localhost:8045"));
+        subscribers.broadcast(vec![event1.clone()]);
+        subscribers.broadcast(vec![event2.clone()]);
+        assert_eq!(
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("No event received"),
+            event1
+        );
+        assert_eq!(
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("No event received"),
+            event2
+        );
+
+        drop(receiver);
+        subscribers.broadcast(vec![event1]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !subscribers
+            .subscribers
+            .lock()
+            .expect("subscriber lock poisoned")
+            .is_empty()
+        {
+            assert!(Instant::now() < deadline, "Subscriber was not removed");
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let dispatcher_subscribers = Arc::downgrade(&subscribers.subscribers);
+        drop(subscribers);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while dispatcher_subscribers.upgrade().is_some() {
+            assert!(Instant::now() < deadline, "Dispatch thread did not stop");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
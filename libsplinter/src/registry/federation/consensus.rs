@@ -0,0 +1,306 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::{TryFrom, TryInto};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use openssl::hash::{hash, MessageDigest};
+use protobuf::Message;
+
+use crate::consensus::{
+    error::ProposalManagerError, two_phase::TwoPhaseEngine, ConsensusRunner, Proposal, ProposalId,
+    ProposalManager, ProposalUpdate, ServiceConsensusNetworkSender, ServicePeers,
+};
+use crate::protos::registry::{
+    ProposedRegistryChange, RegistryChange as RegistryChangeProto, RegistryMessage,
+    RegistryMessage_Type,
+};
+use crate::registry::RwRegistry;
+
+use super::error::RegistryFederationError;
+use super::shared::RegistryFederationShared;
+use super::{apply_change, check_change, RegistryChange};
+
+/// Component used by the registry service to manage and interact with consensus
+pub struct RegistryConsensusManager {
+    runner: ConsensusRunner,
+}
+
+impl RegistryConsensusManager {
+    /// Create the proposal manager, network sender, and channels used to communicate with
+    /// consensus, and start consensus in a separate thread.
+    pub fn new(
+        service_id: String,
+        shared: Arc<Mutex<RegistryFederationShared>>,
+        store: Box<dyn RwRegistry>,
+        // The coordinator timeout for the two-phase commit consensus engine
+        coordinator_timeout: Duration,
+    ) -> Result<Self, RegistryFederationError> {
+        let peer_ids = shared
+            .lock()
+            .map_err(|_| RegistryFederationError::LockPoisoned)?
+            .peer_services()
+            .iter()
+            .map(|id| id.as_bytes().into())
+            .collect();
+
+        let (proposal_update_tx, proposal_update_rx) = channel();
+
+        let proposal_manager = RegistryProposalManager::new(
+            service_id.clone(),
+            proposal_update_tx.clone(),
+            shared.clone(),
+            store,
+        );
+        let consensus_network_sender =
+            ServiceConsensusNetworkSender::new(service_id.clone(), shared, |consensus_message| {
+                let mut msg = RegistryMessage::new();
+                msg.set_message_type(RegistryMessage_Type::CONSENSUS_MESSAGE);
+                msg.set_consensus_message(consensus_message);
+                msg.write_to_bytes()
+            });
+
+        let runner = ConsensusRunner::start(
+            &service_id,
+            peer_ids,
+            Box::new(TwoPhaseEngine::new(coordinator_timeout)),
+            Box::new(consensus_network_sender),
+            Box::new(proposal_manager),
+            (proposal_update_tx, proposal_update_rx),
+        )
+        .map_err(|err| RegistryFederationError::ConsensusFailed(Box::new(err)))?;
+
+        Ok(RegistryConsensusManager { runner })
+    }
+
+    /// Consumes self and shuts down the consensus thread.
+    pub fn shutdown(self) -> Result<(), RegistryFederationError> {
+        self.runner
+            .shutdown()
+            .map_err(|err| RegistryFederationError::ConsensusFailed(Box::new(err)))
+    }
+
+    pub fn handle_message(&self, message_bytes: &[u8]) -> Result<(), RegistryFederationError> {
+        self.runner
+            .handle_message(message_bytes)
+            .map_err(|err| RegistryFederationError::ConsensusFailed(Box::new(err)))
+    }
+
+    pub fn send_update(&self, update: ProposalUpdate) -> Result<(), RegistryFederationError> {
+        self.runner
+            .send_update(update)
+            .map_err(|err| RegistryFederationError::ConsensusFailed(Box::new(err)))
+    }
+}
+
+pub struct RegistryProposalManager {
+    service_id: String,
+    proposal_update_sender: Sender<ProposalUpdate>,
+    shared: Arc<Mutex<RegistryFederationShared>>,
+    store: Box<dyn RwRegistry>,
+}
+
+impl RegistryProposalManager {
+    pub fn new(
+        service_id: String,
+        proposal_update_sender: Sender<ProposalUpdate>,
+        shared: Arc<Mutex<RegistryFederationShared>>,
+        store: Box<dyn RwRegistry>,
+    ) -> Self {
+        RegistryProposalManager {
+            service_id,
+            proposal_update_sender,
+            shared,
+            store,
+        }
+    }
+
+    /// Parses the given change and checks that it can be applied to this service's copy of the
+    /// registry.
+    fn validate_change(&self, change: &RegistryChangeProto) -> Result<(), String> {
+        RegistryChange::try_from(change)
+            .and_then(|change| check_change(&self.store, &change))
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl ProposalManager for RegistryProposalManager {
+    fn create_proposal(
+        &self,
+        // Ignoring previous proposal ID and consensus data, because this service and two phase
+        // consensus don't care about it.
+        _previous_proposal_id: Option<ProposalId>,
+        _consensus_data: Vec<u8>,
+    ) -> Result<(), ProposalManagerError> {
+        let mut shared = self.shared.lock().map_err(|_| {
+            ProposalManagerError::Internal(Box::new(RegistryFederationError::LockPoisoned))
+        })?;
+
+        let (change, commit_result_sender) = match shared.take_pending_change() {
+            Some(pending) => pending,
+            None => {
+                self.proposal_update_sender
+                    .send(ProposalUpdate::ProposalCreated(None))?;
+                return Ok(());
+            }
+        };
+
+        // The registry may have changed since the change was submitted, so it is checked again
+        // before it is proposed to the other services.
+        if let Err(err) = self.validate_change(&change) {
+            warn!("Dropping invalid registry change: {}", err);
+            // The submitter may have stopped waiting, so a failed send is not an error
+            let _ = commit_result_sender.send(Err(err));
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalCreated(None))?;
+            return Ok(());
+        }
+
+        // Intentionally leaving out the previous_id and proposal_height fields, since this
+        // service and two phase consensus don't use them. The proposal ID is the hash of the
+        // change, which every service can verify from the change itself.
+        let change_hash = hash_change(&change)?;
+        let mut proposal = Proposal::default();
+        proposal.id = change_hash.clone().into();
+        proposal.summary = change_hash;
+
+        shared.add_proposed_change(proposal.id.clone(), change.clone());
+        shared.add_commit_waiter(proposal.id.clone(), commit_result_sender);
+
+        // Send the proposal to the other services
+        let mut proposed_change = ProposedRegistryChange::new();
+        proposed_change.set_proposal(
+            proposal
+                .clone()
+                .try_into()
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?,
+        );
+        proposed_change.set_change(change);
+        proposed_change.set_service_id(self.service_id.clone());
+
+        let mut msg = RegistryMessage::new();
+        msg.set_message_type(RegistryMessage_Type::PROPOSED_CHANGE);
+        msg.set_proposed_change(proposed_change);
+        let msg_bytes = msg
+            .write_to_bytes()
+            .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+
+        shared.send_to_peers(&msg_bytes)?;
+
+        self.proposal_update_sender
+            .send(ProposalUpdate::ProposalCreated(Some(proposal)))?;
+
+        Ok(())
+    }
+
+    fn check_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
+        let change = self
+            .shared
+            .lock()
+            .map_err(|_| {
+                ProposalManagerError::Internal(Box::new(RegistryFederationError::LockPoisoned))
+            })?
+            .get_proposed_change(id)
+            .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?
+            .clone();
+
+        let change_hash = hash_change(&change)?;
+
+        if change_hash.as_slice() != id.as_ref() {
+            warn!(
+                "Hash mismatch: expected {} but was {}",
+                id,
+                ProposalId::from(change_hash)
+            );
+
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalInvalid(id.clone()))?;
+        } else if let Err(err) = self.validate_change(&change) {
+            warn!(
+                "Proposal {} contains an invalid registry change: {}",
+                id, err
+            );
+
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalInvalid(id.clone()))?;
+        } else {
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalValid(id.clone()))?;
+        }
+
+        Ok(())
+    }
+
+    fn accept_proposal(
+        &self,
+        id: &ProposalId,
+        // Ignoring consensus data, because this service and two phase consensus don't care about
+        // it.
+        _consensus_data: Option<Vec<u8>>,
+    ) -> Result<(), ProposalManagerError> {
+        let mut shared = self.shared.lock().map_err(|_| {
+            ProposalManagerError::Internal(Box::new(RegistryFederationError::LockPoisoned))
+        })?;
+
+        let change = shared
+            .remove_proposed_change(id)
+            .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?;
+
+        match RegistryChange::try_from(&change).and_then(|change| apply_change(&self.store, change))
+        {
+            Ok(()) => {
+                shared.notify_commit_waiters(id, Ok(()));
+                self.proposal_update_sender
+                    .send(ProposalUpdate::ProposalAccepted(id.clone()))?;
+
+                info!("Committed proposal {}", id);
+            }
+            Err(err) => {
+                let reason = format!("failed to apply registry change: {}", err);
+                shared.notify_commit_waiters(id, Err(reason.clone()));
+                self.proposal_update_sender
+                    .send(ProposalUpdate::ProposalAcceptFailed(id.clone(), reason))?
+            }
+        }
+
+        Ok(())
+    }
+
+    fn reject_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
+        let mut shared = self.shared.lock().map_err(|_| {
+            ProposalManagerError::Internal(Box::new(RegistryFederationError::LockPoisoned))
+        })?;
+
+        shared
+            .remove_proposed_change(id)
+            .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?;
+        shared.notify_commit_waiters(id, Err("the federation rejected the change".into()));
+
+        info!("Rejected proposal {}", id);
+
+        Ok(())
+    }
+}
+
+/// Returns the SHA-256 hash of the given change's protobuf representation.
+fn hash_change(change: &RegistryChangeProto) -> Result<Vec<u8>, ProposalManagerError> {
+    let bytes = change
+        .write_to_bytes()
+        .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+    hash(MessageDigest::sha256(), &bytes)
+        .map(|digest| digest.to_vec())
+        .map_err(|err| ProposalManagerError::Internal(Box::new(err)))
+}
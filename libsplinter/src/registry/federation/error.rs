@@ -0,0 +1,59 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+/// Errors that can occur in a registry federation service
+#[derive(Debug)]
+pub enum RegistryFederationError {
+    /// The service's consensus manager failed
+    ConsensusFailed(Box<dyn Error + Send>),
+    /// The service's shared state lock was poisoned
+    LockPoisoned,
+    /// A message with an unset type was received
+    MessageTypeUnset,
+    /// The service was unable to synchronize its copy of the registry
+    SyncFailed(Box<dyn Error + Send>),
+}
+
+impl Error for RegistryFederationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RegistryFederationError::ConsensusFailed(err) => Some(&**err),
+            RegistryFederationError::LockPoisoned => None,
+            RegistryFederationError::MessageTypeUnset => None,
+            RegistryFederationError::SyncFailed(err) => Some(&**err),
+        }
+    }
+}
+
+impl fmt::Display for RegistryFederationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryFederationError::ConsensusFailed(err) => {
+                write!(f, "registry federation consensus failed: {}", err)
+            }
+            RegistryFederationError::LockPoisoned => {
+                f.write_str("registry federation state lock was poisoned")
+            }
+            RegistryFederationError::MessageTypeUnset => {
+                f.write_str("received message with unset type")
+            }
+            RegistryFederationError::SyncFailed(err) => {
+                write!(f, "failed to synchronize registry: {}", err)
+            }
+        }
+    }
+}
@@ -0,0 +1,926 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A registry that is replicated over the Splinter network.
+//!
+//! This module contains the [`FederatedRegistry`], which provides an implementation of the
+//! [`RwRegistry`] trait, and the [`RegistryServiceFactory`], which creates the registry services
+//! that replicate it.
+//!
+//! # Federation
+//!
+//! A federated registry is shared by the members of a designated federation circuit. Each member
+//! runs a registry service (of type `registry`) on the circuit, and each service keeps a copy of
+//! the registry in its member's local store. Changes to the registry are proposed to the other
+//! registry services and are only applied once every service has verified them using two-phase
+//! commit consensus, so membership of the federation is managed by proposal.
+//!
+//! # Synchronization
+//!
+//! A member can miss changes, for example because it joined the federation circuit after they
+//! were made. When a member's registry service starts, it requests a snapshot of the registry
+//! from the federation's coordinator (the registry service with the lowest service ID, which
+//! coordinates every proposal) and replaces its copy of the registry with the snapshot. The
+//! coordinator's copy is treated as authoritative, so its own service does not request a
+//! snapshot.
+//!
+//! # Writing
+//!
+//! Writing to a [`FederatedRegistry`] submits the change to the registry service for the
+//! federation circuit and waits for the federation to commit it; the write returns once the
+//! change has been applied to the local copy of the registry, or with an error if the change was
+//! rejected or was not committed in time. Changes are rejected if the node's registry service is
+//! not running.
+//!
+//! # Reading
+//!
+//! Read operations are served from the local copy of the registry.
+//!
+//! [`FederatedRegistry`]: struct.FederatedRegistry.html
+//! [`RegistryServiceFactory`]: struct.RegistryServiceFactory.html
+//! [`RwRegistry`]: ../trait.RwRegistry.html
+
+mod consensus;
+mod error;
+mod service;
+mod shared;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+
+use crate::consensus::ServicePeers;
+use crate::protos::registry::{
+    RegistryChange as RegistryChangeProto, RegistryChange_Type as RegistryChangeProto_Type,
+    RegistrySnapshot,
+};
+
+#[cfg(feature = "registry-node-signing")]
+use super::node_signature::{check_node_removal, check_node_signature};
+#[cfg(feature = "registry-node-signing")]
+use super::NodeSignature;
+#[cfg(feature = "registry-events")]
+use super::RegistryEventSubscriber;
+use super::{
+    validate_nodes, MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader,
+    RegistryWriter, RwRegistry,
+};
+
+pub use self::service::RegistryServiceFactory;
+use self::shared::RegistryFederationShared;
+
+/// The service type of the services that replicate a federated registry.
+pub const REGISTRY_SERVICE_TYPE: &str = "registry";
+
+/// A registry that is replicated by the members of a federation circuit.
+///
+/// See the [module-level documentation](index.html) for details on how changes are replicated.
+/// Each change is agreed on individually, so the registry does not support batch changes.
+#[derive(Clone)]
+pub struct FederatedRegistry {
+    circuit_id: String,
+    store: Box<dyn RwRegistry>,
+    shared: Arc<Mutex<RegistryFederationShared>>,
+}
+
+impl FederatedRegistry {
+    /// Creates a new federated registry for the given circuit.
+    ///
+    /// # Arguments
+    ///
+    /// * `circuit_id` - The ID of the federation circuit that the registry is replicated on.
+    /// * `store` - The registry that holds this node's copy of the federated registry.
+    pub fn new<S: Into<String>>(circuit_id: S, store: Box<dyn RwRegistry>) -> Self {
+        FederatedRegistry {
+            circuit_id: circuit_id.into(),
+            store,
+            shared: Arc::new(Mutex::new(RegistryFederationShared::default())),
+        }
+    }
+
+    /// Returns the ID of the federation circuit that the registry is replicated on.
+    pub fn circuit_id(&self) -> &str {
+        &self.circuit_id
+    }
+
+    /// Creates a factory for the registry service that replicates this registry. The factory
+    /// should be provided to the node's service orchestrator.
+    pub fn service_factory(&self) -> RegistryServiceFactory {
+        RegistryServiceFactory::new(
+            self.circuit_id.clone(),
+            self.store.clone(),
+            self.shared.clone(),
+        )
+    }
+
+    /// Checks the change against the local copy of the registry, submits it to the registry
+    /// service to be proposed and waits for the federation to commit it.
+    fn submit_change(&self, change: RegistryChange) -> Result<(), RegistryError> {
+        check_change(&self.store, &change)?;
+        let change = change.into_proto()?;
+
+        let (commit_result_tx, commit_result_rx) = channel();
+        let commit_timeout = {
+            let mut shared = self.shared.lock().map_err(|_| {
+                RegistryError::general_error("Registry federation lock was poisoned")
+            })?;
+
+            if shared.network_sender().is_none() {
+                return Err(RegistryError::general_error(&format!(
+                    "Registry service for federation circuit {} is not running",
+                    self.circuit_id
+                )));
+            }
+
+            shared.add_pending_change(change, commit_result_tx);
+            shared.commit_timeout()
+        };
+
+        match commit_result_rx.recv_timeout(commit_timeout) {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(reason)) => Err(RegistryError::general_error(&format!(
+                "Registry change was not committed: {}",
+                reason
+            ))),
+            Err(RecvTimeoutError::Timeout) => Err(RegistryError::general_error(&format!(
+                "Registry change was not committed within {} seconds; it may still be applied",
+                commit_timeout.as_secs()
+            ))),
+            Err(RecvTimeoutError::Disconnected) => Err(RegistryError::general_error(&format!(
+                "Registry service for federation circuit {} stopped before the change was \
+                 committed",
+                self.circuit_id
+            ))),
+        }
+    }
+}
+
+impl RegistryReader for FederatedRegistry {
+    fn list_nodes<'a, 'b: 'a>(
+        &'b self,
+        predicates: &'a [MetadataPredicate],
+    ) -> Result<NodeIter<'a>, RegistryError> {
+        self.store.list_nodes(predicates)
+    }
+
+    fn count_nodes(&self, predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+        self.store.count_nodes(predicates)
+    }
+
+    fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        self.store.fetch_node(identity)
+    }
+
+    fn has_node(&self, identity: &str) -> Result<bool, RegistryError> {
+        self.store.has_node(identity)
+    }
+
+    #[cfg(feature = "registry-events")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.store.add_event_subscriber(subscriber)
+    }
+}
+
+impl RegistryWriter for FederatedRegistry {
+    /// Proposes adding or replacing the node and waits until the federation has committed the
+    /// change.
+    fn insert_node(&self, node: Node) -> Result<(), RegistryError> {
+        self.submit_change(RegistryChange::InsertNode(node))
+    }
+
+    /// Proposes deleting the node, waits until the federation has committed the change and
+    /// returns the node's previous definition.
+    fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        let node = self.store.fetch_node(identity)?;
+        if node.is_some() {
+            self.submit_change(RegistryChange::DeleteNode {
+                identity: identity.into(),
+                #[cfg(feature = "registry-node-signing")]
+                removal: None,
+            })?;
+        }
+        Ok(node)
+    }
+
+    /// Proposes deleting the node with the given removal signature, which every member checks
+    /// before the change is committed, waits until the federation has committed the change and
+    /// returns the node's previous definition.
+    #[cfg(feature = "registry-node-signing")]
+    fn delete_node_with_removal(
+        &self,
+        identity: &str,
+        removal: Option<&NodeSignature>,
+    ) -> Result<Option<Node>, RegistryError> {
+        let node = self.store.fetch_node(identity)?;
+        if node.is_some() {
+            self.submit_change(RegistryChange::DeleteNode {
+                identity: identity.into(),
+                removal: removal.cloned(),
+            })?;
+        }
+        Ok(node)
+    }
+}
+
+impl RwRegistry for FederatedRegistry {
+    fn clone_box(&self) -> Box<dyn RwRegistry> {
+        Box::new(self.clone())
+    }
+
+    fn clone_box_as_reader(&self) -> Box<dyn RegistryReader> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn clone_box_as_writer(&self) -> Box<dyn RegistryWriter> {
+        Box::new(Clone::clone(self))
+    }
+}
+
+/// A change to a federated registry.
+#[derive(Clone, Debug, PartialEq)]
+enum RegistryChange {
+    InsertNode(Node),
+    DeleteNode {
+        identity: String,
+        /// The signed removal of the node, which is required to delete a self-signed node
+        #[cfg(feature = "registry-node-signing")]
+        removal: Option<NodeSignature>,
+    },
+}
+
+impl RegistryChange {
+    fn into_proto(self) -> Result<RegistryChangeProto, RegistryError> {
+        let mut proto = RegistryChangeProto::new();
+        match self {
+            RegistryChange::InsertNode(node) => {
+                proto.set_change_type(RegistryChangeProto_Type::INSERT_NODE);
+                proto.set_node(serde_json::to_vec(&node).map_err(|err| {
+                    RegistryError::general_error_with_source(
+                        "Failed to serialize node",
+                        Box::new(err),
+                    )
+                })?);
+            }
+            RegistryChange::DeleteNode {
+                identity,
+                #[cfg(feature = "registry-node-signing")]
+                removal,
+            } => {
+                proto.set_change_type(RegistryChangeProto_Type::DELETE_NODE);
+                proto.set_identity(identity);
+                #[cfg(feature = "registry-node-signing")]
+                {
+                    if let Some(removal) = removal {
+                        proto.set_removal(serde_json::to_vec(&removal).map_err(|err| {
+                            RegistryError::general_error_with_source(
+                                "Failed to serialize node removal",
+                                Box::new(err),
+                            )
+                        })?);
+                    }
+                }
+            }
+        }
+        Ok(proto)
+    }
+}
+
+impl TryFrom<&RegistryChangeProto> for RegistryChange {
+    type Error = RegistryError;
+
+    fn try_from(proto: &RegistryChangeProto) -> Result<Self, Self::Error> {
+        match proto.get_change_type() {
+            RegistryChangeProto_Type::INSERT_NODE => serde_json::from_slice(proto.get_node())
+                .map(RegistryChange::InsertNode)
+                .map_err(|err| {
+                    RegistryError::general_error_with_source(
+                        "Failed to deserialize node",
+                        Box::new(err),
+                    )
+                }),
+            RegistryChangeProto_Type::DELETE_NODE => Ok(RegistryChange::DeleteNode {
+                identity: proto.get_identity().into(),
+                #[cfg(feature = "registry-node-signing")]
+                removal: if proto.get_removal().is_empty() {
+                    None
+                } else {
+                    Some(serde_json::from_slice(proto.get_removal()).map_err(|err| {
+                        RegistryError::general_error_with_source(
+                            "Failed to deserialize node removal",
+                            Box::new(err),
+                        )
+                    })?)
+                },
+            }),
+            RegistryChangeProto_Type::UNSET => Err(RegistryError::general_error(
+                "Registry change type is unset",
+            )),
+        }
+    }
+}
+
+/// Checks that the change can be applied to the given registry without invalidating it.
+fn check_change<R>(registry: &R, change: &RegistryChange) -> Result<(), RegistryError>
+where
+    R: RegistryReader + ?Sized,
+{
+    match change {
+        RegistryChange::InsertNode(node) => {
+            #[cfg(feature = "registry-node-signing")]
+            check_node_signature(node, registry.fetch_node(&node.identity)?.as_ref())?;
+
+            // The new node is checked against every other node in the registry, so it is placed
+            // first; any existing definition of the node is replaced by the change.
+            let nodes = std::iter::once(node.clone())
+                .chain(
+                    registry
+                        .list_nodes(&[])?
+                        .filter(|existing| existing.identity != node.identity),
+                )
+                .collect::<Vec<_>>();
+            validate_nodes(&nodes).map_err(RegistryError::from)
+        }
+        RegistryChange::DeleteNode {
+            identity,
+            #[cfg(feature = "registry-node-signing")]
+            removal,
+        } => {
+            if identity.is_empty() {
+                return Err(RegistryError::general_error(
+                    "Cannot delete a node with an empty identity",
+                ));
+            }
+
+            // A self-signed node can only be deleted with a removal signed by one of its keys
+            #[cfg(feature = "registry-node-signing")]
+            {
+                if let Some(existing) = registry.fetch_node(identity)? {
+                    check_node_removal(&existing, removal.as_ref())?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Applies the change to the given registry.
+fn apply_change<W>(registry: &W, change: RegistryChange) -> Result<(), RegistryError>
+where
+    W: RegistryWriter + ?Sized,
+{
+    match change {
+        RegistryChange::InsertNode(node) => registry.insert_node(node),
+        RegistryChange::DeleteNode { identity, .. } => registry.delete_node(&identity).map(|_| ()),
+    }
+}
+
+/// Creates a snapshot of every node in the given registry.
+fn create_snapshot<R>(registry: &R) -> Result<RegistrySnapshot, RegistryError>
+where
+    R: RegistryReader + ?Sized,
+{
+    let nodes = registry
+        .list_nodes(&[])?
+        .map(|node| {
+            serde_json::to_vec(&node).map_err(|err| {
+                RegistryError::general_error_with_source("Failed to serialize node", Box::new(err))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut snapshot = RegistrySnapshot::new();
+    snapshot.set_nodes(nodes.into());
+    Ok(snapshot)
+}
+
+/// Replaces the contents of the given registry with the nodes in the snapshot.
+fn apply_snapshot<R>(registry: &R, snapshot: &RegistrySnapshot) -> Result<(), RegistryError>
+where
+    R: RwRegistry + ?Sized,
+{
+    let nodes = snapshot
+        .get_nodes()
+        .iter()
+        .map(|bytes| {
+            serde_json::from_slice::<Node>(bytes).map_err(|err| {
+                RegistryError::general_error_with_source(
+                    "Failed to deserialize node",
+                    Box::new(err),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    validate_nodes(&nodes)?;
+
+    let mut existing = registry
+        .list_nodes(&[])?
+        .map(|node| (node.identity.clone(), node))
+        .collect::<HashMap<_, _>>();
+
+    // Only the nodes that differ from the local copy are written
+    let changed = nodes
+        .into_iter()
+        .filter(|node| existing.remove(&node.identity).as_ref() != Some(node))
+        .collect::<Vec<_>>();
+    let removed = existing
+        .into_iter()
+        .map(|(identity, _)| identity)
+        .collect::<Vec<_>>();
+
+    if !removed.is_empty() {
+        registry.delete_nodes(&removed)?;
+    }
+    if !changed.is_empty() {
+        registry.insert_nodes(changed)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::sync::mpsc::{channel, Receiver, Sender};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use tempdir::TempDir;
+
+    use crate::registry::LocalYamlRegistry;
+    use crate::service::{
+        error, Service, ServiceFactory, ServiceMessageContext, ServiceNetworkRegistry,
+        ServiceNetworkSender,
+    };
+
+    const CIRCUIT_ID: &str = "federation";
+
+    /// Verifies that a registry change survives the round trip through its protobuf
+    /// representation.
+    #[test]
+    fn change_proto_round_trip() {
+        let insert = RegistryChange::InsertNode(get_node("node-1", "tcps://12.0.0.123:8431"));
+        let proto = insert
+            .clone()
+            .into_proto()
+            .expect("Failed to convert insert change");
+        assert_eq!(
+            RegistryChange::try_from(&proto).expect("Failed to parse insert change"),
+            insert
+        );
+
+        let delete = RegistryChange::DeleteNode {
+            identity: "node-1".into(),
+            #[cfg(feature = "registry-node-signing")]
+            removal: None,
+        };
+        let proto = delete
+            .clone()
+            .into_proto()
+            .expect("Failed to convert delete change");
+        assert_eq!(
+            RegistryChange::try_from(&proto).expect("Failed to parse delete change"),
+            delete
+        );
+
+        assert!(RegistryChange::try_from(&RegistryChangeProto::new()).is_err());
+    }
+
+    /// Verifies that a self-signed node can only be deleted by a change that carries a removal
+    /// signed by one of the node's keys, and that the removal survives the round trip through the
+    /// change's protobuf representation.
+    #[cfg(feature = "registry-node-signing")]
+    #[test]
+    fn check_signed_node_removal() {
+        use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
+
+        use crate::hex::to_hex;
+        use crate::signing::{sawtooth::SawtoothSecp256k1RefSigner, Signer};
+
+        let temp_dir =
+            TempDir::new("check_signed_node_removal").expect("Failed to create temp dir");
+        let store = create_store(&temp_dir, "registry");
+
+        let context = Secp256k1Context::new();
+        let signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(
+                "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088",
+            )
+            .expect("Failed to parse private key"),
+        )
+        .expect("Failed to create signer");
+
+        let mut node = Node::builder("node-1")
+            .with_endpoint("tcps://12.0.0.123:8431")
+            .with_key(to_hex(signer.public_key()))
+            .build()
+            .expect("Failed to build node");
+        node.sign(1, &signer).expect("Failed to sign node");
+        store.insert_node(node).expect("Failed to insert node");
+
+        let unsigned_delete = RegistryChange::DeleteNode {
+            identity: "node-1".into(),
+            removal: None,
+        };
+        assert!(check_change(&store, &unsigned_delete).is_err());
+
+        let signed_delete = RegistryChange::DeleteNode {
+            identity: "node-1".into(),
+            removal: Some(
+                NodeSignature::sign_removal("node-1", 2, &signer).expect("Failed to sign removal"),
+            ),
+        };
+        let proto = signed_delete
+            .clone()
+            .into_proto()
+            .expect("Failed to convert delete change");
+        let received = RegistryChange::try_from(&proto).expect("Failed to parse delete change");
+        assert_eq!(received, signed_delete);
+        assert!(check_change(&store, &received).is_ok());
+    }
+
+    /// Verifies that writes to a federated registry are rejected when its registry service is
+    /// not running, and that invalid nodes are rejected before they are proposed.
+    #[test]
+    fn write_requires_running_service() {
+        let temp_dir =
+            TempDir::new("write_requires_running_service").expect("Failed to create temp dir");
+        let registry = FederatedRegistry::new(CIRCUIT_ID, create_store(&temp_dir, "registry"));
+
+        assert!(registry
+            .insert_node(get_node("node-1", "tcps://12.0.0.123:8431"))
+            .is_err());
+
+        let mut invalid_node = get_node("node-1", "tcps://12.0.0.123:8431");
+        invalid_node.keys.clear();
+        match registry.insert_node(invalid_node) {
+            Err(RegistryError::InvalidNode(_)) => (),
+            res => panic!("Expected an invalid node error, got {:?}", res),
+        }
+
+        // Deleting a node that is not in the registry does not require a proposal
+        assert_eq!(
+            registry
+                .delete_node("node-1")
+                .expect("Failed to delete node"),
+            None
+        );
+    }
+
+    /// Verifies that the registry service factory only creates services on the federation
+    /// circuit and requires the `peer_services` argument.
+    #[test]
+    fn factory_create() {
+        let temp_dir = TempDir::new("factory_create").expect("Failed to create temp dir");
+        let factory = FederatedRegistry::new(CIRCUIT_ID, create_store(&temp_dir, "registry"))
+            .service_factory();
+
+        let mut args = HashMap::new();
+        args.insert("peer_services".to_string(), "[\"reg-b\"]".to_string());
+
+        assert!(factory
+            .create(
+                "reg-a".into(),
+                REGISTRY_SERVICE_TYPE,
+                "other-circuit",
+                args.clone()
+            )
+            .is_err());
+        assert!(factory
+            .create(
+                "reg-a".into(),
+                REGISTRY_SERVICE_TYPE,
+                CIRCUIT_ID,
+                HashMap::new()
+            )
+            .is_err());
+
+        let service = factory
+            .create("reg-a".into(), REGISTRY_SERVICE_TYPE, CIRCUIT_ID, args)
+            .expect("Failed to create service");
+        assert_eq!(service.service_id(), "reg-a");
+        assert_eq!(service.service_type(), REGISTRY_SERVICE_TYPE);
+    }
+
+    /// Verifies that changes written to one member's federated registry are replicated to the
+    /// other member once the registry services have agreed on them.
+    ///
+    /// 1. Start the registry services for two members, routing their messages to each other
+    /// 2. Insert a node using the member that is not the consensus coordinator
+    /// 3. Verify that the node is added to both members' registries
+    /// 4. Delete the node using the coordinator and verify that it is removed from both
+    ///    members' registries
+    #[test]
+    fn replicate_changes() {
+        let temp_dir = TempDir::new("replicate_changes").expect("Failed to create temp dir");
+        let registry_a = FederatedRegistry::new(CIRCUIT_ID, create_store(&temp_dir, "a"));
+        let registry_b = FederatedRegistry::new(CIRCUIT_ID, create_store(&temp_dir, "b"));
+
+        let (tx, rx) = channel();
+        let network = MockNetworkRegistry { tx };
+        let mut service_a = create_service(&registry_a, "reg-a", "reg-b");
+        let mut service_b = create_service(&registry_b, "reg-b", "reg-a");
+        service_a
+            .start(&network)
+            .expect("Failed to start service a");
+        service_b
+            .start(&network)
+            .expect("Failed to start service b");
+
+        let node = get_node("node-1", "tcps://12.0.0.123:8431");
+        let writer = registry_b.clone();
+        let inserted = node.clone();
+        write_and_route_messages(&rx, &[&*service_a, &*service_b], move || {
+            writer.insert_node(inserted)
+        })
+        .expect("Failed to insert node");
+
+        // The write returns once the change has been committed by the writing member
+        assert!(registry_b.has_node("node-1").expect("Failed to check node"));
+        route_messages_until(&rx, &[&*service_a, &*service_b], || {
+            registry_a.has_node("node-1").unwrap_or(false)
+        });
+        assert_eq!(
+            registry_a
+                .fetch_node("node-1")
+                .expect("Failed to fetch node"),
+            Some(node.clone())
+        );
+
+        let writer = registry_a.clone();
+        assert_eq!(
+            write_and_route_messages(&rx, &[&*service_a, &*service_b], move || {
+                writer.delete_node("node-1")
+            })
+            .expect("Failed to delete node"),
+            Some(node)
+        );
+
+        assert!(!registry_a.has_node("node-1").expect("Failed to check node"));
+        route_messages_until(&rx, &[&*service_a, &*service_b], || {
+            !registry_b.has_node("node-1").unwrap_or(true)
+        });
+
+        service_a.stop(&network).expect("Failed to stop service a");
+        service_b.stop(&network).expect("Failed to stop service b");
+
+        assert!(registry_a
+            .insert_node(get_node("node-2", "tcps://12.0.0.123:8432"))
+            .is_err());
+    }
+
+    /// Verifies that a write that has not been committed when the registry service stops returns
+    /// an error.
+    ///
+    /// 1. Start the registry services for two members without routing their messages
+    /// 2. Insert a node in a separate thread
+    /// 3. Stop the registry services and verify that the write fails
+    #[test]
+    fn uncommitted_write_fails_on_stop() {
+        let temp_dir =
+            TempDir::new("uncommitted_write_fails_on_stop").expect("Failed to create temp dir");
+        let registry_a = FederatedRegistry::new(CIRCUIT_ID, create_store(&temp_dir, "a"));
+        let registry_b = FederatedRegistry::new(CIRCUIT_ID, create_store(&temp_dir, "b"));
+
+        let (tx, _rx) = channel();
+        let network = MockNetworkRegistry { tx };
+        let mut service_a = create_service(&registry_a, "reg-a", "reg-b");
+        let mut service_b = create_service(&registry_b, "reg-b", "reg-a");
+        service_a
+            .start(&network)
+            .expect("Failed to start service a");
+        service_b
+            .start(&network)
+            .expect("Failed to start service b");
+
+        let writer = registry_b.clone();
+        let write =
+            thread::spawn(move || writer.insert_node(get_node("node-1", "tcps://12.0.0.123:8431")));
+
+        service_a.stop(&network).expect("Failed to stop service a");
+        service_b.stop(&network).expect("Failed to stop service b");
+
+        assert!(write.join().expect("Write thread panicked").is_err());
+        assert!(!registry_b.has_node("node-1").expect("Failed to check node"));
+    }
+
+    /// Verifies that a member's copy of the registry is replaced with the coordinator's copy when
+    /// its registry service starts, and that the coordinator's copy is left unchanged.
+    ///
+    /// 1. Add a node to the coordinator's copy and a different node to the other member's copy
+    /// 2. Start the registry services for both members, routing their messages to each other
+    /// 3. Verify that the other member's copy only contains the coordinator's node
+    /// 4. Verify that the coordinator's copy is unchanged
+    #[test]
+    fn sync_on_start() {
+        let temp_dir = TempDir::new("sync_on_start").expect("Failed to create temp dir");
+        let store_a = create_store(&temp_dir, "a");
+        store_a
+            .insert_node(get_node("node-1", "tcps://12.0.0.123:8431"))
+            .expect("Failed to insert node in store a");
+        let store_b = create_store(&temp_dir, "b");
+        store_b
+            .insert_node(get_node("node-2", "tcps://12.0.0.123:8432"))
+            .expect("Failed to insert node in store b");
+        let registry_a = FederatedRegistry::new(CIRCUIT_ID, store_a);
+        let registry_b = FederatedRegistry::new(CIRCUIT_ID, store_b);
+
+        let (tx, rx) = channel();
+        let network = MockNetworkRegistry { tx };
+        let mut service_a = create_service(&registry_a, "reg-a", "reg-b");
+        let mut service_b = create_service(&registry_b, "reg-b", "reg-a");
+        service_a
+            .start(&network)
+            .expect("Failed to start service a");
+        service_b
+            .start(&network)
+            .expect("Failed to start service b");
+
+        route_messages_until(&rx, &[&*service_a, &*service_b], || {
+            registry_b.has_node("node-1").unwrap_or(false)
+        });
+        assert_eq!(
+            registry_b
+                .list_nodes(&[])
+                .expect("Failed to list nodes")
+                .map(|node| node.identity)
+                .collect::<Vec<_>>(),
+            vec!["node-1".to_string()]
+        );
+        assert_eq!(
+            registry_a
+                .list_nodes(&[])
+                .expect("Failed to list nodes")
+                .map(|node| node.identity)
+                .collect::<Vec<_>>(),
+            vec!["node-1".to_string()]
+        );
+
+        service_a.stop(&network).expect("Failed to stop service a");
+        service_b.stop(&network).expect("Failed to stop service b");
+    }
+
+    fn create_store(temp_dir: &TempDir, name: &str) -> Box<dyn RwRegistry> {
+        let path = temp_dir
+            .path()
+            .join(format!("{}.yaml", name))
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+        Box::new(LocalYamlRegistry::new(&path).expect("Failed to create local registry"))
+    }
+
+    fn create_service(
+        registry: &FederatedRegistry,
+        service_id: &str,
+        peer_service: &str,
+    ) -> Box<dyn Service> {
+        let mut args = HashMap::new();
+        args.insert(
+            "peer_services".to_string(),
+            format!("[\"{}\"]", peer_service),
+        );
+        registry
+            .service_factory()
+            .create(service_id.into(), REGISTRY_SERVICE_TYPE, CIRCUIT_ID, args)
+            .expect("Failed to create service")
+    }
+
+    /// Delivers the messages sent by the services to their recipients until the condition is met,
+    /// panicking if it is not met within 10 seconds.
+    fn route_messages_until<F>(
+        rx: &Receiver<(String, String, Vec<u8>)>,
+        services: &[&dyn Service],
+        condition: F,
+    ) where
+        F: Fn() -> bool,
+    {
+        let start = Instant::now();
+        while !condition() {
+            if start.elapsed() > Duration::from_secs(10) {
+                panic!("Timed out waiting for registry change to be replicated");
+            }
+
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok((sender, recipient, message)) => {
+                    let service = services
+                        .iter()
+                        .find(|service| service.service_id() == recipient)
+                        .expect("Message sent to unknown service");
+                    let context = ServiceMessageContext {
+                        sender,
+                        circuit: CIRCUIT_ID.into(),
+                        correlation_id: "".into(),
+                    };
+                    service
+                        .handle_message(&message, &context)
+                        .expect("Failed to handle message");
+                }
+                Err(_) => thread::yield_now(),
+            }
+        }
+    }
+
+    /// Performs the write in a separate thread, delivering the messages sent by the services to
+    /// their recipients until the write returns.
+    fn write_and_route_messages<F, T>(
+        rx: &Receiver<(String, String, Vec<u8>)>,
+        services: &[&dyn Service],
+        write: F,
+    ) -> T
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = thread::spawn(write);
+        route_messages_until(rx, services, || handle.is_finished());
+        handle.join().expect("Write thread panicked")
+    }
+
+    fn get_node(identity: &str, endpoint: &str) -> Node {
+        Node::builder(identity)
+            .with_endpoint(endpoint)
+            .with_display_name("Node")
+            .with_key("0123")
+            .build()
+            .expect("Failed to build node")
+    }
+
+    struct MockNetworkRegistry {
+        tx: Sender<(String, String, Vec<u8>)>,
+    }
+
+    impl ServiceNetworkRegistry for MockNetworkRegistry {
+        fn connect(
+            &self,
+            service_id: &str,
+        ) -> Result<Box<dyn ServiceNetworkSender>, error::ServiceConnectionError> {
+            Ok(Box::new(MockNetworkSender {
+                service_id: service_id.into(),
+                tx: self.tx.clone(),
+            }))
+        }
+
+        fn disconnect(&self, _service_id: &str) -> Result<(), error::ServiceDisconnectionError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Clone)]
+    struct MockNetworkSender {
+        service_id: String,
+        tx: Sender<(String, String, Vec<u8>)>,
+    }
+
+    impl ServiceNetworkSender for MockNetworkSender {
+        fn send(&self, recipient: &str, message: &[u8]) -> Result<(), error::ServiceSendError> {
+            self.tx
+                .send((
+                    self.service_id.clone(),
+                    recipient.to_string(),
+                    message.to_vec(),
+                ))
+                .expect("Unable to send test message");
+
+            Ok(())
+        }
+
+        fn send_and_await(
+            &self,
+            _recipient: &str,
+            _message: &[u8],
+        ) -> Result<Vec<u8>, error::ServiceSendError> {
+            panic!("MockNetworkSender.send_and_await unexpectedly called")
+        }
+
+        fn reply(
+            &self,
+            _message_origin: &ServiceMessageContext,
+            _message: &[u8],
+        ) -> Result<(), error::ServiceSendError> {
+            panic!("MockNetworkSender.reply unexpectedly called")
+        }
+
+        fn clone_box(&self) -> Box<dyn ServiceNetworkSender> {
+            Box::new(self.clone())
+        }
+    }
+}
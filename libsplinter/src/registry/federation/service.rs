@@ -0,0 +1,375 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use protobuf::Message;
+
+use crate::consensus::{Proposal, ProposalUpdate, ServicePeers};
+use crate::protos::registry::{RegistryMessage, RegistryMessage_Type};
+use crate::registry::RwRegistry;
+use crate::service::{
+    FactoryCreateError, Service, ServiceDestroyError, ServiceError, ServiceFactory,
+    ServiceMessageContext, ServiceNetworkRegistry, ServiceStartError, ServiceStopError,
+};
+
+use super::consensus::RegistryConsensusManager;
+use super::error::RegistryFederationError;
+use super::shared::RegistryFederationShared;
+use super::{apply_snapshot, create_snapshot, REGISTRY_SERVICE_TYPE};
+
+const DEFAULT_COORDINATOR_TIMEOUT: u64 = 30; // 30 seconds
+
+/// A service that replicates a federated registry with the other registry services on the
+/// federation circuit.
+pub struct RegistryService {
+    service_id: String,
+    peer_services: HashSet<String>,
+    coordinator_timeout: Duration,
+    store: Box<dyn RwRegistry>,
+    shared: Arc<Mutex<RegistryFederationShared>>,
+    consensus: Mutex<Option<RegistryConsensusManager>>,
+    /// Whether the service has requested a snapshot of the registry that it has not received yet
+    awaiting_snapshot: AtomicBool,
+}
+
+impl RegistryService {
+    /// Returns the ID of the federation's coordinator if it is a peer of this service; the
+    /// coordinator is the service with the lowest ID, as in two-phase commit consensus.
+    fn peer_coordinator(&self) -> Option<&str> {
+        self.peer_services
+            .iter()
+            .map(String::as_str)
+            .min()
+            .filter(|peer| *peer < self.service_id.as_str())
+    }
+
+    /// Requests a snapshot of the registry from the coordinator, if this service is not the
+    /// coordinator.
+    fn request_snapshot(&self) -> Result<(), RegistryFederationError> {
+        let coordinator = match self.peer_coordinator() {
+            Some(coordinator) => coordinator,
+            None => return Ok(()),
+        };
+
+        let mut msg = RegistryMessage::new();
+        msg.set_message_type(RegistryMessage_Type::SNAPSHOT_REQUEST);
+        let msg_bytes = msg
+            .write_to_bytes()
+            .map_err(|err| RegistryFederationError::SyncFailed(Box::new(err)))?;
+
+        self.awaiting_snapshot.store(true, Ordering::SeqCst);
+
+        let shared = self
+            .shared
+            .lock()
+            .map_err(|_| RegistryFederationError::LockPoisoned)?;
+        if let Some(sender) = shared.network_sender() {
+            sender
+                .send(coordinator, &msg_bytes)
+                .map_err(|err| RegistryFederationError::SyncFailed(Box::new(err)))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Service for RegistryService {
+    fn service_id(&self) -> &str {
+        &self.service_id
+    }
+
+    fn service_type(&self) -> &str {
+        REGISTRY_SERVICE_TYPE
+    }
+
+    fn start(
+        &mut self,
+        service_registry: &dyn ServiceNetworkRegistry,
+    ) -> Result<(), ServiceStartError> {
+        let mut consensus = self
+            .consensus
+            .lock()
+            .map_err(|_| ServiceStartError::PoisonedLock("consensus lock poisoned".into()))?;
+
+        if consensus.is_some() {
+            return Err(ServiceStartError::AlreadyStarted);
+        }
+
+        {
+            let mut shared = self
+                .shared
+                .lock()
+                .map_err(|_| ServiceStartError::PoisonedLock("shared lock poisoned".into()))?;
+
+            // Only one registry service may replicate the federated registry at a time
+            if shared.network_sender().is_some() {
+                return Err(ServiceStartError::AlreadyStarted);
+            }
+
+            shared.set_network_sender(service_registry.connect(self.service_id())?);
+            shared.set_peer_services(self.peer_services.clone());
+            // A change is proposed once the proposal ahead of it has been decided, and each
+            // proposal is decided within the coordinator timeout
+            shared.set_commit_timeout(self.coordinator_timeout * 2);
+        }
+
+        // Setup consensus
+        consensus.replace(
+            RegistryConsensusManager::new(
+                self.service_id().into(),
+                self.shared.clone(),
+                self.store.clone(),
+                self.coordinator_timeout,
+            )
+            .map_err(|err| ServiceStartError::Internal(Box::new(err)))?,
+        );
+
+        // This member's copy of the registry may be missing changes that were made while its
+        // service was not running, so it is brought up to date with the coordinator's copy
+        if let Err(err) = self.request_snapshot() {
+            warn!("Unable to synchronize the federated registry: {}", err);
+        }
+
+        Ok(())
+    }
+
+    fn stop(
+        &mut self,
+        service_registry: &dyn ServiceNetworkRegistry,
+    ) -> Result<(), ServiceStopError> {
+        debug!("Stopping registry service with id {}", self.service_id);
+
+        // Shutdown consensus
+        self.consensus
+            .lock()
+            .map_err(|_| ServiceStopError::PoisonedLock("consensus lock poisoned".into()))?
+            .take()
+            .ok_or_else(|| ServiceStopError::NotStarted)?
+            .shutdown()
+            .map_err(|err| ServiceStopError::Internal(Box::new(err)))?;
+
+        self.shared
+            .lock()
+            .map_err(|_| ServiceStopError::PoisonedLock("shared lock poisoned".into()))?
+            .take_network_sender();
+
+        service_registry.disconnect(self.service_id())?;
+
+        Ok(())
+    }
+
+    fn destroy(self: Box<Self>) -> Result<(), ServiceDestroyError> {
+        if self
+            .consensus
+            .lock()
+            .map_err(|_| ServiceDestroyError::PoisonedLock("consensus lock poisoned".into()))?
+            .is_some()
+        {
+            Err(ServiceDestroyError::NotStopped)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn handle_message(
+        &self,
+        message_bytes: &[u8],
+        message_context: &ServiceMessageContext,
+    ) -> Result<(), ServiceError> {
+        let message: RegistryMessage = protobuf::parse_from_bytes(message_bytes)?;
+
+        match message.get_message_type() {
+            RegistryMessage_Type::CONSENSUS_MESSAGE => self
+                .consensus
+                .lock()
+                .map_err(|_| ServiceError::PoisonedLock("consensus lock poisoned".into()))?
+                .as_ref()
+                .ok_or_else(|| ServiceError::NotStarted)?
+                .handle_message(message.get_consensus_message())
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err))),
+            RegistryMessage_Type::PROPOSED_CHANGE => {
+                let proposed_change = message.get_proposed_change();
+
+                let proposal = Proposal::try_from(proposed_change.get_proposal())?;
+
+                self.shared
+                    .lock()
+                    .map_err(|_| ServiceError::PoisonedLock("shared lock poisoned".into()))?
+                    .add_proposed_change(proposal.id.clone(), proposed_change.get_change().clone());
+
+                self.consensus
+                    .lock()
+                    .map_err(|_| ServiceError::PoisonedLock("consensus lock poisoned".into()))?
+                    .as_ref()
+                    .ok_or_else(|| ServiceError::NotStarted)?
+                    .send_update(ProposalUpdate::ProposalReceived(
+                        proposal,
+                        proposed_change.get_service_id().as_bytes().into(),
+                    ))
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+            }
+            RegistryMessage_Type::SNAPSHOT_REQUEST => {
+                let snapshot = create_snapshot(&*self.store)
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                let mut msg = RegistryMessage::new();
+                msg.set_message_type(RegistryMessage_Type::SNAPSHOT);
+                msg.set_snapshot(snapshot);
+                let msg_bytes = msg.write_to_bytes()?;
+
+                self.shared
+                    .lock()
+                    .map_err(|_| ServiceError::PoisonedLock("shared lock poisoned".into()))?
+                    .network_sender()
+                    .ok_or_else(|| ServiceError::NotStarted)?
+                    .send(&message_context.sender, &msg_bytes)?;
+
+                Ok(())
+            }
+            RegistryMessage_Type::SNAPSHOT => {
+                // Only the snapshot that was requested from the coordinator is applied
+                if self.peer_coordinator() != Some(message_context.sender.as_str())
+                    || !self.awaiting_snapshot.swap(false, Ordering::SeqCst)
+                {
+                    warn!(
+                        "Ignoring unrequested registry snapshot from {}",
+                        message_context.sender
+                    );
+                    return Ok(());
+                }
+
+                apply_snapshot(&*self.store, message.get_snapshot())
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                info!(
+                    "Synchronized the federated registry with {}",
+                    message_context.sender
+                );
+
+                Ok(())
+            }
+            RegistryMessage_Type::UNSET => Err(ServiceError::InvalidMessageFormat(Box::new(
+                RegistryFederationError::MessageTypeUnset,
+            ))),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Creates the registry service that replicates a federated registry.
+///
+/// Registry services may only be created on the federated registry's circuit.
+pub struct RegistryServiceFactory {
+    service_types: Vec<String>,
+    circuit_id: String,
+    store: Box<dyn RwRegistry>,
+    shared: Arc<Mutex<RegistryFederationShared>>,
+}
+
+impl RegistryServiceFactory {
+    pub(super) fn new(
+        circuit_id: String,
+        store: Box<dyn RwRegistry>,
+        shared: Arc<Mutex<RegistryFederationShared>>,
+    ) -> Self {
+        RegistryServiceFactory {
+            service_types: vec![REGISTRY_SERVICE_TYPE.into()],
+            circuit_id,
+            store,
+            shared,
+        }
+    }
+}
+
+impl ServiceFactory for RegistryServiceFactory {
+    fn available_service_types(&self) -> &[String] {
+        self.service_types.as_slice()
+    }
+
+    /// `args` must include the following:
+    /// - `peer_services`: list of other registry services on the federation circuit that this
+    ///   service will replicate the registry with, formatted as a serialized JSON array of strings
+    ///
+    /// `args` may include the following optional entries:
+    /// - `coordinator_timeout`: the length of time (in milliseconds) that the network has to
+    ///   commit a proposal before the coordinator rejects it (if not provided, default is 30
+    ///   seconds)
+    fn create(
+        &self,
+        service_id: String,
+        _service_type: &str,
+        circuit_id: &str,
+        args: HashMap<String, String>,
+    ) -> Result<Box<dyn Service>, FactoryCreateError> {
+        if circuit_id != self.circuit_id {
+            return Err(FactoryCreateError::InvalidArguments(format!(
+                "registry services may only be created on the federation circuit ({})",
+                self.circuit_id
+            )));
+        }
+
+        let peer_services_str = args.get("peer_services").ok_or_else(|| {
+            FactoryCreateError::InvalidArguments("peer_services argument not provided".into())
+        })?;
+        let peer_services = HashSet::from_iter(
+            serde_json::from_str::<Vec<_>>(peer_services_str)
+                .map_err(|err| {
+                    FactoryCreateError::InvalidArguments(format!(
+                        "failed to parse peer_services list: {}",
+                        err,
+                    ))
+                })?
+                .into_iter(),
+        );
+
+        let coordinator_timeout = args
+            .get("coordinator_timeout")
+            .map(|timeout| match timeout.parse::<u64>() {
+                Ok(timeout) => Ok(Duration::from_millis(timeout)),
+                Err(err) => Err(FactoryCreateError::InvalidArguments(format!(
+                    "invalid coordinator_timeout: {}",
+                    err
+                ))),
+            })
+            .transpose()?
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_COORDINATOR_TIMEOUT));
+
+        Ok(Box::new(RegistryService {
+            service_id,
+            peer_services,
+            coordinator_timeout,
+            store: self.store.clone(),
+            shared: self.shared.clone(),
+            consensus: Mutex::new(None),
+            awaiting_snapshot: AtomicBool::new(false),
+        }))
+    }
+
+    #[cfg(feature = "rest-api")]
+    /// The registry service does not provide any REST API resources; the federated registry is
+    /// served by the node's registry endpoints.
+    fn get_rest_endpoints(&self) -> Vec<crate::service::rest_api::ServiceEndpoint> {
+        vec![]
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use crate::consensus::{ProposalId, ServicePeers};
+use crate::protos::registry::RegistryChange as RegistryChangeProto;
+use crate::service::ServiceNetworkSender;
+
+/// The result of a submitted change, sent to the submitter once the change has been committed
+/// or rejected; a rejected change carries the reason it was rejected.
+pub type CommitResult = Result<(), String>;
+
+/// State that is shared between a federated registry, its service and the service's consensus
+/// components.
+#[derive(Default)]
+pub struct RegistryFederationShared {
+    /// Sender for messages to the other registry services; only set while the service is running
+    network_sender: Option<Box<dyn ServiceNetworkSender>>,
+    /// The IDs of the other registry services on the federation circuit
+    peer_services: HashSet<String>,
+    /// How long a submitted change may take to be committed
+    commit_timeout: Duration,
+    /// Changes that have been submitted locally but have not been proposed yet, along with the
+    /// sender for their commit results
+    pending_changes: VecDeque<(RegistryChangeProto, Sender<CommitResult>)>,
+    /// Changes that are currently being evaluated by consensus
+    proposed_changes: HashMap<ProposalId, RegistryChangeProto>,
+    /// The senders for the commit results of the locally submitted changes that are currently
+    /// being evaluated by consensus
+    commit_waiters: HashMap<ProposalId, Vec<Sender<CommitResult>>>,
+}
+
+impl RegistryFederationShared {
+    pub fn set_network_sender(&mut self, sender: Box<dyn ServiceNetworkSender>) {
+        self.network_sender = Some(sender)
+    }
+
+    /// Takes the network sender and drops every change that has not been committed yet; the
+    /// submitters of those changes are notified by their commit result senders being dropped.
+    pub fn take_network_sender(&mut self) -> Option<Box<dyn ServiceNetworkSender>> {
+        self.pending_changes.clear();
+        self.proposed_changes.clear();
+        self.commit_waiters.clear();
+        self.network_sender.take()
+    }
+
+    pub fn set_peer_services(&mut self, peer_services: HashSet<String>) {
+        self.peer_services = peer_services
+    }
+
+    pub fn commit_timeout(&self) -> Duration {
+        self.commit_timeout
+    }
+
+    pub fn set_commit_timeout(&mut self, commit_timeout: Duration) {
+        self.commit_timeout = commit_timeout
+    }
+
+    pub fn add_pending_change(
+        &mut self,
+        change: RegistryChangeProto,
+        commit_result_sender: Sender<CommitResult>,
+    ) {
+        self.pending_changes
+            .push_back((change, commit_result_sender))
+    }
+
+    pub fn take_pending_change(&mut self) -> Option<(RegistryChangeProto, Sender<CommitResult>)> {
+        self.pending_changes.pop_front()
+    }
+
+    pub fn add_proposed_change(&mut self, id: ProposalId, change: RegistryChangeProto) {
+        self.proposed_changes.insert(id, change);
+    }
+
+    pub fn get_proposed_change(&self, id: &ProposalId) -> Option<&RegistryChangeProto> {
+        self.proposed_changes.get(id)
+    }
+
+    pub fn remove_proposed_change(&mut self, id: &ProposalId) -> Option<RegistryChangeProto> {
+        self.proposed_changes.remove(id)
+    }
+
+    pub fn add_commit_waiter(
+        &mut self,
+        id: ProposalId,
+        commit_result_sender: Sender<CommitResult>,
+    ) {
+        self.commit_waiters
+            .entry(id)
+            .or_default()
+            .push(commit_result_sender)
+    }
+
+    /// Sends the given result to everyone waiting for the proposal to be committed.
+    pub fn notify_commit_waiters(&mut self, id: &ProposalId, result: CommitResult) {
+        for sender in self.commit_waiters.remove(id).unwrap_or_default() {
+            // The submitter may have stopped waiting, so a failed send is not an error
+            let _ = sender.send(result.clone());
+        }
+    }
+}
+
+impl ServicePeers for RegistryFederationShared {
+    fn network_sender(&self) -> Option<&dyn ServiceNetworkSender> {
+        self.network_sender.as_deref()
+    }
+
+    fn peer_services(&self) -> &HashSet<String> {
+        &self.peer_services
+    }
+}
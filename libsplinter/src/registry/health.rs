@@ -0,0 +1,983 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Health checks of the entries in a registry.
+//!
+//! A [`RegistryChecker`] checks a list of nodes for problems that the validation done when nodes
+//! are added to a registry can't catch:
+//!
+//! * endpoints that aren't valid for any of the supported transports
+//! * endpoints or keys that are listed by more than one node, e.g. by different sources of a
+//!   unified registry
+//! * endpoints that can't be connected to within the connect timeout, if the checker has a
+//!   [`Transport`]; endpoints are probed in parallel by a bounded pool of threads
+//! * stale nodes, which none of the endpoints have been reachable for since the stale period
+//!
+//! A [`RegistryValidator`] runs the checks on a registry in the background and keeps the latest
+//! [`HealthReport`].
+//!
+//! [`RegistryChecker`]: struct.RegistryChecker.html
+//! [`RegistryValidator`]: struct.RegistryValidator.html
+//! [`HealthReport`]: struct.HealthReport.html
+//! [`Transport`]: ../transport/trait.Transport.html
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+    Arc, Mutex,
+};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::transport::Transport;
+
+use super::{Node, RegistryError, RegistryReader};
+
+/// The default amount of time that none of a node's endpoints must be reachable for before the
+/// node is reported as stale.
+pub const DEFAULT_STALE_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The default amount of time that a connection to an endpoint may take before the endpoint is
+/// reported as unreachable.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A problem found with the entries of a registry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistryIssue {
+    /// The endpoint is not valid for any of the supported transports.
+    InvalidEndpoint {
+        identity: String,
+        endpoint: String,
+        reason: String,
+    },
+    /// The endpoint could not be connected to.
+    UnreachableEndpoint {
+        identity: String,
+        endpoint: String,
+        reason: String,
+    },
+    /// The endpoint is listed by more than one node.
+    DuplicateEndpoint {
+        endpoint: String,
+        identities: Vec<String>,
+    },
+    /// The key is listed by more than one node.
+    DuplicateKey {
+        key: String,
+        identities: Vec<String>,
+    },
+    /// None of the node's endpoints have been reachable since the given time.
+    StaleNode {
+        identity: String,
+        unreachable_since: SystemTime,
+    },
+}
+
+impl RegistryIssue {
+    /// Returns the identities of the nodes that the issue was found with.
+    pub fn identities(&self) -> Vec<&str> {
+        match self {
+            RegistryIssue::InvalidEndpoint { identity, .. }
+            | RegistryIssue::UnreachableEndpoint { identity, .. }
+            | RegistryIssue::StaleNode { identity, .. } => vec![identity],
+            RegistryIssue::DuplicateEndpoint { identities, .. }
+            | RegistryIssue::DuplicateKey { identities, .. } => {
+                identities.iter().map(String::as_str).collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for RegistryIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryIssue::InvalidEndpoint {
+                identity,
+                endpoint,
+                reason,
+            } => write!(
+                f,
+                "endpoint {} of node {} is invalid: {}",
+                endpoint, identity, reason
+            ),
+            RegistryIssue::UnreachableEndpoint {
+                identity,
+                endpoint,
+                reason,
+            } => write!(
+                f,
+                "endpoint {} of node {} is unreachable: {}",
+                endpoint, identity, reason
+            ),
+            RegistryIssue::DuplicateEndpoint {
+                endpoint,
+                identities,
+            } => write!(
+                f,
+                "endpoint {} is listed by nodes {}",
+                endpoint,
+                identities.join(", ")
+            ),
+            RegistryIssue::DuplicateKey { key, identities } => write!(
+                f,
+                "key {} is listed by nodes {}",
+                key,
+                identities.join(", ")
+            ),
+            RegistryIssue::StaleNode {
+                identity,
+                unreachable_since,
+            } => write!(
+                f,
+                "node {} has been unreachable for {}s",
+                identity,
+                unreachable_since
+                    .elapsed()
+                    .map(|elapsed| elapsed.as_secs())
+                    .unwrap_or(0)
+            ),
+        }
+    }
+}
+
+/// The result of checking the nodes of a registry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HealthReport {
+    /// When the check finished
+    pub checked_at: SystemTime,
+    /// The number of nodes that were checked
+    pub node_count: usize,
+    /// Whether the reachability of the endpoints was checked
+    pub probed: bool,
+    /// The problems found, with the duplicate endpoints and keys last
+    pub issues: Vec<RegistryIssue>,
+}
+
+impl HealthReport {
+    /// Returns `true` if no issues were found.
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks the nodes of a registry for invalid, duplicate, unreachable and stale entries.
+///
+/// The checker remembers since when each node has been unreachable, so a node is only reported as
+/// stale once it has been unreachable in the checks made over the stale period.
+pub struct RegistryChecker {
+    transports: Vec<Box<dyn Transport>>,
+    probe_pool: Option<ProbePool>,
+    connect_timeout: Duration,
+    stale_period: Duration,
+    unreachable_since: HashMap<String, SystemTime>,
+}
+
+impl RegistryChecker {
+    /// Creates a checker that only checks the syntax and uniqueness of the entries.
+    pub fn new() -> Self {
+        Self {
+            transports: vec![],
+            probe_pool: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            stale_period: DEFAULT_STALE_PERIOD,
+            unreachable_since: HashMap::new(),
+        }
+    }
+
+    /// Connects to the endpoints with the given transport to check that they are reachable, one
+    /// endpoint at a time. Endpoints that the transport doesn't accept are reported as
+    /// unreachable.
+    pub fn with_transport(self, transport: Box<dyn Transport>) -> Self {
+        self.with_transports(vec![transport])
+    }
+
+    /// Connects to the endpoints with the given transports to check that they are reachable.
+    /// Each transport probes one endpoint at a time on a thread of its own, so the number of
+    /// transports bounds the number of endpoints that are probed in parallel.
+    pub fn with_transports(mut self, transports: Vec<Box<dyn Transport>>) -> Self {
+        self.transports = transports;
+        self
+    }
+
+    /// Sets how long a connection to an endpoint may take before the endpoint is reported as
+    /// unreachable; defaults to [`DEFAULT_CONNECT_TIMEOUT`].
+    ///
+    /// [`DEFAULT_CONNECT_TIMEOUT`]: constant.DEFAULT_CONNECT_TIMEOUT.html
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Sets how long none of a node's endpoints must be reachable for before the node is reported
+    /// as stale; defaults to [`DEFAULT_STALE_PERIOD`].
+    ///
+    /// [`DEFAULT_STALE_PERIOD`]: constant.DEFAULT_STALE_PERIOD.html
+    pub fn with_stale_period(mut self, stale_period: Duration) -> Self {
+        self.stale_period = stale_period;
+        self
+    }
+
+    /// Checks the given nodes.
+    pub fn check(&mut self, nodes: &[Node]) -> HealthReport {
+        let mut issues = vec![];
+
+        // Endpoints and keys mapped to the nodes that list them; sorted for a stable report
+        let mut endpoint_identities: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        let mut key_identities: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+
+        // The syntactically valid endpoints of each node, which are probed together
+        let mut probed_endpoints: Vec<Vec<&str>> = vec![];
+
+        for node in nodes {
+            let mut valid_endpoints = vec![];
+
+            for endpoint in &node.endpoints {
+                endpoint_identities
+                    .entry(endpoint)
+                    .or_default()
+                    .insert(&node.identity);
+
+                match check_endpoint_syntax(endpoint) {
+                    Ok(()) => valid_endpoints.push(endpoint.as_str()),
+                    Err(reason) => issues.push(RegistryIssue::InvalidEndpoint {
+                        identity: node.identity.clone(),
+                        endpoint: endpoint.clone(),
+                        reason,
+                    }),
+                }
+            }
+
+            for key in &node.keys {
+                key_identities
+                    .entry(key)
+                    .or_default()
+                    .insert(&node.identity);
+            }
+
+            probed_endpoints.push(valid_endpoints);
+        }
+
+        let probe_results = self.probe(
+            probed_endpoints
+                .iter()
+                .flatten()
+                .map(|endpoint| endpoint.to_string())
+                .collect(),
+        );
+
+        if let Some(probe_results) = probe_results {
+            let mut probe_results = probe_results.into_iter();
+
+            for (node, endpoints) in nodes.iter().zip(probed_endpoints) {
+                // Nodes without valid endpoints can't be probed
+                if endpoints.is_empty() {
+                    continue;
+                }
+
+                let mut reachable = false;
+
+                for endpoint in endpoints {
+                    match probe_results.next() {
+                        Some(Ok(())) => reachable = true,
+                        Some(Err(reason)) => issues.push(RegistryIssue::UnreachableEndpoint {
+                            identity: node.identity.clone(),
+                            endpoint: endpoint.to_string(),
+                            reason,
+                        }),
+                        None => (),
+                    }
+                }
+
+                if reachable {
+                    self.unreachable_since.remove(&node.identity);
+                } else {
+                    let unreachable_since = *self
+                        .unreachable_since
+                        .entry(node.identity.clone())
+                        .or_insert_with(SystemTime::now);
+                    let stale = unreachable_since
+                        .elapsed()
+                        .map(|elapsed| elapsed >= self.stale_period)
+                        .unwrap_or(false);
+                    if stale {
+                        issues.push(RegistryIssue::StaleNode {
+                            identity: node.identity.clone(),
+                            unreachable_since,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Forget the nodes that have been removed from the registry
+        self.unreachable_since
+            .retain(|identity, _| nodes.iter().any(|node| &node.identity == identity));
+
+        issues.extend(
+            endpoint_identities
+                .into_iter()
+                .filter(|(_, identities)| identities.len() > 1)
+                .map(|(endpoint, identities)| RegistryIssue::DuplicateEndpoint {
+                    endpoint: endpoint.to_string(),
+                    identities: identities.into_iter().map(String::from).collect(),
+                }),
+        );
+        issues.extend(
+            key_identities
+                .into_iter()
+                .filter(|(_, identities)| identities.len() > 1)
+                .map(|(key, identities)| RegistryIssue::DuplicateKey {
+                    key: key.to_string(),
+                    identities: identities.into_iter().map(String::from).collect(),
+                }),
+        );
+
+        HealthReport {
+            checked_at: SystemTime::now(),
+            node_count: nodes.len(),
+            probed: self.probe_pool.is_some(),
+            issues,
+        }
+    }
+
+    /// Probes the given endpoints and returns the results in the same order, or `None` if the
+    /// checker has no transports. The probe threads are started on the first check.
+    fn probe(&mut self, endpoints: Vec<String>) -> Option<Vec<Result<(), String>>> {
+        if self.probe_pool.is_none() && !self.transports.is_empty() {
+            match ProbePool::start(self.transports.drain(..).collect()) {
+                Ok(pool) => self.probe_pool = Some(pool),
+                Err(err) => {
+                    error!("Failed to start registry probe threads: {}", err);
+                    return None;
+                }
+            }
+        }
+
+        let connect_timeout = self.connect_timeout;
+        self.probe_pool
+            .as_mut()
+            .map(|pool| pool.probe(endpoints, connect_timeout))
+    }
+}
+
+impl Default for RegistryChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks that the endpoint has the form `[<protocol>://]<host>:<port>`, where the protocol is
+/// one of the network transports that nodes can be reached with. Endpoints without a protocol use
+/// TCP.
+pub fn check_endpoint_syntax(endpoint: &str) -> Result<(), String> {
+    let (protocol, address) = match endpoint.find("://") {
+        Some(index) => (&endpoint[..index], &endpoint[index + 3..]),
+        None => ("tcp", endpoint),
+    };
+
+    match protocol {
+        "tcp" | "tcps" | "tls" => (),
+        #[cfg(feature = "ws-transport")]
+        "ws" => (),
+        _ => return Err(format!("unsupported protocol '{}'", protocol)),
+    }
+
+    // Web socket endpoints may have a path after the port
+    let address = address.splitn(2, '/').next().unwrap_or_default();
+
+    let mut parts = address.rsplitn(2, ':');
+    let port = parts.next().unwrap_or_default();
+    let host = parts
+        .next()
+        .ok_or_else(|| format!("missing port in '{}'", address))?;
+
+    if host.is_empty() {
+        return Err("missing host".into());
+    }
+    match port.parse::<u16>() {
+        Ok(port) if port != 0 => Ok(()),
+        _ => Err(format!("invalid port '{}'", port)),
+    }
+}
+
+/// Connects to the endpoint and disconnects again.
+fn probe_endpoint(transport: &mut dyn Transport, endpoint: &str) -> Result<(), String> {
+    if !transport.accepts(endpoint) {
+        return Err("no transport for the endpoint's protocol".into());
+    }
+    let mut connection = transport.connect(endpoint).map_err(|err| err.to_string())?;
+    if let Err(err) = connection.disconnect() {
+        debug!("Failed to disconnect from {}: {}", endpoint, err);
+    }
+    Ok(())
+}
+
+/// A probe of an endpoint that is passed to the probe threads.
+struct ProbeJob {
+    id: u64,
+    endpoint: String,
+}
+
+/// The progress of a probe, reported by the probe threads.
+enum ProbeEvent {
+    Started(u64),
+    Finished(u64, Result<(), String>),
+}
+
+/// A bounded pool of threads that probe endpoints, each with a transport of its own.
+///
+/// The threads stop once the pool is dropped and they have finished their current probe.
+struct ProbePool {
+    job_sender: Sender<ProbeJob>,
+    event_receiver: Receiver<ProbeEvent>,
+    next_id: u64,
+}
+
+impl ProbePool {
+    /// Starts a probe thread for each of the transports.
+    fn start(transports: Vec<Box<dyn Transport>>) -> Result<Self, std::io::Error> {
+        let (job_sender, job_receiver) = channel::<ProbeJob>();
+        let (event_sender, event_receiver) = channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        for (index, mut transport) in transports.into_iter().enumerate() {
+            let job_receiver = job_receiver.clone();
+            let event_sender = event_sender.clone();
+            thread::Builder::new()
+                .name(format!("Registry Probe {}", index))
+                .spawn(move || loop {
+                    let job = match job_receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => break,
+                    };
+                    let job = match job {
+                        Ok(job) => job,
+                        // The pool has been dropped
+                        Err(_) => break,
+                    };
+
+                    if event_sender.send(ProbeEvent::Started(job.id)).is_err() {
+                        break;
+                    }
+                    let result = probe_endpoint(&mut *transport, &job.endpoint);
+                    if event_sender
+                        .send(ProbeEvent::Finished(job.id, result))
+                        .is_err()
+                    {
+                        break;
+                    }
+                })?;
+        }
+
+        Ok(Self {
+            job_sender,
+            event_receiver,
+            next_id: 0,
+        })
+    }
+
+    /// Probes the given endpoints and returns the results in the same order. A probe that takes
+    /// longer than the connect timeout is reported as failed; its thread is free to probe again
+    /// once the connection attempt returns, and its late result is ignored.
+    fn probe(
+        &mut self,
+        endpoints: Vec<String>,
+        connect_timeout: Duration,
+    ) -> Vec<Result<(), String>> {
+        let first_id = self.next_id;
+        let count = endpoints.len();
+        self.next_id += count as u64;
+
+        let mut results: Vec<Option<Result<(), String>>> = vec![None; count];
+        for (index, endpoint) in endpoints.into_iter().enumerate() {
+            let job = ProbeJob {
+                id: first_id + index as u64,
+                endpoint,
+            };
+            if self.job_sender.send(job).is_err() {
+                results[index] = Some(Err("probe threads have stopped".into()));
+            }
+        }
+
+        // The index of each probe in progress, mapped to when it started
+        let mut started: HashMap<usize, Instant> = HashMap::new();
+        let mut remaining = results.iter().filter(|result| result.is_none()).count();
+
+        while remaining > 0 {
+            let now = Instant::now();
+            for (index, start) in &started {
+                if now.duration_since(*start) >= connect_timeout {
+                    results[*index] = Some(Err(format!(
+                        "connection timed out after {}ms",
+                        connect_timeout.as_millis()
+                    )));
+                    remaining -= 1;
+                }
+            }
+            started.retain(|index, _| results[*index].is_none());
+            if remaining == 0 {
+                break;
+            }
+
+            let wait = started
+                .values()
+                .map(|start| (*start + connect_timeout).saturating_duration_since(now))
+                .min()
+                .unwrap_or(connect_timeout);
+
+            // Events for probes of earlier checks, or for probes that have timed out, are ignored
+            let index_of = |id: u64| {
+                id.checked_sub(first_id)
+                    .map(|index| index as usize)
+                    .filter(|index| *index < count && results[*index].is_none())
+            };
+            match self.event_receiver.recv_timeout(wait) {
+                Ok(ProbeEvent::Started(id)) => {
+                    if let Some(index) = index_of(id) {
+                        started.insert(index, Instant::now());
+                    }
+                }
+                Ok(ProbeEvent::Finished(id, result)) => {
+                    if let Some(index) = index_of(id) {
+                        started.remove(&index);
+                        results[index] = Some(result);
+                        remaining -= 1;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err("probe threads have stopped".into())))
+            .collect()
+    }
+}
+
+/// Checks the nodes of a registry in the background and keeps the latest report.
+#[derive(Clone)]
+pub struct RegistryValidator {
+    report: Arc<Mutex<Option<HealthReport>>>,
+    running: Arc<AtomicBool>,
+    thread_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl RegistryValidator {
+    /// Starts a thread that checks the nodes of the registry with the checker every `interval`,
+    /// starting immediately.
+    pub fn start(
+        registry: Box<dyn RegistryReader>,
+        mut checker: RegistryChecker,
+        interval: Duration,
+    ) -> Result<Self, RegistryError> {
+        let report = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_report = report.clone();
+        let thread_running = running.clone();
+        let thread_handle = thread::Builder::new()
+            .name("Registry Validator".into())
+            .spawn(move || {
+                while thread_running.load(Ordering::SeqCst) {
+                    let next_check = Instant::now() + interval;
+
+                    match registry.list_nodes(&[]) {
+                        Ok(nodes) => {
+                            let nodes = nodes.collect::<Vec<_>>();
+                            let report = checker.check(&nodes);
+                            if report.is_healthy() {
+                                debug!("Registry check found no issues");
+                            } else {
+                                for issue in &report.issues {
+                                    warn!("Registry check: {}", issue);
+                                }
+                            }
+                            match thread_report.lock() {
+                                Ok(mut latest) => *latest = Some(report),
+                                Err(_) => {
+                                    error!("Registry validator report lock poisoned");
+                                    break;
+                                }
+                            }
+                        }
+                        Err(err) => error!("Failed to list nodes to check: {}", err),
+                    }
+
+                    // Wait for the next check, checking for shutdown every second
+                    while thread_running.load(Ordering::SeqCst) {
+                        match next_check.checked_duration_since(Instant::now()) {
+                            Some(time_left) => {
+                                thread::sleep(std::cmp::min(time_left, Duration::from_secs(1)))
+                            }
+                            None => break,
+                        }
+                    }
+                }
+            })
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    "Failed to spawn registry validator thread",
+                    Box::new(err),
+                )
+            })?;
+
+        Ok(Self {
+            report,
+            running,
+            thread_handle: Arc::new(Mutex::new(Some(thread_handle))),
+        })
+    }
+
+    /// Returns the report of the latest check, or `None` if the registry hasn't been checked yet.
+    pub fn report(&self) -> Result<Option<HealthReport>, RegistryError> {
+        Ok(self
+            .report
+            .lock()
+            .map_err(|_| RegistryError::general_error("Validator report lock poisoned"))?
+            .clone())
+    }
+
+    /// Stops checking the registry and waits for the check in progress, if any, to finish.
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+
+        let thread_handle = match self.thread_handle.lock() {
+            Ok(mut thread_handle) => thread_handle.take(),
+            Err(_) => {
+                error!("Registry validator thread handle lock poisoned");
+                None
+            }
+        };
+        if let Some(thread_handle) = thread_handle {
+            if thread_handle.join().is_err() {
+                error!("Registry validator thread panicked");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Condvar;
+
+    use crate::transport::{socket::TcpTransport, ConnectError, Connection, ListenError, Listener};
+
+    use super::super::{MetadataPredicate, NodeIter};
+
+    /// Verifies that endpoints are checked for a supported protocol, a host and a valid port.
+    #[test]
+    fn endpoint_syntax() {
+        assert!(check_endpoint_syntax("tcps://12.0.0.123:8431").is_ok());
+        assert!(check_endpoint_syntax("tcp://localhost:8431").is_ok());
+        assert!(check_endpoint_syntax("12.0.0.123:8431").is_ok());
+
+        assert!(check_endpoint_syntax("udp://12.0.0.123:8431").is_err());
+        assert!(check_endpoint_syntax("inproc://admin-service").is_err());
+        assert!(check_endpoint_syntax("tcps://12.0.0.123").is_err());
+        assert!(check_endpoint_syntax("tcps://:8431").is_err());
+        assert!(check_endpoint_syntax("tcps://12.0.0.123:99999").is_err());
+        assert!(check_endpoint_syntax("tcps://12.0.0.123:0").is_err());
+    }
+
+    /// Verifies that the checker reports invalid endpoints, and endpoints and keys that are listed
+    /// by more than one node.
+    #[test]
+    fn invalid_and_duplicate_entries() {
+        let nodes = vec![
+            node(
+                "Node-1",
+                &["tcps://12.0.0.123:8431", "udp://12.0.0.123:8431"],
+                "0123",
+            ),
+            node("Node-2", &["tcps://12.0.0.123:8431"], "0123"),
+            node("Node-3", &["tcps://12.0.0.123:8433"], "4567"),
+        ];
+
+        let report = RegistryChecker::new().check(&nodes);
+
+        assert_eq!(report.node_count, 3);
+        assert!(!report.probed);
+        assert_eq!(
+            report.issues,
+            vec![
+                RegistryIssue::InvalidEndpoint {
+                    identity: "Node-1".into(),
+                    endpoint: "udp://12.0.0.123:8431".into(),
+                    reason: "unsupported protocol 'udp'".into(),
+                },
+                RegistryIssue::DuplicateEndpoint {
+                    endpoint: "tcps://12.0.0.123:8431".into(),
+                    identities: vec!["Node-1".into(), "Node-2".into()],
+                },
+                RegistryIssue::DuplicateKey {
+                    key: "0123".into(),
+                    identities: vec!["Node-1".into(), "Node-2".into()],
+                },
+            ]
+        );
+    }
+
+    /// Verifies that a checker with a transport reports the endpoints it can't connect to, and
+    /// reports nodes that none of the endpoints are reachable for as stale once the stale period
+    /// has passed.
+    #[test]
+    fn unreachable_and_stale_nodes() {
+        let mut transport = TcpTransport::default();
+        let mut listener = transport
+            .listen("tcp://127.0.0.1:0")
+            .expect("Failed to listen");
+        let reachable_endpoint = listener.endpoint();
+        let accept_thread = thread::spawn(move || {
+            listener.accept().expect("Failed to accept connection");
+        });
+
+        // Find a port that nothing listens on
+        let unreachable_endpoint = transport
+            .listen("tcp://127.0.0.1:0")
+            .expect("Failed to listen")
+            .endpoint();
+
+        let nodes = vec![
+            node("Node-1", &[&reachable_endpoint], "0123"),
+            node("Node-2", &[&unreachable_endpoint], "4567"),
+        ];
+
+        let mut checker = RegistryChecker::new()
+            .with_transport(Box::new(TcpTransport::default()))
+            .with_stale_period(Duration::from_secs(0));
+        let report = checker.check(&nodes);
+        accept_thread.join().expect("Failed to join accept thread");
+
+        assert!(report.probed);
+        assert_eq!(report.issues.len(), 2);
+        match &report.issues[0] {
+            RegistryIssue::UnreachableEndpoint {
+                identity, endpoint, ..
+            } => {
+                assert_eq!(identity, "Node-2");
+                assert_eq!(endpoint, &unreachable_endpoint);
+            }
+            issue => panic!("Unexpected issue: {:?}", issue),
+        }
+        match &report.issues[1] {
+            RegistryIssue::StaleNode { identity, .. } => assert_eq!(identity, "Node-2"),
+            issue => panic!("Unexpected issue: {:?}", issue),
+        }
+    }
+
+    /// Verifies that an endpoint that takes longer than the connect timeout to connect to is
+    /// reported as unreachable without waiting for the connection attempt to finish.
+    #[test]
+    fn probe_timeout() {
+        let mut transport = TcpTransport::default();
+        let listener = transport
+            .listen("tcp://127.0.0.1:0")
+            .expect("Failed to listen");
+        let nodes = vec![node("Node-1", &[&listener.endpoint()], "0123")];
+
+        let mut checker = RegistryChecker::new()
+            .with_transport(Box::new(SlowTransport {
+                delay: Duration::from_secs(3),
+                inner: TcpTransport::default(),
+            }))
+            .with_connect_timeout(Duration::from_millis(200));
+
+        let start = Instant::now();
+        let report = checker.check(&nodes);
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        match &report.issues[0] {
+            RegistryIssue::UnreachableEndpoint { reason, .. } => {
+                assert!(
+                    reason.contains("timed out"),
+                    "Unexpected reason: {}",
+                    reason
+                )
+            }
+            issue => panic!("Unexpected issue: {:?}", issue),
+        }
+    }
+
+    /// Verifies that a checker with more than one transport probes endpoints in parallel, by
+    /// using transports that only connect once every transport is connecting at the same time.
+    #[test]
+    fn parallel_probes() {
+        let mut transport = TcpTransport::default();
+        let (endpoints, accept_threads): (Vec<_>, Vec<_>) = (0..2)
+            .map(|_| {
+                let mut listener = transport
+                    .listen("tcp://127.0.0.1:0")
+                    .expect("Failed to listen");
+                let endpoint = listener.endpoint();
+                let accept_thread = thread::spawn(move || {
+                    listener.accept().expect("Failed to accept connection");
+                });
+                (endpoint, accept_thread)
+            })
+            .unzip();
+        let nodes = vec![
+            node("Node-1", &[&endpoints[0]], "0123"),
+            node("Node-2", &[&endpoints[1]], "4567"),
+        ];
+
+        let connecting = Arc::new((Mutex::new(0), Condvar::new()));
+        let transports = (0..2)
+            .map(|_| {
+                Box::new(ConcurrentTransport {
+                    connecting: connecting.clone(),
+                    required: 2,
+                    inner: TcpTransport::default(),
+                }) as Box<dyn Transport>
+            })
+            .collect();
+
+        let report = RegistryChecker::new()
+            .with_transports(transports)
+            .with_connect_timeout(Duration::from_secs(2))
+            .check(&nodes);
+        for accept_thread in accept_threads {
+            accept_thread.join().expect("Failed to join accept thread");
+        }
+
+        assert!(report.probed);
+        assert!(
+            report.is_healthy(),
+            "Unexpected issues: {:?}",
+            report.issues
+        );
+    }
+
+    /// Verifies that shutting down a validator waits for its thread to exit, which drops the
+    /// validator's registry.
+    #[test]
+    fn validator_shutdown_joins_thread() {
+        let registry = MockRegistry(Arc::new(()));
+        let registry_ref = registry.0.clone();
+
+        let validator = RegistryValidator::start(
+            Box::new(registry),
+            RegistryChecker::new(),
+            Duration::from_secs(3600),
+        )
+        .expect("Failed to start validator");
+
+        let start = Instant::now();
+        while validator.report().expect("Failed to get report").is_none() {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        validator.shutdown();
+        assert_eq!(Arc::strong_count(&registry_ref), 1);
+    }
+
+    fn node(identity: &str, endpoints: &[&str], key: &str) -> Node {
+        Node::builder(identity)
+            .with_endpoints(
+                endpoints
+                    .iter()
+                    .map(|endpoint| endpoint.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .with_display_name(identity)
+            .with_key(key)
+            .build()
+            .expect("Failed to build node")
+    }
+
+    /// A transport that waits before connecting.
+    struct SlowTransport {
+        delay: Duration,
+        inner: TcpTransport,
+    }
+
+    impl Transport for SlowTransport {
+        fn accepts(&self, address: &str) -> bool {
+            self.inner.accepts(address)
+        }
+
+        fn connect(&mut self, endpoint: &str) -> Result<Box<dyn Connection>, ConnectError> {
+            thread::sleep(self.delay);
+            self.inner.connect(endpoint)
+        }
+
+        fn listen(&mut self, _bind: &str) -> Result<Box<dyn Listener>, ListenError> {
+            unimplemented!()
+        }
+    }
+
+    /// A transport that waits until `required` connections are being made at the same time
+    /// before connecting.
+    struct ConcurrentTransport {
+        connecting: Arc<(Mutex<usize>, Condvar)>,
+        required: usize,
+        inner: TcpTransport,
+    }
+
+    impl Transport for ConcurrentTransport {
+        fn accepts(&self, address: &str) -> bool {
+            self.inner.accepts(address)
+        }
+
+        fn connect(&mut self, endpoint: &str) -> Result<Box<dyn Connection>, ConnectError> {
+            let (lock, condvar) = &*self.connecting;
+            let mut connecting = lock.lock().expect("Connecting lock poisoned");
+            *connecting += 1;
+            condvar.notify_all();
+            let start = Instant::now();
+            while *connecting < self.required {
+                assert!(
+                    start.elapsed() < Duration::from_secs(5),
+                    "Probes not parallel"
+                );
+                connecting = condvar
+                    .wait_timeout(connecting, Duration::from_millis(100))
+                    .expect("Connecting lock poisoned")
+                    .0;
+            }
+            drop(connecting);
+            self.inner.connect(endpoint)
+        }
+
+        fn listen(&mut self, _bind: &str) -> Result<Box<dyn Listener>, ListenError> {
+            unimplemented!()
+        }
+    }
+
+    /// An empty registry that holds a reference, to check when it is dropped.
+    struct MockRegistry(Arc<()>);
+
+    impl RegistryReader for MockRegistry {
+        fn list_nodes<'a, 'b: 'a>(
+            &'b self,
+            _predicates: &'a [MetadataPredicate],
+        ) -> Result<NodeIter<'a>, RegistryError> {
+            Ok(Box::new(std::iter::empty()))
+        }
+
+        fn count_nodes(&self, _predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+            Ok(0)
+        }
+
+        fn fetch_node(&self, _identity: &str) -> Result<Option<Node>, RegistryError> {
+            Ok(None)
+        }
+    }
+}
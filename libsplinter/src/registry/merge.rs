@@ -0,0 +1,370 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Strategies for combining the definitions of a node that exists in more than one registry.
+//!
+//! The [`UnifiedRegistry`] uses a [`MergeStrategy`] to combine the definitions of a node from its
+//! sources, and can report where each field of a node came from ([`NodeProvenance`]) and which
+//! nodes have conflicting definitions ([`NodeConflict`]).
+//!
+//! [`UnifiedRegistry`]: struct.UnifiedRegistry.html
+//! [`MergeStrategy`]: enum.MergeStrategy.html
+//! [`NodeProvenance`]: struct.NodeProvenance.html
+//! [`NodeConflict`]: struct.NodeConflict.html
+
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+
+use super::{Node, RegistryError};
+
+/// The fields of a node that may conflict between registries.
+const ENDPOINTS_FIELD: &str = "endpoints";
+const KEYS_FIELD: &str = "keys";
+
+/// How the definitions of a node that exists in more than one registry are combined.
+///
+/// In all strategies, the node's display name is taken from the highest-precedence definition and
+/// its metadata is merged from all definitions, with the value for each key taken from the
+/// highest-precedence definition that sets it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeStrategy {
+    /// The endpoints and keys of the highest-precedence definition are used.
+    Priority,
+    /// The endpoints and keys of all definitions are combined, in order of precedence.
+    FieldMerge,
+    /// A node whose definitions have different endpoints or keys is left out of the registry until
+    /// the conflict is resolved.
+    RejectConflicts,
+}
+
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        MergeStrategy::Priority
+    }
+}
+
+impl FromStr for MergeStrategy {
+    type Err = RegistryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "priority" => Ok(MergeStrategy::Priority),
+            "field-merge" => Ok(MergeStrategy::FieldMerge),
+            "reject-conflicts" => Ok(MergeStrategy::RejectConflicts),
+            _ => Err(RegistryError::general_error(&format!(
+                "Invalid merge strategy '{}'; expected 'priority', 'field-merge' or \
+                 'reject-conflicts'",
+                s
+            ))),
+        }
+    }
+}
+
+/// The names of the registries that each field of a merged node came from.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NodeProvenance {
+    pub display_name: String,
+    /// The registries that contributed endpoints, in order of precedence
+    pub endpoints: Vec<String>,
+    /// The registries that contributed keys, in order of precedence
+    pub keys: Vec<String>,
+    /// The registry of each metadata key
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// A node whose definitions in different registries have different endpoints or keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeConflict {
+    pub identity: String,
+    /// The names of the conflicting fields
+    pub fields: Vec<String>,
+    /// The node's definitions with the name of the registry they came from, in order of precedence
+    pub definitions: Vec<(String, Node)>,
+}
+
+/// Combines the definitions of a node, given in descending order of precedence with the name of
+/// the registry that each came from. Returns `None` if there are no definitions or if the strategy
+/// rejects them.
+pub(super) fn merge_definitions(
+    strategy: MergeStrategy,
+    definitions: Vec<(&str, Node)>,
+) -> Option<(Node, NodeProvenance)> {
+    let mut definitions = definitions.into_iter();
+    let (source, mut node) = definitions.next()?;
+    let mut provenance = NodeProvenance {
+        display_name: source.to_string(),
+        endpoints: vec![source.to_string()],
+        keys: vec![source.to_string()],
+        metadata: node
+            .metadata
+            .keys()
+            .map(|key| (key.clone(), source.to_string()))
+            .collect(),
+    };
+
+    for (source, other) in definitions {
+        match strategy {
+            MergeStrategy::Priority => (),
+            MergeStrategy::FieldMerge => {
+                if merge_values(&mut node.endpoints, &other.endpoints) {
+                    provenance.endpoints.push(source.to_string());
+                }
+                if merge_values(&mut node.keys, &other.keys) {
+                    provenance.keys.push(source.to_string());
+                }
+            }
+            MergeStrategy::RejectConflicts => {
+                let fields = conflicting_fields(&node, &other);
+                if !fields.is_empty() {
+                    debug!(
+                        "Ignoring node {}; its {} differ between registries",
+                        node.identity,
+                        fields.join(" and ")
+                    );
+                    return None;
+                }
+            }
+        }
+
+        for (key, value) in other.metadata {
+            if !node.metadata.contains_key(&key) {
+                provenance.metadata.insert(key.clone(), source.to_string());
+                node.metadata.insert(key, value);
+            }
+        }
+    }
+
+    // A signature only covers the definition it was made for, so it is dropped if any field,
+    // including any metadata entry, came from another definition
+    #[cfg(feature = "registry-node-signing")]
+    {
+        let first_source = &provenance.display_name;
+        if provenance.endpoints.len() > 1
+            || provenance.keys.len() > 1
+            || provenance
+                .metadata
+                .values()
+                .any(|source| source != first_source)
+        {
+            node.signature = None;
+        }
+    }
+
+    Some((node, provenance))
+}
+
+/// Returns the conflict between the definitions of a node, given in descending order of
+/// precedence with the name of the registry that each came from, if there is one.
+pub(super) fn find_conflict(definitions: Vec<(&str, Node)>) -> Option<NodeConflict> {
+    let (_, first) = definitions.first()?;
+    let mut fields = definitions[1..]
+        .iter()
+        .flat_map(|(_, other)| conflicting_fields(first, other))
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    fields.sort();
+    fields.dedup();
+
+    Some(NodeConflict {
+        identity: first.identity.clone(),
+        fields: fields.into_iter().map(String::from).collect(),
+        definitions: definitions
+            .into_iter()
+            .map(|(source, node)| (source.to_string(), node))
+            .collect(),
+    })
+}
+
+/// Returns the fields of two definitions of a node that have different values. Endpoints and keys
+/// are compared regardless of order.
+fn conflicting_fields(node: &Node, other: &Node) -> Vec<&'static str> {
+    let mut fields = vec![];
+    if !same_values(&node.endpoints, &other.endpoints) {
+        fields.push(ENDPOINTS_FIELD);
+    }
+    if !same_values(&node.keys, &other.keys) {
+        fields.push(KEYS_FIELD);
+    }
+    fields
+}
+
+fn same_values(values: &[String], other: &[String]) -> bool {
+    values.iter().collect::<HashSet<_>>() == other.iter().collect::<HashSet<_>>()
+}
+
+/// Appends the values that are not already present; returns whether any value was added.
+fn merge_values(values: &mut Vec<String>, other: &[String]) -> bool {
+    let mut added = false;
+    for value in other {
+        if !values.contains(value) {
+            values.push(value.clone());
+            added = true;
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(endpoints: &[&str], keys: &[&str], metadata: &[(&str, &str)]) -> Node {
+        let mut builder = Node::builder("node1")
+            .with_endpoints(
+                endpoints
+                    .iter()
+                    .map(|endpoint| endpoint.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .with_keys(keys.iter().map(|key| key.to_string()).collect::<Vec<_>>());
+        for (key, value) in metadata {
+            builder = builder.with_metadata(*key, *value);
+        }
+        builder.build().expect("Failed to build node")
+    }
+
+    /// Verify how each strategy combines two definitions with different endpoints and keys, and
+    /// that the provenance of each field is reported.
+    #[test]
+    fn merge_strategies() {
+        let high = node(
+            &["tcps://high:8044"],
+            &["abcd"],
+            &[("company", "Bitwise IO")],
+        );
+        let low = node(
+            &["tcps://low:8044"],
+            &["abcd", "0123"],
+            &[("company", "Cargill"), ("admin", "Carol")],
+        );
+        let definitions = || vec![("internal", high.clone()), ("remote", low.clone())];
+
+        let (merged, provenance) = merge_definitions(MergeStrategy::Priority, definitions())
+            .expect("Priority merge rejected the node");
+        assert_eq!(merged.endpoints, vec!["tcps://high:8044".to_string()]);
+        assert_eq!(merged.keys, vec!["abcd".to_string()]);
+        assert_eq!(
+            merged.metadata.get("company").map(String::as_str),
+            Some("Bitwise IO")
+        );
+        assert_eq!(
+            merged.metadata.get("admin").map(String::as_str),
+            Some("Carol")
+        );
+        assert_eq!(provenance.endpoints, vec!["internal".to_string()]);
+        assert_eq!(
+            provenance.metadata.get("admin").map(String::as_str),
+            Some("remote")
+        );
+
+        let (merged, provenance) = merge_definitions(MergeStrategy::FieldMerge, definitions())
+            .expect("Field merge rejected the node");
+        assert_eq!(
+            merged.endpoints,
+            vec![
+                "tcps://high:8044".to_string(),
+                "tcps://low:8044".to_string()
+            ]
+        );
+        assert_eq!(merged.keys, vec!["abcd".to_string(), "0123".to_string()]);
+        assert_eq!(
+            provenance.keys,
+            vec!["internal".to_string(), "remote".to_string()]
+        );
+
+        assert!(merge_definitions(MergeStrategy::RejectConflicts, definitions()).is_none());
+        assert!(merge_definitions(
+            MergeStrategy::RejectConflicts,
+            vec![("internal", high.clone()), ("remote", high.clone())]
+        )
+        .is_some());
+    }
+
+    /// Verify that the signature of the first definition is only kept if every field of the merged
+    /// node came from that definition.
+    #[cfg(feature = "registry-node-signing")]
+    #[test]
+    fn merge_signed_definition() {
+        use crate::registry::NodeSignature;
+
+        let mut signed = node(
+            &["tcps://high:8044"],
+            &["abcd"],
+            &[("company", "Bitwise IO")],
+        );
+        signed.signature = Some(NodeSignature {
+            public_key: "abcd".into(),
+            signature: "0123".into(),
+            version: 1,
+        });
+        let same_metadata = node(&["tcps://low:8044"], &["abcd"], &[("company", "Cargill")]);
+        let extra_metadata = node(&["tcps://high:8044"], &["abcd"], &[("admin", "Carol")]);
+
+        let (merged, _) = merge_definitions(
+            MergeStrategy::Priority,
+            vec![("internal", signed.clone()), ("remote", same_metadata)],
+        )
+        .expect("Priority merge rejected the node");
+        assert_eq!(merged, signed);
+
+        let (merged, _) = merge_definitions(
+            MergeStrategy::Priority,
+            vec![("internal", signed), ("remote", extra_metadata)],
+        )
+        .expect("Priority merge rejected the node");
+        assert_eq!(merged.signature, None);
+        assert_eq!(
+            merged.metadata.get("admin").map(String::as_str),
+            Some("Carol")
+        );
+    }
+
+    /// Verify that a conflict is reported with the conflicting fields, and that definitions that
+    /// only differ in order or metadata do not conflict.
+    #[test]
+    fn conflicts() {
+        let node1 = node(&["tcps://a:8044", "tcps://b:8044"], &["abcd"], &[]);
+        let node1_reordered = node(
+            &["tcps://b:8044", "tcps://a:8044"],
+            &["abcd"],
+            &[("company", "Cargill")],
+        );
+        let node1_rekeyed = node(&["tcps://a:8044", "tcps://b:8044"], &["0123"], &[]);
+
+        assert!(find_conflict(vec![
+            ("internal", node1.clone()),
+            ("remote", node1_reordered.clone())
+        ])
+        .is_none());
+
+        let conflict = find_conflict(vec![
+            ("internal", node1),
+            ("remote", node1_reordered),
+            ("other", node1_rekeyed),
+        ])
+        .expect("No conflict found");
+        assert_eq!(conflict.identity, "node1");
+        assert_eq!(conflict.fields, vec![KEYS_FIELD.to_string()]);
+        assert_eq!(
+            conflict
+                .definitions
+                .iter()
+                .map(|(source, _)| source.as_str())
+                .collect::<Vec<_>>(),
+            vec!["internal", "remote", "other"]
+        );
+    }
+}
@@ -24,7 +24,21 @@
 //! [`RegistryWriter`]: trait.RegistryWriter.html
 //! [`RwRegistry`]: trait.RwRegistry.html
 
+#[cfg(all(feature = "registry-database", feature = "diesel"))]
+mod diesel;
 mod error;
+#[cfg(feature = "registry-events")]
+mod events;
+#[cfg(feature = "registry-federation")]
+mod federation;
+#[cfg(feature = "registry-health")]
+mod health;
+#[cfg(feature = "registry-merge")]
+mod merge;
+#[cfg(all(feature = "registry-database", feature = "diesel"))]
+pub mod migrations;
+#[cfg(feature = "registry-node-signing")]
+mod node_signature;
 #[cfg(feature = "rest-api")]
 mod rest_api;
 mod unified;
@@ -33,11 +47,32 @@ mod yaml;
 use std::collections::HashMap;
 use std::iter::ExactSizeIterator;
 
+#[cfg(all(feature = "registry-database", feature = "diesel"))]
+pub use self::diesel::DieselRegistry;
 pub use error::{InvalidNodeError, RegistryError};
+#[cfg(feature = "registry-events")]
+pub use events::{RegistryEvent, RegistryEventSubscriber, RegistrySubscriberError};
+#[cfg(feature = "registry-federation")]
+pub use federation::{FederatedRegistry, RegistryServiceFactory, REGISTRY_SERVICE_TYPE};
+#[cfg(feature = "registry-health")]
+pub use health::{
+    check_endpoint_syntax, HealthReport, RegistryChecker, RegistryIssue, RegistryValidator,
+    DEFAULT_STALE_PERIOD,
+};
+#[cfg(feature = "registry-merge")]
+pub use merge::{MergeStrategy, NodeConflict, NodeProvenance};
+#[cfg(feature = "registry-node-signing")]
+pub use node_signature::NodeSignature;
+#[cfg(all(feature = "audit", feature = "rest-api"))]
+pub use rest_api::AuditedRegistryResourceProvider;
 pub use unified::UnifiedRegistry;
 pub use yaml::LocalYamlRegistry;
+#[cfg(feature = "registry-remote-auth")]
+pub use yaml::RemoteRegistryConfig;
+#[cfg(feature = "registry-signing")]
+pub use yaml::{read_public_key_file, RegistrySignature, SIGNATURE_FILE_SUFFIX};
 #[cfg(feature = "registry-remote")]
-pub use yaml::{RemoteYamlRegistry, RemoteYamlShutdownHandle};
+pub use yaml::{RemoteYamlRegistry, RemoteYamlRegistryBuilder, RemoteYamlShutdownHandle};
 
 /// Native representation of a node in a registry.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -54,6 +89,10 @@ pub struct Node {
     pub keys: Vec<String>,
     /// A map with node metadata.
     pub metadata: HashMap<String, String>,
+    /// The node's signature of its own entry, if the entry is self-signed.
+    #[cfg(feature = "registry-node-signing")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<NodeSignature>,
 }
 
 impl Node {
@@ -138,6 +177,8 @@ impl NodeBuilder {
             display_name,
             keys: self.keys,
             metadata: self.metadata,
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
 
         check_node_required_fields_are_not_empty(&node)?;
@@ -153,8 +194,12 @@ impl NodeBuilder {
 /// against the predicate's value (the second item in the tuple).
 ///
 /// If the item is missing in a node's metadata table, the predicate returns false (with the
-/// exception of the `Ne` variant).
-#[derive(Clone)]
+/// exception of the `Ne` and `Not` variants).
+///
+/// The `Eq`, `Ne`, `Gt`, `Ge`, `Lt` and `Le` variants compare the values as strings. The `Num*`
+/// variants parse the metadata value as a number and compare it numerically; a node whose value
+/// is not a number does not match.
+#[derive(Clone, Debug)]
 pub enum MetadataPredicate {
     /// Applies the `==` operator.
     Eq(String, String),
@@ -168,6 +213,45 @@ pub enum MetadataPredicate {
     Lt(String, String),
     /// Applies the `<=` operator.
     Le(String, String),
+    /// Matches if the value is equal to any of the given values.
+    #[cfg(feature = "registry-query")]
+    In(String, Vec<String>),
+    /// Matches if the value starts with the given prefix.
+    #[cfg(feature = "registry-query")]
+    StartsWith(String, String),
+    /// Matches if the value contains the given string.
+    #[cfg(feature = "registry-query")]
+    Contains(String, String),
+    /// Matches if the key is present in the node's metadata table.
+    #[cfg(feature = "registry-query")]
+    Exists(String),
+    /// Applies the `==` operator to the value as a number.
+    #[cfg(feature = "registry-query")]
+    NumEq(String, f64),
+    /// Applies the `>` operator to the value as a number.
+    #[cfg(feature = "registry-query")]
+    NumGt(String, f64),
+    /// Applies the `>=` operator to the value as a number.
+    #[cfg(feature = "registry-query")]
+    NumGe(String, f64),
+    /// Applies the `<` operator to the value as a number.
+    #[cfg(feature = "registry-query")]
+    NumLt(String, f64),
+    /// Applies the `<=` operator to the value as a number.
+    #[cfg(feature = "registry-query")]
+    NumLe(String, f64),
+    /// Applies a string match to one of the node's fields, rather than to its metadata.
+    #[cfg(feature = "registry-query")]
+    Field(NodeField, FieldMatch),
+    /// Matches if all of the predicates match; an empty list matches every node.
+    #[cfg(feature = "registry-query")]
+    And(Vec<MetadataPredicate>),
+    /// Matches if any of the predicates match; an empty list matches no nodes.
+    #[cfg(feature = "registry-query")]
+    Or(Vec<MetadataPredicate>),
+    /// Matches if the predicate does not match.
+    #[cfg(feature = "registry-query")]
+    Not(Box<MetadataPredicate>),
 }
 
 impl MetadataPredicate {
@@ -193,6 +277,66 @@ impl MetadataPredicate {
             MetadataPredicate::Le(key, val) => {
                 node.metadata.get(key).map(|v| v <= val).unwrap_or(false)
             }
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::In(key, vals) => node
+                .metadata
+                .get(key)
+                .map(|v| vals.contains(v))
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::StartsWith(key, prefix) => node
+                .metadata
+                .get(key)
+                .map(|v| v.starts_with(prefix.as_str()))
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::Contains(key, val) => node
+                .metadata
+                .get(key)
+                .map(|v| v.contains(val.as_str()))
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::Exists(key) => node.metadata.contains_key(key),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::NumEq(key, val) => numeric_metadata(node, key)
+                .map(|v| v == *val)
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::NumGt(key, val) => numeric_metadata(node, key)
+                .map(|v| v > *val)
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::NumGe(key, val) => numeric_metadata(node, key)
+                .map(|v| v >= *val)
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::NumLt(key, val) => numeric_metadata(node, key)
+                .map(|v| v < *val)
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::NumLe(key, val) => numeric_metadata(node, key)
+                .map(|v| v <= *val)
+                .unwrap_or(false),
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::Field(field, field_match) => match field {
+                NodeField::Identity => field_match.matches(&node.identity),
+                NodeField::DisplayName => field_match.matches(&node.display_name),
+                NodeField::Endpoints => node
+                    .endpoints
+                    .iter()
+                    .any(|endpoint| field_match.matches(endpoint)),
+                NodeField::Keys => node.keys.iter().any(|key| field_match.matches(key)),
+            },
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::And(predicates) => {
+                predicates.iter().all(|predicate| predicate.apply(node))
+            }
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::Or(predicates) => {
+                predicates.iter().any(|predicate| predicate.apply(node))
+            }
+            #[cfg(feature = "registry-query")]
+            MetadataPredicate::Not(predicate) => !predicate.apply(node),
         }
     }
 
@@ -205,6 +349,77 @@ impl MetadataPredicate {
     pub fn ne<S: Into<String>>(key: S, value: S) -> MetadataPredicate {
         MetadataPredicate::Ne(key.into(), value.into())
     }
+
+    /// Returns the `In` predicate for the given key and values
+    #[cfg(feature = "registry-query")]
+    pub fn is_in<S: Into<String>>(key: S, values: Vec<S>) -> MetadataPredicate {
+        MetadataPredicate::In(key.into(), values.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the `Exists` predicate for the given key
+    #[cfg(feature = "registry-query")]
+    pub fn exists<S: Into<String>>(key: S) -> MetadataPredicate {
+        MetadataPredicate::Exists(key.into())
+    }
+
+    /// Returns the `Not` predicate for the given predicate
+    #[cfg(feature = "registry-query")]
+    pub fn not(predicate: MetadataPredicate) -> MetadataPredicate {
+        MetadataPredicate::Not(Box::new(predicate))
+    }
+}
+
+/// Parses the metadata value at the given key as a number, if it is present and numeric.
+#[cfg(feature = "registry-query")]
+fn numeric_metadata(node: &Node, key: &str) -> Option<f64> {
+    node.metadata
+        .get(key)
+        .and_then(|value| value.trim().parse::<f64>().ok())
+}
+
+/// A node field, other than the metadata table, that a `MetadataPredicate::Field` predicate
+/// matches against.
+///
+/// The `Endpoints` and `Keys` fields are lists; a predicate on one of them matches if any entry
+/// in the list matches.
+#[cfg(feature = "registry-query")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeField {
+    /// The node's identity.
+    Identity,
+    /// The node's display name.
+    DisplayName,
+    /// The node's endpoints.
+    Endpoints,
+    /// The node's public keys.
+    Keys,
+}
+
+/// A string match applied to a node field by a `MetadataPredicate::Field` predicate.
+#[cfg(feature = "registry-query")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldMatch {
+    /// Matches if the field is equal to the given value.
+    Eq(String),
+    /// Matches if the field is equal to any of the given values.
+    In(Vec<String>),
+    /// Matches if the field starts with the given prefix.
+    StartsWith(String),
+    /// Matches if the field contains the given string.
+    Contains(String),
+}
+
+#[cfg(feature = "registry-query")]
+impl FieldMatch {
+    /// Determines whether or not the given field value matches.
+    pub fn matches(&self, field: &str) -> bool {
+        match self {
+            FieldMatch::Eq(value) => field == value,
+            FieldMatch::In(values) => values.iter().any(|value| field == value),
+            FieldMatch::StartsWith(prefix) => field.starts_with(prefix.as_str()),
+            FieldMatch::Contains(value) => field.contains(value.as_str()),
+        }
+    }
 }
 
 /// Type returned by the `RegistryReader::list_nodes` method
@@ -248,6 +463,46 @@ pub trait RegistryReader: Send + Sync {
     fn has_node(&self, identity: &str) -> Result<bool, RegistryError> {
         self.fetch_node(identity).map(|opt| opt.is_some())
     }
+
+    /// Adds a subscriber that will be sent a `RegistryEvent` each time a node is added to,
+    /// updated in or removed from the registry. Returns an error if the registry does not support
+    /// subscriptions.
+    ///
+    /// # Arguments
+    ///
+    ///  * `subscriber` - The subscriber that will receive the registry's events.
+    #[cfg(feature = "registry-events")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        let _ = subscriber;
+        Err(RegistryError::general_error(
+            "Registry does not support event subscriptions",
+        ))
+    }
+
+    /// Returns the names of the source registries that each field of the node with the given
+    /// identity came from. Registries that do not combine multiple sources return `None`.
+    ///
+    /// # Arguments
+    ///
+    ///  * `identity` - The identity of the node.
+    #[cfg(feature = "registry-merge")]
+    fn fetch_node_provenance(
+        &self,
+        identity: &str,
+    ) -> Result<Option<NodeProvenance>, RegistryError> {
+        let _ = identity;
+        Ok(None)
+    }
+
+    /// Returns the nodes whose definitions in the registry's sources have conflicting endpoints
+    /// or keys. Registries that do not combine multiple sources have no conflicts.
+    #[cfg(feature = "registry-merge")]
+    fn list_conflicts(&self) -> Result<Vec<NodeConflict>, RegistryError> {
+        Ok(vec![])
+    }
 }
 
 /// Defines registry write capabilities.
@@ -266,6 +521,63 @@ pub trait RegistryWriter: Send + Sync {
     ///
     ///  * `identity` - The Splinter identity of the node.
     fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError>;
+
+    /// Deletes a node with the given identity, along with the `removal` signature that authorizes
+    /// deleting a self-signed node (see [`NodeSignature::sign_removal`]), and returns the node if
+    /// it was in the registry.
+    ///
+    /// Registries that replicate their changes pass the removal on, so that every copy of the
+    /// registry can check it. By default, the removal is not checked and the node is deleted as
+    /// it is by `delete_node`.
+    ///
+    /// # Arguments
+    ///
+    ///  * `identity` - The Splinter identity of the node.
+    ///  * `removal` - The signed removal of the node, if any.
+    ///
+    /// [`NodeSignature::sign_removal`]: struct.NodeSignature.html#method.sign_removal
+    #[cfg(feature = "registry-node-signing")]
+    fn delete_node_with_removal(
+        &self,
+        identity: &str,
+        removal: Option<&NodeSignature>,
+    ) -> Result<Option<Node>, RegistryError> {
+        let _ = removal;
+        self.delete_node(identity)
+    }
+
+    /// Adds the given nodes to the registry, replacing any existing nodes with the same
+    /// identities. The nodes are inserted as a single transaction: if any node is invalid or
+    /// cannot be inserted, none of the nodes are inserted.
+    ///
+    /// Returns an error if the registry does not support batch changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The nodes to be added to or updated in the registry.
+    #[cfg(feature = "registry-bulk")]
+    fn insert_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError> {
+        let _ = nodes;
+        Err(RegistryError::general_error(
+            "Registry does not support batch changes",
+        ))
+    }
+
+    /// Deletes the nodes with the given identities as a single transaction and returns the nodes
+    /// that were in the registry. Identities that are not in the registry are ignored.
+    ///
+    /// Returns an error if the registry does not support batch changes.
+    ///
+    /// # Arguments
+    ///
+    ///  * `identities` - The Splinter identities of the nodes.
+    #[cfg(feature = "registry-bulk")]
+    fn delete_nodes(&self, identities: &[String]) -> Result<Vec<Node>, RegistryError> {
+        let _ = identities;
+        Err(RegistryError::general_error(
+            "Registry does not support batch changes",
+        ))
+    }
 }
 
 /// Provides a marker trait for a clonable, readable and writable registry.
@@ -316,6 +628,27 @@ where
     fn has_node(&self, identity: &str) -> Result<bool, RegistryError> {
         (**self).has_node(identity)
     }
+
+    #[cfg(feature = "registry-events")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        (**self).add_event_subscriber(subscriber)
+    }
+
+    #[cfg(feature = "registry-merge")]
+    fn fetch_node_provenance(
+        &self,
+        identity: &str,
+    ) -> Result<Option<NodeProvenance>, RegistryError> {
+        (**self).fetch_node_provenance(identity)
+    }
+
+    #[cfg(feature = "registry-merge")]
+    fn list_conflicts(&self) -> Result<Vec<NodeConflict>, RegistryError> {
+        (**self).list_conflicts()
+    }
 }
 
 impl<NW> RegistryWriter for Box<NW>
@@ -329,6 +662,25 @@ where
     fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
         (**self).delete_node(identity)
     }
+
+    #[cfg(feature = "registry-node-signing")]
+    fn delete_node_with_removal(
+        &self,
+        identity: &str,
+        removal: Option<&NodeSignature>,
+    ) -> Result<Option<Node>, RegistryError> {
+        (**self).delete_node_with_removal(identity, removal)
+    }
+
+    #[cfg(feature = "registry-bulk")]
+    fn insert_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError> {
+        (**self).insert_nodes(nodes)
+    }
+
+    #[cfg(feature = "registry-bulk")]
+    fn delete_nodes(&self, identities: &[String]) -> Result<Vec<Node>, RegistryError> {
+        (**self).delete_nodes(identities)
+    }
 }
 
 /// Returns `Err` if not all `nodes` are valid.
@@ -336,6 +688,12 @@ fn validate_nodes(nodes: &[Node]) -> Result<(), InvalidNodeError> {
     for (idx, node) in nodes.iter().enumerate() {
         check_node_required_fields_are_not_empty(node)?;
         check_if_node_is_duplicate(node, &nodes[idx + 1..])?;
+        #[cfg(feature = "registry-node-signing")]
+        {
+            if node.signature.is_some() {
+                node.verify_signature()?;
+            }
+        }
     }
     Ok(())
 }
@@ -562,6 +920,62 @@ mod tests {
         assert!(!MetadataPredicate::Le("key".into(), "4".into()).apply(&node));
     }
 
+    /// Verify that the `MetadataPredicate::apply` method properly applies the set, string,
+    /// numeric, field and grouping predicates.
+    #[cfg(feature = "registry-query")]
+    #[test]
+    fn query_predicates() {
+        let node = Node::builder("identity")
+            .with_endpoint("tcps://localhost:8044")
+            .with_display_name("Node 1")
+            .with_key("key")
+            .with_metadata("region", "eu".into())
+            .with_metadata("tier", "10".into())
+            .build()
+            .expect("Failed to build node");
+
+        assert!(MetadataPredicate::is_in("region", vec!["eu", "uk"]).apply(&node));
+        assert!(!MetadataPredicate::is_in("region", vec!["us"]).apply(&node));
+        assert!(!MetadataPredicate::is_in("missing", vec!["eu"]).apply(&node));
+
+        assert!(MetadataPredicate::StartsWith("region".into(), "e".into()).apply(&node));
+        assert!(!MetadataPredicate::StartsWith("region".into(), "u".into()).apply(&node));
+        assert!(MetadataPredicate::Contains("region".into(), "u".into()).apply(&node));
+
+        assert!(MetadataPredicate::exists("tier").apply(&node));
+        assert!(!MetadataPredicate::exists("missing").apply(&node));
+
+        // "10" is less than "2" as a string, but not as a number
+        assert!(!MetadataPredicate::Ge("tier".into(), "2".into()).apply(&node));
+        assert!(MetadataPredicate::NumGe("tier".into(), 2.0).apply(&node));
+        assert!(MetadataPredicate::NumEq("tier".into(), 10.0).apply(&node));
+        assert!(!MetadataPredicate::NumLt("tier".into(), 10.0).apply(&node));
+        assert!(!MetadataPredicate::NumGt("region".into(), 0.0).apply(&node));
+
+        assert!(
+            MetadataPredicate::Field(NodeField::DisplayName, FieldMatch::Eq("Node 1".into()))
+                .apply(&node)
+        );
+        assert!(MetadataPredicate::Field(
+            NodeField::Endpoints,
+            FieldMatch::StartsWith("tcps://".into())
+        )
+        .apply(&node));
+        assert!(
+            !MetadataPredicate::Field(NodeField::Keys, FieldMatch::In(vec!["other".into()]))
+                .apply(&node)
+        );
+
+        assert!(MetadataPredicate::And(vec![]).apply(&node));
+        assert!(!MetadataPredicate::Or(vec![]).apply(&node));
+        assert!(MetadataPredicate::Or(vec![
+            MetadataPredicate::eq("region", "us"),
+            MetadataPredicate::NumGe("tier".into(), 2.0),
+        ])
+        .apply(&node));
+        assert!(MetadataPredicate::not(MetadataPredicate::exists("missing")).apply(&node));
+    }
+
     /// Verify that the `validate_nodes` method properly validates nodes based on the following
     /// criteria:
     ///
@@ -592,6 +1006,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec!["key3".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), empty_identity]) {
             Err(InvalidNodeError::EmptyIdentity) => {}
@@ -607,6 +1023,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec!["key3".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), missing_endpoints]) {
             Err(InvalidNodeError::MissingEndpoints) => {}
@@ -622,6 +1040,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec!["key3".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), empty_endpoint]) {
             Err(InvalidNodeError::EmptyEndpoint) => {}
@@ -637,6 +1057,8 @@ mod tests {
             display_name: "".into(),
             keys: vec!["key3".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), empty_display_name]) {
             Err(InvalidNodeError::EmptyDisplayName) => {}
@@ -652,6 +1074,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec![],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), missing_keys]) {
             Err(InvalidNodeError::MissingKeys) => {}
@@ -667,6 +1091,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec!["".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), empty_key]) {
             Err(InvalidNodeError::EmptyKey) => {}
@@ -682,6 +1108,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec!["key3".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), duplicate_identity]) {
             Err(InvalidNodeError::DuplicateIdentity(id)) if &id == "identity1" => {}
@@ -697,6 +1125,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec!["key3".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         match validate_nodes(&[node1.clone(), node2.clone(), duplicate_endpoint]) {
             Err(InvalidNodeError::DuplicateEndpoint(endpoint)) if &endpoint == "endpoint1" => {}
@@ -712,6 +1142,8 @@ mod tests {
             display_name: "display name".into(),
             keys: vec!["key3".into()],
             metadata: HashMap::new(),
+            #[cfg(feature = "registry-node-signing")]
+            signature: None,
         };
         assert!(validate_nodes(&[node1, node2, valid_node3]).is_ok());
     }
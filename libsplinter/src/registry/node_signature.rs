@@ -0,0 +1,415 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signed node records.
+//!
+//! A node signs its own registry entry with one of the keys listed in the entry. The signature
+//! covers the node's identity, endpoints, display name, keys and metadata, so a registry can
+//! check that an entry was authored by the holder of one of the node's keys.
+//!
+//! Each signature also covers a version, such as a timestamp, chosen by the node. A self-signed
+//! entry can only be replaced or removed with a signature that has a higher version, so an older
+//! signed entry or removal cannot be replayed.
+
+use std::collections::BTreeMap;
+
+use crate::hex::{parse_hex, to_hex};
+use crate::signing::{sawtooth::SawtoothSecp256k1SignatureVerifier, SignatureVerifier, Signer};
+
+use super::{InvalidNodeError, Node, RegistryError};
+
+/// The signature of a node's registry entry.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NodeSignature {
+    /// The hex-encoded public key that made the signature; must be one of the node's keys.
+    pub public_key: String,
+    /// The hex-encoded signature of the node's entry.
+    pub signature: String,
+    /// The version of the signed entry; it must increase each time the entry is replaced.
+    pub version: u64,
+}
+
+impl NodeSignature {
+    /// Signs the removal of the node with the given `identity` from a registry. A self-signed node
+    /// can only be removed with a removal signed by one of its keys, with a `version` higher than
+    /// that of the node's current entry.
+    pub fn sign_removal(
+        identity: &str,
+        version: u64,
+        signer: &dyn Signer,
+    ) -> Result<Self, RegistryError> {
+        let signature = signer
+            .sign(&removal_bytes(identity, version)?)
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to sign removal of node {}", identity),
+                    Box::new(err),
+                )
+            })?;
+
+        Ok(Self {
+            public_key: to_hex(signer.public_key()),
+            signature: to_hex(&signature),
+            version,
+        })
+    }
+}
+
+/// The fields of a node that are covered by its signature. The metadata is ordered by key so
+/// that the serialized bytes are deterministic.
+#[derive(Serialize)]
+struct SignedNodeFields<'a> {
+    identity: &'a str,
+    endpoints: &'a [String],
+    display_name: &'a str,
+    keys: &'a [String],
+    metadata: BTreeMap<&'a str, &'a str>,
+    version: u64,
+}
+
+/// The fields covered by the signature of a node's removal.
+#[derive(Serialize)]
+struct SignedNodeRemoval<'a> {
+    removed_identity: &'a str,
+    version: u64,
+}
+
+impl Node {
+    /// Signs the node's entry and `version` with the given `signer`, replacing any existing
+    /// signature. The signer's public key must be one of the node's keys.
+    pub fn sign(&mut self, version: u64, signer: &dyn Signer) -> Result<(), RegistryError> {
+        let public_key = to_hex(signer.public_key());
+        if !self.has_key(&public_key) {
+            return Err(RegistryError::general_error(&format!(
+                "Signing key {} is not one of the keys of node {}",
+                public_key, self.identity
+            )));
+        }
+
+        let signature = signer.sign(&self.signed_bytes(version)?).map_err(|err| {
+            RegistryError::general_error_with_source(
+                &format!("Failed to sign node {}", self.identity),
+                Box::new(err),
+            )
+        })?;
+
+        self.signature = Some(NodeSignature {
+            public_key,
+            signature: to_hex(&signature),
+            version,
+        });
+
+        Ok(())
+    }
+
+    /// Verifies the node's signature. Returns an error if the node is not signed, if the signing
+    /// key is not one of the node's keys, or if the signature does not match the node's entry.
+    pub fn verify_signature(&self) -> Result<(), InvalidNodeError> {
+        let signature = self.signature.as_ref().ok_or_else(|| {
+            InvalidNodeError::InvalidSignature(format!("node {} is not signed", self.identity))
+        })?;
+
+        if !self.has_key(&signature.public_key) {
+            return Err(InvalidNodeError::InvalidSignature(format!(
+                "signing key {} is not one of the node's keys",
+                signature.public_key
+            )));
+        }
+
+        let signed_bytes = self
+            .signed_bytes(signature.version)
+            .map_err(|err| InvalidNodeError::InvalidSignature(err.to_string()))?;
+
+        verify(&signed_bytes, signature, "the node")
+    }
+
+    /// Returns the bytes of the node's entry that are covered by its signature.
+    fn signed_bytes(&self, version: u64) -> Result<Vec<u8>, RegistryError> {
+        serde_json::to_vec(&SignedNodeFields {
+            identity: &self.identity,
+            endpoints: &self.endpoints,
+            display_name: &self.display_name,
+            keys: &self.keys,
+            metadata: self
+                .metadata
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect(),
+            version,
+        })
+        .map_err(|err| {
+            RegistryError::general_error_with_source(
+                &format!("Failed to serialize node {}", self.identity),
+                Box::new(err),
+            )
+        })
+    }
+}
+
+/// Returns the bytes covered by the signature of the removal of the node with the given
+/// `identity`.
+fn removal_bytes(identity: &str, version: u64) -> Result<Vec<u8>, RegistryError> {
+    serde_json::to_vec(&SignedNodeRemoval {
+        removed_identity: identity,
+        version,
+    })
+    .map_err(|err| {
+        RegistryError::general_error_with_source(
+            &format!("Failed to serialize removal of node {}", identity),
+            Box::new(err),
+        )
+    })
+}
+
+/// Verifies that the `signature` matches the `signed_bytes` of the given `subject`.
+fn verify(
+    signed_bytes: &[u8],
+    signature: &NodeSignature,
+    subject: &str,
+) -> Result<(), InvalidNodeError> {
+    let invalid_hex =
+        |_| InvalidNodeError::InvalidSignature("signature is not hex encoded".to_string());
+    let public_key = parse_hex(&signature.public_key).map_err(invalid_hex)?;
+    let signature_bytes = parse_hex(&signature.signature).map_err(invalid_hex)?;
+
+    match SawtoothSecp256k1SignatureVerifier::new().verify(
+        signed_bytes,
+        &signature_bytes,
+        &public_key,
+    ) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(InvalidNodeError::InvalidSignature(format!(
+            "signature does not match {}",
+            subject
+        ))),
+        Err(err) => Err(InvalidNodeError::InvalidSignature(err.to_string())),
+    }
+}
+
+/// Checks that the `signature` of a replacement or removal of the `existing` self-signed node was
+/// made by one of the node's current keys, with a version higher than that of its entry, and
+/// returns the signature.
+fn check_current_key<'a>(
+    existing: &Node,
+    existing_signature: &NodeSignature,
+    signature: Option<&'a NodeSignature>,
+    change: &str,
+) -> Result<&'a NodeSignature, InvalidNodeError> {
+    match signature {
+        Some(signature) if existing.has_key(&signature.public_key) => {
+            if signature.version > existing_signature.version {
+                Ok(signature)
+            } else {
+                Err(InvalidNodeError::InvalidSignature(format!(
+                    "node {} can only be {} with a version higher than {}",
+                    existing.identity, change, existing_signature.version
+                )))
+            }
+        }
+        _ => Err(InvalidNodeError::InvalidSignature(format!(
+            "node {} is self-signed; it can only be {} with a signature from one of its current \
+             keys",
+            existing.identity, change
+        ))),
+    }
+}
+
+/// Checks the signature of a node that is about to be written to a registry, replacing the
+/// `existing` node with the same identity (if any).
+///
+/// A signed node must have a valid signature. Once a node's entry is signed, it can only be
+/// replaced by an entry that is signed by one of the keys of the existing entry, with a higher
+/// version; this keeps others from taking over a node's self-maintained entry, or from restoring
+/// an older version of it. Writing an entry identical to the existing one leaves the registry
+/// unchanged, so it is always allowed.
+pub(super) fn check_node_signature(
+    node: &Node,
+    existing: Option<&Node>,
+) -> Result<(), InvalidNodeError> {
+    if node.signature.is_some() {
+        node.verify_signature()?;
+    }
+
+    match existing {
+        Some(existing) if existing == node => Ok(()),
+        Some(existing) => match &existing.signature {
+            Some(existing_signature) => check_current_key(
+                existing,
+                existing_signature,
+                node.signature.as_ref(),
+                "replaced",
+            )
+            .map(|_| ()),
+            None => Ok(()),
+        },
+        None => Ok(()),
+    }
+}
+
+/// Checks that the `existing` node can be removed from a registry with the given `removal`
+/// signature (see [`NodeSignature::sign_removal`]).
+///
+/// An unsigned node can always be removed. A self-signed node can only be removed with a valid
+/// removal signed by one of its keys, with a version higher than that of its entry.
+///
+/// [`NodeSignature::sign_removal`]: struct.NodeSignature.html#method.sign_removal
+pub(super) fn check_node_removal(
+    existing: &Node,
+    removal: Option<&NodeSignature>,
+) -> Result<(), InvalidNodeError> {
+    let existing_signature = match &existing.signature {
+        Some(existing_signature) => existing_signature,
+        None => return Ok(()),
+    };
+
+    let removal = check_current_key(existing, existing_signature, removal, "removed")?;
+    let signed_bytes = removal_bytes(&existing.identity, removal.version)
+        .map_err(|err| InvalidNodeError::InvalidSignature(err.to_string()))?;
+
+    verify(&signed_bytes, removal, "the removal")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
+
+    use crate::signing::sawtooth::SawtoothSecp256k1RefSigner;
+
+    const NODE_KEY: &str = "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088";
+    const OTHER_KEY: &str = "cd48b28db1e3ff1be2e2e7e2e5d5e4e1f2b8d8cf0a1ab8e9c9e7b2f0f4e3c2a1";
+
+    fn test_signer<'c>(context: &'c Secp256k1Context, key: &str) -> SawtoothSecp256k1RefSigner<'c> {
+        SawtoothSecp256k1RefSigner::new(
+            context,
+            Secp256k1PrivateKey::from_hex(key).expect("Failed to parse private key"),
+        )
+        .expect("Failed to create signer")
+    }
+
+    fn node(keys: Vec<String>) -> Node {
+        Node::builder("Node-123")
+            .with_endpoint("tcps://12.0.0.123:8431")
+            .with_display_name("Bitwise IO - Node 1")
+            .with_keys(keys)
+            .with_metadata("company", "Bitwise IO")
+            .build()
+            .expect("Failed to build node")
+    }
+
+    /// Verifies that a node signed with one of its keys verifies, and that the signature no
+    /// longer verifies after the node or the signed version is modified.
+    #[test]
+    fn sign_and_verify() {
+        let context = Secp256k1Context::new();
+        let signer = test_signer(&context, NODE_KEY);
+        let mut node = node(vec![to_hex(signer.public_key())]);
+
+        assert!(node.verify_signature().is_err());
+
+        node.sign(1, &signer).expect("Failed to sign node");
+        assert!(node.verify_signature().is_ok());
+
+        let mut replayed = node.clone();
+        if let Some(signature) = replayed.signature.as_mut() {
+            signature.version = 2;
+        }
+        assert!(replayed.verify_signature().is_err());
+
+        node.endpoints.push("tcps://12.0.0.124:8431".into());
+        assert!(node.verify_signature().is_err());
+    }
+
+    /// Verifies that a node cannot be signed by a key that is not one of its keys.
+    #[test]
+    fn sign_with_unlisted_key() {
+        let context = Secp256k1Context::new();
+        let signer = test_signer(&context, NODE_KEY);
+        let other_signer = test_signer(&context, OTHER_KEY);
+        let mut node = node(vec![to_hex(signer.public_key())]);
+
+        assert!(node.sign(1, &other_signer).is_err());
+    }
+
+    /// Verifies that a signed node can only be replaced by an entry signed by one of its current
+    /// keys with a higher version, while an unsigned node can be replaced by any valid entry.
+    #[test]
+    fn replace_signed_node() {
+        let context = Secp256k1Context::new();
+        let signer = test_signer(&context, NODE_KEY);
+        let other_signer = test_signer(&context, OTHER_KEY);
+
+        let unsigned = node(vec![to_hex(signer.public_key())]);
+        let mut signed = unsigned.clone();
+        signed.sign(2, &signer).expect("Failed to sign node");
+        let mut taken_over = node(vec![to_hex(other_signer.public_key())]);
+        taken_over
+            .sign(3, &other_signer)
+            .expect("Failed to sign node");
+
+        assert!(check_node_signature(&signed, None).is_ok());
+        assert!(check_node_signature(&signed, Some(&unsigned)).is_ok());
+        assert!(check_node_signature(&taken_over, Some(&unsigned)).is_ok());
+        assert!(check_node_signature(&signed, Some(&signed)).is_ok());
+        assert!(check_node_signature(&unsigned, Some(&signed)).is_err());
+        assert!(check_node_signature(&taken_over, Some(&signed)).is_err());
+
+        let mut updated = signed.clone();
+        updated.endpoints = vec!["tcps://12.0.0.124:8431".into()];
+        let mut older = updated.clone();
+        older.sign(1, &signer).expect("Failed to sign node");
+        assert!(check_node_signature(&older, Some(&signed)).is_err());
+        let mut same_version = updated.clone();
+        same_version.sign(2, &signer).expect("Failed to sign node");
+        assert!(check_node_signature(&same_version, Some(&signed)).is_err());
+        updated.sign(3, &signer).expect("Failed to sign node");
+        assert!(check_node_signature(&updated, Some(&signed)).is_ok());
+        assert!(check_node_signature(&signed, Some(&updated)).is_err());
+    }
+
+    /// Verifies that an unsigned node can always be removed, while a signed node can only be
+    /// removed with a valid removal signed by one of its current keys with a higher version.
+    #[test]
+    fn remove_signed_node() {
+        let context = Secp256k1Context::new();
+        let signer = test_signer(&context, NODE_KEY);
+        let other_signer = test_signer(&context, OTHER_KEY);
+
+        let unsigned = node(vec![to_hex(signer.public_key())]);
+        let mut signed = unsigned.clone();
+        signed.sign(2, &signer).expect("Failed to sign node");
+
+        let removal = |version, signer: &dyn Signer| {
+            NodeSignature::sign_removal("Node-123", version, signer)
+                .expect("Failed to sign removal")
+        };
+
+        assert!(check_node_removal(&unsigned, None).is_ok());
+        assert!(check_node_removal(&signed, None).is_err());
+        assert!(check_node_removal(&signed, Some(&removal(3, &other_signer))).is_err());
+        assert!(check_node_removal(&signed, Some(&removal(2, &signer))).is_err());
+        assert!(check_node_removal(&signed, Some(&removal(3, &signer))).is_ok());
+
+        let mut other_node = removal(3, &signer);
+        other_node.signature = NodeSignature::sign_removal("Node-456", 3, &signer)
+            .expect("Failed to sign removal")
+            .signature;
+        assert!(check_node_removal(&signed, Some(&other_node)).is_err());
+
+        let mut replayed = removal(3, &signer);
+        replayed.version = 4;
+        assert!(check_node_removal(&signed, Some(&replayed)).is_err());
+    }
+}
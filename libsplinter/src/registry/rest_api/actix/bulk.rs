@@ -0,0 +1,647 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /registry/export` for exporting every node in the registry
+//! * `POST /registry/import` for adding or replacing a list of nodes in the registry
+//! * `POST /registry/remove` for deleting a list of nodes from the registry
+//!
+//! Nodes are exported and imported as a list, in the same format as a YAML registry file. The
+//! `format` query parameter of `GET /registry/export` is either `yaml` (the default) or `json`.
+//! The body of `POST /registry/import` is parsed as JSON if its content type is
+//! `application/json`, and as YAML otherwise. The body of `POST /registry/remove` is a JSON list
+//! of node identities; identities that are not in the registry are ignored. A self-signed node is
+//! only removed if it is given as an object with the node's `identity` and a `removal` signed by
+//! one of the node's keys (see [`NodeSignature::sign_removal`]).
+//!
+//! Imports and removals are applied to the registry as a single transaction: if any of the nodes
+//! is invalid or cannot be changed, the registry is left unchanged.
+//!
+//! [`NodeSignature::sign_removal`]: ../../../struct.NodeSignature.html#method.sign_removal
+
+use std::collections::HashMap;
+
+use crate::actix_web::{error::BlockingError, web, Error, HttpMessage, HttpRequest, HttpResponse};
+#[cfg(feature = "audit")]
+use crate::audit::{rest_api::request_actor, AuditLog, AuditOutcome};
+use crate::futures::{future::IntoFuture, stream::Stream, Future};
+use crate::protocol;
+use crate::registry::{
+    rest_api::resources::bulk::{
+        ImportNodesData, ImportNodesResponse, RemoveNodeRequest, RemoveNodesData,
+        RemoveNodesResponse,
+    },
+    Node, RegistryError, RegistryReader, RegistryWriter, RwRegistry,
+};
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+#[cfg(feature = "registry-node-signing")]
+use super::verify_node_removal;
+use super::verify_submitted_node;
+
+/// The formats that lists of nodes are exported and imported in.
+#[derive(Clone, Copy)]
+enum NodesFormat {
+    Json,
+    Yaml,
+}
+
+impl NodesFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            NodesFormat::Json => "application/json",
+            NodesFormat::Yaml => "application/yaml",
+        }
+    }
+
+    fn serialize(self, nodes: &[Node]) -> Result<Vec<u8>, String> {
+        match self {
+            NodesFormat::Json => serde_json::to_vec(nodes).map_err(|err| err.to_string()),
+            NodesFormat::Yaml => serde_yaml::to_vec(nodes).map_err(|err| err.to_string()),
+        }
+    }
+
+    fn deserialize(self, body: &[u8]) -> Result<Vec<Node>, String> {
+        match self {
+            NodesFormat::Json => serde_json::from_slice(body).map_err(|err| err.to_string()),
+            NodesFormat::Yaml => serde_yaml::from_slice(body).map_err(|err| err.to_string()),
+        }
+    }
+}
+
+pub fn make_export_resource(registry: Box<dyn RwRegistry>) -> Resource {
+    Resource::build("/registry/export")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_BULK_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |r, _| {
+            export_nodes(r, web::Data::new(registry.clone_box_as_reader()))
+        })
+}
+
+pub fn make_import_resource(
+    registry: Box<dyn RwRegistry>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/registry/import")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_BULK_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Post, move |r, p| {
+            import_nodes(
+                r,
+                p,
+                web::Data::new(registry.clone_box_as_writer()),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            )
+        })
+}
+
+pub fn make_remove_resource(
+    registry: Box<dyn RwRegistry>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
+    Resource::build("/registry/remove")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_BULK_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Post, move |r, p| {
+            remove_nodes(
+                r,
+                p,
+                web::Data::new(registry.clone_box_as_writer()),
+                #[cfg(feature = "registry-node-signing")]
+                web::Data::new(registry.clone_box_as_reader()),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            )
+        })
+}
+
+fn export_nodes(
+    req: HttpRequest,
+    registry: web::Data<Box<dyn RegistryReader>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let query: web::Query<HashMap<String, String>> =
+        if let Ok(q) = web::Query::from_query(req.query_string()) {
+            q
+        } else {
+            return Box::new(
+                HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request("Invalid query"))
+                    .into_future(),
+            );
+        };
+
+    let format = match query.get("format").map(String::as_str) {
+        Some("yaml") | None => NodesFormat::Yaml,
+        Some("json") => NodesFormat::Json,
+        Some(value) => {
+            return Box::new(
+                HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(&format!(
+                        "Invalid format value passed: {}. Expected yaml or json",
+                        value
+                    )))
+                    .into_future(),
+            )
+        }
+    };
+
+    Box::new(
+        web::block(move || {
+            registry
+                .list_nodes(&[])
+                .map(|nodes| nodes.collect::<Vec<_>>())
+        })
+        .then(move |res: Result<_, BlockingError<RegistryError>>| {
+            Ok(match res.map(|nodes| format.serialize(&nodes)) {
+                Ok(Ok(body)) => HttpResponse::Ok()
+                    .content_type(format.content_type())
+                    .body(body),
+                Ok(Err(err)) => {
+                    error!("Unable to serialize nodes: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+                Err(err) => {
+                    error!("Unable to export nodes: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+            })
+        }),
+    )
+}
+
+fn import_nodes(
+    request: HttpRequest,
+    payload: web::Payload,
+    registry: web::Data<Box<dyn RegistryWriter>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let format = if request.content_type() == NodesFormat::Json.content_type() {
+        NodesFormat::Json
+    } else {
+        NodesFormat::Yaml
+    };
+    #[cfg(feature = "audit")]
+    let actor = request_actor(&request);
+    Box::new(
+        payload
+            .from_err::<Error>()
+            .fold(web::BytesMut::new(), move |mut body, chunk| {
+                body.extend_from_slice(&chunk);
+                Ok::<_, Error>(body)
+            })
+            .into_future()
+            .and_then(move |body| match format.deserialize(&body) {
+                Ok(nodes) => Box::new(
+                    web::block(move || {
+                        let identities = nodes
+                            .iter()
+                            .map(|node| node.identity.clone())
+                            .collect::<Vec<_>>();
+                        let result = nodes
+                            .iter()
+                            .try_for_each(verify_submitted_node)
+                            .and_then(|_| registry.insert_nodes(nodes));
+                        #[cfg(feature = "audit")]
+                        for identity in &identities {
+                            audit_log.record_result(
+                                &actor,
+                                "registry.node.import",
+                                identity,
+                                &result,
+                            );
+                        }
+                        result.map(|_| identities)
+                    })
+                    .then(|res| {
+                        Ok(match res {
+                            Ok(identities) => HttpResponse::Ok().json(ImportNodesResponse {
+                                data: ImportNodesData {
+                                    inserted: identities.iter().map(String::as_str).collect(),
+                                },
+                            }),
+                            Err(BlockingError::Error(RegistryError::InvalidNode(err))) => {
+                                HttpResponse::BadRequest().json(ErrorResponse::bad_request(
+                                    &format!("Invalid node: {}", err),
+                                ))
+                            }
+                            Err(err) => {
+                                error!("Unable to import nodes: {}", err);
+                                HttpResponse::InternalServerError()
+                                    .json(ErrorResponse::internal_error())
+                            }
+                        })
+                    }),
+                )
+                    as Box<dyn Future<Item = HttpResponse, Error = Error>>,
+                Err(err) => Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Invalid nodes: {}",
+                            err
+                        )))
+                        .into_future(),
+                ),
+            }),
+    )
+}
+
+fn remove_nodes(
+    request: HttpRequest,
+    payload: web::Payload,
+    registry: web::Data<Box<dyn RegistryWriter>>,
+    #[cfg(feature = "registry-node-signing")] reader: web::Data<Box<dyn RegistryReader>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    #[cfg(feature = "audit")]
+    let actor = request_actor(&request);
+    #[cfg(not(feature = "audit"))]
+    let _ = request;
+    Box::new(
+        payload
+            .from_err::<Error>()
+            .fold(web::BytesMut::new(), move |mut body, chunk| {
+                body.extend_from_slice(&chunk);
+                Ok::<_, Error>(body)
+            })
+            .into_future()
+            .and_then(
+                move |body| match serde_json::from_slice::<Vec<RemoveNodeRequest>>(&body) {
+                    Ok(requests) => Box::new(
+                        web::block(move || {
+                            let identities = requests
+                                .iter()
+                                .map(|request| request.identity().to_string())
+                                .collect::<Vec<_>>();
+                            #[cfg(feature = "registry-node-signing")]
+                            let result = requests
+                                .iter()
+                                .try_for_each(|request| {
+                                    verify_node_removal(
+                                        &**reader,
+                                        request.identity(),
+                                        request.removal(),
+                                    )
+                                })
+                                .and_then(|_| registry.delete_nodes(&identities));
+                            #[cfg(not(feature = "registry-node-signing"))]
+                            let result = registry.delete_nodes(&identities);
+                            #[cfg(feature = "audit")]
+                            for identity in &identities {
+                                audit_log.record(
+                                    &actor,
+                                    "registry.node.remove",
+                                    identity,
+                                    removal_outcome(identity, &result),
+                                );
+                            }
+                            result
+                        })
+                        .then(|res| {
+                            Ok(match res {
+                                Ok(nodes) => HttpResponse::Ok().json(RemoveNodesResponse {
+                                    data: RemoveNodesData {
+                                        removed: nodes
+                                            .iter()
+                                            .map(|node| node.identity.as_str())
+                                            .collect(),
+                                    },
+                                }),
+                                Err(BlockingError::Error(RegistryError::InvalidNode(err))) => {
+                                    HttpResponse::Forbidden().json(ErrorResponse::forbidden(
+                                        &format!("Cannot remove node: {}", err),
+                                    ))
+                                }
+                                Err(err) => {
+                                    error!("Unable to remove nodes: {}", err);
+                                    HttpResponse::InternalServerError()
+                                        .json(ErrorResponse::internal_error())
+                                }
+                            })
+                        }),
+                    )
+                        as Box<dyn Future<Item = HttpResponse, Error = Error>>,
+                    Err(err) => Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Invalid identities: {}",
+                                err
+                            )))
+                            .into_future(),
+                    ),
+                },
+            ),
+    )
+}
+
+/// Returns the audit outcome of the removal of the node with the given identity.
+#[cfg(feature = "audit")]
+fn removal_outcome(identity: &str, result: &Result<Vec<Node>, RegistryError>) -> AuditOutcome {
+    match result {
+        Ok(deleted) if deleted.iter().any(|node| node.identity == identity) => {
+            AuditOutcome::Success
+        }
+        Ok(_) => AuditOutcome::Failure("Node not found".into()),
+        Err(err) => AuditOutcome::Failure(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
+    use tempdir::TempDir;
+
+    use crate::registry::LocalYamlRegistry;
+    use crate::rest_api::{RestApiBuilder, RestApiServerError, RestApiShutdownHandle};
+
+    #[test]
+    /// Tests that nodes imported with a POST /registry/import request are returned by a
+    /// GET /registry/export request, and that a POST /registry/remove request deletes them.
+    ///
+    /// 1. Import two nodes as YAML and verify that both identities are returned
+    /// 2. Import a list containing an invalid node and verify that the request is rejected
+    /// 3. Export the nodes as JSON and verify that only the two valid nodes are returned
+    /// 4. Remove one of the nodes and an unknown identity, and verify that only the node is
+    ///    reported as removed
+    /// 5. Export the nodes as YAML and verify that only the remaining node is returned
+    fn test_import_export_remove() {
+        let temp_dir =
+            TempDir::new("test_import_export_remove").expect("Failed to create temp dir");
+        let registry: Box<dyn RwRegistry> = Box::new(
+            LocalYamlRegistry::new(
+                temp_dir
+                    .path()
+                    .join("registry.yaml")
+                    .to_str()
+                    .expect("Failed to get path"),
+            )
+            .expect("Failed to create registry"),
+        );
+
+        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
+            make_export_resource(registry.clone()),
+            make_import_resource(
+                registry.clone(),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            ),
+            make_remove_resource(
+                registry,
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            ),
+        ]);
+        let client = Client::new();
+
+        let resp = client
+            .post(
+                Url::parse(&format!("http://{}/registry/import", bind_url))
+                    .expect("Failed to parse URL"),
+            )
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .header("Content-Type", "application/yaml")
+            .body(
+                serde_yaml::to_vec(&vec![get_node_1(), get_node_2()])
+                    .expect("Failed to serialize nodes"),
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        assert_eq!(
+            body["data"]["inserted"],
+            serde_json::json!(["Node-123", "Node-456"])
+        );
+
+        let mut invalid_node = get_node_3();
+        invalid_node.endpoints = get_node_1().endpoints;
+        let resp = client
+            .post(
+                Url::parse(&format!("http://{}/registry/import", bind_url))
+                    .expect("Failed to parse URL"),
+            )
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .json(&vec![invalid_node])
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = client
+            .get(
+                Url::parse(&format!("http://{}/registry/export?format=json", bind_url))
+                    .expect("Failed to parse URL"),
+            )
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let nodes: Vec<Node> = resp.json().expect("Failed to deserialize body");
+        assert_eq!(nodes, vec![get_node_1(), get_node_2()]);
+
+        let resp = client
+            .post(
+                Url::parse(&format!("http://{}/registry/remove", bind_url))
+                    .expect("Failed to parse URL"),
+            )
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .json(&vec!["Node-123", "Node-000"])
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        assert_eq!(body["data"]["removed"], serde_json::json!(["Node-123"]));
+
+        let resp = client
+            .get(
+                Url::parse(&format!("http://{}/registry/export", bind_url))
+                    .expect("Failed to parse URL"),
+            )
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let nodes: Vec<Node> = serde_yaml::from_slice(&resp.bytes().expect("Failed to get body"))
+            .expect("Failed to deserialize body");
+        assert_eq!(nodes, vec![get_node_2()]);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    #[cfg(feature = "registry-node-signing")]
+    #[test]
+    /// Tests that a POST /registry/remove request only removes a self-signed node with a removal
+    /// signed by one of the node's keys, and that the registry is unchanged otherwise.
+    ///
+    /// 1. Add a signed and an unsigned node
+    /// 2. Remove both nodes by identity and verify that the request is forbidden
+    /// 3. Remove the signed node with a signed removal and the other node by identity, and verify
+    ///    that both are removed
+    fn test_remove_signed_node() {
+        use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
+
+        use crate::hex::to_hex;
+        use crate::registry::NodeSignature;
+        use crate::signing::{sawtooth::SawtoothSecp256k1RefSigner, Signer};
+
+        let context = Secp256k1Context::new();
+        let signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(
+                "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088",
+            )
+            .expect("Failed to parse private key"),
+        )
+        .expect("Failed to create signer");
+        let mut signed_node = get_node_1();
+        signed_node.keys = vec![to_hex(signer.public_key())];
+        signed_node.sign(1, &signer).expect("Failed to sign node");
+
+        let temp_dir = TempDir::new("test_remove_signed_node").expect("Failed to create temp dir");
+        let registry = LocalYamlRegistry::new(
+            temp_dir
+                .path()
+                .join("registry.yaml")
+                .to_str()
+                .expect("Failed to get path"),
+        )
+        .expect("Failed to create registry");
+        registry
+            .insert_nodes(vec![signed_node, get_node_2()])
+            .expect("Failed to insert nodes");
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_remove_resource(
+                Box::new(registry.clone()),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
+        let remove = |body: JsonValue| {
+            Client::new()
+                .post(
+                    Url::parse(&format!("http://{}/registry/remove", bind_url))
+                        .expect("Failed to parse URL"),
+                )
+                .header(
+                    "SplinterProtocolVersion",
+                    protocol::REGISTRY_PROTOCOL_VERSION,
+                )
+                .json(&body)
+                .send()
+                .expect("Failed to perform request")
+        };
+
+        let resp = remove(serde_json::json!(["Node-123", "Node-456"]));
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(registry.count_nodes(&[]).expect("Failed to count nodes"), 2);
+
+        let removal =
+            NodeSignature::sign_removal("Node-123", 2, &signer).expect("Failed to sign removal");
+        let resp = remove(serde_json::json!([
+            { "identity": "Node-123", "removal": removal },
+            "Node-456",
+        ]));
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        assert_eq!(
+            body["data"]["removed"],
+            serde_json::json!(["Node-123", "Node-456"])
+        );
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        (10000..20000)
+            .find_map(|port| {
+                let bind_url = format!("127.0.0.1:{}", port);
+                let result = RestApiBuilder::new()
+                    .with_bind(&bind_url)
+                    .add_resources(resources.clone())
+                    .build()
+                    .expect("Failed to build REST API")
+                    .run();
+                match result {
+                    Ok((shutdown_handle, join_handle)) => {
+                        Some((shutdown_handle, join_handle, bind_url))
+                    }
+                    Err(RestApiServerError::BindError(_)) => None,
+                    Err(err) => panic!("Failed to run REST API: {}", err),
+                }
+            })
+            .expect("No port available")
+    }
+
+    fn get_node_1() -> Node {
+        Node::builder("Node-123")
+            .with_endpoint("12.0.0.123:8431")
+            .with_display_name("Bitwise IO - Node 1")
+            .with_key("0123")
+            .with_metadata("company", "Bitwise IO")
+            .build()
+            .expect("Failed to build node1")
+    }
+
+    fn get_node_2() -> Node {
+        Node::builder("Node-456")
+            .with_endpoint("13.0.0.123:8434")
+            .with_display_name("Cargill - Node 1")
+            .with_key("abcd")
+            .with_metadata("company", "Cargill")
+            .build()
+            .expect("Failed to build node2")
+    }
+
+    fn get_node_3() -> Node {
+        Node::builder("Node-789")
+            .with_endpoint("13.0.0.123:8435")
+            .with_display_name("Cargill - Node 2")
+            .with_key("4567")
+            .with_metadata("company", "Cargill")
+            .build()
+            .expect("Failed to build node3")
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoint:
+//!
+//! * `GET /registry/conflicts` for listing the nodes whose definitions conflict between the
+//!   registry's sources
+
+use crate::actix_web::{web, Error, HttpResponse};
+use crate::futures::Future;
+use crate::protocol;
+use crate::registry::{
+    rest_api::resources::conflicts::{ListConflictsResponse, NodeConflictResponse},
+    RegistryReader, RwRegistry,
+};
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+pub fn make_conflicts_resource(registry: Box<dyn RwRegistry>) -> Resource {
+    Resource::build("/registry/conflicts")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_LIST_CONFLICTS_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |_, _| {
+            list_conflicts(web::Data::new(registry.clone_box_as_reader()))
+        })
+}
+
+fn list_conflicts(
+    registry: web::Data<Box<dyn RegistryReader>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    Box::new(web::block(move || registry.list_conflicts()).then(|res| {
+        Ok(match res {
+            Ok(conflicts) => HttpResponse::Ok().json(ListConflictsResponse {
+                data: conflicts.iter().map(NodeConflictResponse::from).collect(),
+            }),
+            Err(err) => {
+                error!("Unable to list registry conflicts: {}", err);
+                HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+            }
+        })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::{to_value, Value as JsonValue};
+    use tempdir::TempDir;
+
+    use crate::registry::{LocalYamlRegistry, Node, RegistryWriter, UnifiedRegistry};
+    use crate::rest_api::{RestApiBuilder, RestApiServerError, RestApiShutdownHandle};
+
+    #[test]
+    /// Tests a GET /registry/conflicts request returns the nodes whose definitions conflict
+    /// between the sources of a unified registry.
+    fn test_list_conflicts_ok() {
+        let temp_dir = TempDir::new("test_list_conflicts_ok").expect("Failed to create temp dir");
+        let path = |name: &str| {
+            temp_dir
+                .path()
+                .join(name)
+                .to_str()
+                .expect("Failed to get path")
+                .to_string()
+        };
+        let internal = LocalYamlRegistry::new(&path("internal.yaml"))
+            .expect("Failed to create internal registry");
+        let external = LocalYamlRegistry::new(&path("external.yaml"))
+            .expect("Failed to create external registry");
+        internal
+            .insert_node(get_node("12.0.0.123:8431"))
+            .expect("Failed to insert node");
+        external
+            .insert_node(get_node("12.0.0.123:9431"))
+            .expect("Failed to insert node");
+
+        let registry = UnifiedRegistry::new(Box::new(internal), vec![Box::new(external)])
+            .with_source_names(vec!["local".into(), "remote".into()]);
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_conflicts_resource(Box::new(registry))]);
+
+        let url = Url::parse(&format!("http://{}/registry/conflicts", bind_url))
+            .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        let conflicts = body
+            .get("data")
+            .expect("No data field in response")
+            .as_array()
+            .expect("data field is not an array");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0]["identity"], "Node-123");
+        assert_eq!(
+            conflicts[0]["fields"],
+            to_value(vec!["endpoints"]).expect("Failed to convert value")
+        );
+        assert_eq!(conflicts[0]["definitions"][0]["source"], "local");
+        assert_eq!(
+            conflicts[0]["definitions"][1]["node"]["endpoints"],
+            to_value(vec!["12.0.0.123:9431"]).expect("Failed to convert value")
+        );
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        (10000..20000)
+            .find_map(|port| {
+                let bind_url = format!("127.0.0.1:{}", port);
+                let result = RestApiBuilder::new()
+                    .with_bind(&bind_url)
+                    .add_resources(resources.clone())
+                    .build()
+                    .expect("Failed to build REST API")
+                    .run();
+                match result {
+                    Ok((shutdown_handle, join_handle)) => {
+                        Some((shutdown_handle, join_handle, bind_url))
+                    }
+                    Err(RestApiServerError::BindError(_)) => None,
+                    Err(err) => panic!("Failed to run REST API: {}", err),
+                }
+            })
+            .expect("No port available")
+    }
+
+    fn get_node(endpoint: &str) -> Node {
+        Node::builder("Node-123")
+            .with_endpoint(endpoint)
+            .with_display_name("Bitwise IO - Node 1")
+            .with_key("0123")
+            .build()
+            .expect("Failed to build node")
+    }
+}
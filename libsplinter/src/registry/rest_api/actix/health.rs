@@ -0,0 +1,149 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoint:
+//!
+//! * `GET /registry/health` for fetching the report of the latest check of the registry's nodes
+
+use crate::actix_web::HttpResponse;
+use crate::futures::future::IntoFuture;
+use crate::protocol;
+use crate::registry::{rest_api::resources::health::HealthReportResponse, RegistryValidator};
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+pub fn make_health_resource(validator: RegistryValidator) -> Resource {
+    Resource::build("/registry/health")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_FETCH_HEALTH_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |_, _| {
+            Box::new(
+                match validator.report() {
+                    Ok(Some(report)) => {
+                        HttpResponse::Ok().json(HealthReportResponse::from(&report))
+                    }
+                    Ok(None) => HttpResponse::NotFound().json(ErrorResponse::not_found(
+                        "The registry has not been checked yet",
+                    )),
+                    Err(err) => {
+                        error!("Unable to fetch registry health report: {}", err);
+                        HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                    }
+                }
+                .into_future(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
+    use tempdir::TempDir;
+
+    use crate::registry::{LocalYamlRegistry, Node, RegistryChecker, RegistryWriter};
+    use crate::rest_api::{RestApiBuilder, RestApiServerError, RestApiShutdownHandle};
+
+    #[test]
+    /// Tests a GET /registry/health request returns the issues found by the latest check of the
+    /// registry.
+    fn test_fetch_health_ok() {
+        let temp_dir = TempDir::new("test_fetch_health_ok").expect("Failed to create temp dir");
+        let registry = LocalYamlRegistry::new(
+            temp_dir
+                .path()
+                .join("registry.yaml")
+                .to_str()
+                .expect("Failed to get path"),
+        )
+        .expect("Failed to create registry");
+        registry
+            .insert_node(
+                Node::builder("Node-123")
+                    .with_endpoint("udp://12.0.0.123:8431")
+                    .with_display_name("Bitwise IO - Node 1")
+                    .with_key("0123")
+                    .build()
+                    .expect("Failed to build node"),
+            )
+            .expect("Failed to insert node");
+
+        let validator = RegistryValidator::start(
+            Box::new(registry),
+            RegistryChecker::new(),
+            Duration::from_secs(60),
+        )
+        .expect("Failed to start validator");
+        while validator.report().expect("Failed to get report").is_none() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_health_resource(validator.clone())]);
+
+        let url = Url::parse(&format!("http://{}/registry/health", bind_url))
+            .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: JsonValue = resp.json().expect("Failed to deserialize body");
+        let data = body.get("data").expect("No data field in response");
+        assert_eq!(data["node_count"], 1);
+        assert_eq!(data["healthy"], false);
+        assert_eq!(data["issues"][0]["kind"], "invalid_endpoint");
+        assert_eq!(data["issues"][0]["identity"], "Node-123");
+        assert_eq!(data["issues"][0]["endpoint"], "udp://12.0.0.123:8431");
+
+        validator.shutdown();
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        (10000..20000)
+            .find_map(|port| {
+                let bind_url = format!("127.0.0.1:{}", port);
+                let result = RestApiBuilder::new()
+                    .with_bind(&bind_url)
+                    .add_resources(resources.clone())
+                    .build()
+                    .expect("Failed to build REST API")
+                    .run();
+                match result {
+                    Ok((shutdown_handle, join_handle)) => {
+                        Some((shutdown_handle, join_handle, bind_url))
+                    }
+                    Err(RestApiServerError::BindError(_)) => None,
+                    Err(err) => panic!("Failed to run REST API: {}", err),
+                }
+            })
+            .expect("No port available")
+    }
+}
@@ -12,5 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "registry-bulk")]
+pub(super) mod bulk;
+#[cfg(feature = "registry-merge")]
+pub(super) mod conflicts;
+#[cfg(feature = "registry-health")]
+pub(super) mod health;
 pub(super) mod nodes;
 pub(super) mod nodes_identity;
+#[cfg(feature = "registry-events")]
+pub(super) mod ws_registry;
+
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::{node_signature::check_node_removal, NodeSignature, RegistryReader};
+use crate::registry::{Node, RegistryError};
+
+/// Verifies the signature of a node submitted through the REST API, if the node is signed.
+#[cfg_attr(not(feature = "registry-node-signing"), allow(unused_variables))]
+fn verify_submitted_node(node: &Node) -> Result<(), RegistryError> {
+    #[cfg(feature = "registry-node-signing")]
+    {
+        if node.signature.is_some() {
+            node.verify_signature()?;
+        }
+    }
+    Ok(())
+}
+
+/// Verifies that a node submitted for removal through the REST API may be removed with the given
+/// `removal` signature; a self-signed node can only be removed with a removal signed by one of its
+/// keys. Nodes that are not in the registry are ignored.
+#[cfg(feature = "registry-node-signing")]
+fn verify_node_removal(
+    registry: &dyn RegistryReader,
+    identity: &str,
+    removal: Option<&NodeSignature>,
+) -> Result<(), RegistryError> {
+    match registry.fetch_node(identity)? {
+        Some(existing) => Ok(check_node_removal(&existing, removal)?),
+        None => Ok(()),
+    }
+}
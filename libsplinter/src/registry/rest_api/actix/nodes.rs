@@ -16,10 +16,24 @@
 //!
 //! * `GET /registry/nodes` for listing nodes in the registry
 //! * `POST /registry/nodes` for adding a node to the registry
+//!
+//! The `filter` query parameter of `GET /registry/nodes` is a JSON object that maps metadata keys
+//! to `[operator, value]` pairs, e.g. `{"company": ["=", "Cargill"]}`; a node must match every
+//! entry. With the `registry-query` feature, the object may also contain:
+//!
+//! * the `in`, `starts_with`, `contains` and `exists` operators, e.g. `["in", ["eu", "uk"]]` or
+//!   `["exists"]`
+//! * numeric values for the comparison operators, which compare the metadata value as a number,
+//!   e.g. `{"tier": [">=", 2]}`
+//! * the `$identity`, `$display_name`, `$endpoints` and `$keys` fields, with the `=`, `in`,
+//!   `starts_with` and `contains` operators
+//! * the `$and` and `$or` groups, each a list of filter objects, and `$not`, a filter object
 
 use std::collections::HashMap;
 
 use crate::actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+#[cfg(feature = "audit")]
+use crate::audit::{rest_api::request_actor, AuditLog};
 use crate::futures::{future::IntoFuture, stream::Stream, Future};
 use crate::protocol;
 use crate::registry::{
@@ -32,9 +46,22 @@ use crate::rest_api::{
     percent_encode_filter_query, ErrorResponse, Method, ProtocolVersionRangeGuard, Resource,
 };
 
+use super::verify_submitted_node;
+
+#[cfg(feature = "registry-query")]
+use crate::registry::{FieldMatch, NodeField};
+#[cfg(feature = "registry-query")]
+use serde_json::{Map, Value};
+
+#[cfg(not(feature = "registry-query"))]
 type Filter = HashMap<String, (String, String)>;
+#[cfg(feature = "registry-query")]
+type Filter = Map<String, Value>;
 
-pub fn make_nodes_resource(registry: Box<dyn RwRegistry>) -> Resource {
+pub fn make_nodes_resource(
+    registry: Box<dyn RwRegistry>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
     let registry1 = registry.clone();
     Resource::build("/registry/nodes")
         .add_request_guard(ProtocolVersionRangeGuard::new(
@@ -44,8 +71,14 @@ pub fn make_nodes_resource(registry: Box<dyn RwRegistry>) -> Resource {
         .add_method(Method::Get, move |r, _| {
             list_nodes(r, web::Data::new(registry.clone_box_as_reader()))
         })
-        .add_method(Method::Post, move |_, p| {
-            add_node(p, web::Data::new(registry1.clone()))
+        .add_method(Method::Post, move |r, p| {
+            add_node(
+                r,
+                p,
+                web::Data::new(registry1.clone()),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            )
         })
 }
 
@@ -177,6 +210,7 @@ fn query_list_nodes(
     })
 }
 
+#[cfg(not(feature = "registry-query"))]
 fn to_predicates(filters: Option<Filter>) -> Result<Vec<MetadataPredicate>, String> {
     match filters {
         Some(filters) => filters
@@ -195,10 +229,145 @@ fn to_predicates(filters: Option<Filter>) -> Result<Vec<MetadataPredicate>, Stri
     }
 }
 
+#[cfg(feature = "registry-query")]
+fn to_predicates(filters: Option<Filter>) -> Result<Vec<MetadataPredicate>, String> {
+    match filters {
+        Some(filters) => filters
+            .into_iter()
+            .map(|(key, value)| match key.as_str() {
+                "$and" => to_group(value).map(MetadataPredicate::And),
+                "$or" => to_group(value).map(MetadataPredicate::Or),
+                "$not" => match value {
+                    Value::Object(filter) => to_predicates(Some(filter)).map(|predicates| {
+                        MetadataPredicate::not(MetadataPredicate::And(predicates))
+                    }),
+                    _ => Err("$not must be a filter object".into()),
+                },
+                "$identity" => to_field_predicate(NodeField::Identity, &key, value),
+                "$display_name" => to_field_predicate(NodeField::DisplayName, &key, value),
+                "$endpoints" => to_field_predicate(NodeField::Endpoints, &key, value),
+                "$keys" => to_field_predicate(NodeField::Keys, &key, value),
+                _ => to_metadata_predicate(key, value),
+            })
+            .collect(),
+        None => Ok(vec![]),
+    }
+}
+
+/// Converts a list of filter objects into predicates, one for each filter object.
+#[cfg(feature = "registry-query")]
+fn to_group(value: Value) -> Result<Vec<MetadataPredicate>, String> {
+    match value {
+        Value::Array(filters) => filters
+            .into_iter()
+            .map(|filter| match filter {
+                Value::Object(filter) => to_predicates(Some(filter)).map(MetadataPredicate::And),
+                _ => Err("$and and $or must be lists of filter objects".into()),
+            })
+            .collect(),
+        _ => Err("$and and $or must be lists of filter objects".into()),
+    }
+}
+
+#[cfg(feature = "registry-query")]
+fn to_metadata_predicate(key: String, value: Value) -> Result<MetadataPredicate, String> {
+    match to_operation(&key, value)? {
+        (operator, None) => match operator.as_str() {
+            "exists" => Ok(MetadataPredicate::Exists(key)),
+            _ => Err(format!("{} requires a value", operator)),
+        },
+        (operator, Some(Value::Number(number))) => {
+            let number = number
+                .as_f64()
+                .ok_or_else(|| format!("{} is not a valid number", number))?;
+            match operator.as_str() {
+                "=" => Ok(MetadataPredicate::NumEq(key, number)),
+                "!=" => Ok(MetadataPredicate::not(MetadataPredicate::NumEq(
+                    key, number,
+                ))),
+                ">" => Ok(MetadataPredicate::NumGt(key, number)),
+                ">=" => Ok(MetadataPredicate::NumGe(key, number)),
+                "<" => Ok(MetadataPredicate::NumLt(key, number)),
+                "<=" => Ok(MetadataPredicate::NumLe(key, number)),
+                _ => Err(format!("{} is not a valid numeric operator", operator)),
+            }
+        }
+        (operator, Some(value)) => match operator.as_str() {
+            "=" => Ok(MetadataPredicate::Eq(key, to_string(value)?)),
+            "!=" => Ok(MetadataPredicate::Ne(key, to_string(value)?)),
+            ">" => Ok(MetadataPredicate::Gt(key, to_string(value)?)),
+            ">=" => Ok(MetadataPredicate::Ge(key, to_string(value)?)),
+            "<" => Ok(MetadataPredicate::Lt(key, to_string(value)?)),
+            "<=" => Ok(MetadataPredicate::Le(key, to_string(value)?)),
+            "in" => Ok(MetadataPredicate::In(key, to_strings(value)?)),
+            "starts_with" => Ok(MetadataPredicate::StartsWith(key, to_string(value)?)),
+            "contains" => Ok(MetadataPredicate::Contains(key, to_string(value)?)),
+            _ => Err(format!("{} is not a valid operator", operator)),
+        },
+    }
+}
+
+#[cfg(feature = "registry-query")]
+fn to_field_predicate(
+    field: NodeField,
+    key: &str,
+    value: Value,
+) -> Result<MetadataPredicate, String> {
+    let (operator, value) = match to_operation(key, value)? {
+        (operator, Some(value)) => (operator, value),
+        (operator, None) => return Err(format!("{} requires a value", operator)),
+    };
+    let field_match = match operator.as_str() {
+        "=" => FieldMatch::Eq(to_string(value)?),
+        "in" => FieldMatch::In(to_strings(value)?),
+        "starts_with" => FieldMatch::StartsWith(to_string(value)?),
+        "contains" => FieldMatch::Contains(to_string(value)?),
+        _ => return Err(format!("{} is not a valid operator for {}", operator, key)),
+    };
+    Ok(MetadataPredicate::Field(field, field_match))
+}
+
+/// Splits an `[operator]` or `[operator, value]` list into the operator and the optional value.
+#[cfg(feature = "registry-query")]
+fn to_operation(key: &str, value: Value) -> Result<(String, Option<Value>), String> {
+    match value {
+        Value::Array(mut items) if items.len() == 1 || items.len() == 2 => {
+            let value = if items.len() == 2 { items.pop() } else { None };
+            match items.pop() {
+                Some(Value::String(operator)) => Ok((operator, value)),
+                _ => Err(format!("the operator for {} must be a string", key)),
+            }
+        }
+        _ => Err(format!("{} must be an [operator, value] list", key)),
+    }
+}
+
+#[cfg(feature = "registry-query")]
+fn to_string(value: Value) -> Result<String, String> {
+    match value {
+        Value::String(value) => Ok(value),
+        _ => Err(format!("{} is not a string", value)),
+    }
+}
+
+#[cfg(feature = "registry-query")]
+fn to_strings(value: Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::Array(values) => values.into_iter().map(to_string).collect(),
+        _ => Err(format!("{} is not a list of strings", value)),
+    }
+}
+
 fn add_node(
+    request: HttpRequest,
     payload: web::Payload,
     registry: web::Data<Box<dyn RwRegistry>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    #[cfg(feature = "audit")]
+    let actor = request_actor(&request);
+    #[cfg(not(feature = "audit"))]
+    let _ = request;
     Box::new(
         payload
             .from_err::<Error>()
@@ -210,13 +379,22 @@ fn add_node(
             .and_then(move |body| match serde_json::from_slice::<Node>(&body) {
                 Ok(node) => Box::new(
                     web::block(move || {
-                        if registry.has_node(&node.identity)? {
-                            Err(RegistryError::InvalidNode(
-                                InvalidNodeError::DuplicateIdentity(node.identity),
-                            ))
-                        } else {
-                            registry.insert_node(node)
-                        }
+                        #[cfg(feature = "audit")]
+                        let identity = node.identity.clone();
+                        let result = verify_submitted_node(&node).and_then(|_| {
+                            registry.has_node(&node.identity).and_then(|exists| {
+                                if exists {
+                                    Err(RegistryError::InvalidNode(
+                                        InvalidNodeError::DuplicateIdentity(node.identity),
+                                    ))
+                                } else {
+                                    registry.insert_node(node)
+                                }
+                            })
+                        });
+                        #[cfg(feature = "audit")]
+                        audit_log.record_result(&actor, "registry.node.add", &identity, &result);
+                        result
                     })
                     .then(|res| {
                         Ok(match res {
@@ -264,9 +442,12 @@ mod tests {
     #[test]
     /// Tests a GET /registry/nodes request with no filters returns the expected nodes.
     fn test_list_nodes_ok() {
-        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
-            make_nodes_resource(Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()]))),
-        ]);
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_nodes_resource(
+                Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         let url = Url::parse(&format!("http://{}/registry/nodes", bind_url))
             .expect("Failed to parse URL");
@@ -321,9 +502,12 @@ mod tests {
     #[test]
     /// Tests a GET /registry/nodes request with filters returns the expected node.
     fn test_list_nodes_with_filters_ok() {
-        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
-            make_nodes_resource(Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()]))),
-        ]);
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_nodes_resource(
+                Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         let filter = percent_encode_filter_query("{\"company\":[\"=\",\"Bitwise IO\"]}");
         let url = Url::parse(&format!(
@@ -368,12 +552,74 @@ mod tests {
         join_handle.join().expect("Unable to join rest api thread");
     }
 
+    #[cfg(feature = "registry-query")]
+    #[test]
+    /// Tests a GET /registry/nodes request with grouped, field and set filters returns the
+    /// expected node, and that the filter is kept in the paging links.
+    fn test_list_nodes_with_query_filters_ok() {
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_nodes_resource(
+                Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
+
+        let filter = percent_encode_filter_query(
+            "{\"$or\":[{\"company\":[\"in\",[\"Cargill\",\"Other\"]]},\
+             {\"$display_name\":[\"starts_with\",\"Bitwise\"]}],\
+             \"$keys\":[\"=\",\"abcd\"],\"$not\":{\"retired\":[\"exists\"]}}",
+        );
+        let url = Url::parse(&format!(
+            "http://{}/registry/nodes?filter={}&limit=1",
+            bind_url, filter
+        ))
+        .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let nodes: JsonValue = resp.json().expect("Failed to deserialize body");
+
+        assert_eq!(
+            nodes.get("data").expect("no data field in response"),
+            &to_value(vec![NodeResponse::from(&get_node_2())])
+                .expect("failed to convert expected data"),
+        );
+        assert_eq!(
+            nodes.get("paging").expect("no paging field in response"),
+            &to_value(create_test_paging_response(
+                0,
+                1,
+                0,
+                0,
+                0,
+                1,
+                &format!("/registry/nodes?filter={}&", filter)
+            ))
+            .expect("failed to convert expected paging")
+        );
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
     #[test]
     /// Tests a GET /registry/nodes request with invalid filter returns BadRequest response.
     fn test_list_node_with_filters_bad_request() {
-        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
-            make_nodes_resource(Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()]))),
-        ]);
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_nodes_resource(
+                Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         let filter = percent_encode_filter_query("{\"company\":[\"*\",\"Bitwise IO\"]}");
         let url = Url::parse(&format!(
@@ -402,7 +648,11 @@ mod tests {
     /// Test the POST /registry/nodes route for adding a node to the registry.
     fn test_add_node() {
         let (shutdown_handle, join_handle, bind_url) =
-            run_rest_api_on_open_port(vec![make_nodes_resource(Box::new(MemRegistry::default()))]);
+            run_rest_api_on_open_port(vec![make_nodes_resource(
+                Box::new(MemRegistry::default()),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         // Verify an invalid node gets a BAD_REQUEST response
         let url = Url::parse(&format!("http://{}/registry/nodes", bind_url))
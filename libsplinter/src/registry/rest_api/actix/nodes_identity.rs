@@ -17,19 +17,37 @@
 //! * `GET /registry/nodes/{identity}` for fetching a node in the registry
 //! * `PUT /registry/nodes/{identity}` for replacing a node in the registry
 //! * `DELETE /registry/nodes/{identity}` for deleting a node from the registry
+//!
+//! A self-signed node can only be deleted if the body of the `DELETE` request is a removal of the
+//! node signed by one of its keys (see [`NodeSignature::sign_removal`]).
+//!
+//! [`NodeSignature::sign_removal`]: ../../../struct.NodeSignature.html#method.sign_removal
 
 use crate::actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+#[cfg(feature = "audit")]
+use crate::audit::{rest_api::request_actor, AuditLog, AuditOutcome};
 use crate::futures::{future::IntoFuture, stream::Stream, Future};
 use crate::protocol;
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::NodeSignature;
 use crate::registry::{
     rest_api::resources::nodes_identity::NodeResponse, InvalidNodeError, Node, RegistryError,
     RegistryReader, RegistryWriter, RwRegistry,
 };
 use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
 
-pub fn make_nodes_identity_resource(registry: Box<dyn RwRegistry>) -> Resource {
+#[cfg(feature = "registry-node-signing")]
+use super::verify_node_removal;
+use super::verify_submitted_node;
+
+pub fn make_nodes_identity_resource(
+    registry: Box<dyn RwRegistry>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Resource {
     let registry1 = registry.clone();
     let registry2 = registry.clone();
+    #[cfg(feature = "audit")]
+    let audit_log1 = audit_log.clone();
     Resource::build("/registry/nodes/{identity}")
         .add_request_guard(ProtocolVersionRangeGuard::new(
             protocol::REGISTRY_FETCH_NODE_MIN,
@@ -39,10 +57,24 @@ pub fn make_nodes_identity_resource(registry: Box<dyn RwRegistry>) -> Resource {
             fetch_node(r, web::Data::new(registry.clone_box_as_reader()))
         })
         .add_method(Method::Put, move |r, p| {
-            put_node(r, p, web::Data::new(registry1.clone_box_as_writer()))
+            put_node(
+                r,
+                p,
+                web::Data::new(registry1.clone_box_as_writer()),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            )
         })
-        .add_method(Method::Delete, move |r, _| {
-            delete_node(r, web::Data::new(registry2.clone_box_as_writer()))
+        .add_method(Method::Delete, move |r, p| {
+            delete_node(
+                r,
+                p,
+                web::Data::new(registry2.clone_box_as_writer()),
+                #[cfg(feature = "registry-node-signing")]
+                web::Data::new(registry2.clone_box_as_reader()),
+                #[cfg(feature = "audit")]
+                audit_log1.clone(),
+            )
         })
 }
 
@@ -56,8 +88,22 @@ fn fetch_node(
         .unwrap_or("")
         .to_string();
     Box::new(
-        web::block(move || registry.fetch_node(&identity)).then(|res| {
+        web::block(move || {
+            let node = registry.fetch_node(&identity)?;
+            // Include where the node's fields came from, if the registry combines sources
+            #[cfg(feature = "registry-merge")]
+            let node = match node {
+                Some(node) => Some((node, registry.fetch_node_provenance(&identity)?)),
+                None => None,
+            };
+            Ok::<_, RegistryError>(node)
+        })
+        .then(|res| {
             Ok(match res {
+                #[cfg(feature = "registry-merge")]
+                Ok(Some((node, provenance))) => HttpResponse::Ok()
+                    .json(NodeResponse::from(&node).with_provenance(provenance.as_ref())),
+                #[cfg(not(feature = "registry-merge"))]
                 Ok(Some(node)) => HttpResponse::Ok().json(NodeResponse::from(&node)),
                 Ok(None) => {
                     HttpResponse::NotFound().json(ErrorResponse::not_found("Node not found"))
@@ -75,12 +121,15 @@ fn put_node(
     request: HttpRequest,
     payload: web::Payload,
     registry: web::Data<Box<dyn RegistryWriter>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
     let path_identity = request
         .match_info()
         .get("identity")
         .unwrap_or("")
         .to_string();
+    #[cfg(feature = "audit")]
+    let actor = request_actor(&request);
     Box::new(
         payload
             .from_err::<Error>()
@@ -92,7 +141,7 @@ fn put_node(
             .and_then(move |body| match serde_json::from_slice::<Node>(&body) {
                 Ok(node) => Box::new(
                     web::block(move || {
-                        if node.identity != path_identity {
+                        let result = if node.identity != path_identity {
                             Err(RegistryError::InvalidNode(
                                 InvalidNodeError::InvalidIdentity(
                                     node.identity,
@@ -100,8 +149,16 @@ fn put_node(
                                 ),
                             ))
                         } else {
-                            registry.insert_node(node)
-                        }
+                            verify_submitted_node(&node).and_then(|_| registry.insert_node(node))
+                        };
+                        #[cfg(feature = "audit")]
+                        audit_log.record_result(
+                            &actor,
+                            "registry.node.update",
+                            &path_identity,
+                            &result,
+                        );
+                        result
                     })
                     .then(|res| {
                         Ok(match res {
@@ -134,26 +191,92 @@ fn put_node(
 
 fn delete_node(
     request: HttpRequest,
+    payload: web::Payload,
     registry: web::Data<Box<dyn RegistryWriter>>,
+    #[cfg(feature = "registry-node-signing")] reader: web::Data<Box<dyn RegistryReader>>,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
 ) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
     let identity = request
         .match_info()
         .get("identity")
         .unwrap_or("")
         .to_string();
+    #[cfg(feature = "audit")]
+    let actor = request_actor(&request);
     Box::new(
-        web::block(move || registry.delete_node(&identity)).then(|res| {
-            Ok(match res {
-                Ok(Some(_)) => HttpResponse::Ok().finish(),
-                Ok(None) => {
-                    HttpResponse::NotFound().json(ErrorResponse::not_found("Node not found"))
-                }
-                Err(err) => {
-                    error!("Unable to delete node: {}", err);
-                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
-                }
+        payload
+            .from_err::<Error>()
+            .fold(web::BytesMut::new(), move |mut body, chunk| {
+                body.extend_from_slice(&chunk);
+                Ok::<_, Error>(body)
             })
-        }),
+            .into_future()
+            .and_then(move |body| {
+                // The body is only used for the removal signature of a self-signed node
+                #[cfg(feature = "registry-node-signing")]
+                let removal = if body.is_empty() {
+                    None
+                } else {
+                    match serde_json::from_slice::<NodeSignature>(&body) {
+                        Ok(removal) => Some(removal),
+                        Err(err) => {
+                            return Box::new(
+                                HttpResponse::BadRequest()
+                                    .json(ErrorResponse::bad_request(&format!(
+                                        "Invalid removal signature: {}",
+                                        err
+                                    )))
+                                    .into_future(),
+                            )
+                                as Box<dyn Future<Item = HttpResponse, Error = Error>>
+                        }
+                    }
+                };
+                #[cfg(not(feature = "registry-node-signing"))]
+                let _ = body;
+
+                Box::new(
+                    web::block(move || {
+                        #[cfg(feature = "registry-node-signing")]
+                        let result = verify_node_removal(&**reader, &identity, removal.as_ref())
+                            .and_then(|_| {
+                                registry.delete_node_with_removal(&identity, removal.as_ref())
+                            });
+                        #[cfg(not(feature = "registry-node-signing"))]
+                        let result = registry.delete_node(&identity);
+                        #[cfg(feature = "audit")]
+                        audit_log.record(
+                            &actor,
+                            "registry.node.remove",
+                            &identity,
+                            match &result {
+                                Ok(Some(_)) => AuditOutcome::Success,
+                                Ok(None) => AuditOutcome::Failure("Node not found".into()),
+                                Err(err) => AuditOutcome::Failure(err.to_string()),
+                            },
+                        );
+                        result
+                    })
+                    .then(|res| {
+                        Ok(match res {
+                            Ok(Some(_)) => HttpResponse::Ok().finish(),
+                            Ok(None) => HttpResponse::NotFound()
+                                .json(ErrorResponse::not_found("Node not found")),
+                            Err(BlockingError::Error(RegistryError::InvalidNode(err))) => {
+                                HttpResponse::Forbidden().json(ErrorResponse::forbidden(&format!(
+                                    "Cannot remove node: {}",
+                                    err
+                                )))
+                            }
+                            Err(err) => {
+                                error!("Unable to delete node: {}", err);
+                                HttpResponse::InternalServerError()
+                                    .json(ErrorResponse::internal_error())
+                            }
+                        })
+                    }),
+                )
+            }),
     )
 }
 
@@ -173,9 +296,11 @@ mod tests {
     /// Tests a GET /registry/nodes/{identity} request returns the expected node.
     fn test_fetch_node_ok() {
         let (shutdown_handle, join_handle, bind_url) =
-            run_rest_api_on_open_port(vec![make_nodes_identity_resource(Box::new(
-                MemRegistry::new(vec![get_node_1(), get_node_2()]),
-            ))]);
+            run_rest_api_on_open_port(vec![make_nodes_identity_resource(
+                Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         let url = Url::parse(&format!(
             "http://{}/registry/nodes/{}",
@@ -202,14 +327,86 @@ mod tests {
         join_handle.join().expect("Unable to join rest api thread");
     }
 
+    #[cfg(feature = "registry-merge")]
+    #[test]
+    /// Tests a GET /registry/nodes/{identity} request to a unified registry includes the names of
+    /// the sources that the node's fields came from.
+    fn test_fetch_node_with_provenance_ok() {
+        use tempdir::TempDir;
+
+        use crate::registry::{LocalYamlRegistry, UnifiedRegistry};
+
+        let temp_dir =
+            TempDir::new("test_fetch_node_with_provenance_ok").expect("Failed to create temp dir");
+        let path = |name: &str| {
+            temp_dir
+                .path()
+                .join(name)
+                .to_str()
+                .expect("Failed to get path")
+                .to_string()
+        };
+        let internal = LocalYamlRegistry::new(&path("internal.yaml"))
+            .expect("Failed to create internal registry");
+        let external = LocalYamlRegistry::new(&path("external.yaml"))
+            .expect("Failed to create external registry");
+        internal
+            .insert_node(get_node_1())
+            .expect("Failed to insert node");
+        let mut external_node = get_node_1();
+        external_node.metadata = vec![("admin".to_string(), "Bob".to_string())]
+            .into_iter()
+            .collect();
+        external
+            .insert_node(external_node)
+            .expect("Failed to insert node");
+
+        let registry = UnifiedRegistry::new(Box::new(internal), vec![Box::new(external)])
+            .with_source_names(vec!["local".into(), "remote".into()]);
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_nodes_identity_resource(
+                Box::new(registry),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
+
+        let url = Url::parse(&format!(
+            "http://{}/registry/nodes/{}",
+            bind_url,
+            get_node_1().identity
+        ))
+        .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = resp.json().expect("Failed to deserialize body");
+        assert_eq!(body["provenance"]["display_name"], "local");
+        assert_eq!(body["provenance"]["metadata"]["company"], "local");
+        assert_eq!(body["provenance"]["metadata"]["admin"], "remote");
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
     #[test]
     /// Tests a GET /registry/nodes/{identity} request returns NotFound when an invalid identity is
     /// passed.
     fn test_fetch_node_not_found() {
         let (shutdown_handle, join_handle, bind_url) =
-            run_rest_api_on_open_port(vec![make_nodes_identity_resource(Box::new(
-                MemRegistry::new(vec![get_node_1(), get_node_2()]),
-            ))]);
+            run_rest_api_on_open_port(vec![make_nodes_identity_resource(
+                Box::new(MemRegistry::new(vec![get_node_1(), get_node_2()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         let url = Url::parse(&format!(
             "http://{}/registry/nodes/Node-not-valid",
@@ -237,9 +434,11 @@ mod tests {
     /// Test the PUT /registry/nodes/{identity} route for adding or updating a node in the registry.
     fn test_put_node() {
         let (shutdown_handle, join_handle, bind_url) =
-            run_rest_api_on_open_port(vec![make_nodes_identity_resource(Box::new(
-                MemRegistry::new(vec![get_node_1()]),
-            ))]);
+            run_rest_api_on_open_port(vec![make_nodes_identity_resource(
+                Box::new(MemRegistry::new(vec![get_node_1()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         // Verify no body (i.e. no updated Node) gets a BAD_REQUEST response
         let url = Url::parse(&format!(
@@ -327,9 +526,11 @@ mod tests {
     /// Test the DELETE /registry/nodes/{identity} route for deleting a node from the registry.
     fn test_delete_node() {
         let (shutdown_handle, join_handle, bind_url) =
-            run_rest_api_on_open_port(vec![make_nodes_identity_resource(Box::new(
-                MemRegistry::new(vec![get_node_1()]),
-            ))]);
+            run_rest_api_on_open_port(vec![make_nodes_identity_resource(
+                Box::new(MemRegistry::new(vec![get_node_1()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
 
         // Verify that an existing node gets an OK response
         let url = Url::parse(&format!(
@@ -367,6 +568,72 @@ mod tests {
         join_handle.join().expect("Unable to join rest api thread");
     }
 
+    #[cfg(feature = "registry-node-signing")]
+    #[test]
+    /// Test that the DELETE /registry/nodes/{identity} route only deletes a self-signed node with
+    /// a removal signed by one of the node's keys.
+    fn test_delete_signed_node() {
+        use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
+
+        use crate::hex::to_hex;
+        use crate::signing::{sawtooth::SawtoothSecp256k1RefSigner, Signer};
+
+        let context = Secp256k1Context::new();
+        let signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(
+                "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088",
+            )
+            .expect("Failed to parse private key"),
+        )
+        .expect("Failed to create signer");
+        let mut node = get_node_1();
+        node.keys = vec![to_hex(signer.public_key())];
+        node.sign(2, &signer).expect("Failed to sign node");
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![make_nodes_identity_resource(
+                Box::new(MemRegistry::new(vec![node.clone()])),
+                #[cfg(feature = "audit")]
+                AuditLog::default(),
+            )]);
+        let url = Url::parse(&format!(
+            "http://{}/registry/nodes/{}",
+            bind_url, node.identity
+        ))
+        .expect("Failed to parse URL");
+        let delete = |removal: Option<NodeSignature>| {
+            let request = Client::new().delete(url.clone()).header(
+                "SplinterProtocolVersion",
+                protocol::REGISTRY_PROTOCOL_VERSION,
+            );
+            match removal {
+                Some(removal) => request.json(&removal),
+                None => request,
+            }
+            .send()
+            .expect("Failed to perform request")
+            .status()
+        };
+
+        // Verify that the node is not deleted without a removal, or with a replayed removal
+        assert_eq!(delete(None), StatusCode::FORBIDDEN);
+        let replayed = NodeSignature::sign_removal(&node.identity, 2, &signer)
+            .expect("Failed to sign removal");
+        assert_eq!(delete(Some(replayed)), StatusCode::FORBIDDEN);
+
+        // Verify that the node is deleted with a removal with a higher version
+        let removal = NodeSignature::sign_removal(&node.identity, 3, &signer)
+            .expect("Failed to sign removal");
+        assert_eq!(delete(Some(removal)), StatusCode::OK);
+        assert_eq!(delete(None), StatusCode::NOT_FOUND);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
     fn run_rest_api_on_open_port(
         resources: Vec<Resource>,
     ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
@@ -0,0 +1,70 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoint:
+//!
+//! * `GET /ws/registry` for receiving the changes to the nodes in the registry over a websocket
+
+use crate::actix_web::HttpResponse;
+use crate::futures::IntoFuture;
+use crate::protocol;
+use crate::registry::{
+    rest_api::resources::events::RegistryEventResponse, RegistryEvent, RegistryEventSubscriber,
+    RegistrySubscriberError, RwRegistry,
+};
+use crate::rest_api::{
+    new_websocket_event_sender, EventSender, Method, ProtocolVersionRangeGuard, Request, Resource,
+};
+
+pub fn make_ws_registry_resource(registry: Box<dyn RwRegistry>) -> Resource {
+    Resource::build("/ws/registry")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::REGISTRY_WS_EVENTS_MIN,
+            protocol::REGISTRY_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |request, payload| {
+            let request = Request::from((request, payload));
+            match new_websocket_event_sender(request, Box::new(std::iter::empty())) {
+                Ok((sender, res)) => {
+                    if let Err(err) = registry
+                        .add_event_subscriber(Box::new(WsRegistryEventSubscriber { sender }))
+                    {
+                        error!("Unable to add registry event subscriber: {}", err);
+                        return Box::new(
+                            HttpResponse::InternalServerError().finish().into_future(),
+                        );
+                    }
+                    debug!("Websocket response: {:?}", res);
+                    Box::new(res.into_future())
+                }
+                Err(err) => {
+                    debug!("Failed to create websocket: {:?}", err);
+                    Box::new(HttpResponse::InternalServerError().finish().into_future())
+                }
+            }
+        })
+}
+
+struct WsRegistryEventSubscriber {
+    sender: EventSender<RegistryEventResponse>,
+}
+
+impl RegistryEventSubscriber for WsRegistryEventSubscriber {
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+        self.sender.send(event.into()).map_err(|_| {
+            debug!("Dropping registry event and unsubscribing due to websocket being closed");
+            RegistrySubscriberError::Unsubscribe
+        })
+    }
+}
@@ -18,8 +18,12 @@
 mod actix;
 mod resources;
 
+#[cfg(feature = "audit")]
+use crate::audit::AuditLog;
 use crate::rest_api::{Resource, RestResourceProvider};
 
+#[cfg(feature = "registry-health")]
+use super::RegistryValidator;
 use super::RwRegistry;
 
 /// The `RwRegistry` trait service provides the following endpoints as REST API resources:
@@ -29,11 +33,64 @@ use super::RwRegistry;
 /// * `GET /registry/nodes/{identity}` - Fetch a specific node in the registry
 /// * `PUT /registry/nodes/{identity}` - Replace a node in the registry
 /// * `DELETE /registry/nodes/{identity}` - Delete a node from the registry
+/// * `GET /registry/export` - Export the nodes in the registry as YAML or JSON (requires the
+///   `registry-bulk` feature)
+/// * `POST /registry/import` - Add or replace a list of nodes in the registry as a single
+///   transaction (requires the `registry-bulk` feature)
+/// * `POST /registry/remove` - Delete a list of nodes from the registry as a single transaction
+///   (requires the `registry-bulk` feature)
+/// * `GET /registry/conflicts` - List the nodes whose definitions conflict between the
+///   registry's sources (requires the `registry-merge` feature)
+/// * `GET /ws/registry` - Receive the changes to the nodes in the registry over a websocket
+///   (requires the `registry-events` feature)
 ///
 /// These endpoints are only available if the following REST API backend feature is enabled:
 ///
 /// * `rest-api-actix`
 impl RestResourceProvider for dyn RwRegistry {
+    fn resources(&self) -> Vec<Resource> {
+        registry_resources(
+            self,
+            #[cfg(feature = "audit")]
+            AuditLog::default(),
+        )
+    }
+}
+
+/// Provides the same REST API resources as a `RwRegistry`, and records the nodes added, replaced
+/// and deleted through them in an audit log.
+#[cfg(feature = "audit")]
+pub struct AuditedRegistryResourceProvider {
+    registry: Box<dyn RwRegistry>,
+    audit_log: AuditLog,
+}
+
+#[cfg(feature = "audit")]
+impl AuditedRegistryResourceProvider {
+    pub fn new(registry: Box<dyn RwRegistry>, audit_log: AuditLog) -> Self {
+        Self {
+            registry,
+            audit_log,
+        }
+    }
+}
+
+#[cfg(feature = "audit")]
+impl RestResourceProvider for AuditedRegistryResourceProvider {
+    fn resources(&self) -> Vec<Resource> {
+        registry_resources(&*self.registry, self.audit_log.clone())
+    }
+}
+
+/// The registry validator provides the following endpoint as a REST API resource:
+///
+/// * `GET /registry/health` - Fetch the report of the latest check of the registry's nodes
+///
+/// This endpoint is only available if the following REST API backend feature is enabled:
+///
+/// * `rest-api-actix`
+#[cfg(feature = "registry-health")]
+impl RestResourceProvider for RegistryValidator {
     fn resources(&self) -> Vec<Resource> {
         // Allowing unused_mut because resources must be mutable if feature rest-api-actix is
         // enabled
@@ -41,13 +98,61 @@ impl RestResourceProvider for dyn RwRegistry {
         let mut resources = Vec::new();
 
         #[cfg(feature = "rest-api-actix")]
-        {
-            resources.append(&mut vec![
-                actix::nodes_identity::make_nodes_identity_resource(self.clone_box()),
-                actix::nodes::make_nodes_resource(self.clone_box()),
-            ]);
-        }
+        resources.push(actix::health::make_health_resource(self.clone()));
 
         resources
     }
 }
+
+// Allowing unused_variables because the arguments are only used if feature rest-api-actix is
+// enabled
+#[allow(unused_variables)]
+fn registry_resources(
+    registry: &dyn RwRegistry,
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Vec<Resource> {
+    // Allowing unused_mut because resources must be mutable if feature rest-api-actix is
+    // enabled
+    #[allow(unused_mut)]
+    let mut resources = Vec::new();
+
+    #[cfg(feature = "rest-api-actix")]
+    {
+        #[cfg(feature = "registry-bulk")]
+        resources.append(&mut vec![
+            actix::bulk::make_export_resource(registry.clone_box()),
+            actix::bulk::make_import_resource(
+                registry.clone_box(),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            ),
+            actix::bulk::make_remove_resource(
+                registry.clone_box(),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            ),
+        ]);
+        resources.append(&mut vec![
+            actix::nodes_identity::make_nodes_identity_resource(
+                registry.clone_box(),
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            ),
+            actix::nodes::make_nodes_resource(
+                registry.clone_box(),
+                #[cfg(feature = "audit")]
+                audit_log,
+            ),
+        ]);
+        #[cfg(feature = "registry-merge")]
+        resources.push(actix::conflicts::make_conflicts_resource(
+            registry.clone_box(),
+        ));
+        #[cfg(feature = "registry-events")]
+        resources.push(actix::ws_registry::make_ws_registry_resource(
+            registry.clone_box(),
+        ));
+    }
+
+    resources
+}
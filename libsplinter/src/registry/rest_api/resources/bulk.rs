@@ -0,0 +1,69 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::NodeSignature;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportNodesResponse<'a> {
+    pub data: ImportNodesData<'a>,
+}
+
+/// The identities of the nodes that were added to or replaced in the registry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportNodesData<'a> {
+    pub inserted: Vec<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoveNodesResponse<'a> {
+    pub data: RemoveNodesData<'a>,
+}
+
+/// The identities of the nodes that were deleted from the registry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoveNodesData<'a> {
+    pub removed: Vec<&'a str>,
+}
+
+/// A node to remove from the registry: either the node's identity or, to remove a self-signed
+/// node, its identity and a removal signed by one of its keys
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum RemoveNodeRequest {
+    Identity(String),
+    #[cfg(feature = "registry-node-signing")]
+    Signed {
+        identity: String,
+        removal: NodeSignature,
+    },
+}
+
+impl RemoveNodeRequest {
+    pub fn identity(&self) -> &str {
+        match self {
+            RemoveNodeRequest::Identity(identity) => identity,
+            #[cfg(feature = "registry-node-signing")]
+            RemoveNodeRequest::Signed { identity, .. } => identity,
+        }
+    }
+
+    #[cfg(feature = "registry-node-signing")]
+    pub fn removal(&self) -> Option<&NodeSignature> {
+        match self {
+            RemoveNodeRequest::Identity(_) => None,
+            RemoveNodeRequest::Signed { removal, .. } => Some(removal),
+        }
+    }
+}
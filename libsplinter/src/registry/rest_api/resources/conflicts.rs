@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::NodeConflict;
+
+use super::nodes_identity::NodeResponse;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListConflictsResponse<'a> {
+    pub data: Vec<NodeConflictResponse<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeConflictResponse<'a> {
+    pub identity: &'a str,
+    pub fields: &'a [String],
+    pub definitions: Vec<NodeDefinitionResponse<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeDefinitionResponse<'a> {
+    pub source: &'a str,
+    pub node: NodeResponse<'a>,
+}
+
+impl<'a> From<&'a NodeConflict> for NodeConflictResponse<'a> {
+    fn from(conflict: &'a NodeConflict) -> Self {
+        Self {
+            identity: &conflict.identity,
+            fields: &conflict.fields,
+            definitions: conflict
+                .definitions
+                .iter()
+                .map(|(source, node)| NodeDefinitionResponse {
+                    source,
+                    node: NodeResponse::from(node),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::{Node, RegistryEvent};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "eventType", content = "message")]
+pub enum RegistryEventResponse {
+    NodeAdded { node: Node },
+    NodeUpdated { previous: Node, node: Node },
+    NodeRemoved { node: Node },
+}
+
+impl From<&RegistryEvent> for RegistryEventResponse {
+    fn from(event: &RegistryEvent) -> Self {
+        match event {
+            RegistryEvent::NodeAdded(node) => {
+                RegistryEventResponse::NodeAdded { node: node.clone() }
+            }
+            RegistryEvent::NodeUpdated { previous, node } => RegistryEventResponse::NodeUpdated {
+                previous: previous.clone(),
+                node: node.clone(),
+            },
+            RegistryEvent::NodeRemoved(node) => {
+                RegistryEventResponse::NodeRemoved { node: node.clone() }
+            }
+        }
+    }
+}
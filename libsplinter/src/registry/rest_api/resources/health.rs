@@ -0,0 +1,126 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::registry::{HealthReport, RegistryIssue};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReportResponse<'a> {
+    pub data: HealthReportData<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReportData<'a> {
+    /// Seconds since the Unix epoch
+    pub checked_at: u64,
+    pub node_count: usize,
+    pub probed: bool,
+    pub healthy: bool,
+    pub issues: Vec<RegistryIssueResponse<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RegistryIssueResponse<'a> {
+    InvalidEndpoint {
+        identity: &'a str,
+        endpoint: &'a str,
+        reason: &'a str,
+    },
+    UnreachableEndpoint {
+        identity: &'a str,
+        endpoint: &'a str,
+        reason: &'a str,
+    },
+    DuplicateEndpoint {
+        endpoint: &'a str,
+        identities: &'a [String],
+    },
+    DuplicateKey {
+        key: &'a str,
+        identities: &'a [String],
+    },
+    StaleNode {
+        identity: &'a str,
+        /// Seconds since the Unix epoch
+        unreachable_since: u64,
+    },
+}
+
+impl<'a> From<&'a HealthReport> for HealthReportResponse<'a> {
+    fn from(report: &'a HealthReport) -> Self {
+        Self {
+            data: HealthReportData {
+                checked_at: unix_time(report.checked_at),
+                node_count: report.node_count,
+                probed: report.probed,
+                healthy: report.is_healthy(),
+                issues: report
+                    .issues
+                    .iter()
+                    .map(RegistryIssueResponse::from)
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a RegistryIssue> for RegistryIssueResponse<'a> {
+    fn from(issue: &'a RegistryIssue) -> Self {
+        match issue {
+            RegistryIssue::InvalidEndpoint {
+                identity,
+                endpoint,
+                reason,
+            } => RegistryIssueResponse::InvalidEndpoint {
+                identity,
+                endpoint,
+                reason,
+            },
+            RegistryIssue::UnreachableEndpoint {
+                identity,
+                endpoint,
+                reason,
+            } => RegistryIssueResponse::UnreachableEndpoint {
+                identity,
+                endpoint,
+                reason,
+            },
+            RegistryIssue::DuplicateEndpoint {
+                endpoint,
+                identities,
+            } => RegistryIssueResponse::DuplicateEndpoint {
+                endpoint,
+                identities,
+            },
+            RegistryIssue::DuplicateKey { key, identities } => {
+                RegistryIssueResponse::DuplicateKey { key, identities }
+            }
+            RegistryIssue::StaleNode {
+                identity,
+                unreachable_since,
+            } => RegistryIssueResponse::StaleNode {
+                identity,
+                unreachable_since: unix_time(*unreachable_since),
+            },
+        }
+    }
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
@@ -12,5 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "registry-bulk")]
+pub(super) mod bulk;
+#[cfg(feature = "registry-merge")]
+pub(super) mod conflicts;
+#[cfg(feature = "registry-events")]
+pub(super) mod events;
+#[cfg(feature = "registry-health")]
+pub(super) mod health;
 pub(super) mod nodes;
 pub(super) mod nodes_identity;
@@ -15,6 +15,8 @@
 use std::collections::HashMap;
 
 use crate::registry::Node;
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::NodeSignature;
 use crate::rest_api::paging::Paging;
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -30,6 +32,9 @@ pub struct NodeResponse<'a> {
     pub display_name: &'a str,
     pub keys: &'a [String],
     pub metadata: &'a HashMap<String, String>,
+    #[cfg(feature = "registry-node-signing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<&'a NodeSignature>,
 }
 
 impl<'a> From<&'a Node> for NodeResponse<'a> {
@@ -40,6 +45,8 @@ impl<'a> From<&'a Node> for NodeResponse<'a> {
             display_name: &node.display_name,
             keys: &node.keys,
             metadata: &node.metadata,
+            #[cfg(feature = "registry-node-signing")]
+            signature: node.signature.as_ref(),
         }
     }
 }
@@ -15,6 +15,10 @@
 use std::collections::HashMap;
 
 use crate::registry::Node;
+#[cfg(feature = "registry-merge")]
+use crate::registry::NodeProvenance;
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::NodeSignature;
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct NodeResponse<'a> {
@@ -23,6 +27,20 @@ pub struct NodeResponse<'a> {
     pub display_name: &'a str,
     pub keys: &'a [String],
     pub metadata: &'a HashMap<String, String>,
+    #[cfg(feature = "registry-node-signing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<&'a NodeSignature>,
+    #[cfg(feature = "registry-merge")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<&'a NodeProvenance>,
+}
+
+#[cfg(feature = "registry-merge")]
+impl<'a> NodeResponse<'a> {
+    pub fn with_provenance(mut self, provenance: Option<&'a NodeProvenance>) -> Self {
+        self.provenance = provenance;
+        self
+    }
 }
 
 impl<'a> From<&'a Node> for NodeResponse<'a> {
@@ -33,6 +51,10 @@ impl<'a> From<&'a Node> for NodeResponse<'a> {
             display_name: &node.display_name,
             keys: &node.keys,
             metadata: &node.metadata,
+            #[cfg(feature = "registry-node-signing")]
+            signature: node.signature.as_ref(),
+            #[cfg(feature = "registry-merge")]
+            provenance: None,
         }
     }
 }
@@ -22,10 +22,22 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-
+#[cfg(feature = "registry-events")]
+use std::sync::{Mutex, Weak};
+
+#[cfg(feature = "registry-events")]
+use super::events::{diff_nodes, RegistryEventSubscribers};
+#[cfg(feature = "registry-merge")]
+use super::merge::{find_conflict, merge_definitions};
+#[cfg(feature = "registry-node-signing")]
+use super::NodeSignature;
+#[cfg(feature = "registry-merge")]
+use super::{MergeStrategy, NodeConflict, NodeProvenance};
 use super::{
     MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader, RegistryWriter, RwRegistry,
 };
+#[cfg(feature = "registry-events")]
+use super::{RegistryEvent, RegistryEventSubscriber, RegistrySubscriberError};
 
 /// A registry with multiple sources.
 ///
@@ -61,6 +73,22 @@ use super::{
 /// If the same metadata key is set for the node in different registires, the value for that key
 /// from the highest-precedence registry will be used.
 ///
+/// ## Merge Strategies
+///
+/// With the `registry-merge` feature, the way the endpoints and keys of a node are combined is
+/// set by the registry's [`MergeStrategy`] (see [`with_merge_strategy`]); the default strategy is
+/// the precedence-based behavior described above. The unified registry can also report which
+/// source each field of a node came from, and which nodes have conflicting definitions in its
+/// sources. Sources are identified by the names given to [`with_source_names`].
+///
+/// # Events
+///
+/// When the first event subscriber is added, the unified registry takes a snapshot of each source
+/// registry and subscribes to the source's events. Subscribers receive events for the changes to
+/// the unified view of a node, so a change that is hidden by a higher-precedence source is not
+/// reported. Sources that do not support event subscriptions are only reflected in the initial
+/// snapshot.
+///
 /// [`RegistryReader`]: ../trait.RegistryReader.html
 /// [`RegistryWriter`]: ../trait.RegistryWriter.html
 /// [`RwRegistry`]: ../trait.RwRegistry.html
@@ -68,10 +96,20 @@ use super::{
 /// [`identity`]: ../struct.Node.html#structfield.identity
 /// [`metadata`]: ../struct.Node.html#structfield.metadata
 /// [`Metadata Merging`]: #metadata-merging
+/// [`MergeStrategy`]: ../enum.MergeStrategy.html
+/// [`with_merge_strategy`]: #method.with_merge_strategy
+/// [`with_source_names`]: #method.with_source_names
 #[derive(Clone)]
 pub struct UnifiedRegistry {
     internal_source: Arc<dyn RwRegistry>,
     external_sources: Vec<Arc<dyn RegistryReader>>,
+    #[cfg(feature = "registry-events")]
+    source_nodes: Arc<Mutex<Option<SourceNodes>>>,
+    #[cfg(feature = "registry-merge")]
+    merge_strategy: MergeStrategy,
+    /// The names of the internal source followed by the read-only sources
+    #[cfg(feature = "registry-merge")]
+    source_names: Vec<String>,
 }
 
 impl UnifiedRegistry {
@@ -81,13 +119,95 @@ impl UnifiedRegistry {
         internal_source: Box<dyn RwRegistry>,
         external_sources: Vec<Box<dyn RegistryReader>>,
     ) -> Self {
+        #[cfg(feature = "registry-merge")]
+        let source_names = std::iter::once("internal".to_string())
+            .chain((1..=external_sources.len()).map(|n| format!("external-{}", n)))
+            .collect();
+
         Self {
             internal_source: internal_source.into(),
             external_sources: external_sources.into_iter().map(Arc::from).collect(),
+            #[cfg(feature = "registry-events")]
+            source_nodes: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "registry-merge")]
+            merge_strategy: MergeStrategy::default(),
+            #[cfg(feature = "registry-merge")]
+            source_names,
+        }
+    }
+
+    /// Sets how the definitions of a node that exists in more than one source are combined.
+    #[cfg(feature = "registry-merge")]
+    pub fn with_merge_strategy(mut self, merge_strategy: MergeStrategy) -> Self {
+        self.merge_strategy = merge_strategy;
+        self
+    }
+
+    /// Sets the names that identify the sources in provenance and conflict reports. The names are
+    /// given for the internal source followed by the read-only sources, in the order they were
+    /// passed to `new`. Sources without a name keep their default name: `internal` for the
+    /// internal source, and `external-<n>` for the nth read-only source.
+    #[cfg(feature = "registry-merge")]
+    pub fn with_source_names(mut self, names: Vec<String>) -> Self {
+        for (source_name, name) in self.source_names.iter_mut().zip(names) {
+            *source_name = name;
         }
+        self
+    }
+
+    /// Gets the definitions of each node from all sources, with the name of the source, in
+    /// descending order of precedence.
+    #[cfg(feature = "registry-merge")]
+    fn node_definitions(&self) -> HashMap<String, Vec<(&str, Node)>> {
+        let mut definitions: HashMap<String, Vec<(&str, Node)>> = HashMap::new();
+        let sources = std::iter::once(self.internal_source.list_nodes(&[]))
+            .chain(
+                self.external_sources
+                    .iter()
+                    .map(|registry| registry.list_nodes(&[])),
+            )
+            .zip(self.source_names.iter());
+        for (res, name) in sources {
+            match res {
+                Ok(nodes) => {
+                    for node in nodes {
+                        definitions
+                            .entry(node.identity.clone())
+                            .or_default()
+                            .push((name.as_str(), node));
+                    }
+                }
+                // Log the error and ignore the failing registry
+                Err(err) => debug!("Failed to list nodes in source registry: {}", err),
+            }
+        }
+        definitions
+    }
+
+    /// Gets the definitions of the node with the given identity from all sources, with the name
+    /// of the source, in descending order of precedence.
+    #[cfg(feature = "registry-merge")]
+    fn fetch_definitions(&self, identity: &str) -> Vec<(&str, Node)> {
+        std::iter::once(self.internal_source.fetch_node(identity))
+            .chain(
+                self.external_sources
+                    .iter()
+                    .map(|registry| registry.fetch_node(identity)),
+            )
+            .zip(self.source_names.iter())
+            .filter_map(|(res, name)| match res {
+                Ok(node) => node.map(|node| (name.as_str(), node)),
+                // Log the error and ignore the failing registry
+                Err(err) => {
+                    debug!("Failed to fetch node from source registry: {}", err);
+                    None
+                }
+            })
+            .collect()
     }
 
     /// Gets all nodes from all sources (in ascending order of precedence) without deduplication.
+    #[cfg(not(feature = "registry-merge"))]
     fn all_nodes<'a>(&'a self) -> Box<dyn Iterator<Item = Node> + 'a> {
         Box::new(
             // Get node iterators from all read-only sources
@@ -115,6 +235,17 @@ impl RegistryReader for UnifiedRegistry {
         &'b self,
         predicates: &'a [MetadataPredicate],
     ) -> Result<NodeIter<'a>, RegistryError> {
+        // Get the definitions of each node and combine them with the merge strategy
+        #[cfg(feature = "registry-merge")]
+        let mut id_map = self
+            .node_definitions()
+            .into_iter()
+            .filter_map(|(identity, definitions)| {
+                merge_definitions(self.merge_strategy, definitions)
+                    .map(|(node, _)| (identity, node))
+            })
+            .collect::<HashMap<_, _>>();
+        #[cfg(not(feature = "registry-merge"))]
         let mut id_map = self
             // Get all nodes from all sources
             .all_nodes()
@@ -141,6 +272,15 @@ impl RegistryReader for UnifiedRegistry {
         self.list_nodes(predicates).map(|iter| iter.count() as u32)
     }
 
+    #[cfg(feature = "registry-merge")]
+    fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        Ok(
+            merge_definitions(self.merge_strategy, self.fetch_definitions(identity))
+                .map(|(node, _)| node),
+        )
+    }
+
+    #[cfg(not(feature = "registry-merge"))]
     fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
         // Get node from all read-only sources
         Ok(self
@@ -175,6 +315,195 @@ impl RegistryReader for UnifiedRegistry {
                 }
             }))
     }
+
+    #[cfg(feature = "registry-events")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        let mut source_nodes = self
+            .source_nodes
+            .lock()
+            .map_err(|_| RegistryError::general_error("Unified registry's event lock poisoned"))?;
+
+        if source_nodes.is_none() {
+            // Get all sources in ascending order of precedence
+            let internal_source = self.internal_source.clone_box_as_reader();
+            let sources = self
+                .external_sources
+                .iter()
+                .rev()
+                .map(|registry| &**registry)
+                .chain(std::iter::once(&*internal_source))
+                .collect::<Vec<_>>();
+            let nodes_by_source = sources
+                .iter()
+                .map(|registry| match registry.list_nodes(&[]) {
+                    Ok(nodes) => nodes.map(|node| (node.identity.clone(), node)).collect(),
+                    Err(err) => {
+                        debug!("Failed to list nodes in source registry: {}", err);
+                        HashMap::new()
+                    }
+                })
+                .collect();
+            *source_nodes = Some(SourceNodes {
+                nodes_by_source,
+                subscribers: RegistryEventSubscribers::default(),
+                #[cfg(feature = "registry-merge")]
+                merge_strategy: self.merge_strategy,
+                #[cfg(feature = "registry-merge")]
+                source_names: self.source_names.clone(),
+            });
+
+            for (index, registry) in sources.iter().enumerate() {
+                let forwarder = SourceEventForwarder {
+                    index,
+                    source_nodes: Arc::downgrade(&self.source_nodes),
+                };
+                if let Err(err) = registry.add_event_subscriber(Box::new(forwarder)) {
+                    warn!(
+                        "Changes to a source registry will not be reported by the unified \
+                         registry: {}",
+                        err
+                    );
+                }
+            }
+        }
+
+        match source_nodes.as_ref() {
+            Some(source_nodes) => source_nodes.subscribers.add(subscriber),
+            None => Err(RegistryError::general_error(
+                "Unified registry's event state was not initialized",
+            )),
+        }
+    }
+
+    #[cfg(feature = "registry-merge")]
+    fn fetch_node_provenance(
+        &self,
+        identity: &str,
+    ) -> Result<Option<NodeProvenance>, RegistryError> {
+        Ok(
+            merge_definitions(self.merge_strategy, self.fetch_definitions(identity))
+                .map(|(_, provenance)| provenance),
+        )
+    }
+
+    #[cfg(feature = "registry-merge")]
+    fn list_conflicts(&self) -> Result<Vec<NodeConflict>, RegistryError> {
+        let mut conflicts = self
+            .node_definitions()
+            .into_iter()
+            .filter_map(|(_, definitions)| find_conflict(definitions))
+            .collect::<Vec<_>>();
+        conflicts.sort_by(|a, b| a.identity.cmp(&b.identity));
+        Ok(conflicts)
+    }
+}
+
+/// The nodes of each source registry, used to compute the changes to the unified view of a node
+/// when a source reports an event.
+#[cfg(feature = "registry-events")]
+struct SourceNodes {
+    /// The nodes of each source by identity, in ascending order of precedence
+    nodes_by_source: Vec<HashMap<String, Node>>,
+    subscribers: RegistryEventSubscribers,
+    #[cfg(feature = "registry-merge")]
+    merge_strategy: MergeStrategy,
+    /// The names of the sources, in descending order of precedence
+    #[cfg(feature = "registry-merge")]
+    source_names: Vec<String>,
+}
+
+#[cfg(feature = "registry-events")]
+impl SourceNodes {
+    /// Gets the unified definition of the node with the given identity, combining its definitions
+    /// in the same way as `UnifiedRegistry::fetch_node`.
+    #[cfg(feature = "registry-merge")]
+    fn unified_node(&self, identity: &str) -> Option<Node> {
+        let definitions = self
+            .nodes_by_source
+            .iter()
+            .rev()
+            .zip(self.source_names.iter())
+            .filter_map(|(nodes, name)| {
+                nodes
+                    .get(identity)
+                    .map(|node| (name.as_str(), node.clone()))
+            })
+            .collect();
+        merge_definitions(self.merge_strategy, definitions).map(|(node, _)| node)
+    }
+
+    /// Gets the unified definition of the node with the given identity, merging metadata in the
+    /// same way as `UnifiedRegistry::fetch_node`.
+    #[cfg(not(feature = "registry-merge"))]
+    fn unified_node(&self, identity: &str) -> Option<Node> {
+        self.nodes_by_source
+            .iter()
+            .filter_map(|nodes| nodes.get(identity).cloned())
+            .fold(None, |final_opt, mut node| {
+                if let Some(existing) = final_opt {
+                    let mut merged_metadata = existing.metadata;
+                    merged_metadata.extend(node.metadata);
+                    node.metadata = merged_metadata;
+                }
+                Some(node)
+            })
+    }
+}
+
+/// Applies the events of a source registry to the unified registry's snapshot and sends the
+/// resulting changes to the unified registry's subscribers.
+#[cfg(feature = "registry-events")]
+struct SourceEventForwarder {
+    /// The position of the source in `SourceNodes::nodes_by_source`
+    index: usize,
+    source_nodes: Weak<Mutex<Option<SourceNodes>>>,
+}
+
+#[cfg(feature = "registry-events")]
+impl RegistryEventSubscriber for SourceEventForwarder {
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+        // If the unified registry has been dropped, there is no one left to notify
+        let source_nodes = self
+            .source_nodes
+            .upgrade()
+            .ok_or(RegistrySubscriberError::Unsubscribe)?;
+        let mut source_nodes = source_nodes.lock().map_err(|_| {
+            RegistrySubscriberError::UnableToHandleEvent(
+                "Unified registry's event lock poisoned".into(),
+            )
+        })?;
+        let source_nodes = match source_nodes.as_mut() {
+            Some(source_nodes) => source_nodes,
+            None => return Ok(()),
+        };
+
+        let identity = event.node().identity.clone();
+        let previous = source_nodes.unified_node(&identity);
+
+        let nodes = source_nodes
+            .nodes_by_source
+            .get_mut(self.index)
+            .ok_or(RegistrySubscriberError::Unsubscribe)?;
+        match event {
+            RegistryEvent::NodeAdded(node) | RegistryEvent::NodeUpdated { node, .. } => {
+                nodes.insert(identity.clone(), node.clone());
+            }
+            RegistryEvent::NodeRemoved(_) => {
+                nodes.remove(&identity);
+            }
+        }
+
+        let current = source_nodes.unified_node(&identity);
+        source_nodes.subscribers.broadcast(diff_nodes(
+            &previous.into_iter().collect::<Vec<_>>(),
+            &current.into_iter().collect::<Vec<_>>(),
+        ));
+
+        Ok(())
+    }
 }
 
 impl RegistryWriter for UnifiedRegistry {
@@ -185,6 +514,26 @@ impl RegistryWriter for UnifiedRegistry {
     fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
         self.internal_source.delete_node(identity)
     }
+
+    #[cfg(feature = "registry-node-signing")]
+    fn delete_node_with_removal(
+        &self,
+        identity: &str,
+        removal: Option<&NodeSignature>,
+    ) -> Result<Option<Node>, RegistryError> {
+        self.internal_source
+            .delete_node_with_removal(identity, removal)
+    }
+
+    #[cfg(feature = "registry-bulk")]
+    fn insert_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError> {
+        self.internal_source.insert_nodes(nodes)
+    }
+
+    #[cfg(feature = "registry-bulk")]
+    fn delete_nodes(&self, identities: &[String]) -> Result<Vec<Node>, RegistryError> {
+        self.internal_source.delete_nodes(identities)
+    }
 }
 
 impl RwRegistry for UnifiedRegistry {
@@ -615,6 +964,217 @@ mod test {
             .expect("Unable to check writeable for node1"));
     }
 
+    /// Verify that the unified registry combines nodes with its merge strategy, and reports the
+    /// provenance of a node's fields and the conflicts between its sources.
+    ///
+    /// 1. Add the same node to the internal registry and a read-only registry with different
+    ///    endpoints and keys, and add a node that only exists in the read-only registry.
+    /// 2. Verify that the conflict is reported with the names of the sources.
+    /// 3. Verify the node's definition and provenance with each merge strategy, and that the
+    ///    conflicting node is left out with the reject-conflicts strategy.
+    #[cfg(feature = "registry-merge")]
+    #[test]
+    fn merge_strategies() {
+        let internal_node = new_node("node1", "endpoint1", &[("meta_a", "val_a")]);
+        let mut external_node = new_node("node1", "endpoint2", &[("meta_b", "val_b")]);
+        external_node.keys = vec!["0123".into()];
+        let other_node = new_node("node2", "endpoint3", &[]);
+
+        let writable = MemRegistry::default();
+        writable
+            .insert_node(internal_node.clone())
+            .expect("Unable to insert node");
+        let readable = MemRegistry::default();
+        readable
+            .insert_node(external_node.clone())
+            .expect("Unable to insert node");
+        readable
+            .insert_node(other_node)
+            .expect("Unable to insert node");
+
+        let unified = |strategy| {
+            UnifiedRegistry::new(Box::new(writable.clone()), vec![Box::new(readable.clone())])
+                .with_merge_strategy(strategy)
+                .with_source_names(vec!["local".into(), "remote".into()])
+        };
+
+        let conflicts = unified(MergeStrategy::Priority)
+            .list_conflicts()
+            .expect("Unable to list conflicts");
+        assert_eq!(
+            conflicts,
+            vec![NodeConflict {
+                identity: "node1".into(),
+                fields: vec!["endpoints".into(), "keys".into()],
+                definitions: vec![
+                    ("local".into(), internal_node.clone()),
+                    ("remote".into(), external_node),
+                ],
+            }]
+        );
+
+        let registry = unified(MergeStrategy::Priority);
+        let node = registry
+            .fetch_node("node1")
+            .expect("Unable to fetch node")
+            .expect("Node not found");
+        assert_eq!(node.endpoints, vec!["endpoint1".to_string()]);
+        assert_eq!(node.metadata.len(), 2);
+        let provenance = registry
+            .fetch_node_provenance("node1")
+            .expect("Unable to fetch provenance")
+            .expect("Provenance not found");
+        assert_eq!(provenance.endpoints, vec!["local".to_string()]);
+        assert_eq!(
+            provenance.metadata.get("meta_b").map(String::as_str),
+            Some("remote")
+        );
+
+        let registry = unified(MergeStrategy::FieldMerge);
+        let node = registry
+            .fetch_node("node1")
+            .expect("Unable to fetch node")
+            .expect("Node not found");
+        assert_eq!(
+            node.endpoints,
+            vec!["endpoint1".to_string(), "endpoint2".to_string()]
+        );
+        assert_eq!(node.keys, vec!["abcd".to_string(), "0123".to_string()]);
+
+        let registry = unified(MergeStrategy::RejectConflicts);
+        assert!(registry
+            .fetch_node("node1")
+            .expect("Unable to fetch node")
+            .is_none());
+        assert_eq!(
+            registry
+                .list_nodes(&[])
+                .expect("Unable to list nodes")
+                .map(|node| node.identity)
+                .collect::<Vec<_>>(),
+            vec!["node2".to_string()]
+        );
+    }
+
+    /// Verify that the subscribers of a unified registry are notified of the changes to the unified
+    /// view of the nodes in its sources.
+    ///
+    /// 1. Create a unified registry from an internal and an external YAML registry and subscribe
+    ///    to its events.
+    /// 2. Add a node to the external registry and verify that it is reported as added.
+    /// 3. Add the node to the internal registry and verify that it is reported as updated, with the
+    ///    internal registry's endpoint and the merged metadata.
+    /// 4. Change the node's endpoint in the external registry and verify that nothing is reported,
+    ///    since the change is hidden by the internal registry.
+    /// 5. Remove the node from the internal registry, then from the external registry, and verify
+    ///    that it is reported as updated and then removed.
+    #[cfg(feature = "registry-events")]
+    #[test]
+    fn event_subscriber() {
+        use std::sync::mpsc::{channel, Receiver, Sender};
+        use std::time::Duration;
+
+        use tempdir::TempDir;
+
+        use crate::registry::LocalYamlRegistry;
+
+        struct ChannelSubscriber(Sender<RegistryEvent>);
+
+        impl RegistryEventSubscriber for ChannelSubscriber {
+            fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+                self.0
+                    .send(event.clone())
+                    .map_err(|_| RegistrySubscriberError::Unsubscribe)
+            }
+        }
+
+        let temp_dir = TempDir::new("unified_event_subscriber").expect("Failed to create temp dir");
+        let path = |name: &str| {
+            temp_dir
+                .path()
+                .join(name)
+                .to_str()
+                .expect("Failed to get path")
+                .to_string()
+        };
+        let internal = LocalYamlRegistry::new(&path("internal.yaml"))
+            .expect("Failed to create internal registry");
+        let external = LocalYamlRegistry::new(&path("external.yaml"))
+            .expect("Failed to create external registry");
+
+        let unified =
+            UnifiedRegistry::new(Box::new(internal.clone()), vec![Box::new(external.clone())]);
+        let (sender, receiver) = channel();
+        unified
+            .add_event_subscriber(Box::new(ChannelSubscriber(sender)))
+            .expect("Failed to add subscriber");
+        // Each source delivers its events on its own thread, so every change is awaited before
+        // the next one is made
+        let receive = |receiver: &Receiver<RegistryEvent>| {
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("No event received")
+        };
+
+        let external_node = new_node("node1", "endpoint1", &[("meta_a", "val_a")]);
+        let external_node_moved = new_node("node1", "endpoint3", &[("meta_a", "val_a")]);
+        let internal_node = new_node("node1", "endpoint2", &[("meta_b", "val_b")]);
+        let merged_node = new_node(
+            "node1",
+            "endpoint2",
+            &[("meta_a", "val_a"), ("meta_b", "val_b")],
+        );
+
+        external
+            .insert_node(external_node.clone())
+            .expect("Unable to insert external node");
+        assert_eq!(
+            receive(&receiver),
+            RegistryEvent::NodeAdded(external_node.clone())
+        );
+
+        internal
+            .insert_node(internal_node)
+            .expect("Unable to insert internal node");
+        assert_eq!(
+            receive(&receiver),
+            RegistryEvent::NodeUpdated {
+                previous: external_node,
+                node: merged_node.clone(),
+            }
+        );
+
+        // The unified registry's subscriber to the external registry was added first, so it has
+        // handled the change once this subscriber receives it
+        let (external_sender, external_receiver) = channel();
+        external
+            .add_event_subscriber(Box::new(ChannelSubscriber(external_sender)))
+            .expect("Failed to add subscriber");
+        external
+            .insert_node(external_node_moved.clone())
+            .expect("Unable to update external node");
+        receive(&external_receiver);
+
+        internal
+            .delete_node("node1")
+            .expect("Unable to delete internal node");
+        assert_eq!(
+            receive(&receiver),
+            RegistryEvent::NodeUpdated {
+                previous: merged_node,
+                node: external_node_moved.clone(),
+            }
+        );
+
+        external
+            .delete_node("node1")
+            .expect("Unable to delete external node");
+        assert_eq!(
+            receive(&receiver),
+            RegistryEvent::NodeRemoved(external_node_moved)
+        );
+    }
+
     #[derive(Clone, Default)]
     struct MemRegistry {
         nodes: Arc<Mutex<HashMap<String, Node>>>,
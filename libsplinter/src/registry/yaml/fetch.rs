@@ -0,0 +1,739 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fetching of remote registry files.
+
+#[cfg(feature = "registry-remote-auth")]
+use std::fs::File;
+use std::time::SystemTime;
+
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+#[cfg(feature = "registry-remote-auth")]
+use reqwest::redirect::Policy;
+use reqwest::StatusCode;
+
+use crate::registry::RegistryError;
+
+/// The URL scheme of registry files on the local filesystem.
+pub(super) const FILE_SCHEME: &str = "file://";
+/// The URL scheme of registry files that are fetched over HTTPS.
+#[cfg(feature = "registry-remote-auth")]
+const HTTPS_SCHEME: &str = "https://";
+/// The number of redirects that are followed when fetching with credentials, matching the
+/// default redirect policy.
+#[cfg(feature = "registry-remote-auth")]
+const MAX_REDIRECTS: usize = 10;
+
+/// Settings for fetching remote registry files from an authenticated server.
+///
+/// A configuration applies to every registry URL that starts with its `url_prefix`. The
+/// configurations can be loaded from a YAML file of the following form:
+///
+/// ```yaml
+/// - url_prefix: https://artifacts.example.com/registries/
+///   # Optional; at most one of bearer_token and basic_auth may be set
+///   bearer_token: secret-token
+///   basic_auth:
+///     username: splinter
+///     password: secret
+///   # Optional; PEM file with additional CA certificates to trust
+///   ca_certificate: /etc/splinter/certs/registry-ca.pem
+///   # Optional; PKCS #12 archive with the client certificate and its private key
+///   client_certificate: /etc/splinter/certs/registry-client.p12
+///   client_certificate_password: secret
+///   # Optional; credentials are only sent to HTTPS URLs unless this is set to true
+///   allow_insecure_credentials: false
+/// ```
+#[cfg(feature = "registry-remote-auth")]
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RemoteRegistryConfig {
+    url_prefix: String,
+    #[serde(default)]
+    bearer_token: Option<String>,
+    #[serde(default)]
+    basic_auth: Option<BasicAuth>,
+    #[serde(default)]
+    ca_certificate: Option<String>,
+    #[serde(default)]
+    client_certificate: Option<String>,
+    #[serde(default)]
+    client_certificate_password: Option<String>,
+    #[serde(default)]
+    allow_insecure_credentials: bool,
+}
+
+/// Username and password sent in an `Authorization: Basic` header.
+#[cfg(feature = "registry-remote-auth")]
+#[derive(Clone, Debug, Deserialize)]
+struct BasicAuth {
+    username: String,
+    #[serde(default)]
+    password: Option<String>,
+}
+
+#[cfg(feature = "registry-remote-auth")]
+impl RemoteRegistryConfig {
+    /// Creates a new configuration for the registry URLs that start with the given prefix.
+    pub fn new<S: Into<String>>(url_prefix: S) -> Self {
+        RemoteRegistryConfig {
+            url_prefix: url_prefix.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Loads a list of configurations from a YAML file.
+    pub fn load_all(path: &str) -> Result<Vec<Self>, RegistryError> {
+        let file = File::open(path).map_err(|err| {
+            RegistryError::general_error_with_source(
+                &format!("Unable to open remote registry config {}", path),
+                Box::new(err),
+            )
+        })?;
+        let configs: Vec<Self> = serde_yaml::from_reader(file).map_err(|err| {
+            RegistryError::general_error_with_source(
+                &format!("Unable to parse remote registry config {}", path),
+                Box::new(err),
+            )
+        })?;
+
+        if let Some(config) = configs
+            .iter()
+            .find(|config| config.bearer_token.is_some() && config.basic_auth.is_some())
+        {
+            return Err(RegistryError::general_error(&format!(
+                "Remote registry config for {} sets both bearer_token and basic_auth",
+                config.url_prefix
+            )));
+        }
+
+        Ok(configs)
+    }
+
+    /// Sends the given token in an `Authorization: Bearer` header; replaces any basic auth.
+    pub fn with_bearer_token<S: Into<String>>(mut self, token: S) -> Self {
+        self.bearer_token = Some(token.into());
+        self.basic_auth = None;
+        self
+    }
+
+    /// Sends the given username and password in an `Authorization: Basic` header; replaces any
+    /// bearer token.
+    pub fn with_basic_auth<S: Into<String>>(mut self, username: S, password: Option<S>) -> Self {
+        self.basic_auth = Some(BasicAuth {
+            username: username.into(),
+            password: password.map(Into::into),
+        });
+        self.bearer_token = None;
+        self
+    }
+
+    /// Trusts the CA certificates in the given PEM file, in addition to the system's.
+    pub fn with_ca_certificate<S: Into<String>>(mut self, path: S) -> Self {
+        self.ca_certificate = Some(path.into());
+        self
+    }
+
+    /// Presents the client certificate in the given PKCS #12 archive.
+    pub fn with_client_certificate<S: Into<String>>(mut self, path: S, password: S) -> Self {
+        self.client_certificate = Some(path.into());
+        self.client_certificate_password = Some(password.into());
+        self
+    }
+
+    /// Allows the bearer token or basic auth credentials to be sent to registry URLs that do not
+    /// use HTTPS, where they are visible to anyone on the network.
+    pub fn with_allow_insecure_credentials(mut self, allow: bool) -> Self {
+        self.allow_insecure_credentials = allow;
+        self
+    }
+
+    /// Returns the prefix of the registry URLs that the configuration applies to.
+    pub fn url_prefix(&self) -> &str {
+        &self.url_prefix
+    }
+
+    /// Returns `true` if the configuration applies to the given registry URL.
+    pub fn matches(&self, url: &str) -> bool {
+        url.starts_with(&self.url_prefix)
+    }
+
+    /// Returns `true` if the configuration sends credentials with its requests.
+    fn has_credentials(&self) -> bool {
+        self.bearer_token.is_some() || self.basic_auth.is_some()
+    }
+
+    /// Returns `true` if credentials may only be sent to HTTPS URLs.
+    fn requires_https(&self) -> bool {
+        self.has_credentials() && !self.allow_insecure_credentials
+    }
+}
+
+/// Returns `true` if the given URL uses HTTPS.
+#[cfg(feature = "registry-remote-auth")]
+fn is_https(url: &str) -> bool {
+    url.get(..HTTPS_SCHEME.len())
+        .map(|scheme| scheme.eq_ignore_ascii_case(HTTPS_SCHEME))
+        .unwrap_or(false)
+}
+
+/// Identifies the version of a fetched registry file, so that it is only downloaded again if it
+/// has changed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    file_modified: Option<SystemTime>,
+}
+
+/// Fetches registry files over HTTP(S) or from the local filesystem.
+pub(super) struct Fetcher {
+    client: Client,
+    #[cfg(feature = "registry-remote-auth")]
+    config: Option<RemoteRegistryConfig>,
+}
+
+impl Fetcher {
+    pub fn new() -> Result<Self, RegistryError> {
+        Ok(Fetcher {
+            client: Client::builder().build().map_err(|err| {
+                RegistryError::general_error_with_source(
+                    "Failed to create HTTP client for remote registry",
+                    Box::new(err),
+                )
+            })?,
+            #[cfg(feature = "registry-remote-auth")]
+            config: None,
+        })
+    }
+
+    /// Creates a fetcher that authenticates with the settings of the given configuration.
+    #[cfg(feature = "registry-remote-auth")]
+    pub fn with_config(config: RemoteRegistryConfig) -> Result<Self, RegistryError> {
+        let mut builder = Client::builder();
+
+        if let Some(path) = &config.ca_certificate {
+            let pem = std::fs::read(path).map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to read CA certificate {}", path),
+                    Box::new(err),
+                )
+            })?;
+            let certificate = reqwest::Certificate::from_pem(&pem).map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to parse CA certificate {}", path),
+                    Box::new(err),
+                )
+            })?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        if let Some(path) = &config.client_certificate {
+            let der = std::fs::read(path).map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to read client certificate {}", path),
+                    Box::new(err),
+                )
+            })?;
+            let identity = reqwest::Identity::from_pkcs12_der(
+                &der,
+                config
+                    .client_certificate_password
+                    .as_deref()
+                    .unwrap_or_default(),
+            )
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    &format!("Failed to parse client certificate {}", path),
+                    Box::new(err),
+                )
+            })?;
+            builder = builder.identity(identity);
+        }
+
+        if config.requires_https() {
+            // The credentials must not follow a redirect to an insecure URL
+            builder = builder.redirect(Policy::custom(|attempt| {
+                if attempt.url().scheme() != "https" {
+                    attempt.error("refusing to send registry credentials to an insecure URL")
+                } else if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else {
+                    attempt.follow()
+                }
+            }));
+        }
+
+        Ok(Fetcher {
+            client: builder.build().map_err(|err| {
+                RegistryError::general_error_with_source(
+                    "Failed to create HTTP client for remote registry",
+                    Box::new(err),
+                )
+            })?,
+            config: Some(config),
+        })
+    }
+
+    /// Fetches the file at the given URL. If `validators` are given and the file has not changed
+    /// since it was fetched with them, `None` is returned; otherwise the file's contents are
+    /// returned with the validators of the new version.
+    pub fn fetch(
+        &self,
+        url: &str,
+        validators: Option<&Validators>,
+    ) -> Result<Option<(Vec<u8>, Validators)>, RegistryError> {
+        if let Some(path) = url.strip_prefix(FILE_SCHEME) {
+            fetch_file(path, validators)
+        } else {
+            self.fetch_http(url, validators)
+        }
+    }
+
+    /// Fetches the whole file at the given URL.
+    #[cfg(feature = "registry-signing")]
+    pub fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>, RegistryError> {
+        self.fetch(url, None)?
+            .map(|(bytes, _)| bytes)
+            .ok_or_else(|| {
+                RegistryError::general_error(&format!("Failed to fetch remote file from {}", url))
+            })
+    }
+
+    fn fetch_http(
+        &self,
+        url: &str,
+        validators: Option<&Validators>,
+    ) -> Result<Option<(Vec<u8>, Validators)>, RegistryError> {
+        let mut request = self.client.get(url);
+
+        #[cfg(feature = "registry-remote-auth")]
+        {
+            if let Some(config) = &self.config {
+                if config.requires_https() && !is_https(url) {
+                    return Err(RegistryError::general_error(&format!(
+                        "Refusing to send registry credentials to {} because it does not use \
+                         HTTPS; set allow_insecure_credentials to allow it",
+                        url
+                    )));
+                }
+
+                if let Some(token) = &config.bearer_token {
+                    request = request.bearer_auth(token);
+                } else if let Some(basic_auth) = &config.basic_auth {
+                    request =
+                        request.basic_auth(&basic_auth.username, basic_auth.password.as_ref());
+                }
+            }
+        }
+
+        if let Some(validators) = validators {
+            if let Some(etag) = &validators.etag {
+                request = request.header(IF_NONE_MATCH, etag.as_str());
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+            }
+        }
+
+        let response = request.send().map_err(|err| {
+            RegistryError::general_error_with_source(
+                &format!("Failed to fetch remote file from {}", url),
+                Box::new(err),
+            )
+        })?;
+
+        if validators.is_some() && response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let response = response.error_for_status().map_err(|err| {
+            RegistryError::general_error_with_source(
+                &format!("Failed to fetch remote file from {}", url),
+                Box::new(err),
+            )
+        })?;
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        };
+        let new_validators = Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            file_modified: None,
+        };
+
+        let bytes = response.bytes().map_err(|err| {
+            RegistryError::general_error_with_source(
+                "Failed to get bytes from remote file HTTP response",
+                Box::new(err),
+            )
+        })?;
+
+        Ok(Some((bytes.to_vec(), new_validators)))
+    }
+}
+
+/// Reads the file at the given path, unless its modification time matches the given validators.
+fn fetch_file(
+    path: &str,
+    validators: Option<&Validators>,
+) -> Result<Option<(Vec<u8>, Validators)>, RegistryError> {
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|err| {
+            RegistryError::general_error_with_source(
+                &format!("Failed to read modification time of registry file {}", path),
+                Box::new(err),
+            )
+        })?;
+
+    if validators.and_then(|validators| validators.file_modified) == Some(modified) {
+        return Ok(None);
+    }
+
+    let bytes = std::fs::read(path).map_err(|err| {
+        RegistryError::general_error_with_source(
+            &format!("Failed to read registry file {}", path),
+            Box::new(err),
+        )
+    })?;
+
+    Ok(Some((
+        bytes,
+        Validators {
+            file_modified: Some(modified),
+            ..Default::default()
+        },
+    )))
+}
+
+#[cfg(all(test, feature = "rest-api", feature = "rest-api-actix"))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "registry-remote-auth")]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "registry-remote-auth")]
+    use std::sync::Arc;
+
+    use actix_web::HttpResponse;
+    use futures::future::IntoFuture;
+    #[cfg(feature = "registry-remote-auth")]
+    use openssl::{
+        asn1::Asn1Time,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        x509::{X509NameBuilder, X509},
+    };
+    use tempdir::TempDir;
+
+    use crate::rest_api::{
+        Method, Resource, RestApiBuilder, RestApiServerError, RestApiShutdownHandle,
+    };
+
+    const REGISTRY_BYTES: &[u8] = b"- identity: node-1\n";
+    const ETAG_VALUE: &str = "\"v1\"";
+
+    /// Verifies that a file fetched over HTTP is only returned again if its entity tag has
+    /// changed.
+    ///
+    /// 1. Fetch the file without validators and verify that its contents and entity tag are
+    ///    returned
+    /// 2. Fetch the file with the returned validators and verify that nothing is returned
+    /// 3. Fetch the file with validators for another version and verify that it is returned
+    #[test]
+    fn conditional_http_fetch() {
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![Resource::build("/registry.yaml").add_method(
+                Method::Get,
+                |request, _| {
+                    let not_modified = request
+                        .headers()
+                        .get("If-None-Match")
+                        .map(|value| value == ETAG_VALUE)
+                        .unwrap_or(false);
+                    Box::new(if not_modified {
+                        HttpResponse::NotModified().finish().into_future()
+                    } else {
+                        HttpResponse::Ok()
+                            .header("ETag", ETAG_VALUE)
+                            .body(REGISTRY_BYTES)
+                            .into_future()
+                    })
+                },
+            )]);
+        let url = format!("http://{}/registry.yaml", bind_url);
+        let fetcher = Fetcher::new().expect("Failed to create fetcher");
+
+        let (bytes, validators) = fetcher
+            .fetch(&url, None)
+            .expect("Failed to fetch file")
+            .expect("File not returned");
+        assert_eq!(bytes, REGISTRY_BYTES);
+        assert_eq!(validators.etag.as_deref(), Some(ETAG_VALUE));
+
+        assert!(fetcher
+            .fetch(&url, Some(&validators))
+            .expect("Failed to fetch file")
+            .is_none());
+
+        let other_version = Validators {
+            etag: Some("\"v0\"".into()),
+            ..Default::default()
+        };
+        assert!(fetcher
+            .fetch(&url, Some(&other_version))
+            .expect("Failed to fetch file")
+            .is_some());
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Verifies that a file on the local filesystem is only returned again if its modification
+    /// time does not match the validators.
+    #[test]
+    fn conditional_file_fetch() {
+        let temp_dir = TempDir::new("conditional_file_fetch").expect("Failed to create temp dir");
+        let path = temp_dir.path().join("registry.yaml");
+        std::fs::write(&path, REGISTRY_BYTES).expect("Failed to write registry file");
+        let url = format!(
+            "{}{}",
+            FILE_SCHEME,
+            path.to_str().expect("Failed to get path")
+        );
+        let fetcher = Fetcher::new().expect("Failed to create fetcher");
+
+        let (bytes, validators) = fetcher
+            .fetch(&url, None)
+            .expect("Failed to fetch file")
+            .expect("File not returned");
+        assert_eq!(bytes, REGISTRY_BYTES);
+        assert!(validators.file_modified.is_some());
+
+        assert!(fetcher
+            .fetch(&url, Some(&validators))
+            .expect("Failed to fetch file")
+            .is_none());
+        assert!(fetcher
+            .fetch(&url, Some(&Validators::default()))
+            .expect("Failed to fetch file")
+            .is_some());
+    }
+
+    /// Verifies that the configured bearer token or basic auth credentials are sent with the
+    /// request, and that the request fails without them.
+    #[cfg(feature = "registry-remote-auth")]
+    #[test]
+    fn auth_credentials_sent() {
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![Resource::build("/registry.yaml").add_method(
+                Method::Get,
+                |request, _| {
+                    let authorized = request
+                        .headers()
+                        .get("Authorization")
+                        .map(|value| {
+                            value == "Bearer secret-token" || value == "Basic c3BsaW50ZXI6c2VjcmV0"
+                        })
+                        .unwrap_or(false);
+                    Box::new(if authorized {
+                        HttpResponse::Ok().body(REGISTRY_BYTES).into_future()
+                    } else {
+                        HttpResponse::Unauthorized().finish().into_future()
+                    })
+                },
+            )]);
+        let url = format!("http://{}/registry.yaml", bind_url);
+
+        assert!(Fetcher::new()
+            .expect("Failed to create fetcher")
+            .fetch(&url, None)
+            .is_err());
+
+        let bearer_config = RemoteRegistryConfig::new(format!("http://{}/", bind_url))
+            .with_bearer_token("secret-token")
+            .with_allow_insecure_credentials(true);
+        let (bytes, _) = Fetcher::with_config(bearer_config)
+            .expect("Failed to create fetcher")
+            .fetch(&url, None)
+            .expect("Failed to fetch file with bearer token")
+            .expect("File not returned");
+        assert_eq!(bytes, REGISTRY_BYTES);
+
+        let basic_config = RemoteRegistryConfig::new(format!("http://{}/", bind_url))
+            .with_basic_auth("splinter", Some("secret"))
+            .with_allow_insecure_credentials(true);
+        let (bytes, _) = Fetcher::with_config(basic_config)
+            .expect("Failed to create fetcher")
+            .fetch(&url, None)
+            .expect("Failed to fetch file with basic auth")
+            .expect("File not returned");
+        assert_eq!(bytes, REGISTRY_BYTES);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Verifies that credentials are not sent to a URL that does not use HTTPS unless insecure
+    /// credentials are allowed, and that a configuration without credentials may be used with
+    /// such a URL.
+    ///
+    /// 1. Fetch the file with a bearer token and verify that the fetch fails without sending a
+    ///    request
+    /// 2. Fetch the file with a configuration that has no credentials and verify that it is
+    ///    returned
+    #[cfg(feature = "registry-remote-auth")]
+    #[test]
+    fn credentials_require_https() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let request_count = requests.clone();
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![Resource::build("/registry.yaml").add_method(
+                Method::Get,
+                move |_, _| {
+                    request_count.fetch_add(1, Ordering::SeqCst);
+                    Box::new(HttpResponse::Ok().body(REGISTRY_BYTES).into_future())
+                },
+            )]);
+        let url = format!("http://{}/registry.yaml", bind_url);
+
+        let config = RemoteRegistryConfig::new(format!("http://{}/", bind_url))
+            .with_bearer_token("secret-token");
+        assert!(Fetcher::with_config(config)
+            .expect("Failed to create fetcher")
+            .fetch(&url, None)
+            .is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        let config = RemoteRegistryConfig::new(format!("http://{}/", bind_url));
+        assert!(Fetcher::with_config(config)
+            .expect("Failed to create fetcher")
+            .fetch(&url, None)
+            .expect("Failed to fetch file")
+            .is_some());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        assert!(is_https("HTTPS://registry.example.com/registry.yaml"));
+        assert!(!is_https("http://registry.example.com/registry.yaml"));
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Verifies that a configured CA certificate bundle must exist and contain a valid PEM
+    /// certificate.
+    #[cfg(feature = "registry-remote-auth")]
+    #[test]
+    fn ca_certificate_bundle() {
+        let temp_dir = TempDir::new("ca_certificate_bundle").expect("Failed to create temp dir");
+        let path = |name| {
+            temp_dir
+                .path()
+                .join(name)
+                .to_str()
+                .expect("Failed to get path")
+                .to_string()
+        };
+
+        let missing = RemoteRegistryConfig::new("https://").with_ca_certificate(path("missing"));
+        assert!(Fetcher::with_config(missing).is_err());
+
+        std::fs::write(path("invalid.pem"), b"not a certificate")
+            .expect("Failed to write invalid bundle");
+        let invalid =
+            RemoteRegistryConfig::new("https://").with_ca_certificate(path("invalid.pem"));
+        assert!(Fetcher::with_config(invalid).is_err());
+
+        std::fs::write(path("ca.pem"), create_ca_certificate()).expect("Failed to write bundle");
+        let valid = RemoteRegistryConfig::new("https://").with_ca_certificate(path("ca.pem"));
+        assert!(Fetcher::with_config(valid).is_ok());
+    }
+
+    /// Creates a self-signed CA certificate in PEM format.
+    #[cfg(feature = "registry-remote-auth")]
+    fn create_ca_certificate() -> Vec<u8> {
+        let group =
+            EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).expect("Failed to create EC group");
+        let key = PKey::from_ec_key(EcKey::generate(&group).expect("Failed to generate key"))
+            .expect("Failed to create key");
+
+        let mut name = X509NameBuilder::new().expect("Failed to create name builder");
+        name.append_entry_by_text("CN", "registry-ca")
+            .expect("Failed to set common name");
+        let name = name.build();
+
+        let mut builder = X509::builder().expect("Failed to create certificate builder");
+        builder.set_version(2).expect("Failed to set version");
+        builder
+            .set_subject_name(&name)
+            .expect("Failed to set subject");
+        builder
+            .set_issuer_name(&name)
+            .expect("Failed to set issuer");
+        builder.set_pubkey(&key).expect("Failed to set public key");
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).expect("Failed to create time"))
+            .expect("Failed to set start time");
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).expect("Failed to create time"))
+            .expect("Failed to set end time");
+        builder
+            .sign(&key, MessageDigest::sha256())
+            .expect("Failed to sign certificate");
+
+        builder
+            .build()
+            .to_pem()
+            .expect("Failed to encode certificate")
+    }
+
+    /// Runs a REST API with the given `resources` on an open port. Returned string is the URL the
+    /// REST API is bound to.
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        (10000..20000)
+            .find_map(|port| {
+                let bind_url = format!("127.0.0.1:{}", port);
+                let result = RestApiBuilder::new()
+                    .with_bind(&bind_url)
+                    .add_resources(resources.clone())
+                    .build()
+                    .expect("Failed to build REST API")
+                    .run();
+                match result {
+                    Ok((shutdown_handle, join_handle)) => {
+                        Some((shutdown_handle, join_handle, bind_url))
+                    }
+                    Err(RestApiServerError::BindError(_)) => None,
+                    Err(err) => panic!("Failed to run REST API: {}", err),
+                }
+            })
+            .expect("No port available")
+    }
+}
@@ -23,13 +23,21 @@
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
+#[cfg(feature = "registry-events")]
+use std::sync::MutexGuard;
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
+#[cfg(feature = "registry-events")]
+use crate::registry::events::{diff_nodes, RegistryEventSubscribers};
+#[cfg(feature = "registry-node-signing")]
+use crate::registry::node_signature::check_node_signature;
 use crate::registry::{
     validate_nodes, MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader,
     RegistryWriter, RwRegistry,
 };
+#[cfg(feature = "registry-events")]
+use crate::registry::{RegistryEvent, RegistryEventSubscriber};
 
 /// A local, read/write registry.
 ///
@@ -46,10 +54,16 @@ use crate::registry::{
 /// file already exists, the registry will attempt to load, parse, and validate it. If the backing
 /// file does not already exist, the registry will attempt to create it.
 ///
+/// Event subscribers are notified of the changes made through the registry, as well as the changes
+/// made to the backing file by other means; the latter are detected when the registry is next
+/// read.
+///
 /// [`Node`]: struct.Node.html
 #[derive(Clone)]
 pub struct LocalYamlRegistry {
     internal: Arc<Mutex<Internal>>,
+    #[cfg(feature = "registry-events")]
+    subscribers: RegistryEventSubscribers,
 }
 
 impl LocalYamlRegistry {
@@ -64,24 +78,41 @@ impl LocalYamlRegistry {
     pub fn new(file_path: &str) -> Result<LocalYamlRegistry, RegistryError> {
         Ok(LocalYamlRegistry {
             internal: Arc::new(Mutex::new(Internal::new(file_path)?)),
+            #[cfg(feature = "registry-events")]
+            subscribers: RegistryEventSubscribers::default(),
         })
     }
 
     /// Get all nodes in the registry.
     pub(super) fn get_nodes(&self) -> Result<Vec<Node>, RegistryError> {
-        Ok(self
+        let mut internal = self
             .internal
             .lock()
-            .map_err(|_| RegistryError::general_error("YAML registry's internal lock poisoned"))?
-            .get_nodes())
+            .map_err(|_| RegistryError::general_error("YAML registry's internal lock poisoned"))?;
+        let nodes = internal.get_nodes();
+        #[cfg(feature = "registry-events")]
+        self.broadcast_pending_events(internal);
+        Ok(nodes)
     }
 
     /// Write the given list of nodes to the backing YAML file.
     pub(super) fn write_nodes(&self, nodes: Vec<Node>) -> Result<(), RegistryError> {
-        self.internal
+        let mut internal = self
+            .internal
             .lock()
-            .map_err(|_| RegistryError::general_error("YAML registry's internal lock poisoned"))?
-            .write_nodes(nodes)
+            .map_err(|_| RegistryError::general_error("YAML registry's internal lock poisoned"))?;
+        let result = internal.write_nodes(nodes);
+        #[cfg(feature = "registry-events")]
+        self.broadcast_pending_events(internal);
+        result
+    }
+
+    /// Send the events for the changes to the cached nodes to the subscribers. The events are
+    /// queued while the internal lock is held, so they are delivered in the order of the changes.
+    #[cfg(feature = "registry-events")]
+    fn broadcast_pending_events(&self, mut internal: MutexGuard<Internal>) {
+        let events = std::mem::replace(&mut internal.pending_events, vec![]);
+        self.subscribers.broadcast(events);
     }
 }
 
@@ -110,11 +141,26 @@ impl RegistryReader for LocalYamlRegistry {
             .filter(move |node| predicates.iter().all(|predicate| predicate.apply(node)))
             .count() as u32)
     }
+
+    #[cfg(feature = "registry-events")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.subscribers.add(subscriber)
+    }
 }
 
 impl RegistryWriter for LocalYamlRegistry {
     fn insert_node(&self, node: Node) -> Result<(), RegistryError> {
         let mut nodes = self.get_nodes()?;
+        #[cfg(feature = "registry-node-signing")]
+        check_node_signature(
+            &node,
+            nodes
+                .iter()
+                .find(|existing_node| existing_node.identity == node.identity),
+        )?;
         // If a node with the same identity already exists, remove it
         nodes.retain(|existing_node| existing_node.identity != node.identity);
         nodes.push(node);
@@ -136,6 +182,41 @@ impl RegistryWriter for LocalYamlRegistry {
 
         Ok(opt)
     }
+
+    #[cfg(feature = "registry-bulk")]
+    fn insert_nodes(&self, new_nodes: Vec<Node>) -> Result<(), RegistryError> {
+        let mut nodes = self.get_nodes()?;
+        #[cfg(feature = "registry-node-signing")]
+        for node in &new_nodes {
+            check_node_signature(
+                node,
+                nodes
+                    .iter()
+                    .find(|existing_node| existing_node.identity == node.identity),
+            )?;
+        }
+        // Replace any existing nodes with the same identities; the file is only written if the
+        // resulting registry is valid, so either all of the nodes are inserted or none are
+        nodes.retain(|existing_node| {
+            !new_nodes
+                .iter()
+                .any(|node| node.identity == existing_node.identity)
+        });
+        nodes.extend(new_nodes);
+        self.write_nodes(nodes)
+    }
+
+    #[cfg(feature = "registry-bulk")]
+    fn delete_nodes(&self, identities: &[String]) -> Result<Vec<Node>, RegistryError> {
+        let (deleted, nodes): (Vec<Node>, Vec<Node>) = self
+            .get_nodes()?
+            .into_iter()
+            .partition(|node| identities.contains(&node.identity));
+
+        self.write_nodes(nodes)?;
+
+        Ok(deleted)
+    }
 }
 
 impl RwRegistry for LocalYamlRegistry {
@@ -157,6 +238,9 @@ struct Internal {
     file_path: String,
     cached_nodes: Vec<Node>,
     last_read: SystemTime,
+    /// The changes to the cached nodes that have not been sent to the subscribers yet
+    #[cfg(feature = "registry-events")]
+    pending_events: Vec<RegistryEvent>,
 }
 
 impl Internal {
@@ -165,6 +249,8 @@ impl Internal {
             file_path: file_path.into(),
             cached_nodes: vec![],
             last_read: SystemTime::UNIX_EPOCH,
+            #[cfg(feature = "registry-events")]
+            pending_events: vec![],
         };
 
         // If file already exists, read it; otherwise initialize it.
@@ -174,6 +260,10 @@ impl Internal {
             internal.write_nodes(vec![])?;
         }
 
+        // The initial contents of the file are not changes
+        #[cfg(feature = "registry-events")]
+        internal.pending_events.clear();
+
         Ok(internal)
     }
 
@@ -225,8 +315,7 @@ impl Internal {
 
         validate_nodes(&nodes)?;
 
-        self.cached_nodes = nodes;
-        self.last_read = SystemTime::now();
+        self.set_cached_nodes(nodes);
 
         Ok(())
     }
@@ -260,11 +349,19 @@ impl Internal {
             )
         })?;
 
-        self.cached_nodes = nodes;
-        self.last_read = SystemTime::now();
+        self.set_cached_nodes(nodes);
 
         Ok(())
     }
+
+    /// Replace the cached nodes, recording the changes for the subscribers.
+    fn set_cached_nodes(&mut self, nodes: Vec<Node>) {
+        #[cfg(feature = "registry-events")]
+        self.pending_events
+            .extend(diff_nodes(&self.cached_nodes, &nodes));
+        self.cached_nodes = nodes;
+        self.last_read = SystemTime::now();
+    }
 }
 
 #[cfg(test)]
@@ -796,6 +893,71 @@ mod test {
         assert_eq!(nodes, vec![node]);
     }
 
+    ///
+    /// Verifies that a self-signed node is stored with its signature, and that it cannot be
+    /// replaced by an unsigned entry.
+    ///
+    #[cfg(feature = "registry-node-signing")]
+    #[test]
+    fn test_insert_node_signed() {
+        use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
+
+        use crate::hex::to_hex;
+        use crate::signing::{sawtooth::SawtoothSecp256k1RefSigner, Signer};
+
+        let temp_dir = TempDir::new("test_insert_node_signed").expect("Failed to create temp dir");
+        let path = temp_dir
+            .path()
+            .join("registry.yaml")
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+
+        let registry = LocalYamlRegistry::new(&path).expect("Failed to create LocalYamlRegistry");
+
+        let context = Secp256k1Context::new();
+        let signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(
+                "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088",
+            )
+            .expect("Failed to parse private key"),
+        )
+        .expect("Failed to create signer");
+
+        let unsigned = Node::builder("Node-123")
+            .with_endpoint("tcps://12.0.0.123:8431")
+            .with_key(to_hex(signer.public_key()))
+            .build()
+            .expect("Failed to build node");
+        let mut signed = unsigned.clone();
+        signed.sign(1, &signer).expect("Failed to sign node");
+
+        registry
+            .insert_node(signed.clone())
+            .expect("Failed to insert node");
+        assert_eq!(
+            registry
+                .fetch_node("Node-123")
+                .expect("Failed to fetch node"),
+            Some(signed.clone())
+        );
+
+        match registry.insert_node(unsigned) {
+            Err(RegistryError::InvalidNode(InvalidNodeError::InvalidSignature(_))) => {}
+            res => panic!(
+                "Result should have been Err(InvalidNodeError::InvalidSignature), got: {:?}",
+                res
+            ),
+        }
+        assert_eq!(
+            registry
+                .fetch_node("Node-123")
+                .expect("Failed to fetch node"),
+            Some(signed)
+        );
+    }
+
     ///
     /// Verifies that insert_node returns InvalidNodeError::DuplicateEndpoint when a node
     /// with the same endpoint already exists in the yaml file.
@@ -1089,6 +1251,90 @@ mod test {
         }
     }
 
+    ///
+    /// Verifies that insert_nodes adds new nodes and replaces existing ones, and that no nodes
+    /// are inserted if any of them is invalid.
+    ///
+    #[cfg(feature = "registry-bulk")]
+    #[test]
+    fn test_insert_nodes() {
+        let temp_dir = TempDir::new("test_insert_nodes").expect("Failed to create temp dir");
+        let path = temp_dir
+            .path()
+            .join("registry.yaml")
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+
+        write_to_file(&vec![get_node_1()], &path);
+
+        let registry = LocalYamlRegistry::new(&path).expect("Failed to create LocalYamlRegistry");
+
+        let mut updated_node = get_node_1();
+        updated_node.display_name = "Bitwise IO - Updated".into();
+        registry
+            .insert_nodes(vec![updated_node.clone(), get_node_2()])
+            .expect("Failed to insert nodes");
+
+        let nodes = registry
+            .list_nodes(&[])
+            .expect("Failed to retrieve nodes")
+            .collect::<Vec<_>>();
+        assert_eq!(nodes, vec![updated_node.clone(), get_node_2()]);
+
+        let mut invalid_node = get_node_3();
+        invalid_node.identity = "Node-000".into();
+        invalid_node.endpoints = get_node_2().endpoints;
+        match registry.insert_nodes(vec![get_node_3(), invalid_node]) {
+            Err(RegistryError::InvalidNode(InvalidNodeError::DuplicateEndpoint(_))) => {}
+            res => panic!(
+                "Should have gotten InvalidNodeError::DuplicateEndpoint but got {:?}",
+                res
+            ),
+        }
+
+        let nodes = registry
+            .list_nodes(&[])
+            .expect("Failed to retrieve nodes")
+            .collect::<Vec<_>>();
+        assert_eq!(nodes, vec![updated_node, get_node_2()]);
+    }
+
+    ///
+    /// Verifies that delete_nodes deletes the nodes with the given identities, ignores the
+    /// identities that are not in the registry, and returns the deleted nodes.
+    ///
+    #[cfg(feature = "registry-bulk")]
+    #[test]
+    fn test_delete_nodes() {
+        let temp_dir = TempDir::new("test_delete_nodes").expect("Failed to create temp dir");
+        let path = temp_dir
+            .path()
+            .join("registry.yaml")
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+
+        write_to_file(&vec![get_node_1(), get_node_2(), get_node_3()], &path);
+
+        let registry = LocalYamlRegistry::new(&path).expect("Failed to create LocalYamlRegistry");
+
+        let deleted = registry
+            .delete_nodes(&[
+                get_node_1().identity,
+                "NodeNotInRegistry".into(),
+                get_node_3().identity,
+            ])
+            .expect("Failed to delete nodes");
+        assert_eq!(deleted, vec![get_node_1(), get_node_3()]);
+
+        let nodes = registry
+            .list_nodes(&[])
+            .expect("Failed to retrieve nodes")
+            .collect::<Vec<_>>();
+        assert_eq!(nodes, vec![get_node_2()]);
+    }
+
     ///
     /// Verifies that if the YAML file does not exist on initialization, `LocalYamlRegistry` will
     /// create and initialize it as an empty registry.
@@ -1182,6 +1428,94 @@ mod test {
         assert_eq!(nodes, vec![get_node_1()]);
     }
 
+    ///
+    /// Verifies that event subscribers are notified of the nodes added, updated and removed through
+    /// the registry, and of the changes made to the backing file.
+    ///
+    #[cfg(feature = "registry-events")]
+    #[test]
+    fn test_event_subscriber() {
+        use std::sync::mpsc::{channel, Sender};
+
+        use crate::registry::RegistrySubscriberError;
+
+        struct ChannelSubscriber(Sender<RegistryEvent>);
+
+        impl RegistryEventSubscriber for ChannelSubscriber {
+            fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+                self.0
+                    .send(event.clone())
+                    .map_err(|_| RegistrySubscriberError::Unsubscribe)
+            }
+        }
+
+        let temp_dir = TempDir::new("test_event_subscriber").expect("Failed to create temp dir");
+        let path = temp_dir
+            .path()
+            .join("registry.yaml")
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+
+        write_to_file(&[get_node_1()], &path);
+
+        let registry = LocalYamlRegistry::new(&path).expect("Failed to create LocalYamlRegistry");
+        let (sender, receiver) = channel();
+        registry
+            .add_event_subscriber(Box::new(ChannelSubscriber(sender)))
+            .expect("Failed to add subscriber");
+
+        // Events are delivered by the registry's dispatch thread
+        let receive = |count| {
+            (0..count)
+                .map(|_| {
+                    receiver
+                        .recv_timeout(std::time::Duration::from_secs(5))
+                        .expect("No event received")
+                })
+                .collect::<Vec<_>>()
+        };
+
+        registry
+            .insert_node(get_node_2())
+            .expect("Unable to insert node");
+        let mut updated_node = get_node_1();
+        updated_node.endpoints = vec!["tcps://12.0.0.123:9431".into()];
+        registry
+            .insert_node(updated_node.clone())
+            .expect("Unable to update node");
+        registry
+            .delete_node(&get_node_2().identity)
+            .expect("Unable to delete node");
+
+        assert_eq!(
+            receive(3),
+            vec![
+                RegistryEvent::NodeAdded(get_node_2()),
+                RegistryEvent::NodeUpdated {
+                    previous: get_node_1(),
+                    node: updated_node.clone(),
+                },
+                RegistryEvent::NodeRemoved(get_node_2()),
+            ]
+        );
+
+        // Allow some time before writing the file to make sure the read time is earlier than the
+        // write time; the sytem clock may not be very precise.
+        std::thread::sleep(std::time::Duration::from_secs(1));
+
+        write_to_file(&[get_node_3()], &path);
+        registry.get_nodes().expect("Failed to get nodes");
+
+        assert_eq!(
+            receive(2),
+            vec![
+                RegistryEvent::NodeAdded(get_node_3()),
+                RegistryEvent::NodeRemoved(updated_node),
+            ]
+        );
+    }
+
     fn get_node_1() -> Node {
         Node::builder("Node-123")
             .with_endpoint("tcps://12.0.0.123:8431")
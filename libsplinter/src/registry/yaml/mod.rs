@@ -14,10 +14,20 @@
 
 //! YAML file-backed registry implementations.
 
+#[cfg(feature = "registry-remote")]
+mod fetch;
 mod local;
 #[cfg(feature = "registry-remote")]
 mod remote;
+#[cfg(feature = "registry-signing")]
+mod signature;
 
+#[cfg(feature = "registry-remote-auth")]
+pub use fetch::RemoteRegistryConfig;
 pub use local::LocalYamlRegistry;
 #[cfg(feature = "registry-remote")]
-pub use remote::{RemoteYamlRegistry, ShutdownHandle as RemoteYamlShutdownHandle};
+pub use remote::{
+    RemoteYamlRegistry, RemoteYamlRegistryBuilder, ShutdownHandle as RemoteYamlShutdownHandle,
+};
+#[cfg(feature = "registry-signing")]
+pub use signature::{read_public_key_file, RegistrySignature, SIGNATURE_FILE_SUFFIX};
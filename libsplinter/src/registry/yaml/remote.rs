@@ -15,11 +15,14 @@
 //! A remote, read-only registry.
 //!
 //! This module contains the [`RemoteYamlRegistry`], which provides an implementation of the
-//! [`RegistryReader`] trait.
+//! [`RegistryReader`] trait, and the [`RemoteYamlRegistryBuilder`] for configuring it.
 //!
 //! [`RemoteYamlRegistry`]: struct.RemoteYamlRegistry.html
+//! [`RemoteYamlRegistryBuilder`]: struct.RemoteYamlRegistryBuilder.html
 //! [`RegistryReader`]: ../../trait.RegistryReader.html
 
+#[cfg(feature = "registry-signing")]
+use std::fs;
 use std::path::Path;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -31,17 +34,27 @@ use std::time::{Duration, Instant};
 use openssl::hash::{hash, MessageDigest};
 
 use crate::hex::to_hex;
+#[cfg(feature = "registry-events")]
+use crate::registry::RegistryEventSubscriber;
 use crate::registry::{
     validate_nodes, MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader,
 };
-
+#[cfg(feature = "registry-signing")]
+use crate::signing::SignatureVerifier;
+
+#[cfg(feature = "registry-remote-auth")]
+use super::fetch::RemoteRegistryConfig;
+use super::fetch::{Fetcher, Validators, FILE_SCHEME};
+#[cfg(feature = "registry-signing")]
+use super::signature::{RegistrySignature, SIGNATURE_FILE_SUFFIX};
 use super::LocalYamlRegistry;
 
 /// A remote, read-only registry.
 ///
-/// The `RemoteYamlRegistry` provides access to a remote registry YAML file over HTTP(S). The remote
-/// registry file must be a YAML sequence of nodes, where each node is valid (see [`Node`] for
-/// validity criteria). Read operations are provided by the [`RegistryReader`] implementation.
+/// The `RemoteYamlRegistry` provides access to a remote registry YAML file over HTTP(S) or, with a
+/// `file://` URL, on the local filesystem. The remote registry file must be a YAML sequence of
+/// nodes, where each node is valid (see [`Node`] for validity criteria). Read operations are
+/// provided by the [`RegistryReader`] implementation.
 ///
 /// The remote YAML file is cached locally by saving it to the filesystem. This ensures that the
 /// registry will remain available even if the remote file becomes unreachable. The on-disk
@@ -63,13 +76,31 @@ use super::LocalYamlRegistry;
 /// attempt to cache the remote file on each read after the given time since the last successful
 /// cache attempt has elapsed.
 ///
+/// The registry remembers the `ETag` and `Last-Modified` headers of the last fetched file and sends
+/// them with the next request, so an unchanged file is not downloaded again. A `file://` registry
+/// is checked for changes to the file's modification time on every read.
+///
 /// If a forced or automatic cache refresh fails for any reason, an error message will be logged
 /// and the previously cached registry values will continue to be used. The next time the registry
 /// is read, it will try again to refresh the cache.
 ///
+/// A registry built with signature verification also fetches the file's detached signature (the
+/// file's URL with `.sig` appended) and only caches the file if it was signed by one of the
+/// trusted publisher keys. An unsigned or invalidly signed file is treated like any other failed
+/// refresh. Such a registry uses a separate cache file for each set of trusted keys, so a file
+/// cached without verification or under different keys is never used. The signature of the cached
+/// file is cached next to it, and a file whose signed serial is lower than that of the cached file
+/// is rejected, even after a restart, so an older signed file cannot be replayed.
+///
+/// Event subscribers are notified of the changes between successive refreshes of the cache.
+///
+/// Registries with authentication or signature verification are created with a
+/// [`RemoteYamlRegistryBuilder`].
+///
 /// [`Node`]: struct.Node.html
 /// [`RegistryReader`]: trait.RegistryReader.html
 /// [`constructor`]: struct.RemoteYamlRegistry.html#method.new
+/// [`RemoteYamlRegistryBuilder`]: struct.RemoteYamlRegistryBuilder.html
 pub struct RemoteYamlRegistry {
     internal: Arc<Mutex<Internal>>,
     shutdown_handle: ShutdownHandle,
@@ -94,47 +125,55 @@ impl RemoteYamlRegistry {
         automatic_refresh_period: Option<Duration>,
         forced_refresh_period: Option<Duration>,
     ) -> Result<Self, RegistryError> {
-        let internal = Arc::new(Mutex::new(Internal::new(
-            url,
-            cache_dir,
-            forced_refresh_period,
-        )?));
-
-        let running = automatic_refresh_period
-            .map::<Result<_, RegistryError>, _>(|refresh_period| {
-                let running = Arc::new(AtomicBool::new(true));
+        let mut builder = Self::builder(url, cache_dir);
+        builder.automatic_refresh_period = automatic_refresh_period;
+        builder.forced_refresh_period = forced_refresh_period;
+        builder.build()
+    }
 
-                let thread_internal = internal.clone();
-                let thread_url = url.to_string();
-                let thread_running = running.clone();
-                thread::Builder::new()
-                    .name(format!("Remote Registry Automatic Refresh: {}", url))
-                    .spawn(move || {
-                        automatic_refresh_loop(
-                            refresh_period,
-                            thread_internal,
-                            &thread_url,
-                            thread_running,
-                        )
-                    })
-                    .map_err(|err| {
-                        RegistryError::general_error_with_source(
-                            &format!(
-                                "Failed to spawn automatic refresh thread for remote registry '{}'",
-                                url
-                            ),
-                            Box::new(err),
-                        )
-                    })?;
-                Ok(running)
-            })
-            .transpose()?;
-        let shutdown_handle = ShutdownHandle { running };
+    /// Construct a new `RemoteYamlRegistry` that only accepts registry files signed by one of the
+    /// given publisher keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the registry's backing YAML file.
+    /// * `cache_dir` - Directory that the local registry cache will be stored in.
+    /// * `automatic_refresh_period` - See [`new`].
+    /// * `forced_refresh_period` - See [`new`].
+    /// * `trusted_keys` - Public keys of the registry publishers whose signatures are accepted;
+    ///   must not be empty.
+    /// * `verifier` - Verifies the signatures made by the publisher keys.
+    ///
+    /// [`new`]: struct.RemoteYamlRegistry.html#method.new
+    #[cfg(feature = "registry-signing")]
+    pub fn new_with_signature_verification(
+        url: &str,
+        cache_dir: &str,
+        automatic_refresh_period: Option<Duration>,
+        forced_refresh_period: Option<Duration>,
+        trusted_keys: Vec<Vec<u8>>,
+        verifier: Box<dyn SignatureVerifier>,
+    ) -> Result<Self, RegistryError> {
+        let mut builder =
+            Self::builder(url, cache_dir).with_signature_verification(trusted_keys, verifier);
+        builder.automatic_refresh_period = automatic_refresh_period;
+        builder.forced_refresh_period = forced_refresh_period;
+        builder.build()
+    }
 
-        Ok(Self {
-            internal,
-            shutdown_handle,
-        })
+    /// Create a builder for a `RemoteYamlRegistry` that fetches the file at the given URL and
+    /// caches it in the given directory.
+    pub fn builder(url: &str, cache_dir: &str) -> RemoteYamlRegistryBuilder {
+        RemoteYamlRegistryBuilder {
+            url: url.to_string(),
+            cache_dir: cache_dir.to_string(),
+            automatic_refresh_period: None,
+            forced_refresh_period: None,
+            #[cfg(feature = "registry-signing")]
+            signature_verification: None,
+            #[cfg(feature = "registry-remote-auth")]
+            config: None,
+        }
     }
 
     /// Get a copy of the registry's `ShutdownHandle`.
@@ -175,6 +214,166 @@ impl RegistryReader for RemoteYamlRegistry {
             .filter(move |node| predicates.iter().all(|predicate| predicate.apply(node)))
             .count() as u32)
     }
+
+    #[cfg(feature = "registry-events")]
+    fn add_event_subscriber(
+        &self,
+        subscriber: Box<dyn RegistryEventSubscriber>,
+    ) -> Result<(), RegistryError> {
+        self.internal
+            .lock()
+            .map_err(|_| RegistryError::general_error("Internal lock poisoned"))?
+            .cache
+            .add_event_subscriber(subscriber)
+    }
+}
+
+/// Builds a [`RemoteYamlRegistry`].
+///
+/// [`RemoteYamlRegistry`]: struct.RemoteYamlRegistry.html
+pub struct RemoteYamlRegistryBuilder {
+    url: String,
+    cache_dir: String,
+    automatic_refresh_period: Option<Duration>,
+    forced_refresh_period: Option<Duration>,
+    #[cfg(feature = "registry-signing")]
+    signature_verification: Option<SignatureVerification>,
+    #[cfg(feature = "registry-remote-auth")]
+    config: Option<RemoteRegistryConfig>,
+}
+
+impl RemoteYamlRegistryBuilder {
+    /// Refresh the cache in the background after the given amount of time since the last
+    /// automatic refresh attempt; see [`RemoteYamlRegistry::new`].
+    ///
+    /// [`RemoteYamlRegistry::new`]: struct.RemoteYamlRegistry.html#method.new
+    pub fn with_automatic_refresh_period(mut self, period: Duration) -> Self {
+        self.automatic_refresh_period = Some(period);
+        self
+    }
+
+    /// Refresh the cache on every read after the given amount of time since the last successful
+    /// refresh; see [`RemoteYamlRegistry::new`].
+    ///
+    /// [`RemoteYamlRegistry::new`]: struct.RemoteYamlRegistry.html#method.new
+    pub fn with_forced_refresh_period(mut self, period: Duration) -> Self {
+        self.forced_refresh_period = Some(period);
+        self
+    }
+
+    /// Only accept registry files signed by one of the given publisher keys; `trusted_keys` must
+    /// not be empty.
+    #[cfg(feature = "registry-signing")]
+    pub fn with_signature_verification(
+        mut self,
+        trusted_keys: Vec<Vec<u8>>,
+        verifier: Box<dyn SignatureVerifier>,
+    ) -> Self {
+        self.signature_verification = Some(SignatureVerification {
+            trusted_keys,
+            verifier,
+            signature_cache_filename: String::new(),
+            cached_serial: None,
+        });
+        self
+    }
+
+    /// Fetch the registry file with the credentials and TLS settings of the given configuration.
+    #[cfg(feature = "registry-remote-auth")]
+    pub fn with_config(mut self, config: RemoteRegistryConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Build the registry and attempt to fetch the remote file; the automatic refresh thread is
+    /// started if an automatic refresh period was set.
+    pub fn build(self) -> Result<RemoteYamlRegistry, RegistryError> {
+        #[cfg(feature = "registry-signing")]
+        {
+            if let Some(verification) = &self.signature_verification {
+                if verification.trusted_keys.is_empty() {
+                    return Err(RegistryError::general_error(
+                        "At least one trusted key is required to verify registry signatures",
+                    ));
+                }
+            }
+        }
+
+        #[cfg(feature = "registry-remote-auth")]
+        let fetcher = match self.config {
+            Some(config) => Fetcher::with_config(config)?,
+            None => Fetcher::new()?,
+        };
+        #[cfg(not(feature = "registry-remote-auth"))]
+        let fetcher = Fetcher::new()?;
+
+        let url = &self.url;
+        let internal = Arc::new(Mutex::new(Internal::new(
+            url,
+            &self.cache_dir,
+            self.forced_refresh_period,
+            fetcher,
+            #[cfg(feature = "registry-signing")]
+            self.signature_verification,
+        )?));
+
+        let running = self
+            .automatic_refresh_period
+            .map::<Result<_, RegistryError>, _>(|refresh_period| {
+                let running = Arc::new(AtomicBool::new(true));
+
+                let thread_internal = internal.clone();
+                let thread_url = url.to_string();
+                let thread_running = running.clone();
+                thread::Builder::new()
+                    .name(format!("Remote Registry Automatic Refresh: {}", url))
+                    .spawn(move || {
+                        automatic_refresh_loop(
+                            refresh_period,
+                            thread_internal,
+                            &thread_url,
+                            thread_running,
+                        )
+                    })
+                    .map_err(|err| {
+                        RegistryError::general_error_with_source(
+                            &format!(
+                                "Failed to spawn automatic refresh thread for remote registry '{}'",
+                                url
+                            ),
+                            Box::new(err),
+                        )
+                    })?;
+                Ok(running)
+            })
+            .transpose()?;
+        let shutdown_handle = ShutdownHandle { running };
+
+        Ok(RemoteYamlRegistry {
+            internal,
+            shutdown_handle,
+        })
+    }
+}
+
+/// The publisher keys and verifier used to check the signature of the remote registry file.
+#[cfg(feature = "registry-signing")]
+struct SignatureVerification {
+    trusted_keys: Vec<Vec<u8>>,
+    verifier: Box<dyn SignatureVerifier>,
+    /// Where the signature of the cached file is stored, next to the cache file.
+    signature_cache_filename: String,
+    /// The serial of the cached file; a fetched file with a lower serial is rejected.
+    cached_serial: Option<u64>,
+}
+
+/// A remote registry file that has been fetched, parsed and validated.
+struct FetchedRegistry {
+    nodes: Vec<Node>,
+    validators: Validators,
+    /// The file's verified signature, if the registry requires signatures.
+    #[cfg(feature = "registry-signing")]
+    signature: Option<RegistrySignature>,
 }
 
 /// Holds the internal state of the remote registry.
@@ -184,6 +383,11 @@ struct Internal {
     last_refresh_successful: bool,
     forced_refresh_period: Option<Duration>,
     next_forced_refresh: Option<Instant>,
+    fetcher: Fetcher,
+    /// Identifies the version of the remote file in the cache; `None` until it has been cached.
+    validators: Option<Validators>,
+    #[cfg(feature = "registry-signing")]
+    signature_verification: Option<SignatureVerification>,
 }
 
 impl Internal {
@@ -192,10 +396,32 @@ impl Internal {
         url: &str,
         cache_dir: &str,
         forced_refresh_period: Option<Duration>,
+        fetcher: Fetcher,
+        #[cfg(feature = "registry-signing")] signature_verification: Option<SignatureVerification>,
     ) -> Result<Self, RegistryError> {
         let url = url.to_string();
 
-        let cache = LocalYamlRegistry::new(&compute_cache_filename(&url, cache_dir)?)?;
+        #[cfg(not(feature = "registry-signing"))]
+        let cache_filename = compute_cache_filename(&url, cache_dir, &[])?;
+        #[cfg(feature = "registry-signing")]
+        let cache_filename = compute_cache_filename(
+            &url,
+            cache_dir,
+            signature_verification
+                .as_ref()
+                .map(|verification| &verification.trusted_keys[..])
+                .unwrap_or(&[]),
+        )?;
+
+        let cache = LocalYamlRegistry::new(&cache_filename)?;
+
+        #[cfg(feature = "registry-signing")]
+        let signature_verification = signature_verification.map(|mut verification| {
+            verification.signature_cache_filename =
+                format!("{}{}", cache_filename, SIGNATURE_FILE_SUFFIX);
+            verification.cached_serial = read_cached_serial(&verification.signature_cache_filename);
+            verification
+        });
 
         let mut internal = Self {
             url,
@@ -203,6 +429,10 @@ impl Internal {
             last_refresh_successful: false,
             forced_refresh_period,
             next_forced_refresh: None,
+            fetcher,
+            validators: None,
+            #[cfg(feature = "registry-signing")]
+            signature_verification,
         };
 
         // If initial fetch/cache fails, it will be re-attempted on the next registry read, so just
@@ -219,29 +449,76 @@ impl Internal {
 
     /// Attempt to refresh the internal cache and update state accordingly.
     fn refresh_cache(&mut self) -> Result<(), RegistryError> {
-        fetch_nodes_from_remote(&self.url)
-            .and_then(|nodes| self.cache.write_nodes(nodes))
-            .map_err(|err| {
-                self.last_refresh_successful = false;
-                err
-            })
-            .and_then(|_| {
-                self.last_refresh_successful = true;
-                // If a forced refresh period was configured, set the next time a forced refresh
-                // will be required
-                self.next_forced_refresh = self
-                    .forced_refresh_period
-                    .map(|duration| {
-                        Instant::now().checked_add(duration).ok_or_else(|| {
-                            RegistryError::general_error(
-                                "Forced refresh time could not be determined; \
+        fetch_nodes_from_remote(
+            &self.fetcher,
+            &self.url,
+            self.validators.as_ref(),
+            #[cfg(feature = "registry-signing")]
+            self.signature_verification.as_ref(),
+        )
+        .and_then(|fetched| match fetched {
+            Some(fetched) => self.cache_fetched(fetched),
+            // The remote file has not changed, so the cache is still up-to-date
+            None => Ok(()),
+        })
+        .map_err(|err| {
+            self.last_refresh_successful = false;
+            err
+        })
+        .and_then(|_| {
+            self.last_refresh_successful = true;
+            // If a forced refresh period was configured, set the next time a forced refresh
+            // will be required
+            self.next_forced_refresh = self
+                .forced_refresh_period
+                .map(|duration| {
+                    Instant::now().checked_add(duration).ok_or_else(|| {
+                        RegistryError::general_error(
+                            "Forced refresh time could not be determined; \
                                  forced_refresh_period may be too large",
-                            )
-                        })
+                        )
                     })
-                    .transpose()?;
-                Ok(())
-            })
+                })
+                .transpose()?;
+            Ok(())
+        })
+    }
+
+    /// Write a fetched registry file to the cache.
+    fn cache_fetched(&mut self, fetched: FetchedRegistry) -> Result<(), RegistryError> {
+        // The signature is cached first, so the cached serial is never lower than that of the
+        // cached file
+        #[cfg(feature = "registry-signing")]
+        {
+            if let (Some(verification), Some(signature)) = (
+                self.signature_verification.as_ref(),
+                fetched.signature.as_ref(),
+            ) {
+                fs::write(&verification.signature_cache_filename, signature.to_yaml()?).map_err(
+                    |err| {
+                        RegistryError::general_error_with_source(
+                            "Failed to write cached registry signature",
+                            Box::new(err),
+                        )
+                    },
+                )?;
+            }
+        }
+
+        self.cache.write_nodes(fetched.nodes)?;
+
+        // Only remember the new validators once the file they identify has been cached
+        self.validators = Some(fetched.validators);
+        #[cfg(feature = "registry-signing")]
+        {
+            if let (Some(verification), Some(signature)) =
+                (self.signature_verification.as_mut(), fetched.signature)
+            {
+                verification.cached_serial = Some(signature.serial);
+            }
+        }
+
+        Ok(())
     }
 
     /// Attempt to refresh the internal cache if necessary and return the cache's contents.
@@ -254,6 +531,12 @@ impl Internal {
                 Err(err) => debug!("Failed to refresh remote registry '{}': {}", self.url, err),
             }
         }
+        // Checking a local file for changes is cheap, so it is done on every read
+        else if self.url.starts_with(FILE_SCHEME) {
+            if let Err(err) = self.refresh_cache() {
+                warn!("Refresh of remote registry '{}' failed: {}", self.url, err);
+            }
+        }
         // If the forced refresh period has elapsed, attempt to refresh the cache
         else if self
             .next_forced_refresh
@@ -278,10 +561,21 @@ impl Internal {
     }
 }
 
-// Derive the filename for the cache from a hash of the URL; this makes the location deterministic,
-// which allows the local cache to be used across restarts.
-fn compute_cache_filename(url: &str, cache_dir: &str) -> Result<String, RegistryError> {
-    let hash = hash(MessageDigest::sha256(), url.as_bytes())
+// Derive the filename for the cache from a hash of the URL and any trusted keys; this makes the
+// location deterministic, which allows the local cache to be used across restarts.
+fn compute_cache_filename(
+    url: &str,
+    cache_dir: &str,
+    trusted_keys: &[Vec<u8>],
+) -> Result<String, RegistryError> {
+    let mut hash_input = url.as_bytes().to_vec();
+    let mut trusted_keys = trusted_keys.to_vec();
+    trusted_keys.sort();
+    for key in trusted_keys {
+        hash_input.push(b'\n');
+        hash_input.extend(key);
+    }
+    let hash = hash(MessageDigest::sha256(), &hash_input)
         .map(|digest| to_hex(&*digest))
         .map_err(|err| {
             RegistryError::general_error_with_source(
@@ -297,23 +591,50 @@ fn compute_cache_filename(url: &str, cache_dir: &str) -> Result<String, Registry
         .to_string())
 }
 
-/// Fetch, parse, and validate the YAML registry file at the given URL.
-fn fetch_nodes_from_remote(url: &str) -> Result<Vec<Node>, RegistryError> {
-    let bytes = reqwest::blocking::get(url)
-        .and_then(|response| response.error_for_status())
-        .map_err(|err| {
-            RegistryError::general_error_with_source(
-                &format!("Failed to fetch remote registry file from {}", url),
-                Box::new(err),
-            )
-        })?
-        .bytes()
-        .map_err(|err| {
-            RegistryError::general_error_with_source(
-                "Failed to get bytes from remote registry file HTTP response",
-                Box::new(err),
-            )
-        })?;
+/// Read the serial of the signature cached at the given location, if there is a valid one.
+#[cfg(feature = "registry-signing")]
+fn read_cached_serial(signature_cache_filename: &str) -> Option<u64> {
+    fs::read(signature_cache_filename)
+        .ok()
+        .and_then(|bytes| RegistrySignature::from_yaml(&bytes).ok())
+        .map(|signature| signature.serial)
+}
+
+/// Fetch, parse, and validate the YAML registry file at the given URL, unless it has not changed
+/// since it was fetched with the given validators. If a signature verification is given, the
+/// file's signature is fetched and verified before the file is parsed, and the file is rejected if
+/// its serial is lower than that of the cached file.
+fn fetch_nodes_from_remote(
+    fetcher: &Fetcher,
+    url: &str,
+    validators: Option<&Validators>,
+    #[cfg(feature = "registry-signing")] signature_verification: Option<&SignatureVerification>,
+) -> Result<Option<FetchedRegistry>, RegistryError> {
+    let (bytes, validators) = match fetcher.fetch(url, validators)? {
+        Some(fetched) => fetched,
+        None => return Ok(None),
+    };
+
+    #[cfg(feature = "registry-signing")]
+    let signature = signature_verification
+        .map(|verification| {
+            let signature_url = format!("{}{}", url, SIGNATURE_FILE_SUFFIX);
+            let signature = RegistrySignature::from_yaml(&fetcher.fetch_bytes(&signature_url)?)?;
+            signature.verify(&bytes, &verification.trusted_keys, &*verification.verifier)?;
+
+            match verification.cached_serial {
+                Some(cached_serial) if signature.serial < cached_serial => {
+                    Err(RegistryError::general_error(&format!(
+                        "Remote registry file has serial {}, which is lower than the serial {} \
+                         of the cached file",
+                        signature.serial, cached_serial
+                    )))
+                }
+                _ => Ok(signature),
+            }
+        })
+        .transpose()?;
+
     let nodes: Vec<Node> = serde_yaml::from_slice(&bytes).map_err(|_| {
         RegistryError::general_error(
             "Failed to deserialize remote registry file: Not a valid YAML sequence of nodes",
@@ -322,7 +643,12 @@ fn fetch_nodes_from_remote(url: &str) -> Result<Vec<Node>, RegistryError> {
 
     validate_nodes(&nodes)?;
 
-    Ok(nodes)
+    Ok(Some(FetchedRegistry {
+        nodes,
+        validators,
+        #[cfg(feature = "registry-signing")]
+        signature,
+    }))
 }
 
 /// Infinitely loop, attempting to refresh the `internal` cache every `refresh_period`, until no
@@ -395,14 +721,22 @@ mod tests {
     use super::*;
 
     use std::fs::File;
+    use std::sync::atomic::AtomicUsize;
 
     use actix_web::HttpResponse;
     use futures::future::IntoFuture;
+    #[cfg(all(feature = "registry-signing", feature = "sawtooth-signing-compat"))]
+    use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
     use tempdir::TempDir;
 
     use crate::rest_api::{
         Method, Resource, RestApiBuilder, RestApiServerError, RestApiShutdownHandle,
     };
+    #[cfg(all(feature = "registry-signing", feature = "sawtooth-signing-compat"))]
+    use crate::signing::{
+        sawtooth::{SawtoothSecp256k1RefSigner, SawtoothSecp256k1SignatureVerifier},
+        Signer,
+    };
 
     /// Verifies that a remote file that contains two nodes with the same identity is rejected (not
     /// loaded).
@@ -925,8 +1259,360 @@ mod tests {
         test_config.shutdown();
     }
 
+    /// Verifies that the registry sends the ETag of the cached file when refreshing, and keeps its
+    /// cache when the server responds that the file has not been modified.
+    #[test]
+    fn conditional_fetch() {
+        let temp_dir = TempDir::new("conditional_fetch").expect("Failed to create temp dir");
+        let temp_dir_path = temp_dir.path().to_str().expect("Failed to get path");
+
+        let registry_bytes =
+            serde_yaml::to_vec(&mock_registry()).expect("Failed to serialize registry file");
+        let downloads = Arc::new(AtomicUsize::new(0));
+
+        let thread_downloads = downloads.clone();
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![Resource::build("/registry.yaml").add_method(
+                Method::Get,
+                move |request, _| {
+                    let etag = "\"v1\"";
+                    if request
+                        .headers()
+                        .get("If-None-Match")
+                        .map(|value| value == etag)
+                        .unwrap_or(false)
+                    {
+                        return Box::new(HttpResponse::NotModified().finish().into_future());
+                    }
+                    thread_downloads.fetch_add(1, Ordering::SeqCst);
+                    Box::new(
+                        HttpResponse::Ok()
+                            .header("ETag", etag)
+                            .body(registry_bytes.clone())
+                            .into_future(),
+                    )
+                },
+            )]);
+        let url = format!("http://{}/registry.yaml", bind_url);
+
+        let refresh_period = Duration::from_millis(10);
+        let remote_registry = RemoteYamlRegistry::builder(&url, temp_dir_path)
+            .with_forced_refresh_period(refresh_period)
+            .build()
+            .expect("Failed to create registry");
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+
+        // Wait for the forced refresh, which should not download the file again
+        std::thread::sleep(refresh_period);
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+
+        remote_registry.shutdown_handle().shutdown();
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Verifies that a registry with a `file://` URL loads the file, and picks up changes to the
+    /// file on the next read.
+    #[test]
+    fn file_url() {
+        let temp_dir = TempDir::new("file_url").expect("Failed to create temp dir");
+        let temp_dir_path = temp_dir.path().to_str().expect("Failed to get path");
+        let registry_path = temp_dir.path().join("registry.yaml");
+        let url = format!(
+            "file://{}",
+            registry_path.to_str().expect("Failed to get path")
+        );
+
+        let write_registry = |nodes: &[Node]| {
+            let file = File::create(&registry_path).expect("Failed to create registry file");
+            serde_yaml::to_writer(file, nodes).expect("Failed to write registry file");
+        };
+
+        write_registry(&mock_registry());
+        let remote_registry = RemoteYamlRegistry::new(&url, temp_dir_path, None, None)
+            .expect("Failed to create registry");
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+
+        // Make sure the file's modification time changes
+        std::thread::sleep(Duration::from_millis(10));
+        write_registry(&mock_registry()[..1]);
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()[..1].to_vec()
+        );
+
+        remote_registry.shutdown_handle().shutdown();
+    }
+
+    /// Verifies that a registry only loads a file from a server that requires a bearer token when
+    /// it is built with a config that provides the token.
+    #[cfg(feature = "registry-remote-auth")]
+    #[test]
+    fn bearer_token() {
+        let temp_dir = TempDir::new("bearer_token").expect("Failed to create temp dir");
+        let temp_dir_path = temp_dir.path().to_str().expect("Failed to get path");
+
+        let registry_bytes =
+            serde_yaml::to_vec(&mock_registry()).expect("Failed to serialize registry file");
+
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![Resource::build("/registry.yaml").add_method(
+                Method::Get,
+                move |request, _| {
+                    let authorized = request
+                        .headers()
+                        .get("Authorization")
+                        .map(|value| value == "Bearer secret")
+                        .unwrap_or(false);
+                    Box::new(if authorized {
+                        HttpResponse::Ok()
+                            .body(registry_bytes.clone())
+                            .into_future()
+                    } else {
+                        HttpResponse::Unauthorized().finish().into_future()
+                    })
+                },
+            )]);
+        let url = format!("http://{}/registry.yaml", bind_url);
+
+        let remote_registry = RemoteYamlRegistry::new(&url, temp_dir_path, None, None)
+            .expect("Failed to create registry");
+        assert!(remote_registry
+            .get_nodes()
+            .expect("Failed to get nodes")
+            .is_empty());
+        remote_registry.shutdown_handle().shutdown();
+
+        let config = RemoteRegistryConfig::new(format!("http://{}/", bind_url))
+            .with_bearer_token("secret")
+            .with_allow_insecure_credentials(true);
+        assert!(config.matches(&url));
+        let remote_registry = RemoteYamlRegistry::builder(&url, temp_dir_path)
+            .with_config(config)
+            .build()
+            .expect("Failed to create registry");
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+        remote_registry.shutdown_handle().shutdown();
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
     // Restart, remote file not available
 
+    /// Verifies that a registry that requires signatures does not load a remote file that has no
+    /// signature, even if the same file was previously cached by a registry without verification.
+    #[cfg(all(feature = "registry-signing", feature = "sawtooth-signing-compat"))]
+    #[test]
+    fn unsigned_file_rejected() {
+        let test_config = TestConfig::setup("unsigned_file_rejected", Some(mock_registry()));
+
+        let remote_registry =
+            RemoteYamlRegistry::new(test_config.url(), test_config.path(), None, None)
+                .expect("Failed to create registry");
+        verify_internal_cache(&test_config, &remote_registry, mock_registry());
+        remote_registry.shutdown_handle().shutdown();
+
+        let context = Secp256k1Context::new();
+        let signer = test_signer(&context);
+        let remote_registry = RemoteYamlRegistry::new_with_signature_verification(
+            test_config.url(),
+            test_config.path(),
+            None,
+            None,
+            vec![signer.public_key().to_vec()],
+            Box::new(SawtoothSecp256k1SignatureVerifier::new()),
+        )
+        .expect("Failed to create registry");
+        assert!(remote_registry
+            .get_nodes()
+            .expect("Failed to get nodes")
+            .is_empty());
+
+        remote_registry.shutdown_handle().shutdown();
+        test_config.shutdown();
+    }
+
+    /// Verifies that a registry that requires signatures loads a remote file signed by a trusted
+    /// key, and does not load it when the signing key is not trusted.
+    #[cfg(all(feature = "registry-signing", feature = "sawtooth-signing-compat"))]
+    #[test]
+    fn signed_file() {
+        let temp_dir = TempDir::new("signed_file").expect("Failed to create temp dir");
+        let temp_dir_path = temp_dir.path().to_str().expect("Failed to get path");
+
+        let context = Secp256k1Context::new();
+        let signer = test_signer(&context);
+
+        let registry_bytes =
+            serde_yaml::to_vec(&mock_registry()).expect("Failed to serialize registry file");
+        let signature_bytes = RegistrySignature::sign(&registry_bytes, 1, &signer)
+            .and_then(|signature| signature.to_yaml())
+            .expect("Failed to sign registry file");
+
+        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
+            Resource::build("/registry.yaml").add_method(Method::Get, move |_, _| {
+                Box::new(
+                    HttpResponse::Ok()
+                        .body(registry_bytes.clone())
+                        .into_future(),
+                )
+            }),
+            Resource::build("/registry.yaml.sig").add_method(Method::Get, move |_, _| {
+                Box::new(
+                    HttpResponse::Ok()
+                        .body(signature_bytes.clone())
+                        .into_future(),
+                )
+            }),
+        ]);
+        let url = format!("http://{}/registry.yaml", bind_url);
+
+        let remote_registry = RemoteYamlRegistry::new_with_signature_verification(
+            &url,
+            temp_dir_path,
+            None,
+            None,
+            vec![signer.public_key().to_vec()],
+            Box::new(SawtoothSecp256k1SignatureVerifier::new()),
+        )
+        .expect("Failed to create registry");
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+        remote_registry.shutdown_handle().shutdown();
+
+        let remote_registry = RemoteYamlRegistry::new_with_signature_verification(
+            &url,
+            temp_dir_path,
+            None,
+            None,
+            vec![vec![2; 33]],
+            Box::new(SawtoothSecp256k1SignatureVerifier::new()),
+        )
+        .expect("Failed to create registry");
+        assert!(remote_registry
+            .get_nodes()
+            .expect("Failed to get nodes")
+            .is_empty());
+        remote_registry.shutdown_handle().shutdown();
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Verifies that a registry that requires signatures does not replace its cached file with a
+    /// validly signed file that has a lower serial, including after a restart.
+    ///
+    /// 1. Serve a file signed with serial 2 and verify that it is cached
+    /// 2. Serve a different file signed with serial 1 and verify that the cache is unchanged
+    /// 3. Restart the registry and verify that the file with serial 1 is still rejected
+    /// 4. Serve the file again, signed with serial 3, and verify that it is cached
+    #[cfg(all(feature = "registry-signing", feature = "sawtooth-signing-compat"))]
+    #[test]
+    fn lower_serial_rejected() {
+        let temp_dir = TempDir::new("lower_serial_rejected").expect("Failed to create temp dir");
+        let temp_dir_path = temp_dir.path().to_str().expect("Failed to get path");
+
+        let context = Secp256k1Context::new();
+        let signer = test_signer(&context);
+        let sign = |nodes: &[Node], serial| {
+            let registry_bytes =
+                serde_yaml::to_vec(nodes).expect("Failed to serialize registry file");
+            let signature_bytes = RegistrySignature::sign(&registry_bytes, serial, &signer)
+                .and_then(|signature| signature.to_yaml())
+                .expect("Failed to sign registry file");
+            (registry_bytes, signature_bytes)
+        };
+
+        let served = Arc::new(Mutex::new(sign(&mock_registry(), 2)));
+        let served_registry = served.clone();
+        let served_signature = served.clone();
+        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
+            Resource::build("/registry.yaml").add_method(Method::Get, move |_, _| {
+                let (registry_bytes, _) = served_registry.lock().expect("lock poisoned").clone();
+                Box::new(HttpResponse::Ok().body(registry_bytes).into_future())
+            }),
+            Resource::build("/registry.yaml.sig").add_method(Method::Get, move |_, _| {
+                let (_, signature_bytes) = served_signature.lock().expect("lock poisoned").clone();
+                Box::new(HttpResponse::Ok().body(signature_bytes).into_future())
+            }),
+        ]);
+        let url = format!("http://{}/registry.yaml", bind_url);
+        let build_registry = || {
+            RemoteYamlRegistry::builder(&url, temp_dir_path)
+                .with_forced_refresh_period(Duration::from_secs(0))
+                .with_signature_verification(
+                    vec![signer.public_key().to_vec()],
+                    Box::new(SawtoothSecp256k1SignatureVerifier::new()),
+                )
+                .build()
+                .expect("Failed to create registry")
+        };
+
+        let remote_registry = build_registry();
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+
+        let older_registry = vec![mock_registry()[0].clone()];
+        *served.lock().expect("lock poisoned") = sign(&older_registry, 1);
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+        remote_registry.shutdown_handle().shutdown();
+
+        let remote_registry = build_registry();
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            mock_registry()
+        );
+
+        *served.lock().expect("lock poisoned") = sign(&older_registry, 3);
+        assert_eq!(
+            remote_registry.get_nodes().expect("Failed to get nodes"),
+            older_registry
+        );
+        remote_registry.shutdown_handle().shutdown();
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Creates the signer of a registry publisher.
+    #[cfg(all(feature = "registry-signing", feature = "sawtooth-signing-compat"))]
+    fn test_signer(context: &Secp256k1Context) -> SawtoothSecp256k1RefSigner {
+        let private_key = Secp256k1PrivateKey::from_hex(
+            "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088",
+        )
+        .expect("Failed to parse private key");
+        SawtoothSecp256k1RefSigner::new(context, private_key).expect("Failed to create signer")
+    }
+
     /// Creates a mock registry.
     fn mock_registry() -> Vec<Node> {
         vec![
@@ -971,7 +1657,7 @@ mod tests {
         );
 
         // Verify the backing file's contents
-        let filename = compute_cache_filename(test_config.url(), test_config.path())
+        let filename = compute_cache_filename(test_config.url(), test_config.path(), &[])
             .expect("Failed to compute cache filename");
         let file = File::open(filename).expect("Failed to open cache file");
         let file_contents: Vec<Node> =
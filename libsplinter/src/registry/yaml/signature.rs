@@ -0,0 +1,235 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Detached signatures for registry YAML files.
+//!
+//! A registry file is signed by a registry publisher; the resulting [`RegistrySignature`] is
+//! published next to the file, at the file's location with the [`SIGNATURE_FILE_SUFFIX`]
+//! appended (for example, `nodes.yaml.sig` for `nodes.yaml`). The signature covers the exact
+//! bytes of the registry file, so the file must not be modified or re-serialized after signing.
+//!
+//! The signature also covers a serial number chosen by the publisher, which must increase with
+//! each published version of the file. A registry that has accepted a file refuses to replace it
+//! with one that has a lower serial, so an older, validly signed file cannot be replayed to roll
+//! back the registry.
+//!
+//! [`RegistrySignature`]: struct.RegistrySignature.html
+//! [`SIGNATURE_FILE_SUFFIX`]: constant.SIGNATURE_FILE_SUFFIX.html
+
+use std::fs;
+
+use crate::hex::{as_hex, deserialize_hex, parse_hex, to_hex};
+use crate::registry::RegistryError;
+use crate::signing::{SignatureVerifier, Signer};
+
+/// The suffix appended to the location of a registry file to find its signature file.
+pub const SIGNATURE_FILE_SUFFIX: &str = ".sig";
+
+/// A detached signature of a registry file.
+///
+/// The signature file is a YAML mapping with the publisher's `public_key` and the `signature`,
+/// both hex encoded, and the `serial` of the signed file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RegistrySignature {
+    /// The serial number of the registry file, which is signed along with the file's bytes.
+    pub serial: u64,
+    /// The public key of the registry publisher that signed the file.
+    #[serde(serialize_with = "as_hex", deserialize_with = "deserialize_hex")]
+    pub public_key: Vec<u8>,
+    /// The signature of the registry file's bytes.
+    #[serde(serialize_with = "as_hex", deserialize_with = "deserialize_hex")]
+    pub signature: Vec<u8>,
+}
+
+impl RegistrySignature {
+    /// Sign the given registry file contents and `serial` with the publisher's `signer`.
+    pub fn sign(
+        registry_bytes: &[u8],
+        serial: u64,
+        signer: &dyn Signer,
+    ) -> Result<Self, RegistryError> {
+        let signature = signer
+            .sign(&signed_message(registry_bytes, serial))
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    "Failed to sign registry file",
+                    Box::new(err),
+                )
+            })?;
+
+        Ok(Self {
+            serial,
+            public_key: signer.public_key().to_vec(),
+            signature,
+        })
+    }
+
+    /// Parse a signature from the contents of a signature file.
+    pub fn from_yaml(bytes: &[u8]) -> Result<Self, RegistryError> {
+        serde_yaml::from_slice(bytes).map_err(|_| {
+            RegistryError::general_error(
+                "Failed to deserialize registry signature file: Not a valid signature",
+            )
+        })
+    }
+
+    /// Serialize the signature into the contents of a signature file.
+    pub fn to_yaml(&self) -> Result<Vec<u8>, RegistryError> {
+        serde_yaml::to_vec(self).map_err(|err| {
+            RegistryError::general_error_with_source(
+                "Failed to serialize registry signature",
+                Box::new(err),
+            )
+        })
+    }
+
+    /// Verify that this is a valid signature of the given registry file contents and this
+    /// signature's serial, made by one of the `trusted_keys`.
+    ///
+    /// Returns an error if the signing key is not trusted or if the signature does not match the
+    /// registry file and serial.
+    pub fn verify(
+        &self,
+        registry_bytes: &[u8],
+        trusted_keys: &[Vec<u8>],
+        verifier: &dyn SignatureVerifier,
+    ) -> Result<(), RegistryError> {
+        if !trusted_keys.contains(&self.public_key) {
+            return Err(RegistryError::general_error(&format!(
+                "Registry file was signed by an untrusted key: {}",
+                to_hex(&self.public_key)
+            )));
+        }
+
+        let valid = verifier
+            .verify(
+                &signed_message(registry_bytes, self.serial),
+                &self.signature,
+                &self.public_key,
+            )
+            .map_err(|err| {
+                RegistryError::general_error_with_source(
+                    "Failed to verify registry file signature",
+                    Box::new(err),
+                )
+            })?;
+
+        if valid {
+            Ok(())
+        } else {
+            Err(RegistryError::general_error(
+                "Registry file signature is not valid",
+            ))
+        }
+    }
+}
+
+/// Builds the message that is signed for a registry file: the big-endian bytes of the `serial`
+/// followed by the file's bytes.
+fn signed_message(registry_bytes: &[u8], serial: u64) -> Vec<u8> {
+    let mut message = serial.to_be_bytes().to_vec();
+    message.extend_from_slice(registry_bytes);
+    message
+}
+
+/// Read a hex-encoded public key, such as a trusted registry publisher's key, from a file.
+pub fn read_public_key_file(path: &str) -> Result<Vec<u8>, RegistryError> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        RegistryError::general_error_with_source(
+            &format!("Failed to read public key file '{}'", path),
+            Box::new(err),
+        )
+    })?;
+    parse_hex(contents.trim()).map_err(|err| {
+        RegistryError::general_error_with_source(
+            &format!(
+                "Public key file '{}' does not contain a hex-encoded key",
+                path
+            ),
+            Box::new(err),
+        )
+    })
+}
+
+#[cfg(all(test, feature = "sawtooth-signing-compat"))]
+mod tests {
+    use super::*;
+
+    use sawtooth_sdk::signing::secp256k1::{Secp256k1Context, Secp256k1PrivateKey};
+
+    use crate::signing::sawtooth::{
+        SawtoothSecp256k1RefSigner, SawtoothSecp256k1SignatureVerifier,
+    };
+
+    const PUBLISHER_KEY: &str = "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088";
+    const OTHER_KEY: &str = "cd48b28db1e3ff1be2e2e7e2e5d5e4e1f2b8d8cf0a1ab8e9c9e7b2f0f4e3c2a1";
+
+    const REGISTRY: &[u8] =
+        b"- identity: Node-123\n  endpoints:\n    - \"tcps://12.0.0.123:8431\"\n";
+
+    /// Verifies that a signature made by a trusted key verifies, survives a round trip through
+    /// its YAML representation, and is rejected once the registry file or serial is modified.
+    #[test]
+    fn sign_and_verify() {
+        let context = Secp256k1Context::new();
+        let signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(PUBLISHER_KEY).expect("Failed to parse key"),
+        )
+        .expect("Failed to create signer");
+        let verifier = SawtoothSecp256k1SignatureVerifier::new();
+        let trusted_keys = vec![signer.public_key().to_vec()];
+
+        let signature = RegistrySignature::sign(REGISTRY, 2, &signer).expect("Failed to sign");
+        let signature =
+            RegistrySignature::from_yaml(&signature.to_yaml().expect("Failed to write"))
+                .expect("Failed to read");
+
+        assert!(signature.verify(REGISTRY, &trusted_keys, &verifier).is_ok());
+
+        let mut modified = REGISTRY.to_vec();
+        modified.extend_from_slice(b"    - \"tcps://12.0.0.124:8431\"\n");
+        assert!(signature
+            .verify(&modified, &trusted_keys, &verifier)
+            .is_err());
+
+        let mut replayed = signature.clone();
+        replayed.serial = 3;
+        assert!(replayed.verify(REGISTRY, &trusted_keys, &verifier).is_err());
+    }
+
+    /// Verifies that a valid signature made by a key that is not trusted is rejected.
+    #[test]
+    fn untrusted_key() {
+        let context = Secp256k1Context::new();
+        let signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(PUBLISHER_KEY).expect("Failed to parse key"),
+        )
+        .expect("Failed to create signer");
+        let other_signer = SawtoothSecp256k1RefSigner::new(
+            &context,
+            Secp256k1PrivateKey::from_hex(OTHER_KEY).expect("Failed to parse key"),
+        )
+        .expect("Failed to create signer");
+        let verifier = SawtoothSecp256k1SignatureVerifier::new();
+
+        let signature = RegistrySignature::sign(REGISTRY, 1, &signer).expect("Failed to sign");
+
+        assert!(signature
+            .verify(REGISTRY, &[other_signer.public_key().to_vec()], &verifier)
+            .is_err());
+        assert!(signature.verify(REGISTRY, &[], &verifier).is_err());
+    }
+}
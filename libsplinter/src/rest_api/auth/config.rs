@@ -0,0 +1,193 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The YAML authorization configuration used by splinterd.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::net::IpAddr;
+use std::path::Path;
+
+use super::identity::{ApiTokenIdentityProvider, ClientCertIdentityProvider, IdentityProvider};
+use super::{AuthConfigError, Authorizer, AuthorizerBuilder, Identity};
+
+/// Configures how REST API clients are authenticated and which permissions they are granted.
+///
+/// An example configuration:
+///
+/// ```yaml
+/// api_tokens:
+///   ci: "0b2f7a0c1e6d4e1a9d43"
+/// client_cert_header: X-Client-Cert-Subject
+/// client_cert_trusted_proxies:
+///   - 10.0.0.1
+/// permissions:
+///   "token:ci":
+///     - registry.read
+///     - registry.write
+///   "cert:CN=admin":
+///     - circuit.read
+///     - circuit.write
+///   "user:3f6e1b63":
+///     - scabbard.read
+///     - scabbard.submit
+/// authenticated_permissions:
+///   - registry.read
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AuthConfig {
+    /// Static API tokens, keyed by token name
+    #[serde(default)]
+    pub api_tokens: HashMap<String, String>,
+    /// The header in which a TLS-terminating proxy passes the verified client certificate
+    /// subject; client certificate identities are disabled if not set
+    #[serde(default)]
+    pub client_cert_header: Option<String>,
+    /// The addresses of the TLS-terminating proxies that are trusted to set the client
+    /// certificate header; required if `client_cert_header` is set
+    #[serde(default)]
+    pub client_cert_trusted_proxies: Vec<String>,
+    /// Permissions granted to specific identities, keyed by identity (e.g. `token:<name>`)
+    #[serde(default)]
+    pub permissions: HashMap<String, Vec<String>>,
+    /// Permissions granted to every authenticated identity
+    #[serde(default)]
+    pub authenticated_permissions: Vec<String>,
+}
+
+impl AuthConfig {
+    /// Loads the configuration from a YAML file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, AuthConfigError> {
+        let file = File::open(path)?;
+        serde_yaml::from_reader(file).map_err(|err| AuthConfigError::InvalidConfig(err.to_string()))
+    }
+
+    /// Builds an `Authorizer` from this configuration. The given identity providers (such as a
+    /// provider for biome JWTs) are consulted after the configured API tokens and client
+    /// certificate header.
+    pub fn build_authorizer(
+        &self,
+        extra_providers: Vec<Box<dyn IdentityProvider>>,
+    ) -> Result<Authorizer, AuthConfigError> {
+        let mut builder = AuthorizerBuilder::new();
+
+        if !self.api_tokens.is_empty() {
+            if let Some((name, _)) = self.api_tokens.iter().find(|(_, token)| token.is_empty()) {
+                return Err(AuthConfigError::InvalidConfig(format!(
+                    "API token {} is empty",
+                    name
+                )));
+            }
+            builder = builder.with_identity_provider(Box::new(ApiTokenIdentityProvider::new(
+                self.api_tokens.clone(),
+            )));
+        }
+
+        if let Some(header) = &self.client_cert_header {
+            if self.client_cert_trusted_proxies.is_empty() {
+                return Err(AuthConfigError::InvalidConfig(
+                    "client_cert_header requires at least one client_cert_trusted_proxies address"
+                        .into(),
+                ));
+            }
+            let trusted_proxies = self
+                .client_cert_trusted_proxies
+                .iter()
+                .map(|addr| {
+                    addr.parse::<IpAddr>().map_err(|err| {
+                        AuthConfigError::InvalidConfig(format!(
+                            "invalid client_cert_trusted_proxies address {}: {}",
+                            addr, err
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            builder = builder.with_identity_provider(Box::new(ClientCertIdentityProvider::new(
+                header,
+                trusted_proxies,
+            )));
+        }
+
+        for provider in extra_providers {
+            builder = builder.with_identity_provider(provider);
+        }
+
+        for (identity, permissions) in &self.permissions {
+            let identity = identity.parse::<Identity>()?;
+            for permission in permissions {
+                builder = builder.with_permission(identity.clone(), permission);
+            }
+        }
+
+        for permission in &self.authenticated_permissions {
+            builder = builder.with_authenticated_permission(permission);
+        }
+
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a YAML configuration is parsed and turned into an authorizer with the
+    /// configured permissions, and that invalid identities are rejected.
+    #[test]
+    fn build_authorizer_from_yaml() {
+        let yaml = r#"
+api_tokens:
+  ci: "ci-token"
+permissions:
+  "token:ci":
+    - registry.write
+authenticated_permissions:
+  - registry.read
+"#;
+        let config: AuthConfig = serde_yaml::from_str(yaml).expect("failed to parse config");
+        let authorizer = config
+            .build_authorizer(vec![])
+            .expect("failed to build authorizer");
+
+        let ci = Identity::ApiToken("ci".into());
+        let other = Identity::User("other".into());
+        assert!(authorizer.has_permission(&ci, "registry.write").unwrap());
+        assert!(authorizer.has_permission(&ci, "registry.read").unwrap());
+        assert!(authorizer.has_permission(&other, "registry.read").unwrap());
+        assert!(!authorizer.has_permission(&other, "registry.write").unwrap());
+
+        let mut config = AuthConfig::default();
+        config
+            .permissions
+            .insert("ci".into(), vec!["registry.read".into()]);
+        assert!(config.build_authorizer(vec![]).is_err());
+    }
+
+    /// Verify that the client certificate header is only accepted along with valid trusted proxy
+    /// addresses.
+    #[test]
+    fn client_cert_header_requires_trusted_proxies() {
+        let mut config = AuthConfig {
+            client_cert_header: Some("X-Client-Cert-Subject".into()),
+            ..Default::default()
+        };
+        assert!(config.build_authorizer(vec![]).is_err());
+
+        config.client_cert_trusted_proxies = vec!["not-an-address".into()];
+        assert!(config.build_authorizer(vec![]).is_err());
+
+        config.client_cert_trusted_proxies = vec!["10.0.0.1".into()];
+        assert!(config.build_authorizer(vec![]).is_ok());
+    }
+}
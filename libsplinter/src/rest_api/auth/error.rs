@@ -0,0 +1,66 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+/// Returned when an identity provider is unable to determine the identity of a request's
+/// client; for example, because a secret could not be loaded.
+#[derive(Debug)]
+pub struct IdentityError(pub String);
+
+impl Error for IdentityError {}
+
+impl fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unable to get identity: {}", self.0)
+    }
+}
+
+/// Errors that may occur when loading an authorization configuration
+#[derive(Debug)]
+pub enum AuthConfigError {
+    /// Returned when the configuration file cannot be read
+    ReadError(std::io::Error),
+    /// Returned when the configuration is not valid
+    InvalidConfig(String),
+}
+
+impl Error for AuthConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AuthConfigError::ReadError(err) => Some(err),
+            AuthConfigError::InvalidConfig(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for AuthConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthConfigError::ReadError(err) => {
+                write!(f, "unable to read authorization config: {}", err)
+            }
+            AuthConfigError::InvalidConfig(msg) => {
+                write!(f, "invalid authorization config: {}", msg)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for AuthConfigError {
+    fn from(err: std::io::Error) -> Self {
+        AuthConfigError::ReadError(err)
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Identity providers, which determine who made a REST API request.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use actix_web::HttpRequest;
+use jsonwebtoken::Validation;
+use openssl::{memcmp, sha::sha256};
+
+use crate::rest_api::get_authorization_token;
+use crate::rest_api::secrets::SecretManager;
+#[cfg(feature = "biome-sessions")]
+use crate::rest_api::sessions::AccessTokenDenylist;
+use crate::rest_api::sessions::{decode_token, Claims, TokenValidationError};
+
+use super::{Identity, IdentityError};
+
+const JWT_LEEWAY: i64 = 10; // leeway in seconds
+
+/// Determines the identity of the client that made a request.
+pub trait IdentityProvider: Send + Sync {
+    /// Returns the identity of the client that made the request, or `None` if the request does
+    /// not carry credentials that this provider recognizes.
+    fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError>;
+
+    /// Returns `true` if this provider grants the permission to the identity. Providers that
+    /// manage permissions of their own, such as biome roles, override this; by default no
+    /// permissions are granted.
+    fn has_permission(
+        &self,
+        _identity: &Identity,
+        _permission: &str,
+    ) -> Result<bool, IdentityError> {
+        Ok(false)
+    }
+}
+
+/// Identifies biome users by the JWT access tokens issued by biome, which are passed in the
+/// `Authorization: Bearer <token>` header.
+pub struct JwtIdentityProvider {
+    secret_manager: Arc<dyn SecretManager>,
+    validation: Validation,
+    #[cfg(feature = "biome-sessions")]
+    denylist: Option<AccessTokenDenylist>,
+}
+
+impl JwtIdentityProvider {
+    /// Creates a new provider that validates tokens with the keys from the given secret manager
+    /// and requires that they were issued by the given `issuer`.
+    pub fn new(secret_manager: Arc<dyn SecretManager>, issuer: &str) -> Self {
+        let mut validation = Validation::default();
+        validation.leeway = JWT_LEEWAY;
+        validation.iss = Some(issuer.to_string());
+
+        Self {
+            secret_manager,
+            validation,
+            #[cfg(feature = "biome-sessions")]
+            denylist: None,
+        }
+    }
+
+    /// Rejects tokens that belong to a session in the given denylist.
+    #[cfg(feature = "biome-sessions")]
+    pub fn with_denylist(mut self, denylist: AccessTokenDenylist) -> Self {
+        self.denylist = Some(denylist);
+        self
+    }
+}
+
+impl IdentityProvider for JwtIdentityProvider {
+    fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError> {
+        let token = match get_authorization_token(request) {
+            Ok(token) => token,
+            Err(_) => return Ok(None),
+        };
+
+        match decode_token::<Claims>(&token, &*self.secret_manager, &self.validation) {
+            #[cfg(feature = "biome-sessions")]
+            Ok(ref claims)
+                if self
+                    .denylist
+                    .as_ref()
+                    .map(|denylist| denylist.is_denied(claims))
+                    .unwrap_or(false) =>
+            {
+                debug!("Token belongs to a revoked session");
+                Ok(None)
+            }
+            Ok(claims) => Ok(Some(Identity::User(claims.user_id()))),
+            Err(TokenValidationError::SecretError(err)) => {
+                Err(IdentityError(format!("failed to fetch secret: {}", err)))
+            }
+            Err(err) => {
+                debug!("Invalid token: {}", err);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Identifies clients by static API tokens, which are passed in the
+/// `Authorization: Bearer <token>` header.
+pub struct ApiTokenIdentityProvider {
+    /// The name of each token, along with the SHA-256 digest of its value
+    tokens: Vec<(String, [u8; 32])>,
+}
+
+impl ApiTokenIdentityProvider {
+    /// Creates a new provider from a map of token names to token values.
+    pub fn new(tokens: HashMap<String, String>) -> Self {
+        Self {
+            tokens: tokens
+                .into_iter()
+                .map(|(name, value)| (name, sha256(value.as_bytes())))
+                .collect(),
+        }
+    }
+}
+
+impl IdentityProvider for ApiTokenIdentityProvider {
+    fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError> {
+        let token = match get_authorization_token(request) {
+            Ok(token) => token,
+            Err(_) => return Ok(None),
+        };
+
+        // Compare fixed-length digests in constant time, and check every token, so neither the
+        // values nor the lengths of the tokens can be guessed from response times
+        let digest = sha256(token.as_bytes());
+        Ok(self
+            .tokens
+            .iter()
+            .fold(None, |identity, (name, value)| {
+                if memcmp::eq(value, &digest) {
+                    Some(name)
+                } else {
+                    identity
+                }
+            })
+            .map(|name| Identity::ApiToken(name.clone())))
+    }
+}
+
+/// Identifies clients by the subject of their TLS client certificate.
+///
+/// The REST API does not terminate TLS itself, so this provider relies on a TLS-terminating
+/// proxy that verifies client certificates and passes the verified subject to splinterd in the
+/// configured header. The header is only trusted on requests whose peer address is one of the
+/// configured trusted proxies; it is ignored on requests from any other address. The proxy must
+/// still strip this header from the requests it forwards, or clients can claim any identity.
+pub struct ClientCertIdentityProvider {
+    header: String,
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl ClientCertIdentityProvider {
+    /// Creates a new provider that reads the verified certificate subject from the given header
+    /// of requests sent by one of the `trusted_proxies`.
+    pub fn new(header: &str, trusted_proxies: Vec<IpAddr>) -> Self {
+        Self {
+            header: header.into(),
+            trusted_proxies,
+        }
+    }
+}
+
+impl IdentityProvider for ClientCertIdentityProvider {
+    fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError> {
+        let subject = match request
+            .headers()
+            .get(self.header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|subject| !subject.is_empty())
+        {
+            Some(subject) => subject,
+            None => return Ok(None),
+        };
+
+        if self.is_trusted(request.peer_addr()) {
+            Ok(Some(Identity::ClientCert(subject.into())))
+        } else {
+            warn!(
+                "Ignoring {} header from untrusted address {:?}",
+                self.header,
+                request.peer_addr()
+            );
+            Ok(None)
+        }
+    }
+}
+
+impl ClientCertIdentityProvider {
+    fn is_trusted(&self, peer_addr: Option<SocketAddr>) -> bool {
+        peer_addr
+            .map(|addr| self.trusted_proxies.contains(&addr.ip()))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::test::TestRequest;
+
+    /// Verify that the client certificate header is only trusted on requests from a trusted
+    /// proxy.
+    #[test]
+    fn client_cert_trusted_proxy() {
+        let provider = ClientCertIdentityProvider::new(
+            "X-Client-Cert-Subject",
+            vec!["10.0.0.1".parse().expect("failed to parse address")],
+        );
+
+        assert!(provider.is_trusted(Some("10.0.0.1:4000".parse().expect("invalid address"))));
+        assert!(!provider.is_trusted(Some("10.0.0.2:4000".parse().expect("invalid address"))));
+        assert!(!provider.is_trusted(None));
+
+        // Test requests have no peer address, so the header must be ignored
+        let request =
+            TestRequest::with_header("X-Client-Cert-Subject", "CN=admin").to_http_request();
+        assert_eq!(
+            provider
+                .get_identity(&request)
+                .expect("failed to get identity"),
+            None
+        );
+    }
+}
@@ -0,0 +1,523 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Authentication and authorization for the REST API.
+//!
+//! Requests are authenticated by a list of [`IdentityProvider`]s, each of which may determine the
+//! [`Identity`] of the client that made the request; the first provider that recognizes the
+//! request's credentials wins. Identities are then granted permissions by an [`Authorizer`].
+//!
+//! Resources are protected by adding a [`PermissionGuard`] to them; the guard requires one
+//! permission for read requests (`GET` and `HEAD`) and another for all other requests. Requests
+//! without a recognized identity are rejected with `401 Unauthorized`, and requests from an
+//! identity that lacks the required permission are rejected with `403 Forbidden`. Requests that
+//! are allowed carry the client's [`Identity`] in their extensions, so that handlers can tell who
+//! made them.
+//!
+//! [`IdentityProvider`]: identity/trait.IdentityProvider.html
+//! [`Identity`]: enum.Identity.html
+//! [`Authorizer`]: struct.Authorizer.html
+//! [`PermissionGuard`]: struct.PermissionGuard.html
+
+mod config;
+mod error;
+pub mod identity;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use actix_web::{http::Method as ActixMethod, HttpRequest, HttpResponse};
+use futures::IntoFuture;
+
+use super::{Continuation, ErrorResponse, RequestGuard};
+
+pub use config::AuthConfig;
+pub use error::{AuthConfigError, IdentityError};
+use identity::IdentityProvider;
+
+/// Permission to list the entries in the audit log
+pub const AUDIT_READ_PERMISSION: &str = "audit.read";
+/// Permission to list and view circuits and circuit proposals
+pub const CIRCUIT_READ_PERMISSION: &str = "circuit.read";
+/// Permission to submit circuit management payloads
+pub const CIRCUIT_WRITE_PERMISSION: &str = "circuit.write";
+/// Permission to list and view registry nodes
+pub const REGISTRY_READ_PERMISSION: &str = "registry.read";
+/// Permission to add, update and remove registry nodes
+pub const REGISTRY_WRITE_PERMISSION: &str = "registry.write";
+/// Permission to read scabbard state and batch statuses
+pub const SCABBARD_READ_PERMISSION: &str = "scabbard.read";
+/// Permission to submit batches to scabbard
+pub const SCABBARD_SUBMIT_PERMISSION: &str = "scabbard.submit";
+
+/// The identity of a client that made a REST API request.
+///
+/// Identities are written as `<kind>:<id>`; for example, `user:<biome user id>`,
+/// `token:<api token name>` or `cert:<client certificate subject>`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Identity {
+    /// A biome user, identified by user ID
+    User(String),
+    /// A static API token, identified by the token's name
+    ApiToken(String),
+    /// A TLS client certificate, identified by its subject
+    ClientCert(String),
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Identity::User(id) => write!(f, "user:{}", id),
+            Identity::ApiToken(name) => write!(f, "token:{}", name),
+            Identity::ClientCert(subject) => write!(f, "cert:{}", subject),
+        }
+    }
+}
+
+impl FromStr for Identity {
+    type Err = AuthConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(_), Some("")) => Err(AuthConfigError::InvalidConfig(format!(
+                "identity is missing an ID: {}",
+                s
+            ))),
+            (Some("user"), Some(id)) => Ok(Identity::User(id.into())),
+            (Some("token"), Some(name)) => Ok(Identity::ApiToken(name.into())),
+            (Some("cert"), Some(subject)) => Ok(Identity::ClientCert(subject.into())),
+            _ => Err(AuthConfigError::InvalidConfig(format!(
+                "identity must be of the form user:<id>, token:<name> or cert:<subject>, got {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Authenticates requests and checks the permissions granted to their identities.
+pub struct Authorizer {
+    identity_providers: Vec<Box<dyn IdentityProvider>>,
+    permissions: HashMap<Identity, HashSet<String>>,
+    authenticated_permissions: HashSet<String>,
+}
+
+impl Authorizer {
+    /// Returns the identity of the client that made the request, using the first identity
+    /// provider that recognizes the request's credentials.
+    pub fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError> {
+        for provider in &self.identity_providers {
+            if let Some(identity) = provider.get_identity(request)? {
+                return Ok(Some(identity));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns `true` if the identity has been granted the permission, either directly, because
+    /// the permission is granted to all authenticated identities, or by one of the identity
+    /// providers.
+    pub fn has_permission(
+        &self,
+        identity: &Identity,
+        permission: &str,
+    ) -> Result<bool, IdentityError> {
+        if self.authenticated_permissions.contains(permission)
+            || self
+                .permissions
+                .get(identity)
+                .map(|permissions| permissions.contains(permission))
+                .unwrap_or(false)
+        {
+            return Ok(true);
+        }
+
+        for provider in &self.identity_providers {
+            if provider.has_permission(identity, permission)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Builds an `Authorizer`.
+#[derive(Default)]
+pub struct AuthorizerBuilder {
+    identity_providers: Vec<Box<dyn IdentityProvider>>,
+    permissions: HashMap<Identity, HashSet<String>>,
+    authenticated_permissions: HashSet<String>,
+}
+
+impl AuthorizerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an identity provider. Providers are consulted in the order they are added.
+    pub fn with_identity_provider(mut self, provider: Box<dyn IdentityProvider>) -> Self {
+        self.identity_providers.push(provider);
+        self
+    }
+
+    /// Grants a permission to the given identity.
+    pub fn with_permission(mut self, identity: Identity, permission: &str) -> Self {
+        self.permissions
+            .entry(identity)
+            .or_insert_with(HashSet::new)
+            .insert(permission.into());
+        self
+    }
+
+    /// Grants a permission to every authenticated identity.
+    pub fn with_authenticated_permission(mut self, permission: &str) -> Self {
+        self.authenticated_permissions.insert(permission.into());
+        self
+    }
+
+    pub fn build(self) -> Authorizer {
+        Authorizer {
+            identity_providers: self.identity_providers,
+            permissions: self.permissions,
+            authenticated_permissions: self.authenticated_permissions,
+        }
+    }
+}
+
+/// Guards a resource by requiring that the client has a permission.
+///
+/// `GET` and `HEAD` requests require the read permission; all other requests require the write
+/// permission.
+#[derive(Clone)]
+pub struct PermissionGuard {
+    authorizer: Arc<Authorizer>,
+    read_permission: String,
+    write_permission: String,
+    owner_parameter: Option<String>,
+}
+
+impl PermissionGuard {
+    pub fn new(authorizer: Arc<Authorizer>, read_permission: &str, write_permission: &str) -> Self {
+        Self {
+            authorizer,
+            read_permission: read_permission.into(),
+            write_permission: write_permission.into(),
+            owner_parameter: None,
+        }
+    }
+
+    /// Also allows users to read resources that belong to them without the read permission. The
+    /// ID of the user that owns the resource is taken from the given path parameter.
+    pub fn with_owner_read_access(mut self, path_parameter: &str) -> Self {
+        self.owner_parameter = Some(path_parameter.into());
+        self
+    }
+
+    fn is_owner(&self, req: &HttpRequest, identity: &Identity) -> bool {
+        match (&self.owner_parameter, identity) {
+            (Some(parameter), Identity::User(user_id)) => {
+                req.match_info().get(parameter) == Some(user_id.as_str())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl RequestGuard for PermissionGuard {
+    fn evaluate(&self, req: &HttpRequest) -> Continuation {
+        let identity = match self.authorizer.get_identity(req) {
+            Ok(Some(identity)) => identity,
+            Ok(None) => {
+                return Continuation::terminate(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized("Not authorized"))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Unable to authenticate request: {}", err);
+                return Continuation::terminate(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        let permission = match *req.method() {
+            ActixMethod::GET | ActixMethod::HEAD => {
+                if self.is_owner(req, &identity) {
+                    req.extensions_mut().insert(identity);
+                    return Continuation::Continue;
+                }
+                &self.read_permission
+            }
+            _ => &self.write_permission,
+        };
+
+        match self.authorizer.has_permission(&identity, permission) {
+            Ok(true) => {
+                // Make the identity available to the resource's handlers
+                req.extensions_mut().insert(identity);
+                Continuation::Continue
+            }
+            Ok(false) => {
+                debug!("{} does not have permission {}", identity, permission);
+                Continuation::terminate(
+                    HttpResponse::Forbidden()
+                        .json(ErrorResponse::forbidden(&format!(
+                            "Client does not have permission {}",
+                            permission
+                        )))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!(
+                    "Unable to check permission {} for {}: {}",
+                    permission, identity, err
+                );
+                Continuation::terminate(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::test::TestRequest;
+    use futures::Future;
+
+    use super::identity::ApiTokenIdentityProvider;
+
+    /// Verify that identities round-trip through their string form and that malformed identities
+    /// are rejected.
+    #[test]
+    fn identity_from_str() {
+        for identity in &[
+            Identity::User("abc".into()),
+            Identity::ApiToken("ci".into()),
+            Identity::ClientCert("CN=admin:1".into()),
+        ] {
+            assert_eq!(
+                &identity
+                    .to_string()
+                    .parse::<Identity>()
+                    .expect("failed to parse identity"),
+                identity
+            );
+        }
+
+        assert!("user:".parse::<Identity>().is_err());
+        assert!("group:admins".parse::<Identity>().is_err());
+        assert!("admin".parse::<Identity>().is_err());
+    }
+
+    /// Verify that API tokens are mapped to their names and that unknown or missing tokens
+    /// produce no identity.
+    #[test]
+    fn api_token_identity() {
+        let mut tokens = HashMap::new();
+        tokens.insert("ci".to_string(), "secret-token".to_string());
+        let provider = ApiTokenIdentityProvider::new(tokens);
+
+        let req = TestRequest::default()
+            .header("Authorization", "Bearer secret-token")
+            .to_http_request();
+        assert_eq!(
+            provider.get_identity(&req).expect("failed to get identity"),
+            Some(Identity::ApiToken("ci".into()))
+        );
+
+        let req = TestRequest::default()
+            .header("Authorization", "Bearer wrong-token")
+            .to_http_request();
+        assert_eq!(
+            provider.get_identity(&req).expect("failed to get identity"),
+            None
+        );
+
+        let req = TestRequest::default()
+            .header("Authorization", "Bearer secret")
+            .to_http_request();
+        assert_eq!(
+            provider.get_identity(&req).expect("failed to get identity"),
+            None
+        );
+
+        let req = TestRequest::default().to_http_request();
+        assert_eq!(
+            provider.get_identity(&req).expect("failed to get identity"),
+            None
+        );
+    }
+
+    /// Verify that the guard returns 401 for unauthenticated requests, 403 for requests without
+    /// the required permission, and continues otherwise; reads and writes require their own
+    /// permissions.
+    #[test]
+    fn permission_guard() {
+        let mut tokens = HashMap::new();
+        tokens.insert("reader".to_string(), "reader-token".to_string());
+        tokens.insert("writer".to_string(), "writer-token".to_string());
+        let authorizer = AuthorizerBuilder::new()
+            .with_identity_provider(Box::new(ApiTokenIdentityProvider::new(tokens)))
+            .with_authenticated_permission(REGISTRY_READ_PERMISSION)
+            .with_permission(
+                Identity::ApiToken("writer".into()),
+                REGISTRY_WRITE_PERMISSION,
+            )
+            .build();
+        let guard = PermissionGuard::new(
+            Arc::new(authorizer),
+            REGISTRY_READ_PERMISSION,
+            REGISTRY_WRITE_PERMISSION,
+        );
+
+        let req = TestRequest::get().to_http_request();
+        assert_terminated(guard.evaluate(&req), 401);
+
+        let req = TestRequest::get()
+            .header("Authorization", "Bearer reader-token")
+            .to_http_request();
+        assert!(is_continue(guard.evaluate(&req)));
+
+        let req = TestRequest::post()
+            .header("Authorization", "Bearer reader-token")
+            .to_http_request();
+        assert_terminated(guard.evaluate(&req), 403);
+
+        let req = TestRequest::post()
+            .header("Authorization", "Bearer writer-token")
+            .to_http_request();
+        assert!(is_continue(guard.evaluate(&req)));
+    }
+
+    /// An identity provider that grants every permission to the "admin" token's identity.
+    struct AdminProvider(ApiTokenIdentityProvider);
+
+    impl IdentityProvider for AdminProvider {
+        fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError> {
+            self.0.get_identity(request)
+        }
+
+        fn has_permission(&self, identity: &Identity, _: &str) -> Result<bool, IdentityError> {
+            Ok(identity == &Identity::ApiToken("admin".into()))
+        }
+    }
+
+    /// Verify that the guard accepts permissions granted by an identity provider.
+    #[test]
+    fn permission_guard_provider_permissions() {
+        let mut tokens = HashMap::new();
+        tokens.insert("admin".to_string(), "admin-token".to_string());
+        tokens.insert("other".to_string(), "other-token".to_string());
+        let authorizer = AuthorizerBuilder::new()
+            .with_identity_provider(Box::new(AdminProvider(ApiTokenIdentityProvider::new(
+                tokens,
+            ))))
+            .build();
+        let guard = PermissionGuard::new(
+            Arc::new(authorizer),
+            REGISTRY_READ_PERMISSION,
+            REGISTRY_WRITE_PERMISSION,
+        );
+
+        let req = TestRequest::post()
+            .header("Authorization", "Bearer admin-token")
+            .to_http_request();
+        assert!(is_continue(guard.evaluate(&req)));
+
+        let req = TestRequest::post()
+            .header("Authorization", "Bearer other-token")
+            .to_http_request();
+        assert_terminated(guard.evaluate(&req), 403);
+    }
+
+    /// An identity provider that identifies the "alice-token" token as the user "alice".
+    struct UserProvider;
+
+    impl IdentityProvider for UserProvider {
+        fn get_identity(&self, request: &HttpRequest) -> Result<Option<Identity>, IdentityError> {
+            match request.headers().get("Authorization") {
+                Some(value) if value == "Bearer alice-token" => {
+                    Ok(Some(Identity::User("alice".into())))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    /// Verify that a guard with owner read access lets users read their own resources without
+    /// the read permission, but not other users' resources, and not write their own resources.
+    #[test]
+    fn permission_guard_owner_read_access() {
+        let authorizer = AuthorizerBuilder::new()
+            .with_identity_provider(Box::new(UserProvider))
+            .build();
+        let guard = PermissionGuard::new(
+            Arc::new(authorizer),
+            REGISTRY_READ_PERMISSION,
+            REGISTRY_WRITE_PERMISSION,
+        )
+        .with_owner_read_access("id");
+
+        let req = TestRequest::get()
+            .param("id", "alice")
+            .header("Authorization", "Bearer alice-token")
+            .to_http_request();
+        assert!(is_continue(guard.evaluate(&req)));
+        assert_eq!(
+            req.extensions().get::<Identity>(),
+            Some(&Identity::User("alice".into()))
+        );
+
+        let req = TestRequest::get()
+            .param("id", "bob")
+            .header("Authorization", "Bearer alice-token")
+            .to_http_request();
+        assert_terminated(guard.evaluate(&req), 403);
+
+        let req = TestRequest::put()
+            .param("id", "alice")
+            .header("Authorization", "Bearer alice-token")
+            .to_http_request();
+        assert_terminated(guard.evaluate(&req), 403);
+    }
+
+    fn is_continue(continuation: Continuation) -> bool {
+        match continuation {
+            Continuation::Continue => true,
+            Continuation::Terminate(_) => false,
+        }
+    }
+
+    fn assert_terminated(continuation: Continuation, status: u16) {
+        match continuation {
+            Continuation::Terminate(fut) => {
+                let response = fut.wait().expect("failed to get response");
+                assert_eq!(response.status().as_u16(), status);
+            }
+            Continuation::Continue => panic!("expected request to be terminated"),
+        }
+    }
+}
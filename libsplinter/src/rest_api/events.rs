@@ -66,6 +66,12 @@ impl<T: Serialize + Debug + 'static> EventSender<T> {
             })
     }
 
+    /// Returns `true` if the websocket that receives the events has been closed, in which case
+    /// any further events would fail to send.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
     pub fn shutdown(self) {
         if self
             .sender
@@ -51,6 +51,8 @@
 //!     .run();
 //! ```
 
+#[cfg(feature = "rest-api-auth")]
+pub mod auth;
 #[cfg(feature = "rest-api-cors")]
 pub mod cors;
 mod errors;
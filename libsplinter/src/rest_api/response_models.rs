@@ -61,4 +61,11 @@ impl ErrorResponse {
             message: message.to_string(),
         }
     }
+
+    pub fn service_unavailable(message: &str) -> ErrorResponse {
+        ErrorResponse {
+            code: "503".to_string(),
+            message: message.to_string(),
+        }
+    }
 }
@@ -0,0 +1,77 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::time::Duration;
+
+use super::{FileSecretManagerBuilder, SecretManagerError, SigningAlgorithm};
+
+/// Configures how the keys of a FileSecretManager are generated and rotated.
+///
+/// The configuration can be loaded from a YAML file of the following form:
+///
+/// ```yaml
+/// # Optional; one of HS256, RS256 or ES256, defaults to HS256
+/// algorithm: ES256
+/// # Optional, in seconds; if not set, the keys are never rotated automatically
+/// rotation_interval: 86400
+/// # Optional; the number of previous keys kept to verify tokens, defaults to 2
+/// retained_keys: 2
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct KeyringConfig {
+    #[serde(default)]
+    algorithm: SigningAlgorithm,
+    #[serde(default)]
+    rotation_interval: Option<u64>,
+    #[serde(default)]
+    retained_keys: Option<usize>,
+}
+
+impl KeyringConfig {
+    /// Loads the configuration from a YAML file
+    pub fn from_file(path: &str) -> Result<Self, SecretManagerError> {
+        let file = File::open(path).map_err(|err| {
+            SecretManagerError::InvalidConfig(format!("unable to open {}: {}", path, err))
+        })?;
+        serde_yaml::from_reader(file).map_err(|err| {
+            SecretManagerError::InvalidConfig(format!("unable to parse {}: {}", path, err))
+        })
+    }
+
+    pub fn algorithm(&self) -> SigningAlgorithm {
+        self.algorithm
+    }
+
+    pub fn rotation_interval(&self) -> Option<Duration> {
+        self.rotation_interval.map(Duration::from_secs)
+    }
+
+    pub fn retained_keys(&self) -> Option<usize> {
+        self.retained_keys
+    }
+
+    /// Returns a builder for a FileSecretManager that keeps its keys in the file at the given
+    /// path and is configured by this configuration
+    pub fn secret_manager_builder(&self, path: &str) -> FileSecretManagerBuilder {
+        let mut builder = FileSecretManagerBuilder::new(path).with_algorithm(self.algorithm);
+        if let Some(retained_keys) = self.retained_keys {
+            builder = builder.with_retained_keys(retained_keys);
+        }
+        if let Some(interval) = self.rotation_interval() {
+            builder = builder.with_rotation_interval(interval);
+        }
+        builder
+    }
+}
@@ -22,6 +22,8 @@ pub enum SecretManagerError {
     UpdateSecretError(Box<dyn Error>),
     /// Returned when the manager fails to fetch a secret
     SecretError(Box<dyn Error>),
+    /// Returned when the manager's configuration is invalid
+    InvalidConfig(String),
 }
 
 impl Error for SecretManagerError {
@@ -29,6 +31,7 @@ impl Error for SecretManagerError {
         match self {
             SecretManagerError::UpdateSecretError(err) => Some(&**err),
             SecretManagerError::SecretError(err) => Some(&**err),
+            SecretManagerError::InvalidConfig(_) => None,
         }
     }
 }
@@ -40,6 +43,9 @@ impl fmt::Display for SecretManagerError {
                 write!(f, "failed to update secret: {}", s)
             }
             SecretManagerError::SecretError(ref s) => write!(f, "failed to fetch secret: {}", s),
+            SecretManagerError::InvalidConfig(ref s) => {
+                write!(f, "invalid secret manager configuration: {}", s)
+            }
         }
     }
 }
@@ -0,0 +1,804 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A SecretManager that keeps its signing keys in a file, so that tokens remain valid across
+//! restarts and can be shared by every REST API instance that reads the same file.
+
+use std::cmp;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use jsonwebtoken::Algorithm;
+use nix::fcntl::{flock, FlockArg};
+use openssl::base64;
+use openssl::bn::BigNumContext;
+use openssl::ec::{EcGroup, EcKey, PointConversionForm};
+use openssl::nid::Nid;
+use openssl::pkey::PKey;
+use openssl::rsa::Rsa;
+use uuid::Uuid;
+
+use super::auto_secret_manager::generate_random_secret;
+use super::{Jwk, SecretManager, SecretManagerError, SigningKey, VerificationKey};
+
+/// The number of previous keys that are kept, by default, to verify tokens after a rotation
+const DEFAULT_RETAINED_KEYS: usize = 2;
+const RSA_KEY_BITS: u32 = 2048;
+/// The longest time the rotation thread waits before checking if the keys are due for rotation
+const MAX_ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The algorithms a FileSecretManager can sign tokens with
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SigningAlgorithm {
+    /// HMAC using SHA-256, with a shared secret
+    #[serde(rename = "HS256")]
+    Hs256,
+    /// RSASSA-PKCS1-v1_5 using SHA-256, with a 2048-bit key
+    #[serde(rename = "RS256")]
+    Rs256,
+    /// ECDSA using the P-256 curve and SHA-256
+    #[serde(rename = "ES256")]
+    Es256,
+}
+
+impl SigningAlgorithm {
+    fn algorithm(self) -> Algorithm {
+        match self {
+            SigningAlgorithm::Hs256 => Algorithm::HS256,
+            SigningAlgorithm::Rs256 => Algorithm::RS256,
+            SigningAlgorithm::Es256 => Algorithm::ES256,
+        }
+    }
+}
+
+impl Default for SigningAlgorithm {
+    fn default() -> Self {
+        SigningAlgorithm::Hs256
+    }
+}
+
+impl FromStr for SigningAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "HS256" => Ok(SigningAlgorithm::Hs256),
+            "RS256" => Ok(SigningAlgorithm::Rs256),
+            "ES256" => Ok(SigningAlgorithm::Es256),
+            _ => Err(format!("unsupported signing algorithm: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for SigningAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SigningAlgorithm::Hs256 => f.write_str("HS256"),
+            SigningAlgorithm::Rs256 => f.write_str("RS256"),
+            SigningAlgorithm::Es256 => f.write_str("ES256"),
+        }
+    }
+}
+
+/// A key as it is saved in the keyring file
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredKey {
+    key_id: String,
+    algorithm: SigningAlgorithm,
+    /// When the key was created, in seconds since the epoch
+    created: u64,
+    /// The secret for HS256 keys, or the base64-encoded DER private key otherwise
+    private_key: String,
+    /// The base64-encoded public key, as expected by `jsonwebtoken::decode`, for RS256 and
+    /// ES256 keys
+    #[serde(default)]
+    public_key: Option<String>,
+}
+
+impl StoredKey {
+    fn generate(algorithm: SigningAlgorithm) -> Result<Self, String> {
+        let (private_key, public_key) = match algorithm {
+            SigningAlgorithm::Hs256 => (generate_random_secret(), None),
+            SigningAlgorithm::Rs256 => {
+                let rsa = Rsa::generate(RSA_KEY_BITS).map_err(|err| err.to_string())?;
+                let private_key = rsa.private_key_to_der().map_err(|err| err.to_string())?;
+                let public_key = rsa
+                    .public_key_to_der_pkcs1()
+                    .map_err(|err| err.to_string())?;
+                (
+                    base64::encode_block(&private_key),
+                    Some(base64::encode_block(&public_key)),
+                )
+            }
+            SigningAlgorithm::Es256 => {
+                let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)
+                    .map_err(|err| err.to_string())?;
+                let ec_key = EcKey::generate(&group).map_err(|err| err.to_string())?;
+                let mut ctx = BigNumContext::new().map_err(|err| err.to_string())?;
+                let public_key = ec_key
+                    .public_key()
+                    .to_bytes(&group, PointConversionForm::UNCOMPRESSED, &mut ctx)
+                    .map_err(|err| err.to_string())?;
+                // The body of a PEM file is the base64-encoded DER key
+                let pem = PKey::from_ec_key(ec_key)
+                    .and_then(|pkey| pkey.private_key_to_pem_pkcs8())
+                    .map_err(|err| err.to_string())?;
+                let private_key = String::from_utf8(pem)
+                    .map_err(|err| err.to_string())?
+                    .lines()
+                    .filter(|line| !line.starts_with("-----"))
+                    .collect::<String>();
+                (private_key, Some(base64::encode_block(&public_key)))
+            }
+        };
+
+        Ok(StoredKey {
+            key_id: Uuid::new_v4().to_string(),
+            algorithm,
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0),
+            private_key,
+            public_key,
+        })
+    }
+
+    fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH + Duration::from_secs(self.created))
+            .unwrap_or_default()
+    }
+
+    fn signing_key(&self) -> Result<SigningKey, String> {
+        let key = match self.algorithm {
+            SigningAlgorithm::Hs256 => self.private_key.as_bytes().to_vec(),
+            _ => base64::decode_block(&self.private_key).map_err(|err| err.to_string())?,
+        };
+
+        Ok(SigningKey {
+            key_id: Some(self.key_id.clone()),
+            algorithm: self.algorithm.algorithm(),
+            key,
+        })
+    }
+
+    fn verification_key(&self) -> Result<VerificationKey, String> {
+        let key = match (self.algorithm, &self.public_key) {
+            (SigningAlgorithm::Hs256, _) => self.private_key.as_bytes().to_vec(),
+            (_, Some(public_key)) => {
+                base64::decode_block(public_key).map_err(|err| err.to_string())?
+            }
+            (_, None) => return Err(format!("key {} has no public key", self.key_id)),
+        };
+
+        Ok(VerificationKey {
+            algorithm: self.algorithm.algorithm(),
+            key,
+        })
+    }
+
+    fn jwk(&self) -> Result<Option<Jwk>, String> {
+        let public_key = match (self.algorithm, &self.public_key) {
+            (SigningAlgorithm::Hs256, _) => return Ok(None),
+            (_, Some(public_key)) => {
+                base64::decode_block(public_key).map_err(|err| err.to_string())?
+            }
+            (_, None) => return Err(format!("key {} has no public key", self.key_id)),
+        };
+
+        let mut jwk = Jwk {
+            kty: String::new(),
+            kid: self.key_id.clone(),
+            alg: self.algorithm.to_string(),
+            key_use: "sig".into(),
+            n: None,
+            e: None,
+            crv: None,
+            x: None,
+            y: None,
+        };
+
+        if self.algorithm == SigningAlgorithm::Rs256 {
+            let rsa = Rsa::public_key_from_der_pkcs1(&public_key).map_err(|err| err.to_string())?;
+            jwk.kty = "RSA".into();
+            jwk.n = Some(base64url(&rsa.n().to_vec()));
+            jwk.e = Some(base64url(&rsa.e().to_vec()));
+        } else {
+            // An uncompressed P-256 point is 0x04 followed by the 32-byte x and y coordinates
+            if public_key.len() != 65 {
+                return Err(format!("key {} has an invalid public key", self.key_id));
+            }
+            jwk.kty = "EC".into();
+            jwk.crv = Some("P-256".into());
+            jwk.x = Some(base64url(&public_key[1..33]));
+            jwk.y = Some(base64url(&public_key[33..]));
+        }
+
+        Ok(Some(jwk))
+    }
+}
+
+fn base64url(bytes: &[u8]) -> String {
+    base64::encode_block(bytes)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+/// The contents of the keyring file
+#[derive(Default, Serialize, Deserialize)]
+struct KeyringFile {
+    /// The keys, newest first; the newest key signs new tokens
+    keys: Vec<StoredKey>,
+}
+
+struct Keyring {
+    file: AtomicFile,
+    algorithm: SigningAlgorithm,
+    retained_keys: usize,
+    /// The keys, newest first
+    keys: RwLock<Vec<StoredKey>>,
+    /// The modification time of the file when the keys were last read
+    modified: Mutex<Option<SystemTime>>,
+}
+
+impl Keyring {
+    /// Reads the keys from the file; there are no keys if the file does not exist yet
+    fn reload(&self, keys: &mut Vec<StoredKey>) -> Result<(), String> {
+        let mut modified = self
+            .modified
+            .lock()
+            .map_err(|_| "keyring modified time lock poisoned".to_string())?;
+        *keys = match File::open(self.file.path()) {
+            Ok(file) => {
+                *modified = file
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .ok();
+                serde_yaml::from_reader::<_, KeyringFile>(file)
+                    .map_err(|err| {
+                        format!("unable to parse {}: {}", self.file.path().display(), err)
+                    })?
+                    .keys
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                *modified = None;
+                vec![]
+            }
+            Err(err) => {
+                return Err(format!(
+                    "unable to open {}: {}",
+                    self.file.path().display(),
+                    err
+                ))
+            }
+        };
+        Ok(())
+    }
+
+    /// Takes an exclusive advisory lock on the `.lock` file next to the keyring file, so that
+    /// only one of the instances sharing the file rotates the keys at a time. The lock is
+    /// released when the returned file is dropped.
+    fn lock_file(&self) -> Result<File, String> {
+        let mut path = OsString::from(self.file.path());
+        path.push(".lock");
+        let path = PathBuf::from(path);
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(&path)
+            .map_err(|err| format!("unable to open {}: {}", path.display(), err))?;
+        flock(file.as_raw_fd(), FlockArg::LockExclusive)
+            .map_err(|err| format!("unable to lock {}: {}", path.display(), err))?;
+        Ok(file)
+    }
+
+    /// Adds a new key, which becomes the signing key, and drops the keys that are no longer
+    /// retained
+    fn rotate(&self) -> Result<(), String> {
+        let mut keys = self
+            .keys
+            .write()
+            .map_err(|_| "keyring lock poisoned".to_string())?;
+        let _lock = self.lock_file()?;
+        // Another instance sharing the file may have rotated the keys
+        self.reload(&mut keys)?;
+        self.rotate_keys(&mut keys)
+    }
+
+    fn rotate_keys(&self, keys: &mut Vec<StoredKey>) -> Result<(), String> {
+        let mut new_keys = keys.clone();
+        new_keys.insert(0, StoredKey::generate(self.algorithm)?);
+        new_keys.truncate(self.retained_keys + 1);
+
+        let contents = KeyringFile { keys: new_keys };
+        self.file
+            .write(|file| {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+                }
+                serde_yaml::to_writer(file, &contents)
+                    .map_err(|err| io::Error::new(ErrorKind::Other, err))
+            })
+            .map_err(|err| format!("unable to write {}: {}", self.file.path().display(), err))?;
+
+        debug!("Rotated JWT signing keys in {}", self.file.path().display());
+
+        let mut modified = self
+            .modified
+            .lock()
+            .map_err(|_| "keyring modified time lock poisoned".to_string())?;
+        *modified = self.file_modified();
+        *keys = contents.keys;
+        Ok(())
+    }
+
+    /// Rotates the keys if there is no signing key, if the signing key uses a different
+    /// algorithm than the configured one, or if the signing key is older than `max_age`
+    fn rotate_if_needed(&self, max_age: Option<Duration>) -> Result<(), String> {
+        let mut keys = self
+            .keys
+            .write()
+            .map_err(|_| "keyring lock poisoned".to_string())?;
+        let _lock = self.lock_file()?;
+        self.reload(&mut keys)?;
+
+        let needs_rotation = match keys.first() {
+            Some(key) => {
+                key.algorithm != self.algorithm
+                    || max_age.map(|max_age| key.age() >= max_age).unwrap_or(false)
+            }
+            None => true,
+        };
+
+        if needs_rotation {
+            self.rotate_keys(&mut keys)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn signing_key(&self) -> Result<SigningKey, String> {
+        let keys = self
+            .keys
+            .read()
+            .map_err(|_| "keyring lock poisoned".to_string())?;
+        keys.first()
+            .ok_or_else(|| "keyring has no keys".to_string())?
+            .signing_key()
+    }
+
+    /// Returns the modification time of the file, if it exists
+    fn file_modified(&self) -> Option<SystemTime> {
+        fs::metadata(self.file.path())
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Returns `true` if the file has been modified since the keys were last read
+    fn file_changed(&self) -> Result<bool, String> {
+        let loaded = *self
+            .modified
+            .lock()
+            .map_err(|_| "keyring modified time lock poisoned".to_string())?;
+        Ok(self.file_modified() != loaded)
+    }
+
+    fn verification_key(&self, key_id: &str) -> Result<Option<VerificationKey>, String> {
+        {
+            let keys = self
+                .keys
+                .read()
+                .map_err(|_| "keyring lock poisoned".to_string())?;
+            if let Some(key) = keys.iter().find(|key| key.key_id == key_id) {
+                return key.verification_key().map(Some);
+            }
+        }
+
+        // The key may have been added by another instance sharing the file. The file is only read
+        // again if it has changed, so that tokens with made-up key IDs can not cause the file to
+        // be read on every request.
+        if !self.file_changed()? {
+            return Ok(None);
+        }
+        let mut keys = self
+            .keys
+            .write()
+            .map_err(|_| "keyring lock poisoned".to_string())?;
+        self.reload(&mut keys)?;
+        keys.iter()
+            .find(|key| key.key_id == key_id)
+            .map(StoredKey::verification_key)
+            .transpose()
+    }
+
+    fn public_keys(&self) -> Result<Vec<Jwk>, String> {
+        let keys = self
+            .keys
+            .read()
+            .map_err(|_| "keyring lock poisoned".to_string())?;
+        keys.iter()
+            .filter_map(|key| key.jwk().transpose())
+            .collect()
+    }
+}
+
+/// A SecretManager that keeps a keyring of signing keys in a YAML file.
+///
+/// The newest key in the keyring signs new tokens, and its ID is set as the `kid` header of the
+/// tokens. When the keys are rotated, a new key is added and the previous keys are retained, up
+/// to a configured number, so that the tokens they signed remain valid until they expire.
+///
+/// Several REST API instances may share a keyring file, in which case tokens issued by one
+/// instance are accepted by the others: a token signed with a key the manager does not know
+/// causes the file to be read again if it has been modified since it was last read.
+pub struct FileSecretManager {
+    keyring: Arc<Keyring>,
+    // Dropping the sender stops the rotation thread
+    _rotation_sender: Option<Mutex<Sender<()>>>,
+}
+
+impl FileSecretManager {
+    /// Rotates the keys: a new signing key is generated and the oldest retained key is dropped
+    /// if there are more keys than are retained.
+    pub fn rotate(&self) -> Result<(), SecretManagerError> {
+        self.keyring
+            .rotate()
+            .map_err(|err| SecretManagerError::UpdateSecretError(err.into()))
+    }
+}
+
+impl SecretManager for FileSecretManager {
+    /// Returns the secret of the signing key, which is only available if the keys are HS256 keys
+    fn secret(&self) -> Result<String, SecretManagerError> {
+        let key = self.signing_key()?;
+        if key.algorithm != Algorithm::HS256 {
+            return Err(SecretManagerError::SecretError(
+                "the signing key is not a shared secret".into(),
+            ));
+        }
+        String::from_utf8(key.key).map_err(|err| SecretManagerError::SecretError(Box::new(err)))
+    }
+
+    fn update_secret(&mut self) -> Result<(), SecretManagerError> {
+        self.rotate()
+    }
+
+    fn signing_key(&self) -> Result<SigningKey, SecretManagerError> {
+        self.keyring
+            .signing_key()
+            .map_err(|err| SecretManagerError::SecretError(err.into()))
+    }
+
+    fn verification_key(
+        &self,
+        key_id: Option<&str>,
+    ) -> Result<Option<VerificationKey>, SecretManagerError> {
+        match key_id {
+            Some(key_id) => self
+                .keyring
+                .verification_key(key_id)
+                .map_err(|err| SecretManagerError::SecretError(err.into())),
+            // Every token signed by a keyring key carries the key's ID
+            None => Ok(None),
+        }
+    }
+
+    fn public_keys(&self) -> Result<Vec<Jwk>, SecretManagerError> {
+        self.keyring
+            .public_keys()
+            .map_err(|err| SecretManagerError::SecretError(err.into()))
+    }
+}
+
+/// Builder for FileSecretManager
+pub struct FileSecretManagerBuilder {
+    path: String,
+    algorithm: SigningAlgorithm,
+    retained_keys: usize,
+    rotation_interval: Option<Duration>,
+}
+
+impl FileSecretManagerBuilder {
+    /// Creates a builder for a FileSecretManager that keeps its keys in the file at the given
+    /// path; the file is created if it does not exist.
+    pub fn new(path: &str) -> Self {
+        FileSecretManagerBuilder {
+            path: path.into(),
+            algorithm: SigningAlgorithm::default(),
+            retained_keys: DEFAULT_RETAINED_KEYS,
+            rotation_interval: None,
+        }
+    }
+
+    /// Sets the algorithm new keys are generated for; defaults to HS256.
+    ///
+    /// If the signing key in the file uses a different algorithm, the keys are rotated when the
+    /// manager is built.
+    pub fn with_algorithm(mut self, algorithm: SigningAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the number of previous keys that are kept to verify tokens after a rotation;
+    /// defaults to 2.
+    pub fn with_retained_keys(mut self, retained_keys: usize) -> Self {
+        self.retained_keys = retained_keys;
+        self
+    }
+
+    /// Rotates the keys in a background thread whenever the signing key is older than the given
+    /// interval. By default, the keys are only rotated when `FileSecretManager::rotate` is
+    /// called.
+    pub fn with_rotation_interval(mut self, interval: Duration) -> Self {
+        self.rotation_interval = Some(interval);
+        self
+    }
+
+    /// Consumes the builder, loads the keyring and returns the FileSecretManager
+    pub fn build(self) -> Result<FileSecretManager, SecretManagerError> {
+        let keyring = Arc::new(Keyring {
+            file: AtomicFile::new(&self.path, AllowOverwrite),
+            algorithm: self.algorithm,
+            retained_keys: self.retained_keys,
+            keys: RwLock::new(vec![]),
+            modified: Mutex::new(None),
+        });
+
+        keyring
+            .rotate_if_needed(self.rotation_interval)
+            .map_err(|err| SecretManagerError::SecretError(err.into()))?;
+
+        let rotation_sender = match self.rotation_interval {
+            Some(interval) => {
+                let (sender, receiver) = channel::<()>();
+                let thread_keyring = keyring.clone();
+                let check_interval = cmp::min(interval, MAX_ROTATION_CHECK_INTERVAL);
+
+                thread::Builder::new()
+                    .name("JwtKeyRotation".into())
+                    .spawn(move || loop {
+                        match receiver.recv_timeout(check_interval) {
+                            Err(RecvTimeoutError::Timeout) => {
+                                if let Err(err) = thread_keyring.rotate_if_needed(Some(interval)) {
+                                    error!("Unable to rotate JWT signing keys: {}", err);
+                                }
+                            }
+                            _ => break,
+                        }
+                    })
+                    .map_err(|err| SecretManagerError::SecretError(Box::new(err)))?;
+
+                Some(Mutex::new(sender))
+            }
+            None => None,
+        };
+
+        Ok(FileSecretManager {
+            keyring,
+            _rotation_sender: rotation_sender,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use jsonwebtoken::{decode, encode, Header, Validation};
+    use tempdir::TempDir;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TestClaims {
+        sub: String,
+        exp: u64,
+    }
+
+    fn sign_and_verify(manager: &FileSecretManager) {
+        let claims = TestClaims {
+            sub: "user".into(),
+            exp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                + 60,
+        };
+
+        let signing_key = manager.signing_key().expect("Failed to get signing key");
+        let mut header = Header::new(signing_key.algorithm);
+        header.kid = signing_key.key_id.clone();
+        let token = encode(&header, &claims, &signing_key.key).expect("Failed to sign token");
+
+        let verification_key = manager
+            .verification_key(signing_key.key_id.as_deref())
+            .expect("Failed to get verification key")
+            .expect("Verification key not found");
+        let mut validation = Validation::default();
+        validation.algorithms = vec![verification_key.algorithm];
+        let decoded = decode::<TestClaims>(&token, &verification_key.key, &validation)
+            .expect("Failed to verify token");
+        assert_eq!(decoded.claims, claims);
+    }
+
+    /// Verifies that the keys are saved to the file, so that a manager created from the same
+    /// file signs with the same key.
+    #[test]
+    fn test_keys_persisted() {
+        let temp_dir = TempDir::new("test_keys_persisted").expect("Failed to create temp dir");
+        let path = temp_dir.path().join("keys.yaml");
+        let path = path.to_str().unwrap();
+
+        let first = FileSecretManagerBuilder::new(path)
+            .build()
+            .expect("Failed to build manager");
+        let second = FileSecretManagerBuilder::new(path)
+            .build()
+            .expect("Failed to build manager");
+
+        assert_eq!(
+            first.signing_key().unwrap().key_id,
+            second.signing_key().unwrap().key_id
+        );
+        assert_eq!(first.secret().unwrap(), second.secret().unwrap());
+        sign_and_verify(&second);
+    }
+
+    /// Verifies that rotating the keys changes the signing key and retains the configured number
+    /// of previous keys for verification, and that other managers sharing the file find the new
+    /// key.
+    #[test]
+    fn test_rotation() {
+        let temp_dir = TempDir::new("test_rotation").expect("Failed to create temp dir");
+        let path = temp_dir.path().join("keys.yaml");
+        let path = path.to_str().unwrap();
+
+        let manager = FileSecretManagerBuilder::new(path)
+            .with_retained_keys(1)
+            .build()
+            .expect("Failed to build manager");
+        let other = FileSecretManagerBuilder::new(path)
+            .build()
+            .expect("Failed to build manager");
+
+        let first_key_id = manager.signing_key().unwrap().key_id.unwrap();
+        manager.rotate().expect("Failed to rotate keys");
+        let second_key_id = manager.signing_key().unwrap().key_id.unwrap();
+        assert_ne!(first_key_id, second_key_id);
+        assert!(manager
+            .verification_key(Some(&first_key_id))
+            .unwrap()
+            .is_some());
+        assert!(other
+            .verification_key(Some(&second_key_id))
+            .unwrap()
+            .is_some());
+
+        manager.rotate().expect("Failed to rotate keys");
+        assert!(manager
+            .verification_key(Some(&first_key_id))
+            .unwrap()
+            .is_none());
+        assert!(manager
+            .verification_key(Some(&second_key_id))
+            .unwrap()
+            .is_some());
+    }
+
+    /// Verifies that tokens can be signed and verified with RS256 and ES256 keys, and that the
+    /// public keys are published; also verifies that changing the algorithm rotates the keys.
+    #[test]
+    fn test_asymmetric_keys() {
+        let temp_dir = TempDir::new("test_asymmetric_keys").expect("Failed to create temp dir");
+        let path = temp_dir.path().join("keys.yaml");
+        let path = path.to_str().unwrap();
+
+        let manager = FileSecretManagerBuilder::new(path)
+            .with_algorithm(SigningAlgorithm::Rs256)
+            .build()
+            .expect("Failed to build manager");
+        sign_and_verify(&manager);
+        assert!(manager.secret().is_err());
+        let jwks = manager.public_keys().expect("Failed to get public keys");
+        assert_eq!(jwks.len(), 1);
+        assert_eq!(jwks[0].kty, "RSA");
+
+        let manager = FileSecretManagerBuilder::new(path)
+            .with_algorithm(SigningAlgorithm::Es256)
+            .build()
+            .expect("Failed to build manager");
+        sign_and_verify(&manager);
+        let jwks = manager.public_keys().expect("Failed to get public keys");
+        assert_eq!(jwks.len(), 2);
+        assert_eq!(jwks[0].kty, "EC");
+        assert_eq!(jwks[0].crv.as_deref(), Some("P-256"));
+    }
+
+    /// Verifies that managers sharing a file that rotate the keys concurrently do not overwrite
+    /// each other's new keys.
+    #[test]
+    fn test_concurrent_rotation() {
+        let temp_dir = TempDir::new("test_concurrent_rotation").expect("Failed to create temp dir");
+        let path = temp_dir.path().join("keys.yaml");
+
+        let handles = (0..2)
+            .map(|_| {
+                let manager = FileSecretManagerBuilder::new(path.to_str().unwrap())
+                    .with_retained_keys(10)
+                    .build()
+                    .expect("Failed to build manager");
+                thread::spawn(move || {
+                    for _ in 0..5 {
+                        manager.rotate().expect("Failed to rotate keys");
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().expect("Rotation thread panicked");
+        }
+
+        // The key generated when the file was created and the 10 rotated keys are all kept
+        let keyring: KeyringFile =
+            serde_yaml::from_reader(File::open(&path).expect("Failed to open file"))
+                .expect("Failed to parse file");
+        assert_eq!(keyring.keys.len(), 11);
+    }
+
+    /// Verifies that a token with an unknown key ID only causes the file to be read again if the
+    /// file has been modified since it was last read.
+    #[test]
+    fn test_unknown_key_reload() {
+        let temp_dir = TempDir::new("test_unknown_key_reload").expect("Failed to create temp dir");
+        let path = temp_dir.path().join("keys.yaml");
+
+        let manager = FileSecretManagerBuilder::new(path.to_str().unwrap())
+            .build()
+            .expect("Failed to build manager");
+
+        // Replace the file with one that can not be parsed, keeping its modification time
+        let modified = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .expect("Failed to get modification time");
+        fs::write(&path, "not a keyring").expect("Failed to write file");
+        let file = File::options()
+            .write(true)
+            .open(&path)
+            .expect("Failed to open file");
+        file.set_modified(modified)
+            .expect("Failed to set modification time");
+
+        assert!(manager
+            .verification_key(Some("unknown"))
+            .expect("Failed to get verification key")
+            .is_none());
+
+        file.set_modified(modified + Duration::from_secs(1))
+            .expect("Failed to set modification time");
+        assert!(manager.verification_key(Some("unknown")).is_err());
+    }
+}
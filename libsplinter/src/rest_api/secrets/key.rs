@@ -0,0 +1,66 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jsonwebtoken::Algorithm;
+
+/// A key that JWT tokens are signed with
+#[derive(Clone)]
+pub struct SigningKey {
+    /// The ID of the key, which is set as the `kid` header of the tokens it signs
+    pub key_id: Option<String>,
+    /// The algorithm the tokens are signed with
+    pub algorithm: Algorithm,
+    /// The key, as expected by `jsonwebtoken::encode`: the secret for HMAC algorithms, the
+    /// DER-encoded PKCS#1 private key for RSA, or the DER-encoded PKCS#8 private key for ECDSA
+    pub key: Vec<u8>,
+}
+
+/// A key that verifies the signature of JWT tokens
+#[derive(Clone)]
+pub struct VerificationKey {
+    /// The algorithm the tokens were signed with
+    pub algorithm: Algorithm,
+    /// The key, as expected by `jsonwebtoken::decode`: the secret for HMAC algorithms, the
+    /// DER-encoded PKCS#1 public key for RSA, or the uncompressed public point for ECDSA
+    pub key: Vec<u8>,
+}
+
+/// A public key in the JSON Web Key format (RFC 7517)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Jwk {
+    /// The key type, either `RSA` or `EC`
+    pub kty: String,
+    /// The ID of the key, which matches the `kid` header of the tokens it verifies
+    pub kid: String,
+    /// The algorithm the key is used with
+    pub alg: String,
+    /// The intended use of the key, which is always `sig`
+    #[serde(rename = "use")]
+    pub key_use: String,
+    /// The modulus of an RSA key, base64url-encoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    /// The exponent of an RSA key, base64url-encoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    /// The curve of an EC key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    /// The x coordinate of an EC key, base64url-encoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    /// The y coordinate of an EC key, base64url-encoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
@@ -15,10 +15,22 @@
 //! Provides an API for managing secrets
 
 mod auto_secret_manager;
+#[cfg(feature = "rest-api-keyring")]
+mod config;
 mod error;
+#[cfg(feature = "rest-api-keyring")]
+mod file_secret_manager;
+mod key;
+
+use jsonwebtoken::Algorithm;
 
 pub use auto_secret_manager::AutoSecretManager;
+#[cfg(feature = "rest-api-keyring")]
+pub use config::KeyringConfig;
 pub use error::SecretManagerError;
+#[cfg(feature = "rest-api-keyring")]
+pub use file_secret_manager::{FileSecretManager, FileSecretManagerBuilder, SigningAlgorithm};
+pub use key::{Jwk, SigningKey, VerificationKey};
 
 /// Defines a manager for fetching and/or generating a secret.
 pub trait SecretManager: Sync + Send {
@@ -27,4 +39,37 @@ pub trait SecretManager: Sync + Send {
 
     /// Updates the secret
     fn update_secret(&mut self) -> Result<(), SecretManagerError>;
+
+    /// Returns the key that new tokens are signed with.
+    ///
+    /// By default, tokens are signed with the secret using HS256 and do not carry a key ID.
+    fn signing_key(&self) -> Result<SigningKey, SecretManagerError> {
+        Ok(SigningKey {
+            key_id: None,
+            algorithm: Algorithm::HS256,
+            key: self.secret()?.into_bytes(),
+        })
+    }
+
+    /// Returns the key that verifies tokens carrying the given key ID, or `None` if the key is
+    /// not known to the manager (for instance, because it has been rotated out).
+    ///
+    /// By default, every token is verified with the secret using HS256.
+    fn verification_key(
+        &self,
+        _key_id: Option<&str>,
+    ) -> Result<Option<VerificationKey>, SecretManagerError> {
+        Ok(Some(VerificationKey {
+            algorithm: Algorithm::HS256,
+            key: self.secret()?.into_bytes(),
+        }))
+    }
+
+    /// Returns the public keys that verify the tokens signed by this manager, so that other
+    /// services can verify the tokens without sharing a secret.
+    ///
+    /// Managers that sign with a shared secret have no public keys, which is the default.
+    fn public_keys(&self) -> Result<Vec<Jwk>, SecretManagerError> {
+        Ok(vec![])
+    }
 }
@@ -19,6 +19,10 @@ use std::time::{Duration, SystemTime, SystemTimeError, UNIX_EPOCH};
 
 use super::ClaimsBuildError;
 
+/// The custom claim that holds the ID of the session a token was issued for
+#[cfg(feature = "biome-sessions")]
+pub const SESSION_ID_CLAIM: &str = "sid";
+
 /// Defines payload of a JWT Token
 #[derive(Serialize, Deserialize)]
 pub struct Claims {
@@ -50,6 +54,12 @@ impl Claims {
     pub fn custom_claims(&self) -> HashMap<String, String> {
         self.custom_claims.clone()
     }
+
+    /// Returns the ID of the session the token was issued for, if it has one
+    #[cfg(feature = "biome-sessions")]
+    pub fn session_id(&self) -> Option<String> {
+        self.custom_claims.get(SESSION_ID_CLAIM).cloned()
+    }
 }
 /// Builder for a claim
 #[derive(Default)]
@@ -87,6 +97,12 @@ impl ClaimsBuilder {
         self
     }
 
+    /// Session ID to be included in the claims
+    #[cfg(feature = "biome-sessions")]
+    pub fn with_session_id(self, session_id: &str) -> Self {
+        self.with_custom_claim(SESSION_ID_CLAIM, session_id)
+    }
+
     /// Consumes the builder and returns Claims. It calculates the expiration token by adding
     /// the duration set in the builder to the current system time. The `exp` field in the claims
     /// is set the resulting value.
@@ -0,0 +1,110 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An in-memory list of revoked sessions whose access tokens must no longer be accepted
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use super::Claims;
+
+/// Tracks sessions that were revoked before their access tokens expired.
+///
+/// Access tokens are not stored, so they cannot be deleted when a user logs out or a session is
+/// revoked. Instead, the session ID carried by the token is denied until every access token
+/// issued for the session has expired. Entries are discarded once they are no longer needed.
+///
+/// The denylist is not persisted or shared: it is empty after a restart, and each instance that
+/// creates one only knows about the sessions revoked through it. Revoked sessions' access tokens
+/// are accepted by other instances, or after a restart, until they expire, so access tokens
+/// should be kept short-lived.
+#[derive(Clone, Default)]
+pub struct AccessTokenDenylist {
+    inner: Arc<Mutex<HashMap<String, SystemTime>>>,
+}
+
+impl AccessTokenDenylist {
+    /// Creates a new, empty denylist
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Denies access tokens for the given session until `until`, which should be no earlier
+    /// than the expiration of the last access token issued for the session
+    pub fn deny_session(&self, session_id: &str, until: SystemTime) {
+        match self.inner.lock() {
+            Ok(mut inner) => {
+                let now = SystemTime::now();
+                inner.retain(|_, expires| *expires > now);
+                inner.insert(session_id.to_string(), until);
+            }
+            Err(_) => error!(
+                "Failed to deny session {}: denylist lock poisoned",
+                session_id
+            ),
+        }
+    }
+
+    /// Returns true if the claims belong to a session that has been revoked. If the denylist
+    /// cannot be read, every token is treated as denied.
+    pub fn is_denied(&self, claims: &Claims) -> bool {
+        let session_id = match claims.session_id() {
+            Some(session_id) => session_id,
+            None => return false,
+        };
+        match self.inner.lock() {
+            Ok(inner) => match inner.get(&session_id) {
+                Some(expires) => *expires > SystemTime::now(),
+                None => false,
+            },
+            Err(_) => {
+                error!("Failed to check denied sessions: denylist lock poisoned");
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::rest_api::sessions::ClaimsBuilder;
+
+    /// Verifies that only tokens for a denied session are rejected, and only until the denial
+    /// expires.
+    #[test]
+    fn test_deny_session() {
+        let denylist = AccessTokenDenylist::new();
+        let claims = |session_id: &str| {
+            ClaimsBuilder::default()
+                .with_user_id("user")
+                .with_issuer("self-issued")
+                .with_duration(Duration::from_secs(60))
+                .with_session_id(session_id)
+                .build()
+                .expect("Failed to build claims")
+        };
+
+        denylist.deny_session("revoked", SystemTime::now() + Duration::from_secs(60));
+        denylist.deny_session("expired", SystemTime::now() - Duration::from_secs(1));
+
+        assert!(denylist.is_denied(&claims("revoked")));
+        assert!(!denylist.is_denied(&claims("active")));
+        assert!(!denylist.is_denied(&claims("expired")));
+    }
+}
@@ -87,6 +87,8 @@ pub enum TokenValidationError {
     ValidationError(Box<dyn Error>),
     /// Returned when the claims in the token are invalid
     InvalidClaim(String),
+    /// Returned when the key that verifies the token could not be fetched
+    SecretError(Box<dyn Error>),
 }
 
 impl Error for TokenValidationError {
@@ -94,6 +96,7 @@ impl Error for TokenValidationError {
         match self {
             TokenValidationError::ValidationError(err) => Some(&**err),
             TokenValidationError::InvalidClaim(_) => None,
+            TokenValidationError::SecretError(err) => Some(&**err),
         }
     }
 }
@@ -105,10 +108,19 @@ impl fmt::Display for TokenValidationError {
                 write!(f, "failed to validate claim: {}", s)
             }
             TokenValidationError::InvalidClaim(ref s) => write!(f, "claim is invalid: {}", s),
+            TokenValidationError::SecretError(ref s) => {
+                write!(f, "failed to fetch verification key: {}", s)
+            }
         }
     }
 }
 
+impl From<SecretManagerError> for TokenValidationError {
+    fn from(err: SecretManagerError) -> TokenValidationError {
+        TokenValidationError::SecretError(Box::new(err))
+    }
+}
+
 impl From<JWTError> for TokenValidationError {
     fn from(err: JWTError) -> TokenValidationError {
         match err.kind() {
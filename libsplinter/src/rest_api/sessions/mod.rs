@@ -15,14 +15,37 @@
 //! Provides an API for managing user sessions, including issuing and validating JWT tokens
 
 mod claims;
+#[cfg(feature = "biome-sessions")]
+mod denylist;
 mod error;
 mod token_issuer;
 
-#[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
-use jsonwebtoken::Validation;
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "rest-api-auth",
+))]
+use jsonwebtoken::{decode, decode_header, Validation};
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "rest-api-auth",
+))]
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "rest-api-auth",
+))]
+use crate::rest_api::secrets::SecretManager;
+
+#[cfg(feature = "biome-sessions")]
+pub use claims::SESSION_ID_CLAIM;
 pub use claims::{Claims, ClaimsBuilder};
+#[cfg(feature = "biome-sessions")]
+pub use denylist::AccessTokenDenylist;
 pub use error::{ClaimsBuildError, TokenIssuerError, TokenValidationError};
 pub use token_issuer::AccessTokenIssuer;
 
@@ -38,6 +61,33 @@ pub trait TokenIssuer<T: Serialize> {
     fn issue_refresh_token_with_claims(&self, claims: T) -> Result<String, TokenIssuerError>;
 }
 
+/// Decodes a token and validates its claims, verifying its signature with the key the secret
+/// manager has for the ID in the token's header
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "rest-api-auth",
+))]
+pub(crate) fn decode_token<T: DeserializeOwned>(
+    token: &str,
+    secret_manager: &dyn SecretManager,
+    validation: &Validation,
+) -> Result<T, TokenValidationError> {
+    let header = decode_header(token)?;
+    let key = secret_manager
+        .verification_key(header.kid.as_deref())?
+        .ok_or_else(|| {
+            TokenValidationError::InvalidClaim("Token was signed with an unknown key".to_string())
+        })?;
+
+    // Only accept the algorithm of the key, so that a token cannot claim a different algorithm
+    // to have its signature checked with the wrong kind of key
+    let mut validation = validation.clone();
+    validation.algorithms = vec![key.algorithm];
+
+    Ok(decode::<T>(token, &key.key, &validation)?.claims)
+}
+
 #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
 pub(crate) fn default_validation(issuer: &str) -> Validation {
     let mut validation = Validation::default();
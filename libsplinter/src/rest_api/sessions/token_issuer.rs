@@ -44,21 +44,23 @@ impl AccessTokenIssuer {
 
 impl TokenIssuer<Claims> for AccessTokenIssuer {
     fn issue_token_with_claims(&self, claims: Claims) -> Result<String, TokenIssuerError> {
-        let token = encode(
-            &Header::default(),
-            &claims,
-            self.secret_manager.secret()?.as_ref(),
-        )?;
-        Ok(token)
+        sign_token(&*self.secret_manager, &claims)
     }
 
     #[cfg(feature = "biome-credentials")]
     fn issue_refresh_token_with_claims(&self, claims: Claims) -> Result<String, TokenIssuerError> {
-        let token = encode(
-            &Header::default(),
-            &claims,
-            self.refresh_secret_manager.secret()?.as_ref(),
-        )?;
-        Ok(token)
+        sign_token(&*self.refresh_secret_manager, &claims)
     }
 }
+
+/// Signs the claims with the secret manager's signing key, setting the key's algorithm and ID in
+/// the token's header
+fn sign_token(
+    secret_manager: &dyn SecretManager,
+    claims: &Claims,
+) -> Result<String, TokenIssuerError> {
+    let signing_key = secret_manager.signing_key()?;
+    let mut header = Header::new(signing_key.algorithm);
+    header.kid = signing_key.key_id;
+    Ok(encode(&header, claims, &signing_key.key)?)
+}
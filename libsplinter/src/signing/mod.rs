@@ -17,8 +17,6 @@ pub mod error;
 pub mod hash;
 #[cfg(feature = "sawtooth-signing-compat")]
 pub mod sawtooth;
-#[cfg(feature = "ursa-compat")]
-pub mod ursa;
 
 pub use crate::signing::error::Error;
 
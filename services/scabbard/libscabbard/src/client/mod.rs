@@ -16,12 +16,21 @@
 
 mod error;
 
+#[cfg(feature = "events")]
+use std::collections::HashSet;
+#[cfg(feature = "events")]
+use std::sync::{
+    mpsc::{channel, Receiver},
+    Arc, Mutex,
+};
 use std::time::{Duration, Instant, SystemTime};
 
 use reqwest::{
     blocking::{Client, RequestBuilder, Response},
     Url,
 };
+#[cfg(feature = "events")]
+use splinter::events::{ParseBytes, ParseError, Reactor, WebSocketClient, WsResponse};
 use transact::{protocol::batch::Batch, protos::IntoBytes};
 
 use super::hex::parse_hex;
@@ -85,6 +94,81 @@ impl ScabbardClient {
         }
     }
 
+    /// Subscribe to the status transitions of the batches with the given `batch_ids`, or of all
+    /// batches if `batch_ids` is `None`, for the scabbard service with the given `service_id`.
+    ///
+    /// The returned `BatchStatusStream` blocks until the next status is received. If `batch_ids`
+    /// are given, the current status of each batch is received first and the stream ends once
+    /// all of the batches have been committed, found to be invalid or reported as unknown by the
+    /// service; in either case, the stream ends when the connection is closed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The websocket connection could not be started
+    #[cfg(feature = "events")]
+    pub fn subscribe_to_batch_statuses(
+        &self,
+        service_id: &ServiceId,
+        batch_ids: Option<&[String]>,
+    ) -> Result<BatchStatusStream, ScabbardClientError> {
+        let mut url = parse_http_url(&format!(
+            "{}/scabbard/{}/{}/ws/batch_statuses",
+            self.url,
+            service_id.circuit(),
+            service_id.service_id()
+        ))?;
+        if let Some(ids) = batch_ids {
+            url.set_query(Some(&format!("ids={}", ids.join(","))));
+        }
+
+        let (sender, receiver) = channel();
+        // The websocket client requires `Sync` handlers; the sender is shared with the error
+        // handler so it can be dropped when the connection is closed, which ends the stream
+        let sender = Arc::new(Mutex::new(Some(sender)));
+        let message_sender = sender.clone();
+        let mut ws = WebSocketClient::new(url.as_str(), move |_, info: BatchInfo| {
+            let sent = match message_sender.lock() {
+                Ok(sender) => match &*sender {
+                    Some(sender) => sender.send(info).is_ok(),
+                    None => false,
+                },
+                Err(_) => false,
+            };
+            if !sent {
+                WsResponse::Close
+            } else {
+                WsResponse::Empty
+            }
+        });
+        // Called when the connection is closed or could not be established, since the client
+        // does not reconnect
+        ws.on_error(move |err, _| {
+            debug!("Batch status websocket closed: {}", err);
+            if let Ok(mut sender) = sender.lock() {
+                sender.take();
+            }
+            Ok(())
+        });
+        ws.header(
+            "SplinterProtocolVersion",
+            SCABBARD_PROTOCOL_VERSION.to_string(),
+        );
+
+        debug!("Subscribing to batch statuses via {}", url);
+        let reactor = Reactor::new();
+        reactor.igniter().start_ws(&ws).map_err(|err| {
+            ScabbardClientError::new_with_source("failed to start websocket", err.into())
+        })?;
+
+        Ok(BatchStatusStream {
+            receiver,
+            pending_ids: batch_ids.map(|ids| ids.iter().cloned().collect()),
+            reactor: Some(reactor),
+        })
+    }
+
     /// Get the value at the given `address` in state for the scabbard instance with the given
     /// `service_id`. Returns `None` if there is no entry at the given address.
     ///
@@ -246,6 +330,58 @@ impl ScabbardClient {
     }
 }
 
+/// A blocking stream of batch status transitions, returned by
+/// `ScabbardClient::subscribe_to_batch_statuses`. The underlying websocket is closed when the
+/// stream is dropped.
+#[cfg(feature = "events")]
+pub struct BatchStatusStream {
+    receiver: Receiver<BatchInfo>,
+    /// The batches that have not been committed or found to be invalid yet, if the subscription
+    /// is for specific batches
+    pending_ids: Option<HashSet<String>>,
+    reactor: Option<Reactor>,
+}
+
+#[cfg(feature = "events")]
+impl Iterator for BatchStatusStream {
+    type Item = BatchInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(ids) = &self.pending_ids {
+            if ids.is_empty() {
+                return None;
+            }
+        }
+
+        let info = self.receiver.recv().ok()?;
+        if let Some(ids) = &mut self.pending_ids {
+            match info.status {
+                BatchStatus::Invalid(_) | BatchStatus::Committed(_) | BatchStatus::Unknown => {
+                    ids.remove(&info.id);
+                }
+                _ => {}
+            }
+        }
+
+        Some(info)
+    }
+}
+
+#[cfg(feature = "events")]
+impl Drop for BatchStatusStream {
+    fn drop(&mut self) {
+        if let Some(reactor) = self.reactor.take() {
+            if let Err(err) = reactor
+                .shutdown_signaler()
+                .signal_shutdown()
+                .and_then(|_| reactor.wait_for_shutdown())
+            {
+                error!("Failed to shutdown batch status websocket: {}", err);
+            }
+        }
+    }
+}
+
 /// Using the given `base_url` and `batch_link` to check batch statuses, `wait` the given duration
 /// for the batches (encoded in `batch_link`) to commit.
 ///
@@ -448,18 +584,28 @@ impl std::fmt::Display for Link {
     }
 }
 
-/// Used for deserializing `GET /batch_status` responses.
-#[derive(Debug, Serialize, Deserialize)]
-struct BatchInfo {
+/// The status of a batch, as returned by `GET /batch_statuses` and streamed by
+/// `ScabbardClient::subscribe_to_batch_statuses`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchInfo {
     pub id: String,
     pub status: BatchStatus,
     pub timestamp: SystemTime,
 }
 
-/// Used by `BatchInfo` for deserializing `GET /batch_status` responses.
-#[derive(Debug, Serialize, Deserialize)]
+#[cfg(feature = "events")]
+impl ParseBytes<BatchInfo> for BatchInfo {
+    fn from_bytes(bytes: &[u8]) -> Result<BatchInfo, ParseError> {
+        serde_json::from_slice(bytes)
+            .map_err(Box::new)
+            .map_err(|err| ParseError::MalformedMessage(err))
+    }
+}
+
+/// The status of a batch; used by `BatchInfo`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "statusType", content = "message")]
-enum BatchStatus {
+pub enum BatchStatus {
     Unknown,
     Pending,
     Invalid(Vec<InvalidTransaction>),
@@ -467,13 +613,13 @@ enum BatchStatus {
     Committed(Vec<ValidTransaction>),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ValidTransaction {
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValidTransaction {
     pub transaction_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct InvalidTransaction {
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InvalidTransaction {
     pub transaction_id: String,
     pub error_message: String,
     pub error_data: Vec<u8>,
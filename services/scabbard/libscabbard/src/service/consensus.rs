@@ -12,18 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryInto;
 use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
-use std::thread::{Builder, JoinHandle};
 use std::time::Duration;
 
 use protobuf::Message;
+#[cfg(feature = "consensus-quorum")]
+use splinter::consensus::quorum::QuorumEngine;
 use splinter::consensus::{
-    error::{ConsensusSendError, ProposalManagerError},
-    two_phase::TwoPhaseEngine,
-    ConsensusEngine, ConsensusMessage, ConsensusNetworkSender, PeerId, Proposal, ProposalId,
-    ProposalManager, ProposalUpdate, StartupState,
+    error::ProposalManagerError, two_phase::TwoPhaseEngine, ConsensusEngine, ConsensusRunner,
+    Proposal, ProposalId, ProposalManager, ProposalUpdate, ServiceConsensusNetworkSender,
+    ServicePeers,
 };
 use transact::protos::IntoBytes;
 
@@ -33,11 +33,39 @@ use super::error::{ScabbardConsensusManagerError, ScabbardError};
 use super::shared::ScabbardShared;
 use super::state::ScabbardState;
 
+/// The consensus algorithms that a scabbard service can run
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConsensusAlgorithm {
+    /// Two-phase commit; the service with the lowest ID coordinates every proposal and all
+    /// services must verify a proposal before it is committed.
+    TwoPhase,
+    /// Quorum commit with a rotating coordinator; a proposal is committed once `quorum` services
+    /// (a simple majority if `None`) have verified it.
+    #[cfg(feature = "consensus-quorum")]
+    Quorum { quorum: Option<usize> },
+}
+
+impl Default for ConsensusAlgorithm {
+    fn default() -> Self {
+        ConsensusAlgorithm::TwoPhase
+    }
+}
+
+impl ConsensusAlgorithm {
+    fn create_engine(&self, coordinator_timeout: Duration) -> Box<dyn ConsensusEngine> {
+        match self {
+            ConsensusAlgorithm::TwoPhase => Box::new(TwoPhaseEngine::new(coordinator_timeout)),
+            #[cfg(feature = "consensus-quorum")]
+            ConsensusAlgorithm::Quorum { quorum } => {
+                Box::new(QuorumEngine::new(coordinator_timeout, *quorum))
+            }
+        }
+    }
+}
+
 /// Component used by the service to manage and interact with consenus
 pub struct ScabbardConsensusManager {
-    consensus_msg_tx: Sender<ConsensusMessage>,
-    proposal_update_tx: Sender<ProposalUpdate>,
-    thread_handle: JoinHandle<()>,
+    runner: ConsensusRunner,
 }
 
 impl ScabbardConsensusManager {
@@ -47,7 +75,9 @@ impl ScabbardConsensusManager {
         service_id: String,
         shared: Arc<Mutex<ScabbardShared>>,
         state: Arc<Mutex<ScabbardState>>,
-        // The coordinator timeout for the two-phase commit consensus engine
+        // The consensus algorithm to run
+        consensus_algorithm: &ConsensusAlgorithm,
+        // The coordinator timeout for the consensus engine
         coordinator_timeout: Duration,
     ) -> Result<Self, ScabbardConsensusManagerError> {
         let peer_ids = shared
@@ -58,7 +88,6 @@ impl ScabbardConsensusManager {
             .map(|id| id.as_bytes().into())
             .collect();
 
-        let (consensus_msg_tx, consensus_msg_rx) = channel();
         let (proposal_update_tx, proposal_update_rx) = channel();
 
         let proposal_manager = ScabbardProposalManager::new(
@@ -67,65 +96,40 @@ impl ScabbardConsensusManager {
             shared.clone(),
             state,
         );
-        let consensus_network_sender =
-            ScabbardConsensusNetworkSender::new(service_id.clone(), shared);
-        let startup_state = StartupState {
-            id: service_id.as_bytes().into(),
+        let consensus_network_sender = consensus_network_sender(service_id.clone(), shared);
+
+        let runner = ConsensusRunner::start(
+            &service_id,
             peer_ids,
-            last_proposal: None,
-        };
-
-        let thread_handle = Builder::new()
-            .name(format!("consensus-{}", service_id))
-            .spawn(move || {
-                let mut two_phase_engine = TwoPhaseEngine::new(coordinator_timeout);
-                if let Err(err) = two_phase_engine.run(
-                    consensus_msg_rx,
-                    proposal_update_rx,
-                    Box::new(consensus_network_sender),
-                    Box::new(proposal_manager),
-                    startup_state,
-                ) {
-                    error!("two phase consensus exited with an error: {}", err)
-                }
-            })
-            .map_err(|err| ScabbardConsensusManagerError(Box::new(err)))?;
-
-        Ok(ScabbardConsensusManager {
-            consensus_msg_tx,
-            proposal_update_tx,
-            thread_handle,
-        })
+            consensus_algorithm.create_engine(coordinator_timeout),
+            Box::new(consensus_network_sender),
+            Box::new(proposal_manager),
+            (proposal_update_tx, proposal_update_rx),
+        )
+        .map_err(|err| ScabbardConsensusManagerError(Box::new(err)))?;
+
+        Ok(ScabbardConsensusManager { runner })
     }
 
     /// Consumes self and shuts down the consensus thread.
     pub fn shutdown(self) -> Result<(), ScabbardConsensusManagerError> {
-        self.send_update(ProposalUpdate::Shutdown)?;
-
-        self.thread_handle
-            .join()
-            .unwrap_or_else(|err| error!("consensus thread failed: {:?}", err));
-
-        Ok(())
+        self.runner
+            .shutdown()
+            .map_err(|err| ScabbardConsensusManagerError(Box::new(err)))
     }
 
     pub fn handle_message(
         &self,
         message_bytes: &[u8],
     ) -> Result<(), ScabbardConsensusManagerError> {
-        let consensus_message = ConsensusMessage::try_from(message_bytes)
-            .map_err(|err| ScabbardConsensusManagerError(Box::new(err)))?;
-
-        self.consensus_msg_tx
-            .send(consensus_message)
-            .map_err(|err| ScabbardConsensusManagerError(Box::new(err)))?;
-
-        Ok(())
+        self.runner
+            .handle_message(message_bytes)
+            .map_err(|err| ScabbardConsensusManagerError(Box::new(err)))
     }
 
     pub fn send_update(&self, update: ProposalUpdate) -> Result<(), ScabbardConsensusManagerError> {
-        self.proposal_update_tx
-            .send(update)
+        self.runner
+            .send_update(update)
             .map_err(|err| ScabbardConsensusManagerError(Box::new(err)))
     }
 }
@@ -135,6 +139,8 @@ pub struct ScabbardProposalManager {
     proposal_update_sender: Sender<ProposalUpdate>,
     shared: Arc<Mutex<ScabbardShared>>,
     state: Arc<Mutex<ScabbardState>>,
+    /// The proposal whose changes are currently prepared in state, if any
+    prepared_proposal: Mutex<Option<ProposalId>>,
 }
 
 impl ScabbardProposalManager {
@@ -149,7 +155,37 @@ impl ScabbardProposalManager {
             proposal_update_sender,
             shared,
             state,
+            prepared_proposal: Mutex::new(None),
+        }
+    }
+
+    fn set_prepared_proposal(&self, id: Option<ProposalId>) -> Result<(), ProposalManagerError> {
+        *self
+            .prepared_proposal
+            .lock()
+            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))? =
+            id;
+        Ok(())
+    }
+
+    /// Discard the prepared changes if they belong to the given proposal; the changes of any
+    /// other proposal are left in place.
+    fn rollback_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
+        let mut prepared_proposal = self
+            .prepared_proposal
+            .lock()
+            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
+
+        if prepared_proposal.as_ref() == Some(id) {
+            self.state
+                .lock()
+                .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
+                .rollback()
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+            *prepared_proposal = None;
         }
+
+        Ok(())
     }
 }
 
@@ -166,82 +202,104 @@ impl ProposalManager for ScabbardProposalManager {
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
 
-        if let Some(batch) = shared.pop_batch_from_queue() {
-            let expected_hash = self
-                .state
-                .lock()
-                .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
-                .prepare_change(batch.clone())
-                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
-
-            // Intentionally leaving out the previous_id and proposal_height fields, since this
-            // service and two phase consensus don't use them. This means the proposal ID can just
-            // be the summary.
-            let mut proposal = Proposal::default();
-            proposal.id = expected_hash.as_bytes().into();
-            proposal.summary = expected_hash.as_bytes().into();
-
-            shared.add_proposed_batch(proposal.id.clone(), batch.clone());
-
-            // Send the proposal to the other services
-            let mut proposed_batch = ProposedBatch::new();
-            proposed_batch.set_proposal(
-                proposal
-                    .clone()
-                    .try_into()
-                    .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?,
-            );
-            proposed_batch.set_batch(
-                batch
-                    .into_bytes()
-                    .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?,
-            );
-            proposed_batch.set_service_id(self.service_id.clone());
-
-            let mut msg = ScabbardMessage::new();
-            msg.set_message_type(ScabbardMessage_Type::PROPOSED_BATCH);
-            msg.set_proposed_batch(proposed_batch);
-            let msg_bytes = msg
-                .write_to_bytes()
-                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
-
-            let sender = shared
-                .network_sender()
-                .ok_or(ProposalManagerError::NotReady)?;
+        let batches = shared.take_batches_for_proposal();
+        if batches.is_empty() {
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalCreated(None))?;
+            return Ok(());
+        }
 
-            for service in shared.peer_services() {
-                sender
-                    .send(service, msg_bytes.as_slice())
-                    .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
-            }
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
+        let previous_state_root = state.current_state_root().to_string();
+        let (expected_hash, batches) = state
+            .prepare_change(batches)
+            .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+        drop(state);
 
-            self.proposal_update_sender
-                .send(ProposalUpdate::ProposalCreated(Some(proposal)))?;
-        } else {
+        // Invalid batches are excluded from the proposal; if none of the batches were valid,
+        // there is nothing to propose.
+        if batches.is_empty() {
             self.proposal_update_sender
                 .send(ProposalUpdate::ProposalCreated(None))?;
+            return Ok(());
         }
 
+        // The previous_id is the state root the proposal was prepared against, so quorum
+        // consensus can reject proposals that conflict with an accepted one. Intentionally leaving
+        // out the proposal_height field, since this service doesn't use it. This means the
+        // proposal ID can just be the summary.
+        let mut proposal = Proposal::default();
+        proposal.previous_id = previous_state_root.as_bytes().into();
+        proposal.id = expected_hash.as_bytes().into();
+        proposal.summary = expected_hash.as_bytes().into();
+
+        shared.add_own_proposed_batches(proposal.id.clone(), batches.clone());
+        self.set_prepared_proposal(Some(proposal.id.clone()))?;
+
+        // Send the proposal to the other services
+        let mut proposed_batch = ProposedBatch::new();
+        proposed_batch.set_proposal(
+            proposal
+                .clone()
+                .try_into()
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?,
+        );
+        proposed_batch.set_batches(
+            batches
+                .into_iter()
+                .map(|batch| batch.into_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?
+                .into(),
+        );
+        proposed_batch.set_service_id(self.service_id.clone());
+
+        let mut msg = ScabbardMessage::new();
+        msg.set_message_type(ScabbardMessage_Type::PROPOSED_BATCH);
+        msg.set_proposed_batch(proposed_batch);
+        let msg_bytes = msg
+            .write_to_bytes()
+            .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+
+        shared.send_to_peers(&msg_bytes)?;
+
+        self.proposal_update_sender
+            .send(ProposalUpdate::ProposalCreated(Some(proposal)))?;
+
         Ok(())
     }
 
     fn check_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
-        let batch = self
+        let batches = self
             .shared
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
-            .get_proposed_batch(id)
+            .get_proposed_batches(id)
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?
             .clone();
+        let batch_count = batches.len();
 
-        let hash = self
+        let (hash, valid_batches) = self
             .state
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
-            .prepare_change(batch)
+            .prepare_change(batches)
             .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+        self.set_prepared_proposal(Some(id.clone()))?;
 
-        if hash.as_bytes() != id.as_ref() {
+        if valid_batches.len() != batch_count {
+            warn!(
+                "Proposal {} contains {} invalid batch(es)",
+                id,
+                batch_count - valid_batches.len()
+            );
+
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalInvalid(id.clone()))?;
+        } else if hash.as_bytes() != id.as_ref() {
             warn!("Hash mismatch: expected {} but was {}", id, hash);
 
             self.proposal_update_sender
@@ -267,7 +325,7 @@ impl ProposalManager for ScabbardProposalManager {
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
 
         shared
-            .remove_proposed_batch(id)
+            .remove_proposed_batches(id)
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?;
 
         self.state
@@ -275,6 +333,7 @@ impl ProposalManager for ScabbardProposalManager {
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
             .commit()
             .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+        self.set_prepared_proposal(None)?;
 
         self.proposal_update_sender
             .send(ProposalUpdate::ProposalAccepted(id.clone()))?;
@@ -290,86 +349,48 @@ impl ProposalManager for ScabbardProposalManager {
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
 
-        shared
-            .remove_proposed_batch(id)
+        let own_proposal = shared.is_own_proposal(id);
+        let batches = shared
+            .remove_proposed_batches(id)
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?;
 
-        self.state
-            .lock()
-            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
-            .rollback()
-            .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+        // The batches of a rejected proposal that this service created are proposed again
+        if own_proposal {
+            info!(
+                "Returning {} batch(es) from rejected proposal {} to the queue",
+                batches.len(),
+                id
+            );
+            shared.requeue_batches(batches);
+        }
+
+        self.rollback_proposal(id)?;
 
         info!("Rolled back proposal {}", id);
 
         Ok(())
     }
-}
 
-pub struct ScabbardConsensusNetworkSender {
-    service_id: String,
-    shared: Arc<Mutex<ScabbardShared>>,
-}
+    fn abandon_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
+        self.rollback_proposal(id)?;
 
-impl ScabbardConsensusNetworkSender {
-    pub fn new(service_id: String, shared: Arc<Mutex<ScabbardShared>>) -> Self {
-        ScabbardConsensusNetworkSender { service_id, shared }
-    }
-}
-
-impl ConsensusNetworkSender for ScabbardConsensusNetworkSender {
-    fn send_to(&self, peer_id: &PeerId, message: Vec<u8>) -> Result<(), ConsensusSendError> {
-        let peer_id_string = String::from_utf8(peer_id.clone().into())
-            .map_err(|err| ConsensusSendError::Internal(Box::new(err)))?;
-
-        let consensus_message = ConsensusMessage::new(message, self.service_id.as_bytes().into());
-        let mut msg = ScabbardMessage::new();
-        msg.set_message_type(ScabbardMessage_Type::CONSENSUS_MESSAGE);
-        msg.set_consensus_message(consensus_message.try_into()?);
-
-        let shared = self
-            .shared
-            .lock()
-            .map_err(|_| ConsensusSendError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
-
-        if !shared.peer_services().contains(&peer_id_string) {
-            return Err(ConsensusSendError::UnknownPeer(peer_id.clone()));
-        }
-
-        let network_sender = shared
-            .network_sender()
-            .ok_or(ConsensusSendError::NotReady)?;
-
-        network_sender
-            .send(&peer_id_string, msg.write_to_bytes()?.as_slice())
-            .map_err(|err| ConsensusSendError::Internal(Box::new(err)))?;
+        debug!("Abandoned proposal {}", id);
 
         Ok(())
     }
+}
 
-    fn broadcast(&self, message: Vec<u8>) -> Result<(), ConsensusSendError> {
-        let consensus_message = ConsensusMessage::new(message, self.service_id.as_bytes().into());
+/// Creates the network sender that delivers the service's consensus messages to its peers.
+fn consensus_network_sender(
+    service_id: String,
+    shared: Arc<Mutex<ScabbardShared>>,
+) -> ServiceConsensusNetworkSender<ScabbardShared> {
+    ServiceConsensusNetworkSender::new(service_id, shared, |consensus_message| {
         let mut msg = ScabbardMessage::new();
         msg.set_message_type(ScabbardMessage_Type::CONSENSUS_MESSAGE);
-        msg.set_consensus_message(consensus_message.try_into()?);
-
-        let shared = self
-            .shared
-            .lock()
-            .map_err(|_| ConsensusSendError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
-
-        let network_sender = shared
-            .network_sender()
-            .ok_or(ConsensusSendError::NotReady)?;
-
-        for service in shared.peer_services() {
-            network_sender
-                .send(service, msg.write_to_bytes()?.as_slice())
-                .map_err(|err| ConsensusSendError::Internal(Box::new(err)))?;
-        }
-
-        Ok(())
-    }
+        msg.set_consensus_message(consensus_message);
+        msg.write_to_bytes()
+    })
 }
 
 #[cfg(test)]
@@ -377,8 +398,10 @@ mod tests {
     use super::*;
 
     use std::collections::{HashSet, VecDeque};
+    use std::convert::TryFrom;
 
     use splinter::{
+        consensus::{ConsensusMessage, ConsensusNetworkSender},
         service::{ServiceMessageContext, ServiceNetworkSender, ServiceSendError},
         signing::hash::HashVerifier,
     };
@@ -394,11 +417,12 @@ mod tests {
 
         let shared = Arc::new(Mutex::new(ScabbardShared::new(
             VecDeque::new(),
+            Default::default(),
             Some(Box::new(service_sender.clone())),
             peer_services.clone(),
             Box::new(HashVerifier),
         )));
-        let consensus_sender = ScabbardConsensusNetworkSender::new("0".into(), shared);
+        let consensus_sender = consensus_network_sender("0".into(), shared);
 
         // Test send_to
         consensus_sender
@@ -27,7 +27,8 @@ use splinter::{
 #[cfg(feature = "service-arg-validation")]
 use crate::hex::parse_hex;
 
-use super::{Scabbard, SERVICE_TYPE};
+use super::handlers::TransactionHandlerRegistry;
+use super::{ConsensusAlgorithm, ProposalBatchLimits, Scabbard, SERVICE_TYPE};
 
 const DEFAULT_STATE_DB_DIR: &str = "/var/lib/splinter";
 const DEFAULT_STATE_DB_SIZE: usize = 1 << 30; // 1024 ** 3
@@ -41,6 +42,7 @@ pub struct ScabbardFactory {
     receipt_db_dir: String,
     receipt_db_size: usize,
     signature_verifier_factory: Box<dyn SignatureVerifierFactory>,
+    transaction_handlers: TransactionHandlerRegistry,
 }
 
 impl ScabbardFactory {
@@ -58,8 +60,17 @@ impl ScabbardFactory {
             receipt_db_dir: receipt_db_dir.unwrap_or_else(|| DEFAULT_RECEIPT_DB_DIR.into()),
             receipt_db_size: receipt_db_size.unwrap_or(DEFAULT_RECEIPT_DB_SIZE),
             signature_verifier_factory,
+            transaction_handlers: TransactionHandlerRegistry::new(),
         }
     }
+
+    /// Set the native transaction handlers that are available to the services created by this
+    /// factory. Each service only runs the handlers for the families listed in its
+    /// `transaction_families` argument.
+    pub fn with_transaction_handlers(mut self, registry: TransactionHandlerRegistry) -> Self {
+        self.transaction_handlers = registry;
+        self
+    }
 }
 
 #[cfg(feature = "service-arg-validation")]
@@ -72,9 +83,12 @@ impl ServiceArgValidator for ScabbardArgValidator {
             ServiceArgValidationError("peer_services argument not provided".into())
         })?;
 
-        serde_json::from_str::<Vec<String>>(peer_services_str).map_err(|err| {
-            ServiceArgValidationError(format!("failed to parse peer_services list: {}", err,))
-        })?;
+        let peer_services = serde_json::from_str::<Vec<String>>(peer_services_str)
+            .map_err(|err| {
+                ServiceArgValidationError(format!("failed to parse peer_services list: {}", err,))
+            })?
+            .into_iter()
+            .collect::<HashSet<_>>();
 
         let admin_keys_str = args
             .get("admin_keys")
@@ -83,6 +97,19 @@ impl ServiceArgValidator for ScabbardArgValidator {
             ServiceArgValidationError(format!("failed to parse admin_keys list: {}", err,))
         })?;
 
+        if let Some(transaction_families_str) = args.get("transaction_families") {
+            serde_json::from_str::<Vec<String>>(transaction_families_str).map_err(|err| {
+                ServiceArgValidationError(format!(
+                    "failed to parse transaction_families list: {}",
+                    err,
+                ))
+            })?;
+        }
+
+        parse_consensus_algorithm(args, peer_services.len() + 1)
+            .map_err(ServiceArgValidationError)?;
+        parse_proposal_batch_limits(args).map_err(ServiceArgValidationError)?;
+
         for key in admin_keys {
             let key_bytes = parse_hex(&key).map_err(|_| {
                 ServiceArgValidationError(format!(
@@ -118,6 +145,22 @@ impl ServiceFactory for ScabbardFactory {
     /// - `coordinator_timeout`: the length of time (in milliseconds) that the network has to
     ///   commit a proposal before the coordinator rejects it (if not provided, default is 30
     ///   seconds)
+    /// - `consensus`: the consensus algorithm to run; either `two-phase` or, if the
+    ///   `consensus-quorum` feature is enabled, `quorum` (if not provided, default is
+    ///   `two-phase`)
+    /// - `consensus_quorum`: the number of services that must verify a proposal when running
+    ///   `quorum` consensus; must be more than half of the services (if not provided, default is
+    ///   a simple majority of the services)
+    /// - `transaction_families`: list of native transaction families to run in addition to
+    ///   Sabre, formatted as a serialized JSON array of strings; each family must have a handler
+    ///   registered with this factory (if not provided, only Sabre is run)
+    /// - `max_proposal_batches`: the maximum number of queued batches to include in a single
+    ///   proposal (if not provided, default is 100)
+    /// - `max_proposal_bytes`: the maximum total size (in bytes) of the batches in a single
+    ///   proposal (if not provided, default is 10 MiB)
+    /// - `proposal_linger`: the length of time (in milliseconds) to wait for more batches to be
+    ///   queued before proposing fewer than `max_proposal_batches` batches (if not provided,
+    ///   default is 0, so queued batches are proposed immediately)
     fn create(
         &self,
         service_id: String,
@@ -161,6 +204,30 @@ impl ServiceFactory for ScabbardFactory {
             })
             .transpose()?;
 
+        // The network is made up of this service and its peers
+        let consensus_algorithm = parse_consensus_algorithm(&args, peer_services.len() + 1)
+            .map_err(FactoryCreateError::InvalidArguments)?;
+
+        let proposal_batch_limits =
+            parse_proposal_batch_limits(&args).map_err(FactoryCreateError::InvalidArguments)?;
+
+        let transaction_families = args
+            .get("transaction_families")
+            .map(|families| {
+                serde_json::from_str::<Vec<String>>(families).map_err(|err| {
+                    FactoryCreateError::InvalidArguments(format!(
+                        "failed to parse transaction_families list: {}",
+                        err,
+                    ))
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let transaction_handlers = self
+            .transaction_handlers
+            .create_handlers(&transaction_families)
+            .map_err(|err| FactoryCreateError::InvalidArguments(err.to_string()))?;
+
         let service = Scabbard::new(
             service_id,
             circuit_id,
@@ -171,7 +238,10 @@ impl ServiceFactory for ScabbardFactory {
             self.receipt_db_size,
             self.signature_verifier_factory.create_verifier(),
             admin_keys,
+            transaction_handlers,
+            Some(consensus_algorithm),
             coordinator_timeout,
+            Some(proposal_batch_limits),
         )
         .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
 
@@ -184,7 +254,9 @@ impl ServiceFactory for ScabbardFactory {
     ///
     /// * `POST /batches` - Add one or more batches to scabbard's queue
     /// * `GET /batch_statuses` - Get the status of one or more batches
-    /// * `GET /ws/subscribe` - Subscribe to scabbard state-delta events
+    /// * `GET /ws/subscribe` - Subscribe to scabbard state-delta events, optionally filtered by
+    ///   address prefix and change type
+    /// * `GET /ws/batch_statuses` - Subscribe to batch status transitions
     /// * `GET /state/{address}` - Get a value from scabbard's state
     /// * `GET /state` - Get multiple scabbard state entries
     /// * `GET /state_root` - Get the current state root hash of scabbard's state
@@ -206,6 +278,7 @@ impl ServiceFactory for ScabbardFactory {
             endpoints.append(&mut vec![
                 actix::batches::make_add_batches_to_queue_endpoint(),
                 actix::ws_subscribe::make_subscribe_endpoint(),
+                actix::ws_batch_statuses::make_subscribe_batch_statuses_endpoint(),
                 actix::batch_statuses::make_get_batch_status_endpoint(),
                 actix::state_address::make_get_state_at_address_endpoint(),
                 actix::state::make_get_state_with_prefix_endpoint(),
@@ -217,12 +290,86 @@ impl ServiceFactory for ScabbardFactory {
     }
 }
 
+/// Parse the `consensus` and `consensus_quorum` service arguments. The quorum must be a majority
+/// of the `members` of the network, so that two quorums can never be formed without a common
+/// member.
+fn parse_consensus_algorithm(
+    args: &HashMap<String, String>,
+    members: usize,
+) -> Result<ConsensusAlgorithm, String> {
+    let consensus_quorum = args
+        .get("consensus_quorum")
+        .map(|quorum| {
+            quorum
+                .parse::<usize>()
+                .map_err(|err| format!("invalid consensus_quorum: {}", err))
+        })
+        .transpose()?;
+
+    match args.get("consensus").map(String::as_str) {
+        None | Some("two-phase") => {
+            if consensus_quorum.is_some() {
+                Err("consensus_quorum is only valid for quorum consensus".into())
+            } else {
+                Ok(ConsensusAlgorithm::TwoPhase)
+            }
+        }
+        #[cfg(feature = "consensus-quorum")]
+        Some("quorum") => match consensus_quorum {
+            Some(quorum) if quorum <= members / 2 => Err(format!(
+                "consensus_quorum must be greater than half of the {} services",
+                members
+            )),
+            Some(quorum) if quorum > members => Err(format!(
+                "consensus_quorum must not be greater than the number of services ({})",
+                members
+            )),
+            quorum => Ok(ConsensusAlgorithm::Quorum { quorum }),
+        },
+        Some(other) => Err(format!("unsupported consensus algorithm: {}", other)),
+    }
+}
+
+/// Parse the `max_proposal_batches`, `max_proposal_bytes`, and `proposal_linger` service
+/// arguments.
+fn parse_proposal_batch_limits(
+    args: &HashMap<String, String>,
+) -> Result<ProposalBatchLimits, String> {
+    let mut limits = ProposalBatchLimits::default();
+
+    if let Some(max_batch_count) = args.get("max_proposal_batches") {
+        limits.max_batch_count = max_batch_count
+            .parse::<usize>()
+            .map_err(|err| format!("invalid max_proposal_batches: {}", err))?;
+        if limits.max_batch_count == 0 {
+            return Err("max_proposal_batches must be greater than 0".into());
+        }
+    }
+
+    if let Some(max_batch_bytes) = args.get("max_proposal_bytes") {
+        limits.max_batch_bytes = max_batch_bytes
+            .parse::<usize>()
+            .map_err(|err| format!("invalid max_proposal_bytes: {}", err))?;
+    }
+
+    if let Some(linger) = args.get("proposal_linger") {
+        limits.linger = linger
+            .parse::<u64>()
+            .map(Duration::from_millis)
+            .map_err(|err| format!("invalid proposal_linger: {}", err))?;
+    }
+
+    Ok(limits)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use splinter::signing::hash::HashVerifier;
 
+    use crate::service::handlers::NoopTransactionHandler;
+
     /// Verify that the scabbard factory produces a valid `Scabbard` instance.
     #[test]
     fn create_successful() {
@@ -260,6 +407,116 @@ mod tests {
         assert_eq!(scabbard.coordinator_timeout, Duration::from_millis(123));
     }
 
+    /// Verify that the `consensus` service argument is properly set for a new `Scabbard` instance
+    /// and that unknown algorithms are rejected.
+    #[test]
+    fn create_with_consensus() {
+        let factory = get_factory();
+
+        let service = factory
+            .create("".into(), "", "", get_mock_args())
+            .expect("failed to create service");
+        let scabbard = (&*service)
+            .as_any()
+            .downcast_ref::<Scabbard>()
+            .expect("failed to downcast Service to Scabbard");
+        assert_eq!(scabbard.consensus_algorithm, ConsensusAlgorithm::TwoPhase);
+
+        let mut args = get_mock_args();
+        args.insert("consensus".into(), "unknown".into());
+        assert!(
+            factory.create("".into(), "", "", args).is_err(),
+            "Creating service with unknown consensus algorithm did not fail"
+        );
+    }
+
+    /// Verify that `quorum` consensus and the `consensus_quorum` argument are properly set for a
+    /// new `Scabbard` instance.
+    #[cfg(feature = "consensus-quorum")]
+    #[test]
+    fn create_with_quorum_consensus() {
+        let factory = get_factory();
+        let mut args = get_mock_args();
+        args.insert("consensus".into(), "quorum".into());
+        args.insert("consensus_quorum".into(), "3".into());
+
+        let service = factory
+            .create("".into(), "", "", args)
+            .expect("failed to create service");
+        let scabbard = (&*service)
+            .as_any()
+            .downcast_ref::<Scabbard>()
+            .expect("failed to downcast Service to Scabbard");
+        assert_eq!(
+            scabbard.consensus_algorithm,
+            ConsensusAlgorithm::Quorum { quorum: Some(3) }
+        );
+    }
+
+    /// Verify that a `consensus_quorum` that is not a majority of the services, or that is larger
+    /// than the number of services, is rejected.
+    #[cfg(feature = "consensus-quorum")]
+    #[test]
+    fn create_with_invalid_quorum() {
+        let factory = get_factory();
+
+        for quorum in &["2", "5"] {
+            let mut args = get_mock_args();
+            args.insert("consensus".into(), "quorum".into());
+            args.insert("consensus_quorum".into(), quorum.to_string());
+
+            #[cfg(feature = "service-arg-validation")]
+            assert!(
+                ScabbardArgValidator.validate(&args).is_err(),
+                "Validating quorum of {} for 4 services did not fail",
+                quorum
+            );
+            assert!(
+                factory.create("".into(), "", "", args).is_err(),
+                "Creating service with quorum of {} for 4 services did not fail",
+                quorum
+            );
+        }
+    }
+
+    /// Verify that the proposal batch limit service arguments are properly set for a new
+    /// `Scabbard` instance and that invalid limits are rejected.
+    #[test]
+    fn create_with_proposal_batch_limits() {
+        let factory = get_factory();
+        let mut args = get_mock_args();
+        args.insert("max_proposal_batches".into(), "10".into());
+        args.insert("max_proposal_bytes".into(), "2048".into());
+        args.insert("proposal_linger".into(), "50".into());
+
+        let service = factory
+            .create("".into(), "", "", args)
+            .expect("failed to create service");
+        let scabbard = (&*service)
+            .as_any()
+            .downcast_ref::<Scabbard>()
+            .expect("failed to downcast Service to Scabbard");
+        assert_eq!(
+            scabbard
+                .shared
+                .lock()
+                .expect("shared lock poisoned")
+                .proposal_batch_limits(),
+            &ProposalBatchLimits {
+                max_batch_count: 10,
+                max_batch_bytes: 2048,
+                linger: Duration::from_millis(50),
+            }
+        );
+
+        let mut args = get_mock_args();
+        args.insert("max_proposal_batches".into(), "0".into());
+        assert!(
+            factory.create("".into(), "", "", args).is_err(),
+            "Creating service with max_proposal_batches of 0 did not fail"
+        );
+    }
+
     /// Verify that `Scabbard` creation fails when the `peer_services` argument isn't specified.
     #[test]
     fn create_without_peer_services() {
@@ -286,6 +543,40 @@ mod tests {
         );
     }
 
+    /// Verify that a `Scabbard` instance can be created with a registered native transaction
+    /// family enabled.
+    #[test]
+    fn create_with_transaction_families() {
+        let mut registry = TransactionHandlerRegistry::new();
+        registry
+            .register(
+                "noop",
+                Box::new(|| Box::new(NoopTransactionHandler::new("noop"))),
+            )
+            .expect("failed to register handler");
+        let factory = get_factory().with_transaction_handlers(registry);
+        let mut args = get_mock_args();
+        args.insert("transaction_families".into(), "[\"noop\"]".into());
+
+        factory
+            .create("".into(), "", "", args)
+            .expect("failed to create service");
+    }
+
+    /// Verify that `Scabbard` creation fails when the `transaction_families` argument lists a
+    /// family that has no registered handler.
+    #[test]
+    fn create_with_unregistered_transaction_family() {
+        let factory = get_factory();
+        let mut args = get_mock_args();
+        args.insert("transaction_families".into(), "[\"command\"]".into());
+
+        assert!(
+            factory.create("".into(), "", "", args).is_err(),
+            "Creating service with unregistered transaction family did not fail"
+        );
+    }
+
     fn get_factory() -> ScabbardFactory {
         ScabbardFactory::new(
             Some("/tmp".into()),
@@ -0,0 +1,308 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for running native transact transaction handlers in scabbard alongside Sabre.
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+use transact::handler::TransactionHandler;
+#[cfg(test)]
+use transact::handler::{ApplyError, TransactionContext};
+#[cfg(test)]
+use transact::protocol::transaction::TransactionPair;
+
+/// Transaction families whose handlers are always loaded by scabbard and therefore cannot be
+/// registered as native handlers.
+const RESERVED_FAMILIES: &[&str] = &[
+    "sabre",
+    #[cfg(test)]
+    "command",
+];
+
+/// A function that constructs a new instance of a native transaction handler. Each scabbard
+/// service gets its own handler instances, so the constructor is called once per service.
+pub type TransactionHandlerConstructor = Box<dyn Fn() -> Box<dyn TransactionHandler> + Send>;
+
+/// A collection of native transaction handler constructors, keyed by transaction family name.
+///
+/// A `TransactionHandlerRegistry` is provided to the `ScabbardFactory`; each scabbard service
+/// then enables a subset of the registered families using its `transaction_families` argument.
+#[derive(Default)]
+pub struct TransactionHandlerRegistry {
+    constructors: HashMap<String, TransactionHandlerConstructor>,
+}
+
+impl TransactionHandlerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a constructor for the given transaction family. Returns an error if the family is
+    /// reserved by scabbard or if a handler for the family is already registered.
+    pub fn register(
+        &mut self,
+        family_name: &str,
+        constructor: TransactionHandlerConstructor,
+    ) -> Result<(), TransactionHandlerRegistryError> {
+        if RESERVED_FAMILIES.contains(&family_name) {
+            return Err(TransactionHandlerRegistryError::Reserved(
+                family_name.into(),
+            ));
+        }
+
+        if self.constructors.contains_key(family_name) {
+            return Err(TransactionHandlerRegistryError::AlreadyRegistered(
+                family_name.into(),
+            ));
+        }
+
+        self.constructors.insert(family_name.into(), constructor);
+
+        Ok(())
+    }
+
+    /// Returns the names of all registered transaction families.
+    pub fn families(&self) -> Vec<&str> {
+        self.constructors.keys().map(String::as_str).collect()
+    }
+
+    /// Returns `true` if a handler is registered for the given transaction family.
+    pub fn contains(&self, family_name: &str) -> bool {
+        self.constructors.contains_key(family_name)
+    }
+
+    /// Construct new handler instances for the given transaction families. Returns an error if
+    /// any of the families is listed more than once or has not been registered, or if a
+    /// constructor returns a handler for a different family than the one it was registered for.
+    pub fn create_handlers(
+        &self,
+        families: &[String],
+    ) -> Result<Vec<Box<dyn TransactionHandler>>, TransactionHandlerRegistryError> {
+        let mut seen = HashSet::new();
+        families
+            .iter()
+            .map(|family| {
+                if !seen.insert(family) {
+                    return Err(TransactionHandlerRegistryError::Duplicate(family.clone()));
+                }
+
+                let handler = self
+                    .constructors
+                    .get(family)
+                    .map(|constructor| constructor())
+                    .ok_or_else(|| {
+                        TransactionHandlerRegistryError::NotRegistered(family.clone())
+                    })?;
+
+                if handler.family_name() != family {
+                    return Err(TransactionHandlerRegistryError::FamilyMismatch {
+                        registered: family.clone(),
+                        handled: handler.family_name().into(),
+                    });
+                }
+
+                Ok(handler)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum TransactionHandlerRegistryError {
+    AlreadyRegistered(String),
+    Duplicate(String),
+    FamilyMismatch { registered: String, handled: String },
+    NotRegistered(String),
+    Reserved(String),
+}
+
+impl Error for TransactionHandlerRegistryError {}
+
+impl std::fmt::Display for TransactionHandlerRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TransactionHandlerRegistryError::AlreadyRegistered(family) => write!(
+                f,
+                "a transaction handler is already registered for family {}",
+                family
+            ),
+            TransactionHandlerRegistryError::Duplicate(family) => {
+                write!(f, "transaction family {} is listed more than once", family)
+            }
+            TransactionHandlerRegistryError::FamilyMismatch {
+                registered,
+                handled,
+            } => write!(
+                f,
+                "the handler registered for family {} handles family {}",
+                registered, handled
+            ),
+            TransactionHandlerRegistryError::NotRegistered(family) => write!(
+                f,
+                "no transaction handler is registered for family {}",
+                family
+            ),
+            TransactionHandlerRegistryError::Reserved(family) => {
+                write!(f, "transaction family {} is reserved by scabbard", family)
+            }
+        }
+    }
+}
+
+/// A transaction handler for the given family that accepts every transaction without changing
+/// state, for use in tests.
+#[cfg(test)]
+pub(crate) struct NoopTransactionHandler {
+    family_name: String,
+    family_versions: Vec<String>,
+}
+
+#[cfg(test)]
+impl NoopTransactionHandler {
+    pub fn new(family_name: &str) -> Self {
+        Self {
+            family_name: family_name.into(),
+            family_versions: vec!["1".into()],
+        }
+    }
+}
+
+#[cfg(test)]
+impl TransactionHandler for NoopTransactionHandler {
+    fn family_name(&self) -> &str {
+        &self.family_name
+    }
+
+    fn family_versions(&self) -> &[String] {
+        &self.family_versions
+    }
+
+    fn apply(
+        &self,
+        _transaction: &TransactionPair,
+        _context: &mut dyn TransactionContext,
+    ) -> Result<(), ApplyError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that registered families can be used to construct handlers and that unregistered
+    /// families are rejected.
+    #[test]
+    fn create_handlers() {
+        let mut registry = TransactionHandlerRegistry::new();
+        registry
+            .register(
+                "noop",
+                Box::new(|| Box::new(NoopTransactionHandler::new("noop"))),
+            )
+            .expect("failed to register handler");
+
+        assert!(registry.contains("noop"));
+        assert_eq!(registry.families(), vec!["noop"]);
+
+        let handlers = registry
+            .create_handlers(&["noop".to_string()])
+            .expect("failed to create handlers");
+        assert_eq!(handlers.len(), 1);
+        assert_eq!(handlers[0].family_name(), "noop");
+
+        match registry.create_handlers(&["unknown".to_string()]) {
+            Err(TransactionHandlerRegistryError::NotRegistered(family)) => {
+                assert_eq!(family, "unknown")
+            }
+            res => panic!("expected NotRegistered error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    /// Verify that a family cannot be registered twice.
+    #[test]
+    fn register_duplicate() {
+        let mut registry = TransactionHandlerRegistry::new();
+        registry
+            .register(
+                "noop",
+                Box::new(|| Box::new(NoopTransactionHandler::new("noop"))),
+            )
+            .expect("failed to register handler");
+
+        match registry.register(
+            "noop",
+            Box::new(|| Box::new(NoopTransactionHandler::new("noop"))),
+        ) {
+            Err(TransactionHandlerRegistryError::AlreadyRegistered(family)) => {
+                assert_eq!(family, "noop")
+            }
+            res => panic!("expected AlreadyRegistered error, got {:?}", res),
+        }
+    }
+
+    /// Verify that the families whose handlers are built into scabbard cannot be registered.
+    #[test]
+    fn register_reserved() {
+        let mut registry = TransactionHandlerRegistry::new();
+
+        for family in &["sabre", "command"] {
+            match registry.register(
+                family,
+                Box::new(move || Box::new(NoopTransactionHandler::new(family))),
+            ) {
+                Err(TransactionHandlerRegistryError::Reserved(reserved)) => {
+                    assert_eq!(&reserved, family)
+                }
+                res => panic!("expected Reserved error, got {:?}", res),
+            }
+        }
+    }
+
+    /// Verify that handlers are not created when a family is listed twice or when a constructor
+    /// returns a handler for a different family than it was registered for.
+    #[test]
+    fn create_handlers_invalid() {
+        let mut registry = TransactionHandlerRegistry::new();
+        registry
+            .register(
+                "noop",
+                Box::new(|| Box::new(NoopTransactionHandler::new("noop"))),
+            )
+            .expect("failed to register handler");
+        registry
+            .register(
+                "impostor",
+                Box::new(|| Box::new(NoopTransactionHandler::new("sabre"))),
+            )
+            .expect("failed to register handler");
+
+        match registry.create_handlers(&["noop".to_string(), "noop".to_string()]) {
+            Err(TransactionHandlerRegistryError::Duplicate(family)) => assert_eq!(family, "noop"),
+            res => panic!("expected Duplicate error, got {:?}", res.map(|_| ())),
+        }
+
+        match registry.create_handlers(&["impostor".to_string()]) {
+            Err(TransactionHandlerRegistryError::FamilyMismatch {
+                registered,
+                handled,
+            }) => {
+                assert_eq!(registered, "impostor");
+                assert_eq!(handled, "sabre");
+            }
+            res => panic!("expected FamilyMismatch error, got {:?}", res.map(|_| ())),
+        }
+    }
+}
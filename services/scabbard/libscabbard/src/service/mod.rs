@@ -19,6 +19,7 @@
 mod consensus;
 mod error;
 mod factory;
+mod handlers;
 #[cfg(feature = "rest-api")]
 mod rest_api;
 mod shared;
@@ -40,34 +41,43 @@ use splinter::{
     },
     signing::SignatureVerifier,
 };
-use transact::{protocol::batch::BatchPair, protos::FromBytes};
+use transact::{handler::TransactionHandler, protocol::batch::BatchPair, protos::FromBytes};
 
 use super::hex::to_hex;
 use super::protos::scabbard::{ScabbardMessage, ScabbardMessage_Type};
 
+pub use consensus::ConsensusAlgorithm;
 use consensus::ScabbardConsensusManager;
 use error::ScabbardError;
 #[cfg(feature = "service-arg-validation")]
 pub use factory::ScabbardArgValidator;
 pub use factory::ScabbardFactory;
+pub use handlers::{
+    TransactionHandlerConstructor, TransactionHandlerRegistry, TransactionHandlerRegistryError,
+};
+pub use shared::ProposalBatchLimits;
 use shared::ScabbardShared;
 pub use state::{
-    BatchInfo, BatchInfoIter, BatchStatus, Events, StateChange, StateChangeEvent, StateIter,
+    BatchInfo, BatchInfoIter, BatchStatus, Events, StateChange, StateChangeEvent,
+    StateChangeFilter, StateChangeType, StateIter,
 };
-use state::{ScabbardState, StateSubscriber};
+use state::{BatchStatusSubscriber, ScabbardState, StateSubscriber};
 
 const SERVICE_TYPE: &str = "scabbard";
 
 const DEFAULT_COORDINATOR_TIMEOUT: u64 = 30; // 30 seconds
 
-/// A service for running Sawtooth Sabre smart contracts with two-phase commit consensus.
+/// A service for running Sawtooth Sabre smart contracts with two-phase commit (or, optionally,
+/// quorum) consensus.
 #[derive(Clone)]
 pub struct Scabbard {
     circuit_id: String,
     service_id: String,
     shared: Arc<Mutex<ScabbardShared>>,
     state: Arc<Mutex<ScabbardState>>,
-    /// The coordinator timeout for the two-phase commit consensus engine
+    /// The consensus algorithm run by this service
+    consensus_algorithm: ConsensusAlgorithm,
+    /// The coordinator timeout for the consensus engine
     coordinator_timeout: Duration,
     consensus: Arc<Mutex<Option<ScabbardConsensusManager>>>,
 }
@@ -91,11 +101,24 @@ impl Scabbard {
         signature_verifier: Box<dyn SignatureVerifier>,
         // The public keys that are authorized to create and manage sabre contracts
         admin_keys: Vec<String>,
-        // The coordinator timeout for the two-phase commit consensus engine; if `None`, the
-        // default value will be used (30 seconds).
+        // Native transaction handlers to run in addition to Sabre
+        transaction_handlers: Vec<Box<dyn TransactionHandler>>,
+        // The consensus algorithm to run; if `None`, two-phase commit will be used.
+        consensus_algorithm: Option<ConsensusAlgorithm>,
+        // The coordinator timeout for the consensus engine; if `None`, the default value will be
+        // used (30 seconds).
         coordinator_timeout: Option<Duration>,
+        // Limits on the batches included in a single proposal; if `None`, the default limits will
+        // be used.
+        proposal_batch_limits: Option<ProposalBatchLimits>,
     ) -> Result<Self, ScabbardError> {
-        let shared = ScabbardShared::new(VecDeque::new(), None, peer_services, signature_verifier);
+        let shared = ScabbardShared::new(
+            VecDeque::new(),
+            proposal_batch_limits.unwrap_or_default(),
+            None,
+            peer_services,
+            signature_verifier,
+        );
 
         let (state_db_path, receipt_db_path) =
             compute_db_paths(&service_id, circuit_id, state_db_dir, receipt_db_dir)?;
@@ -105,6 +128,7 @@ impl Scabbard {
             receipt_db_path.as_path(),
             receipt_db_size,
             admin_keys,
+            transaction_handlers,
         )
         .map_err(|err| ScabbardError::InitializationFailed(Box::new(err)))?;
 
@@ -116,6 +140,7 @@ impl Scabbard {
             service_id,
             shared: Arc::new(Mutex::new(shared)),
             state: Arc::new(Mutex::new(state)),
+            consensus_algorithm: consensus_algorithm.unwrap_or_default(),
             coordinator_timeout,
             consensus: Arc::new(Mutex::new(None)),
         })
@@ -204,6 +229,22 @@ impl Scabbard {
         Ok(state.batch_history().get_batch_info(ids, wait)?)
     }
 
+    /// Subscribe to the status transitions of the batches with the given `ids`, or of all batches
+    /// if `ids` is `None`.
+    pub fn add_batch_status_subscriber(
+        &self,
+        ids: Option<HashSet<String>>,
+        subscriber: Box<dyn BatchStatusSubscriber>,
+    ) -> Result<(), ScabbardError> {
+        self.state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .batch_history()
+            .add_status_subscriber(ids, subscriber);
+
+        Ok(())
+    }
+
     pub fn get_events_since(&self, event_id: Option<String>) -> Result<Events, ScabbardError> {
         Ok(self
             .state
@@ -258,6 +299,7 @@ impl Service for Scabbard {
                 self.service_id().into(),
                 self.shared.clone(),
                 self.state.clone(),
+                &self.consensus_algorithm,
                 self.coordinator_timeout,
             )
             .map_err(|err| ServiceStartError::Internal(Box::new(ScabbardError::from(err))))?,
@@ -330,13 +372,24 @@ impl Service for Scabbard {
                 let proposed_batch = message.get_proposed_batch();
 
                 let proposal = Proposal::try_from(proposed_batch.get_proposal())?;
-                let batch = BatchPair::from_bytes(proposed_batch.get_batch())
-                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+                // Older peers only set the single `batch` field
+                let batches = if proposed_batch.get_batches().is_empty() {
+                    vec![BatchPair::from_bytes(proposed_batch.get_batch())]
+                } else {
+                    proposed_batch
+                        .get_batches()
+                        .iter()
+                        .map(|batch| BatchPair::from_bytes(batch))
+                        .collect()
+                }
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
 
                 self.shared
                     .lock()
                     .map_err(|_| ServiceError::PoisonedLock("shared lock poisoned".into()))?
-                    .add_proposed_batch(proposal.id.clone(), batch);
+                    .add_proposed_batches(proposal.id.clone(), batches);
 
                 self.consensus
                     .lock()
@@ -404,6 +457,9 @@ pub mod tests {
             1024 * 1024,
             Box::new(HashVerifier),
             vec![],
+            vec![],
+            None,
+            None,
             None,
         )
         .expect("failed to create service");
@@ -425,6 +481,9 @@ pub mod tests {
             1024 * 1024,
             Box::new(HashVerifier),
             vec![],
+            vec![],
+            None,
+            None,
             None,
         )
         .expect("failed to create service");
@@ -446,6 +505,9 @@ pub mod tests {
             1024 * 1024,
             Box::new(HashVerifier),
             vec![],
+            vec![],
+            None,
+            None,
             None,
         )
         .expect("failed to create service");
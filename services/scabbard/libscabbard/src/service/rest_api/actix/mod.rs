@@ -17,4 +17,5 @@ pub mod batches;
 pub mod state;
 pub mod state_address;
 pub mod state_root;
+pub mod ws_batch_statuses;
 pub mod ws_subscribe;
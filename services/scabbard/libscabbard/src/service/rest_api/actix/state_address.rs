@@ -121,6 +121,7 @@ mod tests {
                 &paths.receipt_db_path,
                 TEMP_DB_SIZE,
                 vec![],
+                vec![],
             )
             .expect("Failed to initialize state");
 
@@ -136,7 +137,7 @@ mod tests {
                 .build_pair(&signer)
                 .expect("Failed to build batch");
             state
-                .prepare_change(batch)
+                .prepare_change(vec![batch])
                 .expect("Failed to prepare change");
             state.commit().expect("Failed to commit change");
         }
@@ -152,6 +153,9 @@ mod tests {
             TEMP_DB_SIZE,
             Box::new(HashVerifier),
             vec![],
+            vec![],
+            None,
+            None,
             None,
         )
         .expect("Failed to create scabbard");
@@ -0,0 +1,128 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use futures::IntoFuture;
+use splinter::{
+    rest_api::{
+        new_websocket_event_sender, ErrorResponse, EventSender, Method, ProtocolVersionRangeGuard,
+        Request,
+    },
+    service::rest_api::ServiceEndpoint,
+};
+
+use crate::protocol;
+use crate::service::{
+    error::StateSubscriberError,
+    rest_api::resources::batch_statuses::BatchInfoEvent,
+    state::{BatchInfo, BatchStatusSubscriber},
+    Scabbard, SERVICE_TYPE,
+};
+
+struct WsBatchStatusSubscriber {
+    sender: EventSender<BatchInfoEvent>,
+}
+
+impl BatchStatusSubscriber for WsBatchStatusSubscriber {
+    fn handle_batch_info(&self, info: BatchInfo) -> Result<(), StateSubscriberError> {
+        self.sender.send(BatchInfoEvent(info)).map_err(|_| {
+            debug!(
+                "Dropping scabbard batch status and unsubscribing due to websocket being closed"
+            );
+            StateSubscriberError::Unsubscribe
+        })
+    }
+}
+
+/// Streams batch status transitions over a websocket. If the `ids` query parameter is provided,
+/// the current status of each of the given batches is sent first, and the websocket is closed
+/// once all of them have been committed, found to be invalid or reported as unknown; otherwise, the transitions of all
+/// batches are sent until the client disconnects.
+pub fn make_subscribe_batch_statuses_endpoint() -> ServiceEndpoint {
+    ServiceEndpoint {
+        service_type: SERVICE_TYPE.into(),
+        route: "/ws/batch_statuses".into(),
+        method: Method::Get,
+        handler: Arc::new(move |request, payload, service| {
+            let scabbard = match service.as_any().downcast_ref::<Scabbard>() {
+                Some(s) => s,
+                None => {
+                    error!("Failed to downcast to scabbard service");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            let query =
+                match web::Query::<HashMap<String, String>>::from_query(request.query_string()) {
+                    Ok(query) => query,
+                    Err(_) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request("Invalid query"))
+                                .into_future(),
+                        )
+                    }
+                };
+
+            let ids = match query.get("ids") {
+                Some(ids) if ids.trim().is_empty() => {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("ids must not be empty"))
+                            .into_future(),
+                    );
+                }
+                Some(ids) => Some(ids.split(',').map(String::from).collect::<HashSet<_>>()),
+                None => None,
+            };
+
+            let request = Request::from((request, payload));
+            match new_websocket_event_sender(request, Box::new(std::iter::empty())) {
+                Ok((sender, res)) => {
+                    if let Err(err) = scabbard.add_batch_status_subscriber(
+                        ids,
+                        Box::new(WsBatchStatusSubscriber { sender }),
+                    ) {
+                        error!("Unable to add scabbard batch status sender: {}", err);
+                        return Box::new(
+                            HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future(),
+                        );
+                    }
+                    Box::new(res.into_future())
+                }
+                Err(err) => {
+                    error!("Failed to create websocket: {:?}", err);
+                    Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    )
+                }
+            }
+        }),
+        request_guards: vec![Box::new(ProtocolVersionRangeGuard::new(
+            protocol::SCABBARD_BATCH_STATUSES_SUBSCRIBE_PROTOCOL_MIN,
+            protocol::SCABBARD_PROTOCOL_VERSION,
+        ))],
+    }
+}
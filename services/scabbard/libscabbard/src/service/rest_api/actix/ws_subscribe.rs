@@ -28,16 +28,28 @@ use splinter::{
 use crate::protocol;
 use crate::service::{
     error::StateSubscriberError,
-    state::{StateChangeEvent, StateSubscriber},
+    state::{StateChangeEvent, StateChangeFilter, StateSubscriber},
     Scabbard, SERVICE_TYPE,
 };
 
 struct WsStateSubscriber {
     sender: EventSender<StateChangeEvent>,
+    filter: StateChangeFilter,
 }
 
 impl StateSubscriber for WsStateSubscriber {
     fn handle_event(&self, event: StateChangeEvent) -> Result<(), StateSubscriberError> {
+        let event = match self.filter.filter_event(event) {
+            Some(event) => event,
+            // Events that are filtered out are not sent, so check whether the websocket has been
+            // closed to avoid keeping the subscriber until a matching event is sent
+            None if self.sender.is_closed() => {
+                debug!("Unsubscribing from scabbard state changes due to websocket being closed");
+                return Err(StateSubscriberError::Unsubscribe);
+            }
+            None => return Ok(()),
+        };
+
         self.sender.send(event).map_err(|_| {
             debug!(
                 "Dropping scabbard state change event and unsubscribing due to websocket being
@@ -48,6 +60,35 @@ impl StateSubscriber for WsStateSubscriber {
     }
 }
 
+/// Build the filter for a subscription from the optional `prefixes` and `change_types` query
+/// parameters, which are comma-separated lists of address prefixes and change types (`set` or
+/// `delete`).
+fn parse_filter(query: &HashMap<String, String>) -> Result<StateChangeFilter, String> {
+    let mut filter = StateChangeFilter::new();
+
+    if let Some(prefixes) = query.get("prefixes") {
+        for prefix in prefixes.split(',') {
+            if prefix.is_empty() {
+                return Err("prefixes must not be empty".into());
+            }
+            filter = filter.with_prefix(prefix);
+        }
+    }
+
+    if let Some(change_types) = query.get("change_types") {
+        for change_type in change_types.split(',') {
+            filter = filter.with_change_type(change_type.parse().map_err(|_| {
+                format!(
+                    "invalid change type: {}; must be one of set, delete",
+                    change_type
+                )
+            })?);
+        }
+    }
+
+    Ok(filter)
+}
+
 pub fn make_subscribe_endpoint() -> ServiceEndpoint {
     ServiceEndpoint {
         service_type: SERVICE_TYPE.into(),
@@ -80,6 +121,17 @@ pub fn make_subscribe_endpoint() -> ServiceEndpoint {
 
             let last_seen_event_id = query.remove("last_seen_event");
 
+            let filter = match parse_filter(&query) {
+                Ok(filter) => filter,
+                Err(msg) => {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&msg))
+                            .into_future(),
+                    )
+                }
+            };
+
             match last_seen_event_id {
                 Some(ref id) if id.trim().is_empty() => {
                     return Box::new(
@@ -106,11 +158,15 @@ pub fn make_subscribe_endpoint() -> ServiceEndpoint {
                 }
             };
 
+            let unseen_events_filter = filter.clone();
+            let unseen_events =
+                unseen_events.filter_map(move |event| unseen_events_filter.filter_event(event));
+
             let request = Request::from((request, payload));
             match new_websocket_event_sender(request, Box::new(unseen_events)) {
                 Ok((sender, res)) => {
-                    if let Err(err) =
-                        scabbard.add_state_subscriber(Box::new(WsStateSubscriber { sender }))
+                    if let Err(err) = scabbard
+                        .add_state_subscriber(Box::new(WsStateSubscriber { sender, filter }))
                     {
                         error!("Unable to add scabbard event sender: {}", err);
                         return Box::new(
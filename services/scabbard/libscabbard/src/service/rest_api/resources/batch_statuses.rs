@@ -14,8 +14,20 @@
 
 use std::time::SystemTime;
 
+use serde::{Serialize, Serializer};
+
 use crate::service::state::{BatchInfo, BatchStatus, InvalidTransaction, ValidTransaction};
 
+/// An owned `BatchInfo` that is serialized as a `BatchInfoResponse`, for sending over a websocket.
+#[derive(Debug)]
+pub struct BatchInfoEvent(pub BatchInfo);
+
+impl Serialize for BatchInfoEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BatchInfoResponse::from(&self.0).serialize(serializer)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct BatchInfoResponse<'a> {
     pub id: &'a str,
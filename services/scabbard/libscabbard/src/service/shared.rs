@@ -13,13 +13,14 @@
 // limitations under the License.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
 use transact::protocol::batch::BatchPair;
 use transact::protocol::transaction::{HashMethod, TransactionHeader};
 use transact::protos::FromBytes;
 
 use splinter::{
-    consensus::ProposalId,
+    consensus::{ProposalId, ServicePeers},
     service::ServiceNetworkSender,
     signing::{hash::HashVerifier, SignatureVerifier},
 };
@@ -28,47 +29,130 @@ use crate::hex::parse_hex;
 
 use super::error::ScabbardError;
 
+const DEFAULT_MAX_PROPOSAL_BATCHES: usize = 100;
+const DEFAULT_MAX_PROPOSAL_BYTES: usize = 10 * (1 << 20); // 10 MiB
+const DEFAULT_PROPOSAL_LINGER_MILLIS: u64 = 0;
+
+/// Limits on the batches that are included in a single proposal
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProposalBatchLimits {
+    /// The maximum number of batches in a proposal
+    pub max_batch_count: usize,
+    /// The maximum total size (in bytes) of the batches in a proposal; a single batch that is
+    /// larger than this limit is proposed on its own
+    pub max_batch_bytes: usize,
+    /// How long to wait for more batches to be queued before proposing a partial proposal
+    pub linger: Duration,
+}
+
+impl Default for ProposalBatchLimits {
+    fn default() -> Self {
+        ProposalBatchLimits {
+            max_batch_count: DEFAULT_MAX_PROPOSAL_BATCHES,
+            max_batch_bytes: DEFAULT_MAX_PROPOSAL_BYTES,
+            linger: Duration::from_millis(DEFAULT_PROPOSAL_LINGER_MILLIS),
+        }
+    }
+}
+
 /// Data structure used to store information that's shared between components in this service
 pub struct ScabbardShared {
     /// Queue of batches that have been submitted locally via the REST API, but have not yet been
-    /// proposed.
-    batch_queue: VecDeque<BatchPair>,
+    /// proposed, along with the time each batch was queued.
+    batch_queue: VecDeque<(BatchPair, Instant)>,
+    /// Limits on the batches taken from the queue for a single proposal.
+    proposal_batch_limits: ProposalBatchLimits,
     /// Used to send messages to other services; set when the service is started and unset when the
     /// service is stopped.
     network_sender: Option<Box<dyn ServiceNetworkSender>>,
     /// List of service IDs that this service is configured to communicate and share state with.
     peer_services: HashSet<String>,
     /// Tracks which batches are currently being evaluated, indexed by corresponding proposal IDs.
-    proposed_batches: HashMap<ProposalId, BatchPair>,
+    proposed_batches: HashMap<ProposalId, Vec<BatchPair>>,
+    /// IDs of the proposals in `proposed_batches` that were created by this service from batches
+    /// taken from the queue.
+    own_proposals: HashSet<ProposalId>,
     signature_verifier: Box<dyn SignatureVerifier>,
 }
 
 impl ScabbardShared {
     pub fn new(
         batch_queue: VecDeque<BatchPair>,
+        proposal_batch_limits: ProposalBatchLimits,
         network_sender: Option<Box<dyn ServiceNetworkSender>>,
         peer_services: HashSet<String>,
         signature_verifier: Box<dyn SignatureVerifier>,
     ) -> Self {
+        let now = Instant::now();
         ScabbardShared {
-            batch_queue,
+            batch_queue: batch_queue.into_iter().map(|batch| (batch, now)).collect(),
+            proposal_batch_limits,
             network_sender,
             peer_services,
             proposed_batches: HashMap::new(),
+            own_proposals: HashSet::new(),
             signature_verifier,
         }
     }
 
+    #[cfg(test)]
+    pub fn proposal_batch_limits(&self) -> &ProposalBatchLimits {
+        &self.proposal_batch_limits
+    }
+
     pub fn add_batch_to_queue(&mut self, batch: BatchPair) {
-        self.batch_queue.push_back(batch)
+        self.batch_queue.push_back((batch, Instant::now()))
     }
 
-    pub fn pop_batch_from_queue(&mut self) -> Option<BatchPair> {
-        self.batch_queue.pop_front()
+    /// Take the batches for the next proposal from the front of the queue, up to the configured
+    /// limits. Returns an empty list if the queue is empty, or if the queued batches do not reach
+    /// the limits and the oldest batch has not yet been queued for the linger time.
+    pub fn take_batches_for_proposal(&mut self) -> Vec<BatchPair> {
+        let limits = &self.proposal_batch_limits;
+
+        let oldest_queued_at = match self.batch_queue.front() {
+            Some((_, queued_at)) => *queued_at,
+            None => return vec![],
+        };
+
+        let limits_reached = self.batch_queue.len() >= limits.max_batch_count
+            || self
+                .batch_queue
+                .iter()
+                .take(limits.max_batch_count)
+                .map(|(batch, _)| batch_size(batch))
+                .sum::<usize>()
+                >= limits.max_batch_bytes;
+
+        if !limits_reached && oldest_queued_at.elapsed() < limits.linger {
+            return vec![];
+        }
+
+        let mut batches = vec![];
+        let mut total_bytes = 0;
+        while let Some((batch, _)) = self.batch_queue.front() {
+            let size = batch_size(batch);
+            if batches.len() >= limits.max_batch_count
+                || (!batches.is_empty() && total_bytes + size > limits.max_batch_bytes)
+            {
+                break;
+            }
+
+            total_bytes += size;
+            // unwrap is safe because the front of the queue was just checked
+            batches.push(self.batch_queue.pop_front().unwrap().0);
+        }
+
+        batches
     }
 
-    pub fn network_sender(&self) -> Option<&dyn ServiceNetworkSender> {
-        self.network_sender.as_deref()
+    /// Return batches that were taken for a proposal to the front of the queue, in their original
+    /// order, so they are included in the next proposal.
+    pub fn requeue_batches(&mut self, batches: Vec<BatchPair>) {
+        let now = Instant::now();
+        for batch in batches.into_iter().rev() {
+            self.batch_queue.push_front((batch, now));
+        }
     }
 
     pub fn set_network_sender(&mut self, sender: Box<dyn ServiceNetworkSender>) {
@@ -79,23 +163,34 @@ impl ScabbardShared {
         self.network_sender.take()
     }
 
-    pub fn peer_services(&self) -> &HashSet<String> {
-        &self.peer_services
+    pub fn add_proposed_batches(
+        &mut self,
+        proposal_id: ProposalId,
+        batches: Vec<BatchPair>,
+    ) -> Option<Vec<BatchPair>> {
+        self.proposed_batches.insert(proposal_id, batches)
     }
 
-    pub fn add_proposed_batch(
+    /// Add the batches of a proposal created by this service.
+    pub fn add_own_proposed_batches(
         &mut self,
         proposal_id: ProposalId,
-        batch: BatchPair,
-    ) -> Option<BatchPair> {
-        self.proposed_batches.insert(proposal_id, batch)
+        batches: Vec<BatchPair>,
+    ) -> Option<Vec<BatchPair>> {
+        self.own_proposals.insert(proposal_id.clone());
+        self.proposed_batches.insert(proposal_id, batches)
     }
 
-    pub fn get_proposed_batch(&self, proposal_id: &ProposalId) -> Option<&BatchPair> {
+    pub fn is_own_proposal(&self, proposal_id: &ProposalId) -> bool {
+        self.own_proposals.contains(proposal_id)
+    }
+
+    pub fn get_proposed_batches(&self, proposal_id: &ProposalId) -> Option<&Vec<BatchPair>> {
         self.proposed_batches.get(proposal_id)
     }
 
-    pub fn remove_proposed_batch(&mut self, proposal_id: &ProposalId) -> Option<BatchPair> {
+    pub fn remove_proposed_batches(&mut self, proposal_id: &ProposalId) -> Option<Vec<BatchPair>> {
+        self.own_proposals.remove(proposal_id);
         self.proposed_batches.remove(&proposal_id)
     }
 
@@ -198,3 +293,183 @@ impl ScabbardShared {
         Ok(true)
     }
 }
+
+impl ServicePeers for ScabbardShared {
+    fn network_sender(&self) -> Option<&dyn ServiceNetworkSender> {
+        self.network_sender.as_deref()
+    }
+
+    fn peer_services(&self) -> &HashSet<String> {
+        &self.peer_services
+    }
+}
+
+/// The approximate size of a batch, used to enforce the proposal size limit
+fn batch_size(batch: &BatchPair) -> usize {
+    batch.batch().header().len()
+        + batch
+            .batch()
+            .transactions()
+            .iter()
+            .map(|txn| txn.header().len() + txn.payload().len())
+            .sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use splinter::signing::hash::HashVerifier;
+    use transact::{
+        families::command::make_command_transaction,
+        protocol::{
+            batch::BatchBuilder,
+            command::{BytesEntry, Command, SetState},
+        },
+        signing::hash::HashSigner,
+    };
+
+    /// Verify that batches are taken from the queue up to the configured count limit, in the
+    /// order they were queued.
+    #[test]
+    fn take_batches_count_limit() {
+        let mut shared = ScabbardShared::new(
+            VecDeque::new(),
+            ProposalBatchLimits {
+                max_batch_count: 2,
+                ..Default::default()
+            },
+            None,
+            HashSet::new(),
+            Box::new(HashVerifier),
+        );
+
+        assert!(shared.take_batches_for_proposal().is_empty());
+
+        let batches = (0..3).map(|i| mock_batch(i, 1)).collect::<Vec<_>>();
+        for batch in batches.iter().cloned() {
+            shared.add_batch_to_queue(batch);
+        }
+
+        assert_eq!(
+            batch_ids(&shared.take_batches_for_proposal()),
+            batch_ids(&batches[..2])
+        );
+        assert_eq!(
+            batch_ids(&shared.take_batches_for_proposal()),
+            batch_ids(&batches[2..])
+        );
+        assert!(shared.take_batches_for_proposal().is_empty());
+    }
+
+    /// Verify that batches are taken from the queue up to the configured size limit, and that a
+    /// single batch larger than the limit is still proposed on its own.
+    #[test]
+    fn take_batches_size_limit() {
+        let small_batch = mock_batch(0, 10);
+        let max_batch_bytes = batch_size(&small_batch) * 2;
+        let mut shared = ScabbardShared::new(
+            VecDeque::new(),
+            ProposalBatchLimits {
+                max_batch_bytes,
+                ..Default::default()
+            },
+            None,
+            HashSet::new(),
+            Box::new(HashVerifier),
+        );
+
+        let large_batch = mock_batch(1, max_batch_bytes);
+        let batches = vec![small_batch.clone(), mock_batch(2, 10), large_batch.clone()];
+        for batch in batches.iter().cloned() {
+            shared.add_batch_to_queue(batch);
+        }
+
+        assert_eq!(
+            batch_ids(&shared.take_batches_for_proposal()),
+            batch_ids(&batches[..2])
+        );
+        assert_eq!(
+            batch_ids(&shared.take_batches_for_proposal()),
+            batch_ids(&[large_batch])
+        );
+    }
+
+    /// Verify that a partial proposal is not taken from the queue until the oldest batch has
+    /// been queued for the linger time.
+    #[test]
+    fn take_batches_linger() {
+        let mut shared = ScabbardShared::new(
+            VecDeque::new(),
+            ProposalBatchLimits {
+                max_batch_count: 2,
+                linger: Duration::from_millis(100),
+                ..Default::default()
+            },
+            None,
+            HashSet::new(),
+            Box::new(HashVerifier),
+        );
+
+        shared.add_batch_to_queue(mock_batch(0, 1));
+        assert!(shared.take_batches_for_proposal().is_empty());
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(shared.take_batches_for_proposal().len(), 1);
+    }
+
+    /// Verify that requeued batches are returned to the front of the queue in their original
+    /// order, ahead of batches that were queued after they were taken.
+    #[test]
+    fn requeue_batches() {
+        let mut shared = ScabbardShared::new(
+            VecDeque::new(),
+            ProposalBatchLimits {
+                max_batch_count: 2,
+                ..Default::default()
+            },
+            None,
+            HashSet::new(),
+            Box::new(HashVerifier),
+        );
+
+        let batches = (0..3).map(|i| mock_batch(i, 1)).collect::<Vec<_>>();
+        shared.add_batch_to_queue(batches[0].clone());
+        shared.add_batch_to_queue(batches[1].clone());
+
+        let taken = shared.take_batches_for_proposal();
+        assert_eq!(batch_ids(&taken), batch_ids(&batches[..2]));
+
+        shared.add_batch_to_queue(batches[2].clone());
+        shared.requeue_batches(taken);
+
+        assert_eq!(
+            batch_ids(&shared.take_batches_for_proposal()),
+            batch_ids(&batches[..2])
+        );
+        assert_eq!(
+            batch_ids(&shared.take_batches_for_proposal()),
+            batch_ids(&batches[2..])
+        );
+    }
+
+    fn mock_batch(index: u8, value_size: usize) -> BatchPair {
+        BatchBuilder::new()
+            .with_transactions(vec![
+                make_command_transaction(&[Command::SetState(SetState::new(vec![
+                    BytesEntry::new(format!("abcdef{:02x}", index), vec![index; value_size]),
+                ]))])
+                .take()
+                .0,
+            ])
+            .build_pair(&HashSigner::default())
+            .expect("Failed to build batch")
+    }
+
+    fn batch_ids(batches: &[BatchPair]) -> Vec<String> {
+        batches
+            .iter()
+            .map(|batch| batch.batch().header_signature().to_string())
+            .collect()
+    }
+}
@@ -16,6 +16,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::TryFrom;
 use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::{
     mpsc::{channel, Receiver, RecvTimeoutError, Sender},
     Arc, RwLock,
@@ -38,6 +39,7 @@ use transact::{
         Database,
     },
     execution::{adapter::static_adapter::StaticExecutionAdapter, executor::Executor},
+    handler::TransactionHandler,
     protocol::{
         batch::BatchPair,
         receipt::{TransactionReceipt, TransactionResult},
@@ -70,7 +72,7 @@ pub struct ScabbardState {
     executor: Executor,
     current_state_root: String,
     transaction_receipt_store: Arc<RwLock<TransactionReceiptStore>>,
-    pending_changes: Option<(String, Vec<TransactionReceipt>)>,
+    pending_changes: Option<(Vec<String>, Vec<TransactionReceipt>)>,
     event_subscribers: Vec<Box<dyn StateSubscriber>>,
     batch_history: BatchHistory,
 }
@@ -82,6 +84,7 @@ impl ScabbardState {
         receipt_db_path: &Path,
         receipt_db_size: usize,
         admin_keys: Vec<String>,
+        transaction_handlers: Vec<Box<dyn TransactionHandler>>,
     ) -> Result<Self, ScabbardStateError> {
         // Initialize the database
         let mut indexes = INDEXES.to_vec();
@@ -121,16 +124,18 @@ impl ScabbardState {
             )?
         };
 
-        // Initialize transact
+        // Initialize transact; Sabre is always available, followed by any native handlers
         let context_manager = ContextManager::new(Box::new(MerkleState::new(db.clone())));
+        let mut handlers: Vec<Box<dyn TransactionHandler>> = vec![
+            Box::new(SawtoothToTransactHandlerAdapter::new(
+                SabreTransactionHandler::new(),
+            )),
+            #[cfg(test)]
+            Box::new(CommandTransactionHandler::new()),
+        ];
+        handlers.extend(transaction_handlers);
         let mut executor = Executor::new(vec![Box::new(StaticExecutionAdapter::new_adapter(
-            vec![
-                Box::new(SawtoothToTransactHandlerAdapter::new(
-                    SabreTransactionHandler::new(),
-                )),
-                #[cfg(test)]
-                Box::new(CommandTransactionHandler::new()),
-            ],
+            handlers,
             context_manager.clone(),
         )?)]);
         executor
@@ -221,7 +226,13 @@ impl ScabbardState {
         &self.current_state_root
     }
 
-    pub fn prepare_change(&mut self, batch: BatchPair) -> Result<String, ScabbardStateError> {
+    /// Execute the given batches against the current state and save the resulting changes as
+    /// pending. Batches that are invalid do not contribute any changes; the resulting state root
+    /// is returned along with the batches that were valid.
+    pub fn prepare_change(
+        &mut self,
+        batches: Vec<BatchPair>,
+    ) -> Result<(String, Vec<BatchPair>), ScabbardStateError> {
         // Setup the transact scheduler
         let (result_tx, result_rx) = std::sync::mpsc::channel();
         let mut scheduler = SerialScheduler::new(
@@ -234,49 +245,73 @@ impl ScabbardState {
             }
         }))?;
 
-        // Add the batch to, finalize, and execute the scheduler
-        scheduler.add_batch(batch.clone())?;
+        // Add the batches to, finalize, and execute the scheduler
+        let batch_count = batches.len();
+        for batch in batches {
+            scheduler.add_batch(batch)?;
+        }
         scheduler.finalize()?;
         self.executor
             .execute(scheduler.take_task_iterator()?, scheduler.new_notifier()?)?;
 
         // Get the results and shutdown the scheduler
-        let recv_result = result_rx.recv_timeout(Duration::from_secs(EXECUTION_TIMEOUT));
+        let deadline = Instant::now() + Duration::from_secs(EXECUTION_TIMEOUT);
+        let mut batch_results = Vec::with_capacity(batch_count);
+        let recv_result = loop {
+            if batch_results.len() == batch_count {
+                break Ok(());
+            }
+            match result_rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(Some(batch_result)) => batch_results.push(batch_result),
+                Ok(None) => break Ok(()),
+                Err(_) => break Err(()),
+            }
+        };
 
         scheduler.shutdown();
 
-        let batch_result = recv_result
-            .map_err(|_| ScabbardStateError("failed to receive result in reasonable time".into()))?
-            .ok_or_else(|| ScabbardStateError("no result returned from executor".into()))?;
-
-        let batch_status = batch_result.clone().into();
-        let signature = batch.batch().header_signature();
-        self.batch_history
-            .update_batch_status(&signature, batch_status);
+        recv_result.map_err(|_| {
+            ScabbardStateError("failed to receive result in reasonable time".into())
+        })?;
+        if batch_results.len() != batch_count {
+            return Err(ScabbardStateError(format!(
+                "executor returned {} of {} batch results",
+                batch_results.len(),
+                batch_count
+            )));
+        }
 
-        let txn_receipts = batch_result
-            .receipts
-            .into_iter()
-            .map(|receipt| match receipt.transaction_result {
-                TransactionResult::Valid { .. } => Ok(receipt),
-                TransactionResult::Invalid { error_message, .. } => Err(ScabbardStateError(
-                    format!("transaction failed: {:?}", error_message),
-                )),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut valid_batches = Vec::with_capacity(batch_count);
+        let mut signatures = Vec::with_capacity(batch_count);
+        let mut txn_receipts = vec![];
+        for batch_result in batch_results {
+            let batch_status: BatchStatus = batch_result.clone().into();
+            let signature = batch_result.batch.batch().header_signature().to_string();
+            self.batch_history
+                .update_batch_status(&signature, batch_status.clone());
+
+            match batch_status {
+                BatchStatus::Valid(_) => {
+                    txn_receipts.extend(batch_result.receipts);
+                    signatures.push(signature);
+                    valid_batches.push(batch_result.batch);
+                }
+                _ => debug!("Batch {} is invalid; excluding its changes", signature),
+            }
+        }
 
         // Save the results and compute the resulting state root
         let state_root = MerkleState::new(self.db.clone()).compute_state_id(
             &self.current_state_root,
             &receipts_into_transact_state_changes(&txn_receipts)?,
         )?;
-        self.pending_changes = Some((signature.to_string(), txn_receipts));
-        Ok(state_root)
+        self.pending_changes = Some((signatures, txn_receipts));
+        Ok((state_root, valid_batches))
     }
 
     pub fn commit(&mut self) -> Result<(), ScabbardStateError> {
         match self.pending_changes.take() {
-            Some((signature, txn_receipts)) => {
+            Some((signatures, txn_receipts)) => {
                 let state_changes = receipts_into_transact_state_changes(&txn_receipts)?;
                 self.current_state_root = MerkleState::new(self.db.clone())
                     .commit(&self.current_state_root, &state_changes)?;
@@ -324,7 +359,9 @@ impl ScabbardState {
                     });
                 }
 
-                self.batch_history.commit(&signature);
+                for signature in signatures {
+                    self.batch_history.commit(&signature);
+                }
 
                 Ok(())
             }
@@ -436,6 +473,24 @@ impl fmt::Debug for StateChange {
     }
 }
 
+impl StateChange {
+    /// Get the address that this change applies to.
+    pub fn key(&self) -> &str {
+        match self {
+            StateChange::Set { key, .. } => key,
+            StateChange::Delete { key } => key,
+        }
+    }
+
+    /// Get the type of this change.
+    pub fn change_type(&self) -> StateChangeType {
+        match self {
+            StateChange::Set { .. } => StateChangeType::Set,
+            StateChange::Delete { .. } => StateChangeType::Delete,
+        }
+    }
+}
+
 impl From<transact::protocol::receipt::StateChange> for StateChange {
     fn from(change: transact::protocol::receipt::StateChange) -> Self {
         match change {
@@ -447,10 +502,95 @@ impl From<transact::protocol::receipt::StateChange> for StateChange {
     }
 }
 
+/// The type of a `StateChange`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StateChangeType {
+    Set,
+    Delete,
+}
+
+impl FromStr for StateChangeType {
+    type Err = ScabbardStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "set" => Ok(StateChangeType::Set),
+            "delete" => Ok(StateChangeType::Delete),
+            _ => Err(ScabbardStateError(format!(
+                "invalid state change type: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Selects the state changes that are sent to a subscriber. A change matches the filter if its
+/// address is under one of the filter's prefixes and its type is one of the filter's change
+/// types; an empty filter matches all changes.
+#[derive(Clone, Debug, Default)]
+pub struct StateChangeFilter {
+    prefixes: Vec<String>,
+    change_types: HashSet<StateChangeType>,
+}
+
+impl StateChangeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match changes to addresses under the given prefix (or under any of the other
+    /// prefixes added to the filter).
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Only match changes of the given type (or of any of the other types added to the filter).
+    pub fn with_change_type(mut self, change_type: StateChangeType) -> Self {
+        self.change_types.insert(change_type);
+        self
+    }
+
+    /// Returns `true` if the filter matches all changes.
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && self.change_types.is_empty()
+    }
+
+    pub fn matches(&self, change: &StateChange) -> bool {
+        (self.prefixes.is_empty()
+            || self
+                .prefixes
+                .iter()
+                .any(|prefix| change.key().starts_with(prefix.as_str())))
+            && (self.change_types.is_empty() || self.change_types.contains(&change.change_type()))
+    }
+
+    /// Remove the changes that do not match the filter from the given event. Returns `None` if
+    /// none of the event's changes match.
+    pub fn filter_event(&self, mut event: StateChangeEvent) -> Option<StateChangeEvent> {
+        if self.is_empty() {
+            return Some(event);
+        }
+
+        event.state_changes.retain(|change| self.matches(change));
+
+        if event.state_changes.is_empty() {
+            None
+        } else {
+            Some(event)
+        }
+    }
+}
+
 pub trait StateSubscriber: Send {
     fn handle_event(&self, event: StateChangeEvent) -> Result<(), StateSubscriberError>;
 }
 
+/// Receives every status transition of the batches it is subscribed to.
+pub trait BatchStatusSubscriber: Send {
+    fn handle_batch_info(&self, info: BatchInfo) -> Result<(), StateSubscriberError>;
+}
+
 #[derive(PartialEq)]
 enum EventQuery {
     Fetch(Option<String>),
@@ -619,6 +759,9 @@ pub struct BatchHistory {
     history: HashMap<String, BatchInfo>,
     limit: usize,
     batch_subscribers: Vec<(HashSet<String>, Sender<BatchInfo>)>,
+    /// Subscribers to batch status transitions, along with the IDs of the batches each one is
+    /// waiting on (`None` for subscribers to all batches).
+    status_subscribers: Vec<(Option<HashSet<String>>, Box<dyn BatchStatusSubscriber>)>,
 }
 
 impl BatchHistory {
@@ -646,6 +789,8 @@ impl BatchHistory {
             Some(info) => match info.status.clone() {
                 BatchStatus::Valid(txns) => {
                     info.set_status(BatchStatus::Committed(txns));
+                    let info = info.clone();
+                    self.send_batch_info_to_status_subscribers(info);
                 }
                 _ => {
                     error!(
@@ -664,6 +809,12 @@ impl BatchHistory {
     }
 
     fn upsert_batch(&mut self, signature: String, status: BatchStatus) -> BatchInfo {
+        let batch_info = self.upsert_batch_info(signature, status);
+        self.send_batch_info_to_status_subscribers(batch_info.clone());
+        batch_info
+    }
+
+    fn upsert_batch_info(&mut self, signature: String, status: BatchStatus) -> BatchInfo {
         match self.history.get_mut(&signature) {
             Some(info) => {
                 info.set_status(status);
@@ -751,6 +902,77 @@ impl BatchHistory {
         ))
     }
 
+    /// Add a subscriber that will receive every status transition for the batches with the given
+    /// `ids`, or for all batches if `ids` is `None`. If `ids` are given, the current status of
+    /// each of those batches is sent to the subscriber immediately, and the subscriber is removed
+    /// once all of the batches have been committed or found to be invalid. Batches that are not in
+    /// the history are reported with the `Unknown` status and are not waited on, since their
+    /// status will never be known.
+    pub fn add_status_subscriber(
+        &mut self,
+        ids: Option<HashSet<String>>,
+        subscriber: Box<dyn BatchStatusSubscriber>,
+    ) {
+        let pending_ids = match ids {
+            Some(mut ids) => {
+                for info in self.no_wait_batch_info_iter(&ids).filter_map(Result::ok) {
+                    if is_completed(&info.status) || info.status == BatchStatus::Unknown {
+                        ids.remove(&info.id);
+                    }
+                    if let Err(err) = subscriber.handle_batch_info(info) {
+                        if let StateSubscriberError::UnableToHandleEvent(_) = err {
+                            error!("{}", err);
+                        }
+                        return;
+                    }
+                }
+
+                if ids.is_empty() {
+                    return;
+                }
+
+                Some(ids)
+            }
+            None => None,
+        };
+
+        self.status_subscribers.push((pending_ids, subscriber));
+    }
+
+    fn send_batch_info_to_status_subscribers(&mut self, info: BatchInfo) {
+        let completed = is_completed(&info.status);
+        self.status_subscribers = self
+            .status_subscribers
+            .drain(..)
+            .filter_map(|(mut pending_ids, subscriber)| {
+                let subscribed = match &pending_ids {
+                    Some(ids) => ids.contains(&info.id),
+                    None => true,
+                };
+                if !subscribed {
+                    return Some((pending_ids, subscriber));
+                }
+
+                match subscriber.handle_batch_info(info.clone()) {
+                    Ok(()) => {}
+                    Err(StateSubscriberError::Unsubscribe) => return None,
+                    Err(err @ StateSubscriberError::UnableToHandleEvent(_)) => error!("{}", err),
+                }
+
+                if let Some(ids) = pending_ids.as_mut() {
+                    if completed {
+                        ids.remove(&info.id);
+                    }
+                    if ids.is_empty() {
+                        return None;
+                    }
+                }
+
+                Some((pending_ids, subscriber))
+            })
+            .collect();
+    }
+
     fn send_completed_batch_info_to_subscribers(&mut self, info: BatchInfo) {
         self.batch_subscribers = self
             .batch_subscribers
@@ -777,10 +999,20 @@ impl Default for BatchHistory {
             history: HashMap::new(),
             limit: DEFAULT_BATCH_HISTORY_SIZE,
             batch_subscribers: vec![],
+            status_subscribers: vec![],
         }
     }
 }
 
+/// Returns `true` if the given status is final; that is, the batch has been committed or found to
+/// be invalid.
+fn is_completed(status: &BatchStatus) -> bool {
+    match status {
+        BatchStatus::Invalid(_) | BatchStatus::Committed(_) => true,
+        _ => false,
+    }
+}
+
 pub type BatchInfoIter = Box<dyn Iterator<Item = Result<BatchInfo, String>>>;
 
 pub struct ChannelBatchInfoIter {
@@ -849,13 +1081,23 @@ mod tests {
         families::command::make_command_transaction,
         protocol::{
             batch::BatchBuilder,
-            command::{BytesEntry, Command, SetState},
+            command::{BytesEntry, Command, ReturnInvalid, SetState},
         },
         signing::hash::HashSigner,
     };
 
     const TEMP_DB_SIZE: usize = 1 << 30; // 1024 ** 3
 
+    struct MockBatchStatusSubscriber(Sender<BatchInfo>);
+
+    impl BatchStatusSubscriber for MockBatchStatusSubscriber {
+        fn handle_batch_info(&self, info: BatchInfo) -> Result<(), StateSubscriberError> {
+            self.0
+                .send(info)
+                .map_err(|_| StateSubscriberError::Unsubscribe)
+        }
+    }
+
     /// Verify that an empty receipt store returns an empty iterator
     #[test]
     fn empty_event_iterator() {
@@ -922,6 +1164,173 @@ mod tests {
         assert_eq!(some_event_ids, receipt_ids[1..].to_vec());
     }
 
+    /// Verify that a `StateChangeFilter` only keeps the changes that match its prefixes and change
+    /// types, and drops events that have no matching changes.
+    #[test]
+    fn state_change_filter() {
+        let event = StateChangeEvent {
+            id: "event".into(),
+            state_changes: vec![
+                StateChange::Set {
+                    key: "abcd01".into(),
+                    value: vec![1],
+                },
+                StateChange::Delete {
+                    key: "abcd02".into(),
+                },
+                StateChange::Set {
+                    key: "ef0001".into(),
+                    value: vec![2],
+                },
+            ],
+        };
+        let keys = |event: Option<StateChangeEvent>| {
+            event.map(|event| {
+                event
+                    .state_changes
+                    .iter()
+                    .map(|change| change.key().to_string())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            keys(StateChangeFilter::new().filter_event(event.clone())),
+            Some(vec!["abcd01".into(), "abcd02".into(), "ef0001".into()])
+        );
+        assert_eq!(
+            keys(
+                StateChangeFilter::new()
+                    .with_prefix("abcd")
+                    .filter_event(event.clone())
+            ),
+            Some(vec!["abcd01".into(), "abcd02".into()])
+        );
+        assert_eq!(
+            keys(
+                StateChangeFilter::new()
+                    .with_prefix("abcd")
+                    .with_prefix("ef")
+                    .with_change_type(StateChangeType::Set)
+                    .filter_event(event.clone())
+            ),
+            Some(vec!["abcd01".into(), "ef0001".into()])
+        );
+        assert_eq!(
+            keys(
+                StateChangeFilter::new()
+                    .with_prefix("ef")
+                    .with_change_type(StateChangeType::Delete)
+                    .filter_event(event)
+            ),
+            None
+        );
+    }
+
+    /// Verify that batch status subscribers receive the status transitions of the batches they
+    /// are subscribed to, and are removed once those batches are completed.
+    ///
+    /// 1. Add a batch to the history and subscribe to it, and to all batches.
+    /// 2. Verify that the subscriber for the batch receives its current status.
+    /// 3. Add another batch, then move both batches to valid and committed.
+    /// 4. Verify that the subscriber for the batch only received the batch's transitions and was
+    ///    removed once it was committed, and that the subscriber for all batches received every
+    ///    transition.
+    #[test]
+    fn batch_status_subscribers() {
+        let mut history = BatchHistory::new();
+        history.add_batch("batch1");
+
+        let (batch1_tx, batch1_rx) = channel();
+        let mut ids = HashSet::new();
+        ids.insert("batch1".to_string());
+        history.add_status_subscriber(Some(ids), Box::new(MockBatchStatusSubscriber(batch1_tx)));
+
+        let (all_tx, all_rx) = channel();
+        history.add_status_subscriber(None, Box::new(MockBatchStatusSubscriber(all_tx)));
+
+        history.add_batch("batch2");
+        for id in &["batch1", "batch2"] {
+            history.update_batch_status(id, BatchStatus::Valid(vec![]));
+            history.commit(id);
+        }
+
+        assert_eq!(
+            batch1_rx
+                .try_iter()
+                .map(|info| info.status)
+                .collect::<Vec<_>>(),
+            vec![
+                BatchStatus::Pending,
+                BatchStatus::Valid(vec![]),
+                BatchStatus::Committed(vec![]),
+            ]
+        );
+        assert_eq!(history.status_subscribers.len(), 1);
+
+        assert_eq!(
+            all_rx
+                .try_iter()
+                .map(|info| (info.id, info.status))
+                .collect::<Vec<_>>(),
+            vec![
+                ("batch2".to_string(), BatchStatus::Pending),
+                ("batch1".to_string(), BatchStatus::Valid(vec![])),
+                ("batch1".to_string(), BatchStatus::Committed(vec![])),
+                ("batch2".to_string(), BatchStatus::Valid(vec![])),
+                ("batch2".to_string(), BatchStatus::Committed(vec![])),
+            ]
+        );
+    }
+
+    /// Verify that a subscriber to batches that are not in the history is sent the `Unknown`
+    /// status for each of them and is not kept waiting on them.
+    ///
+    /// 1. Add a batch to the history, then subscribe to it and to a batch that is not in the
+    ///    history.
+    /// 2. Verify the subscriber receives the pending and unknown statuses, and is only waiting on
+    ///    the known batch.
+    /// 3. Subscribe to only the unknown batch and verify the subscriber is not kept.
+    #[test]
+    fn batch_status_subscribers_unknown_ids() {
+        let mut history = BatchHistory::new();
+        history.add_batch("batch1");
+
+        let (tx, rx) = channel();
+        let ids = vec!["batch1".to_string(), "unknown".to_string()]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        history.add_status_subscriber(Some(ids), Box::new(MockBatchStatusSubscriber(tx)));
+
+        let mut statuses = rx
+            .try_iter()
+            .map(|info| (info.id, info.status))
+            .collect::<Vec<_>>();
+        statuses.sort_by(|(id1, _), (id2, _)| id1.cmp(id2));
+        assert_eq!(
+            statuses,
+            vec![
+                ("batch1".to_string(), BatchStatus::Pending),
+                ("unknown".to_string(), BatchStatus::Unknown),
+            ]
+        );
+        assert_eq!(history.status_subscribers.len(), 1);
+        assert_eq!(
+            history.status_subscribers[0].0,
+            Some(vec!["batch1".to_string()].into_iter().collect())
+        );
+
+        let (tx, rx) = channel();
+        let ids = vec!["unknown".to_string()].into_iter().collect();
+        history.add_status_subscriber(Some(ids), Box::new(MockBatchStatusSubscriber(tx)));
+
+        assert_eq!(
+            rx.try_iter().map(|info| info.status).collect::<Vec<_>>(),
+            vec![BatchStatus::Unknown]
+        );
+        assert_eq!(history.status_subscribers.len(), 1);
+    }
+
     /// Verify that the `ScabbardState::get_state_at_address` method works properly.
     ///
     /// 1. Initialize a new, empty `ScabbardState`.
@@ -939,6 +1348,7 @@ mod tests {
             &paths.receipt_db_path,
             TEMP_DB_SIZE,
             vec![],
+            vec![],
         )
         .expect("Failed to initialize state");
 
@@ -958,7 +1368,7 @@ mod tests {
             .build_pair(&signer)
             .expect("Failed to build batch");
         state
-            .prepare_change(batch)
+            .prepare_change(vec![batch])
             .expect("Failed to prepare change");
         state.commit().expect("Failed to commit change");
 
@@ -979,6 +1389,98 @@ mod tests {
         );
     }
 
+    /// Verify that multiple batches can be prepared and committed as a single change, and that
+    /// invalid batches are excluded from the change.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`.
+    /// 2. Prepare a change with two valid batches and one invalid batch, and verify that only the
+    ///    valid batches are returned.
+    /// 3. Commit the change and verify that the values set by the valid batches are in state.
+    /// 4. Verify that the valid batches are committed and the invalid batch is marked invalid in
+    ///    the batch history.
+    #[test]
+    fn prepare_change_multiple_batches() {
+        // Initialize state
+        let paths = StatePaths::new("prepare_change_multiple_batches");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let signer = HashSigner::default();
+        let make_batch = |command| {
+            BatchBuilder::new()
+                .with_transactions(vec![make_command_transaction(&[command]).take().0])
+                .build_pair(&signer)
+                .expect("Failed to build batch")
+        };
+        let batch1 = make_batch(Command::SetState(SetState::new(vec![BytesEntry::new(
+            "abcdef01".into(),
+            b"value1".to_vec(),
+        )])));
+        let invalid_batch =
+            make_batch(Command::ReturnInvalid(ReturnInvalid::new("invalid".into())));
+        let batch2 = make_batch(Command::SetState(SetState::new(vec![BytesEntry::new(
+            "abcdef02".into(),
+            b"value2".to_vec(),
+        )])));
+
+        let (_, valid_batches) = state
+            .prepare_change(vec![batch1.clone(), invalid_batch.clone(), batch2.clone()])
+            .expect("Failed to prepare change");
+        assert_eq!(
+            valid_batches
+                .iter()
+                .map(|batch| batch.batch().header_signature())
+                .collect::<Vec<_>>(),
+            vec![
+                batch1.batch().header_signature(),
+                batch2.batch().header_signature()
+            ]
+        );
+
+        state.commit().expect("Failed to commit change");
+
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef01")
+                .expect("Failed to get state"),
+            Some(b"value1".to_vec()),
+        );
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef02")
+                .expect("Failed to get state"),
+            Some(b"value2".to_vec()),
+        );
+
+        let history = state.batch_history();
+        for batch in &[batch1, batch2] {
+            match history.history.get(batch.batch().header_signature()) {
+                Some(BatchInfo {
+                    status: BatchStatus::Committed(_),
+                    ..
+                }) => {}
+                info => panic!("Batch should have been committed: {:?}", info),
+            }
+        }
+        match history
+            .history
+            .get(invalid_batch.batch().header_signature())
+        {
+            Some(BatchInfo {
+                status: BatchStatus::Invalid(_),
+                ..
+            }) => {}
+            info => panic!("Batch should have been invalid: {:?}", info),
+        }
+    }
+
     /// Verify that the `ScabbardState::get_state_with_prefix` method works properly.
     ///
     /// 1. Initialize a new, empty `ScabbardState`.
@@ -1000,6 +1502,7 @@ mod tests {
             &paths.receipt_db_path,
             TEMP_DB_SIZE,
             vec![],
+            vec![],
         )
         .expect("Failed to initialize state");
 
@@ -1027,7 +1530,7 @@ mod tests {
             .build_pair(&signer)
             .expect("Failed to build batch");
         state
-            .prepare_change(batch)
+            .prepare_change(vec![batch])
             .expect("Failed to prepare change");
         state.commit().expect("Failed to commit change");
 
@@ -291,6 +291,15 @@ impl ConfigBuilder {
                     None => None,
                 })
                 .ok_or_else(|| ConfigError::MissingValue("enable_biome".to_string()))?,
+            #[cfg(feature = "registry-database")]
+            registry_database: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.registry_database() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| ConfigError::MissingValue("registry_database".to_string()))?,
             #[cfg(feature = "rest-api-cors")]
             whitelist: self
                 .partial_configs
@@ -299,6 +308,95 @@ impl ConfigBuilder {
                     Some(v) => Some((v, p.source())),
                     None => None,
                 }),
+            #[cfg(feature = "rest-api-auth")]
+            rest_api_auth_config: self.partial_configs.iter().find_map(|p| {
+                match p.rest_api_auth_config() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-oauth")]
+            biome_oauth_config: self.partial_configs.iter().find_map(|p| {
+                match p.biome_oauth_config() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-oauth")]
+            biome_oauth_insecure_http: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.biome_oauth_insecure_http() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| {
+                    ConfigError::MissingValue("biome_oauth_insecure_http".to_string())
+                })?,
+            #[cfg(feature = "biome-keyring")]
+            biome_keyring_config: self.partial_configs.iter().find_map(|p| {
+                match p.biome_keyring_config() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-key-encryption")]
+            biome_master_key_file: self.partial_configs.iter().find_map(|p| {
+                match p.biome_master_key_file() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-roles")]
+            biome_role_admins: self.partial_configs.iter().find_map(|p| {
+                match p.biome_role_admins() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "audit")]
+            audit_log: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.audit_log() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }),
+            #[cfg(feature = "registry-signing")]
+            registry_trust_keys: self.partial_configs.iter().find_map(|p| {
+                match p.registry_trust_keys() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "registry-merge")]
+            registry_merge_strategy: self.partial_configs.iter().find_map(|p| {
+                match p.registry_merge_strategy() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "registry-federation")]
+            registry_federation_circuit: self.partial_configs.iter().find_map(|p| {
+                match p.registry_federation_circuit() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "registry-remote-auth")]
+            registry_auth_config: self.partial_configs.iter().find_map(|p| {
+                match p.registry_auth_config() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "registry-health")]
+            registry_check_interval: self.partial_configs.iter().find_map(|p| {
+                match p.registry_check_interval() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
             strict_ref_counts: self
                 .partial_configs
                 .iter()
@@ -114,6 +114,17 @@ impl<'a> PartialConfigBuilder for ClapPartialConfigBuilder<'_> {
                 partial_config.with_database(self.matches.value_of("database").map(String::from))
         }
 
+        #[cfg(feature = "registry-database")]
+        {
+            partial_config = partial_config.with_registry_database(
+                if self.matches.is_present("registry_database") {
+                    Some(true)
+                } else {
+                    None
+                },
+            );
+        }
+
         #[cfg(feature = "rest-api-cors")]
         {
             partial_config = partial_config.with_whitelist(
@@ -123,6 +134,108 @@ impl<'a> PartialConfigBuilder for ClapPartialConfigBuilder<'_> {
             )
         }
 
+        #[cfg(feature = "rest-api-auth")]
+        {
+            partial_config = partial_config.with_rest_api_auth_config(
+                self.matches
+                    .value_of("rest_api_auth_config")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "biome-oauth")]
+        {
+            partial_config = partial_config.with_biome_oauth_config(
+                self.matches
+                    .value_of("biome_oauth_config")
+                    .map(String::from),
+            );
+            partial_config = partial_config.with_biome_oauth_insecure_http(
+                if self.matches.is_present("biome_oauth_insecure_http") {
+                    Some(true)
+                } else {
+                    None
+                },
+            )
+        }
+
+        #[cfg(feature = "biome-keyring")]
+        {
+            partial_config = partial_config.with_biome_keyring_config(
+                self.matches
+                    .value_of("biome_keyring_config")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "biome-key-encryption")]
+        {
+            partial_config = partial_config.with_biome_master_key_file(
+                self.matches
+                    .value_of("biome_master_key_file")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "biome-roles")]
+        {
+            partial_config = partial_config.with_biome_role_admins(
+                self.matches
+                    .values_of("biome_role_admins")
+                    .map(|values| values.map(String::from).collect::<Vec<String>>()),
+            )
+        }
+
+        #[cfg(feature = "audit")]
+        {
+            partial_config =
+                partial_config.with_audit_log(self.matches.value_of("audit_log").map(String::from))
+        }
+
+        #[cfg(feature = "registry-signing")]
+        {
+            partial_config = partial_config.with_registry_trust_keys(
+                self.matches
+                    .values_of("registry_trust_keys")
+                    .map(|values| values.map(String::from).collect::<Vec<String>>()),
+            )
+        }
+
+        #[cfg(feature = "registry-merge")]
+        {
+            partial_config = partial_config.with_registry_merge_strategy(
+                self.matches
+                    .value_of("registry_merge_strategy")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "registry-federation")]
+        {
+            partial_config = partial_config.with_registry_federation_circuit(
+                self.matches
+                    .value_of("registry_federation_circuit")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "registry-remote-auth")]
+        {
+            partial_config = partial_config.with_registry_auth_config(
+                self.matches
+                    .value_of("registry_auth_config")
+                    .map(String::from),
+            )
+        }
+
+        #[cfg(feature = "registry-health")]
+        {
+            partial_config = partial_config.with_registry_check_interval(parse_value(
+                &self.matches,
+                "registry_check_interval",
+            )?)
+        }
+
         Ok(partial_config)
     }
 }
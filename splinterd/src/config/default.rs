@@ -85,11 +85,21 @@ impl PartialConfigBuilder for DefaultPartialConfigBuilder {
             partial_config = partial_config.with_enable_biome(Some(false));
         }
 
+        #[cfg(feature = "biome-oauth")]
+        {
+            partial_config = partial_config.with_biome_oauth_insecure_http(Some(false));
+        }
+
         #[cfg(feature = "database")]
         {
             partial_config = partial_config.with_database(Some(String::from(DATABASE)));
         }
 
+        #[cfg(feature = "registry-database")]
+        {
+            partial_config = partial_config.with_registry_database(Some(false));
+        }
+
         Ok(partial_config)
     }
 }
@@ -149,6 +159,8 @@ mod tests {
         assert_eq!(config.no_tls(), Some(false));
         #[cfg(feature = "biome")]
         assert_eq!(config.enable_biome(), Some(false));
+        #[cfg(feature = "registry-database")]
+        assert_eq!(config.registry_database(), Some(false));
         // Assert the source is correctly identified for this `PartialConfig` object.
         assert_eq!(config.source(), ConfigSource::Default);
     }
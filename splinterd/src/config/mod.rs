@@ -68,8 +68,34 @@ pub struct Config {
     no_tls: (bool, ConfigSource),
     #[cfg(feature = "biome")]
     enable_biome: (bool, ConfigSource),
+    #[cfg(feature = "registry-database")]
+    registry_database: (bool, ConfigSource),
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "rest-api-auth")]
+    rest_api_auth_config: Option<(String, ConfigSource)>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_config: Option<(String, ConfigSource)>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_insecure_http: (bool, ConfigSource),
+    #[cfg(feature = "biome-keyring")]
+    biome_keyring_config: Option<(String, ConfigSource)>,
+    #[cfg(feature = "biome-key-encryption")]
+    biome_master_key_file: Option<(String, ConfigSource)>,
+    #[cfg(feature = "biome-roles")]
+    biome_role_admins: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "audit")]
+    audit_log: Option<(String, ConfigSource)>,
+    #[cfg(feature = "registry-signing")]
+    registry_trust_keys: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "registry-merge")]
+    registry_merge_strategy: Option<(String, ConfigSource)>,
+    #[cfg(feature = "registry-federation")]
+    registry_federation_circuit: Option<(String, ConfigSource)>,
+    #[cfg(feature = "registry-remote-auth")]
+    registry_auth_config: Option<(String, ConfigSource)>,
+    #[cfg(feature = "registry-health")]
+    registry_check_interval: Option<(u64, ConfigSource)>,
     strict_ref_counts: (bool, ConfigSource),
 }
 
@@ -185,6 +211,11 @@ impl Config {
         self.enable_biome.0
     }
 
+    #[cfg(feature = "registry-database")]
+    pub fn registry_database(&self) -> bool {
+        self.registry_database.0
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn whitelist(&self) -> Option<&[String]> {
         if let Some((list, _)) = &self.whitelist {
@@ -194,6 +225,110 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "rest-api-auth")]
+    pub fn rest_api_auth_config(&self) -> Option<&str> {
+        if let Some((path, _)) = &self.rest_api_auth_config {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    pub fn biome_oauth_config(&self) -> Option<&str> {
+        if let Some((path, _)) = &self.biome_oauth_config {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    pub fn biome_oauth_insecure_http(&self) -> bool {
+        self.biome_oauth_insecure_http.0
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    pub fn biome_keyring_config(&self) -> Option<&str> {
+        if let Some((path, _)) = &self.biome_keyring_config {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    pub fn biome_master_key_file(&self) -> Option<&str> {
+        if let Some((path, _)) = &self.biome_master_key_file {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-roles")]
+    pub fn biome_role_admins(&self) -> Option<&[String]> {
+        if let Some((list, _)) = &self.biome_role_admins {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "audit")]
+    pub fn audit_log(&self) -> Option<&str> {
+        if let Some((audit_log, _)) = &self.audit_log {
+            Some(audit_log)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-signing")]
+    pub fn registry_trust_keys(&self) -> Option<&[String]> {
+        if let Some((list, _)) = &self.registry_trust_keys {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-merge")]
+    pub fn registry_merge_strategy(&self) -> Option<&str> {
+        if let Some((strategy, _)) = &self.registry_merge_strategy {
+            Some(strategy)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-federation")]
+    pub fn registry_federation_circuit(&self) -> Option<&str> {
+        if let Some((circuit_id, _)) = &self.registry_federation_circuit {
+            Some(circuit_id)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-remote-auth")]
+    pub fn registry_auth_config(&self) -> Option<&str> {
+        if let Some((path, _)) = &self.registry_auth_config {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-health")]
+    pub fn registry_check_interval(&self) -> Option<u64> {
+        if let Some((interval, _)) = &self.registry_check_interval {
+            Some(*interval)
+        } else {
+            None
+        }
+    }
+
     pub fn strict_ref_counts(&self) -> bool {
         self.strict_ref_counts.0
     }
@@ -309,6 +444,11 @@ impl Config {
         &self.enable_biome.1
     }
 
+    #[cfg(feature = "registry-database")]
+    fn registry_database_source(&self) -> &ConfigSource {
+        &self.registry_database.1
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn whitelist_source(&self) -> Option<&ConfigSource> {
         if let Some((_, source)) = &self.whitelist {
@@ -318,6 +458,110 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "rest-api-auth")]
+    pub fn rest_api_auth_config_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.rest_api_auth_config {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    pub fn biome_oauth_config_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.biome_oauth_config {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    fn biome_oauth_insecure_http_source(&self) -> &ConfigSource {
+        &self.biome_oauth_insecure_http.1
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    pub fn biome_keyring_config_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.biome_keyring_config {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    pub fn biome_master_key_file_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.biome_master_key_file {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "biome-roles")]
+    pub fn biome_role_admins_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.biome_role_admins {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "audit")]
+    pub fn audit_log_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.audit_log {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-signing")]
+    pub fn registry_trust_keys_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.registry_trust_keys {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-merge")]
+    pub fn registry_merge_strategy_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.registry_merge_strategy {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-federation")]
+    pub fn registry_federation_circuit_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.registry_federation_circuit {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-remote-auth")]
+    pub fn registry_auth_config_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.registry_auth_config {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "registry-health")]
+    pub fn registry_check_interval_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.registry_check_interval {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
     fn strict_ref_counts_source(&self) -> &ConfigSource {
         &self.strict_ref_counts.1
     }
@@ -449,8 +693,36 @@ impl Config {
             self.enable_biome(),
             self.enable_biome_source()
         );
+        #[cfg(feature = "registry-database")]
+        debug!(
+            "Config: registry_database: {:?} (source: {:?})",
+            self.registry_database(),
+            self.registry_database_source()
+        );
         #[cfg(feature = "rest-api-cors")]
         self.log_whitelist();
+        #[cfg(feature = "rest-api-auth")]
+        self.log_rest_api_auth_config();
+        #[cfg(feature = "biome-oauth")]
+        self.log_biome_oauth_config();
+        #[cfg(feature = "biome-keyring")]
+        self.log_biome_keyring_config();
+        #[cfg(feature = "biome-key-encryption")]
+        self.log_biome_master_key_file();
+        #[cfg(feature = "biome-roles")]
+        self.log_biome_role_admins();
+        #[cfg(feature = "audit")]
+        self.log_audit_log();
+        #[cfg(feature = "registry-signing")]
+        self.log_registry_trust_keys();
+        #[cfg(feature = "registry-merge")]
+        self.log_registry_merge_strategy();
+        #[cfg(feature = "registry-federation")]
+        self.log_registry_federation_circuit();
+        #[cfg(feature = "registry-remote-auth")]
+        self.log_registry_auth_config();
+        #[cfg(feature = "registry-health")]
+        self.log_registry_check_interval();
         debug!(
             "Config: strict_ref_counts: {:?} (source: {:?})",
             self.strict_ref_counts(),
@@ -464,6 +736,146 @@ impl Config {
             debug!("Config: whitelist: {:?} (source: {:?})", list, source,);
         }
     }
+
+    #[cfg(feature = "rest-api-auth")]
+    fn log_rest_api_auth_config(&self) {
+        if let (Some(path), Some(source)) = (
+            self.rest_api_auth_config(),
+            self.rest_api_auth_config_source(),
+        ) {
+            debug!(
+                "Config: rest_api_auth_config: {:?} (source: {:?})",
+                path, source,
+            );
+        }
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    fn log_biome_oauth_config(&self) {
+        if let (Some(path), Some(source)) =
+            (self.biome_oauth_config(), self.biome_oauth_config_source())
+        {
+            debug!(
+                "Config: biome_oauth_config: {:?} (source: {:?})",
+                path, source,
+            );
+        }
+        debug!(
+            "Config: biome_oauth_insecure_http: {:?} (source: {:?})",
+            self.biome_oauth_insecure_http(),
+            self.biome_oauth_insecure_http_source()
+        );
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    fn log_biome_keyring_config(&self) {
+        if let (Some(path), Some(source)) = (
+            self.biome_keyring_config(),
+            self.biome_keyring_config_source(),
+        ) {
+            debug!(
+                "Config: biome_keyring_config: {:?} (source: {:?})",
+                path, source,
+            );
+        }
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    fn log_biome_master_key_file(&self) {
+        if let (Some(path), Some(source)) = (
+            self.biome_master_key_file(),
+            self.biome_master_key_file_source(),
+        ) {
+            debug!(
+                "Config: biome_master_key_file: {:?} (source: {:?})",
+                path, source,
+            );
+        }
+    }
+
+    #[cfg(feature = "biome-roles")]
+    fn log_biome_role_admins(&self) {
+        if let (Some(list), Some(source)) =
+            (self.biome_role_admins(), self.biome_role_admins_source())
+        {
+            debug!(
+                "Config: biome_role_admins: {:?} (source: {:?})",
+                list, source
+            );
+        }
+    }
+
+    #[cfg(feature = "audit")]
+    fn log_audit_log(&self) {
+        if let (Some(audit_log), Some(source)) = (self.audit_log(), self.audit_log_source()) {
+            debug!("Config: audit_log: {:?} (source: {:?})", audit_log, source);
+        }
+    }
+
+    #[cfg(feature = "registry-signing")]
+    fn log_registry_trust_keys(&self) {
+        if let (Some(list), Some(source)) = (
+            self.registry_trust_keys(),
+            self.registry_trust_keys_source(),
+        ) {
+            debug!(
+                "Config: registry_trust_keys: {:?} (source: {:?})",
+                list, source
+            );
+        }
+    }
+
+    #[cfg(feature = "registry-merge")]
+    fn log_registry_merge_strategy(&self) {
+        if let (Some(strategy), Some(source)) = (
+            self.registry_merge_strategy(),
+            self.registry_merge_strategy_source(),
+        ) {
+            debug!(
+                "Config: registry_merge_strategy: {:?} (source: {:?})",
+                strategy, source
+            );
+        }
+    }
+
+    #[cfg(feature = "registry-federation")]
+    fn log_registry_federation_circuit(&self) {
+        if let (Some(circuit_id), Some(source)) = (
+            self.registry_federation_circuit(),
+            self.registry_federation_circuit_source(),
+        ) {
+            debug!(
+                "Config: registry_federation_circuit: {:?} (source: {:?})",
+                circuit_id, source
+            );
+        }
+    }
+
+    #[cfg(feature = "registry-remote-auth")]
+    fn log_registry_auth_config(&self) {
+        if let (Some(path), Some(source)) = (
+            self.registry_auth_config(),
+            self.registry_auth_config_source(),
+        ) {
+            debug!(
+                "Config: registry_auth_config: {:?} (source: {:?})",
+                path, source
+            );
+        }
+    }
+
+    #[cfg(feature = "registry-health")]
+    fn log_registry_check_interval(&self) {
+        if let (Some(interval), Some(source)) = (
+            self.registry_check_interval(),
+            self.registry_check_interval_source(),
+        ) {
+            debug!(
+                "Config: registry_check_interval: {} (source: {:?})",
+                interval, source
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -61,8 +61,34 @@ pub struct PartialConfig {
     no_tls: Option<bool>,
     #[cfg(feature = "biome")]
     enable_biome: Option<bool>,
+    #[cfg(feature = "registry-database")]
+    registry_database: Option<bool>,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "rest-api-auth")]
+    rest_api_auth_config: Option<String>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_config: Option<String>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_insecure_http: Option<bool>,
+    #[cfg(feature = "biome-keyring")]
+    biome_keyring_config: Option<String>,
+    #[cfg(feature = "biome-key-encryption")]
+    biome_master_key_file: Option<String>,
+    #[cfg(feature = "biome-roles")]
+    biome_role_admins: Option<Vec<String>>,
+    #[cfg(feature = "audit")]
+    audit_log: Option<String>,
+    #[cfg(feature = "registry-signing")]
+    registry_trust_keys: Option<Vec<String>>,
+    #[cfg(feature = "registry-merge")]
+    registry_merge_strategy: Option<String>,
+    #[cfg(feature = "registry-federation")]
+    registry_federation_circuit: Option<String>,
+    #[cfg(feature = "registry-remote-auth")]
+    registry_auth_config: Option<String>,
+    #[cfg(feature = "registry-health")]
+    registry_check_interval: Option<u64>,
     strict_ref_counts: Option<bool>,
 }
 
@@ -99,8 +125,34 @@ impl PartialConfig {
             no_tls: None,
             #[cfg(feature = "biome")]
             enable_biome: None,
+            #[cfg(feature = "registry-database")]
+            registry_database: None,
             #[cfg(feature = "rest-api-cors")]
             whitelist: None,
+            #[cfg(feature = "rest-api-auth")]
+            rest_api_auth_config: None,
+            #[cfg(feature = "biome-oauth")]
+            biome_oauth_config: None,
+            #[cfg(feature = "biome-oauth")]
+            biome_oauth_insecure_http: None,
+            #[cfg(feature = "biome-keyring")]
+            biome_keyring_config: None,
+            #[cfg(feature = "biome-key-encryption")]
+            biome_master_key_file: None,
+            #[cfg(feature = "biome-roles")]
+            biome_role_admins: None,
+            #[cfg(feature = "audit")]
+            audit_log: None,
+            #[cfg(feature = "registry-signing")]
+            registry_trust_keys: None,
+            #[cfg(feature = "registry-merge")]
+            registry_merge_strategy: None,
+            #[cfg(feature = "registry-federation")]
+            registry_federation_circuit: None,
+            #[cfg(feature = "registry-remote-auth")]
+            registry_auth_config: None,
+            #[cfg(feature = "registry-health")]
+            registry_check_interval: None,
             strict_ref_counts: None,
         }
     }
@@ -212,11 +264,76 @@ impl PartialConfig {
         self.enable_biome
     }
 
+    #[cfg(feature = "registry-database")]
+    pub fn registry_database(&self) -> Option<bool> {
+        self.registry_database
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn whitelist(&self) -> Option<Vec<String>> {
         self.whitelist.clone()
     }
 
+    #[cfg(feature = "rest-api-auth")]
+    pub fn rest_api_auth_config(&self) -> Option<String> {
+        self.rest_api_auth_config.clone()
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    pub fn biome_oauth_config(&self) -> Option<String> {
+        self.biome_oauth_config.clone()
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    pub fn biome_oauth_insecure_http(&self) -> Option<bool> {
+        self.biome_oauth_insecure_http
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    pub fn biome_keyring_config(&self) -> Option<String> {
+        self.biome_keyring_config.clone()
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    pub fn biome_master_key_file(&self) -> Option<String> {
+        self.biome_master_key_file.clone()
+    }
+
+    #[cfg(feature = "biome-roles")]
+    pub fn biome_role_admins(&self) -> Option<Vec<String>> {
+        self.biome_role_admins.clone()
+    }
+
+    #[cfg(feature = "audit")]
+    pub fn audit_log(&self) -> Option<String> {
+        self.audit_log.clone()
+    }
+
+    #[cfg(feature = "registry-signing")]
+    pub fn registry_trust_keys(&self) -> Option<Vec<String>> {
+        self.registry_trust_keys.clone()
+    }
+
+    #[cfg(feature = "registry-merge")]
+    pub fn registry_merge_strategy(&self) -> Option<String> {
+        self.registry_merge_strategy.clone()
+    }
+
+    #[cfg(feature = "registry-federation")]
+    pub fn registry_federation_circuit(&self) -> Option<String> {
+        self.registry_federation_circuit.clone()
+    }
+
+    #[cfg(feature = "registry-remote-auth")]
+    pub fn registry_auth_config(&self) -> Option<String> {
+        self.registry_auth_config.clone()
+    }
+
+    #[cfg(feature = "registry-health")]
+    pub fn registry_check_interval(&self) -> Option<u64> {
+        self.registry_check_interval
+    }
+
     pub fn strict_ref_counts(&self) -> Option<bool> {
         self.strict_ref_counts
     }
@@ -508,6 +625,18 @@ impl PartialConfig {
         self
     }
 
+    #[cfg(feature = "registry-database")]
+    /// Adds a `registry_database` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_database` - Store the local registry in the database instead of a YAML file
+    ///
+    pub fn with_registry_database(mut self, registry_database: Option<bool>) -> Self {
+        self.registry_database = registry_database;
+        self
+    }
+
     #[cfg(feature = "rest-api-cors")]
     /// Adds a `whitelist` value to the `PartialConfig` object.
     ///
@@ -520,6 +649,164 @@ impl PartialConfig {
         self
     }
 
+    #[cfg(feature = "rest-api-auth")]
+    /// Adds a `rest_api_auth_config` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rest_api_auth_config` - Path to the REST API authorization configuration file
+    ///
+    pub fn with_rest_api_auth_config(mut self, rest_api_auth_config: Option<String>) -> Self {
+        self.rest_api_auth_config = rest_api_auth_config;
+        self
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    /// Adds a `biome_oauth_config` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_oauth_config` - Path to the biome OAuth configuration file
+    ///
+    pub fn with_biome_oauth_config(mut self, biome_oauth_config: Option<String>) -> Self {
+        self.biome_oauth_config = biome_oauth_config;
+        self
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    /// Adds a `biome_oauth_insecure_http` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_oauth_insecure_http` - Allow the OAuth provider to be reached over plain HTTP
+    ///
+    pub fn with_biome_oauth_insecure_http(
+        mut self,
+        biome_oauth_insecure_http: Option<bool>,
+    ) -> Self {
+        self.biome_oauth_insecure_http = biome_oauth_insecure_http;
+        self
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    /// Adds a `biome_keyring_config` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_keyring_config` - Path to the biome token signing key configuration file
+    ///
+    pub fn with_biome_keyring_config(mut self, biome_keyring_config: Option<String>) -> Self {
+        self.biome_keyring_config = biome_keyring_config;
+        self
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    /// Adds a `biome_master_key_file` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_master_key_file` - Path to the file with the master key that encrypts biome
+    ///   users' private keys
+    ///
+    pub fn with_biome_master_key_file(mut self, biome_master_key_file: Option<String>) -> Self {
+        self.biome_master_key_file = biome_master_key_file;
+        self
+    }
+
+    #[cfg(feature = "biome-roles")]
+    /// Adds a `biome_role_admins` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_role_admins` - IDs of the biome users that are assigned the role administrator
+    ///   role on startup
+    ///
+    pub fn with_biome_role_admins(mut self, biome_role_admins: Option<Vec<String>>) -> Self {
+        self.biome_role_admins = biome_role_admins;
+        self
+    }
+
+    #[cfg(feature = "audit")]
+    /// Adds an `audit_log` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_log` - Path to the audit log file, or the URL of the database that stores the
+    ///   audit log
+    ///
+    pub fn with_audit_log(mut self, audit_log: Option<String>) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
+    #[cfg(feature = "registry-signing")]
+    /// Adds a `registry_trust_keys` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_trust_keys` - Paths to the public key files of the publishers whose signatures
+    ///   are accepted on remote registry files
+    ///
+    pub fn with_registry_trust_keys(mut self, registry_trust_keys: Option<Vec<String>>) -> Self {
+        self.registry_trust_keys = registry_trust_keys;
+        self
+    }
+
+    #[cfg(feature = "registry-merge")]
+    /// Adds a `registry_merge_strategy` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_merge_strategy` - How the definitions of a node that is in more than one
+    ///   registry are combined: `priority`, `field-merge` or `reject-conflicts`
+    ///
+    pub fn with_registry_merge_strategy(mut self, registry_merge_strategy: Option<String>) -> Self {
+        self.registry_merge_strategy = registry_merge_strategy;
+        self
+    }
+
+    #[cfg(feature = "registry-federation")]
+    /// Adds a `registry_federation_circuit` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_federation_circuit` - The ID of the circuit whose members replicate the node's
+    ///   writable registry
+    ///
+    pub fn with_registry_federation_circuit(
+        mut self,
+        registry_federation_circuit: Option<String>,
+    ) -> Self {
+        self.registry_federation_circuit = registry_federation_circuit;
+        self
+    }
+
+    #[cfg(feature = "registry-remote-auth")]
+    /// Adds a `registry_auth_config` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_auth_config` - Path to the YAML file with the credentials and TLS settings
+    ///   used to fetch remote registries
+    ///
+    pub fn with_registry_auth_config(mut self, registry_auth_config: Option<String>) -> Self {
+        self.registry_auth_config = registry_auth_config;
+        self
+    }
+
+    #[cfg(feature = "registry-health")]
+    /// Adds a `registry_check_interval` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_check_interval` - How often the nodes in the registry should be checked for
+    ///   invalid, duplicate, unreachable and stale entries (in seconds)
+    ///
+    pub fn with_registry_check_interval(mut self, registry_check_interval: Option<u64>) -> Self {
+        self.registry_check_interval = registry_check_interval;
+        self
+    }
+
     /// Adds a `strict_ref_counts` value to the `PartialConfig` object.
     ///
     /// # Arguments
@@ -53,6 +53,28 @@ struct TomlConfig {
     version: Option<String>,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "rest-api-auth")]
+    rest_api_auth_config: Option<String>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_config: Option<String>,
+    #[cfg(feature = "biome-keyring")]
+    biome_keyring_config: Option<String>,
+    #[cfg(feature = "biome-key-encryption")]
+    biome_master_key_file: Option<String>,
+    #[cfg(feature = "biome-roles")]
+    biome_role_admins: Option<Vec<String>>,
+    #[cfg(feature = "audit")]
+    audit_log: Option<String>,
+    #[cfg(feature = "registry-signing")]
+    registry_trust_keys: Option<Vec<String>>,
+    #[cfg(feature = "registry-merge")]
+    registry_merge_strategy: Option<String>,
+    #[cfg(feature = "registry-federation")]
+    registry_federation_circuit: Option<String>,
+    #[cfg(feature = "registry-remote-auth")]
+    registry_auth_config: Option<String>,
+    #[cfg(feature = "registry-health")]
+    registry_check_interval: Option<u64>,
 
     // Deprecated values
     cert_dir: Option<String>,
@@ -149,6 +171,71 @@ impl PartialConfigBuilder for TomlPartialConfigBuilder {
             partial_config = partial_config.with_whitelist(self.toml_config.whitelist);
         }
 
+        #[cfg(feature = "rest-api-auth")]
+        {
+            partial_config =
+                partial_config.with_rest_api_auth_config(self.toml_config.rest_api_auth_config);
+        }
+
+        #[cfg(feature = "biome-oauth")]
+        {
+            partial_config =
+                partial_config.with_biome_oauth_config(self.toml_config.biome_oauth_config);
+        }
+
+        #[cfg(feature = "biome-keyring")]
+        {
+            partial_config =
+                partial_config.with_biome_keyring_config(self.toml_config.biome_keyring_config);
+        }
+
+        #[cfg(feature = "biome-key-encryption")]
+        {
+            partial_config =
+                partial_config.with_biome_master_key_file(self.toml_config.biome_master_key_file);
+        }
+
+        #[cfg(feature = "biome-roles")]
+        {
+            partial_config =
+                partial_config.with_biome_role_admins(self.toml_config.biome_role_admins);
+        }
+
+        #[cfg(feature = "audit")]
+        {
+            partial_config = partial_config.with_audit_log(self.toml_config.audit_log);
+        }
+
+        #[cfg(feature = "registry-signing")]
+        {
+            partial_config =
+                partial_config.with_registry_trust_keys(self.toml_config.registry_trust_keys);
+        }
+
+        #[cfg(feature = "registry-merge")]
+        {
+            partial_config = partial_config
+                .with_registry_merge_strategy(self.toml_config.registry_merge_strategy);
+        }
+
+        #[cfg(feature = "registry-federation")]
+        {
+            partial_config = partial_config
+                .with_registry_federation_circuit(self.toml_config.registry_federation_circuit);
+        }
+
+        #[cfg(feature = "registry-remote-auth")]
+        {
+            partial_config =
+                partial_config.with_registry_auth_config(self.toml_config.registry_auth_config);
+        }
+
+        #[cfg(feature = "registry-health")]
+        {
+            partial_config = partial_config
+                .with_registry_check_interval(self.toml_config.registry_check_interval);
+        }
+
         // deprecated values, only set if the current value was not set
         if partial_config.tls_cert_dir().is_none() {
             partial_config = partial_config.with_tls_cert_dir(self.toml_config.cert_dir)
@@ -29,12 +29,29 @@ use scabbard::service::ScabbardArgValidator;
 use scabbard::service::ScabbardFactory;
 use splinter::admin::rest_api::CircuitResourceProvider;
 use splinter::admin::service::{admin_service_id, AdminService};
+#[cfg(all(feature = "biome-notifications", feature = "biome-key-management"))]
+use splinter::admin::service::{AdminCommands, ALL_CIRCUIT_MANAGEMENT_TYPES};
+#[cfg(all(feature = "audit", feature = "database"))]
+use splinter::audit::store::DieselAuditStore;
+#[cfg(feature = "audit")]
+use splinter::audit::{store::FileAuditStore, AuditLog};
+#[cfg(feature = "biome-key-encryption")]
+use splinter::biome::key_management::KeyEncryptor;
 #[cfg(feature = "biome")]
 use splinter::biome::rest_api::{BiomeRestResourceManager, BiomeRestResourceManagerBuilder};
 #[cfg(feature = "biome-key-management")]
 use splinter::biome::DieselKeyStore;
+#[cfg(feature = "biome-notifications")]
+use splinter::biome::DieselNotificationStore;
+#[cfg(feature = "biome-roles")]
+use splinter::biome::DieselRoleStore;
 #[cfg(feature = "biome")]
 use splinter::biome::DieselUserStore;
+#[cfg(feature = "biome-oauth")]
+use splinter::biome::{
+    oauth::{OAuthConfig, OpenIdClient},
+    DieselOAuthUserStore,
+};
 #[cfg(feature = "biome-credentials")]
 use splinter::biome::{DieselCredentialsStore, DieselRefreshTokenStore};
 use splinter::circuit::directory::CircuitDirectory;
@@ -44,7 +61,9 @@ use splinter::circuit::handlers::{
 };
 use splinter::circuit::{SplinterState, SplinterStateError};
 #[cfg(feature = "biome")]
-use splinter::database::{self, ConnectionPool};
+use splinter::database;
+#[cfg(any(feature = "biome", feature = "registry-database"))]
+use splinter::database::ConnectionPool;
 use splinter::keys::insecure::AllowAllKeyPermissionManager;
 use splinter::mesh::Mesh;
 use splinter::network::auth::AuthorizationManager;
@@ -59,18 +78,46 @@ use splinter::orchestrator::{NewOrchestratorError, ServiceOrchestrator};
 use splinter::peer::interconnect::NetworkMessageSender;
 use splinter::peer::interconnect::PeerInterconnectBuilder;
 use splinter::peer::PeerManager;
+#[cfg(feature = "registry-events")]
+use splinter::peer::PeerManagerConnector;
 use splinter::protos::circuit::CircuitMessageType;
 use splinter::protos::network::NetworkMessageType;
+#[cfg(feature = "registry-signing")]
+use splinter::registry::read_public_key_file;
+#[cfg(feature = "audit")]
+use splinter::registry::AuditedRegistryResourceProvider;
+#[cfg(feature = "registry-database")]
+use splinter::registry::DieselRegistry;
+#[cfg(feature = "registry-federation")]
+use splinter::registry::FederatedRegistry;
+#[cfg(feature = "registry-merge")]
+use splinter::registry::MergeStrategy;
+#[cfg(feature = "registry-remote-auth")]
+use splinter::registry::RemoteRegistryConfig;
 use splinter::registry::{
     LocalYamlRegistry, RegistryReader, RemoteYamlRegistry, RemoteYamlShutdownHandle, RwRegistry,
     UnifiedRegistry,
 };
+#[cfg(feature = "registry-health")]
+use splinter::registry::{RegistryChecker, RegistryValidator};
+#[cfg(feature = "registry-events")]
+use splinter::registry::{RegistryEvent, RegistryEventSubscriber, RegistrySubscriberError};
+#[cfg(all(feature = "rest-api-auth", feature = "audit"))]
+use splinter::rest_api::auth::AUDIT_READ_PERMISSION;
+#[cfg(feature = "rest-api-auth")]
+use splinter::rest_api::auth::{
+    identity::IdentityProvider, AuthConfig, Authorizer, PermissionGuard, CIRCUIT_READ_PERMISSION,
+    CIRCUIT_WRITE_PERMISSION, REGISTRY_READ_PERMISSION, REGISTRY_WRITE_PERMISSION,
+    SCABBARD_READ_PERMISSION, SCABBARD_SUBMIT_PERMISSION,
+};
+#[cfg(feature = "biome-keyring")]
+use splinter::rest_api::secrets::KeyringConfig;
 use splinter::rest_api::{
     Method, Resource, RestApiBuilder, RestApiServerError, RestResourceProvider,
 };
 #[cfg(feature = "service-arg-validation")]
 use splinter::service::validation::ServiceArgValidator;
-use splinter::service::{self, ServiceProcessor, ShutdownHandle};
+use splinter::service::{self, ServiceFactory, ServiceProcessor, ShutdownHandle};
 use splinter::signing::sawtooth::SawtoothSecp256k1SignatureVerifier;
 use splinter::storage::get_storage;
 use splinter::transport::{
@@ -95,6 +142,12 @@ const HEALTH_SERVICE_PROCESSOR_OUTGOING_CAPACITY: usize = 8;
 #[cfg(feature = "health")]
 const HEALTH_SERVICE_PROCESSOR_CHANNEL_CAPACITY: usize = 8;
 
+/// The files in the state directory that hold the keys that sign biome tokens
+#[cfg(feature = "biome-keyring")]
+const BIOME_ACCESS_TOKEN_KEYRING: &str = "biome_access_token_keys.yaml";
+#[cfg(all(feature = "biome-keyring", feature = "biome-credentials"))]
+const BIOME_REFRESH_TOKEN_KEYRING: &str = "biome_refresh_token_keys.yaml";
+
 type ServiceJoinHandle = service::JoinHandles<Result<(), service::error::ServiceProcessorError>>;
 
 pub struct SplinterDaemon {
@@ -112,13 +165,41 @@ pub struct SplinterDaemon {
     db_url: Option<String>,
     #[cfg(feature = "biome")]
     enable_biome: bool,
+    #[cfg(feature = "registry-database")]
+    enable_registry_database: bool,
     registries: Vec<String>,
     registry_auto_refresh: u64,
     registry_forced_refresh: u64,
+    #[cfg(feature = "registry-signing")]
+    registry_trust_keys: Vec<String>,
+    #[cfg(feature = "registry-merge")]
+    registry_merge_strategy: Option<String>,
+    #[cfg(feature = "registry-federation")]
+    registry_federation_circuit: Option<String>,
+    #[cfg(feature = "registry-remote-auth")]
+    registry_auth_config: Option<String>,
+    #[cfg(feature = "registry-health")]
+    registry_check_interval: Option<u64>,
+    #[cfg(feature = "registry-health")]
+    registry_check_transports: Vec<MultiTransport>,
     storage_type: String,
     admin_timeout: Duration,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "rest-api-auth")]
+    rest_api_auth_config: Option<String>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_config: Option<String>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_insecure_http: bool,
+    #[cfg(feature = "biome-keyring")]
+    biome_keyring_config: Option<String>,
+    #[cfg(feature = "biome-key-encryption")]
+    biome_master_key_file: Option<String>,
+    #[cfg(feature = "biome-roles")]
+    biome_role_admins: Vec<String>,
+    #[cfg(feature = "audit")]
+    audit_log: Option<String>,
     heartbeat: u64,
     strict_ref_counts: bool,
 }
@@ -374,29 +455,99 @@ impl SplinterDaemon {
             }
         }
 
-        let (orchestrator, orchestator_join_handles) = ServiceOrchestrator::new(
+        // The registry may add the factory for its registry service to the orchestrator's
+        // factories
+        #[cfg_attr(not(feature = "registry-federation"), allow(unused_mut))]
+        let mut service_factories: Vec<Box<dyn ServiceFactory>> =
             vec![Box::new(ScabbardFactory::new(
                 None,
                 None,
                 None,
                 None,
                 Box::new(SawtoothSecp256k1SignatureVerifier::new()),
-            ))],
-            orchestrator_connection,
-            ORCHESTRATOR_INCOMING_CAPACITY,
-            ORCHESTRATOR_OUTGOING_CAPACITY,
-            ORCHESTRATOR_CHANNEL_CAPACITY,
-        )?;
-        let orchestrator_resources = orchestrator.resources();
+            ))];
 
         let signature_verifier = SawtoothSecp256k1SignatureVerifier::new();
 
-        let (registry, registry_shutdown) = create_registry(
+        #[cfg(feature = "registry-database")]
+        let registry_db_url = if self.enable_registry_database {
+            self.db_url.as_deref()
+        } else {
+            None
+        };
+
+        #[cfg_attr(not(feature = "registry-health"), allow(unused_mut))]
+        let (registry, mut registry_shutdown) = create_registry(
             &self.state_dir,
+            #[cfg(feature = "registry-database")]
+            registry_db_url,
             &self.registries,
             self.registry_auto_refresh,
             self.registry_forced_refresh,
+            #[cfg(feature = "registry-signing")]
+            &self.registry_trust_keys,
+            #[cfg(feature = "registry-merge")]
+            self.registry_merge_strategy.as_deref(),
+            #[cfg(feature = "registry-federation")]
+            self.registry_federation_circuit.as_deref(),
+            #[cfg(feature = "registry-remote-auth")]
+            self.registry_auth_config.as_deref(),
+            #[cfg(feature = "registry-federation")]
+            &mut service_factories,
+        )?;
+
+        // Check the nodes in the registry in the background, probing their endpoints with a
+        // transport of their own
+        #[cfg(feature = "registry-health")]
+        let registry_validator = match self.registry_check_interval {
+            Some(interval) if interval != 0 => {
+                let checker = RegistryChecker::new().with_transports(
+                    self.registry_check_transports
+                        .drain(..)
+                        .map(|transport| Box::new(transport) as Box<dyn Transport>)
+                        .collect(),
+                );
+                let validator = RegistryValidator::start(
+                    registry.clone_box_as_reader(),
+                    checker,
+                    Duration::from_secs(interval),
+                )
+                .map_err(|err| {
+                    StartError::RegistryError(format!(
+                        "Failed to start registry validator: {}",
+                        err
+                    ))
+                })?;
+                registry_shutdown.add_registry_validator(validator.clone());
+                Some(validator)
+            }
+            _ => None,
+        };
+
+        // Keep the endpoints of connected peers up to date with the registry
+        #[cfg(feature = "registry-events")]
+        registry
+            .add_event_subscriber(Box::new(PeerEndpointUpdater {
+                peer_connector: peer_connector.clone(),
+            }))
+            .map_err(|err| {
+                StartError::RegistryError(format!(
+                    "Failed to subscribe to registry events: {}",
+                    err
+                ))
+            })?;
+
+        let (orchestrator, orchestator_join_handles) = ServiceOrchestrator::new(
+            service_factories,
+            orchestrator_connection,
+            ORCHESTRATOR_INCOMING_CAPACITY,
+            ORCHESTRATOR_OUTGOING_CAPACITY,
+            ORCHESTRATOR_CHANNEL_CAPACITY,
         )?;
+        let orchestrator_resources = orchestrator.resources();
+
+        #[cfg(feature = "audit")]
+        let audit_log = build_audit_log(self.audit_log.as_deref())?;
 
         let (admin_service, admin_notification_join) = AdminService::new(
             &self.node_id,
@@ -421,6 +572,16 @@ impl SplinterDaemon {
             StartError::AdminServiceError(format!("unable to create admin service: {}", err))
         })?;
 
+        #[cfg(feature = "audit")]
+        admin_service
+            .set_audit_log(audit_log.clone())
+            .map_err(|err| {
+                StartError::AdminServiceError(format!(
+                    "unable to set the admin service audit log: {}",
+                    err
+                ))
+            })?;
+
         let node_id = self.node_id.clone();
         let display_name = self.display_name.clone();
         #[cfg(feature = "service-endpoint")]
@@ -431,6 +592,129 @@ impl SplinterDaemon {
         let circuit_resource_provider =
             CircuitResourceProvider::new(self.node_id.to_string(), state);
 
+        #[cfg(feature = "biome")]
+        let biome_resources = if self.enable_biome {
+            let db_url = self.db_url.as_ref().ok_or_else(|| {
+                StartError::StorageError(
+                    "biome was enabled but the builder failed to require the db URL".into(),
+                )
+            })?;
+            Some(build_biome_routes(
+                &db_url,
+                #[cfg(feature = "biome-oauth")]
+                self.biome_oauth_config.as_deref(),
+                #[cfg(feature = "biome-oauth")]
+                self.biome_oauth_insecure_http,
+                #[cfg(feature = "biome-keyring")]
+                &self.state_dir,
+                #[cfg(feature = "biome-keyring")]
+                self.biome_keyring_config.as_deref(),
+                #[cfg(feature = "biome-key-encryption")]
+                self.biome_master_key_file.as_deref(),
+                #[cfg(feature = "biome-roles")]
+                &self.biome_role_admins,
+                #[cfg(feature = "audit")]
+                audit_log.clone(),
+            )?)
+        } else {
+            None
+        };
+
+        #[cfg(all(feature = "biome-notifications", feature = "biome-key-management"))]
+        {
+            if let Some(biome_resources) = &biome_resources {
+                let notifier = biome_resources.admin_event_notifier().map_err(|err| {
+                    StartError::AdminServiceError(format!(
+                        "unable to start biome admin event notifier: {}",
+                        err
+                    ))
+                })?;
+                admin_service
+                    .commands()
+                    .add_event_subscriber(ALL_CIRCUIT_MANAGEMENT_TYPES, Box::new(notifier))
+                    .map_err(|err| {
+                        StartError::AdminServiceError(format!(
+                            "unable to subscribe biome notifications to admin events: {}",
+                            err
+                        ))
+                    })?;
+            }
+        }
+
+        // Allowing unused_mut because the resources are only replaced if the rest-api-auth
+        // feature is enabled
+        #[cfg(not(feature = "audit"))]
+        #[allow(unused_mut)]
+        let mut registry_resources = registry.resources();
+        #[cfg(feature = "audit")]
+        #[allow(unused_mut)]
+        let mut registry_resources =
+            AuditedRegistryResourceProvider::new(registry.clone_box(), audit_log.clone())
+                .resources();
+        #[cfg(feature = "registry-health")]
+        {
+            if let Some(validator) = &registry_validator {
+                registry_resources.extend(validator.resources());
+            }
+        }
+        #[cfg(feature = "audit")]
+        #[allow(unused_mut)]
+        let mut audit_resources = audit_log.resources();
+        #[allow(unused_mut)]
+        let mut admin_resources = admin_service.resources();
+        admin_resources.extend(circuit_resource_provider.resources());
+        #[allow(unused_mut)]
+        let mut scabbard_resources = orchestrator_resources;
+
+        // The status, OpenAPI, biome and health endpoints are left unguarded so that clients can
+        // check on the node and log in to obtain credentials.
+        #[cfg(feature = "rest-api-auth")]
+        {
+            if let Some(path) = &self.rest_api_auth_config {
+                #[allow(unused_mut)]
+                let mut identity_providers: Vec<Box<dyn IdentityProvider>> = vec![];
+                #[cfg(any(feature = "biome-credentials", feature = "biome-key-management"))]
+                {
+                    if let Some(biome_resources) = &biome_resources {
+                        // Users are granted the permissions of their biome roles
+                        #[cfg(feature = "biome-roles")]
+                        identity_providers.push(Box::new(biome_resources.role_identity_provider()));
+                        #[cfg(not(feature = "biome-roles"))]
+                        identity_providers.push(Box::new(biome_resources.jwt_identity_provider()));
+                    }
+                }
+
+                let authorizer = Arc::new(build_authorizer(path, identity_providers)?);
+                registry_resources = guard_resources(
+                    registry_resources,
+                    &authorizer,
+                    REGISTRY_READ_PERMISSION,
+                    REGISTRY_WRITE_PERMISSION,
+                );
+                admin_resources = guard_resources(
+                    admin_resources,
+                    &authorizer,
+                    CIRCUIT_READ_PERMISSION,
+                    CIRCUIT_WRITE_PERMISSION,
+                );
+                scabbard_resources = guard_resources(
+                    scabbard_resources,
+                    &authorizer,
+                    SCABBARD_READ_PERMISSION,
+                    SCABBARD_SUBMIT_PERMISSION,
+                );
+                #[cfg(feature = "audit")]
+                {
+                    audit_resources = guard_resources(
+                        audit_resources,
+                        &authorizer,
+                        AUDIT_READ_PERMISSION,
+                        AUDIT_READ_PERMISSION,
+                    );
+                }
+            }
+        }
+
         // Allowing unused_mut because rest_api_builder must be mutable if feature biome is enabled
         #[allow(unused_mut)]
         let mut rest_api_builder = RestApiBuilder::new()
@@ -450,10 +734,14 @@ impl SplinterDaemon {
                     )
                 }),
             )
-            .add_resources(registry.resources())
-            .add_resources(admin_service.resources())
-            .add_resources(orchestrator_resources)
-            .add_resources(circuit_resource_provider.resources());
+            .add_resources(registry_resources)
+            .add_resources(admin_resources)
+            .add_resources(scabbard_resources);
+
+        #[cfg(feature = "audit")]
+        {
+            rest_api_builder = rest_api_builder.add_resources(audit_resources);
+        }
 
         #[cfg(feature = "rest-api-cors")]
         {
@@ -465,13 +753,7 @@ impl SplinterDaemon {
 
         #[cfg(feature = "biome")]
         {
-            if self.enable_biome {
-                let db_url = self.db_url.as_ref().ok_or_else(|| {
-                    StartError::StorageError(
-                        "biome was enabled but the builder failed to require the db URL".into(),
-                    )
-                })?;
-                let biome_resources = build_biome_routes(&db_url)?;
+            if let Some(biome_resources) = &biome_resources {
                 rest_api_builder = rest_api_builder.add_resources(biome_resources.resources());
             }
         }
@@ -700,16 +982,55 @@ fn start_health_service(
     })?
 }
 
+#[cfg(feature = "rest-api-auth")]
+fn build_authorizer(
+    path: &str,
+    identity_providers: Vec<Box<dyn IdentityProvider>>,
+) -> Result<Authorizer, StartError> {
+    info!("Loading REST API authorization config from {}", path);
+    AuthConfig::from_file(path)
+        .and_then(|config| config.build_authorizer(identity_providers))
+        .map_err(|err| StartError::RestApiError(err.to_string()))
+}
+
+#[cfg(feature = "rest-api-auth")]
+fn guard_resources(
+    resources: Vec<Resource>,
+    authorizer: &Arc<Authorizer>,
+    read_permission: &str,
+    write_permission: &str,
+) -> Vec<Resource> {
+    resources
+        .into_iter()
+        .map(|resource| {
+            resource.add_request_guard(PermissionGuard::new(
+                authorizer.clone(),
+                read_permission,
+                write_permission,
+            ))
+        })
+        .collect()
+}
+
 #[cfg(feature = "biome")]
-fn build_biome_routes(db_url: &str) -> Result<BiomeRestResourceManager, StartError> {
+#[allow(clippy::too_many_arguments)]
+fn build_biome_routes(
+    db_url: &str,
+    #[cfg(feature = "biome-oauth")] oauth_config: Option<&str>,
+    #[cfg(feature = "biome-oauth")] oauth_insecure_http: bool,
+    #[cfg(feature = "biome-keyring")] state_dir: &str,
+    #[cfg(feature = "biome-keyring")] keyring_config: Option<&str>,
+    #[cfg(feature = "biome-key-encryption")] master_key_file: Option<&str>,
+    #[cfg(feature = "biome-roles")] role_admins: &[String],
+    #[cfg(feature = "audit")] audit_log: AuditLog,
+) -> Result<BiomeRestResourceManager, StartError> {
     info!("Adding biome routes");
-    let connection_pool: ConnectionPool =
-        database::ConnectionPool::new_pg(db_url).map_err(|err| {
-            StartError::RestApiError(format!(
-                "Unable to connect to the Splinter database: {}",
-                err
-            ))
-        })?;
+    let connection_pool: ConnectionPool = database::ConnectionPool::new(db_url).map_err(|err| {
+        StartError::RestApiError(format!(
+            "Unable to connect to the Splinter database: {}",
+            err
+        ))
+    })?;
     let mut biome_rest_provider_builder: BiomeRestResourceManagerBuilder = Default::default();
     biome_rest_provider_builder =
         biome_rest_provider_builder.with_user_store(DieselUserStore::new(connection_pool.clone()));
@@ -720,11 +1041,89 @@ fn build_biome_routes(db_url: &str) -> Result<BiomeRestResourceManager, StartErr
         biome_rest_provider_builder = biome_rest_provider_builder
             .with_credentials_store(DieselCredentialsStore::new(connection_pool.clone()));
     }
+    #[cfg(feature = "biome-roles")]
+    {
+        biome_rest_provider_builder = biome_rest_provider_builder
+            .with_role_store(DieselRoleStore::new(connection_pool.clone()))
+            .with_role_admins(role_admins.to_vec());
+    }
+    #[cfg(feature = "biome-notifications")]
+    {
+        biome_rest_provider_builder = biome_rest_provider_builder
+            .with_notification_store(DieselNotificationStore::new(connection_pool.clone()));
+    }
+    #[cfg(feature = "biome-oauth")]
+    {
+        if let Some(path) = oauth_config {
+            info!("Loading biome OAuth config from {}", path);
+            let mut config = OAuthConfig::from_file(path)
+                .map_err(|err| StartError::RestApiError(err.to_string()))?;
+            if oauth_insecure_http {
+                warn!(
+                    "Biome OAuth provider endpoints may use plain HTTP; this is insecure and \
+                     should only be used for testing"
+                );
+                config = config.with_insecure_http(true);
+            }
+            let client = OpenIdClient::new(config)
+                .map_err(|err| StartError::RestApiError(err.to_string()))?;
+            biome_rest_provider_builder = biome_rest_provider_builder
+                .with_oauth_client(client)
+                .with_oauth_user_store(DieselOAuthUserStore::new(connection_pool.clone()));
+        }
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    {
+        let keyring_config = match keyring_config {
+            Some(path) => {
+                info!("Loading biome keyring config from {}", path);
+                KeyringConfig::from_file(path)
+                    .map_err(|err| StartError::RestApiError(err.to_string()))?
+            }
+            None => KeyringConfig::default(),
+        };
+        let build_secret_manager = |file_name: &str| {
+            let path = Path::new(state_dir).join(file_name);
+            keyring_config
+                .secret_manager_builder(&path.to_string_lossy())
+                .build()
+                .map_err(|err| {
+                    StartError::RestApiError(format!(
+                        "Unable to load biome signing keys from {}: {}",
+                        path.display(),
+                        err
+                    ))
+                })
+        };
+        biome_rest_provider_builder = biome_rest_provider_builder
+            .with_token_secret_manager(build_secret_manager(BIOME_ACCESS_TOKEN_KEYRING)?);
+        #[cfg(feature = "biome-credentials")]
+        {
+            biome_rest_provider_builder = biome_rest_provider_builder
+                .with_refresh_token_secret_manager(build_secret_manager(
+                    BIOME_REFRESH_TOKEN_KEYRING,
+                )?);
+        }
+    }
+    #[cfg(feature = "biome-key-encryption")]
+    {
+        if let Some(path) = master_key_file {
+            info!("Loading biome master key from {}", path);
+            let encryptor = KeyEncryptor::from_file(path)
+                .map_err(|err| StartError::RestApiError(err.to_string()))?;
+            biome_rest_provider_builder = biome_rest_provider_builder.with_key_encryptor(encryptor);
+        }
+    }
     #[cfg(feature = "biome-key-management")]
     {
         biome_rest_provider_builder =
             biome_rest_provider_builder.with_key_store(DieselKeyStore::new(connection_pool))
     }
+    #[cfg(feature = "audit")]
+    {
+        biome_rest_provider_builder = biome_rest_provider_builder.with_audit_log(audit_log);
+    }
     let biome_rest_provider = biome_rest_provider_builder.build().map_err(|err| {
         StartError::RestApiError(format!("Unable to build Biome REST routes: {}", err))
     })?;
@@ -732,6 +1131,44 @@ fn build_biome_routes(db_url: &str) -> Result<BiomeRestResourceManager, StartErr
     Ok(biome_rest_provider)
 }
 
+/// Builds the audit log from the `audit_log` setting, which is either the URL of a database or the
+/// path to a file. If the setting is not given, actions are not recorded.
+#[cfg(feature = "audit")]
+fn build_audit_log(audit_log: Option<&str>) -> Result<AuditLog, StartError> {
+    let location = match audit_log {
+        Some(location) => location,
+        None => return Ok(AuditLog::default()),
+    };
+
+    if ["postgres://", "postgresql://", "sqlite://"]
+        .iter()
+        .any(|prefix| location.starts_with(prefix))
+    {
+        #[cfg(feature = "database")]
+        {
+            info!("Recording audit log in database");
+            let connection_pool =
+                splinter::database::ConnectionPool::new(location).map_err(|err| {
+                    StartError::StorageError(format!(
+                        "Unable to connect to the audit log database: {}",
+                        err
+                    ))
+                })?;
+            Ok(AuditLog::new(DieselAuditStore::new(connection_pool)))
+        }
+        #[cfg(not(feature = "database"))]
+        {
+            Err(StartError::StorageError(format!(
+                "Unable to use audit log database {}: database support is not enabled",
+                location
+            )))
+        }
+    } else {
+        info!("Recording audit log in {}", location);
+        Ok(AuditLog::new(FileAuditStore::new(location)))
+    }
+}
+
 #[derive(Default)]
 pub struct SplinterDaemonBuilder {
     state_dir: Option<String>,
@@ -747,14 +1184,42 @@ pub struct SplinterDaemonBuilder {
     db_url: Option<String>,
     #[cfg(feature = "biome")]
     enable_biome: bool,
+    #[cfg(feature = "registry-database")]
+    enable_registry_database: bool,
     registries: Vec<String>,
     registry_auto_refresh: Option<u64>,
     registry_forced_refresh: Option<u64>,
+    #[cfg(feature = "registry-signing")]
+    registry_trust_keys: Vec<String>,
+    #[cfg(feature = "registry-merge")]
+    registry_merge_strategy: Option<String>,
+    #[cfg(feature = "registry-federation")]
+    registry_federation_circuit: Option<String>,
+    #[cfg(feature = "registry-remote-auth")]
+    registry_auth_config: Option<String>,
+    #[cfg(feature = "registry-health")]
+    registry_check_interval: Option<u64>,
+    #[cfg(feature = "registry-health")]
+    registry_check_transports: Vec<MultiTransport>,
     storage_type: Option<String>,
     heartbeat: Option<u64>,
     admin_timeout: Duration,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "rest-api-auth")]
+    rest_api_auth_config: Option<String>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_config: Option<String>,
+    #[cfg(feature = "biome-oauth")]
+    biome_oauth_insecure_http: bool,
+    #[cfg(feature = "biome-keyring")]
+    biome_keyring_config: Option<String>,
+    #[cfg(feature = "biome-key-encryption")]
+    biome_master_key_file: Option<String>,
+    #[cfg(feature = "biome-roles")]
+    biome_role_admins: Vec<String>,
+    #[cfg(feature = "audit")]
+    audit_log: Option<String>,
     strict_ref_counts: Option<bool>,
 }
 
@@ -816,6 +1281,12 @@ impl SplinterDaemonBuilder {
         self
     }
 
+    #[cfg(feature = "registry-database")]
+    pub fn enable_registry_database(mut self, enabled: bool) -> Self {
+        self.enable_registry_database = enabled;
+        self
+    }
+
     pub fn with_registries(mut self, registries: Vec<String>) -> Self {
         self.registries = registries;
         self
@@ -831,6 +1302,42 @@ impl SplinterDaemonBuilder {
         self
     }
 
+    #[cfg(feature = "registry-signing")]
+    pub fn with_registry_trust_keys(mut self, value: Vec<String>) -> Self {
+        self.registry_trust_keys = value;
+        self
+    }
+
+    #[cfg(feature = "registry-merge")]
+    pub fn with_registry_merge_strategy(mut self, value: String) -> Self {
+        self.registry_merge_strategy = Some(value);
+        self
+    }
+
+    #[cfg(feature = "registry-federation")]
+    pub fn with_registry_federation_circuit(mut self, value: String) -> Self {
+        self.registry_federation_circuit = Some(value);
+        self
+    }
+
+    #[cfg(feature = "registry-remote-auth")]
+    pub fn with_registry_auth_config(mut self, value: String) -> Self {
+        self.registry_auth_config = Some(value);
+        self
+    }
+
+    #[cfg(feature = "registry-health")]
+    pub fn with_registry_check_interval(mut self, value: u64) -> Self {
+        self.registry_check_interval = Some(value);
+        self
+    }
+
+    #[cfg(feature = "registry-health")]
+    pub fn with_registry_check_transports(mut self, value: Vec<MultiTransport>) -> Self {
+        self.registry_check_transports = value;
+        self
+    }
+
     pub fn with_storage_type(mut self, value: String) -> Self {
         self.storage_type = Some(value);
         self
@@ -852,6 +1359,48 @@ impl SplinterDaemonBuilder {
         self
     }
 
+    #[cfg(feature = "rest-api-auth")]
+    pub fn with_rest_api_auth_config(mut self, value: Option<String>) -> Self {
+        self.rest_api_auth_config = value;
+        self
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    pub fn with_biome_oauth_config(mut self, value: Option<String>) -> Self {
+        self.biome_oauth_config = value;
+        self
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    pub fn with_biome_oauth_insecure_http(mut self, value: bool) -> Self {
+        self.biome_oauth_insecure_http = value;
+        self
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    pub fn with_biome_keyring_config(mut self, value: Option<String>) -> Self {
+        self.biome_keyring_config = value;
+        self
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    pub fn with_biome_master_key_file(mut self, value: Option<String>) -> Self {
+        self.biome_master_key_file = value;
+        self
+    }
+
+    #[cfg(feature = "biome-roles")]
+    pub fn with_biome_role_admins(mut self, value: Vec<String>) -> Self {
+        self.biome_role_admins = value;
+        self
+    }
+
+    #[cfg(feature = "audit")]
+    pub fn with_audit_log(mut self, value: Option<String>) -> Self {
+        self.audit_log = value;
+        self
+    }
+
     pub fn with_strict_ref_counts(mut self, strict_ref_counts: bool) -> Self {
         self.strict_ref_counts = Some(strict_ref_counts);
         self
@@ -909,6 +1458,15 @@ impl SplinterDaemonBuilder {
             }
         }
 
+        #[cfg(feature = "registry-database")]
+        {
+            if self.enable_registry_database && db_url.is_none() {
+                return Err(CreateError::MissingRequiredField(
+                    "db_url is required to store the registry in the database.".to_string(),
+                ));
+            }
+        }
+
         let registry_auto_refresh = self.registry_auto_refresh.ok_or_else(|| {
             CreateError::MissingRequiredField("Missing field: registry_auto_refresh".to_string())
         })?;
@@ -940,13 +1498,41 @@ impl SplinterDaemonBuilder {
             db_url,
             #[cfg(feature = "biome")]
             enable_biome: self.enable_biome,
+            #[cfg(feature = "registry-database")]
+            enable_registry_database: self.enable_registry_database,
             registries: self.registries,
             registry_auto_refresh,
             registry_forced_refresh,
+            #[cfg(feature = "registry-signing")]
+            registry_trust_keys: self.registry_trust_keys,
+            #[cfg(feature = "registry-merge")]
+            registry_merge_strategy: self.registry_merge_strategy,
+            #[cfg(feature = "registry-federation")]
+            registry_federation_circuit: self.registry_federation_circuit,
+            #[cfg(feature = "registry-remote-auth")]
+            registry_auth_config: self.registry_auth_config,
+            #[cfg(feature = "registry-health")]
+            registry_check_interval: self.registry_check_interval,
+            #[cfg(feature = "registry-health")]
+            registry_check_transports: self.registry_check_transports,
             storage_type,
             admin_timeout: self.admin_timeout,
             #[cfg(feature = "rest-api-cors")]
             whitelist: self.whitelist,
+            #[cfg(feature = "rest-api-auth")]
+            rest_api_auth_config: self.rest_api_auth_config,
+            #[cfg(feature = "biome-oauth")]
+            biome_oauth_config: self.biome_oauth_config,
+            #[cfg(feature = "biome-oauth")]
+            biome_oauth_insecure_http: self.biome_oauth_insecure_http,
+            #[cfg(feature = "biome-keyring")]
+            biome_keyring_config: self.biome_keyring_config,
+            #[cfg(feature = "biome-key-encryption")]
+            biome_master_key_file: self.biome_master_key_file,
+            #[cfg(feature = "biome-roles")]
+            biome_role_admins: self.biome_role_admins,
+            #[cfg(feature = "audit")]
+            audit_log: self.audit_log,
             heartbeat,
             strict_ref_counts,
         })
@@ -1004,29 +1590,72 @@ fn set_up_circuit_dispatcher(
 
 fn create_registry(
     state_dir: &str,
+    #[cfg(feature = "registry-database")] db_url: Option<&str>,
     registries: &[String],
     auto_refresh_interval: u64,
     forced_refresh_interval: u64,
+    #[cfg(feature = "registry-signing")] trust_key_files: &[String],
+    #[cfg(feature = "registry-merge")] merge_strategy: Option<&str>,
+    #[cfg(feature = "registry-federation")] federation_circuit: Option<&str>,
+    #[cfg(feature = "registry-remote-auth")] auth_config: Option<&str>,
+    #[cfg(feature = "registry-federation")] service_factories: &mut Vec<Box<dyn ServiceFactory>>,
 ) -> Result<(Box<dyn RwRegistry>, RegistryShutdownHandle), StartError> {
     let mut registry_shutdown_handle = RegistryShutdownHandle::new();
 
-    let local_registry_path = Path::new(state_dir)
-        .join("local_registry.yaml")
-        .to_str()
-        .expect("path built from &str cannot be invalid")
-        .to_string();
-    debug!(
-        "Creating local registry with registry file: {:?}",
-        local_registry_path
-    );
-    let local_registry = Box::new(LocalYamlRegistry::new(&local_registry_path).map_err(|err| {
-        StartError::RegistryError(format!(
-            "Failed to initialize local LocalYamlRegistry: {}",
-            err
-        ))
-    })?);
+    #[cfg(feature = "registry-database")]
+    let local_registry: Box<dyn RwRegistry> = match db_url {
+        Some(db_url) => {
+            debug!("Creating local registry in the database");
+            let connection_pool = ConnectionPool::new(db_url).map_err(|err| {
+                StartError::RegistryError(format!(
+                    "Failed to connect to the registry database: {}",
+                    err
+                ))
+            })?;
+            Box::new(DieselRegistry::new(connection_pool))
+        }
+        None => create_local_yaml_registry(state_dir)?,
+    };
+    #[cfg(not(feature = "registry-database"))]
+    let local_registry = create_local_yaml_registry(state_dir)?;
+
+    // With federation, the local registry holds this node's copy of the federated registry and
+    // changes to it are replicated by the federation circuit's registry services
+    #[cfg(feature = "registry-federation")]
+    let local_registry: Box<dyn RwRegistry> = match federation_circuit {
+        Some(circuit_id) => {
+            debug!("Replicating local registry on circuit {}", circuit_id);
+            let federated_registry = FederatedRegistry::new(circuit_id, local_registry);
+            service_factories.push(Box::new(federated_registry.service_factory()));
+            Box::new(federated_registry)
+        }
+        None => local_registry,
+    };
 
-    let read_only_registries = registries
+    #[cfg(feature = "registry-signing")]
+    let trusted_keys = trust_key_files
+        .iter()
+        .map(|path| {
+            read_public_key_file(path).map_err(|err| {
+                StartError::RegistryError(format!("Failed to load trusted registry key: {}", err))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    #[cfg(feature = "registry-remote-auth")]
+    let remote_configs = match auth_config {
+        Some(path) => RemoteRegistryConfig::load_all(path).map_err(|err| {
+            StartError::RegistryError(format!(
+                "Failed to load remote registry auth config: {}",
+                err
+            ))
+        })?,
+        None => vec![],
+    };
+
+    // The names of the read-only registries are only used if feature registry-merge is enabled
+    #[cfg_attr(not(feature = "registry-merge"), allow(unused_variables))]
+    let (read_only_registry_names, read_only_registries): (Vec<String>, Vec<_>) = registries
         .iter()
         .filter_map(|registry| {
             let (scheme, path) = parse_registry_arg(registry)
@@ -1039,7 +1668,10 @@ fn create_registry(
                     path
                 );
                 match LocalYamlRegistry::new(path) {
-                    Ok(registry) => Some(Box::new(registry) as Box<dyn RegistryReader>),
+                    Ok(local_registry) => Some((
+                        registry.to_string(),
+                        Box::new(local_registry) as Box<dyn RegistryReader>,
+                    )),
                     Err(err) => {
                         error!(
                             "Failed to add read-only LocalYamlRegistry '{}': {}",
@@ -1053,26 +1685,47 @@ fn create_registry(
                     "Attempting to add remote read-only registry from URL: {}",
                     registry
                 );
-                let auto_refresh_interval = if auto_refresh_interval != 0 {
-                    Some(Duration::from_secs(auto_refresh_interval))
-                } else {
-                    None
-                };
-                let forced_refresh_interval = if forced_refresh_interval != 0 {
-                    Some(Duration::from_secs(forced_refresh_interval))
-                } else {
-                    None
-                };
-                match RemoteYamlRegistry::new(
-                    registry,
-                    state_dir,
-                    auto_refresh_interval,
-                    forced_refresh_interval,
-                ) {
-                    Ok(registry) => {
+                let mut builder = RemoteYamlRegistry::builder(registry, state_dir);
+                if auto_refresh_interval != 0 {
+                    builder = builder
+                        .with_automatic_refresh_period(Duration::from_secs(auto_refresh_interval));
+                }
+                if forced_refresh_interval != 0 {
+                    builder = builder
+                        .with_forced_refresh_period(Duration::from_secs(forced_refresh_interval));
+                }
+                #[cfg(feature = "registry-signing")]
+                {
+                    if !trusted_keys.is_empty() {
+                        builder = builder.with_signature_verification(
+                            trusted_keys.clone(),
+                            Box::new(SawtoothSecp256k1SignatureVerifier::new()),
+                        );
+                    }
+                }
+                #[cfg(feature = "registry-remote-auth")]
+                {
+                    if let Some(config) = remote_configs
+                        .iter()
+                        .find(|config| config.matches(registry))
+                    {
+                        debug!(
+                            "Fetching remote registry {} with the settings for {}",
+                            registry,
+                            config.url_prefix()
+                        );
+                        builder = builder.with_config(config.clone());
+                    }
+                }
+                let remote_registry = builder.build();
+                match remote_registry {
+                    Ok(remote_registry) => {
                         registry_shutdown_handle
-                            .add_remote_yaml_shutdown_handle(registry.shutdown_handle());
-                        Some(Box::new(registry) as Box<dyn RegistryReader>)
+                            .add_remote_yaml_shutdown_handle(remote_registry.shutdown_handle());
+                        Some((
+                            registry.to_string(),
+                            Box::new(remote_registry) as Box<dyn RegistryReader>,
+                        ))
                     }
                     Err(err) => {
                         error!(
@@ -1090,13 +1743,53 @@ fn create_registry(
                 None
             }
         })
-        .collect();
+        .unzip();
 
-    let unified_registry = Box::new(UnifiedRegistry::new(local_registry, read_only_registries));
+    let unified_registry = UnifiedRegistry::new(local_registry, read_only_registries);
+
+    #[cfg(feature = "registry-merge")]
+    let unified_registry = {
+        let merge_strategy = match merge_strategy {
+            Some(merge_strategy) => merge_strategy
+                .parse::<MergeStrategy>()
+                .map_err(|err| StartError::RegistryError(err.to_string()))?,
+            None => MergeStrategy::default(),
+        };
+        debug!("Using registry merge strategy {:?}", merge_strategy);
+        unified_registry
+            .with_merge_strategy(merge_strategy)
+            .with_source_names(
+                std::iter::once("local".to_string())
+                    .chain(read_only_registry_names)
+                    .collect(),
+            )
+    };
+
+    let unified_registry = Box::new(unified_registry);
 
     Ok((unified_registry, registry_shutdown_handle))
 }
 
+fn create_local_yaml_registry(state_dir: &str) -> Result<Box<dyn RwRegistry>, StartError> {
+    let local_registry_path = Path::new(state_dir)
+        .join("local_registry.yaml")
+        .to_str()
+        .expect("path built from &str cannot be invalid")
+        .to_string();
+    debug!(
+        "Creating local registry with registry file: {:?}",
+        local_registry_path
+    );
+    Ok(Box::new(
+        LocalYamlRegistry::new(&local_registry_path).map_err(|err| {
+            StartError::RegistryError(format!(
+                "Failed to initialize local LocalYamlRegistry: {}",
+                err
+            ))
+        })?,
+    ))
+}
+
 fn parse_registry_arg(registry: &str) -> Result<(&str, &str), &str> {
     let mut iter = registry.splitn(2, "://");
     let scheme = iter
@@ -1109,6 +1802,8 @@ fn parse_registry_arg(registry: &str) -> Result<(&str, &str), &str> {
 #[derive(Default)]
 struct RegistryShutdownHandle {
     remote_yaml_shutdown_handles: Vec<RemoteYamlShutdownHandle>,
+    #[cfg(feature = "registry-health")]
+    registry_validators: Vec<RegistryValidator>,
 }
 
 impl RegistryShutdownHandle {
@@ -1120,10 +1815,48 @@ impl RegistryShutdownHandle {
         self.remote_yaml_shutdown_handles.push(handle);
     }
 
+    #[cfg(feature = "registry-health")]
+    fn add_registry_validator(&mut self, validator: RegistryValidator) {
+        self.registry_validators.push(validator);
+    }
+
     fn shutdown(&self) {
         self.remote_yaml_shutdown_handles
             .iter()
             .for_each(|handle| handle.shutdown());
+        #[cfg(feature = "registry-health")]
+        self.registry_validators
+            .iter()
+            .for_each(|validator| validator.shutdown());
+    }
+}
+
+/// Updates the endpoints of a peer in the peer manager when its registry entry's endpoints change.
+#[cfg(feature = "registry-events")]
+struct PeerEndpointUpdater {
+    peer_connector: PeerManagerConnector,
+}
+
+#[cfg(feature = "registry-events")]
+impl RegistryEventSubscriber for PeerEndpointUpdater {
+    fn handle_event(&self, event: &RegistryEvent) -> Result<(), RegistrySubscriberError> {
+        if let RegistryEvent::NodeUpdated { previous, node } = event {
+            if previous.endpoints != node.endpoints {
+                debug!(
+                    "Registry endpoints of node {} changed to {:?}",
+                    node.identity, node.endpoints
+                );
+                self.peer_connector
+                    .update_peer_endpoints(node.identity.clone(), node.endpoints.clone())
+                    .map_err(|err| {
+                        RegistrySubscriberError::UnableToHandleEvent(format!(
+                            "Unable to update endpoints of peer {}: {}",
+                            node.identity, err
+                        ))
+                    })?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -46,6 +46,10 @@ use std::thread;
 use error::UserError;
 use transport::build_transport;
 
+/// The number of registry endpoints that are probed in parallel by the registry health check
+#[cfg(feature = "registry-health")]
+const REGISTRY_CHECK_PROBES: usize = 4;
+
 fn create_config(_toml_path: Option<&str>, _matches: ArgMatches) -> Result<Config, UserError> {
     let mut builder = ConfigBuilder::new();
 
@@ -308,7 +312,7 @@ fn main() {
     let app = app.arg(
         Arg::with_name("database")
             .long("database")
-            .long_help("DB connection URL")
+            .long_help("DB connection URL or path to a SQLite database file")
             .takes_value(true),
     );
 
@@ -319,6 +323,16 @@ fn main() {
             .long_help("Enable the biome subsystem"),
     );
 
+    #[cfg(feature = "registry-database")]
+    let app = app.arg(
+        Arg::with_name("registry_database")
+            .long("registry-database")
+            .long_help(
+                "Store the local registry in the database given by --database instead of a \
+                 YAML file in the state directory",
+            ),
+    );
+
     #[cfg(feature = "rest-api-cors")]
     let app = app.arg(
         Arg::with_name("whitelist")
@@ -329,6 +343,172 @@ fn main() {
             .help("Whitelisted domains"),
     );
 
+    #[cfg(feature = "rest-api-auth")]
+    let app = app.arg(
+        Arg::with_name("rest_api_auth_config")
+            .long("rest-api-auth-config")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Path to the REST API authorization configuration file")
+            .long_help(
+                "Path to a YAML file that configures REST API authentication and the \
+                 permissions granted to each identity. If not set, the REST API does not \
+                 require authentication",
+            ),
+    );
+
+    #[cfg(feature = "biome-oauth")]
+    let app = app.arg(
+        Arg::with_name("biome_oauth_config")
+            .long("biome-oauth-config")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Path to the biome OAuth configuration file")
+            .long_help(
+                "Path to a YAML file that configures the OpenID Connect provider biome users \
+                 can log in with. If not set, biome users can only log in with a username and \
+                 password",
+            ),
+    );
+
+    #[cfg(feature = "biome-oauth")]
+    let app = app.arg(
+        Arg::with_name("biome_oauth_insecure_http")
+            .long("biome-oauth-insecure-http")
+            .long_help(
+                "Allow the OpenID Connect provider's endpoints to use plain HTTP instead of \
+                 HTTPS. Only intended for testing against a local provider",
+            ),
+    );
+
+    #[cfg(feature = "biome-keyring")]
+    let app = app.arg(
+        Arg::with_name("biome_keyring_config")
+            .long("biome-keyring-config")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Path to the biome token signing key configuration file")
+            .long_help(
+                "Path to a YAML file that configures the algorithm and rotation of the keys \
+                 that sign biome access and refresh tokens. The keys are kept in the state \
+                 directory. If not set, HS256 keys are used and never rotated automatically",
+            ),
+    );
+
+    #[cfg(feature = "biome-key-encryption")]
+    let app = app.arg(
+        Arg::with_name("biome_master_key_file")
+            .long("biome-master-key-file")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Path to the master key that encrypts biome users' private keys")
+            .long_help(
+                "Path to a file with the hex-encoded 256-bit master key that the server uses \
+                 to encrypt biome users' private keys. If not set, clients must encrypt \
+                 private keys themselves",
+            ),
+    );
+
+    #[cfg(feature = "biome-roles")]
+    let app = app.arg(
+        Arg::with_name("biome_role_admins")
+            .long("biome-role-admin")
+            .takes_value(true)
+            .multiple(true)
+            .value_name("USER-ID")
+            .help("ID of a biome user to make a role administrator")
+            .long_help(
+                "ID of a biome user that is assigned the role administrator role, which grants \
+                 the permissions to manage roles, when the daemon starts. Role administrators \
+                 can then assign roles to other users",
+            ),
+    );
+
+    #[cfg(feature = "registry-signing")]
+    let app = app.arg(
+        Arg::with_name("registry_trust_keys")
+            .long("registry-trust-key")
+            .takes_value(true)
+            .multiple(true)
+            .value_name("FILE")
+            .help("Public key file of a trusted registry publisher")
+            .long_help(
+                "Path to the public key file of a registry publisher. If any trusted keys are \
+                 given, remote registry files are only loaded if they have a valid signature \
+                 (at the file's URL with '.sig' appended) from one of these keys",
+            ),
+    );
+
+    #[cfg(feature = "registry-merge")]
+    let app = app.arg(
+        Arg::with_name("registry_merge_strategy")
+            .long("registry-merge-strategy")
+            .takes_value(true)
+            .possible_values(&["priority", "field-merge", "reject-conflicts"])
+            .help("How the definitions of a node that is in more than one registry are combined")
+            .long_help(
+                "How the definitions of a node that is in more than one registry are combined: \
+                 'priority' uses the endpoints and keys of the highest-precedence registry \
+                 (default), 'field-merge' combines the endpoints and keys of all registries, \
+                 and 'reject-conflicts' leaves out nodes whose endpoints or keys differ between \
+                 registries",
+            ),
+    );
+
+    #[cfg(feature = "registry-federation")]
+    let app = app.arg(
+        Arg::with_name("registry_federation_circuit")
+            .long("registry-federation-circuit")
+            .takes_value(true)
+            .help("ID of the circuit whose members replicate the local registry")
+            .long_help(
+                "ID of the circuit whose members replicate the local registry. Changes to the \
+                 local registry are proposed to the circuit's registry services and are only \
+                 applied once every member has accepted them",
+            ),
+    );
+
+    #[cfg(feature = "registry-remote-auth")]
+    let app = app.arg(
+        Arg::with_name("registry_auth_config")
+            .long("registry-auth-config")
+            .takes_value(true)
+            .help("Path to the YAML file with the credentials used to fetch remote registries")
+            .long_help(
+                "Path to the YAML file with the credentials and TLS settings used to fetch \
+                 remote registries. Each entry applies to the registry URLs that start with its \
+                 url_prefix; credentials are only sent to HTTPS URLs unless the entry sets \
+                 allow_insecure_credentials",
+            ),
+    );
+
+    #[cfg(feature = "registry-health")]
+    let app = app.arg(
+        Arg::with_name("registry_check_interval")
+            .long("registry-check-interval")
+            .takes_value(true)
+            .help("How often to check the nodes in the registry, in seconds")
+            .long_help(
+                "How often to check the nodes in the registry for invalid, duplicate, unreachable \
+                 and stale entries, in seconds. The results are available at /registry/health. \
+                 The registry is not checked if this is not set",
+            ),
+    );
+
+    #[cfg(feature = "audit")]
+    let app = app.arg(
+        Arg::with_name("audit_log")
+            .long("audit-log")
+            .takes_value(true)
+            .value_name("FILE_OR_DB_URL")
+            .help("Where to record administrative and authentication actions")
+            .long_help(
+                "Path to a file that administrative and authentication actions are appended \
+                 to, or the URL of a PostgreSQL or SQLite database (postgres://..., \
+                 sqlite://...) that stores them. If not set, actions are not recorded",
+            ),
+    );
+
     let matches = app.get_matches();
 
     let log_level = match matches.occurrences_of("verbose") {
@@ -440,11 +620,101 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
         daemon_builder = daemon_builder.enable_biome(config.enable_biome());
     }
 
+    #[cfg(feature = "registry-database")]
+    {
+        daemon_builder = daemon_builder.enable_registry_database(config.registry_database());
+    }
+
     #[cfg(feature = "rest-api-cors")]
     {
         daemon_builder = daemon_builder.with_whitelist(config.whitelist().map(ToOwned::to_owned));
     }
 
+    #[cfg(feature = "rest-api-auth")]
+    {
+        daemon_builder = daemon_builder
+            .with_rest_api_auth_config(config.rest_api_auth_config().map(ToOwned::to_owned));
+    }
+
+    #[cfg(feature = "biome-oauth")]
+    {
+        daemon_builder = daemon_builder
+            .with_biome_oauth_config(config.biome_oauth_config().map(ToOwned::to_owned))
+            .with_biome_oauth_insecure_http(config.biome_oauth_insecure_http());
+    }
+
+    #[cfg(feature = "biome-keyring")]
+    {
+        daemon_builder = daemon_builder
+            .with_biome_keyring_config(config.biome_keyring_config().map(ToOwned::to_owned));
+    }
+
+    #[cfg(feature = "registry-signing")]
+    {
+        daemon_builder = daemon_builder.with_registry_trust_keys(
+            config
+                .registry_trust_keys()
+                .map(ToOwned::to_owned)
+                .unwrap_or_default(),
+        );
+    }
+
+    #[cfg(feature = "registry-merge")]
+    {
+        if let Some(strategy) = config.registry_merge_strategy() {
+            daemon_builder = daemon_builder.with_registry_merge_strategy(strategy.to_string());
+        }
+    }
+
+    #[cfg(feature = "registry-federation")]
+    {
+        if let Some(circuit_id) = config.registry_federation_circuit() {
+            daemon_builder =
+                daemon_builder.with_registry_federation_circuit(circuit_id.to_string());
+        }
+    }
+
+    #[cfg(feature = "registry-remote-auth")]
+    {
+        if let Some(path) = config.registry_auth_config() {
+            daemon_builder = daemon_builder.with_registry_auth_config(path.to_string());
+        }
+    }
+
+    #[cfg(feature = "registry-health")]
+    {
+        if let Some(interval) = config.registry_check_interval() {
+            daemon_builder = daemon_builder
+                .with_registry_check_interval(interval)
+                .with_registry_check_transports(
+                    (0..REGISTRY_CHECK_PROBES)
+                        .map(|_| build_transport(&config))
+                        .collect::<Result<Vec<_>, _>>()?,
+                );
+        }
+    }
+
+    #[cfg(feature = "biome-key-encryption")]
+    {
+        daemon_builder = daemon_builder
+            .with_biome_master_key_file(config.biome_master_key_file().map(ToOwned::to_owned));
+    }
+
+    #[cfg(feature = "biome-roles")]
+    {
+        daemon_builder = daemon_builder.with_biome_role_admins(
+            config
+                .biome_role_admins()
+                .map(ToOwned::to_owned)
+                .unwrap_or_default(),
+        );
+    }
+
+    #[cfg(feature = "audit")]
+    {
+        daemon_builder = daemon_builder.with_audit_log(config.audit_log().map(ToOwned::to_owned));
+    }
+
     let mut node = daemon_builder.build().map_err(|err| {
         UserError::daemon_err_with_source("unable to build the Splinter daemon", Box::new(err))
     })?;